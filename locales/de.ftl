@@ -0,0 +1,216 @@
+# Oberflächentexte, Deutsch. Fehlende Meldungen kommen aus en.ftl.
+
+## Toolbar
+
+toolbar-open-folder = Ordner öffnen (Strg+Umschalt+O)
+toolbar-open-file = Datei öffnen (Strg+O)
+toolbar-recent = Zuletzt geöffnete Bilder und Ordner
+toolbar-move = In Ordner verschieben (M)
+toolbar-export = Bild exportieren (Strg+S)
+toolbar-search = Suche ein-/ausblenden (Strg+F)
+toolbar-search-hint = Bilder suchen...
+toolbar-search-help =
+    Findet Dateinamen und Stichwörter.
+    Nach EXIF filtern mit iso:>1600, lens:35mm, camera:R5, focal:<=50, f:1.8
+    Weitere Felder: keyword:Name, rating:>=3
+toolbar-clear-search = Suche leeren
+toolbar-previous = Vorheriges Bild (←)
+toolbar-go-to = Gehe zu Bild (Strg+G)
+toolbar-next = Nächstes Bild (→)
+toolbar-play-animation = Animation abspielen (Leertaste)
+toolbar-pause-animation = Animation anhalten (Leertaste)
+toolbar-play-video = Video abspielen (Leertaste)
+toolbar-pause-video = Video anhalten (Leertaste)
+toolbar-zoom-out = Verkleinern (-)
+toolbar-zoom-in = Vergrößern (+)
+toolbar-fit = Einpassen
+toolbar-fill = Füllen
+toolbar-rotate-left = Nach links drehen (L)
+toolbar-rotate-right = Nach rechts drehen (R)
+toolbar-crop = Zuschneiden ein/aus (C)
+toolbar-apply-crop = Zuschnitt anwenden
+toolbar-straighten = Ausrichten: entlang des Horizonts ziehen
+toolbar-single-view = Einzelansicht
+toolbar-scroll-view = Bildlaufansicht: alle Bilder in einem Streifen (W)
+toolbar-focus-peaking = Fokus-Peaking (Strg+Umschalt+F)
+toolbar-zebras = Zebras (Alt+Z)
+toolbar-clipping = Beschnittwarnung (J)
+toolbar-grid = Gitter einblenden
+toolbar-loupe = Lupe (Strg+L)
+toolbar-raw = RAW-Dateien in voller Größe laden (umschalten). Aus: nur eingebettete JPEG-Vorschauen.
+toolbar-exif-overlay = EXIF-Einblendung ein/aus (E)
+toolbar-before-after = Vorher/Nachher umschalten (\)
+toolbar-split = Vorher/Nachher geteilt (Y)
+toolbar-settings = Einstellungen
+toolbar-command-palette = Befehlspalette (Strg+P)
+toolbar-fullscreen = Vollbild (F11)
+toolbar-mini-viewer = Mini-Ansicht über anderen Fenstern (Strg+M)
+toolbar-panels = Alle Bedienfelder ein/aus (Umschalt+P)
+toolbar-layout = Layout und Arbeitsbereiche
+toolbar-mute = Diashow-Musik stummschalten
+toolbar-volume = Lautstärke der Diashow-Musik
+
+## Tray icon
+
+tray-show-hide = rView zeigen/verbergen
+tray-recent-folders = Zuletzt geöffneter Ordner
+tray-slideshow = Diashow starten/beenden
+tray-quit = Beenden
+
+## Mini viewer
+
+mini-viewer-move = Ziehen, um das Fenster zu verschieben
+mini-viewer-leave = Zurück zum vollen Fenster (Esc)
+
+## Command palette
+
+palette-hint = Befehl eingeben...
+palette-scripts = Skripte
+palette-recent = Zuletzt angesehen
+
+category-navigation = Navigation
+category-view = Ansicht
+category-editing = Bearbeiten
+category-file = Datei
+
+## Actions
+
+action-next = Nächstes Bild
+action-previous = Vorheriges Bild
+action-first = Erstes Bild
+action-last = Letztes Bild
+action-page_up = Seite zurück
+action-page_down = Seite vor
+action-go_to = Gehe zu Bild
+action-search = Suchen
+action-select_all = Alle auswählen
+action-clear_keyword_filter = Stichwortfilter aufheben
+action-show_picks = Nur Favoriten zeigen
+action-show_rejects = Nur Abgelehnte zeigen
+action-clear_flag_filter = Markierungsfilter aufheben
+action-clear_filter_bar = Filter aufheben
+action-new_smart_collection = Neue Smart-Sammlung
+action-slideshow = Diashow starten/beenden
+action-slideshow_skip = Zur nächsten Folie springen
+action-slideshow_hold = Diashow anhalten/fortsetzen
+action-command_palette = Befehlspalette
+action-next_tab = Nächster Tab
+action-previous_tab = Vorheriger Tab
+action-zoom_in = Vergrößern
+action-zoom_out = Verkleinern
+action-zoom_fit = In Fenster einpassen
+action-actual_size = Originalgröße (100 %)
+action-zoom_200 = Zoom 200 %
+action-fullscreen = Vollbild ein/aus
+action-fullscreen_monitor = Vollbild auf nächstem Monitor
+action-mini_viewer = Mini-Ansicht (immer im Vordergrund)
+action-reference_overlay = Referenz-Überlagerung...
+action-compare = Vergleichsansicht ein/aus
+action-compare_difference = Differenz im Vergleich ein/aus
+action-scroll_view = Bildlaufansicht ein/aus
+action-show_original = Original zeigen
+action-split_view = Vorher/Nachher geteilt ein/aus
+action-split_direction = Vorher/Nachher-Teilung drehen
+action-focus_peaking = Fokus-Peaking ein/aus
+action-zebras = Zebras ein/aus
+action-clipping = Beschnittwarnung ein/aus
+action-grid = Gitter ein/aus
+action-loupe = Lupe ein/aus
+action-sidebar = Seitenleiste ein/aus
+action-soft_proof = Softproof ein/aus
+action-thumbnails = Miniaturen ein/aus
+action-exif = EXIF-Info ein/aus
+action-all_metadata = Alle Metadaten zeigen
+action-timeline = Zeitleiste
+action-exif_overlay = EXIF-Einblendung ein/aus
+action-histogram = Histogramm ein/aus
+action-adjustments = Anpassungen ein/aus
+action-filter_bar = Filterleiste ein/aus
+action-panels = Bedienfelder ein/aus
+action-workspace_cull = Arbeitsbereich Sichten
+action-workspace_edit = Arbeitsbereich Bearbeiten
+action-workspace_review = Arbeitsbereich Prüfen
+action-play_animation = Animation oder Video abspielen/anhalten
+action-undo = Rückgängig
+action-redo = Wiederholen
+action-rotate_left = Nach links drehen
+action-rotate_right = Nach rechts drehen
+action-rate_0 = Bewertung entfernen
+action-rate_1 = Mit 1 Stern bewerten
+action-rate_2 = Mit 2 Sternen bewerten
+action-rate_3 = Mit 3 Sternen bewerten
+action-rate_4 = Mit 4 Sternen bewerten
+action-rate_5 = Mit 5 Sternen bewerten
+action-label_red = Rote Farbmarkierung ein/aus
+action-label_yellow = Gelbe Farbmarkierung ein/aus
+action-label_green = Grüne Farbmarkierung ein/aus
+action-label_blue = Blaue Farbmarkierung ein/aus
+action-pick = Als Favorit markieren
+action-reject = Als abgelehnt markieren
+action-unflag = Markierung entfernen
+action-add_keyword = Stichwort hinzufügen
+action-copy_adjustments = Anpassungen kopieren
+action-paste_adjustments = Anpassungen einfügen
+action-paste_adjustments_selected = Anpassungen in Auswahl einfügen
+action-auto_enhance = Automatisch verbessern
+action-auto_enhance_selected = Auswahl automatisch verbessern
+action-presets = Anpassungsvorgaben verwalten
+action-film_stocks = Filmmaterialien verwalten
+action-open_file = Datei öffnen
+action-open_folder = Ordner öffnen
+action-tether = Mit Ordner verbinden (Tethering)...
+action-open_url = URL öffnen...
+action-new_tab = Neuer Tab
+action-close_tab = Tab schließen
+action-move = In Ordner verschieben
+action-copy_image = Bild in Zwischenablage kopieren
+action-copy_file = Datei in Zwischenablage kopieren
+action-copy_path = Pfad kopieren
+action-paste_image = Bild aus Zwischenablage einfügen
+action-save_pasted_image = Eingefügtes Bild speichern unter...
+action-delete = Bild löschen
+action-move_rejected = Abgelehnte in Ordner verschieben
+action-delete_rejected = Abgelehnte löschen
+action-recently_deleted = Zuletzt gelöscht
+action-batch_rename = Stapelumbenennung
+action-batch_metadata = Metadaten im Stapel bearbeiten
+action-batch_export = Auswahl im Stapel exportieren
+action-convert_dng = RAW in DNG umwandeln
+action-shoot_summary = Shooting-Übersicht
+action-contact_sheet = Kontaktabzug
+action-web_gallery = Webgalerie erstellen
+action-playlist_add = Zur Diashow-Playlist hinzufügen
+action-playlist = Diashow-Playlist
+action-wallpaper = Als Hintergrundbild festlegen
+action-scripts_folder = Skriptordner öffnen
+action-settings = Einstellungen
+
+## Settings
+
+settings-title = ⚙ Einstellungen
+settings-close = ✓ Schließen
+settings-reset = ↺ Standard wiederherstellen
+settings-language = Sprache:
+settings-appearance = Darstellung
+settings-thumbnails = Miniaturen
+settings-panels = Bedienfelder
+settings-view = Ansicht
+settings-exif-overlay = EXIF-Einblendung
+settings-raw = RAW
+settings-hdr = HDR
+settings-photography-tools = Fotowerkzeuge
+settings-color-management = Farbmanagement
+settings-export = Export
+settings-share = Teilen
+settings-remote-control = Fernsteuerung
+settings-tether = Tethering
+settings-slideshow = Diashow
+settings-metadata = Metadaten
+settings-keyboard = Tastenkürzel
+settings-shortcut-conflicts =
+    { $count ->
+        [one] Ein Tastenkürzel ist mehreren Aktionen zugewiesen
+       *[other] { $count } Tastenkürzel sind mehreren Aktionen zugewiesen
+    }
+settings-cache = Cache
+settings-performance = Leistung & Diagnose
@@ -0,0 +1,105 @@
+# Interface text, English. Other locales fall back to these messages.
+# Action titles come from the action table; locales translate them as action-<id>.
+
+## Toolbar
+
+toolbar-open-folder = Open folder (Ctrl+Shift+O)
+toolbar-open-file = Open file (Ctrl+O)
+toolbar-recent = Recent images and folders
+toolbar-move = Move to folder (M)
+toolbar-export = Export image (Ctrl+S)
+toolbar-search = Toggle search (Ctrl+F)
+toolbar-search-hint = Search images...
+toolbar-search-help =
+    Matches file names and keywords.
+    Filter by EXIF with iso:>1600, lens:35mm, camera:R5, focal:<=50, f:1.8
+    Other fields: keyword:name, rating:>=3
+toolbar-clear-search = Clear search
+toolbar-previous = Previous image (←)
+toolbar-go-to = Go to image (Ctrl+G)
+toolbar-next = Next image (→)
+toolbar-play-animation = Play animation (Space)
+toolbar-pause-animation = Pause animation (Space)
+toolbar-play-video = Play video (Space)
+toolbar-pause-video = Pause video (Space)
+toolbar-zoom-out = Zoom out (-)
+toolbar-zoom-in = Zoom in (+)
+toolbar-fit = Fit
+toolbar-fill = Fill
+toolbar-rotate-left = Rotate left (L)
+toolbar-rotate-right = Rotate right (R)
+toolbar-crop = Toggle crop mode (C)
+toolbar-apply-crop = Apply crop
+toolbar-straighten = Straighten: drag along the horizon
+toolbar-single-view = Single view
+toolbar-scroll-view = Scroll view: all images in one strip (W)
+toolbar-focus-peaking = Focus peaking (Ctrl+Shift+F)
+toolbar-zebras = Zebras (Alt+Z)
+toolbar-clipping = Clipping warning (J)
+toolbar-grid = Grid overlay
+toolbar-loupe = Loupe (Ctrl+L)
+toolbar-raw = Load full-size RAW files (toggle). When off, only embedded JPEG previews are used.
+toolbar-exif-overlay = Toggle EXIF overlay (E)
+toolbar-before-after = Toggle before/after view (\)
+toolbar-split = Before/after split (Y)
+toolbar-settings = Settings
+toolbar-command-palette = Command palette (Ctrl+P)
+toolbar-fullscreen = Fullscreen (F11)
+toolbar-mini-viewer = Mini viewer on top of other windows (Ctrl+M)
+toolbar-panels = Toggle all panels (Shift+P)
+toolbar-layout = Layout and workspaces
+toolbar-mute = Mute slideshow music
+toolbar-volume = Slideshow music volume
+
+## Tray icon
+
+tray-show-hide = Show/Hide rView
+tray-recent-folders = Open Recent Folder
+tray-slideshow = Start/Stop Slideshow
+tray-quit = Quit
+
+## Mini viewer
+
+mini-viewer-move = Drag to move the window
+mini-viewer-leave = Back to the full window (Esc)
+
+## Command palette
+
+palette-hint = Type a command...
+palette-scripts = Scripts
+palette-recent = Recent Images
+
+category-navigation = Navigation
+category-view = View
+category-editing = Editing
+category-file = File
+
+## Settings
+
+settings-title = ⚙ Settings
+settings-close = ✓ Close
+settings-reset = ↺ Reset Defaults
+settings-language = Language:
+settings-appearance = Appearance
+settings-thumbnails = Thumbnails
+settings-panels = Panels
+settings-view = View
+settings-exif-overlay = EXIF Overlay
+settings-raw = RAW
+settings-hdr = HDR
+settings-photography-tools = Photography Tools
+settings-color-management = Color Management
+settings-export = Export
+settings-share = Share
+settings-remote-control = Remote Control
+settings-tether = Tether
+settings-slideshow = Slideshow
+settings-metadata = Metadata
+settings-keyboard = Keyboard Shortcuts
+settings-shortcut-conflicts =
+    { $count ->
+        [one] One shortcut is used by more than one action
+       *[other] { $count } shortcuts are used by more than one action
+    }
+settings-cache = Cache
+settings-performance = Performance & Diagnostics
@@ -1,4 +1,13 @@
 // Updated shader for streamlined adjustments - v2
+struct LocalMask {
+    // Radial: centre x, y and radius x, y. Linear: start x, y and end x, y.
+    geometry : vec4<f32>,
+    // Kind (0 radial, 1 linear), feather, invert, unused
+    shape : vec4<f32>,
+    // Exposure, saturation, temperature and tint offsets
+    amount : vec4<f32>,
+};
+
 struct Params {
     exposure : f32,
     saturation : f32,
@@ -43,6 +52,11 @@ struct Params {
     halation_color_g : f32,
     halation_color_b : f32,
     halation_radius : f32,
+    contrast : f32,
+    tint : f32,
+    // Local adjustments, matching LocalAdjustment::weight on the CPU
+    local_count : u32,
+    local_masks : array<LocalMask, 8>,
 };
 
 @group(0) @binding(0) var<storage, read> input_pixels: array<u32>;
@@ -77,6 +91,74 @@ fn hash(x: u32, y: u32, seed: u32) -> f32 {
     return f32(h) / f32(0xFFFFFFFFu) * 2.0 - 1.0;
 }
 
+// ============ LOCAL ADJUSTMENTS ============
+fn local_mask_weight(mask: LocalMask, uv: vec2<f32>, aspect: f32) -> f32 {
+    var weight = 0.0;
+    if (mask.shape.x < 0.5) {
+        let d = length((uv - mask.geometry.xy) / max(mask.geometry.zw, vec2<f32>(0.0001)));
+        weight = 1.0 - smoothstep(1.0 - max(mask.shape.y, 0.001), 1.0, d);
+    } else {
+        let scale = vec2<f32>(aspect, 1.0);
+        let dir = (mask.geometry.zw - mask.geometry.xy) * scale;
+        let t = dot((uv - mask.geometry.xy) * scale, dir) / max(dot(dir, dir), 0.000001);
+        weight = 1.0 - smoothstep(0.0, 1.0, t);
+    }
+    if (mask.shape.z > 0.5) {
+        weight = 1.0 - weight;
+    }
+    return weight;
+}
+
+// Exposure, saturation, temperature and tint offsets of every local adjustment at uv
+fn local_offsets(uv: vec2<f32>, aspect: f32) -> vec4<f32> {
+    var offsets = vec4<f32>(0.0);
+    for (var i = 0u; i < min(params.local_count, 8u); i = i + 1u) {
+        let mask = params.local_masks[i];
+        offsets = offsets + mask.amount * local_mask_weight(mask, uv, aspect);
+    }
+    return offsets;
+}
+
+// ============ WHITE BALANCE ============
+// Mirrors image_loader::white_balance on the CPU
+fn kelvin_to_linear_rgb(kelvin: f32) -> vec3<f32> {
+    let t = clamp(kelvin, 1667.0, 25000.0);
+    let t2 = t * t;
+    let t3 = t2 * t;
+    var x = -3.0258469e9 / t3 + 2.1070379e6 / t2 + 0.2226347e3 / t + 0.240390;
+    if (t <= 4000.0) {
+        x = -0.2661239e9 / t3 - 0.2343589e6 / t2 + 0.8776956e3 / t + 0.179910;
+    }
+    let x2 = x * x;
+    let x3 = x2 * x;
+    var y = 3.081758 * x3 - 5.873387 * x2 + 3.751130 * x - 0.3700148;
+    if (t <= 2222.0) {
+        y = -1.106381 * x3 - 1.348110 * x2 + 2.185558 * x - 0.2021968;
+    } else if (t <= 4000.0) {
+        y = -0.9549476 * x3 - 1.374186 * x2 + 2.091370 * x - 0.1674887;
+    }
+    let big_x = x / y;
+    let big_z = (1.0 - x - y) / y;
+    return vec3<f32>(
+        3.2406 * big_x - 1.5372 - 0.4986 * big_z,
+        -0.9689 * big_x + 1.8758 + 0.0415 * big_z,
+        0.0557 * big_x - 0.2040 + 1.0570 * big_z,
+    );
+}
+
+// Multipliers for gamma-encoded values; temperature doubles Kelvin per unit from 6500K
+fn white_balance_gains(temperature: f32, tint: f32) -> vec3<f32> {
+    if (temperature == 0.0 && tint == 0.0) {
+        return vec3<f32>(1.0);
+    }
+    let neutral = kelvin_to_linear_rgb(6500.0);
+    let light = kelvin_to_linear_rgb(6500.0 * exp2(temperature));
+    var gains = neutral / max(light, vec3<f32>(0.01));
+    gains.y = gains.y * exp2(-tint);
+    let luminance = dot(gains, vec3<f32>(0.2126, 0.7152, 0.0722));
+    return pow(gains / luminance, vec3<f32>(1.0 / 2.2));
+}
+
 // ============ ACES FILMIC TONE MAPPING ============
 // Based on Stephen Hill's fit of the ACES RRT+ODT
 // Provides cinematic highlight rolloff and natural color preservation
@@ -286,13 +368,23 @@ fn main_v2(@builtin(global_invocation_id) GlobalInvocationID: vec3<u32>) {
     
     // ============ STANDARD ADJUSTMENTS ============
     
+    // Local adjustments add to the global values under their masks
+    var local_offset = vec4<f32>(0.0);
+    if (params.local_count > 0u) {
+        let uv = (vec2<f32>(f32(px), f32(py)) + vec2<f32>(0.5)) / vec2<f32>(f32(params.width), f32(params.height));
+        local_offset = local_offsets(uv, f32(params.width) / f32(max(params.height, 1u)));
+    }
+    let exposure = params.exposure + local_offset.x;
+    let saturation = max(params.saturation + local_offset.y, 0.0);
+    let temperature = params.temperature + local_offset.z;
+    let tint = params.tint + local_offset.w;
+    
     // Apply exposure
-    let exposure_mult = pow(2.0, params.exposure);
+    let exposure_mult = pow(2.0, exposure);
     rgb = rgb * exposure_mult;
     
-    // Temperature
-    rgb.x = rgb.x + params.temperature * 25.5;
-    rgb.z = rgb.z - params.temperature * 15.3;
+    // White balance
+    rgb = rgb * white_balance_gains(temperature, tint);
     
     // ============ ACES FILMIC TONE MAPPING ============
     // Apply ACES for better highlight handling and cinematic look
@@ -307,15 +399,20 @@ fn main_v2(@builtin(global_invocation_id) GlobalInvocationID: vec3<u32>) {
         rgb = rgb_tonemapped * 255.0;
     }
     
+    // Contrast around mid-grey
+    if (params.contrast != 0.0) {
+        rgb = vec3<f32>(127.5) + (rgb - vec3<f32>(127.5)) * (1.0 + params.contrast);
+    }
+    
     // ============ OKLAB SATURATION (perceptually uniform) ============
     // Saturation (skip for B&W film) - now using OKLab for better perceptual uniformity
     if (!film_enabled || !film_is_bw) {
-        if (abs(params.saturation - 1.0) > 0.001) {
+        if (abs(saturation - 1.0) > 0.001) {
             // Normalize to 0-1
             let rgb_norm = clamp(rgb / 255.0, vec3<f32>(0.0), vec3<f32>(1.0));
             
             // Apply OKLab saturation for perceptually uniform results
-            let rgb_saturated = apply_oklab_saturation(rgb_norm, params.saturation);
+            let rgb_saturated = apply_oklab_saturation(rgb_norm, saturation);
             
             rgb = rgb_saturated * 255.0;
         }
@@ -0,0 +1,688 @@
+use super::{ImageViewerApp, ViewMode};
+use crate::metadata::Flag;
+use crate::settings::ColorLabel;
+use ActionCategory::{Editing, File, Navigation, View};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionCategory {
+    Navigation,
+    View,
+    Editing,
+    File,
+}
+
+impl ActionCategory {
+    pub fn all() -> &'static [ActionCategory] {
+        &[
+            ActionCategory::Navigation,
+            ActionCategory::View,
+            ActionCategory::Editing,
+            ActionCategory::File,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ActionCategory::Navigation => "Navigation",
+            ActionCategory::View => "View",
+            ActionCategory::Editing => "Editing",
+            ActionCategory::File => "File",
+        }
+    }
+
+    /// Name in the interface language
+    pub fn label(&self) -> String {
+        let key = format!("category-{}", self.name().to_lowercase());
+        crate::i18n::tr_or(&key, self.name())
+    }
+}
+
+/// A command that can be run from the palette or bound to keys
+pub struct Action {
+    pub id: &'static str,
+    pub title: &'static str,
+    pub category: ActionCategory,
+    /// Default shortcuts, in `KeyBinding` text form
+    pub keys: &'static [&'static str],
+    /// Still runs from the keyboard while a dialog is open
+    pub global: bool,
+    pub run: fn(&mut ImageViewerApp),
+}
+
+impl Action {
+    /// Title in the interface language; the English title when not translated
+    pub fn label(&self) -> String {
+        crate::i18n::tr_or(&format!("action-{}", self.id), self.title)
+    }
+}
+
+const fn action(
+    category: ActionCategory,
+    id: &'static str,
+    title: &'static str,
+    keys: &'static [&'static str],
+    run: fn(&mut ImageViewerApp),
+) -> Action {
+    Action {
+        id,
+        title,
+        category,
+        keys,
+        global: false,
+        run,
+    }
+}
+
+const fn global(
+    category: ActionCategory,
+    id: &'static str,
+    title: &'static str,
+    keys: &'static [&'static str],
+    run: fn(&mut ImageViewerApp),
+) -> Action {
+    Action {
+        global: true,
+        ..action(category, id, title, keys, run)
+    }
+}
+
+pub static ACTIONS: &[Action] = &[
+    // Navigation
+    global(Navigation, "next", "Next Image", &["Right", "D"], |app| {
+        app.pending_navigate_next = true
+    }),
+    global(
+        Navigation,
+        "previous",
+        "Previous Image",
+        &["Left", "A"],
+        |app| app.pending_navigate_prev = true,
+    ),
+    global(Navigation, "first", "First Image", &["Home"], |app| {
+        app.pending_navigate_first = true
+    }),
+    global(Navigation, "last", "Last Image", &["End"], |app| {
+        app.pending_navigate_last = true
+    }),
+    global(Navigation, "page_up", "Page Up", &["PageUp"], |app| {
+        app.pending_navigate_page_up = true
+    }),
+    global(Navigation, "page_down", "Page Down", &["PageDown"], |app| {
+        app.pending_navigate_page_down = true
+    }),
+    action(Navigation, "go_to", "Go to Image", &["Ctrl+G"], |app| {
+        app.show_go_to_dialog = true;
+        app.go_to_input.clear();
+    }),
+    global(Navigation, "search", "Search", &["Ctrl+F"], |app| {
+        app.search_visible = !app.search_visible
+    }),
+    action(Navigation, "select_all", "Select All", &["Ctrl+A"], |app| {
+        app.selected_indices = (0..app.filtered_list.len()).collect();
+    }),
+    action(
+        Navigation,
+        "clear_keyword_filter",
+        "Clear Keyword Filter",
+        &[],
+        |app| app.set_keyword_filter(None),
+    ),
+    action(Navigation, "show_picks", "Show Picked Only", &[], |app| {
+        app.set_flag_filter(Some(Flag::Pick))
+    }),
+    action(
+        Navigation,
+        "show_rejects",
+        "Show Rejected Only",
+        &[],
+        |app| app.set_flag_filter(Some(Flag::Reject)),
+    ),
+    action(
+        Navigation,
+        "clear_flag_filter",
+        "Clear Flag Filter",
+        &[],
+        |app| app.set_flag_filter(None),
+    ),
+    action(
+        Navigation,
+        "clear_filter_bar",
+        "Clear Filters",
+        &[],
+        |app| app.clear_filter_bar(),
+    ),
+    action(
+        Navigation,
+        "new_smart_collection",
+        "New Smart Collection",
+        &[],
+        |app| app.new_smart_collection(),
+    ),
+    action(
+        Navigation,
+        "slideshow",
+        "Start/Stop Slideshow",
+        &[],
+        |app| app.toggle_slideshow(),
+    ),
+    action(
+        Navigation,
+        "slideshow_skip",
+        "Skip to Next Slide",
+        &["Shift+Right"],
+        |app| app.skip_slide(),
+    ),
+    action(
+        Navigation,
+        "slideshow_hold",
+        "Hold/Resume Slideshow",
+        &["Shift+Space"],
+        |app| app.toggle_slideshow_hold(),
+    ),
+    action(
+        Navigation,
+        "command_palette",
+        "Command Palette",
+        &["Ctrl+P"],
+        |app| app.open_command_palette(),
+    ),
+    // View
+    action(
+        View,
+        "zoom_in",
+        "Zoom In",
+        &["Plus", "Shift+Plus", "Equals"],
+        |app| app.zoom_in(),
+    ),
+    action(View, "zoom_out", "Zoom Out", &["Minus"], |app| {
+        app.zoom_out()
+    }),
+    action(View, "zoom_fit", "Fit to Window", &["0"], |app| {
+        app.reset_view()
+    }),
+    action(View, "actual_size", "Actual Size (100%)", &["1"], |app| {
+        app.zoom_to(1.0)
+    }),
+    action(View, "zoom_200", "Zoom 200%", &["2"], |app| {
+        app.zoom_to(2.0)
+    }),
+    action(
+        View,
+        "fullscreen",
+        "Toggle Fullscreen",
+        &["F11", "F"],
+        |app| app.toggle_fullscreen(),
+    ),
+    action(
+        View,
+        "fullscreen_monitor",
+        "Fullscreen on Next Monitor",
+        &["Shift+F11"],
+        |app| app.fullscreen_on_next_monitor(),
+    ),
+    action(
+        View,
+        "mini_viewer",
+        "Mini Viewer (Always on Top)",
+        &["Ctrl+M"],
+        |app| app.toggle_mini_viewer(),
+    ),
+    action(
+        View,
+        "reference_overlay",
+        "Reference Overlay...",
+        &["Alt+O"],
+        |app| app.show_reference_overlay_dialog = !app.show_reference_overlay_dialog,
+    ),
+    action(
+        View,
+        "compare",
+        "Toggle Compare View",
+        &["C"],
+        |app| match app.view_mode {
+            ViewMode::Compare => app.view_mode = ViewMode::Single,
+            _ => app.start_compare(),
+        },
+    ),
+    action(View, "scroll_view", "Toggle Scroll View", &["W"], |app| {
+        app.toggle_scroll_view()
+    }),
+    action(
+        View,
+        "compare_difference",
+        "Toggle Compare Difference",
+        &["Shift+C"],
+        |app| {
+            app.compare_difference = !app.compare_difference;
+            if app.compare_difference && app.view_mode != ViewMode::Compare {
+                app.start_compare();
+            }
+        },
+    ),
+    action(
+        View,
+        "show_original",
+        "Show Original",
+        &["Backslash"],
+        |app| {
+            app.show_original = !app.show_original;
+            app.refresh_adjustments();
+        },
+    ),
+    action(
+        View,
+        "split_view",
+        "Toggle Before/After Split",
+        &["Y"],
+        |app| app.toggle_split_view(),
+    ),
+    action(
+        View,
+        "split_direction",
+        "Flip Before/After Split",
+        &["Shift+Y"],
+        |app| app.flip_split_direction(),
+    ),
+    action(
+        View,
+        "focus_peaking",
+        "Toggle Focus Peaking",
+        &["Ctrl+Shift+F"],
+        |app| app.toggle_focus_peaking(),
+    ),
+    action(View, "zebras", "Toggle Zebras", &["Alt+Z"], |app| {
+        app.toggle_zebras()
+    }),
+    action(View, "clipping", "Toggle Clipping Warning", &["J"], |app| {
+        app.toggle_clipping_warning()
+    }),
+    action(
+        View,
+        "grid",
+        "Toggle Grid Overlay",
+        &["Ctrl+Shift+G"],
+        |app| app.settings.show_grid_overlay = !app.settings.show_grid_overlay,
+    ),
+    action(View, "loupe", "Toggle Loupe", &["Ctrl+L"], |app| {
+        app.toggle_loupe()
+    }),
+    action(View, "sidebar", "Toggle Sidebar", &["S"], |app| {
+        app.settings.show_sidebar = !app.settings.show_sidebar
+    }),
+    action(
+        View,
+        "soft_proof",
+        "Toggle Soft Proofing",
+        &["Shift+S"],
+        |app| app.toggle_soft_proof(),
+    ),
+    action(View, "thumbnails", "Toggle Thumbnails", &["T"], |app| {
+        app.settings.show_thumbnails = !app.settings.show_thumbnails
+    }),
+    action(View, "exif", "Toggle EXIF Info", &["I"], |app| {
+        app.settings.show_exif = !app.settings.show_exif
+    }),
+    action(
+        View,
+        "all_metadata",
+        "Show All Metadata",
+        &["Shift+I"],
+        |app| app.open_all_metadata(),
+    ),
+    action(View, "timeline", "Timeline", &[], |app| app.open_timeline()),
+    action(View, "exif_overlay", "Toggle EXIF Overlay", &["E"], |app| {
+        app.settings.show_exif_overlay = !app.settings.show_exif_overlay
+    }),
+    action(View, "histogram", "Toggle Histogram", &["H"], |app| {
+        app.settings.show_histogram = !app.settings.show_histogram
+    }),
+    action(
+        View,
+        "adjustments",
+        "Toggle Adjustments",
+        &["Shift+A"],
+        |app| app.settings.show_adjustments = !app.settings.show_adjustments,
+    ),
+    action(View, "filter_bar", "Toggle Filter Bar", &[], |app| {
+        app.settings.show_filter_bar = !app.settings.show_filter_bar
+    }),
+    action(View, "panels", "Toggle Panels", &["Shift+P"], |app| {
+        app.toggle_panels()
+    }),
+    action(
+        View,
+        "workspace_cull",
+        "Cull Workspace",
+        &["Alt+1"],
+        |app| app.apply_workspace("Cull"),
+    ),
+    action(
+        View,
+        "workspace_edit",
+        "Edit Workspace",
+        &["Alt+2"],
+        |app| app.apply_workspace("Edit"),
+    ),
+    action(
+        View,
+        "workspace_review",
+        "Review Workspace",
+        &["Alt+3"],
+        |app| app.apply_workspace("Review"),
+    ),
+    action(
+        View,
+        "play_animation",
+        "Play/Pause Animation or Video",
+        &["Space"],
+        |app| {
+            if app.animation.is_some() {
+                app.toggle_animation_playback();
+            } else if app.video.is_some() {
+                app.toggle_video_playback();
+            }
+        },
+    ),
+    // Editing
+    action(Editing, "undo", "Undo", &["Ctrl+Z"], |app| {
+        app.undo_last_operation()
+    }),
+    action(
+        Editing,
+        "redo",
+        "Redo",
+        &["Ctrl+Y", "Ctrl+Shift+Z"],
+        |app| app.redo_last_operation(),
+    ),
+    action(Editing, "rotate_left", "Rotate Left", &["L"], |app| {
+        app.rotate_left()
+    }),
+    action(Editing, "rotate_right", "Rotate Right", &["R"], |app| {
+        app.rotate_right()
+    }),
+    action(Editing, "rate_0", "Clear Rating", &["Ctrl+0"], |app| {
+        app.set_current_rating(0)
+    }),
+    action(Editing, "rate_1", "Rate 1 Star", &["Ctrl+1"], |app| {
+        app.set_current_rating(1)
+    }),
+    action(Editing, "rate_2", "Rate 2 Stars", &["Ctrl+2"], |app| {
+        app.set_current_rating(2)
+    }),
+    action(Editing, "rate_3", "Rate 3 Stars", &["Ctrl+3"], |app| {
+        app.set_current_rating(3)
+    }),
+    action(Editing, "rate_4", "Rate 4 Stars", &["Ctrl+4"], |app| {
+        app.set_current_rating(4)
+    }),
+    action(Editing, "rate_5", "Rate 5 Stars", &["Ctrl+5"], |app| {
+        app.set_current_rating(5)
+    }),
+    action(
+        Editing,
+        "label_red",
+        "Toggle Red Label",
+        &["Ctrl+6"],
+        |app| app.toggle_current_color_label(ColorLabel::Red),
+    ),
+    action(
+        Editing,
+        "label_yellow",
+        "Toggle Yellow Label",
+        &["Ctrl+7"],
+        |app| app.toggle_current_color_label(ColorLabel::Yellow),
+    ),
+    action(
+        Editing,
+        "label_green",
+        "Toggle Green Label",
+        &["Ctrl+8"],
+        |app| app.toggle_current_color_label(ColorLabel::Green),
+    ),
+    action(
+        Editing,
+        "label_blue",
+        "Toggle Blue Label",
+        &["Ctrl+9"],
+        |app| app.toggle_current_color_label(ColorLabel::Blue),
+    ),
+    action(Editing, "pick", "Flag as Pick", &["P"], |app| {
+        app.toggle_current_flag(Flag::Pick)
+    }),
+    action(Editing, "reject", "Flag as Reject", &["X"], |app| {
+        app.toggle_current_flag(Flag::Reject)
+    }),
+    action(Editing, "unflag", "Remove Flag", &["U"], |app| {
+        app.toggle_current_flag(Flag::None)
+    }),
+    action(Editing, "add_keyword", "Add Keyword", &["K"], |app| {
+        app.settings.show_sidebar = true;
+        app.focus_keyword_input = true;
+    }),
+    action(
+        Editing,
+        "copy_adjustments",
+        "Copy Adjustments",
+        &["Ctrl+Shift+C"],
+        |app| app.copy_adjustments(),
+    ),
+    action(
+        Editing,
+        "paste_adjustments",
+        "Paste Adjustments",
+        &["Ctrl+Shift+V"],
+        |app| app.paste_adjustments(),
+    ),
+    action(
+        Editing,
+        "paste_adjustments_selected",
+        "Paste Adjustments to Selected",
+        &["Ctrl+Alt+V"],
+        |app| app.paste_adjustments_to_selected(),
+    ),
+    action(Editing, "auto_enhance", "Auto-enhance", &[], |app| {
+        app.auto_enhance()
+    }),
+    action(
+        Editing,
+        "auto_enhance_selected",
+        "Auto-enhance Selected",
+        &[],
+        |app| app.auto_enhance_selected(),
+    ),
+    action(
+        Editing,
+        "presets",
+        "Manage Adjustment Presets",
+        &[],
+        |app| app.show_presets_dialog = true,
+    ),
+    action(Editing, "film_stocks", "Manage Film Stocks", &[], |app| {
+        app.show_film_stocks_dialog = true
+    }),
+    // File
+    action(File, "open_file", "Open File", &["Ctrl+O"], |app| {
+        app.open_file_dialog()
+    }),
+    action(
+        File,
+        "open_folder",
+        "Open Folder",
+        &["Ctrl+Shift+O"],
+        |app| app.open_folder_dialog(),
+    ),
+    action(File, "tether", "Tether to Folder...", &[], |app| {
+        app.toggle_tether()
+    }),
+    action(File, "open_url", "Open URL...", &["Ctrl+Shift+U"], |app| {
+        app.open_url_dialog()
+    }),
+    action(File, "new_tab", "New Tab", &["Ctrl+T"], |app| app.new_tab()),
+    action(File, "close_tab", "Close Tab", &["Ctrl+W"], |app| {
+        app.close_tab(app.active_tab)
+    }),
+    action(Navigation, "next_tab", "Next Tab", &["Ctrl+Tab"], |app| {
+        app.next_tab()
+    }),
+    action(
+        Navigation,
+        "previous_tab",
+        "Previous Tab",
+        &["Ctrl+Shift+Tab"],
+        |app| app.previous_tab(),
+    ),
+    action(File, "move", "Move to Folder", &["M"], |app| {
+        app.handle_move_key()
+    }),
+    action(
+        File,
+        "copy_image",
+        "Copy Image to Clipboard",
+        &["Ctrl+C"],
+        |app| app.copy_image_to_clipboard(),
+    ),
+    action(File, "copy_file", "Copy File to Clipboard", &[], |app| {
+        app.copy_file_to_clipboard()
+    }),
+    action(File, "copy_path", "Copy Path", &[], |app| {
+        app.copy_path_to_clipboard()
+    }),
+    action(
+        File,
+        "paste_image",
+        "Paste Image from Clipboard",
+        &["Ctrl+V"],
+        |app| app.paste_image_from_clipboard(),
+    ),
+    action(
+        File,
+        "save_pasted_image",
+        "Save Pasted Image As...",
+        &["Ctrl+Shift+S"],
+        |app| app.save_pasted_image(),
+    ),
+    action(File, "delete", "Delete Image", &["Delete"], |app| {
+        app.delete_current_image()
+    }),
+    action(
+        File,
+        "move_rejected",
+        "Move Rejected to Folder",
+        &[],
+        |app| {
+            if app.select_rejected() {
+                app.show_move_dialog = true;
+            }
+        },
+    ),
+    action(File, "delete_rejected", "Delete Rejected", &[], |app| {
+        if app.select_rejected() {
+            app.delete_current_image();
+        }
+    }),
+    action(File, "recently_deleted", "Recently Deleted", &[], |app| {
+        app.show_recently_deleted_dialog = true
+    }),
+    action(File, "batch_rename", "Batch Rename", &["F2"], |app| {
+        app.open_rename_dialog()
+    }),
+    action(File, "batch_metadata", "Batch Edit Metadata", &[], |app| {
+        app.open_batch_metadata()
+    }),
+    action(
+        File,
+        "batch_export",
+        "Batch Export Selected",
+        &["Ctrl+Shift+E"],
+        |app| app.start_batch_export(),
+    ),
+    action(File, "convert_dng", "Convert RAW to DNG", &[], |app| {
+        app.start_dng_conversion()
+    }),
+    action(File, "shoot_summary", "Shoot Summary", &[], |app| {
+        app.open_shoot_summary()
+    }),
+    action(File, "contact_sheet", "Contact Sheet", &[], |app| {
+        app.show_contact_sheet_dialog = true
+    }),
+    action(File, "web_gallery", "Generate Web Gallery", &[], |app| {
+        app.show_web_gallery_dialog = true
+    }),
+    action(
+        File,
+        "playlist_add",
+        "Add to Slideshow Playlist",
+        &[],
+        |app| app.add_to_playlist(),
+    ),
+    action(File, "playlist", "Slideshow Playlist", &[], |app| {
+        app.show_playlist_dialog = true
+    }),
+    action(File, "wallpaper", "Set as Wallpaper", &[], |app| {
+        app.set_as_wallpaper()
+    }),
+    action(File, "scripts_folder", "Open Scripts Folder", &[], |app| {
+        app.open_scripts_folder()
+    }),
+    action(File, "settings", "Settings", &[], |app| {
+        app.show_settings_dialog = true
+    }),
+];
+
+pub fn find_action(id: &str) -> Option<&'static Action> {
+    ACTIONS.iter().find(|a| a.id == id)
+}
+
+/// Score `text` against a fuzzy `query`: every query character must appear in
+/// order. Runs of consecutive characters and matches at word starts score higher.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = position + text[position..].iter().position(|&c| c == q)?;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(found);
+        position = found + 1;
+    }
+    // Prefer shorter titles when the match is otherwise equal
+    Some(score * 100 - text.len() as i32)
+}
+
+/// Actions matching `query`, best first; all actions in registry order when it is empty
+pub fn search_actions(query: &str) -> Vec<&'static Action> {
+    if query.trim().is_empty() {
+        return ACTIONS.iter().collect();
+    }
+    let mut matches: Vec<(i32, &'static Action)> = ACTIONS
+        .iter()
+        .filter_map(|a| {
+            // English titles keep working in other languages
+            let label = a.label();
+            let title = fuzzy_score(query, &label).max(fuzzy_score(query, a.title));
+            let category =
+                fuzzy_score(query, &format!("{} {}", a.category.label(), label)).map(|s| s - 50);
+            title.max(category).map(|s| (s, a))
+        })
+        .collect();
+    matches.sort_by(|a, b| b.0.cmp(&a.0));
+    matches.into_iter().map(|(_, a)| a).collect()
+}
+
+impl ImageViewerApp {
+    /// Run a registered action by id
+    pub fn run_action(&mut self, id: &str) {
+        match find_action(id) {
+            Some(action) => (action.run)(self),
+            None => log::warn!("Unknown action: {}", id),
+        }
+    }
+}
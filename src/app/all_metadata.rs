@@ -0,0 +1,35 @@
+use super::{ImageViewerApp, LoaderMessage};
+use crate::exif_data::{self, MetadataTag};
+use std::path::PathBuf;
+
+impl ImageViewerApp {
+    pub fn open_all_metadata(&mut self) {
+        self.show_all_metadata_dialog = true;
+        self.request_all_metadata();
+    }
+
+    /// Read every tag of the current image in the background, unless the
+    /// browser already holds or is reading them
+    pub fn request_all_metadata(&mut self) {
+        let Some(path) = self.get_current_path() else {
+            return;
+        };
+        let loaded = self.all_metadata.as_ref().is_some_and(|(p, _)| *p == path);
+        if loaded || self.all_metadata_pending.as_ref() == Some(&path) {
+            return;
+        }
+
+        self.all_metadata_pending = Some(path.clone());
+        self.spawn_loader(move |_| {
+            let tags = exif_data::all_metadata(&path);
+            Some(LoaderMessage::AllMetadataLoaded(path, tags))
+        });
+    }
+
+    pub fn handle_all_metadata_loaded(&mut self, path: PathBuf, tags: Vec<MetadataTag>) {
+        if self.all_metadata_pending.as_ref() == Some(&path) {
+            self.all_metadata_pending = None;
+        }
+        self.all_metadata = Some((path, tags));
+    }
+}
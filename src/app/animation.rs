@@ -0,0 +1,122 @@
+use super::{ImageViewerApp, LoaderMessage};
+use crate::image_loader::{load_animation, AnimationFrame, MAX_ANIMATION_BYTES};
+use egui::TextureHandle;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Playback state for an animated GIF / APNG / WebP shown in the single view
+pub struct AnimationPlayback {
+    pub path: PathBuf,
+    pub frames: Vec<(TextureHandle, Duration)>,
+    pub current_frame: usize,
+    pub frame_started: Instant,
+    pub playing: bool,
+}
+
+impl AnimationPlayback {
+    /// Advance to the frame that should be visible now and return how long until the next one
+    pub fn advance(&mut self) -> Option<Duration> {
+        if !self.playing || self.frames.is_empty() {
+            return None;
+        }
+
+        let now = Instant::now();
+        let mut elapsed = now.duration_since(self.frame_started);
+        let mut delay = self.frames[self.current_frame].1;
+
+        // Skip frames if rendering fell behind, but never loop more than once per update
+        let mut skipped = 0;
+        while elapsed >= delay && skipped < self.frames.len() {
+            elapsed -= delay;
+            self.current_frame = (self.current_frame + 1) % self.frames.len();
+            delay = self.frames[self.current_frame].1;
+            skipped += 1;
+        }
+        if skipped > 0 {
+            self.frame_started = now - elapsed;
+        }
+
+        Some(delay.saturating_sub(elapsed))
+    }
+
+    pub fn current_texture(&self) -> Option<&TextureHandle> {
+        self.frames.get(self.current_frame).map(|(tex, _)| tex)
+    }
+}
+
+impl ImageViewerApp {
+    /// Decode animation frames in the background for formats that may be animated
+    pub fn request_animation(&mut self, path: &Path) {
+        self.animation = None;
+        if !crate::image_loader::is_animation_candidate(path) {
+            return;
+        }
+
+        let path = path.to_path_buf();
+        self.spawn_loader(move |_| match load_animation(&path, MAX_ANIMATION_BYTES) {
+            Ok(Some(frames)) => Some(LoaderMessage::AnimationLoaded(path, frames)),
+            Ok(None) => None,
+            Err(e) => {
+                log::warn!("Failed to decode animation {}: {}", path.display(), e);
+                None
+            }
+        });
+    }
+
+    pub fn handle_animation_loaded(
+        &mut self,
+        path: PathBuf,
+        frames: Vec<AnimationFrame>,
+        ctx: &egui::Context,
+    ) {
+        if self.get_current_path().as_ref() != Some(&path) {
+            return;
+        }
+
+        let textures = frames
+            .into_iter()
+            .enumerate()
+            .map(|(i, frame)| {
+                let rgba = frame.image.to_rgba8();
+                let size = [rgba.width() as usize, rgba.height() as usize];
+                let texture = ctx.load_texture(
+                    format!("anim_{}_{}", path.display(), i),
+                    egui::ColorImage::from_rgba_unmultiplied(size, rgba.as_raw()),
+                    egui::TextureOptions::LINEAR,
+                );
+                (texture, frame.delay)
+            })
+            .collect();
+
+        self.animation = Some(AnimationPlayback {
+            path,
+            frames: textures,
+            current_frame: 0,
+            frame_started: Instant::now(),
+            playing: true,
+        });
+        ctx.request_repaint();
+    }
+
+    pub fn toggle_animation_playback(&mut self) {
+        if let Some(anim) = &mut self.animation {
+            anim.playing = !anim.playing;
+            // Resume from the frame that was showing instead of jumping ahead
+            anim.frame_started = Instant::now();
+        }
+    }
+
+    /// Texture for the current animation frame, advancing playback and scheduling the next repaint
+    pub fn current_animation_texture(&mut self, ctx: &egui::Context) -> Option<TextureHandle> {
+        let current_path = self.get_current_path();
+        let anim = self.animation.as_mut()?;
+        if current_path.as_ref() != Some(&anim.path) {
+            return None;
+        }
+
+        if let Some(wait) = anim.advance() {
+            ctx.request_repaint_after(wait);
+        }
+        anim.current_texture().cloned()
+    }
+}
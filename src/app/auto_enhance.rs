@@ -0,0 +1,94 @@
+use super::{ImageViewerApp, LoaderMessage};
+use crate::image_loader::{self, AutoEnhance};
+use crate::metadata::FileOperation;
+use std::path::PathBuf;
+
+/// Size of the thumbnail analysed for each photo in a batch
+const BATCH_ANALYSIS_SIZE: u32 = 512;
+
+impl ImageViewerApp {
+    /// Set exposure, contrast and white balance from the current image's histogram
+    pub fn auto_enhance(&mut self) {
+        let Some(image) = self.current_image.as_ref() else {
+            self.show_status("No image to enhance");
+            return;
+        };
+        let auto = image_loader::auto_enhance(image);
+
+        let previous = self.adjustments.clone();
+        auto.apply_to(&mut self.adjustments);
+        if self.adjustments != previous {
+            self.commit_adjustment_change(previous);
+        }
+        self.show_status(&format!("Auto: {}", auto.summary()));
+    }
+
+    /// Auto-enhance every selected image, or the current one when nothing is
+    /// selected. Each photo is analysed from a thumbnail in the background.
+    pub fn auto_enhance_selected(&mut self) {
+        let mut paths = self.selected_paths();
+        if paths.is_empty() {
+            paths.extend(self.get_current_path());
+        }
+        self.auto_enhance_paths(paths);
+    }
+
+    pub fn auto_enhance_paths(&mut self, paths: Vec<PathBuf>) {
+        if paths.is_empty() {
+            self.show_status("No images to enhance");
+            return;
+        }
+
+        self.show_status(&format!("Auto-enhancing {} images...", paths.len()));
+        self.spawn_loader(move |_| {
+            let results: Vec<(PathBuf, AutoEnhance)> = paths
+                .into_iter()
+                .filter_map(
+                    |path| match image_loader::load_thumbnail(&path, BATCH_ANALYSIS_SIZE) {
+                        Ok(thumbnail) => Some((path, image_loader::auto_enhance(&thumbnail))),
+                        Err(e) => {
+                            log::warn!("Auto-enhance skipped {}: {}", path.display(), e);
+                            None
+                        }
+                    },
+                )
+                .collect();
+            Some(LoaderMessage::AutoEnhanced(results))
+        });
+    }
+
+    pub fn handle_auto_enhanced(&mut self, results: Vec<(PathBuf, AutoEnhance)>) {
+        let current_path = self.get_current_path();
+        let mut count = 0;
+        for (path, auto) in results {
+            let previous = if current_path.as_ref() == Some(&path) {
+                self.adjustments.clone()
+            } else {
+                self.metadata_db.get_adjustments(&path).unwrap_or_default()
+            };
+            let mut enhanced = previous.clone();
+            auto.apply_to(&mut enhanced);
+            if enhanced == previous {
+                continue;
+            }
+
+            self.metadata_db.set_adjustments(path.clone(), &enhanced);
+            self.thumbnail_textures.remove(&path);
+            self.thumbnail_requests.remove(&path);
+            if current_path.as_ref() == Some(&path) {
+                self.adjustments = enhanced.clone();
+                self.refresh_adjustments();
+            }
+            // One undo step per image, as when pasting adjustments
+            self.undo_history.push(FileOperation::Adjust {
+                path,
+                adjustments: enhanced,
+                previous_adjustments: Box::new(previous),
+            });
+            count += 1;
+        }
+        self.metadata_db.save();
+
+        self.show_status(&format!("Auto-enhanced {} images", count));
+    }
+}
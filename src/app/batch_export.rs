@@ -0,0 +1,261 @@
+use super::file_ops::ExportOptions;
+use super::share::ShareUpload;
+use super::{ImageViewerApp, LoaderMessage};
+use crate::export_naming::{resolve_export_paths, ExportDestination, ExportNameInfo, ExportNaming};
+use crate::image_loader::ImageAdjustments;
+use crate::rename::RenameInfo;
+use crate::settings::ExportFormat;
+use rayon::prelude::*;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Progress of a background export of several images
+pub struct BatchExport {
+    pub output_folder: PathBuf,
+    pub total: usize,
+    pub completed: usize,
+    /// Files left alone because the export already existed
+    pub skipped: usize,
+    pub failed: Vec<(PathBuf, String)>,
+    pub cancel: Arc<AtomicBool>,
+    pub started: Instant,
+    /// Set once every worker has stopped
+    pub finished_in: Option<Duration>,
+    /// Set when the exported images are uploaded from the Share menu
+    pub share: Option<ShareUpload>,
+}
+
+impl BatchExport {
+    pub fn is_finished(&self) -> bool {
+        self.finished_in.is_some()
+    }
+
+    pub fn processed(&self) -> usize {
+        self.completed + self.skipped + self.failed.len()
+    }
+
+    pub fn cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+}
+
+/// Output file of each `(source, adjustments, rating)` job by the naming rules;
+/// None for files skipped because they were exported before
+fn plan_batch_outputs(
+    jobs: &[(PathBuf, ImageAdjustments, u8)],
+    naming: &ExportNaming,
+    format: ExportFormat,
+) -> Vec<Option<PathBuf>> {
+    let needs_info = naming.needs_info();
+    let targets = jobs
+        .par_iter()
+        .enumerate()
+        .map(|(i, (source, _, rating))| {
+            let info = ExportNameInfo {
+                file: if needs_info {
+                    RenameInfo::from_file(source)
+                } else {
+                    RenameInfo::default()
+                },
+                rating: *rating,
+            };
+            let base = naming.base_folder(source).unwrap_or_default();
+            naming.target(&base, source, i as u32 + 1, &info, format)
+        })
+        .collect();
+    resolve_export_paths(targets, naming.conflict)
+}
+
+/// Output file for each source image, named like single-image export and never
+/// overwriting an existing file or another image from the same batch
+pub fn batch_output_paths(
+    sources: &[PathBuf],
+    folder: &Path,
+    format: ExportFormat,
+) -> Vec<PathBuf> {
+    let mut taken = HashSet::new();
+    sources
+        .iter()
+        .map(|source| {
+            let stem = source
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| "exported_image".to_string());
+            let mut candidate = folder.join(format!("{}_rView.{}", stem, format.extension()));
+            let mut n = 2;
+            while candidate.exists() || taken.contains(&candidate) {
+                candidate = folder.join(format!("{}_rView_{}.{}", stem, n, format.extension()));
+                n += 1;
+            }
+            taken.insert(candidate.clone());
+            candidate
+        })
+        .collect()
+}
+
+impl ImageViewerApp {
+    /// Export the selected thumbnails, or the whole filtered list when nothing is selected
+    pub fn start_batch_export(&mut self) {
+        if self.batch_export.as_ref().is_some_and(|b| !b.is_finished()) {
+            return;
+        }
+
+        let sources = self.selected_or_filtered_paths();
+        if sources.is_empty() {
+            self.show_status("No images to export");
+            return;
+        }
+
+        let mut naming = self.settings.export_naming.clone();
+        if naming.destination == ExportDestination::Ask {
+            let Some(folder) = rfd::FileDialog::new()
+                .set_title(format!("Export {} images to", sources.len()))
+                .pick_folder()
+            else {
+                return;
+            };
+            naming.destination = ExportDestination::Folder(folder);
+        }
+        let output_folder = naming.base_folder(&sources[0]).unwrap_or_default();
+
+        let jobs: Vec<(PathBuf, ImageAdjustments, u8)> = sources
+            .into_iter()
+            .map(|source| {
+                let adjustments = self.export_adjustments(&source);
+                let rating = self.metadata_db.get(&source).rating;
+                (source, adjustments, rating)
+            })
+            .collect();
+
+        let options = ExportOptions::from_settings(&self.settings);
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.batch_export = Some(BatchExport {
+            output_folder,
+            total: jobs.len(),
+            completed: 0,
+            skipped: 0,
+            failed: Vec::new(),
+            cancel: Arc::clone(&cancel),
+            started: Instant::now(),
+            finished_in: None,
+            share: None,
+        });
+
+        let ctx = self.ctx.clone();
+        self.spawn_long_job(move |tx| {
+            let outputs = plan_batch_outputs(&jobs, &naming, options.format);
+            jobs.into_par_iter().zip(outputs).for_each_with(
+                tx.clone(),
+                |tx, ((source, adjustments, _), output)| {
+                    if cancel.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    let message = match output {
+                        Some(output) => {
+                            let folder = output.parent().unwrap_or(Path::new(""));
+                            let error = std::fs::create_dir_all(folder)
+                                .map_err(|e| e.to_string())
+                                .and_then(|()| export_one(&source, &output, &adjustments, &options))
+                                .err();
+                            LoaderMessage::BatchExportProgress(source, error)
+                        }
+                        None => LoaderMessage::BatchExportSkipped(source),
+                    };
+                    let _ = tx.send(message);
+                    if let Some(ctx) = &ctx {
+                        ctx.request_repaint();
+                    }
+                },
+            );
+            Some(LoaderMessage::BatchExportFinished)
+        });
+    }
+
+    /// Each image exports with its own saved adjustments; the current one may have unsaved edits
+    pub fn export_adjustments(&self, path: &Path) -> ImageAdjustments {
+        if self.get_current_path().as_deref() == Some(path) {
+            self.adjustments.clone()
+        } else {
+            self.metadata_db.get_adjustments(path).unwrap_or_default()
+        }
+    }
+
+    pub fn cancel_batch_export(&mut self) {
+        if let Some(batch) = &self.batch_export {
+            batch.cancel.store(true, Ordering::Relaxed);
+        }
+    }
+
+    pub fn handle_batch_export_progress(&mut self, path: PathBuf, error: Option<String>) {
+        if let Some(batch) = &mut self.batch_export {
+            match error {
+                Some(e) => {
+                    log::warn!("Batch export failed for {}: {}", path.display(), e);
+                    batch.failed.push((path, e));
+                }
+                None => batch.completed += 1,
+            }
+        }
+    }
+
+    pub fn handle_batch_export_skipped(&mut self, path: PathBuf) {
+        log::info!("Batch export skipped {}, already exported", path.display());
+        if let Some(batch) = &mut self.batch_export {
+            batch.skipped += 1;
+        }
+    }
+
+    pub fn handle_batch_export_finished(&mut self) {
+        let Some(batch) = &mut self.batch_export else {
+            return;
+        };
+        batch.finished_in = Some(batch.started.elapsed());
+        if batch.share.is_some() {
+            self.handle_share_finished();
+            return;
+        }
+        let message = if batch.cancelled() {
+            format!(
+                "Export cancelled after {} of {} images",
+                batch.processed(),
+                batch.total
+            )
+        } else if batch.failed.is_empty() && batch.skipped == 0 {
+            format!(
+                "Exported {} images to {}",
+                batch.completed,
+                batch.output_folder.display()
+            )
+        } else if batch.failed.is_empty() {
+            format!(
+                "Exported {} images, skipped {} already exported",
+                batch.completed, batch.skipped
+            )
+        } else {
+            format!(
+                "Exported {} images, {} failed",
+                batch.completed,
+                batch.failed.len()
+            )
+        };
+        self.show_status(&message);
+    }
+}
+
+pub fn export_one(
+    source: &Path,
+    output: &Path,
+    adjustments: &ImageAdjustments,
+    options: &ExportOptions,
+) -> Result<(), String> {
+    let image = crate::image_loader::load_image(source).map_err(|e| e.to_string())?;
+    let image = if adjustments.is_default() {
+        image
+    } else {
+        crate::image_loader::apply_adjustments_for_export(&image, adjustments, source)
+    };
+    options.save(image, Some(source), output)
+}
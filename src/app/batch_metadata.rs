@@ -0,0 +1,138 @@
+use super::{ImageViewerApp, LoaderMessage};
+use crate::batch_metadata::{plan_metadata, write_metadata, PlannedMetadata};
+use crate::rename::RenameInfo;
+use rayon::prelude::*;
+use std::path::PathBuf;
+
+/// Files open in the batch metadata dialog
+pub struct MetadataSession {
+    pub sources: Vec<PathBuf>,
+    /// Dates for `{year}` and `{date}`, read in the background when the dialog opens
+    pub infos: Option<Vec<RenameInfo>>,
+    /// Set while the files are being written
+    pub writing: bool,
+    /// Row of the template field the token buttons insert into
+    pub token_field: usize,
+}
+
+impl ImageViewerApp {
+    /// Open the batch metadata dialog for the selection, or the current image when nothing is selected
+    pub fn open_batch_metadata(&mut self) {
+        let mut sources = self.selected_paths();
+        if sources.is_empty() {
+            sources.extend(self.get_current_path());
+        }
+        if sources.is_empty() {
+            self.show_status("No images to edit");
+            return;
+        }
+
+        let paths = sources.clone();
+        self.metadata_session = Some(MetadataSession {
+            sources,
+            infos: None,
+            writing: false,
+            token_field: 2,
+        });
+        self.spawn_loader(move |_| {
+            let entries = paths
+                .into_par_iter()
+                .map(|path| {
+                    let info = RenameInfo::from_file(&path);
+                    (path, info)
+                })
+                .collect();
+            Some(LoaderMessage::MetadataInfoLoaded(entries))
+        });
+    }
+
+    pub fn handle_metadata_info_loaded(&mut self, entries: Vec<(PathBuf, RenameInfo)>) {
+        let Some(session) = &mut self.metadata_session else {
+            return;
+        };
+        // Ignore results for a dialog that has since been reopened on other files
+        if entries.len() != session.sources.len()
+            || entries
+                .iter()
+                .zip(&session.sources)
+                .any(|((p, _), s)| p != s)
+        {
+            return;
+        }
+        session.infos = Some(entries.into_iter().map(|(_, info)| info).collect());
+    }
+
+    /// What the dialog would write to each file, for the dry-run preview
+    pub fn metadata_plan(&self) -> Vec<PlannedMetadata> {
+        let Some(session) = &self.metadata_session else {
+            return Vec::new();
+        };
+        let infos = session.infos.as_deref().unwrap_or_default();
+        plan_metadata(&session.sources, &self.settings.metadata_template, infos)
+    }
+
+    /// Write the fields to every file in the background
+    pub fn apply_batch_metadata(&mut self) {
+        let plans = self.metadata_plan();
+        let Some(session) = &mut self.metadata_session else {
+            return;
+        };
+        session.writing = true;
+
+        // Keep the catalog's keywords in step, or the next rating change
+        // would write the sidecar's keyword list back without them
+        let keywords = self.settings.metadata_template.keyword_list();
+        if !keywords.is_empty() {
+            for plan in &plans {
+                for keyword in &keywords {
+                    self.metadata_db.add_keyword(&plan.path, keyword);
+                }
+            }
+            self.metadata_db.save();
+        }
+
+        self.spawn_loader(move |_| {
+            let results = plans
+                .into_par_iter()
+                .map(|plan| {
+                    let error = write_metadata(&plan).err().map(|e| e.to_string());
+                    (plan.path, error)
+                })
+                .collect();
+            Some(LoaderMessage::MetadataWritten(results))
+        });
+    }
+
+    pub fn handle_metadata_written(&mut self, results: Vec<(PathBuf, Option<String>)>) {
+        let failed: Vec<_> = results
+            .iter()
+            .filter_map(|(path, error)| Some((path, error.as_ref()?)))
+            .collect();
+        for (path, error) in &failed {
+            log::error!("Failed to write metadata to {}: {}", path.display(), error);
+        }
+        // Embedded metadata changed, so the metadata browser may be stale
+        if let Some((path, _)) = &self.all_metadata {
+            if results.iter().any(|(p, _)| p == path) {
+                self.all_metadata = None;
+            }
+        }
+
+        if failed.is_empty() {
+            self.metadata_session = None;
+            self.show_status(&format!("Wrote metadata to {} files", results.len()));
+        } else {
+            if let Some(session) = &mut self.metadata_session {
+                session.writing = false;
+            }
+            self.show_status(&format!(
+                "Metadata not written to {} of {} files",
+                failed.len(),
+                results.len()
+            ));
+        }
+        if self.keyword_filter.is_some() || !self.search_query.is_empty() {
+            self.refilter_keeping_current();
+        }
+    }
+}
@@ -6,10 +6,26 @@ impl ImageViewerApp {
         self.image_cache.get_stats()
     }
 
+    /// Copy the image cache's usage into the diagnostics panel's stats
+    pub fn refresh_cache_stats(&mut self) {
+        let stats = self.image_cache.get_stats();
+        self.cache_stats = crate::profiler::CacheStats {
+            total_images: self.image_list.len(),
+            cached_images: stats.image_count,
+            cache_memory_usage: stats.image_size_bytes,
+            thumbnail_count: stats.thumbnail_count,
+            thumbnail_memory_usage: stats.thumbnail_size_bytes,
+            cache_hit_count: stats.hits,
+            cache_miss_count: stats.misses,
+            eviction_count: stats.evictions,
+        };
+    }
+
     pub fn clear_image_cache(&mut self) {
         self.image_cache.clear();
         self.thumbnail_textures.clear();
         self.thumbnail_requests.clear();
+        self.thumbnail_exposure.clear();
         self.show_status("Cache cleared");
     }
 
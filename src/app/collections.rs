@@ -0,0 +1,159 @@
+use super::{ImageViewerApp, LoaderMessage};
+use crate::exif_data::ExifInfo;
+use crate::metadata::ImageMetadata;
+use crate::search::SmartCollection;
+use rayon::prelude::*;
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+/// Stands in for a folder while a collection's matches from the catalog are listed
+pub fn collection_folder(name: &str) -> PathBuf {
+    std::env::temp_dir().join("Smart collections").join(name)
+}
+
+impl ImageViewerApp {
+    /// Show only images matching a smart collection, or everything with `None`
+    pub fn set_active_collection(&mut self, index: Option<usize>) {
+        self.active_collection = index.filter(|&i| i < self.settings.smart_collections.len());
+        self.refilter_keeping_current();
+    }
+
+    /// List every catalogued image matching a collection, wherever it is,
+    /// instead of only those in the open folder
+    pub fn open_collection_from_catalog(&mut self, index: usize) {
+        let Some(collection) = self.settings.smart_collections.get(index).cloned() else {
+            return;
+        };
+        let catalog: BTreeSet<&PathBuf> = self
+            .metadata_db
+            .images
+            .keys()
+            .chain(self.metadata_db.capture_dates.keys())
+            .collect();
+        let candidates: Vec<(PathBuf, ImageMetadata, Option<ExifInfo>)> = catalog
+            .into_iter()
+            .map(|path| {
+                let metadata = self
+                    .metadata_db
+                    .images
+                    .get(path)
+                    .cloned()
+                    .unwrap_or_default();
+                (path.clone(), metadata, self.search_exif.get(path).cloned())
+            })
+            .collect();
+
+        self.show_status(&format!("Searching the catalog for {}...", collection.name));
+        self.spawn_loader(move |_| {
+            let needs_exif = collection.needs_exif();
+            let matches: Vec<(PathBuf, Option<ExifInfo>)> = candidates
+                .into_par_iter()
+                .filter(|(path, _, _)| path.is_file())
+                .filter_map(|(path, metadata, exif)| {
+                    let exif = match exif {
+                        None if needs_exif => Some(ExifInfo::from_file(&path)),
+                        exif => exif,
+                    };
+                    collection
+                        .matches(&metadata, exif.as_ref())
+                        .then_some((path, exif))
+                })
+                .collect();
+            Some(LoaderMessage::CollectionMatched(index, matches))
+        });
+    }
+
+    pub fn handle_collection_matched(
+        &mut self,
+        index: usize,
+        matches: Vec<(PathBuf, Option<ExifInfo>)>,
+    ) {
+        let Some(name) = self
+            .settings
+            .smart_collections
+            .get(index)
+            .map(|c| c.name.clone())
+        else {
+            return;
+        };
+        if matches.is_empty() {
+            self.show_status(&format!("No catalogued images match {}", name));
+            return;
+        }
+
+        // Keep the open folder in its own tab
+        let folder = collection_folder(&name);
+        if self.current_folder.is_some() && self.current_folder.as_ref() != Some(&folder) {
+            self.push_tab();
+        }
+        self.cancel_folder_scan();
+        self.folder_watcher = None;
+        self.current_folder = Some(folder);
+        self.image_list = Vec::with_capacity(matches.len());
+        for (path, exif) in matches {
+            if let Some(exif) = exif {
+                self.search_exif.insert(path.clone(), exif);
+            }
+            self.image_list.push(path);
+        }
+        self.active_collection = Some(index);
+        self.sort_images();
+        self.apply_filter();
+        self.show_status(&format!(
+            "{}: {} images from the catalog",
+            name,
+            self.image_list.len()
+        ));
+        if !self.filtered_list.is_empty() {
+            self.go_to_index(0);
+        }
+    }
+
+    pub fn new_smart_collection(&mut self) {
+        self.collection_draft = Some((None, SmartCollection::default()));
+    }
+
+    pub fn edit_smart_collection(&mut self, index: usize) {
+        if let Some(collection) = self.settings.smart_collections.get(index) {
+            self.collection_draft = Some((Some(index), collection.clone()));
+        }
+    }
+
+    /// Store the collection being edited and show it
+    pub fn save_collection_draft(&mut self) {
+        let Some((index, mut collection)) = self.collection_draft.take() else {
+            return;
+        };
+        if collection.name.trim().is_empty() {
+            collection.name = "Untitled collection".to_string();
+        }
+
+        let index = match index.filter(|&i| i < self.settings.smart_collections.len()) {
+            Some(i) => {
+                self.settings.smart_collections[i] = collection;
+                i
+            }
+            None => {
+                self.settings.smart_collections.push(collection);
+                self.settings.smart_collections.len() - 1
+            }
+        };
+        self.settings.save();
+        self.set_active_collection(Some(index));
+    }
+
+    pub fn delete_smart_collection(&mut self, index: usize) {
+        if index >= self.settings.smart_collections.len() {
+            return;
+        }
+        self.settings.smart_collections.remove(index);
+        self.settings.save();
+
+        self.active_collection = match self.active_collection {
+            Some(i) if i == index => None,
+            Some(i) if i > index => Some(i - 1),
+            other => other,
+        };
+        self.refilter_keeping_current();
+    }
+}
@@ -0,0 +1,237 @@
+use super::{ImageViewerApp, LoaderMessage, ViewMode};
+use crate::image_loader::{self, Alignment, QualityScores};
+use crate::task_scheduler::{ImageTask, TaskPriority};
+use egui::Vec2;
+use image::DynamicImage;
+use std::path::{Path, PathBuf};
+
+/// Most images shown at once in compare mode
+pub const MAX_COMPARE: usize = 4;
+/// Size of the thumbnails compared frames are aligned from
+const ALIGNMENT_SIZE: u32 = 512;
+
+/// Columns and rows for `count` compare panes that give each image the most room,
+/// assuming 3:2 frames
+pub fn compare_grid(count: usize, available: Vec2) -> (usize, usize) {
+    let count = count.max(1);
+    let fitted = |cols: usize, rows: usize| {
+        let width = available.x / cols as f32;
+        let height = available.y / rows as f32;
+        (width / 1.5).min(height)
+    };
+    (1..=count)
+        .map(|cols| (cols, count.div_ceil(cols)))
+        .max_by(|a, b| fitted(a.0, a.1).total_cmp(&fitted(b.0, b.1)))
+        .unwrap_or((1, 1))
+}
+
+impl ImageViewerApp {
+    /// Display indices shown in compare mode: the first few selected images, or the
+    /// current image and the next when fewer than two are selected
+    pub fn compare_indices(&self) -> Vec<usize> {
+        let mut selected: Vec<usize> = self
+            .selected_indices
+            .iter()
+            .copied()
+            .filter(|&i| i < self.filtered_list.len())
+            .collect();
+        selected.sort_unstable();
+        if selected.len() >= 2 {
+            selected.truncate(MAX_COMPARE);
+            return selected;
+        }
+
+        match self.filtered_list.len() {
+            0 => Vec::new(),
+            1 => vec![0],
+            len => vec![self.current_index, (self.current_index + 1) % len],
+        }
+    }
+
+    /// Compare the selected images, starting from an unzoomed view
+    pub fn start_compare(&mut self) {
+        self.compare_zoom = [1.0; MAX_COMPARE];
+        self.compare_pan = [Vec2::ZERO; MAX_COMPARE];
+        self.view_mode = ViewMode::Compare;
+    }
+
+    /// Delete one compared image, keeping the rest of the selection in the comparison
+    pub fn delete_compare_image(&mut self, display_idx: usize) {
+        let Some(path) = self.path_at(display_idx) else {
+            return;
+        };
+        let others: Vec<PathBuf> = self
+            .selected_paths()
+            .into_iter()
+            .filter(|p| *p != path)
+            .collect();
+
+        self.selected_indices.clear();
+        self.current_index = display_idx;
+        self.delete_current_image();
+
+        self.selected_indices = self
+            .filtered_list
+            .iter()
+            .enumerate()
+            .filter(|(_, &i)| others.contains(&self.image_list[i]))
+            .map(|(pos, _)| pos)
+            .collect();
+    }
+
+    /// Path of the image at a position in the filtered list
+    pub fn path_at(&self, display_idx: usize) -> Option<PathBuf> {
+        self.filtered_list
+            .get(display_idx)
+            .and_then(|&i| self.image_list.get(i).cloned())
+    }
+
+    /// Full-resolution texture for a compared image, queueing its decode when it
+    /// isn't ready yet
+    pub fn compare_texture(&mut self, path: &Path) -> Option<egui::TextureId> {
+        if let Some(texture) = self.compare_textures.get(path) {
+            return Some(texture.id());
+        }
+        if self.compare_requests.insert(path.to_path_buf()) {
+            match self.image_cache.get(path) {
+                Some(image) => self.create_compare_texture(path.to_path_buf(), image),
+                // Ahead of adjacent-image preloads, behind the current image
+                None => {
+                    self.task_scheduler.submit_task(ImageTask::LoadImage {
+                        path: path.to_path_buf(),
+                        priority: TaskPriority::High,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// Upload a decoded compare image off the UI thread, with its saved adjustments
+    pub fn create_compare_texture(&self, path: PathBuf, image: DynamicImage) {
+        let Some(ctx) = self.ctx.clone() else {
+            return;
+        };
+        let adjustments = self
+            .metadata_db
+            .get_adjustments(&path)
+            .filter(|adj| !adj.is_default());
+        let texture_options = self.texture_options_with(false);
+        self.spawn_loader(move |_| {
+            let max_side = ctx.input(|i| i.max_texture_side) as u32;
+            let image = if image.width().max(image.height()) > max_side {
+                crate::image_loader::generate_thumbnail(&image, max_side)
+            } else {
+                image
+            };
+            let image = match adjustments {
+                Some(adj) => crate::image_loader::apply_adjustments(&image, &adj),
+                None => image,
+            };
+            let size = [image.width() as usize, image.height() as usize];
+            let rgba = image.to_rgba8();
+            let texture = ctx.load_texture(
+                format!("compare_{}", path.display()),
+                egui::ColorImage::from_rgba_unmultiplied(size, rgba.as_flat_samples().as_slice()),
+                texture_options,
+            );
+            Some(LoaderMessage::CompareTextureLoaded(path, texture))
+        });
+    }
+
+    /// Drop compare textures for images no longer shown
+    pub fn retain_compare_textures(&mut self, paths: &[PathBuf]) {
+        self.compare_textures.retain(|p, _| paths.contains(p));
+        self.compare_requests.retain(|p| paths.contains(p));
+    }
+
+    /// How a compared image lines up with the first one when auto-align is on.
+    /// Worked out from thumbnails in the background on first use.
+    pub fn compare_alignment(&mut self, path: &Path) -> Option<Alignment> {
+        if !self.settings.compare_auto_align {
+            return None;
+        }
+        let reference = self
+            .compare_indices()
+            .first()
+            .and_then(|&i| self.path_at(i))?;
+        if reference == path {
+            return None;
+        }
+
+        let key = (reference, path.to_path_buf());
+        if let Some(alignment) = self.compare_alignments.get(&key) {
+            return *alignment;
+        }
+        if self.compare_alignment_requests.insert(key.clone()) {
+            self.spawn_loader(move |_| {
+                let (reference, path) = key;
+                let alignment = match (
+                    image_loader::load_thumbnail(&reference, ALIGNMENT_SIZE),
+                    image_loader::load_thumbnail(&path, ALIGNMENT_SIZE),
+                ) {
+                    (Ok(a), Ok(b)) => image_loader::estimate_alignment(&a, &b),
+                    _ => None,
+                };
+                Some(LoaderMessage::CompareAligned(reference, path, alignment))
+            });
+        }
+        None
+    }
+
+    pub fn handle_compare_aligned(
+        &mut self,
+        reference: PathBuf,
+        path: PathBuf,
+        alignment: Option<Alignment>,
+    ) {
+        if alignment.is_none() {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            self.show_status(&format!("Couldn't align {}", name));
+        }
+        let key = (reference, path);
+        self.compare_alignment_requests.remove(&key);
+        self.compare_alignments.insert(key, alignment);
+    }
+
+    /// PSNR and SSIM of `other` against `reference`, measured from full
+    /// decodes in the background on first use
+    pub fn quality_scores(
+        &mut self,
+        reference: &Path,
+        other: &Path,
+    ) -> Option<Result<QualityScores, String>> {
+        let key = (reference.to_path_buf(), other.to_path_buf());
+        if let Some(scores) = self.compare_quality.get(&key) {
+            return Some(scores.clone());
+        }
+        if self.compare_quality_requests.insert(key.clone()) {
+            self.spawn_loader(move |_| {
+                let (reference, other) = key;
+                let scores = image_loader::load_image(&reference)
+                    .and_then(|a| image_loader::load_image(&other).map(|b| (a, b)))
+                    .map(|(a, b)| image_loader::compare_quality(&a, &b))
+                    .map_err(|e| e.to_string());
+                Some(LoaderMessage::CompareQuality(reference, other, scores))
+            });
+        }
+        None
+    }
+
+    pub fn handle_compare_quality(
+        &mut self,
+        reference: PathBuf,
+        path: PathBuf,
+        scores: Result<QualityScores, String>,
+    ) {
+        let key = (reference, path);
+        self.compare_quality_requests.remove(&key);
+        self.compare_quality.insert(key, scores);
+    }
+
+    pub fn release_compare_textures(&mut self) {
+        if !self.compare_requests.is_empty() {
+            self.retain_compare_textures(&[]);
+        }
+    }
+}
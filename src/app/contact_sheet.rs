@@ -0,0 +1,76 @@
+use super::{ImageViewerApp, LoaderMessage};
+use std::path::PathBuf;
+
+impl ImageViewerApp {
+    /// Ask where to save, then render the selection (or everything shown) in the background
+    pub fn start_contact_sheet(&mut self) {
+        if self.contact_sheet_running {
+            return;
+        }
+
+        let sources = self.selected_or_filtered_paths();
+        if sources.is_empty() {
+            self.show_status("No images for a contact sheet");
+            return;
+        }
+
+        let options = self.settings.contact_sheet.clone();
+        let format = options.format;
+        let default_name = match &self.current_folder {
+            Some(folder) => folder
+                .file_name()
+                .map(|n| format!("{}_contact_sheet", n.to_string_lossy()))
+                .unwrap_or_else(|| "contact_sheet".to_string()),
+            None => "contact_sheet".to_string(),
+        };
+        let mut dialog = rfd::FileDialog::new()
+            .set_file_name(format!("{}.{}", default_name, format.extension()))
+            .add_filter(format.name(), &[format.extension()]);
+        if let Some(folder) = &self.current_folder {
+            dialog = dialog.set_directory(folder);
+        }
+        let Some(mut output) = dialog.save_file() else {
+            return;
+        };
+        if output.extension().is_none() {
+            output.set_extension(format.extension());
+        }
+
+        let jobs: Vec<_> = sources
+            .into_iter()
+            .map(|path| {
+                let adjustments = self.export_adjustments(&path);
+                (path, adjustments)
+            })
+            .collect();
+
+        self.contact_sheet_running = true;
+        self.show_status(&format!(
+            "Creating contact sheet of {} images...",
+            jobs.len()
+        ));
+        self.spawn_long_job(move |_| {
+            let result =
+                crate::contact_sheet::generate(jobs, &options, &output).map_err(|e| e.to_string());
+            Some(LoaderMessage::ContactSheetFinished(result))
+        });
+    }
+
+    pub fn handle_contact_sheet_finished(&mut self, result: Result<Vec<PathBuf>, String>) {
+        self.contact_sheet_running = false;
+        match result {
+            Ok(paths) => {
+                let message = match paths.as_slice() {
+                    [path] => format!("Saved contact sheet to {}", path.display()),
+                    _ => format!("Saved contact sheet as {} pages", paths.len()),
+                };
+                self.show_status(&message);
+                self.show_contact_sheet_dialog = false;
+            }
+            Err(e) => {
+                log::error!("Contact sheet failed: {}", e);
+                self.show_status(&format!("Contact sheet failed: {}", e));
+            }
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use super::{ImageViewerApp, LoaderMessage};
+use crate::color_management::DisplayTransform;
+use crate::gpu::display::{self, DisplayUpload};
+use crate::image_loader;
+use image::DynamicImage;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Proxies are built this much larger than the zoom they are for, so a
+/// small zoom in doesn't immediately need a new one
+const PROXY_HEADROOM: f32 = 1.25;
+/// A proxy is rebuilt once it is minified further than this
+const PROXY_MAX_MINIFICATION: f32 = 2.0;
+/// Proxies close to full size aren't worth the resampling
+const PROXY_MAX_SCALE: f32 = 0.8;
+
+/// Pixels for the wgpu display pass at `scale` of the image's size (1.0 for
+/// full resolution). With a `transform` the pass converts them to the display
+/// profile after the adjustments, as the CPU path does.
+pub fn build_display_source(
+    image: &DynamicImage,
+    transform: Option<&DisplayTransform>,
+    max_dimension: u32,
+    scale: f32,
+) -> DisplayUpload {
+    let scaled;
+    let image = if scale < 1.0 {
+        let width = ((image.width() as f32 * scale).round() as u32).max(1);
+        let height = ((image.height() as f32 * scale).round() as u32).max(1);
+        scaled = image_loader::downscale_lanczos(image, width, height);
+        &scaled
+    } else {
+        image
+    };
+
+    DisplayUpload {
+        source: display::prepare_source(image, max_dimension),
+        lut: transform.map(|transform| crate::color_management::display_lut(transform)),
+    }
+}
+
+impl ImageViewerApp {
+    /// Swap the image resident in the wgpu display pass for a Lanczos-downscaled
+    /// proxy while zoomed out, where bilinear sampling of a large image aliases,
+    /// and back to full resolution as the view zooms in. Only runs once the
+    /// zoom has settled.
+    pub fn update_display_proxy(&mut self) {
+        let editing = self.slider_dragging || self.drawing_local_mask();
+        if (self.zoom - self.target_zoom).abs() > 1e-3 || editing {
+            return;
+        }
+        let (Some(max_dim), Some(image), Some(path)) = (
+            self.gpu_display_max_dim,
+            self.current_image.clone(),
+            self.get_current_path(),
+        ) else {
+            return;
+        };
+
+        let wanted = self.zoom.min(1.0);
+        let resident = self.gpu_display_pending.unwrap_or(self.gpu_display_scale);
+        if resident >= wanted && resident <= wanted * PROXY_MAX_MINIFICATION {
+            return;
+        }
+        let scale = (wanted * PROXY_HEADROOM).min(1.0);
+        let scale = if scale > PROXY_MAX_SCALE { 1.0 } else { scale };
+        if scale == resident {
+            return;
+        }
+
+        let transform = self.display_transform(&path);
+        self.gpu_display_pending = Some(scale);
+        self.spawn_loader(move |_| {
+            let source = build_display_source(&image, transform.as_ref(), max_dim, scale);
+            Some(LoaderMessage::DisplaySourceReady(
+                path,
+                scale,
+                Arc::new(source),
+            ))
+        });
+    }
+
+    pub fn handle_display_source_ready(
+        &mut self,
+        path: PathBuf,
+        scale: f32,
+        source: Arc<DisplayUpload>,
+    ) {
+        // A newer request or a new image replaced this one
+        if self.get_current_path() != Some(path) || self.gpu_display_pending != Some(scale) {
+            return;
+        }
+        self.gpu_display_pending = None;
+        self.gpu_display_scale = scale;
+        self.gpu_display_upload = Some(source);
+    }
+}
@@ -0,0 +1,87 @@
+use super::ImageViewerApp;
+use eframe::egui;
+use std::path::PathBuf;
+
+impl ImageViewerApp {
+    /// Remember the files a drag from a thumbnail (or the main view, for None)
+    /// carries, so they can be handed to the OS if it leaves the window
+    pub fn begin_drag_out(&mut self, display_index: Option<usize>) {
+        let index = display_index.unwrap_or(self.current_index);
+        let paths = if display_index.is_some() && self.selected_indices.contains(&index) {
+            self.selected_paths()
+        } else {
+            self.filtered_list
+                .get(index)
+                .and_then(|&i| self.image_list.get(i))
+                .cloned()
+                .into_iter()
+                .collect()
+        };
+        // Pasted images aren't files yet
+        let paths: Vec<PathBuf> = paths.into_iter().filter(|p| p.is_file()).collect();
+        self.drag_out = (!paths.is_empty()).then_some(paths);
+    }
+
+    /// Turn an in-app drag into an OS drag once the pointer leaves the window,
+    /// so photos can be dropped into mail clients, browsers and file managers
+    pub fn update_drag_out(&mut self, ctx: &egui::Context, frame: &eframe::Frame) {
+        if self.drag_out.is_none() {
+            return;
+        }
+        let (down, pointer) = ctx.input(|i| (i.pointer.primary_down(), i.pointer.latest_pos()));
+        if !down {
+            self.drag_out = None;
+            return;
+        }
+        let inside = pointer.is_some_and(|pos| ctx.screen_rect().contains(pos));
+        if inside {
+            return;
+        }
+
+        let Some(paths) = self.drag_out.take() else {
+            return;
+        };
+        // The drop target takes over; nothing inside the window gets the drop
+        egui::DragAndDrop::clear_payload(ctx);
+        if let Err(e) = self.start_os_drag(frame, paths) {
+            log::warn!("Couldn't start dragging files out: {}", e);
+        }
+    }
+
+    #[cfg(any(windows, target_os = "macos"))]
+    fn start_os_drag(&self, frame: &eframe::Frame, paths: Vec<PathBuf>) -> Result<(), String> {
+        let preview = paths
+            .first()
+            .and_then(|path| self.image_cache.get_thumbnail(path))
+            .and_then(|thumb| drag_preview_png(&thumb))
+            .unwrap_or_default();
+        drag::start_drag(
+            frame,
+            drag::DragItem::Files(paths),
+            drag::Image::Raw(preview),
+            |_result, _position| {},
+            drag::Options::default(),
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    /// Linux windows opened through winit can't start a native drag
+    #[cfg(not(any(windows, target_os = "macos")))]
+    fn start_os_drag(&self, _frame: &eframe::Frame, _paths: Vec<PathBuf>) -> Result<(), String> {
+        Err("not supported on this platform".to_string())
+    }
+}
+
+/// Small PNG shown under the cursor while dragging
+#[cfg(any(windows, target_os = "macos"))]
+fn drag_preview_png(thumbnail: &image::DynamicImage) -> Option<Vec<u8>> {
+    let preview = thumbnail.thumbnail(96, 96);
+    let mut bytes = Vec::new();
+    preview
+        .write_to(
+            &mut std::io::Cursor::new(&mut bytes),
+            image::ImageFormat::Png,
+        )
+        .ok()?;
+    Some(bytes)
+}
@@ -2,6 +2,7 @@ use crate::image_loader;
 use crate::image_loader::ImageAdjustments;
 use crate::metadata::FileOperation;
 use image::GenericImageView;
+use std::collections::HashSet;
 use std::path::PathBuf;
 
 use super::ImageViewerApp;
@@ -48,6 +49,8 @@ impl ImageViewerApp {
         if !self.crop_mode {
             self.crop_rect = None;
             self.crop_start_pos = None;
+        } else {
+            self.local_mask_tool = None;
         }
         self.show_status(if self.crop_mode {
             "Crop mode enabled"
@@ -108,108 +111,299 @@ impl ImageViewerApp {
         self.crop_start_pos = None;
     }
 
+    // Straightening
+    pub fn toggle_straighten_mode(&mut self) {
+        self.straighten_mode = !self.straighten_mode;
+        self.straighten_line = None;
+        if self.straighten_mode {
+            // Crop, straighten and the gradient tools all use drag input
+            self.crop_mode = false;
+            self.crop_rect = None;
+            self.crop_start_pos = None;
+            self.local_mask_tool = None;
+        }
+        self.show_status(if self.straighten_mode {
+            "Straighten: drag along the horizon"
+        } else {
+            "Straighten mode disabled"
+        });
+    }
+
+    /// Level the image so the line from `start` to `end` becomes horizontal (or vertical,
+    /// if it was drawn closer to vertical). The line is in screen space, which has the same
+    /// orientation as the displayed image.
+    pub fn apply_straighten_line(&mut self, start: egui::Pos2, end: egui::Pos2) {
+        let delta = end - start;
+        if delta.length() < 10.0 {
+            return;
+        }
+
+        let mut angle = delta.y.atan2(delta.x).to_degrees();
+        // Fold into -45..45 so both horizons and verticals are levelled by the smallest turn
+        while angle > 45.0 {
+            angle -= 90.0;
+        }
+        while angle < -45.0 {
+            angle += 90.0;
+        }
 
+        let previous = self.adjustments.clone();
+        self.adjustments.straighten_angle =
+            (self.adjustments.straighten_angle - angle).clamp(-45.0, 45.0);
+        self.straighten_mode = false;
+        self.straighten_line = None;
 
+        if self.adjustments == previous {
+            return;
+        }
 
+        self.commit_adjustment_change(previous);
+        self.show_status(&format!(
+            "Straightened {:.1}°",
+            self.adjustments.straighten_angle
+        ));
+    }
 
-    // File operations
-    pub fn delete_current_image(&mut self) {
+    /// Record a discrete change to the current adjustments: push undo, persist to the
+    /// metadata database, invalidate the thumbnail and re-render
+    pub fn commit_adjustment_change(&mut self, previous: ImageAdjustments) {
         if let Some(path) = self.get_current_path() {
-            // Backup metadata before deletion
-            let metadata_backup = serde_json::to_string(&self.metadata_db.get(&path)).ok();
-
-            if self.settings.delete_to_trash {
-                if trash::delete(&path).is_ok() {
-                    self.undo_history.push(FileOperation::Delete {
-                        original_path: path.clone(),
-                        trash_path: None,
-                        metadata_backup,
-                    });
-                }
+            self.undo_history.push(FileOperation::Adjust {
+                path: path.clone(),
+                adjustments: self.adjustments.clone(),
+                previous_adjustments: Box::new(previous),
+            });
+            self.metadata_db
+                .set_adjustments(path.clone(), &self.adjustments);
+            self.metadata_db.save();
+            self.thumbnail_textures.remove(&path);
+            self.thumbnail_requests.remove(&path);
+        }
+        self.refresh_adjustments();
+    }
+
+    // Copy / paste adjustments
+    pub fn copy_adjustments(&mut self) {
+        self.copied_adjustments = Some(self.adjustments.look());
+        self.show_status("Adjustments copied");
+    }
+
+    /// Copied adjustments merged onto `target`, keeping the target's own geometry and
+    /// local adjustments, which are placed for that image
+    fn pasted_adjustments(&self, target: &ImageAdjustments) -> Option<ImageAdjustments> {
+        self.copied_adjustments
+            .as_ref()
+            .map(|copied| target.with_look(copied))
+    }
+
+    pub fn paste_adjustments(&mut self) {
+        let Some(pasted) = self.pasted_adjustments(&self.adjustments) else {
+            self.show_status("No adjustments copied");
+            return;
+        };
+
+        let previous = std::mem::replace(&mut self.adjustments, pasted);
+        self.current_film_preset = crate::image_loader::FilmPreset::None;
+        if self.adjustments != previous {
+            self.commit_adjustment_change(previous);
+        }
+        self.show_status("Adjustments pasted");
+    }
+
+    /// Paste the copied adjustments onto every selected thumbnail
+    pub fn paste_adjustments_to_selected(&mut self) {
+        let paths = self.selected_paths();
+        self.paste_adjustments_to_paths(paths);
+    }
+
+    pub fn paste_adjustments_to_paths(&mut self, paths: Vec<PathBuf>) {
+        if self.copied_adjustments.is_none() {
+            self.show_status("No adjustments copied");
+            return;
+        }
+
+        let current_path = self.get_current_path();
+        let mut ops = Vec::new();
+        for path in paths {
+            let previous = if current_path.as_ref() == Some(&path) {
+                self.adjustments.clone()
             } else {
-                let _ = std::fs::remove_file(&path);
+                self.metadata_db.get_adjustments(&path).unwrap_or_default()
+            };
+            let Some(pasted) = self.pasted_adjustments(&previous) else {
+                continue;
+            };
+            if pasted == previous {
+                continue;
             }
 
-            if let Some(&idx) = self.filtered_list.get(self.current_index) {
-                self.image_list.remove(idx);
-            }
-            self.image_cache.remove(&path);
+            self.metadata_db.set_adjustments(path.clone(), &pasted);
             self.thumbnail_textures.remove(&path);
+            self.thumbnail_requests.remove(&path);
+            if current_path.as_ref() == Some(&path) {
+                self.adjustments = pasted.clone();
+                self.current_film_preset = crate::image_loader::FilmPreset::None;
+                self.refresh_adjustments();
+            }
+            ops.push(FileOperation::Adjust {
+                path,
+                adjustments: pasted,
+                previous_adjustments: Box::new(previous),
+            });
+        }
+        self.metadata_db.save();
 
-            self.apply_filter();
+        let count = ops.len();
+        // One undo step reverts the whole paste
+        self.push_undo_batch(ops);
+        self.show_status(&format!("Pasted adjustments to {} images", count));
+    }
 
-            if self.current_index >= self.filtered_list.len() && !self.filtered_list.is_empty() {
-                self.current_index = self.filtered_list.len() - 1;
-            }
 
-            if !self.filtered_list.is_empty() {
-                // Load adjustments for the new current image
-                self.load_adjustments_for_current();
-                self.load_current_image();
-            } else {
-                self.current_texture = None;
-                self.current_image = None;
+
+    // File operations
+    /// The selected images, or the current one when nothing is selected,
+    /// along with the hidden half of any RAW+JPEG pairs among them
+    pub fn action_paths(&self) -> Vec<PathBuf> {
+        let selected = self.selected_paths();
+        let paths = if selected.is_empty() {
+            self.get_current_path().into_iter().collect()
+        } else {
+            selected
+        };
+        self.with_pair_companions(paths)
+    }
+
+    /// Push one undo step for several operations, or the operation itself when there is only one
+    pub fn push_undo_batch(&mut self, mut ops: Vec<FileOperation>) {
+        match ops.len() {
+            0 => {}
+            1 => self.undo_history.push(ops.remove(0)),
+            _ => self.undo_history.push(FileOperation::Batch(ops)),
+        }
+    }
+
+    /// Delete the selected images, or the current one when nothing is selected
+    pub fn delete_current_image(&mut self) {
+        let paths = self.action_paths();
+        if paths.is_empty() {
+            return;
+        }
+
+        let mut ops = Vec::new();
+        let mut deleted = Vec::new();
+        for path in paths {
+            match self.delete_file(&path) {
+                Ok(file) => {
+                    ops.push(file.to_operation());
+                    deleted.push(path);
+                }
+                Err(e) => log::warn!("Failed to delete {}: {}", path.display(), e),
             }
+        }
+        self.push_undo_batch(ops);
+
+        let count = deleted.len();
+        self.remove_from_list(&deleted);
+        self.show_status(&match count {
+            0 => "Delete failed".to_string(),
+            1 => "Image deleted".to_string(),
+            n => format!("Deleted {} images", n),
+        });
+    }
 
-            self.show_status("Image deleted");
+    /// Drop files that are gone from the folder, staying on the current image if it remains
+    pub fn remove_from_list(&mut self, removed: &[PathBuf]) {
+        if removed.is_empty() {
+            return;
+        }
+        let previous = self.get_current_path();
+        let removed: HashSet<&PathBuf> = removed.iter().collect();
+
+        self.image_list.retain(|p| !removed.contains(p));
+        for path in &removed {
+            self.image_cache.remove(path);
+            self.thumbnail_textures.remove(*path);
+            self.thumbnail_requests.remove(*path);
+        }
+        self.selected_indices.clear();
+
+        if previous.as_ref().is_some_and(|p| !removed.contains(p)) {
+            self.refilter_keeping(previous);
+            return;
+        }
+
+        self.apply_filter();
+        if self.current_index >= self.filtered_list.len() && !self.filtered_list.is_empty() {
+            self.current_index = self.filtered_list.len() - 1;
+        }
+        if !self.filtered_list.is_empty() {
+            self.load_current_image();
+        } else {
+            self.current_texture = None;
+            self.current_image = None;
         }
     }
 
+    /// Move the selected images, or the current one, into `dest_folder` in the background
     pub fn move_to_folder(&mut self, dest_folder: std::path::PathBuf) {
-        if let Some(path) = self.get_current_path() {
-            let tx = self.loader_tx.clone();
-            let ctx = self.ctx.clone();
-            let path = path.clone();
-            let dest_folder = dest_folder.clone();
-            std::thread::spawn(move || {
-                // Create the destination folder if it doesn't exist
-                if let Err(e) = std::fs::create_dir_all(&dest_folder) {
-                    let _ = tx.send(super::LoaderMessage::MoveCompleted {
-                        from: path,
-                        dest_folder,
-                        success: false,
-                        error: Some(format!("Failed to create folder: {}", e)),
-                    });
-                    if let Some(ctx) = ctx {
-                        ctx.request_repaint();
-                    }
-                    return;
-                }
+        let paths = self.action_paths();
+        if paths.is_empty() {
+            return;
+        }
+        self.spawn_loader(move |_| {
+            // Create the destination folder if it doesn't exist
+            if let Err(e) = std::fs::create_dir_all(&dest_folder) {
+                return Some(super::LoaderMessage::MoveCompleted {
+                    moved: Vec::new(),
+                    dest_folder,
+                    error: Some(format!("Failed to create folder: {}", e)),
+                });
+            }
 
+            let mut moved = Vec::new();
+            let mut error = None;
+            for path in paths {
                 let filename = path.file_name().unwrap_or_default();
                 let dest_path = dest_folder.join(filename);
-
-                if std::fs::rename(&path, &dest_path).is_ok() {
-                    let _ = tx.send(super::LoaderMessage::MoveCompleted {
-                        from: path,
-                        dest_folder,
-                        success: true,
-                        error: None,
-                    });
+                if dest_path.exists() {
+                    error = Some(format!("{} already exists", dest_path.display()));
+                } else if let Err(e) = std::fs::rename(&path, &dest_path) {
+                    error = Some(format!("Failed to move image: {}", e));
                 } else {
-                    let _ = tx.send(super::LoaderMessage::MoveCompleted {
-                        from: path,
-                        dest_folder,
-                        success: false,
-                        error: Some("Failed to move image".to_string()),
-                    });
-                }
-                if let Some(ctx) = ctx {
-                    ctx.request_repaint();
+                    moved.push((path, dest_path));
                 }
-            });
-        }
+            }
+            Some(super::LoaderMessage::MoveCompleted {
+                moved,
+                dest_folder,
+                error,
+            })
+        });
     }
 
+    /// Copy the selected images, or the current one, into `dest_folder`
     pub fn copy_to_folder(&mut self, dest_folder: PathBuf) {
-        if let Some(path) = self.get_current_path() {
+        let mut ops = Vec::new();
+        for path in self.action_paths() {
             let filename = path.file_name().unwrap_or_default();
             let dest_path = dest_folder.join(filename);
 
-            if std::fs::copy(&path, &dest_path).is_ok() {
-                self.show_status(&format!("Copied to {}", dest_folder.display()));
+            if !dest_path.exists() && std::fs::copy(&path, &dest_path).is_ok() {
+                ops.push(FileOperation::Copy {
+                    from: path,
+                    to: dest_path,
+                });
             }
         }
+
+        let count = ops.len();
+        self.push_undo_batch(ops);
+        self.show_status(&match count {
+            0 => "Copy failed".to_string(),
+            1 => format!("Copied to {}", dest_folder.display()),
+            n => format!("Copied {} images to {}", n, dest_folder.display()),
+        });
     }
 
     pub fn move_to_selected_folder(&mut self) {
@@ -226,101 +420,114 @@ impl ImageViewerApp {
         let op = self.undo_history.undo().cloned();
 
         if let Some(op) = op {
-            match op {
-                FileOperation::Delete {
-                    original_path,
-                    trash_path,
-                    metadata_backup,
-                } => {
-                    // Try to restore from trash or show message
-                    if let Some(trash_path) = trash_path {
-                        if std::fs::rename(trash_path, &original_path).is_ok() {
-                            // Restore metadata if available
-                            if let Some(metadata_json) = metadata_backup {
-                                if let Ok(metadata) =
-                                    serde_json::from_str::<crate::metadata::ImageMetadata>(
-                                        &metadata_json,
-                                    )
-                                {
-                                    self.metadata_db
-                                        .restore_metadata(original_path.clone(), metadata);
-                                }
-                            }
-                            self.image_list.push(original_path.clone());
-                            self.sort_images();
-                            self.apply_filter();
-                            self.show_status("Undo: File restored");
-                        } else {
-                            self.show_status(&format!(
-                                "Cannot undo delete of {}",
-                                original_path.display()
-                            ));
-                        }
-                    } else {
-                        self.show_status(&format!(
-                            "Cannot undo delete of {}",
-                            original_path.display()
-                        ));
-                    }
+            let mut rated = Vec::new();
+            self.undo_operation(op, &current_path, &mut rated);
+            self.metadata_changed_for(&rated);
+        }
+    }
+
+    fn undo_operation(
+        &mut self,
+        op: FileOperation,
+        current_path: &Option<PathBuf>,
+        rated: &mut Vec<PathBuf>,
+    ) {
+        match op {
+            FileOperation::Delete { original_path, .. } => {
+                // The held file or trash entry is tracked in the recently deleted list
+                self.restore_deleted_path(&original_path);
+            }
+            FileOperation::Move { from, to } => {
+                if std::fs::rename(&to, &from).is_ok() {
+                    self.image_list.push(from.clone());
+                    self.sort_images();
+                    self.apply_filter();
+                    self.show_status("Undo: Move reverted");
                 }
-                FileOperation::Move { from, to } => {
-                    if std::fs::rename(&to, &from).is_ok() {
-                        self.image_list.push(from.clone());
-                        self.sort_images();
-                        self.apply_filter();
-                        self.show_status("Undo: Move reverted");
+            }
+            FileOperation::Rename { from, to } => {
+                if std::fs::rename(&to, &from).is_ok() {
+                    if let Some(pos) = self.image_list.iter().position(|p| p == &*to) {
+                        self.image_list[pos] = from.clone();
                     }
+                    self.show_status("Undo: Rename reverted");
                 }
-                FileOperation::Rename { from, to } => {
-                    if std::fs::rename(&to, &from).is_ok() {
-                        if let Some(pos) = self.image_list.iter().position(|p| p == &*to) {
-                            self.image_list[pos] = from.clone();
-                        }
+            }
+            FileOperation::BatchRename { renames } => {
+                let reverted: Vec<(PathBuf, PathBuf)> =
+                    renames.into_iter().map(|(from, to)| (to, from)).collect();
+                match crate::rename::execute_renames(&reverted) {
+                    Ok(()) => {
+                        self.apply_renamed_paths(&reverted);
                         self.show_status("Undo: Rename reverted");
                     }
+                    Err(e) => self.show_status(&format!("Cannot undo rename: {}", e)),
                 }
-                FileOperation::Rotate {
-                    path,
-                    degrees: _degrees,
-                    previous_rotation,
-                } => {
-                    if current_path.as_ref() == Some(&path) {
-                        // Calculate the reverse rotation to undo
-                        let reverse_degrees = previous_rotation - self.rotation;
-                        self.rotation = previous_rotation;
-
-                        if let Some(image) = &self.current_image {
-                            let rotated_image =
-                                image_loader::rotate_image(image, reverse_degrees as i32);
-                            self.set_current_image(&path, rotated_image);
-                        }
+            }
+            FileOperation::Rotate {
+                path,
+                degrees: _degrees,
+                previous_rotation,
+            } => {
+                if current_path.as_ref() == Some(&path) {
+                    // Calculate the reverse rotation to undo
+                    let reverse_degrees = previous_rotation - self.rotation;
+                    self.rotation = previous_rotation;
+
+                    if let Some(image) = &self.current_image {
+                        let rotated_image =
+                            image_loader::rotate_image(image, reverse_degrees as i32);
+                        self.set_current_image(&path, rotated_image);
                     }
-                    self.show_status("Undo: Rotation reverted");
                 }
-                FileOperation::Adjust {
-                    path,
-                    previous_adjustments,
-                    ..
-                } => {
-                    if current_path.as_ref() == Some(&path) {
-                        self.adjustments = *previous_adjustments.clone();
-                        self.refresh_adjustments();
-                    }
-                    // Save the reverted adjustments to metadata
-                    self.metadata_db
-                        .set_adjustments(path.clone(), &previous_adjustments);
-                    self.metadata_db.save();
-                    // Invalidate thumbnail to regenerate with reverted adjustments
-                    self.thumbnail_textures.remove(&path);
-                    self.thumbnail_requests.remove(&path);
-                    self.show_status("Undo: Adjustments reverted");
+                self.show_status("Undo: Rotation reverted");
+            }
+            FileOperation::Adjust {
+                path,
+                previous_adjustments,
+                ..
+            } => {
+                if current_path.as_ref() == Some(&path) {
+                    self.adjustments = *previous_adjustments.clone();
+                    self.refresh_adjustments();
                 }
-                FileOperation::Crop { .. } => {
-                    // For now, just show that crop undo is not implemented
-                    // In a full implementation, we'd need to store the original image
-                    self.show_status("Undo: Crop cannot be reverted (original image not stored)");
+                // Save the reverted adjustments to metadata
+                self.metadata_db
+                    .set_adjustments(path.clone(), &previous_adjustments);
+                self.metadata_db.save();
+                // Invalidate thumbnail to regenerate with reverted adjustments
+                self.thumbnail_textures.remove(&path);
+                self.thumbnail_requests.remove(&path);
+                self.show_status("Undo: Adjustments reverted");
+            }
+            FileOperation::Crop { .. } => {
+                // For now, just show that crop undo is not implemented
+                // In a full implementation, we'd need to store the original image
+                self.show_status("Undo: Crop cannot be reverted (original image not stored)");
+            }
+            FileOperation::Copy { to, .. } => {
+                if std::fs::remove_file(&to).is_ok() {
+                    self.show_status("Undo: Copy removed");
                 }
             }
+            FileOperation::Rate { path, before, .. } => {
+                self.metadata_db.set_rating(&path, before.0);
+                self.metadata_db.set_color_label(&path, before.1);
+                rated.push(path);
+                self.show_status("Undo: Rating reverted");
+            }
+            FileOperation::Flag { path, before, .. } => {
+                self.metadata_db.set_flag(&path, before);
+                rated.push(path);
+                self.show_status("Undo: Flag reverted");
+            }
+            FileOperation::Batch(ops) => {
+                let count = ops.len();
+                for op in ops.into_iter().rev() {
+                    self.undo_operation(op, current_path, rated);
+                }
+                self.show_status(&format!("Undo: {} changes reverted", count));
+            }
         }
     }
 
@@ -355,44 +562,85 @@ impl ImageViewerApp {
         let op = self.undo_history.redo().cloned();
 
         if let Some(op) = op {
-            match op {
-                FileOperation::Delete { original_path, .. } => {
-                    self.redo_delete_operation(original_path);
-                }
-                FileOperation::Move { from, to } => {
-                    self.redo_move_operation(from, to);
-                }
-                FileOperation::Rename { from, to } => {
-                    self.redo_rename_operation(from, to);
-                }
-                FileOperation::Rotate { path, degrees, .. } => {
-                    self.redo_rotate_operation(path, degrees, current_path);
+            let mut rated = Vec::new();
+            self.redo_operation(op, &current_path, &mut rated);
+            self.metadata_changed_for(&rated);
+        }
+    }
+
+    fn redo_operation(
+        &mut self,
+        op: FileOperation,
+        current_path: &Option<PathBuf>,
+        rated: &mut Vec<PathBuf>,
+    ) {
+        match op {
+            FileOperation::Delete { original_path, .. } => {
+                self.redo_delete_operation(original_path);
+            }
+            FileOperation::Move { from, to } => {
+                self.redo_move_operation(from, to);
+            }
+            FileOperation::Copy { from, to } => {
+                if !to.exists() && std::fs::copy(&from, &to).is_ok() {
+                    self.show_status("Redo: Copy reapplied");
                 }
-                FileOperation::Adjust { path, adjustments, .. } => {
-                    self.redo_adjust_operation(path, adjustments, current_path);
+            }
+            FileOperation::Rename { from, to } => {
+                self.redo_rename_operation(from, to);
+            }
+            FileOperation::BatchRename { renames } => {
+                match crate::rename::execute_renames(&renames) {
+                    Ok(()) => {
+                        self.apply_renamed_paths(&renames);
+                        self.show_status("Redo: Rename reapplied");
+                    }
+                    Err(e) => self.show_status(&format!("Cannot redo rename: {}", e)),
                 }
-                FileOperation::Crop { .. } => {
-                    self.show_status("Redo: Crop cannot be reapplied (operation not reversible)");
+            }
+            FileOperation::Rotate { path, degrees, .. } => {
+                self.redo_rotate_operation(path, degrees, current_path.clone());
+            }
+            FileOperation::Adjust { path, adjustments, .. } => {
+                self.redo_adjust_operation(path, adjustments, current_path.clone());
+            }
+            FileOperation::Crop { .. } => {
+                self.show_status("Redo: Crop cannot be reapplied (operation not reversible)");
+            }
+            FileOperation::Rate { path, after, .. } => {
+                self.metadata_db.set_rating(&path, after.0);
+                self.metadata_db.set_color_label(&path, after.1);
+                rated.push(path);
+                self.show_status("Redo: Rating reapplied");
+            }
+            FileOperation::Flag { path, after, .. } => {
+                self.metadata_db.set_flag(&path, after);
+                rated.push(path);
+                self.show_status("Redo: Flag reapplied");
+            }
+            FileOperation::Batch(ops) => {
+                let count = ops.len();
+                for op in ops {
+                    self.redo_operation(op, current_path, rated);
                 }
+                self.show_status(&format!("Redo: {} changes reapplied", count));
             }
         }
     }
 
     fn redo_delete_operation(&mut self, original_path: PathBuf) {
-        if trash::delete(&original_path).is_ok() {
-            if let Some(&idx) = self.filtered_list.get(self.current_index) {
-                self.image_list.remove(idx);
+        match self.delete_file(&original_path) {
+            Ok(_) => {
+                self.remove_from_list(&[original_path]);
+                self.show_status("Redo: File deleted again");
             }
-            self.apply_filter();
-            self.show_status("Redo: File deleted again");
+            Err(e) => self.show_status(&format!("Cannot redo delete: {}", e)),
         }
     }
 
     fn redo_move_operation(&mut self, from: PathBuf, to: PathBuf) {
-        if std::fs::rename(&to, &from).is_ok() {
-            if let Some(pos) = self.image_list.iter().position(|p| *p == from) {
-                self.image_list[pos] = from.clone();
-            }
+        if std::fs::rename(&from, &to).is_ok() {
+            self.remove_from_list(&[from]);
             self.show_status("Redo: Move reapplied");
         }
     }
@@ -0,0 +1,159 @@
+use super::file_ops::{encode_exported_image, ExportOptions};
+use super::{ImageViewerApp, LoaderMessage};
+use crate::export_naming::ExportNameInfo;
+use crate::rename::RenameInfo;
+use crate::settings::ExportFormat;
+use image::DynamicImage;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Side of the 1:1 crop compared before and after compression
+const PREVIEW_CROP: u32 = 256;
+
+/// The rendered image exported with one set of options
+pub struct EncodedPreview {
+    pub options: ExportOptions,
+    /// Size of the whole image's file
+    pub size: u64,
+    /// Pixel size of the exported image
+    pub dimensions: [u32; 2],
+    /// The crop before compression
+    pub original: egui::TextureHandle,
+    /// The crop as it looks after decoding again
+    pub texture: egui::TextureHandle,
+}
+
+/// Before and after views for the export dialog
+pub struct ExportPreview {
+    image: Arc<DynamicImage>,
+    /// The current image and its template values, to show where it will go
+    pub name_info: Option<(PathBuf, ExportNameInfo)>,
+    pub encoded: Option<EncodedPreview>,
+    /// Options last handed to the encoder
+    requested: Option<ExportOptions>,
+    encoding: bool,
+    pub error: Option<String>,
+}
+
+impl ExportPreview {
+    /// Whether newer options are being encoded than the ones shown
+    pub fn is_encoding(&self) -> bool {
+        self.encoding
+    }
+}
+
+/// Top left of a `PREVIEW_CROP` square at the middle of `size`, on the 16px grid
+pub fn preview_crop_origin(size: [u32; 2]) -> [u32; 2] {
+    let origin = |side: u32| (side.saturating_sub(PREVIEW_CROP) / 2) & !15;
+    [origin(size[0]), origin(size[1])]
+}
+
+fn crop(image: &DynamicImage, origin: [u32; 2]) -> DynamicImage {
+    image.crop_imm(origin[0], origin[1], PREVIEW_CROP, PREVIEW_CROP)
+}
+
+fn preview_texture(ctx: &egui::Context, name: &str, image: &DynamicImage) -> egui::TextureHandle {
+    let rgba = image.to_rgba8();
+    ctx.load_texture(
+        name,
+        egui::ColorImage::from_rgba_unmultiplied(
+            [rgba.width() as usize, rgba.height() as usize],
+            rgba.as_flat_samples().as_slice(),
+        ),
+        // Pixels stay sharp so the artifacts can be seen
+        egui::TextureOptions::NEAREST,
+    )
+}
+
+fn encode_preview(
+    ctx: &egui::Context,
+    rendered: &DynamicImage,
+    options: ExportOptions,
+) -> Result<EncodedPreview, String> {
+    let exported = options.resized(rendered.clone());
+    let (format, quality) = (options.format, options.quality);
+    let file = encode_exported_image(&exported, format, quality, Some(options.profile))?;
+    let sample = crop(
+        &exported,
+        preview_crop_origin([exported.width(), exported.height()]),
+    );
+    // Only JPEG loses anything; the other formats look like the original
+    let decoded = if format == ExportFormat::Jpeg {
+        let bytes = encode_exported_image(&sample, format, quality, None)?;
+        image::load_from_memory(&bytes).map_err(|e| e.to_string())?
+    } else {
+        sample.clone()
+    };
+    Ok(EncodedPreview {
+        options,
+        size: file.len() as u64,
+        dimensions: [exported.width(), exported.height()],
+        original: preview_texture(ctx, "export_original", &sample),
+        texture: preview_texture(ctx, "export_encoded", &decoded),
+    })
+}
+
+impl ImageViewerApp {
+    /// Show the export dialog for the current image as rendered
+    pub fn open_export_dialog(&mut self) {
+        if self.current_image.is_none() {
+            self.show_status("No image to export");
+            return;
+        }
+        let Some(image) = self.rendered_image() else {
+            return;
+        };
+        let name_info = self.get_current_path().map(|path| {
+            let info = ExportNameInfo {
+                file: RenameInfo::from_file(&path),
+                rating: self.metadata_db.get(&path).rating,
+            };
+            (path, info)
+        });
+        self.export_preview = Some(ExportPreview {
+            image: Arc::new(image),
+            name_info,
+            encoded: None,
+            requested: None,
+            encoding: false,
+            error: None,
+        });
+    }
+
+    /// Encode the preview with the chosen options once the previous encode is
+    /// done, so the slider only ever waits on the latest value
+    pub fn update_export_preview(&mut self) {
+        let wanted = ExportOptions::from_settings(&self.settings);
+        let Some(preview) = &mut self.export_preview else {
+            return;
+        };
+        if preview.encoding || preview.requested == Some(wanted) {
+            return;
+        }
+        let Some(ctx) = self.ctx.clone() else {
+            return;
+        };
+        preview.requested = Some(wanted);
+        preview.encoding = true;
+        let rendered = Arc::clone(&preview.image);
+        self.spawn_loader(move |_| {
+            let result = encode_preview(&ctx, &rendered, wanted);
+            Some(LoaderMessage::ExportPreviewEncoded(Box::new(result)))
+        });
+    }
+
+    pub fn handle_export_preview_encoded(&mut self, result: Result<EncodedPreview, String>) {
+        // Closed while encoding
+        let Some(preview) = &mut self.export_preview else {
+            return;
+        };
+        preview.encoding = false;
+        match result {
+            Ok(encoded) => {
+                preview.encoded = Some(encoded);
+                preview.error = None;
+            }
+            Err(e) => preview.error = Some(e),
+        }
+    }
+}
@@ -1,11 +1,182 @@
-use crate::image_loader::{is_supported_image, SUPPORTED_EXTENSIONS};
+use crate::cli::CliArgs;
+use crate::color_management::{convert_for_export, OutputProfile};
+use crate::export_naming::{resolve_export_paths, ExportNameInfo, ExportNaming};
+use crate::image_loader::{
+    is_supported_image, opens_as_folder, resize_to_fit, ResizeFilter, ARCHIVE_EXTENSIONS,
+    SUPPORTED_EXTENSIONS,
+};
+use crate::rename::RenameInfo;
+use crate::settings::{ExportFormat, Settings};
 use eframe::egui;
 use image::DynamicImage;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use walkdir::WalkDir;
 
-use super::ImageViewerApp;
+use super::{ImageViewerApp, LoaderMessage};
+
+/// JPEG quality for files saved without choosing one, the encoder's own default
+pub const DEFAULT_JPEG_QUALITY: u8 = 75;
+
+/// How export, batch export and sharing write files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    /// JPEG quality, 1-100
+    pub quality: u8,
+    /// Long edge to fit within, never enlarging
+    pub resize: Option<u32>,
+    pub filter: ResizeFilter,
+    /// Color space the pixels are converted to and tagged with
+    pub profile: OutputProfile,
+}
+
+impl ExportOptions {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            format: settings.export_format,
+            quality: settings.export_quality,
+            resize: settings.export_resize,
+            filter: settings.export_resize_filter,
+            profile: settings.export_profile,
+        }
+    }
+
+    /// `image` shrunk to the export size
+    pub fn resized(&self, image: DynamicImage) -> DynamicImage {
+        match self.resize {
+            Some(max) => resize_to_fit(image, max, self.filter),
+            None => image,
+        }
+    }
+
+    /// Write `image`, rendered from the file `source`, at the export size and
+    /// in the output profile
+    pub fn save(
+        &self,
+        image: DynamicImage,
+        source: Option<&Path>,
+        path: &Path,
+    ) -> Result<(), String> {
+        let image = convert_for_export(self.resized(image), source, self.profile);
+        save_exported_image(&image, path, self.quality, Some(self.profile))
+    }
+}
+
+/// File contents of an exported image; `quality` (1-100) applies to JPEG, the
+/// other formats are lossless. `profile` is embedded where the format allows;
+/// the pixels must already be in it.
+pub fn encode_exported_image(
+    image: &DynamicImage,
+    format: ExportFormat,
+    quality: u8,
+    profile: Option<OutputProfile>,
+) -> Result<Vec<u8>, String> {
+    use image::codecs::{jpeg::JpegEncoder, png::PngEncoder, tiff::TiffEncoder, webp::WebPEncoder};
+
+    let icc_profile = profile.map(|p| p.icc_profile());
+    let mut bytes = Vec::new();
+    let mut cursor = std::io::Cursor::new(&mut bytes);
+    let result = match format {
+        // The JPEG XL encoder can't embed profiles
+        ExportFormat::Jxl => return crate::image_loader::encode_jxl(image),
+        // JPEG has no alpha channel
+        ExportFormat::Jpeg => write_encoded(
+            &DynamicImage::ImageRgb8(image.to_rgb8()),
+            JpegEncoder::new_with_quality(&mut cursor, quality.clamp(1, 100)),
+            icc_profile,
+        ),
+        ExportFormat::Png => write_encoded(image, PngEncoder::new(&mut cursor), icc_profile),
+        ExportFormat::Tiff => write_encoded(image, TiffEncoder::new(&mut cursor), icc_profile),
+        ExportFormat::WebP => {
+            write_encoded(image, WebPEncoder::new_lossless(&mut cursor), icc_profile)
+        }
+    };
+    result.map_err(|e| e.to_string())?;
+    Ok(bytes)
+}
+
+fn write_encoded(
+    image: &DynamicImage,
+    mut encoder: impl image::ImageEncoder,
+    icc_profile: Option<Vec<u8>>,
+) -> image::ImageResult<()> {
+    if let Some(icc_profile) = icc_profile {
+        if let Err(e) = encoder.set_icc_profile(icc_profile) {
+            log::warn!("Exporting without a color profile: {}", e);
+        }
+    }
+    image.write_with_encoder(encoder)
+}
+
+/// Write an exported image, picking the encoder from the file extension
+pub fn save_exported_image(
+    image: &DynamicImage,
+    path: &Path,
+    quality: u8,
+    profile: Option<OutputProfile>,
+) -> Result<(), String> {
+    match ExportFormat::from_path(path) {
+        Some(format) => {
+            let bytes = encode_exported_image(image, format, quality, profile)?;
+            std::fs::write(path, bytes).map_err(|e| e.to_string())
+        }
+        None => image.save(path).map_err(|e| e.to_string()),
+    }
+}
+
+/// Windows `CF_HDROP` clipboard data: a `DROPFILES` header followed by the
+/// wide-character paths, each null-terminated, and a final null
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn drop_files_payload(paths: &[PathBuf]) -> Vec<u8> {
+    // Offset of the file list, cursor point, non-client flag, wide-character flag
+    let header: [u32; 5] = [20, 0, 0, 0, 1];
+    let mut payload: Vec<u8> = header.iter().flat_map(|v| v.to_le_bytes()).collect();
+    for path in paths {
+        for unit in path.to_string_lossy().encode_utf16().chain([0]) {
+            payload.extend(unit.to_le_bytes());
+        }
+    }
+    payload.extend(0u16.to_le_bytes());
+    payload
+}
+
+#[cfg(windows)]
+fn set_clipboard_files(paths: &[PathBuf]) -> Result<(), String> {
+    use winapi::um::winbase::{GlobalAlloc, GlobalFree, GlobalLock, GlobalUnlock, GMEM_MOVEABLE};
+    use winapi::um::winuser::{
+        CloseClipboard, EmptyClipboard, OpenClipboard, SetClipboardData, CF_HDROP,
+    };
+
+    let payload = drop_files_payload(paths);
+    // SAFETY: the global block is sized for the payload and only written while
+    // locked; once SetClipboardData succeeds the clipboard owns it
+    unsafe {
+        let handle = GlobalAlloc(GMEM_MOVEABLE, payload.len());
+        if handle.is_null() {
+            return Err("Out of memory".to_string());
+        }
+        let target = GlobalLock(handle) as *mut u8;
+        if target.is_null() {
+            GlobalFree(handle);
+            return Err("Out of memory".to_string());
+        }
+        std::ptr::copy_nonoverlapping(payload.as_ptr(), target, payload.len());
+        GlobalUnlock(handle);
+
+        if OpenClipboard(std::ptr::null_mut()) == 0 {
+            GlobalFree(handle);
+            return Err("The clipboard is in use".to_string());
+        }
+        EmptyClipboard();
+        let set = SetClipboardData(CF_HDROP, handle as _);
+        CloseClipboard();
+        if set.is_null() {
+            GlobalFree(handle);
+            return Err("The clipboard refused the files".to_string());
+        }
+    }
+    Ok(())
+}
 
 #[allow(dead_code)]
 impl ImageViewerApp {
@@ -15,6 +186,7 @@ impl ImageViewerApp {
 
         if let Some(path) = rfd::FileDialog::new()
             .add_filter("Images", &extensions)
+            .add_filter("Archives", ARCHIVE_EXTENSIONS)
             .pick_file()
         {
             self.load_image_file(path);
@@ -35,7 +207,7 @@ impl ImageViewerApp {
                 if path.is_file() && is_supported_image(path) {
                     self.load_image_file(path.clone());
                     break;
-                } else if path.is_dir() {
+                } else if opens_as_folder(path) {
                     self.load_folder(path.clone());
                     break;
                 }
@@ -62,7 +234,7 @@ impl ImageViewerApp {
         }
     }
 
-    pub fn copy_to_clipboard(&self) {
+    pub fn copy_path_to_clipboard(&self) {
         if let Some(path) = self.get_current_path() {
             if let Ok(mut clipboard) = arboard::Clipboard::new() {
                 let _ = clipboard.set_text(path.display().to_string());
@@ -70,96 +242,198 @@ impl ImageViewerApp {
         }
     }
 
-    pub fn export_image(&mut self) {
-        if let Some(image) = &self.current_image {
-            let extensions = vec!["jpg", "jpeg", "png", "bmp", "tiff", "tif"];
-
-            // Generate default filename based on current image path
-            let default_filename = if let Some(current_path) = self.get_current_path() {
-                if let Some(file_stem) = current_path.file_stem() {
-                    if let Some(extension) = current_path.extension() {
-                        format!(
-                            "{}_rView.{}",
-                            file_stem.to_string_lossy(),
-                            extension.to_string_lossy()
-                        )
-                    } else {
-                        format!("{}_rView.jpg", file_stem.to_string_lossy())
-                    }
-                } else {
-                    "exported_image_rView.jpg".to_string()
-                }
-            } else {
-                "exported_image_rView.jpg".to_string()
+    /// Put the image as shown, adjustments included, on the clipboard as a bitmap
+    pub fn copy_image_to_clipboard(&mut self) {
+        let Some(image) = self.rendered_image() else {
+            self.show_status("No image to copy");
+            return;
+        };
+        let rgba = image.to_rgba8();
+        let data = arboard::ImageData {
+            width: rgba.width() as usize,
+            height: rgba.height() as usize,
+            bytes: std::borrow::Cow::Owned(rgba.into_raw()),
+        };
+        let result = arboard::Clipboard::new().and_then(|mut c| c.set_image(data));
+        match result {
+            Ok(()) => self.show_status("Copied image to clipboard"),
+            Err(e) => self.show_status(&format!("Failed to copy image: {}", e)),
+        }
+    }
+
+    /// Copy the selected files, or the current one, so they can be pasted
+    /// into a file manager
+    pub fn copy_file_to_clipboard(&mut self) {
+        let mut paths = self.selected_paths();
+        if paths.is_empty() {
+            paths.extend(self.get_current_path());
+        }
+        if paths.is_empty() {
+            return;
+        }
+
+        #[cfg(windows)]
+        {
+            let message = match set_clipboard_files(&paths) {
+                Ok(()) if paths.len() == 1 => "Copied file to clipboard".to_string(),
+                Ok(()) => format!("Copied {} files to clipboard", paths.len()),
+                Err(e) => format!("Failed to copy files: {}", e),
             };
+            self.show_status(&message);
+        }
 
-            if let Some(path) = rfd::FileDialog::new()
-                .add_filter("Images", &extensions)
-                .set_file_name(&default_filename)
-                .save_file()
+        // Other platforms have no common file clipboard format; paste the paths instead
+        #[cfg(not(windows))]
+        {
+            let text = paths
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join("\n");
+            if let Ok(mut clipboard) = arboard::Clipboard::new() {
+                let _ = clipboard.set_text(text);
+                self.show_status("Copied file paths to clipboard");
+            }
+        }
+    }
+
+    /// The current image as shown, with its adjustments applied
+    pub fn rendered_image(&self) -> Option<DynamicImage> {
+        let image = self.current_image.as_ref()?;
+        // Apply current adjustments to the image
+        let rendered = if !self.adjustments.is_default() && !self.show_original {
+            // Use CPU for frames, LUTs and brush masks since GPU doesn't support them yet
+            if self.adjustments.frame_enabled
+                || self.adjustments.lut_path.is_some()
+                || self.adjustments.has_brush_masks()
             {
-                // Apply current adjustments to the image before saving
-                let image_to_save = if !self.adjustments.is_default() && !self.show_original {
-                    // Use CPU for frame processing since GPU doesn't support it yet
-                    if self.adjustments.frame_enabled {
-                        crate::image_loader::apply_adjustments(image, &self.adjustments)
-                    } else if let Some(gpu) = &self.gpu_processor {
-                        let gpu_clone = Arc::clone(gpu);
-                        let image_clone = image.clone();
-                        let adjustments_clone = self.adjustments.clone();
-
-                        match pollster::block_on(async {
-                            gpu_clone
-                                .apply_adjustments_texture(&image_clone, &adjustments_clone)
-                                .await
-                        }) {
-                            Ok(img) => img,
-                            Err(e) => {
-                                log::warn!(
-                                    "GPU texture export failed: {}; falling back to buffer method",
-                                    e
-                                );
-                                // Fallback to buffer-based GPU method
-                                match gpu.apply_adjustments(&image_clone, &adjustments_clone) {
-                                    Ok(pixels) => {
-                                        let width = image_clone.width();
-                                        let height = image_clone.height();
-                                        if let Some(buf) =
-                                            image::ImageBuffer::from_raw(width, height, pixels)
-                                        {
-                                            DynamicImage::ImageRgba8(buf)
-                                        } else {
-                                            crate::image_loader::apply_adjustments(
-                                                &image_clone,
-                                                &adjustments_clone,
-                                            )
-                                        }
-                                    }
-                                    Err(_) => crate::image_loader::apply_adjustments(
+                match self.get_current_path() {
+                    Some(source) => crate::image_loader::apply_adjustments_for_export(
+                        image,
+                        &self.adjustments,
+                        &source,
+                    ),
+                    None => crate::image_loader::apply_adjustments(image, &self.adjustments),
+                }
+            } else if let Some(gpu) = &self.gpu_processor {
+                let gpu_clone = Arc::clone(gpu);
+                // GPU kernels are per-pixel only; straighten on the CPU first
+                let image_clone =
+                    crate::image_loader::straighten_image(image, self.adjustments.straighten_angle);
+                let adjustments_clone = self.adjustments.without_geometry();
+
+                match pollster::block_on(async {
+                    gpu_clone
+                        .apply_adjustments_texture(&image_clone, &adjustments_clone)
+                        .await
+                }) {
+                    Ok(img) => img,
+                    Err(e) => {
+                        log::warn!(
+                            "GPU texture export failed: {}; falling back to buffer method",
+                            e
+                        );
+                        // Fallback to buffer-based GPU method
+                        match gpu.apply_adjustments(&image_clone, &adjustments_clone) {
+                            Ok(pixels) => {
+                                let width = image_clone.width();
+                                let height = image_clone.height();
+                                if let Some(buf) =
+                                    image::ImageBuffer::from_raw(width, height, pixels)
+                                {
+                                    DynamicImage::ImageRgba8(buf)
+                                } else {
+                                    crate::image_loader::apply_adjustments(
                                         &image_clone,
                                         &adjustments_clone,
-                                    ),
+                                    )
                                 }
                             }
+                            Err(_) => crate::image_loader::apply_adjustments(
+                                &image_clone,
+                                &adjustments_clone,
+                            ),
                         }
-                    } else {
-                        crate::image_loader::apply_adjustments(image, &self.adjustments)
-                    }
-                } else {
-                    image.clone()
-                };
-
-                match image_to_save.save(&path) {
-                    Ok(_) => {
-                        self.show_status(&format!("Exported to {}", path.display()));
-                    }
-                    Err(e) => {
-                        self.show_status(&format!("Failed to export image: {}", e));
                     }
                 }
+            } else {
+                crate::image_loader::apply_adjustments(image, &self.adjustments)
             }
         } else {
+            DynamicImage::clone(image)
+        };
+        Some(rendered)
+    }
+
+    /// Values for the export templates of `path`; file dates are only read
+    /// when a template uses them
+    pub fn export_name_info(&self, path: &Path, naming: &ExportNaming) -> ExportNameInfo {
+        ExportNameInfo {
+            file: if naming.needs_info() {
+                RenameInfo::from_file(path)
+            } else {
+                RenameInfo::default()
+            },
+            rating: self.metadata_db.get(path).rating,
+        }
+    }
+
+    /// Where the current image is exported to, following the naming rules;
+    /// None when the save dialog is cancelled or the file is skipped
+    fn export_destination(&mut self, options: &ExportOptions) -> Option<PathBuf> {
+        let naming = self.settings.export_naming.clone();
+        let source = self
+            .get_current_path()
+            .unwrap_or_else(|| PathBuf::from("exported_image"));
+        let info = self.export_name_info(&source, &naming);
+
+        if let Some(base) = naming.base_folder(&source) {
+            let target = naming.target(&base, &source, 1, &info, options.format);
+            let resolved = resolve_export_paths(vec![target.clone()], naming.conflict);
+            if resolved.first().is_some_and(Option::is_none) {
+                self.show_status(&format!("Skipped, {} already exists", target.display()));
+            }
+            return resolved.into_iter().flatten().next();
+        }
+
+        // Only the file name template applies to a file picked by hand
+        let target = naming.target(Path::new(""), &source, 1, &info, options.format);
+        let format = options.format;
+        // Preferred format first so it is the dialog's default filter
+        let mut dialog = rfd::FileDialog::new().add_filter(format.name(), format.extensions());
+        for other in ExportFormat::all().iter().filter(|f| **f != format) {
+            dialog = dialog.add_filter(other.name(), other.extensions());
+        }
+        dialog
+            .set_file_name(target.file_name()?.to_string_lossy())
+            .save_file()
+    }
+
+    pub fn export_image(&mut self) {
+        if self.current_image.is_none() {
             self.show_status("No image to export");
+            return;
+        }
+        let options = ExportOptions::from_settings(&self.settings);
+        let Some(path) = self.export_destination(&options) else {
+            return;
+        };
+        let Some(image_to_save) = self.rendered_image() else {
+            return;
+        };
+        let source = self.get_current_path();
+        // Subfolder templates may name folders that don't exist yet
+        let folder = path.parent().unwrap_or(Path::new(""));
+        let result = std::fs::create_dir_all(folder)
+            .map_err(|e| e.to_string())
+            .and_then(|()| options.save(image_to_save, source.as_deref(), &path));
+        match result {
+            Ok(_) => {
+                self.show_status(&format!("Exported to {}", path.display()));
+            }
+            Err(e) => {
+                self.show_status(&format!("Failed to export image: {}", e));
+            }
         }
     }
 
@@ -169,45 +443,91 @@ impl ImageViewerApp {
         self.pending_fit_to_window = true;
     }
 
-    // Load a folder (tabs removed) - populate app image list directly
-    pub fn load_folder(&mut self, folder: PathBuf) {
-        self.current_folder = Some(folder.clone());
-        self.settings.add_recent_folder(folder.clone());
-
-        self.image_list.clear();
-        self.thumbnail_textures.clear();
-        self.thumbnail_requests.clear();
+    /// Open the files later launches are given in this window instead
+    pub fn listen_for_other_launches(&self) {
+        let tx = self.loader_tx.clone();
+        let ctx = self.ctx.clone();
+        let result = crate::single_instance::listen(move |path, options| {
+            let options = CliArgs::parse(options).unwrap_or_default();
+            let _ = tx.send(LoaderMessage::OpenedFromLaunch(path, options));
+            if let Some(ctx) = &ctx {
+                ctx.request_repaint();
+            }
+        });
+        if let Err(e) = result {
+            log::warn!("Could not listen for other launches: {}", e);
+        }
+    }
 
-        if self.settings.include_subfolders {
-            for entry in WalkDir::new(&folder)
-                .follow_links(true)
-                .into_iter()
-                .filter_map(|e| e.ok())
-            {
-                let path = entry.path().to_path_buf();
-                if path.is_file() && is_supported_image(&path) {
-                    self.image_list.push(path);
-                }
+    /// Open what a second launch was asked to, with its options, and bring
+    /// the window to the front
+    pub fn handle_opened_from_launch(
+        &mut self,
+        path: Option<PathBuf>,
+        options: CliArgs,
+        ctx: &egui::Context,
+    ) {
+        // Without a path the options apply to the open folder
+        let relist = options.recursive && !self.settings.include_subfolders;
+        options.apply_to(&mut self.settings);
+        if path.is_none() {
+            if let Some(folder) = self.current_folder.clone().filter(|_| relist) {
+                let current = self.get_current_path();
+                self.load_folder_at(folder, current);
+            } else if options.sort.is_some() || options.filter_rating.is_some() {
+                self.sort_images();
+                self.refilter_keeping_current();
             }
-        } else if let Ok(entries) = std::fs::read_dir(&folder) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.is_file() && is_supported_image(&path) {
-                    self.image_list.push(path);
-                }
+        }
+        match path {
+            Some(path) if crate::download::is_url(&path.to_string_lossy()) => {
+                self.open_url(path.to_string_lossy().to_string())
             }
+            Some(path) if opens_as_folder(&path) => self.load_folder(path),
+            Some(path) => self.load_image_file(path),
+            None => {}
+        }
+        if options.fullscreen {
+            self.set_fullscreen(true);
+        }
+        if let Some(seconds) = options.slideshow {
+            self.start_slideshow(seconds);
         }
+        ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+        ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+    }
 
-        self.sort_images();
-        self.apply_filter();
+    pub fn load_folder(&mut self, folder: PathBuf) {
+        self.load_folder_at(folder, None);
+    }
 
-        if !self.filtered_list.is_empty() {
-            self.current_index = 0;
-            // Load adjustments for the first image
-            self.load_adjustments_for_current();
-            self.load_current_image();
+    /// Open a folder and show `select` once the background scan reaches it,
+    /// or the first image if it isn't given or never turns up
+    pub fn load_folder_at(&mut self, folder: PathBuf, select: Option<PathBuf>) {
+        if self.tether.as_ref().is_some_and(|t| t.folder != folder) {
+            self.stop_tether();
         }
+        self.current_folder = Some(folder.clone());
+        self.settings.add_recent_folder(folder.clone());
+
+        self.image_list.clear();
+        self.filtered_list.clear();
+        self.current_index = 0;
+        self.thumbnail_textures.clear();
+        self.thumbnail_requests.clear();
+        self.thumbnail_exposure.clear();
+        self.strip_sizes.clear();
+        self.current_texture = None;
+        self.current_image = None;
+        self.current_exif = None;
+        self.shoot_summary = None;
+        // A day picked in the timeline rarely has images in another folder
+        self.date_filter = None;
+        self.animation = None;
+        // The old folder's watcher would add its files to the new list
+        self.folder_watcher = None;
 
-        self.show_status(&format!("Loaded {} images", self.image_list.len()));
+        self.show_status(&format!("Scanning {}...", folder.display()));
+        self.start_folder_scan(folder, select);
     }
 }
@@ -0,0 +1,112 @@
+use super::ImageViewerApp;
+use crate::image_loader::{FilmPreset, FilmStock};
+
+impl ImageViewerApp {
+    /// Save the current film look under `name`, replacing a stock with the same name
+    pub fn save_film_stock(&mut self, name: &str) {
+        let name = name.trim();
+        if name.is_empty() {
+            return;
+        }
+
+        let stock = FilmStock::from_adjustments(name, &self.adjustments);
+        self.add_film_stock(stock);
+        self.settings.save();
+        self.show_status(&format!("Saved film stock \"{}\"", name));
+    }
+
+    fn add_film_stock(&mut self, stock: FilmStock) {
+        match self
+            .settings
+            .film_stocks
+            .iter_mut()
+            .find(|s| s.name == stock.name)
+        {
+            Some(existing) => *existing = stock,
+            None => self.settings.film_stocks.push(stock),
+        }
+    }
+
+    pub fn apply_film_stock(&mut self, index: usize) {
+        let Some(stock) = self.settings.film_stocks.get(index).cloned() else {
+            return;
+        };
+
+        let previous = self.adjustments.clone();
+        stock.apply_to(&mut self.adjustments);
+        self.current_film_preset = FilmPreset::None;
+        if self.adjustments != previous {
+            self.commit_adjustment_change(previous);
+        }
+        self.show_status(&format!("Applied film stock \"{}\"", stock.name));
+    }
+
+    pub fn rename_film_stock(&mut self, index: usize, name: &str) {
+        let name = match name.trim() {
+            "" => "Untitled stock",
+            name => name,
+        };
+        if let Some(stock) = self.settings.film_stocks.get_mut(index) {
+            stock.name = name.to_string();
+            self.settings.save();
+        }
+    }
+
+    pub fn delete_film_stock(&mut self, index: usize) {
+        if index < self.settings.film_stocks.len() {
+            let stock = self.settings.film_stocks.remove(index);
+            self.settings.save();
+            self.show_status(&format!("Deleted film stock \"{}\"", stock.name));
+        }
+    }
+
+    /// Add stocks from files shared by another machine, replacing ones with
+    /// the same name
+    pub fn import_film_stocks(&mut self) {
+        let Some(paths) = rfd::FileDialog::new()
+            .add_filter("Film stock", &[FilmStock::EXTENSION])
+            .pick_files()
+        else {
+            return;
+        };
+
+        let mut imported = 0;
+        for path in paths {
+            match FilmStock::load(&path) {
+                Ok(stock) => {
+                    self.add_film_stock(stock);
+                    imported += 1;
+                }
+                Err(e) => {
+                    log::warn!("Failed to import film stock {}: {}", path.display(), e);
+                    self.show_status(&format!("Failed to import film stock: {}", e));
+                }
+            }
+        }
+        if imported > 0 {
+            self.settings.save();
+            self.show_status(&format!("Imported {} film stock(s)", imported));
+        }
+    }
+
+    pub fn export_film_stock(&mut self, index: usize) {
+        let Some(stock) = self.settings.film_stocks.get(index) else {
+            return;
+        };
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Film stock", &[FilmStock::EXTENSION])
+            .set_file_name(format!("{}.{}", stock.name, FilmStock::EXTENSION))
+            .save_file()
+        else {
+            return;
+        };
+
+        match stock.save(&path) {
+            Ok(()) => {
+                let message = format!("Exported film stock \"{}\"", stock.name);
+                self.show_status(&message);
+            }
+            Err(e) => self.show_status(&format!("Failed to export film stock: {}", e)),
+        }
+    }
+}
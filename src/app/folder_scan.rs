@@ -0,0 +1,164 @@
+use super::recently_deleted::is_held;
+use super::{ImageViewerApp, LoaderMessage};
+use crate::image_loader::{is_archive_file, is_supported_image, list_archive_images};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use walkdir::WalkDir;
+
+/// Send what has been found at least this often, so a slow share still
+/// shows its first images quickly
+const SCAN_BATCH_INTERVAL: Duration = Duration::from_millis(100);
+/// Largest batch sent in one message while the scan is finding files quickly
+const SCAN_BATCH_SIZE: usize = 500;
+
+/// A folder being listed in the background
+pub struct FolderScan {
+    id: u64,
+    cancel: Arc<AtomicBool>,
+    /// File to show once the scan finds it
+    select: Option<PathBuf>,
+}
+
+/// List the supported images in `folder`, handing them to `emit` in batches.
+/// Stops early once `cancel` is set. A zip or cbz is listed whole, subfolders
+/// and all.
+pub fn scan_folder(
+    folder: &Path,
+    include_subfolders: bool,
+    cancel: &AtomicBool,
+    mut emit: impl FnMut(Vec<PathBuf>),
+) {
+    if is_archive_file(folder) && folder.is_file() {
+        match list_archive_images(folder) {
+            Ok(paths) if !paths.is_empty() => emit(paths),
+            Ok(_) => {}
+            Err(e) => log::warn!("Failed to read archive {}: {}", folder.display(), e),
+        }
+        return;
+    }
+
+    let mut batch = Vec::new();
+    let mut last_sent = Instant::now();
+    let mut found = |path: PathBuf, batch: &mut Vec<PathBuf>| {
+        if path.is_file() && is_supported_image(&path) && !is_held(&path) {
+            batch.push(path);
+        }
+        if !batch.is_empty()
+            && (batch.len() >= SCAN_BATCH_SIZE || last_sent.elapsed() >= SCAN_BATCH_INTERVAL)
+        {
+            emit(std::mem::take(batch));
+            last_sent = Instant::now();
+        }
+    };
+
+    if include_subfolders {
+        for entry in WalkDir::new(folder)
+            .follow_links(true)
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            if cancel.load(Ordering::Relaxed) {
+                return;
+            }
+            found(entry.into_path(), &mut batch);
+        }
+    } else if let Ok(entries) = std::fs::read_dir(folder) {
+        for entry in entries.flatten() {
+            if cancel.load(Ordering::Relaxed) {
+                return;
+            }
+            found(entry.path(), &mut batch);
+        }
+    }
+    if !batch.is_empty() {
+        emit(batch);
+    }
+}
+
+impl ImageViewerApp {
+    /// List `folder` on a background thread; images are added as they are found
+    /// and `select` is shown once it turns up
+    pub fn start_folder_scan(&mut self, folder: PathBuf, select: Option<PathBuf>) {
+        self.cancel_folder_scan();
+        self.folder_scan_generation += 1;
+        let id = self.folder_scan_generation;
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.folder_scan = Some(FolderScan {
+            id,
+            cancel: Arc::clone(&cancel),
+            select,
+        });
+
+        let include_subfolders = self.settings.include_subfolders;
+        let tx = self.loader_tx.clone();
+        let ctx = self.ctx.clone();
+        std::thread::spawn(move || {
+            let repaint = || {
+                if let Some(ctx) = &ctx {
+                    ctx.request_repaint();
+                }
+            };
+            scan_folder(&folder, include_subfolders, &cancel, |paths| {
+                let _ = tx.send(LoaderMessage::FolderScanned(id, paths));
+                repaint();
+            });
+            if !cancel.load(Ordering::Relaxed) {
+                let _ = tx.send(LoaderMessage::FolderScanFinished(id));
+                repaint();
+            }
+        });
+    }
+
+    /// Stop listing the folder, e.g. because another one was opened
+    pub fn cancel_folder_scan(&mut self) {
+        if let Some(scan) = self.folder_scan.take() {
+            scan.cancel.store(true, Ordering::Relaxed);
+        }
+    }
+
+    pub fn handle_folder_scanned(&mut self, id: u64, paths: Vec<PathBuf>) {
+        let Some(scan) = self.folder_scan.as_mut().filter(|s| s.id == id) else {
+            return;
+        };
+        // Hold off showing anything while the file asked for is still to come,
+        // so the first image doesn't flash up before it
+        let waiting = match &scan.select {
+            Some(file) if paths.contains(file) => false,
+            Some(_) => true,
+            None => false,
+        };
+        let select = if waiting { None } else { scan.select.take() };
+        let target = select.or_else(|| self.get_current_path());
+
+        self.image_list.extend(paths);
+        self.sort_images();
+        if waiting {
+            self.apply_filter();
+        } else {
+            self.refilter_keeping(target);
+        }
+    }
+
+    pub fn handle_folder_scan_finished(&mut self, id: u64) {
+        if !self.folder_scan.as_ref().is_some_and(|s| s.id == id) {
+            return;
+        }
+        let scan = self.folder_scan.take();
+        // The file asked for never turned up, so start from the first image
+        if scan.is_some_and(|s| s.select.is_some()) {
+            self.refilter_keeping(None);
+        }
+
+        self.import_xmp_sidecars();
+        self.watch_current_folder();
+        if self.show_shoot_summary_dialog {
+            self.refresh_shoot_summary();
+        }
+        if self.show_timeline_dialog {
+            self.refresh_timeline();
+        }
+        self.show_status(&format!("Loaded {} images", self.image_list.len()));
+    }
+}
@@ -0,0 +1,96 @@
+use super::ImageViewerApp;
+use crate::monitors::Monitor;
+use std::time::{Duration, Instant};
+
+/// How long to wait for the window to reach another monitor before going
+/// fullscreen wherever it is
+const MOVE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Window on its way to the monitor chosen for fullscreen
+pub struct FullscreenMove {
+    target: Monitor,
+    started: Instant,
+}
+
+impl ImageViewerApp {
+    pub fn set_fullscreen(&mut self, fullscreen: bool) {
+        self.is_fullscreen = fullscreen;
+        self.fullscreen_move = None;
+        let Some(ctx) = self.ctx.clone() else {
+            return;
+        };
+        if !fullscreen {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(false));
+            return;
+        }
+
+        // Fullscreen covers the monitor the window is on, so move it first
+        let target = self.settings.fullscreen_monitor.as_ref().and_then(|name| {
+            crate::monitors::list()
+                .into_iter()
+                .find(|monitor| &monitor.name == name)
+        });
+        match target {
+            Some(target) if !window_on_monitor(&ctx, &target) => {
+                let scale = ctx.input(|i| i.viewport().native_pixels_per_point.unwrap_or(1.0));
+                ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(false));
+                ctx.send_viewport_cmd(egui::ViewportCommand::Maximized(false));
+                ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(
+                    target.window_position(scale),
+                ));
+                self.fullscreen_move = Some(FullscreenMove {
+                    target,
+                    started: Instant::now(),
+                });
+                ctx.request_repaint();
+            }
+            _ => ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(true)),
+        }
+    }
+
+    pub fn toggle_fullscreen(&mut self) {
+        self.set_fullscreen(!self.is_fullscreen);
+    }
+
+    /// Go fullscreen on the next monitor, remembering it for later fullscreens
+    pub fn fullscreen_on_next_monitor(&mut self) {
+        let monitors = crate::monitors::list();
+        let current = self.settings.fullscreen_monitor.as_deref();
+        let Some(next) = crate::monitors::next_monitor(&monitors, current) else {
+            self.set_fullscreen(true);
+            return;
+        };
+        self.settings.fullscreen_monitor = Some(next.name.clone());
+        self.show_status(&format!("Fullscreen on {}", next.label()));
+        self.set_fullscreen(true);
+    }
+
+    /// Go fullscreen once the window has reached the chosen monitor
+    pub fn update_fullscreen_move(&mut self, ctx: &egui::Context) {
+        let Some(pending) = &self.fullscreen_move else {
+            return;
+        };
+        if window_on_monitor(ctx, &pending.target) || pending.started.elapsed() > MOVE_TIMEOUT {
+            self.fullscreen_move = None;
+            ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(true));
+        } else {
+            ctx.request_repaint_after(Duration::from_millis(50));
+        }
+    }
+}
+
+/// Whether the middle of the window is on `monitor`
+fn window_on_monitor(ctx: &egui::Context, monitor: &Monitor) -> bool {
+    let (rect, scale) = ctx.input(|i| {
+        let viewport = i.viewport();
+        (
+            viewport.outer_rect,
+            viewport.native_pixels_per_point.unwrap_or(1.0),
+        )
+    });
+    let scale = crate::monitors::desktop_scale(scale);
+    rect.is_some_and(|rect| {
+        let center = rect.center();
+        monitor.contains(center.x * scale, center.y * scale)
+    })
+}
@@ -2,9 +2,11 @@ use crate::exif_data::ExifInfo;
 use crate::gpu::types::GpuProcessor;
 use crate::image_loader;
 use crate::profiler;
+use crate::task_scheduler::{TaskPriority, TaskScheduler};
 use eframe::egui::{self, TextureHandle, Vec2};
-use image::{imageops, DynamicImage, ImageBuffer, Rgba};
+use image::DynamicImage;
 use pollster;
+use std::borrow::Cow;
 use std::path::{Path, PathBuf};
 use std::sync::mpsc::Sender;
 use std::sync::Arc;
@@ -13,25 +15,38 @@ use super::ImageViewerApp;
 
 impl ImageViewerApp {
     pub fn load_image_file(&mut self, path: PathBuf) {
+        if image_loader::is_archive_file(&path) {
+            self.load_folder(path);
+            return;
+        }
         if let Some(parent) = path.parent() {
-            self.load_folder(parent.to_path_buf());
-
-            if let Some(idx) = self.image_list.iter().position(|p| p == &path) {
-                self.current_index = idx;
-                // Load adjustments for this image
-                self.load_adjustments_for_current();
-                self.load_current_image();
-            }
+            self.load_folder_at(parent.to_path_buf(), Some(path.clone()));
         }
     }
 
     pub fn spawn_loader<F>(&self, f: F)
+    where
+        F: FnOnce(&Sender<super::LoaderMessage>) -> Option<super::LoaderMessage> + Send + 'static,
+    {
+        self.spawn_on(&self.loader_pool, f);
+    }
+
+    /// Like `spawn_loader`, for jobs that take minutes such as exports,
+    /// uploads and downloads, so they can't fill every loader thread
+    pub fn spawn_long_job<F>(&self, f: F)
+    where
+        F: FnOnce(&Sender<super::LoaderMessage>) -> Option<super::LoaderMessage> + Send + 'static,
+    {
+        self.spawn_on(&self.long_job_pool, f);
+    }
+
+    fn spawn_on<F>(&self, pool: &TaskScheduler, f: F)
     where
         F: FnOnce(&Sender<super::LoaderMessage>) -> Option<super::LoaderMessage> + Send + 'static,
     {
         let tx = self.loader_tx.clone();
         let ctx = self.ctx.clone();
-        std::thread::spawn(move || {
+        pool.run(TaskPriority::Medium, move || {
             if let Some(msg) = f(&tx) {
                 let _ = tx.send(msg);
             }
@@ -42,14 +57,26 @@ impl ImageViewerApp {
     }
 
     pub fn load_current_image(&mut self) {
+        self.sync_adjustments_for_current();
         if let Some(path) = self.get_current_path() {
             self.reset_image_state();
+            // Pasted images only exist in memory
+            if let Some(image) = self.pasted_images.get(&path).cloned() {
+                self.set_current_image(&path, image);
+                return;
+            }
             self.settings.last_file = Some(path.clone());
+            self.settings.add_recent_image(path.clone());
+            self.cancel_stale_loads(&path);
+            self.request_animation(&path);
+            self.request_video(&path);
 
             if self.try_load_from_cache(&path) {
                 return;
             }
 
+            self.request_progressive_passes(&path);
+
             // Use new task scheduler for prioritized loading
             self.task_scheduler.submit_task(crate::task_scheduler::ImageTask::LoadImage {
                 path: path.clone(),
@@ -73,150 +100,262 @@ impl ImageViewerApp {
         self.histogram_data = None;
         self.focus_peaking_texture = None;
         self.zebra_texture = None;
+        self.clipping_texture = None;
+        self.invalidate_overlay_source();
         self.showing_preview = false;
+        self.stop_progressive_passes();
     }
 
     fn try_load_from_cache(&mut self, path: &Path) -> bool {
         if let Some(image) = self.image_cache.get(path) {
             self.set_current_image(path, image);
-            self.load_exif_data(path);
+            self.load_exif_data(path, crate::task_scheduler::TaskPriority::High);
             return true;
         }
         false
     }
 
-    fn load_raw_image(&mut self, path: &Path) {
-        // Load quick preview first
-        let path_clone = path.to_path_buf();
-        self.spawn_loader(move |_tx| {
-            image_loader::load_thumbnail(&path_clone, 1920)
-                .ok()
-                .map(|preview| super::LoaderMessage::PreviewLoaded(path_clone, preview))
+    /// Show low resolution passes of a large JPEG while the scheduler decodes it
+    /// in full, so a file on a slow share shows something almost at once
+    fn request_progressive_passes(&mut self, path: &Path) {
+        if !image_loader::wants_progressive_passes(path) {
+            return;
+        }
+        let cancel = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        self.progressive_cancel = Some(Arc::clone(&cancel));
+        let path = path.to_path_buf();
+        let ctx = self.ctx.clone();
+        self.spawn_loader(move |tx| {
+            let result = image_loader::decode_progressive_passes(
+                &path,
+                image_loader::PROGRESSIVE_MAX_SIDE,
+                &cancel,
+                |pass, size| {
+                    let _ = tx.send(super::LoaderMessage::ProgressiveLoaded(
+                        path.clone(),
+                        pass,
+                        size,
+                    ));
+                    if let Some(ctx) = &ctx {
+                        ctx.request_repaint();
+                    }
+                },
+            );
+            if let Err(e) = result {
+                log::debug!("Progressive decode of {} failed: {}", path.display(), e);
+            }
+            None
         });
+    }
 
-        if self.settings.load_raw_full_size {
-            // Spawn full image load
-            let path_clone = path.to_path_buf();
-            self.spawn_loader(move |_tx| {
-                Some(match image_loader::load_image(&path_clone) {
-                    Ok(image) => super::LoaderMessage::ImageLoaded(path_clone, image),
-                    Err(e) => super::LoaderMessage::LoadError(path_clone, format!("{}", e)),
-                })
-            });
+    /// Decode RAW files with the options in settings from now on. RAW images
+    /// cached with the old options are dropped and the current one reloaded.
+    pub fn apply_raw_decode_options(&mut self) {
+        image_loader::set_raw_decode_options(self.settings.raw_decode.clone());
+        for path in self
+            .image_list
+            .iter()
+            .filter(|p| image_loader::is_raw_file(p))
+        {
+            self.image_cache.remove(path);
+        }
+        let current_is_raw = self
+            .get_current_path()
+            .is_some_and(|p| image_loader::is_raw_file(&p));
+        if current_is_raw && self.settings.load_raw_full_size {
+            self.load_current_image();
         }
     }
 
-    fn load_standard_image(&mut self, path: &Path) {
-        // Load progressive versions for better UX
-        let path_clone = path.to_path_buf();
-        self.spawn_loader(move |_tx| match image_loader::load_image(&path_clone) {
-            Ok(full_image) => {
-                let preview = image_loader::generate_thumbnail(&full_image, 1920);
-                Some(super::LoaderMessage::ProgressiveLoaded(
-                    path_clone.clone(),
-                    preview,
-                ))
-            }
-            Err(e) => Some(super::LoaderMessage::LoadError(
-                path_clone,
-                format!("{}", e),
-            )),
-        });
-
-        // Then load the full resolution
-        let path_clone = path.to_path_buf();
-        self.spawn_loader(move |_tx| {
-            Some(match image_loader::load_image(&path_clone) {
-                Ok(image) => super::LoaderMessage::ImageLoaded(path_clone, image),
-                Err(e) => super::LoaderMessage::LoadError(path_clone, format!("{}", e)),
-            })
-        });
+    fn stop_progressive_passes(&mut self) {
+        self.progressive_size = None;
+        if let Some(cancel) = self.progressive_cancel.take() {
+            cancel.store(true, std::sync::atomic::Ordering::Relaxed);
+        }
     }
 
-    pub fn load_exif_data(&self, path: &Path) {
-        let path_clone = path.to_path_buf();
-        self.spawn_loader(move |_tx| {
-            let exif = ExifInfo::from_file(&path_clone);
-            Some(super::LoaderMessage::ExifLoaded(path_clone, Box::new(exif)))
-        });
+    pub fn load_exif_data(&self, path: &Path, priority: crate::task_scheduler::TaskPriority) {
+        self.task_scheduler
+            .submit_task(crate::task_scheduler::ImageTask::LoadExif {
+                path: path.to_path_buf(),
+                priority,
+            });
     }
 
-    pub fn set_current_image(&mut self, path: &std::path::Path, image: DynamicImage) {
+    pub fn set_current_image(
+        &mut self,
+        path: &std::path::Path,
+        image: impl Into<Arc<DynamicImage>>,
+    ) {
         let ctx = match &self.ctx {
             Some(c) => c.clone(),
             None => return,
         };
 
-        self.current_image = Some(image.clone());
+        let image = image.into();
+        self.current_image = Some(Arc::clone(&image));
         self.showing_preview = false;
+        self.stop_progressive_passes();
+        // Rebuilt from the new display image below if it is still too big for one texture
+        self.tiled_image = None;
+
+        // Queue the unadjusted source for the wgpu display pass; it stays resident on the
+        // GPU so adjustment previews and zoom/pan only touch uniforms
+        let transform = self.display_transform(path);
+        // Float images are tone mapped once here; the adjustments and the
+        // texture only ever see display-referred values
+        let display_source = image_loader::display_referred(&image, &self.settings.hdr_display);
+        if let Some(max_dim) = self.gpu_display_max_dim {
+            let source = super::display_proxy::build_display_source(
+                &image,
+                transform.as_ref(),
+                max_dim,
+                1.0,
+            );
+            self.gpu_display_upload = Some(Arc::new(source));
+            // Full resolution until a zoomed-out view asks for a proxy
+            self.gpu_display_scale = 1.0;
+            self.gpu_display_pending = None;
+        }
 
-        let adjusted_image = self.apply_adjustments_with_fallbacks(&image);
-        let display_image = self.apply_frame_to_image(&adjusted_image);
+        // The display pass draws these adjustments from the source uploaded above,
+        // so the texture holds the unadjusted image and only the histogram needs
+        // them, from a small copy
+        let draws_adjustments = self.display_pass_draws(&image, &ctx);
+        let adjusted_image = if !draws_adjustments {
+            Cow::Owned(self.apply_adjustments_with_fallbacks(&display_source))
+        } else if self.adjustments.is_default() {
+            display_source.clone()
+        } else {
+            let preview = display_source.thumbnail(1024, 1024);
+            Cow::Owned(image_loader::apply_adjustments(&preview, &self.adjustments))
+        };
+        let display_image = if draws_adjustments {
+            display_source.into_owned()
+        } else {
+            self.apply_frame_to_image(&adjusted_image)
+        };
+        // Convert to the display profile last so adjustments operate in the image's own space
+        let display_image = match (self.display_soft_proof(path), &transform) {
+            (Some(proof), _) => crate::color_management::apply_soft_proof(&proof, &display_image),
+            (None, Some(transform)) => {
+                crate::color_management::apply_transform(transform, &display_image)
+            }
+            (None, None) => display_image,
+        };
 
         self.create_texture_and_setup(path, &display_image, &ctx, &adjusted_image, &image);
     }
 
-    fn apply_frame_to_image(&self, image: &DynamicImage) -> DynamicImage {
-        if self.adjustments.frame_enabled && self.adjustments.frame_thickness > 0.0 {
-            let img = image.to_rgba8();
-            let (width, height) = img.dimensions();
-            let thickness = self.adjustments.frame_thickness as u32;
-            let new_width = width + 2 * thickness;
-            let new_height = height + 2 * thickness;
+    /// Whether the wgpu display pass shows `image` with the current adjustments
+    /// on its own. Tiles, overlays and views drawn from the texture need the
+    /// adjustments rendered into it.
+    fn display_pass_draws(&self, image: &DynamicImage, ctx: &egui::Context) -> bool {
+        let max_side = ctx.input(|i| i.max_texture_side) as u32;
+        self.gpu_display_max_dim.is_some()
+            && crate::gpu::display::can_preview(&self.adjustments)
+            && !self.soft_proof_enabled
+            && self.view_mode == super::ViewMode::Single
+            && !self.settings.loupe_enabled
+            && !self.settings.show_focus_peaking
+            && !self.settings.show_zebras
+            && !self.settings.show_clipping
+            && !crate::gpu::tiles::needs_tiling(image.width(), image.height(), max_side)
+    }
 
-            let mut framed = ImageBuffer::new(new_width, new_height);
+    fn apply_frame_to_image(&self, image: &DynamicImage) -> DynamicImage {
+        image_loader::apply_frame(image, &self.adjustments, None)
+    }
 
-            let frame_r = (self.adjustments.frame_color[0] * 255.0) as u8;
-            let frame_g = (self.adjustments.frame_color[1] * 255.0) as u8;
-            let frame_b = (self.adjustments.frame_color[2] * 255.0) as u8;
+    fn apply_adjustments_with_fallbacks(&self, image: &DynamicImage) -> DynamicImage {
+        // The GPU kernels are per-pixel only, so straighten on the CPU first
+        if self.adjustments.straighten_angle != 0.0 {
+            let straightened =
+                image_loader::straighten_image(image, self.adjustments.straighten_angle);
+            return self
+                .apply_tonal_adjustments(&straightened, &self.adjustments.without_geometry());
+        }
+        self.apply_tonal_adjustments(image, &self.adjustments)
+    }
 
-            for pixel in framed.pixels_mut() {
-                *pixel = Rgba([frame_r, frame_g, frame_b, 255]);
+    fn apply_tonal_adjustments(
+        &self,
+        image: &DynamicImage,
+        adjustments: &image_loader::ImageAdjustments,
+    ) -> DynamicImage {
+        if let Some(gpu) = &self.gpu_processor {
+            if let Some(img) = Self::apply_tonal_adjustments_gpu(gpu, image, adjustments) {
+                return img;
             }
+        }
 
-            imageops::overlay(&mut framed, &img, thickness as i64, thickness as i64);
+        // Final fallback to CPU
+        image_loader::apply_adjustments(image, adjustments)
+    }
 
-            DynamicImage::ImageRgba8(framed)
-        } else {
-            image.clone()
+    /// The per-pixel adjustments, film emulation included, on the GPU. None
+    /// when the GPU can't handle these settings or both of its paths fail.
+    fn apply_tonal_adjustments_gpu(
+        gpu: &GpuProcessor,
+        image: &DynamicImage,
+        adjustments: &image_loader::ImageAdjustments,
+    ) -> Option<DynamicImage> {
+        // LUTs and brush masks are only implemented on the CPU, and the GPU
+        // kernels work in 8 bits
+        if adjustments.lut_path.is_some()
+            || adjustments.has_brush_masks()
+            || image_loader::is_high_precision(image)
+        {
+            return None;
         }
-    }
 
-    fn apply_adjustments_with_fallbacks(&self, image: &DynamicImage) -> DynamicImage {
         // Try GPU texture-based path first (async)
-        if let Some(gpu) = &self.gpu_processor {
-            match pollster::block_on(async {
-                gpu.apply_adjustments_texture(image, &self.adjustments)
-                    .await
-            }) {
-                Ok(img) => return img,
-                Err(e) => {
-                    log::warn!(
-                        "GPU texture adjustments failed: {}; falling back to buffer method",
-                        e
-                    );
-                }
+        match pollster::block_on(async { gpu.apply_adjustments_texture(image, adjustments).await })
+        {
+            Ok(img) => return Some(img),
+            Err(e) => {
+                log::warn!(
+                    "GPU texture adjustments failed: {}; falling back to buffer method",
+                    e
+                );
             }
+        }
 
-            // Fallback to buffer-based GPU method
-            match gpu.apply_adjustments(image, &self.adjustments) {
-                Ok(pixels) => {
-                    let width = image.width();
-                    let height = image.height();
-                    if let Some(buf) = image::ImageBuffer::from_raw(width, height, pixels) {
-                        return DynamicImage::ImageRgba8(buf);
-                    } else {
-                        log::warn!("GPU returned unexpected buffer size; falling back to CPU");
-                    }
-                }
-                Err(e) => {
-                    log::warn!("GPU buffer adjustments failed: {}; falling back to CPU", e);
+        // Fallback to buffer-based GPU method
+        match gpu.apply_adjustments(image, adjustments) {
+            Ok(pixels) => {
+                let width = image.width();
+                let height = image.height();
+                if let Some(buf) = image::ImageBuffer::from_raw(width, height, pixels) {
+                    return Some(DynamicImage::ImageRgba8(buf));
+                } else {
+                    log::warn!("GPU returned unexpected buffer size; falling back to CPU");
                 }
             }
+            Err(e) => {
+                log::warn!("GPU buffer adjustments failed: {}; falling back to CPU", e);
+            }
         }
+        None
+    }
 
-        // Final fallback to CPU
-        image_loader::apply_adjustments(image, &self.adjustments)
+    /// The full adjustments for a preview while a slider is dragged. The
+    /// compute shader keeps up with the film look, so it stays on.
+    fn apply_preview_adjustments_gpu(
+        gpu: &GpuProcessor,
+        image: &DynamicImage,
+        adjustments: &image_loader::ImageAdjustments,
+    ) -> Option<DynamicImage> {
+        // The GPU kernels are per-pixel only, so straighten on the CPU first
+        if adjustments.straighten_angle != 0.0 {
+            let straightened = image_loader::straighten_image(image, adjustments.straighten_angle);
+            return Self::apply_tonal_adjustments_gpu(
+                gpu,
+                &straightened,
+                &adjustments.without_geometry(),
+            );
+        }
+        Self::apply_tonal_adjustments_gpu(gpu, image, adjustments)
     }
 
     fn create_texture_and_setup(
@@ -238,6 +377,11 @@ impl ImageViewerApp {
 
         let texture_name = self.generate_texture_name(path, size[0], size[1]);
 
+        let max_side = ctx.input(|i| i.max_texture_side) as u32;
+        if crate::gpu::tiles::needs_tiling(size[0] as u32, size[1] as u32, max_side) {
+            self.build_tiles_async(path, display_image);
+        }
+
         // Check if texture is already cached
         if let Some(texture) = self.get_cached_texture(&texture_name) {
             self.current_texture = Some(texture);
@@ -256,6 +400,7 @@ impl ImageViewerApp {
 
         // Calculate histogram
         self.histogram_data = Some(self.compute_histogram(adjusted_image));
+        self.request_scopes();
 
         // Generate overlays if enabled
         self.generate_overlays_if_needed(adjusted_image, ctx);
@@ -314,21 +459,16 @@ impl ImageViewerApp {
         let texture_name_clone = texture_name.clone();
         let display_image_clone = display_image.clone();
         let _tx_clone = self.loader_tx.clone();
+        let texture_options = self.texture_options();
 
         self.spawn_loader(move |_tx| {
             if let Some(ctx) = ctx_clone {
-                let rgba = display_image_clone.to_rgba8();
+                let rgba = Self::texture_pixels(&ctx, &display_image_clone);
                 let pixels = rgba.as_flat_samples();
-                let size = [
-                    display_image_clone.width() as usize,
-                    display_image_clone.height() as usize,
-                ];
+                let size = [rgba.width() as usize, rgba.height() as usize];
                 let color_image = egui::ColorImage::from_rgba_unmultiplied(size, pixels.as_slice());
-                let texture = ctx.load_texture(
-                    texture_name_clone.clone(),
-                    color_image,
-                    egui::TextureOptions::LINEAR,
-                );
+                let texture =
+                    ctx.load_texture(texture_name_clone.clone(), color_image, texture_options);
                 Some(super::LoaderMessage::TextureCreated(
                     PathBuf::from(texture_name_clone),
                     texture,
@@ -342,30 +482,97 @@ impl ImageViewerApp {
         profiler::with_profiler(|p| p.end_timer("texture_load"));
     }
 
+    /// Pixels for the display texture. Images drawn from tiles only need an
+    /// overview here, which also keeps the texture within what the GPU accepts.
+    fn texture_pixels(ctx: &egui::Context, image: &DynamicImage) -> image::RgbaImage {
+        let max_side = ctx.input(|i| i.max_texture_side) as u32;
+        if crate::gpu::tiles::needs_tiling(image.width(), image.height(), max_side) {
+            let side = crate::gpu::tiles::TILING_THRESHOLD.min(max_side);
+            image.thumbnail(side, side).to_rgba8()
+        } else {
+            image.to_rgba8()
+        }
+    }
+
+    /// Split an image too big for one texture into a tile pyramid in the background
+    fn build_tiles_async(&self, path: &std::path::Path, display_image: &DynamicImage) {
+        let path = path.to_path_buf();
+        let image = display_image.clone();
+        self.spawn_loader(move |_tx| {
+            let pyramid = crate::gpu::tiles::TilePyramid::new(image.to_rgba8());
+            Some(super::LoaderMessage::TilesReady(path, Arc::new(pyramid)))
+        });
+    }
+
     fn compute_histogram(&self, display_image: &DynamicImage) -> Vec<Vec<u32>> {
         if let Some(gpu) = &self.gpu_processor {
             match pollster::block_on(async { gpu.compute_histogram(display_image).await }) {
                 Ok(hist) => hist,
                 Err(e) => {
                     log::warn!("GPU histogram failed: {}; falling back to CPU", e);
-                    self.compute_histogram_cpu(display_image)
+                    Self::compute_histogram_cpu(display_image)
                 }
             }
         } else {
-            self.compute_histogram_cpu(display_image)
+            Self::compute_histogram_cpu(display_image)
         }
     }
 
-    fn compute_histogram_cpu(&self, display_image: &DynamicImage) -> Vec<Vec<u32>> {
+    /// RGB and luminance counts, laid out like the GPU histogram
+    fn compute_histogram_cpu(display_image: &DynamicImage) -> Vec<Vec<u32>> {
         // Use concurrent histogram computation for better performance
         let tile_count = crate::task_scheduler::concurrent_histogram::optimal_tile_count(
             display_image.width(),
             display_image.height(),
         );
-        crate::task_scheduler::concurrent_histogram::compute_parallel(display_image, tile_count)
+        let mut histogram = crate::task_scheduler::concurrent_histogram::compute_parallel(
+            display_image,
+            tile_count,
+        );
+        // The fourth channel counts alpha; the panel plots luminance there
+        histogram.truncate(3);
+        histogram.push(image_loader::calculate_luminance_histogram(display_image));
+        histogram
+    }
+
+    /// Recompute the histogram from the preview adjustments while a slider is
+    /// dragged. Runs on a small copy, at most a few times a second and one at
+    /// a time; releasing the slider computes the exact one.
+    pub fn request_live_histogram(&mut self) {
+        const INTERVAL: std::time::Duration = std::time::Duration::from_millis(150);
+        if !self.settings.show_histogram
+            || self.live_histogram_pending
+            || self.live_histogram_time.elapsed() < INTERVAL
+        {
+            return;
+        }
+        let Some(image) = &self.current_image else {
+            return;
+        };
+        let image = image.thumbnail(1024, 1024);
+
+        self.live_histogram_pending = true;
+        self.live_histogram_time = std::time::Instant::now();
+        let hdr = self.settings.hdr_display.clone();
+        let adjustments = (!self.show_original).then(|| self.adjustments.preview());
+        let gpu = self.gpu_processor.clone();
+        self.spawn_loader(move |_| {
+            let display = image_loader::display_referred(&image, &hdr);
+            let hist = match &adjustments {
+                Some(adjustments) => Self::compute_histogram_static(
+                    &image_loader::apply_adjustments(&display, adjustments),
+                    &gpu,
+                ),
+                None => Self::compute_histogram_static(&display, &gpu),
+            };
+            Some(super::LoaderMessage::LiveHistogramUpdated(hist))
+        });
     }
 
     fn generate_overlays_if_needed(&mut self, image: &DynamicImage, ctx: &egui::Context) {
+        // New pixels: the GPU overlays upload this image on first use
+        self.invalidate_overlay_source();
+
         if self.settings.show_focus_peaking {
             self.generate_focus_peaking_overlay(image, ctx);
         }
@@ -374,6 +581,10 @@ impl ImageViewerApp {
             self.generate_zebra_overlay(image, ctx);
         }
 
+        if self.settings.show_clipping {
+            self.generate_clipping_overlay(image, ctx);
+        }
+
         if self.settings.show_custom_overlay {
             self.load_custom_overlay(ctx);
         }
@@ -385,7 +596,11 @@ impl ImageViewerApp {
 
     /// Fast version of set_current_image that skips histogram and overlay generation.
     /// Used during slider dragging for responsive UI.
-    pub fn set_current_image_fast(&mut self, path: &std::path::Path, image: DynamicImage) {
+    pub fn set_current_image_fast(
+        &mut self,
+        path: &std::path::Path,
+        image: impl Into<Arc<DynamicImage>>,
+    ) {
         self.set_current_image_fast_internal(path, image, false);
     }
 
@@ -393,12 +608,15 @@ impl ImageViewerApp {
     pub fn set_current_image_fast_internal(
         &mut self,
         path: &std::path::Path,
-        image: DynamicImage,
+        image: impl Into<Arc<DynamicImage>>,
         compute_histogram: bool,
     ) {
         crate::profiler::with_profiler(|p| p.start_timer("set_current_image_fast_total"));
 
-        self.current_image = Some(image.clone());
+        let image = image.into();
+        self.current_image = Some(Arc::clone(&image));
+        // Tiles of the previous adjustments; the overview stands in until new ones are built
+        self.tiled_image = None;
 
         let display_input = self.prepare_display_image(&image);
         let size = [
@@ -423,10 +641,11 @@ impl ImageViewerApp {
 
     fn prepare_display_image(&mut self, image: &DynamicImage) -> DynamicImage {
         // Fast-path: when dragging, use a downscaled preview
-        let mut display_input = image.clone();
+        let mut display_input =
+            image_loader::display_referred(image, &self.settings.hdr_display).into_owned();
         self.showing_preview = false;
 
-        if self.slider_dragging {
+        if self.slider_dragging || self.drawing_local_mask() {
             let max_preview_dim = 1024u32;
             let max_dim = std::cmp::max(display_input.width(), display_input.height());
             if max_dim > max_preview_dim {
@@ -452,55 +671,58 @@ impl ImageViewerApp {
     }
 
     fn process_image_in_background(
-        &self,
-        _path: &std::path::Path,
+        &mut self,
+        path: &std::path::Path,
         display_input: &DynamicImage,
         texture_name: &str,
         compute_histogram: bool,
     ) {
         let ctx_clone = self.ctx.clone();
         let texture_name_clone = texture_name.to_string();
+        let texture_options = self.texture_options();
         let display_input_clone = display_input.clone();
-        let adjustments_clone = if self.slider_dragging {
-            self.adjustments.preview()
-        } else {
-            self.adjustments.clone()
-        };
+        let adjustments_clone = self.adjustments.clone();
+        let dragging = self.slider_dragging;
         let show_original_clone = self.show_original;
         let gpu_clone = self.gpu_processor.clone();
         let compute_histogram_clone = compute_histogram;
+        let transform = self.display_transform(path);
+        let soft_proof = self.display_soft_proof(path);
+        let path_clone = path.to_path_buf();
 
         self.spawn_loader(move |tx| {
             let start = std::time::Instant::now();
             let adjusted_image = if !adjustments_clone.is_default() && !show_original_clone {
-                image_loader::apply_adjustments(&display_input_clone, &adjustments_clone)
+                let gpu_preview = gpu_clone.as_deref().filter(|_| dragging).and_then(|gpu| {
+                    Self::apply_preview_adjustments_gpu(
+                        gpu,
+                        &display_input_clone,
+                        &adjustments_clone,
+                    )
+                });
+                gpu_preview.unwrap_or_else(|| {
+                    // Without a GPU the film look is too slow to follow a drag
+                    let adjustments = if dragging {
+                        adjustments_clone.preview()
+                    } else {
+                        adjustments_clone.clone()
+                    };
+                    image_loader::apply_adjustments(&display_input_clone, &adjustments)
+                })
             } else {
                 display_input_clone.clone()
             };
             let display_image =
-                if adjustments_clone.frame_enabled && adjustments_clone.frame_thickness > 0.0 {
-                    let img = adjusted_image.to_rgba8();
-                    let (width, height) = img.dimensions();
-                    let thickness = adjustments_clone.frame_thickness as u32;
-                    let new_width = width + 2 * thickness;
-                    let new_height = height + 2 * thickness;
-
-                    let mut framed = ImageBuffer::new(new_width, new_height);
-
-                    let frame_r = (adjustments_clone.frame_color[0] * 255.0) as u8;
-                    let frame_g = (adjustments_clone.frame_color[1] * 255.0) as u8;
-                    let frame_b = (adjustments_clone.frame_color[2] * 255.0) as u8;
-
-                    for pixel in framed.pixels_mut() {
-                        *pixel = Rgba([frame_r, frame_g, frame_b, 255]);
-                    }
-
-                    imageops::overlay(&mut framed, &img, thickness as i64, thickness as i64);
-
-                    DynamicImage::ImageRgba8(framed)
-                } else {
-                    adjusted_image.clone()
-                };
+                image_loader::apply_frame(&adjusted_image, &adjustments_clone, None);
+            let display_image = match (&soft_proof, &transform) {
+                (Some(proof), _) => {
+                    crate::color_management::apply_soft_proof(proof, &display_image)
+                }
+                (None, Some(transform)) => {
+                    crate::color_management::apply_transform(transform, &display_image)
+                }
+                (None, None) => display_image,
+            };
             let elapsed = start.elapsed().as_millis();
             log::debug!(
                 "apply_adjustments_fast worker took {} ms for preview",
@@ -512,7 +734,28 @@ impl ImageViewerApp {
                 let _ = tx.send(super::LoaderMessage::HistogramUpdated(hist));
             }
 
-            Self::create_texture_for_background(&ctx_clone, &texture_name_clone, &display_image, tx)
+            if let Some(ctx) = &ctx_clone {
+                let max_side = ctx.input(|i| i.max_texture_side) as u32;
+                if crate::gpu::tiles::needs_tiling(
+                    display_image.width(),
+                    display_image.height(),
+                    max_side,
+                ) {
+                    let pyramid = crate::gpu::tiles::TilePyramid::new(display_image.to_rgba8());
+                    let _ = tx.send(super::LoaderMessage::TilesReady(
+                        path_clone,
+                        Arc::new(pyramid),
+                    ));
+                }
+            }
+
+            Self::create_texture_for_background(
+                &ctx_clone,
+                &texture_name_clone,
+                texture_options,
+                &display_image,
+                tx,
+            )
         });
     }
 
@@ -525,33 +768,27 @@ impl ImageViewerApp {
                 Ok(h) => h,
                 Err(e) => {
                     log::warn!("GPU histogram failed: {}; falling back to CPU", e);
-                    image_loader::calculate_histogram(display_image)
+                    Self::compute_histogram_cpu(display_image)
                 }
             }
         } else {
-            image_loader::calculate_histogram(display_image)
+            Self::compute_histogram_cpu(display_image)
         }
     }
 
     fn create_texture_for_background(
         ctx: &Option<egui::Context>,
         texture_name: &str,
+        texture_options: egui::TextureOptions,
         display_image: &DynamicImage,
         _tx: &Sender<super::LoaderMessage>,
     ) -> Option<super::LoaderMessage> {
         if let Some(ctx) = ctx {
-            let rgba = display_image.to_rgba8();
+            let rgba = Self::texture_pixels(ctx, display_image);
             let pixels = rgba.as_flat_samples();
-            let size = [
-                display_image.width() as usize,
-                display_image.height() as usize,
-            ];
+            let size = [rgba.width() as usize, rgba.height() as usize];
             let color_image = egui::ColorImage::from_rgba_unmultiplied(size, pixels.as_slice());
-            let texture = ctx.load_texture(
-                texture_name.to_string(),
-                color_image,
-                egui::TextureOptions::LINEAR,
-            );
+            let texture = ctx.load_texture(texture_name.to_string(), color_image, texture_options);
             Some(super::LoaderMessage::TextureCreated(
                 PathBuf::from(texture_name),
                 texture,
@@ -562,37 +799,55 @@ impl ImageViewerApp {
         }
     }
 
+    /// Images within the preload distance of the current one, nearest first
+    fn adjacent_paths(&self) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        for i in 1..=self.settings.preload_adjacent {
+            let after = self.current_index + i;
+            let before = self.current_index.checked_sub(i);
+            for display_idx in std::iter::once(after).chain(before) {
+                if let Some(path) = self
+                    .filtered_list
+                    .get(display_idx)
+                    .and_then(|&idx| self.image_list.get(idx))
+                {
+                    paths.push(path.clone());
+                }
+            }
+        }
+        paths
+    }
+
+    /// Drop queued decodes for images navigation has moved away from, so
+    /// skimming through a folder doesn't leave the workers busy behind it
+    fn cancel_stale_loads(&self, current: &Path) {
+        use crate::task_scheduler::ImageTask;
+
+        let adjacent = self.adjacent_paths();
+        let wanted = |path: &PathBuf| {
+            path == current || adjacent.contains(path) || self.compare_requests.contains(path)
+        };
+        let cancelled = self.task_scheduler.cancel_where(|task| match task {
+            ImageTask::LoadImage { path, .. } => !wanted(path),
+            // RAW previews for neighbours; the strip's own thumbnails are tracked separately
+            ImageTask::LoadThumbnail { path, .. } => {
+                !wanted(path) && !self.thumbnail_requests.contains(path)
+            }
+            _ => false,
+        });
+        if cancelled > 0 {
+            log::debug!("Cancelled {} stale image loads", cancelled);
+        }
+    }
+
     fn preload_adjacent(&self) {
-        let count = self.settings.preload_adjacent;
         let mut full_paths = Vec::new();
         let mut thumb_paths = Vec::new();
-
-        for i in 1..=count {
-            if self.current_index + i < self.filtered_list.len() {
-                if let Some(&idx) = self.filtered_list.get(self.current_index + i) {
-                    if let Some(path) = self.image_list.get(idx) {
-                        if crate::image_loader::is_raw_file(path)
-                            && !self.settings.load_raw_full_size
-                        {
-                            thumb_paths.push(path.clone());
-                        } else {
-                            full_paths.push(path.clone());
-                        }
-                    }
-                }
-            }
-            if self.current_index >= i {
-                if let Some(&idx) = self.filtered_list.get(self.current_index - i) {
-                    if let Some(path) = self.image_list.get(idx) {
-                        if crate::image_loader::is_raw_file(path)
-                            && !self.settings.load_raw_full_size
-                        {
-                            thumb_paths.push(path.clone());
-                        } else {
-                            full_paths.push(path.clone());
-                        }
-                    }
-                }
+        for path in self.adjacent_paths() {
+            if crate::image_loader::is_raw_file(&path) && !self.settings.load_raw_full_size {
+                thumb_paths.push(path);
+            } else {
+                full_paths.push(path);
             }
         }
 
@@ -608,7 +863,7 @@ impl ImageViewerApp {
             self.task_scheduler.submit_task(crate::task_scheduler::ImageTask::LoadThumbnail {
                 path,
                 size: 1920,
-                priority: crate::task_scheduler::TaskPriority::Low,
+                priority: crate::task_scheduler::TaskPriority::Medium,
             });
         }
     }
@@ -630,6 +885,7 @@ impl ImageViewerApp {
             // Send result through task scheduler for consistency
             let result = crate::task_scheduler::TaskResult::ThumbnailLoaded {
                 path: path.clone(),
+                exposure: crate::image_loader::exposure_warning(&img),
                 image: img,
             };
             // Since we're on the main thread, we need to handle this directly
@@ -680,10 +936,21 @@ impl ImageViewerApp {
                     self.pending_fit_to_window = true;
                 } else {
                     self.image_cache.insert(path.clone(), image.clone());
+                    if self.compare_requests.contains(&path) {
+                        self.create_compare_texture(path, image);
+                    }
                 }
             }
-            crate::task_scheduler::TaskResult::ThumbnailLoaded { path, image } => {
+            crate::task_scheduler::TaskResult::ThumbnailLoaded {
+                path,
+                image,
+                exposure,
+            } => {
                 crate::profiler::with_profiler(|p| p.increment_counter("thumbnails_loaded"));
+                match exposure {
+                    Some(warning) => self.thumbnail_exposure.insert(path.clone(), warning),
+                    None => self.thumbnail_exposure.remove(&path),
+                };
 
                 // Apply adjustments to thumbnail if any exist for this image
                 let display_thumb = if let Some(adj) = self.metadata_db.get_adjustments(&path) {
@@ -724,7 +991,7 @@ impl ImageViewerApp {
                 self.histogram_data = Some(histogram);
             }
             crate::task_scheduler::TaskResult::AdjustmentsApplied { image } => {
-                self.current_image = Some(image);
+                self.current_image = Some(Arc::new(image));
                 self.is_loading = false;
             }
             crate::task_scheduler::TaskResult::Error { task, error } => {
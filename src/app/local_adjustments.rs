@@ -0,0 +1,143 @@
+use super::ImageViewerApp;
+use crate::image_loader::{LocalAdjustment, LocalMask, LocalMaskKind, MAX_LOCAL_ADJUSTMENTS};
+
+impl ImageViewerApp {
+    /// Arm the mask tool so the next drag on the image places a mask, or
+    /// disarm it if `kind` is already armed
+    pub fn toggle_local_mask_tool(&mut self, kind: LocalMaskKind) {
+        if self.local_mask_tool == Some(kind) {
+            self.local_mask_tool = None;
+            self.show_status(if kind == LocalMaskKind::Brush {
+                "Brush disabled"
+            } else {
+                "Gradient tool disabled"
+            });
+            return;
+        }
+        let adds_mask = kind != LocalMaskKind::Brush || !self.selected_brush_mask();
+        if adds_mask && self.adjustments.local.len() >= MAX_LOCAL_ADJUSTMENTS {
+            self.show_status(&format!(
+                "At most {} local adjustments per image",
+                MAX_LOCAL_ADJUSTMENTS
+            ));
+            return;
+        }
+
+        // Crop, straighten and the gradient tools all use drag input
+        self.local_mask_tool = Some(kind);
+        self.crop_mode = false;
+        self.crop_rect = None;
+        self.crop_start_pos = None;
+        self.straighten_mode = false;
+        self.straighten_line = None;
+        self.show_status(match kind {
+            LocalMaskKind::Radial => "Radial gradient: drag out from the centre",
+            LocalMaskKind::Linear => "Linear gradient: drag from full effect to none",
+            LocalMaskKind::Brush => "Brush: paint over the area to adjust",
+        });
+    }
+
+    /// Whether the selected local adjustment is painted, so brush strokes
+    /// add to it
+    fn selected_brush_mask(&self) -> bool {
+        self.selected_local_adjustment
+            .and_then(|i| self.adjustments.local.get(i))
+            .is_some_and(|local| local.mask.is_brush())
+    }
+
+    /// Width over height of the adjusted image, which masks are placed on
+    pub fn local_mask_aspect(&self) -> f32 {
+        self.current_image.as_ref().map_or(1.0, |image| {
+            image.width() as f32 / image.height().max(1) as f32
+        })
+    }
+
+    /// Whether a mask is being drawn. The image is previewed at proxy size
+    /// until the drag ends, when it's rendered in full once.
+    pub fn drawing_local_mask(&self) -> bool {
+        self.local_mask_drag.is_some()
+    }
+
+    /// Start drawing a mask at image-relative `start`. Brush strokes add to
+    /// the selected brush mask, so an area can be built up stroke by stroke.
+    pub fn begin_local_mask(&mut self, start: [f32; 2]) {
+        let Some(kind) = self.local_mask_tool else {
+            return;
+        };
+        let previous = self.adjustments.clone();
+        if kind != LocalMaskKind::Brush || !self.selected_brush_mask() {
+            if self.adjustments.local.len() >= MAX_LOCAL_ADJUSTMENTS {
+                self.local_mask_tool = None;
+                return;
+            }
+            let mask = LocalMask::from_drag(kind, start, start, self.local_mask_aspect());
+            self.adjustments.local.push(LocalAdjustment {
+                mask,
+                // Start with a visible change so the mask shows up while drawing
+                exposure: 0.5,
+                ..Default::default()
+            });
+            self.selected_local_adjustment = Some(self.adjustments.local.len() - 1);
+        }
+        self.local_mask_drag = Some((start, previous));
+        self.paint_brush(start, false);
+        self.mark_adjustments_dirty();
+    }
+
+    pub fn update_local_mask(&mut self, end: [f32; 2]) {
+        let (Some(kind), Some((start, _))) = (self.local_mask_tool, &self.local_mask_drag) else {
+            return;
+        };
+        if kind == LocalMaskKind::Brush {
+            self.paint_brush(end, true);
+            return;
+        }
+        let mask = LocalMask::from_drag(kind, *start, end, self.local_mask_aspect());
+        if let Some(local) = self.adjustments.local.last_mut() {
+            local.mask = mask;
+            self.mark_adjustments_dirty();
+        }
+    }
+
+    /// Paint a dab of the brush at image-relative `at` into the selected mask
+    fn paint_brush(&mut self, at: [f32; 2], connect: bool) {
+        let aspect = self.local_mask_aspect();
+        let dab = self.settings.brush.dab(at, self.pen_pressure);
+        let Some(local) = self
+            .selected_local_adjustment
+            .and_then(|i| self.adjustments.local.get_mut(i))
+        else {
+            return;
+        };
+        if local.mask.paint(dab, connect, aspect) {
+            self.mark_adjustments_dirty();
+        }
+    }
+
+    pub fn finish_local_mask(&mut self) {
+        let Some((_, previous)) = self.local_mask_drag.take() else {
+            return;
+        };
+        // The brush stays armed for the next stroke
+        if self.local_mask_tool != Some(LocalMaskKind::Brush) {
+            self.local_mask_tool = None;
+        }
+        // Replaces the pending proxy preview with the full render
+        self.adjustments_dirty = false;
+        self.commit_adjustment_change(previous);
+    }
+
+    pub fn delete_local_adjustment(&mut self, index: usize) {
+        if index >= self.adjustments.local.len() {
+            return;
+        }
+        let previous = self.adjustments.clone();
+        self.adjustments.local.remove(index);
+        self.selected_local_adjustment = match self.selected_local_adjustment {
+            Some(selected) if selected == index => None,
+            Some(selected) if selected > index => Some(selected - 1),
+            selected => selected,
+        };
+        self.commit_adjustment_change(previous);
+    }
+}
@@ -0,0 +1,96 @@
+use super::ImageViewerApp;
+use egui::{Vec2, ViewportCommand};
+
+/// Longest side of the mini viewer window when it opens
+const MINI_LONG_SIDE: f32 = 480.0;
+
+/// Smallest the mini viewer can be resized to
+const MINI_MIN_SIZE: [f32; 2] = [160.0, 120.0];
+
+/// The window as it was before turning into the mini viewer
+pub struct MiniViewer {
+    maximized: bool,
+    fullscreen: bool,
+    size: Option<Vec2>,
+    position: Option<egui::Pos2>,
+    /// View size the image was last fitted to
+    view_size: Vec2,
+}
+
+/// Window size showing an image of `image` size with its longest side at
+/// `long_side`, never below the smallest mini viewer
+pub fn mini_window_size(image: Vec2, long_side: f32) -> Vec2 {
+    if image.x <= 0.0 || image.y <= 0.0 {
+        return egui::vec2(long_side, long_side * 0.75);
+    }
+    let size = image * (long_side / image.x.max(image.y));
+    size.max(Vec2::from(MINI_MIN_SIZE))
+}
+
+impl ImageViewerApp {
+    /// Shrink to a frameless always-on-top window showing just the image, or
+    /// go back to the window as it was
+    pub fn toggle_mini_viewer(&mut self) {
+        let Some(ctx) = self.ctx.clone() else {
+            return;
+        };
+        if let Some(saved) = self.mini_viewer.take() {
+            ctx.send_viewport_cmd(ViewportCommand::Decorations(true));
+            ctx.send_viewport_cmd(ViewportCommand::WindowLevel(egui::WindowLevel::Normal));
+            ctx.send_viewport_cmd(ViewportCommand::MinInnerSize(Vec2::from(
+                crate::MIN_WINDOW_SIZE,
+            )));
+            if let Some(size) = saved.size {
+                ctx.send_viewport_cmd(ViewportCommand::InnerSize(size));
+            }
+            if let Some(position) = saved.position {
+                ctx.send_viewport_cmd(ViewportCommand::OuterPosition(position));
+            }
+            if saved.maximized {
+                ctx.send_viewport_cmd(ViewportCommand::Maximized(true));
+            }
+            if saved.fullscreen {
+                self.set_fullscreen(true);
+            }
+            self.pending_fit_to_window = true;
+            return;
+        }
+
+        let viewport = ctx.input(|i| i.viewport().clone());
+        self.mini_viewer = Some(MiniViewer {
+            maximized: viewport.maximized.unwrap_or(false),
+            fullscreen: self.is_fullscreen,
+            size: viewport.inner_rect.map(|rect| rect.size()),
+            position: viewport.outer_rect.map(|rect| rect.min),
+            view_size: Vec2::ZERO,
+        });
+        if self.is_fullscreen {
+            self.set_fullscreen(false);
+        }
+        let image = self
+            .current_texture
+            .as_ref()
+            .map_or(Vec2::ZERO, |texture| texture.size_vec2());
+        ctx.send_viewport_cmd(ViewportCommand::Maximized(false));
+        ctx.send_viewport_cmd(ViewportCommand::Decorations(false));
+        ctx.send_viewport_cmd(ViewportCommand::WindowLevel(egui::WindowLevel::AlwaysOnTop));
+        ctx.send_viewport_cmd(ViewportCommand::MinInnerSize(Vec2::from(MINI_MIN_SIZE)));
+        ctx.send_viewport_cmd(ViewportCommand::InnerSize(mini_window_size(
+            image,
+            MINI_LONG_SIDE,
+        )));
+        self.show_status("Mini viewer on top (Esc to leave)");
+    }
+
+    /// Keep the image fitted while the mini viewer is resized
+    pub fn update_mini_viewer(&mut self) {
+        let view_size = self.available_view_size;
+        let Some(mini) = &mut self.mini_viewer else {
+            return;
+        };
+        if mini.view_size != view_size {
+            mini.view_size = view_size;
+            self.fit_to_window_internal();
+        }
+    }
+}
@@ -1,11 +1,54 @@
+pub mod actions;
+pub mod all_metadata;
+pub mod animation;
+pub mod auto_enhance;
+pub mod batch_export;
+pub mod batch_metadata;
 pub mod cache;
+pub mod collections;
+pub mod compare;
+pub mod contact_sheet;
+pub mod display_proxy;
+pub mod drag_out;
 pub mod editing;
+pub mod export_preview;
 pub mod file_ops;
+pub mod film_stocks;
+pub mod folder_scan;
+pub mod fullscreen;
 pub mod image_loading;
+pub mod local_adjustments;
+pub mod mini_viewer;
+pub mod music;
 pub mod navigation;
 pub mod overlays;
+pub mod pasted_images;
+pub mod presets;
+pub mod ratings;
+pub mod raw_pairs;
+pub mod recently_deleted;
+pub mod reference_overlay;
+pub mod remote_control;
+pub mod rename;
+pub mod scopes;
+pub mod scripts;
+pub mod scroll_strip;
+pub mod share;
+pub mod shoot_summary;
+pub mod slideshow;
+pub mod soft_proof;
 pub mod sorting;
 pub mod state;
+pub mod tabs;
+pub mod tether;
+pub mod timeline;
+pub mod tray;
+pub mod url_open;
+pub mod video;
+pub mod watched_folders;
+pub mod watcher;
+pub mod web_gallery;
+pub mod workspace;
 pub mod zoom;
 
 pub use state::*;
@@ -0,0 +1,81 @@
+use super::{ImageViewerApp, LoaderMessage};
+use crate::music::{MusicPlayer, MusicTrack, AUDIO_EXTENSIONS};
+use std::path::PathBuf;
+
+impl ImageViewerApp {
+    pub fn start_music(&mut self) {
+        let music = &self.settings.music;
+        if music.tracks.is_empty() {
+            return;
+        }
+        let tracks = music.tracks.iter().map(|t| t.path.clone()).collect();
+        match MusicPlayer::start(tracks, music.output_volume()) {
+            Ok(player) => self.music = Some(player),
+            Err(e) => self.show_status(&format!("Couldn't play slideshow music: {}", e)),
+        }
+    }
+
+    /// Position in the playing track and seconds per slide, when the music
+    /// sets the slideshow's pace
+    pub fn music_timing(&self, interval: std::time::Duration) -> Option<(f32, f32)> {
+        let player = self.music.as_ref()?;
+        let seconds = self
+            .settings
+            .music
+            .slide_seconds(player.current_track(), interval.as_secs_f32())?;
+        Some((player.position(), seconds))
+    }
+
+    pub fn apply_music_volume(&mut self) {
+        if let Some(player) = &self.music {
+            player.set_volume(self.settings.music.output_volume());
+        }
+    }
+
+    pub fn toggle_music_mute(&mut self) {
+        self.settings.music.muted = !self.settings.music.muted;
+        self.apply_music_volume();
+    }
+
+    /// Ask for audio files to add to the slideshow music, estimating their tempo
+    pub fn add_music_tracks_dialog(&mut self) {
+        let Some(paths) = rfd::FileDialog::new()
+            .set_title("Add slideshow music")
+            .add_filter("Audio", AUDIO_EXTENSIONS)
+            .pick_files()
+        else {
+            return;
+        };
+        let tracks = &mut self.settings.music.tracks;
+        let added: Vec<PathBuf> = paths
+            .into_iter()
+            .filter(|path| !tracks.iter().any(|t| &t.path == path))
+            .collect();
+        tracks.extend(added.iter().cloned().map(MusicTrack::new));
+        self.settings.music.enabled = true;
+
+        self.spawn_long_job(move |tx| {
+            for path in added {
+                let bpm = crate::music::analyze_track(&path).unwrap_or_else(|e| {
+                    log::warn!("Couldn't estimate the tempo of {}: {}", path.display(), e);
+                    None
+                });
+                let _ = tx.send(LoaderMessage::MusicTempo(path, bpm));
+            }
+            None
+        });
+    }
+
+    pub fn handle_music_tempo(&mut self, path: PathBuf, bpm: Option<f32>) {
+        // Leave tempos typed in while the track was being listened to
+        if let Some(track) = self
+            .settings
+            .music
+            .tracks
+            .iter_mut()
+            .find(|t| t.path == path && t.bpm.is_none())
+        {
+            track.bpm = bpm;
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use super::ImageViewerApp;
 use crate::image_loader::ImageAdjustments;
+use eframe::egui;
 use std::path::PathBuf;
 
 impl ImageViewerApp {
@@ -10,6 +11,38 @@ impl ImageViewerApp {
             .cloned()
     }
 
+    /// Paths of the thumbnails selected in the filmstrip, in list order
+    pub fn selected_paths(&self) -> Vec<PathBuf> {
+        let mut indices: Vec<usize> = self.selected_indices.iter().copied().collect();
+        indices.sort_unstable();
+        indices
+            .into_iter()
+            .filter_map(|i| self.filtered_list.get(i))
+            .filter_map(|&idx| self.image_list.get(idx).cloned())
+            .collect()
+    }
+
+    /// The selection, or every image shown when nothing is selected
+    pub fn selected_or_filtered_paths(&self) -> Vec<PathBuf> {
+        let selected = self.selected_paths();
+        if !selected.is_empty() {
+            return selected;
+        }
+        self.filtered_list
+            .iter()
+            .filter_map(|&idx| self.image_list.get(idx).cloned())
+            .collect()
+    }
+
+    /// The selection, or the image being viewed when nothing is selected
+    pub fn selected_or_current_paths(&self) -> Vec<PathBuf> {
+        let selected = self.selected_paths();
+        if !selected.is_empty() {
+            return selected;
+        }
+        self.get_current_path().into_iter().collect()
+    }
+
     // Navigation
     pub fn next_image(&mut self) {
         if self.filtered_list.is_empty() {
@@ -50,17 +83,11 @@ impl ImageViewerApp {
             return;
         }
 
-        // Save current adjustments before navigating
-        self.save_current_adjustments();
-
         let saved_zoom = self.zoom;
         let saved_pan = self.pan_offset;
 
         self.current_index = index;
 
-        // Load adjustments for the new image
-        self.load_adjustments_for_current();
-
         self.load_current_image();
 
         if self.settings.maintain_zoom_on_navigate {
@@ -77,17 +104,176 @@ impl ImageViewerApp {
         self.navigate_to_index(index);
     }
 
-    /// Save current image's adjustments to metadata database
-    pub fn save_current_adjustments(&mut self) {
+    /// Show an image from the recent images list, opening its folder if needed
+    pub fn open_recent_image(&mut self, path: PathBuf) {
+        if !path.is_file() {
+            self.settings.recent_images.retain(|r| r.path != path);
+            self.show_status(&format!("{} is no longer on disk", path.display()));
+            return;
+        }
+        let listed = self
+            .filtered_list
+            .iter()
+            .position(|&i| self.image_list.get(i) == Some(&path));
+        match listed {
+            Some(index) => self.go_to_index(index),
+            None => self.load_image_file(path),
+        }
+    }
+
+    pub fn start_slideshow(&mut self, seconds: f32) {
+        self.slideshow_interval = Some(std::time::Duration::from_secs_f32(seconds));
+        self.slideshow_advanced = std::time::Instant::now();
+        self.slideshow_held = false;
+        self.music_slot = None;
         if let Some(path) = self.get_current_path() {
-            self.metadata_db.set_adjustments(path, &self.adjustments);
-            self.metadata_db.save();
+            self.slideshow_shown.insert(path);
+        }
+        if self.settings.music.enabled && self.music.is_none() {
+            self.start_music();
+        }
+    }
+
+    pub fn stop_slideshow(&mut self) {
+        self.slideshow_interval = None;
+        self.slideshow_held = false;
+        self.music = None;
+    }
+
+    /// Show the next slide now and restart its time, without stopping the slideshow
+    pub fn skip_slide(&mut self) {
+        if self.slideshow_interval.is_none() {
+            self.next_image();
+            return;
+        }
+        self.next_slide();
+        self.slideshow_advanced = std::time::Instant::now();
+    }
+
+    /// Stay on the current image until held again, without stopping the slideshow
+    pub fn toggle_slideshow_hold(&mut self) {
+        if self.slideshow_interval.is_none() {
+            return;
+        }
+        self.slideshow_held = !self.slideshow_held;
+        self.slideshow_advanced = std::time::Instant::now();
+        self.show_status(if self.slideshow_held {
+            "Slideshow held"
+        } else {
+            "Slideshow resumed"
+        });
+    }
+
+    /// Move to the slide after the current one in the slideshow order
+    fn next_slide(&mut self) {
+        let count = self.filtered_list.len();
+        if count == 0 {
+            return;
+        }
+        let path_at = |index: usize| {
+            self.filtered_list
+                .get(index)
+                .and_then(|&i| self.image_list.get(i))
+        };
+        let (next, new_round) = crate::slideshow::next_slide(
+            self.settings.slideshow_order,
+            self.current_index,
+            count,
+            |index| path_at(index).is_some_and(|path| self.slideshow_shown.contains(path)),
+            &mut rand::thread_rng(),
+        );
+        if new_round {
+            self.slideshow_shown.clear();
+        }
+        if let Some(path) = path_at(next).cloned() {
+            self.slideshow_shown.insert(path);
+        }
+        self.navigate_to_index(next);
+    }
+
+    /// Start the slideshow at the usual interval, or stop it
+    pub fn toggle_slideshow(&mut self) {
+        if self.slideshow_interval.is_some() {
+            self.stop_slideshow();
+        } else {
+            self.start_slideshow(crate::cli::DEFAULT_SLIDESHOW_SECONDS);
+        }
+    }
+
+    /// Move to the next image once the slideshow interval has passed, looping
+    /// at the end of the folder
+    pub fn advance_slideshow(&mut self, ctx: &egui::Context) {
+        let Some(interval) = self.slideshow_interval else {
+            return;
+        };
+        if let Some(music) = &mut self.music {
+            music.update();
+        }
+        // Wait for the folder and the shown image before counting
+        if self.filtered_list.is_empty() || self.is_loading || self.slideshow_held {
+            self.slideshow_advanced = std::time::Instant::now();
+            if self.slideshow_held {
+                // Resume from the music's next slide rather than catching up
+                self.music_slot = None;
+            }
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+            return;
+        }
+        // Follow the music's clock so slides change on the beat
+        if let Some((position, seconds)) = self.music_timing(interval) {
+            let slot = (position / seconds) as u64;
+            if self.music_slot.is_some_and(|shown| shown != slot) {
+                self.next_slide();
+            }
+            self.music_slot = Some(slot);
+            let until = (slot + 1) as f32 * seconds - position;
+            ctx.request_repaint_after(std::time::Duration::from_secs_f32(until.max(0.01)));
+            return;
+        }
+        let elapsed = self.slideshow_advanced.elapsed();
+        if elapsed >= interval {
+            self.next_slide();
+            self.slideshow_advanced = std::time::Instant::now();
+            ctx.request_repaint_after(interval);
+        } else {
+            ctx.request_repaint_after(interval - elapsed);
+        }
+    }
+
+    /// Save the in-memory adjustments to the metadata database for the image they belong to
+    pub fn save_current_adjustments(&mut self) {
+        if let Some(path) = self.adjustments_path.clone() {
+            // Don't leave entries behind for files that were deleted or moved away
+            if path.exists() {
+                let stored = self.metadata_db.get_adjustments(&path).unwrap_or_default();
+                if stored != self.adjustments {
+                    // Regenerate the thumbnail with edits that weren't committed yet
+                    self.thumbnail_textures.remove(&path);
+                    self.thumbnail_requests.remove(&path);
+                }
+                self.metadata_db.set_adjustments(path, &self.adjustments);
+                self.metadata_db.save();
+            }
+        }
+    }
+
+    /// Make the in-memory adjustments match the current image, saving the previous
+    /// image's edits first. Called from `load_current_image`, so every way of changing
+    /// the current image keeps per-image adjustments.
+    pub fn sync_adjustments_for_current(&mut self) {
+        let current = self.get_current_path();
+        if current.is_some() && current != self.adjustments_path {
+            self.save_current_adjustments();
+            self.load_adjustments_for_current();
         }
     }
 
     /// Load adjustments for the current image from metadata database
     pub fn load_adjustments_for_current(&mut self) {
         if let Some(path) = self.get_current_path() {
+            self.adjustments_path = Some(path.clone());
+            self.selected_local_adjustment = None;
+            self.local_mask_drag = None;
             if let Some(adjustments) = self.metadata_db.get_adjustments(&path) {
                 self.adjustments = adjustments;
                 // Also update the film preset if film is enabled
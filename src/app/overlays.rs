@@ -1,87 +1,170 @@
 use super::ImageViewerApp;
+use crate::gpu::overlays::{GpuOverlay, OverlayRequest};
 use crate::image_loader;
-use image::DynamicImage;
+use egui::TextureHandle;
+use image::{DynamicImage, RgbaImage};
+
+/// A focus peaking or zebra overlay: computed into a GPU texture when eframe
+/// runs on wgpu, otherwise generated on the CPU and uploaded
+pub enum OverlayTexture {
+    Cpu(TextureHandle),
+    Gpu(egui::TextureId),
+}
+
+impl OverlayTexture {
+    pub fn id(&self) -> egui::TextureId {
+        match self {
+            OverlayTexture::Cpu(texture) => texture.id(),
+            OverlayTexture::Gpu(id) => *id,
+        }
+    }
+}
+
+fn load_overlay_texture(ctx: &egui::Context, name: &str, overlay: &RgbaImage) -> TextureHandle {
+    let size = [overlay.width() as usize, overlay.height() as usize];
+    ctx.load_texture(
+        name,
+        egui::ColorImage::from_rgba_unmultiplied(size, overlay.as_raw()),
+        egui::TextureOptions::LINEAR,
+    )
+}
 
 impl ImageViewerApp {
     pub fn generate_focus_peaking_overlay(&mut self, image: &DynamicImage, ctx: &egui::Context) {
-        let overlay = if let Some(gpu) = &self.gpu_processor {
-            match pollster::block_on(async {
-                // Normalize threshold for GPU: the shader works with normalized luminance (0.0-1.0)
-                // and Sobel edge detection on normalized values produces edge strengths roughly in 0.0-1.0 range.
-                // The CPU version uses raw pixel values (0-255), so threshold of 50 means magnitude ~50.
-                // For GPU, we need to convert: threshold / 255.0 gives us a comparable normalized threshold.
-                let normalized_threshold = self.settings.focus_peaking_threshold / 255.0;
-                gpu.generate_focus_peaking_overlay(image, normalized_threshold)
-                    .await
-            }) {
-                Ok(overlay) => overlay,
-                Err(e) => {
-                    log::warn!("GPU focus peaking failed: {}; falling back to CPU", e);
-                    DynamicImage::ImageRgba8(image_loader::generate_focus_peaking_overlay(
-                        image,
-                        self.settings.focus_peaking_threshold,
-                    ))
-                }
-            }
-        } else {
-            DynamicImage::ImageRgba8(image_loader::generate_focus_peaking_overlay(
-                image,
-                self.settings.focus_peaking_threshold,
-            ))
+        // The shader works on normalized luminance, so Sobel magnitudes are 1/255 of the CPU ones
+        let request = OverlayRequest {
+            overlay: GpuOverlay::FocusPeaking,
+            threshold: self.settings.focus_peaking_threshold / 255.0,
+            high_threshold: 0.0,
+            low_threshold: 0.0,
+            color: self.settings.focus_peaking_color.to_color(),
         };
+        if let Some(texture) = self.render_gpu_overlay(image, &request) {
+            self.focus_peaking_texture = Some(texture);
+            return;
+        }
 
-        let size = [overlay.width() as usize, overlay.height() as usize];
-        let rgba = overlay.to_rgba8();
-        let pixels: Vec<u8> = rgba.into_raw();
-
-        let texture = ctx.load_texture(
-            "focus_peaking",
-            egui::ColorImage::from_rgba_unmultiplied(size, &pixels),
-            egui::TextureOptions::LINEAR,
+        let overlay = image_loader::generate_focus_peaking_overlay(
+            image,
+            self.settings.focus_peaking_threshold,
+            request.color.to_array(),
         );
-
-        self.focus_peaking_texture = Some(texture);
+        self.focus_peaking_texture = Some(OverlayTexture::Cpu(load_overlay_texture(
+            ctx,
+            "focus_peaking",
+            &overlay,
+        )));
     }
 
     pub fn generate_zebra_overlay(&mut self, image: &DynamicImage, ctx: &egui::Context) {
-        let overlay = if let Some(gpu) = &self.gpu_processor {
-            match pollster::block_on(async {
-                gpu.generate_zebra_overlay(
-                    image,
-                    self.settings.zebra_high_threshold as f32 / 255.0,
-                    self.settings.zebra_low_threshold as f32 / 255.0,
-                )
-                .await
-            }) {
-                Ok(overlay) => overlay,
-                Err(e) => {
-                    log::warn!("GPU zebra overlay failed: {}; falling back to CPU", e);
-                    DynamicImage::ImageRgba8(image_loader::generate_zebra_overlay(
-                        image,
-                        self.settings.zebra_high_threshold,
-                        self.settings.zebra_low_threshold,
-                    ))
-                }
-            }
-        } else {
-            DynamicImage::ImageRgba8(image_loader::generate_zebra_overlay(
-                image,
-                self.settings.zebra_high_threshold,
-                self.settings.zebra_low_threshold,
-            ))
+        let request = OverlayRequest {
+            overlay: GpuOverlay::Zebra,
+            threshold: 0.0,
+            high_threshold: self.settings.zebra_high_threshold as f32 / 255.0,
+            low_threshold: self.settings.zebra_low_threshold as f32 / 255.0,
+            color: egui::Color32::RED,
         };
+        if let Some(texture) = self.render_gpu_overlay(image, &request) {
+            self.zebra_texture = Some(texture);
+            return;
+        }
+
+        let overlay = image_loader::generate_zebra_overlay(
+            image,
+            self.settings.zebra_high_threshold,
+            self.settings.zebra_low_threshold,
+        );
+        self.zebra_texture = Some(OverlayTexture::Cpu(load_overlay_texture(
+            ctx, "zebra", &overlay,
+        )));
+    }
 
+    /// Compute an overlay on the display device. The image is only uploaded
+    /// when the overlays have no source yet for the current image.
+    fn render_gpu_overlay(
+        &mut self,
+        image: &DynamicImage,
+        request: &OverlayRequest,
+    ) -> Option<OverlayTexture> {
+        let renderer = self.gpu_overlays.as_mut()?;
+        if !renderer.has_source() {
+            renderer.set_source(image);
+        }
+        renderer.render(request).map(OverlayTexture::Gpu)
+    }
+
+    /// Forget the uploaded overlay source once the image or its adjustments change
+    pub fn invalidate_overlay_source(&mut self) {
+        if let Some(renderer) = &mut self.gpu_overlays {
+            renderer.clear_source();
+        }
+    }
+
+    pub fn generate_clipping_overlay(&mut self, image: &DynamicImage, ctx: &egui::Context) {
+        let overlay = image_loader::generate_clipping_overlay(image, &self.settings.clipping);
         let size = [overlay.width() as usize, overlay.height() as usize];
-        let rgba = overlay.to_rgba8();
-        let pixels: Vec<u8> = rgba.into_raw();
 
         let texture = ctx.load_texture(
-            "zebra",
-            egui::ColorImage::from_rgba_unmultiplied(size, &pixels),
-            egui::TextureOptions::LINEAR,
+            "clipping",
+            egui::ColorImage::from_rgba_unmultiplied(size, overlay.as_raw()),
+            egui::TextureOptions::NEAREST,
         );
 
-        self.zebra_texture = Some(texture);
+        self.clipping_texture = Some(texture);
+    }
+
+    pub fn toggle_focus_peaking(&mut self) {
+        self.settings.show_focus_peaking = !self.settings.show_focus_peaking;
+        if self.refresh_display_pass() || !self.settings.show_focus_peaking {
+            return;
+        }
+        if let (Some(image), Some(ctx)) = (self.current_image.clone(), self.ctx.clone()) {
+            self.generate_focus_peaking_overlay(&image, &ctx);
+        }
+    }
+
+    pub fn toggle_zebras(&mut self) {
+        self.settings.show_zebras = !self.settings.show_zebras;
+        if self.refresh_display_pass() || !self.settings.show_zebras {
+            return;
+        }
+        if let (Some(image), Some(ctx)) = (self.current_image.clone(), self.ctx.clone()) {
+            self.generate_zebra_overlay(&image, &ctx);
+        }
+    }
+
+    pub fn toggle_loupe(&mut self) {
+        self.settings.loupe_enabled = !self.settings.loupe_enabled;
+        self.refresh_display_pass();
+    }
+
+    pub fn toggle_clipping_warning(&mut self) {
+        self.settings.show_clipping = !self.settings.show_clipping;
+        if !self.refresh_display_pass() {
+            self.refresh_clipping_warning();
+        }
+    }
+
+    /// The display pass draws adjustments only while no overlay needs them in
+    /// the texture, so render the image again when one is turned on or off.
+    /// Returns whether it did, which regenerates the overlays too.
+    fn refresh_display_pass(&mut self) -> bool {
+        if self.gpu_display_max_dim.is_none() || self.adjustments.is_default() {
+            return false;
+        }
+        self.refresh_adjustments();
+        true
+    }
+
+    /// Rebuild the clipping warning after it was turned on or its thresholds changed
+    pub fn refresh_clipping_warning(&mut self) {
+        self.clipping_texture = None;
+        if !self.settings.show_clipping {
+            return;
+        }
+        if let (Some(image), Some(ctx)) = (self.current_image.clone(), self.ctx.clone()) {
+            self.generate_clipping_overlay(&image, &ctx);
+        }
     }
 
     pub fn load_custom_overlay(&mut self, ctx: &egui::Context) {
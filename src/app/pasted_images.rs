@@ -0,0 +1,110 @@
+use super::file_ops::{save_exported_image, DEFAULT_JPEG_QUALITY};
+use super::ImageViewerApp;
+use crate::settings::ExportFormat;
+use chrono::{DateTime, Local};
+use image::DynamicImage;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Folder pasted images are listed under. Nothing is written there; the
+/// images only live in memory until saved.
+pub fn pasted_folder() -> PathBuf {
+    std::env::temp_dir().join("Pasted images")
+}
+
+/// Name for an image pasted at `now`, numbered when one was already pasted
+/// that second
+pub fn pasted_image_path(now: DateTime<Local>, pasted: &HashMap<PathBuf, DynamicImage>) -> PathBuf {
+    let stem = format!("Pasted {}", now.format("%Y-%m-%d %H-%M-%S"));
+    let folder = pasted_folder();
+    let mut path = folder.join(format!("{}.png", stem));
+    let mut n = 2;
+    while pasted.contains_key(&path) {
+        path = folder.join(format!("{} ({}).png", stem, n));
+        n += 1;
+    }
+    path
+}
+
+impl ImageViewerApp {
+    pub fn is_pasted_image(&self, path: &Path) -> bool {
+        self.pasted_images.contains_key(path)
+    }
+
+    /// Show the bitmap on the clipboard, e.g. a screenshot, in a tab of
+    /// pasted images
+    pub fn paste_image_from_clipboard(&mut self) {
+        let data = arboard::Clipboard::new().and_then(|mut c| c.get_image());
+        let image = data.ok().and_then(|data| {
+            image::RgbaImage::from_raw(
+                data.width as u32,
+                data.height as u32,
+                data.bytes.into_owned(),
+            )
+        });
+        let Some(image) = image.map(DynamicImage::ImageRgba8) else {
+            self.show_status("The clipboard has no image");
+            return;
+        };
+
+        let path = pasted_image_path(Local::now(), &self.pasted_images);
+        let thumbnail =
+            crate::image_loader::generate_thumbnail(&image, self.settings.thumbnail_size as u32);
+        self.image_cache.insert_thumbnail(path.clone(), thumbnail);
+        self.pasted_images.insert(path.clone(), image);
+
+        // Keep the open folder in its own tab
+        let folder = pasted_folder();
+        if self.current_folder.is_some() && self.current_folder.as_ref() != Some(&folder) {
+            self.push_tab();
+        }
+        self.cancel_folder_scan();
+        self.folder_watcher = None;
+        self.current_folder = Some(folder);
+        self.image_list = self.pasted_images.keys().cloned().collect();
+        self.image_list.sort();
+        self.apply_filter();
+        match self
+            .filtered_list
+            .iter()
+            .position(|&i| self.image_list[i] == path)
+        {
+            Some(index) => self.go_to_index(index),
+            None => self.show_status("Pasted image is hidden by the filter"),
+        }
+    }
+
+    /// Write the pasted image being shown to a file and open it from there
+    pub fn save_pasted_image(&mut self) {
+        let Some(path) = self.get_current_path() else {
+            return;
+        };
+        let Some(image) = self.pasted_images.get(&path) else {
+            self.show_status("Only pasted images need saving");
+            return;
+        };
+
+        // PNG first so lossless is the default
+        let png = ExportFormat::Png;
+        let mut dialog = rfd::FileDialog::new()
+            .set_title("Save Pasted Image")
+            .add_filter(png.name(), png.extensions());
+        for other in ExportFormat::all().iter().filter(|f| **f != png) {
+            dialog = dialog.add_filter(other.name(), other.extensions());
+        }
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        let Some(destination) = dialog.set_file_name(file_name).save_file() else {
+            return;
+        };
+
+        match save_exported_image(image, &destination, DEFAULT_JPEG_QUALITY, None) {
+            Ok(()) => {
+                self.pasted_images.remove(&path);
+                self.image_cache.remove(&path);
+                self.show_status(&format!("Saved to {}", destination.display()));
+                self.load_image_file(destination);
+            }
+            Err(e) => self.show_status(&format!("Failed to save image: {}", e)),
+        }
+    }
+}
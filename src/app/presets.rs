@@ -0,0 +1,72 @@
+use super::ImageViewerApp;
+use crate::settings::UserPreset;
+
+impl ImageViewerApp {
+    /// Save the current adjustments under `name`, replacing a preset with the same name
+    pub fn save_user_preset(&mut self, name: &str) {
+        let name = name.trim();
+        if name.is_empty() {
+            return;
+        }
+
+        let adjustments = self.adjustments.look();
+        match self
+            .settings
+            .user_presets
+            .iter_mut()
+            .find(|p| p.name == name)
+        {
+            Some(existing) => existing.adjustments = adjustments,
+            None => self.settings.user_presets.push(UserPreset {
+                name: name.to_string(),
+                adjustments,
+            }),
+        }
+        self.settings.save();
+        self.show_status(&format!("Saved preset \"{}\"", name));
+    }
+
+    /// Apply a user preset, keeping the current image's geometry and local adjustments
+    pub fn apply_user_preset(&mut self, index: usize) {
+        let Some(preset) = self.settings.user_presets.get(index).cloned() else {
+            return;
+        };
+
+        let previous = self.adjustments.clone();
+        self.adjustments = previous.with_look(&preset.adjustments);
+        self.current_film_preset = crate::image_loader::FilmPreset::None;
+        if self.adjustments != previous {
+            self.commit_adjustment_change(previous);
+        }
+        self.show_status(&format!("Applied preset \"{}\"", preset.name));
+    }
+
+    pub fn rename_user_preset(&mut self, index: usize, name: &str) {
+        let name = match name.trim() {
+            "" => "Untitled preset",
+            name => name,
+        };
+        if let Some(preset) = self.settings.user_presets.get_mut(index) {
+            preset.name = name.to_string();
+            self.settings.save();
+        }
+    }
+
+    pub fn delete_user_preset(&mut self, index: usize) {
+        if index < self.settings.user_presets.len() {
+            let preset = self.settings.user_presets.remove(index);
+            self.settings.save();
+            self.show_status(&format!("Deleted preset \"{}\"", preset.name));
+        }
+    }
+
+    /// Move a preset up (negative offset) or down (positive offset) in the list
+    pub fn move_user_preset(&mut self, index: usize, offset: isize) {
+        let len = self.settings.user_presets.len();
+        let target = index as isize + offset;
+        if index < len && target >= 0 && (target as usize) < len {
+            self.settings.user_presets.swap(index, target as usize);
+            self.settings.save();
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use super::watcher::OWN_WRITE_GRACE;
+use super::{ImageViewerApp, LoaderMessage};
+use crate::metadata::{FileOperation, Flag};
+use crate::settings::ColorLabel;
+use crate::xmp::XmpData;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+
+impl ImageViewerApp {
+    /// Rate the selected images, or the current one when nothing is selected
+    pub fn set_current_rating(&mut self, rating: u8) {
+        let paths = self.action_paths();
+        if paths.is_empty() {
+            return;
+        }
+        self.set_rating_for(&paths, rating);
+        self.show_status(&match rating {
+            0 => "Rating cleared".to_string(),
+            n => format!("Rated {}", "★".repeat(n as usize)),
+        });
+    }
+
+    /// Rate the given images, keeping their color labels
+    pub fn set_rating_for(&mut self, paths: &[PathBuf], rating: u8) {
+        let label = |app: &Self, path: &PathBuf| app.metadata_db.get(path).color_label;
+        self.apply_culling(paths, |app, path| (rating, label(app, path)));
+    }
+
+    /// Set the color label, or clear it when every targeted image already has that label
+    pub fn toggle_current_color_label(&mut self, label: ColorLabel) {
+        let paths = self.action_paths();
+        if paths.is_empty() {
+            return;
+        }
+        let new_label = if paths
+            .iter()
+            .all(|p| self.metadata_db.get(p).color_label == label)
+        {
+            ColorLabel::None
+        } else {
+            label
+        };
+        self.apply_culling(&paths, |app, path| {
+            (app.metadata_db.get(path).rating, new_label)
+        });
+        self.show_status(&format!("Label: {}", new_label.name()));
+    }
+
+    /// Pick or reject the targeted images, or clear the flag when every one already has it
+    pub fn toggle_current_flag(&mut self, flag: Flag) {
+        let paths = self.action_paths();
+        if paths.is_empty() {
+            return;
+        }
+        let new_flag = if paths.iter().all(|p| self.metadata_db.get(p).flag() == flag) {
+            Flag::None
+        } else {
+            flag
+        };
+
+        let mut ops = Vec::new();
+        for path in &paths {
+            let before = self.metadata_db.get(path).flag();
+            if before == new_flag {
+                continue;
+            }
+            self.metadata_db.set_flag(path, new_flag);
+            ops.push(FileOperation::Flag {
+                path: path.clone(),
+                before,
+                after: new_flag,
+            });
+        }
+        self.push_undo_batch(ops);
+        self.metadata_changed_for(&paths);
+        self.show_status(new_flag.name());
+    }
+
+    /// Show only picked or rejected images, or clear the filter with `None`
+    pub fn set_flag_filter(&mut self, flag: Option<Flag>) {
+        self.flag_filter = flag;
+        self.refilter_keeping_current();
+    }
+
+    /// Show just the rejected images and select them, ready to move or delete.
+    /// Returns false when there are none.
+    pub fn select_rejected(&mut self) -> bool {
+        self.set_flag_filter(Some(Flag::Reject));
+        self.selected_indices = (0..self.filtered_list.len()).collect();
+        if self.selected_indices.is_empty() {
+            self.show_status("No rejected images");
+            return false;
+        }
+        true
+    }
+
+    /// Set rating and label on each path as one undoable step
+    fn apply_culling(
+        &mut self,
+        paths: &[PathBuf],
+        value: impl Fn(&Self, &PathBuf) -> (u8, ColorLabel),
+    ) {
+        let mut ops = Vec::new();
+        for path in paths {
+            let metadata = self.metadata_db.get(path);
+            let before = (metadata.rating, metadata.color_label);
+            let after = value(self, path);
+            if before == after {
+                continue;
+            }
+            self.metadata_db.set_rating(path, after.0);
+            self.metadata_db.set_color_label(path, after.1);
+            ops.push(FileOperation::Rate {
+                path: path.clone(),
+                before,
+                after,
+            });
+        }
+        self.push_undo_batch(ops);
+        self.metadata_changed_for(paths);
+    }
+
+    /// Add comma-separated keywords to the current image
+    pub fn add_keywords_to_current(&mut self, input: &str) {
+        let Some(path) = self.get_current_path() else {
+            return;
+        };
+        let mut added = 0;
+        for keyword in input.split(',') {
+            if self.metadata_db.add_keyword(&path, keyword) {
+                added += 1;
+            }
+        }
+        if added > 0 {
+            self.metadata_changed(&path);
+        }
+    }
+
+    pub fn remove_keyword_from_current(&mut self, keyword: &str) {
+        if let Some(path) = self.get_current_path() {
+            self.metadata_db.remove_keyword(&path, keyword);
+            self.metadata_changed(&path);
+        }
+    }
+
+    /// Show only images carrying `keyword`, or clear the filter with `None`
+    pub fn set_keyword_filter(&mut self, keyword: Option<String>) {
+        self.keyword_filter = keyword;
+        self.refilter_keeping_current();
+    }
+
+    /// Persist a rating/label/keyword change and mirror it to the XMP sidecar if enabled
+    pub fn metadata_changed(&mut self, path: &Path) {
+        self.metadata_changed_for(&[path.to_path_buf()]);
+    }
+
+    pub fn metadata_changed_for(&mut self, paths: &[PathBuf]) {
+        if paths.is_empty() {
+            return;
+        }
+        self.metadata_db.save();
+        if self.settings.write_xmp_sidecars {
+            for path in paths {
+                if let Err(e) = crate::xmp::write_sidecar(path, &self.metadata_db.get(path)) {
+                    log::warn!("Failed to write XMP sidecar for {}: {}", path.display(), e);
+                }
+            }
+        }
+        if self.settings.write_os_tags && crate::os_tags::file_manager_name().is_some() {
+            // Explorer rewrites the file to store the rating, so keep it off the UI thread
+            let tagged: Vec<_> = paths
+                .iter()
+                .map(|p| (p.clone(), self.metadata_db.get(p)))
+                .collect();
+            // So the folder watcher doesn't reload the image for the rewrite
+            let own_writes = Arc::clone(&self.own_writes);
+            own_writes
+                .lock()
+                .unwrap()
+                .extend(paths.iter().map(|p| (p.clone(), None)));
+            self.spawn_loader(move |_| {
+                for (path, metadata) in tagged {
+                    if let Err(e) = crate::os_tags::write_tags(&path, &metadata) {
+                        log::warn!("Failed to write tags for {}: {}", path.display(), e);
+                    }
+                    let until = Instant::now() + OWN_WRITE_GRACE;
+                    own_writes.lock().unwrap().insert(path, Some(until));
+                }
+                None
+            });
+        }
+        // Keyword and filter bar filters, search terms and smart collections all depend on metadata
+        if self.keyword_filter.is_some()
+            || self.filter_bar_active()
+            || self.active_collection.is_some()
+            || !self.search_query.is_empty()
+        {
+            self.refilter_keeping_current();
+        }
+    }
+
+    /// Read .xmp sidecars for the loaded folder in the background
+    pub fn import_xmp_sidecars(&self) {
+        let paths = self.image_list.clone();
+        self.spawn_loader(move |_| {
+            let sidecars: Vec<(PathBuf, XmpData)> = paths
+                .into_iter()
+                .filter_map(|path| crate::xmp::read_sidecar(&path).map(|xmp| (path, xmp)))
+                .collect();
+            (!sidecars.is_empty()).then_some(LoaderMessage::XmpLoaded(sidecars))
+        });
+    }
+
+    /// Sidecars are treated as the source of truth, since they may have been edited elsewhere
+    pub fn handle_xmp_loaded(&mut self, sidecars: Vec<(PathBuf, XmpData)>) {
+        let count = sidecars.len();
+        for (path, xmp) in sidecars {
+            let mut metadata = self.metadata_db.get(&path);
+            xmp.apply_to(&mut metadata);
+            self.metadata_db.restore_metadata(path, metadata);
+        }
+        self.metadata_db.save();
+        log::info!("Imported metadata from {} XMP sidecars", count);
+
+        // Imported keywords can change what the current filter shows
+        if self.keyword_filter.is_some()
+            || self.active_collection.is_some()
+            || !self.search_query.is_empty()
+        {
+            self.refilter_keeping_current();
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use super::{ImageViewerApp, LoaderMessage};
+use crate::image_loader::is_raw_file;
+use crate::settings::PairPreference;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn is_jpeg(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("jpg") || e.eq_ignore_ascii_case("jpeg"))
+}
+
+fn pair_key(path: &Path) -> (Option<&Path>, String) {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    (path.parent(), stem)
+}
+
+/// RAW files with a JPEG of the same name beside them, as the file that is
+/// listed mapped to the one hidden behind it
+pub fn raw_jpeg_pairs(paths: &[PathBuf], prefer: PairPreference) -> HashMap<PathBuf, PathBuf> {
+    let raws: HashMap<_, &PathBuf> = paths
+        .iter()
+        .filter(|p| is_raw_file(p))
+        .map(|p| (pair_key(p), p))
+        .collect();
+
+    let mut pairs = HashMap::new();
+    for jpeg in paths.iter().filter(|p| is_jpeg(p)) {
+        if let Some(raw) = raws.get(&pair_key(jpeg)) {
+            let (shown, hidden) = match prefer {
+                PairPreference::Raw => (*raw, jpeg),
+                PairPreference::Jpeg => (jpeg, *raw),
+            };
+            pairs.insert(shown.clone(), hidden.clone());
+        }
+    }
+    pairs
+}
+
+/// A command line tool that writes DNG files
+#[derive(Debug, Clone, PartialEq)]
+pub enum DngConverter {
+    /// The open source dnglab
+    Dnglab(PathBuf),
+    /// Adobe's free DNG Converter
+    Adobe(PathBuf),
+}
+
+impl DngConverter {
+    fn from_path(path: PathBuf) -> Self {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if name.contains("dnglab") {
+            DngConverter::Dnglab(path)
+        } else {
+            DngConverter::Adobe(path)
+        }
+    }
+
+    /// The converter set in settings, else dnglab on the PATH, else Adobe's
+    /// converter where its installer puts it
+    pub fn find(configured: Option<&Path>) -> Option<Self> {
+        if let Some(path) = configured {
+            return path.is_file().then(|| Self::from_path(path.to_path_buf()));
+        }
+        let exe = if cfg!(windows) {
+            "dnglab.exe"
+        } else {
+            "dnglab"
+        };
+        if let Some(paths) = std::env::var_os("PATH") {
+            if let Some(found) = std::env::split_paths(&paths)
+                .map(|dir| dir.join(exe))
+                .find(|p| p.is_file())
+            {
+                return Some(DngConverter::Dnglab(found));
+            }
+        }
+        [
+            r"C:\Program Files\Adobe\Adobe DNG Converter\Adobe DNG Converter.exe",
+            "/Applications/Adobe DNG Converter.app/Contents/MacOS/Adobe DNG Converter",
+        ]
+        .iter()
+        .map(PathBuf::from)
+        .find(|p| p.is_file())
+        .map(DngConverter::Adobe)
+    }
+
+    /// Arguments that convert `source` into `output`; both tools copy the
+    /// EXIF and maker notes across
+    pub fn command(&self, source: &Path, output: &Path) -> Command {
+        match self {
+            DngConverter::Dnglab(exe) => {
+                let mut command = Command::new(exe);
+                command.arg("convert").arg(source).arg(output);
+                command
+            }
+            DngConverter::Adobe(exe) => {
+                let mut command = Command::new(exe);
+                command.arg("-c");
+                if let Some(folder) = output.parent() {
+                    command.arg("-d").arg(folder);
+                }
+                if let Some(name) = output.file_name() {
+                    command.arg("-o").arg(name);
+                }
+                command.arg(source);
+                command
+            }
+        }
+    }
+
+    /// Convert one file, next to it with a .dng extension
+    pub fn convert(&self, source: &Path) -> Result<PathBuf, String> {
+        let output = source.with_extension("dng");
+        if output.exists() {
+            return Err(format!("{} already exists", output.display()));
+        }
+        let result = self
+            .command(source, &output)
+            .output()
+            .map_err(|e| format!("Could not run converter: {}", e))?;
+        if !result.status.success() || !output.exists() {
+            let stderr = String::from_utf8_lossy(&result.stderr);
+            let message = stderr.lines().last().unwrap_or("conversion failed");
+            return Err(message.trim().to_string());
+        }
+        Ok(output)
+    }
+}
+
+impl ImageViewerApp {
+    /// The other half of a RAW+JPEG pair shown as one item
+    pub fn pair_companion(&self, path: &Path) -> Option<&PathBuf> {
+        self.raw_pairs.get(path)
+    }
+
+    /// `paths` plus the hidden half of any pairs among them, so an action on
+    /// a pair reaches both files
+    pub fn with_pair_companions(&self, paths: Vec<PathBuf>) -> Vec<PathBuf> {
+        let companions: Vec<PathBuf> = paths
+            .iter()
+            .filter_map(|p| self.pair_companion(p))
+            .filter(|c| !paths.contains(c))
+            .cloned()
+            .collect();
+        paths.into_iter().chain(companions).collect()
+    }
+
+    /// Convert the selected RAW files, or all shown ones, to DNG in the background
+    pub fn start_dng_conversion(&mut self) {
+        if self.dng_converting {
+            return;
+        }
+        let sources: Vec<PathBuf> = self
+            .with_pair_companions(self.selected_or_filtered_paths())
+            .into_iter()
+            .filter(|p| is_raw_file(p))
+            .filter(|p| !p.extension().is_some_and(|e| e.eq_ignore_ascii_case("dng")))
+            .collect();
+        if sources.is_empty() {
+            self.show_status("No RAW files to convert");
+            return;
+        }
+        let Some(converter) = DngConverter::find(self.settings.dng_converter.as_deref()) else {
+            self.show_status("No DNG converter found; install dnglab or set one in Settings");
+            return;
+        };
+
+        self.dng_converting = true;
+        self.show_status(&format!("Converting {} files to DNG...", sources.len()));
+        self.spawn_loader(move |_| {
+            let mut converted = Vec::new();
+            let mut failed = Vec::new();
+            for source in sources {
+                match converter.convert(&source) {
+                    Ok(output) => converted.push((source, output)),
+                    Err(e) => failed.push((source, e)),
+                }
+            }
+            Some(LoaderMessage::DngConversionFinished { converted, failed })
+        });
+    }
+
+    pub fn handle_dng_conversion_finished(
+        &mut self,
+        converted: Vec<(PathBuf, PathBuf)>,
+        failed: Vec<(PathBuf, String)>,
+    ) {
+        self.dng_converting = false;
+        for (source, output) in &converted {
+            // Ratings, labels, keywords and edits follow the file into its DNG
+            if let Some(metadata) = self.metadata_db.images.get(source).cloned() {
+                self.metadata_db.restore_metadata(output.clone(), metadata);
+            }
+            if !self.image_list.contains(output) {
+                self.image_list.push(output.clone());
+            }
+        }
+        if !converted.is_empty() {
+            self.metadata_db.save();
+            self.sort_images();
+            self.refilter_keeping_current();
+        }
+
+        for (source, error) in &failed {
+            log::warn!("DNG conversion of {} failed: {}", source.display(), error);
+        }
+        self.show_status(&match (converted.len(), failed.first()) {
+            (n, None) => format!("Converted {} files to DNG", n),
+            (n, Some((_, error))) => {
+                format!(
+                    "Converted {} files to DNG, {} failed: {}",
+                    n,
+                    failed.len(),
+                    error
+                )
+            }
+        });
+    }
+}
@@ -0,0 +1,247 @@
+use super::ImageViewerApp;
+use crate::metadata::{FileOperation, ImageMetadata};
+use std::path::{Path, PathBuf};
+
+/// A file deleted during this session
+#[derive(Debug, Clone)]
+pub struct DeletedFile {
+    pub original_path: PathBuf,
+    /// Where the file is kept when it was deleted without the system trash
+    pub held_path: Option<PathBuf>,
+    pub metadata_backup: Option<String>,
+    pub deleted_at: chrono::DateTime<chrono::Local>,
+}
+
+impl DeletedFile {
+    pub fn to_operation(&self) -> FileOperation {
+        FileOperation::Delete {
+            original_path: self.original_path.clone(),
+            trash_path: self.held_path.clone(),
+            metadata_backup: self.metadata_backup.clone(),
+        }
+    }
+}
+
+/// Name of the hidden folder files deleted without the trash are kept in
+pub const HOLDING_DIR_NAME: &str = ".rview-deleted";
+
+/// Folder for `path` when it's deleted with the trash turned off. It sits next
+/// to the file, so holding it is a rename on the same drive.
+pub fn holding_dir(path: &Path) -> PathBuf {
+    path.parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(HOLDING_DIR_NAME)
+}
+
+/// Whether `path` is a deleted file waiting in a holding folder
+pub fn is_held(path: &Path) -> bool {
+    path.components()
+        .any(|c| c.as_os_str() == std::ffi::OsStr::new(HOLDING_DIR_NAME))
+}
+
+/// Record of the holding folders in use, so ones left by a crash are found
+fn holding_dirs_file() -> Option<PathBuf> {
+    directories::ProjectDirs::from("com", "imageviewer", "ImageViewer")
+        .map(|dirs| dirs.data_dir().join("holding folders"))
+}
+
+fn remember_holding_dir(dir: &Path) -> std::io::Result<()> {
+    let Some(file) = holding_dirs_file() else {
+        return Ok(());
+    };
+    let mut text = std::fs::read_to_string(&file).unwrap_or_default();
+    if text.lines().any(|line| Path::new(line) == dir) {
+        return Ok(());
+    }
+    text.push_str(&format!("{}\n", dir.display()));
+    if let Some(parent) = file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(file, text)
+}
+
+/// Permanently remove everything held without the trash, both this session's
+/// files when the app closes and any a crash left behind at the next start
+pub fn purge_holding_dirs() {
+    let Some(file) = holding_dirs_file() else {
+        return;
+    };
+    let text = std::fs::read_to_string(&file).unwrap_or_default();
+    for dir in text.lines().map(Path::new) {
+        // Never remove a folder this app didn't name
+        if dir.file_name() != Some(std::ffi::OsStr::new(HOLDING_DIR_NAME)) || !dir.exists() {
+            continue;
+        }
+        if let Err(e) = std::fs::remove_dir_all(dir) {
+            log::warn!("Failed to empty {}: {}", dir.display(), e);
+        }
+    }
+    let _ = std::fs::remove_file(file);
+}
+
+/// Move a file into its holding folder under a name no other held file uses
+fn hold_file(path: &Path) -> std::io::Result<PathBuf> {
+    let dir = holding_dir(path);
+    std::fs::create_dir_all(&dir)?;
+    remember_holding_dir(&dir)?;
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let mut held = dir.join(&*name);
+    let mut n = 2;
+    while held.exists() {
+        held = dir.join(format!("{}-{}", n, name));
+        n += 1;
+    }
+    move_file(path, &held)?;
+    Ok(held)
+}
+
+/// Rename, falling back to copy and remove when the folders are on different drives
+fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    std::fs::copy(from, to)?;
+    std::fs::remove_file(from)
+}
+
+/// Put the most recently trashed file with this original path back in place
+#[cfg(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+fn restore_from_trash(original: &Path) -> Result<(), String> {
+    let item = trash::os_limited::list()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|item| item.original_parent.join(&item.name) == original)
+        .max_by_key(|item| item.time_deleted)
+        .ok_or_else(|| "no longer in the trash".to_string())?;
+    trash::os_limited::restore_all([item]).map_err(|e| e.to_string())
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+)))]
+fn restore_from_trash(_original: &Path) -> Result<(), String> {
+    Err("restore it from the system trash".to_string())
+}
+
+impl ImageViewerApp {
+    /// Send a file to the trash, or to the holding folder when the trash is turned off
+    pub fn delete_file(&mut self, path: &Path) -> Result<DeletedFile, String> {
+        if crate::image_loader::is_archive_entry(path) {
+            return Err("Images inside archives can't be deleted".to_string());
+        }
+        let metadata_backup = serde_json::to_string(&self.metadata_db.get(path)).ok();
+        let held_path = if self.settings.delete_to_trash {
+            trash::delete(path).map_err(|e| e.to_string())?;
+            None
+        } else {
+            Some(hold_file(path).map_err(|e| e.to_string())?)
+        };
+
+        let deleted = DeletedFile {
+            original_path: path.to_path_buf(),
+            held_path,
+            metadata_backup,
+            deleted_at: chrono::Local::now(),
+        };
+        self.recently_deleted.push(deleted.clone());
+        Ok(deleted)
+    }
+
+    /// Restore the most recent deletion of `original_path`
+    pub fn restore_deleted_path(&mut self, original_path: &Path) {
+        match self
+            .recently_deleted
+            .iter()
+            .rposition(|d| d.original_path == original_path)
+        {
+            Some(index) => self.restore_deleted(&[index]),
+            None => self.show_status(&format!(
+                "Cannot undo delete of {}",
+                original_path.display()
+            )),
+        }
+    }
+
+    /// Restore entries of `recently_deleted` by index
+    pub fn restore_deleted(&mut self, indices: &[usize]) {
+        let previous = self.get_current_path();
+        let mut restored = Vec::new();
+        let mut errors = Vec::new();
+
+        for &index in indices {
+            let Some(deleted) = self.recently_deleted.get(index) else {
+                continue;
+            };
+            let original = &deleted.original_path;
+            let result = if original.exists() {
+                Err("a file with that name already exists".to_string())
+            } else {
+                match &deleted.held_path {
+                    Some(held) => {
+                        if let Some(parent) = original.parent() {
+                            let _ = std::fs::create_dir_all(parent);
+                        }
+                        move_file(held, original).map_err(|e| e.to_string())
+                    }
+                    None => restore_from_trash(original),
+                }
+            };
+            match result {
+                Ok(()) => restored.push(index),
+                Err(e) => errors.push(format!("{}: {}", original.display(), e)),
+            }
+        }
+
+        // Remove from the back so earlier indices stay valid
+        restored.sort_unstable();
+        let mut files: Vec<DeletedFile> = restored
+            .iter()
+            .rev()
+            .map(|&index| self.recently_deleted.remove(index))
+            .collect();
+        files.reverse();
+
+        for deleted in &files {
+            if let Some(metadata) = deleted
+                .metadata_backup
+                .as_deref()
+                .and_then(|json| serde_json::from_str::<ImageMetadata>(json).ok())
+            {
+                self.metadata_db
+                    .restore_metadata(deleted.original_path.clone(), metadata);
+            }
+            let in_folder = deleted.original_path.parent() == self.current_folder.as_deref();
+            if in_folder && !self.image_list.contains(&deleted.original_path) {
+                self.image_list.push(deleted.original_path.clone());
+            }
+        }
+        if !files.is_empty() {
+            self.metadata_db.save();
+            self.sort_images();
+            self.refilter_keeping(previous);
+        }
+
+        for error in &errors {
+            log::warn!("Restore failed for {}", error);
+        }
+        match (files.len(), errors.first()) {
+            (_, Some(error)) => self.show_status(&format!("Cannot restore {}", error)),
+            (1, None) => self.show_status("File restored"),
+            (n, None) => self.show_status(&format!("Restored {} files", n)),
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use super::{ImageViewerApp, LoaderMessage};
+use image::imageops::FilterType;
+use image::{DynamicImage, RgbaImage};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Longest side the reference and difference textures are built at
+const OVERLAY_SIZE: u32 = 2048;
+
+/// How the current image is combined with the reference under it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlayBlend {
+    Normal,
+    /// Black where the images match, bright where they differ
+    Difference,
+}
+
+impl OverlayBlend {
+    pub fn all() -> &'static [OverlayBlend] {
+        &[OverlayBlend::Normal, OverlayBlend::Difference]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            OverlayBlend::Normal => "Normal",
+            OverlayBlend::Difference => "Difference",
+        }
+    }
+}
+
+/// What the current image is laid over
+#[derive(Debug, Clone, PartialEq)]
+pub enum OverlaySource {
+    Image(PathBuf),
+    /// Whatever is behind the window, for tracing
+    Desktop,
+}
+
+/// The current image laid semi-transparently over a reference
+pub struct ReferenceOverlay {
+    pub source: OverlaySource,
+    /// Opacity of the current image over the reference
+    pub opacity: f32,
+    pub blend: OverlayBlend,
+    reference: Option<(DynamicImage, egui::TextureHandle)>,
+    /// Difference with the current image, keyed by its path and size
+    difference: Option<(PathBuf, [u32; 2], egui::TextureHandle)>,
+    difference_pending: Option<(PathBuf, [u32; 2])>,
+}
+
+/// Per-channel difference of `image` and `reference`, with the reference
+/// stretched to the image's size
+pub fn difference_image(image: &DynamicImage, reference: &DynamicImage) -> RgbaImage {
+    let image = image.to_rgba8();
+    let mut reference = reference.to_rgba8();
+    if reference.dimensions() != image.dimensions() {
+        reference = image::imageops::resize(
+            &reference,
+            image.width(),
+            image.height(),
+            FilterType::Triangle,
+        );
+    }
+    RgbaImage::from_fn(image.width(), image.height(), |x, y| {
+        let a = image.get_pixel(x, y);
+        let b = reference.get_pixel(x, y);
+        image::Rgba([
+            a[0].abs_diff(b[0]),
+            a[1].abs_diff(b[1]),
+            a[2].abs_diff(b[2]),
+            255,
+        ])
+    })
+}
+
+/// `image` no bigger than the overlay textures
+fn downscale(image: DynamicImage) -> DynamicImage {
+    if image.width().max(image.height()) > OVERLAY_SIZE {
+        crate::image_loader::generate_thumbnail(&image, OVERLAY_SIZE)
+    } else {
+        image
+    }
+}
+
+fn overlay_texture(
+    ctx: &egui::Context,
+    name: String,
+    image: &RgbaImage,
+    options: egui::TextureOptions,
+) -> egui::TextureHandle {
+    ctx.load_texture(
+        name,
+        egui::ColorImage::from_rgba_unmultiplied(
+            [image.width() as usize, image.height() as usize],
+            image.as_flat_samples().as_slice(),
+        ),
+        options,
+    )
+}
+
+impl ImageViewerApp {
+    /// Lay the current image over `source`, keeping the opacity and blend
+    pub fn set_overlay_source(&mut self, source: OverlaySource) {
+        let (opacity, blend) = self
+            .reference_overlay
+            .as_ref()
+            .map_or((0.5, OverlayBlend::Normal), |o| (o.opacity, o.blend));
+        if let OverlaySource::Image(path) = &source {
+            self.load_overlay_reference(path.clone());
+        }
+        self.reference_overlay = Some(ReferenceOverlay {
+            source,
+            opacity,
+            blend,
+            reference: None,
+            difference: None,
+            difference_pending: None,
+        });
+    }
+
+    fn load_overlay_reference(&self, path: PathBuf) {
+        let Some(ctx) = self.ctx.clone() else {
+            return;
+        };
+        let texture_options = self.texture_options();
+        self.spawn_loader(move |_| {
+            let result = crate::image_loader::load_image(&path)
+                .map(|image| {
+                    let image = downscale(image);
+                    let name = format!("overlay_{}", path.display());
+                    let texture = overlay_texture(&ctx, name, &image.to_rgba8(), texture_options);
+                    (image, texture)
+                })
+                .map_err(|e| e.to_string());
+            Some(LoaderMessage::OverlayReferenceLoaded(path, result))
+        });
+    }
+
+    pub fn handle_overlay_reference_loaded(
+        &mut self,
+        path: PathBuf,
+        result: Result<(DynamicImage, egui::TextureHandle), String>,
+    ) {
+        let Some(overlay) = &mut self.reference_overlay else {
+            return;
+        };
+        // Another reference was picked while this one loaded
+        if overlay.source != OverlaySource::Image(path) {
+            return;
+        }
+        match result {
+            Ok(reference) => overlay.reference = Some(reference),
+            Err(e) => {
+                self.reference_overlay = None;
+                self.show_status(&format!("Couldn't load the overlay reference: {}", e));
+            }
+        }
+    }
+
+    /// Reference to draw over the current image, once loaded
+    pub fn overlay_reference_texture(&self) -> Option<egui::TextureId> {
+        let overlay = self.reference_overlay.as_ref()?;
+        overlay.reference.as_ref().map(|(_, texture)| texture.id())
+    }
+
+    /// Difference of the current image with the reference, built in the
+    /// background
+    pub fn overlay_difference_texture(&mut self) -> Option<egui::TextureId> {
+        let ctx = self.ctx.clone()?;
+        let path = self.get_current_path()?;
+        let image = self.current_image.as_ref()?;
+        let size = [image.width(), image.height()];
+        let overlay = self.reference_overlay.as_mut()?;
+        let (reference, _) = overlay.reference.as_ref()?;
+        if let Some((texture_path, texture_size, texture)) = &overlay.difference {
+            if *texture_path == path && *texture_size == size {
+                return Some(texture.id());
+            }
+        }
+        if overlay.difference_pending == Some((path.clone(), size)) {
+            return None;
+        }
+
+        overlay.difference_pending = Some((path.clone(), size));
+        let image = Arc::clone(image);
+        let reference = reference.clone();
+        let texture_options = self.texture_options();
+        self.spawn_loader(move |_| {
+            let image = downscale(Arc::unwrap_or_clone(image));
+            let difference = difference_image(&image, &reference);
+            let name = format!("overlay_difference_{}", path.display());
+            let texture = overlay_texture(&ctx, name, &difference, texture_options);
+            Some(LoaderMessage::OverlayDifferenceReady(path, size, texture))
+        });
+        None
+    }
+
+    pub fn handle_overlay_difference_ready(
+        &mut self,
+        path: PathBuf,
+        size: [u32; 2],
+        texture: egui::TextureHandle,
+    ) {
+        let Some(overlay) = &mut self.reference_overlay else {
+            return;
+        };
+        // Ignore differences for an image that was left or edited while building
+        if overlay.difference_pending == Some((path.clone(), size)) {
+            overlay.difference_pending = None;
+            overlay.difference = Some((path, size, texture));
+        }
+    }
+
+    /// Opacity of the current image when it's laid over the desktop
+    pub fn desktop_overlay_opacity(&self) -> Option<f32> {
+        self.reference_overlay
+            .as_ref()
+            .filter(|overlay| overlay.source == OverlaySource::Desktop)
+            .map(|overlay| overlay.opacity)
+    }
+}
@@ -0,0 +1,90 @@
+use super::{ImageViewerApp, LoaderMessage};
+use crate::cli::DEFAULT_SLIDESHOW_SECONDS;
+use crate::remote_control::{self, RemoteCommand, RemoteRequest, RemoteStatus};
+
+impl ImageViewerApp {
+    /// Start, restart or stop the remote control server to match the settings
+    pub fn apply_remote_control_settings(&mut self) {
+        // The old server has to let go of the port first
+        self.remote_server = None;
+        if !self.settings.remote_control.enabled {
+            return;
+        }
+        // Never listen without a token, not even on this computer
+        if self.settings.remote_control.token.is_empty() {
+            self.settings.remote_control.token = uuid::Uuid::new_v4().simple().to_string();
+        }
+
+        let tx = self.loader_tx.clone();
+        let ctx = self.ctx.clone();
+        let result = remote_control::serve(&self.settings.remote_control, move |request| {
+            let _ = tx.send(LoaderMessage::RemoteCommand(request));
+            if let Some(ctx) = &ctx {
+                ctx.request_repaint();
+            }
+        });
+        match result {
+            Ok(server) => {
+                log::info!("Remote control listening on {}", server.address);
+                self.remote_server = Some(server);
+            }
+            Err(e) => {
+                log::warn!("Remote control couldn't start: {}", e);
+                self.show_status(&format!("Remote control couldn't start: {}", e));
+            }
+        }
+    }
+
+    /// Carry out a remote's command and tell it where the viewer is now
+    pub fn handle_remote_command(&mut self, request: RemoteRequest) {
+        match request.command {
+            RemoteCommand::Next => self.next_image(),
+            RemoteCommand::Previous => self.previous_image(),
+            RemoteCommand::First => self.go_to_first(),
+            RemoteCommand::Last => self.go_to_last(),
+            RemoteCommand::GoTo(index) => {
+                if index < self.filtered_list.len() {
+                    self.go_to_index(index);
+                }
+            }
+            RemoteCommand::Rate(stars) => {
+                if let Some(path) = self.get_current_path() {
+                    self.set_rating_for(&[path], stars);
+                }
+            }
+            RemoteCommand::StartSlideshow(seconds) => {
+                self.start_slideshow(seconds.unwrap_or(DEFAULT_SLIDESHOW_SECONDS))
+            }
+            RemoteCommand::StopSlideshow => self.stop_slideshow(),
+            RemoteCommand::ToggleSlideshow => self.toggle_slideshow(),
+            RemoteCommand::Status => {}
+        }
+        let _ = request.reply.send(self.remote_status());
+    }
+
+    pub fn remote_status(&self) -> RemoteStatus {
+        let path = self.get_current_path();
+        let metadata = path
+            .as_ref()
+            .map(|p| self.metadata_db.get(p))
+            .unwrap_or_default();
+        RemoteStatus {
+            name: path
+                .as_ref()
+                .and_then(|p| p.file_name())
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            index: if path.is_some() {
+                self.current_index + 1
+            } else {
+                0
+            },
+            path,
+            total: self.filtered_list.len(),
+            rating: metadata.rating,
+            color_label: metadata.color_label.name().to_string(),
+            flag: metadata.flag().name().to_string(),
+            slideshow: self.slideshow_interval.is_some(),
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use super::{ImageViewerApp, LoaderMessage};
+use crate::metadata::FileOperation;
+use crate::rename::{execute_renames, plan_renames, PlannedRename, RenameInfo};
+use rayon::prelude::*;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+/// Files being renamed by the batch rename dialog
+pub struct RenameSession {
+    pub sources: Vec<PathBuf>,
+    /// Dates and camera models, read in the background when the dialog opens
+    pub infos: Option<Vec<RenameInfo>>,
+}
+
+impl ImageViewerApp {
+    /// Open the rename dialog for the selection, or the current image when nothing is selected
+    pub fn open_rename_dialog(&mut self) {
+        let mut sources = self.selected_paths();
+        if sources.is_empty() {
+            sources.extend(self.get_current_path());
+        }
+        if sources.is_empty() {
+            self.show_status("No images to rename");
+            return;
+        }
+
+        let paths = sources.clone();
+        self.rename_session = Some(RenameSession {
+            sources,
+            infos: None,
+        });
+        self.spawn_loader(move |_| {
+            let entries = paths
+                .into_par_iter()
+                .map(|path| {
+                    let info = RenameInfo::from_file(&path);
+                    (path, info)
+                })
+                .collect();
+            Some(LoaderMessage::RenameInfoLoaded(entries))
+        });
+    }
+
+    pub fn handle_rename_info_loaded(&mut self, entries: Vec<(PathBuf, RenameInfo)>) {
+        let Some(session) = &mut self.rename_session else {
+            return;
+        };
+        // Ignore results for a dialog that has since been reopened on other files
+        if entries.len() != session.sources.len()
+            || entries
+                .iter()
+                .zip(&session.sources)
+                .any(|((p, _), s)| p != s)
+        {
+            return;
+        }
+        session.infos = Some(entries.into_iter().map(|(_, info)| info).collect());
+    }
+
+    /// New names for the files in the rename dialog, with any collisions flagged
+    pub fn rename_plan(&self) -> Vec<PlannedRename> {
+        let Some(session) = &self.rename_session else {
+            return Vec::new();
+        };
+        let infos = session.infos.as_deref().unwrap_or_default();
+        plan_renames(&session.sources, &self.settings.rename_pattern, infos)
+    }
+
+    /// Rename the files in the dialog and record one undo step for the whole batch
+    pub fn apply_batch_rename(&mut self) {
+        let plans = self.rename_plan();
+        if let Some(conflict) = plans.iter().find_map(|p| p.conflict) {
+            self.show_status(&format!("Cannot rename: {}", conflict.description()));
+            return;
+        }
+
+        let mut renames: Vec<(PathBuf, PathBuf)> = plans
+            .iter()
+            .filter(|p| p.is_change())
+            .map(|p| (p.from.clone(), p.to.clone()))
+            .collect();
+        if renames.is_empty() {
+            self.rename_session = None;
+            return;
+        }
+        let count = renames.len();
+
+        // Sidecars follow their image, unless shared by two images or blocked by an existing file
+        let mut claimed = HashSet::new();
+        let mut sidecars = Vec::new();
+        for (from, to) in &renames {
+            let Some(sidecar) = crate::xmp::find_sidecar(from) else {
+                continue;
+            };
+            if !claimed.insert(sidecar.clone()) {
+                continue;
+            }
+            let target = if sidecar == crate::xmp::sidecar_path(from) {
+                crate::xmp::sidecar_path(to)
+            } else {
+                let name = to.file_name().unwrap_or_default().to_string_lossy();
+                to.with_file_name(format!("{}.xmp", name))
+            };
+            if !target.exists() {
+                sidecars.push((sidecar, target));
+            }
+        }
+        renames.extend(sidecars);
+
+        if let Err(e) = execute_renames(&renames) {
+            log::error!("Batch rename failed: {}", e);
+            self.show_status(&format!("Rename failed, nothing was changed: {}", e));
+            return;
+        }
+
+        self.apply_renamed_paths(&renames);
+        self.undo_history
+            .push(FileOperation::BatchRename { renames });
+        self.rename_session = None;
+        self.show_status(&format!("Renamed {} files", count));
+    }
+
+    /// Point the file list, catalog and caches at files renamed on disk
+    pub fn apply_renamed_paths(&mut self, renames: &[(PathBuf, PathBuf)]) {
+        let current = self.get_current_path().map(|path| {
+            renames
+                .iter()
+                .find(|(from, _)| *from == path)
+                .map(|(_, to)| to.clone())
+                .unwrap_or(path)
+        });
+
+        // Two passes so that swapped names don't clobber each other's entries
+        let mut moved = Vec::new();
+        for (from, _) in renames {
+            let position = self.image_list.iter().position(|p| p == from);
+            let texture = self.thumbnail_textures.remove(from);
+            let exif = self.search_exif.remove(from);
+            let exposure = self.thumbnail_exposure.remove(from);
+            let metadata = self.metadata_db.images.remove(from);
+            let taken = self.metadata_db.capture_dates.remove(from);
+            self.thumbnail_requests.remove(from);
+            self.image_cache.remove(from);
+            moved.push((position, texture, exif, exposure, metadata, taken));
+        }
+        for ((_, to), moved) in renames.iter().zip(moved) {
+            let (position, texture, exif, exposure, metadata, taken) = moved;
+            if let Some(pos) = position {
+                self.image_list[pos] = to.clone();
+            }
+            if let Some(texture) = texture {
+                self.thumbnail_textures.insert(to.clone(), texture);
+            }
+            if let Some(exif) = exif {
+                self.search_exif.insert(to.clone(), exif);
+            }
+            if let Some(exposure) = exposure {
+                self.thumbnail_exposure.insert(to.clone(), exposure);
+            }
+            if let Some(metadata) = metadata {
+                self.metadata_db.images.insert(to.clone(), metadata);
+            }
+            if let Some(taken) = taken {
+                self.metadata_db.capture_dates.insert(to.clone(), taken);
+            }
+        }
+        self.metadata_db.save();
+
+        // Selection indices would point at other files once re-sorted
+        self.selected_indices.clear();
+        if !matches!(self.settings.sort_mode, crate::settings::SortMode::Random) {
+            self.sort_images();
+        }
+        self.refilter_keeping(current);
+    }
+}
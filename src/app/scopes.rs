@@ -0,0 +1,54 @@
+use super::{ImageViewerApp, LoaderMessage};
+use crate::image_loader::{self, ScopeData, ScopeType};
+use egui::TextureHandle;
+
+/// The drawn scope, kept until the data or the look changes
+pub struct ScopeTexture {
+    pub scope: ScopeType,
+    pub intensity: f32,
+    pub texture: TextureHandle,
+}
+
+impl ImageViewerApp {
+    /// Recompute the scopes for the current image and adjustments in the
+    /// background. Runs on a small copy, so it's cheap enough to follow every
+    /// histogram update.
+    pub fn request_scopes(&mut self) {
+        if !self.settings.show_scopes {
+            // Dropped so turning the scopes back on doesn't show an old image
+            self.scope_data = None;
+            self.scope_texture = None;
+            return;
+        }
+        let Some(image) = &self.current_image else {
+            return;
+        };
+        let image = image.thumbnail(1024, 1024);
+
+        self.scope_generation += 1;
+        self.scopes_pending = true;
+        let generation = self.scope_generation;
+        let hdr = self.settings.hdr_display.clone();
+        let adjustments = (!self.show_original).then(|| self.adjustments.clone());
+        self.spawn_loader(move |_| {
+            let display = image_loader::display_referred(&image, &hdr);
+            let scopes = match &adjustments {
+                Some(adjustments) => image_loader::calculate_scopes(
+                    &image_loader::apply_adjustments(&display, adjustments),
+                ),
+                None => image_loader::calculate_scopes(&display),
+            };
+            Some(LoaderMessage::ScopesUpdated(generation, Box::new(scopes)))
+        });
+    }
+
+    pub fn handle_scopes_updated(&mut self, generation: u64, scopes: ScopeData) {
+        // A later request is on its way
+        if generation != self.scope_generation {
+            return;
+        }
+        self.scopes_pending = false;
+        self.scope_data = Some(scopes);
+        self.scope_texture = None;
+    }
+}
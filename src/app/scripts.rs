@@ -0,0 +1,146 @@
+use super::{ImageViewerApp, LoaderMessage};
+use crate::exif_data::ExifInfo;
+use crate::metadata::FileOperation;
+use crate::scripting::{self, Script, ScriptContext, ScriptEffect};
+
+/// EXIF fields handed to scripts, under the names they appear as in `rview.exif`
+fn exif_fields(exif: &ExifInfo) -> Vec<(&'static str, String)> {
+    let mut fields: Vec<(&'static str, String)> = [
+        ("make", &exif.camera_make),
+        ("model", &exif.camera_model),
+        ("lens", &exif.lens),
+        ("focal_length", &exif.focal_length),
+        ("aperture", &exif.aperture),
+        ("shutter_speed", &exif.shutter_speed),
+        ("iso", &exif.iso),
+        ("date_taken", &exif.date_taken),
+        ("dimensions", &exif.dimensions),
+        ("copyright", &exif.copyright),
+        ("artist", &exif.artist),
+    ]
+    .into_iter()
+    .filter_map(|(key, value)| Some((key, value.clone()?)))
+    .collect();
+    if let (Some(lat), Some(lon)) = (exif.gps_latitude, exif.gps_longitude) {
+        fields.push(("gps_latitude", lat.to_string()));
+        fields.push(("gps_longitude", lon.to_string()));
+    }
+    fields
+}
+
+impl ImageViewerApp {
+    /// Open the palette with the scripts folder read again, so new scripts show up
+    pub fn open_command_palette(&mut self) {
+        self.command_palette_open = true;
+        self.command_palette_query.clear();
+        self.scripts = scripting::scripts_folder()
+            .map(|folder| scripting::find_scripts(&folder))
+            .unwrap_or_default();
+    }
+
+    pub fn open_scripts_folder(&mut self) {
+        let Some(folder) = scripting::scripts_folder() else {
+            return;
+        };
+        if let Err(e) = std::fs::create_dir_all(&folder) {
+            self.show_status(&format!("Couldn't create the scripts folder: {}", e));
+            return;
+        }
+        let _ = open::that(&folder);
+    }
+
+    fn script_context(&self) -> ScriptContext {
+        let path = self.get_current_path();
+        ScriptContext {
+            metadata: path
+                .as_ref()
+                .map(|p| self.metadata_db.get(p))
+                .unwrap_or_default(),
+            exif: self
+                .current_exif
+                .as_ref()
+                .map(exif_fields)
+                .unwrap_or_default(),
+            path,
+            folder: self.current_folder.clone(),
+            selection: self.selected_paths(),
+            adjustments: self.adjustments.clone(),
+        }
+    }
+
+    /// Run a script in the background; what it asks for happens when it ends
+    pub fn run_script(&mut self, script: Script) {
+        let source = match std::fs::read_to_string(&script.path) {
+            Ok(source) => source,
+            Err(e) => {
+                self.show_status(&format!("Couldn't read {}: {}", script.title, e));
+                return;
+            }
+        };
+        let context = self.script_context();
+        self.show_status(&format!("Running {}...", script.title));
+        self.spawn_loader(move |_| {
+            let name = script.path.to_string_lossy().to_string();
+            let result =
+                scripting::run_script(&source, &name, &context, scripting::SCRIPT_TIME_LIMIT);
+            Some(LoaderMessage::ScriptFinished(script.title, result))
+        });
+    }
+
+    pub fn handle_script_finished(
+        &mut self,
+        title: String,
+        result: Result<Vec<ScriptEffect>, String>,
+    ) {
+        let effects = match result {
+            Ok(effects) => effects,
+            Err(e) => {
+                log::warn!("Script {} failed: {}", title, e);
+                self.show_status(&format!("{} failed: {}", title, e));
+                return;
+            }
+        };
+
+        let mut status = None;
+        let mut renames = Vec::new();
+        for effect in effects {
+            match effect {
+                ScriptEffect::Status(text) => status = Some(text),
+                ScriptEffect::CopyText(text) => {
+                    if let Err(e) = arboard::Clipboard::new().and_then(|mut c| c.set_text(text)) {
+                        log::warn!("Script {} couldn't copy text: {}", title, e);
+                    }
+                }
+                ScriptEffect::SetRating(path, stars) => self.set_rating_for(&[path], stars),
+                ScriptEffect::AddKeyword(path, keyword) => {
+                    if self.metadata_db.add_keyword(&path, &keyword) {
+                        self.metadata_changed(&path);
+                    }
+                }
+                ScriptEffect::SetAdjustments(path, adjustments) => {
+                    if self.get_current_path().as_ref() == Some(&path) {
+                        let previous = std::mem::replace(&mut self.adjustments, adjustments);
+                        self.commit_adjustment_change(previous);
+                    }
+                }
+                ScriptEffect::Rename(from, to) => {
+                    if to.exists() {
+                        status = Some(format!("{} already exists", to.display()));
+                    } else if let Err(e) = std::fs::rename(&from, &to) {
+                        status = Some(format!("Couldn't rename {}: {}", from.display(), e));
+                    } else {
+                        self.apply_renamed_paths(&[(from.clone(), to.clone())]);
+                        renames.push((from, to));
+                    }
+                }
+                ScriptEffect::Open(path) => self.load_image_file(path),
+            }
+        }
+        // Everything a script renamed is undone in one step
+        if !renames.is_empty() {
+            self.undo_history
+                .push(FileOperation::BatchRename { renames });
+        }
+        self.show_status(&status.unwrap_or_else(|| format!("{} finished", title)));
+    }
+}
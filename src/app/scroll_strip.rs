@@ -0,0 +1,80 @@
+use super::{ImageViewerApp, ViewMode};
+use egui::Vec2;
+use std::ops::Range;
+
+/// View heights beyond the visible part of the strip that are loaded ahead;
+/// images further away than twice this are unloaded
+pub const STRIP_PRELOAD_SCREENS: f32 = 1.0;
+
+/// Top and display size of each image in the scroll strip, fitted to `width`.
+/// Images aren't enlarged past their own width; those whose size isn't known
+/// yet are shown square.
+pub fn strip_layout(sizes: &[Option<Vec2>], width: f32) -> Vec<(f32, Vec2)> {
+    let mut top = 0.0;
+    sizes
+        .iter()
+        .map(|size| {
+            let shown = match size {
+                Some(size) if size.x > 0.0 && size.y > 0.0 => {
+                    let fitted = width.min(size.x);
+                    Vec2::new(fitted, size.y * fitted / size.x)
+                }
+                _ => Vec2::splat(width),
+            };
+            let placed = (top, shown);
+            top += shown.y;
+            placed
+        })
+        .collect()
+}
+
+/// Images of the strip that overlap the band from `start` to `end`
+pub fn strip_range(layout: &[(f32, Vec2)], start: f32, end: f32) -> Range<usize> {
+    let first = layout.partition_point(|(top, size)| top + size.y <= start);
+    let last = layout.partition_point(|(top, _)| *top < end);
+    first..last.max(first)
+}
+
+impl ImageViewerApp {
+    pub fn toggle_scroll_view(&mut self) {
+        self.view_mode = match self.view_mode {
+            ViewMode::Scroll => ViewMode::Single,
+            _ => ViewMode::Scroll,
+        };
+        // Opens at the current image
+        self.strip_index = None;
+    }
+
+    /// Size of each image in the filtered list as far as it is known: from its
+    /// texture once loaded, otherwise the shape of its thumbnail at `width`
+    pub fn known_strip_sizes(&self, width: f32) -> Vec<Option<Vec2>> {
+        (0..self.filtered_list.len())
+            .map(|index| {
+                let path = self.path_at(index)?;
+                if let Some(size) = self.strip_sizes.get(&path) {
+                    return Some(*size);
+                }
+                let thumbnail = self.thumbnail_textures.get(&path)?.size_vec2();
+                Some(thumbnail * (width / thumbnail.x))
+            })
+            .collect()
+    }
+
+    /// Texture for the image at `index` in the strip, loading it if needed
+    pub fn strip_texture(&mut self, index: usize) -> Option<egui::TextureHandle> {
+        let path = self.path_at(index)?;
+        self.compare_texture(&path)?;
+        let texture = self.compare_textures.get(&path)?.clone();
+        self.strip_sizes.insert(path, texture.size_vec2());
+        Some(texture)
+    }
+
+    /// Load the images in `wanted` and unload everything outside `keep`
+    pub fn update_strip_textures(&mut self, wanted: Range<usize>, keep: Range<usize>) {
+        let kept: Vec<_> = keep.filter_map(|index| self.path_at(index)).collect();
+        self.retain_compare_textures(&kept);
+        for index in wanted {
+            self.strip_texture(index);
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use super::batch_export::{batch_output_paths, export_one, BatchExport};
+use super::file_ops::ExportOptions;
+use super::{ImageViewerApp, LoaderMessage};
+use crate::image_loader::ImageAdjustments;
+use crate::share::ShareService;
+use rayon::prelude::*;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// An export whose images are uploaded to a share target
+pub struct ShareUpload {
+    pub target: String,
+    /// Links to the uploaded images, in the order they finished
+    pub links: Vec<String>,
+}
+
+/// A share waiting for the target's secret key or header values, asked for
+/// once per session
+pub struct SecretPrompt {
+    pub target_index: usize,
+    pub sources: Vec<PathBuf>,
+    pub secret: String,
+}
+
+/// Where images are exported before uploading; each is removed once sent
+pub fn share_folder() -> PathBuf {
+    std::env::temp_dir().join("rView share")
+}
+
+impl ImageViewerApp {
+    /// Export images with their adjustments and upload them to the share
+    /// target at `target_index`, on the batch export queue
+    pub fn share_to(&mut self, target_index: usize, sources: Vec<PathBuf>) {
+        if self.batch_export.as_ref().is_some_and(|b| !b.is_finished()) {
+            self.show_status("Wait for the running export to finish");
+            return;
+        }
+        let Some(target) = self.settings.share_targets.get(target_index).cloned() else {
+            return;
+        };
+        if target.service.needs_secret() {
+            // Headers are filled in where their names were saved
+            let secret = match &target.service {
+                ShareService::Http(http) => http.headers.clone(),
+                _ => String::new(),
+            };
+            self.share_secret_prompt = Some(SecretPrompt {
+                target_index,
+                sources,
+                secret,
+            });
+            return;
+        }
+        // Pasted images aren't files yet
+        let sources: Vec<PathBuf> = sources.into_iter().filter(|p| p.is_file()).collect();
+        if sources.is_empty() {
+            self.show_status("No images to share");
+            return;
+        }
+
+        let folder = share_folder();
+        if let Err(e) = std::fs::create_dir_all(&folder) {
+            self.show_status(&format!("Couldn't prepare images for sharing: {}", e));
+            return;
+        }
+        let outputs = batch_output_paths(&sources, &folder, self.settings.export_format);
+        let jobs: Vec<(PathBuf, PathBuf, ImageAdjustments)> = sources
+            .into_iter()
+            .zip(outputs)
+            .map(|(source, output)| {
+                let adjustments = self.export_adjustments(&source);
+                (source, output, adjustments)
+            })
+            .collect();
+
+        let options = ExportOptions::from_settings(&self.settings);
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.batch_export = Some(BatchExport {
+            output_folder: folder,
+            total: jobs.len(),
+            completed: 0,
+            skipped: 0,
+            failed: Vec::new(),
+            cancel: Arc::clone(&cancel),
+            started: Instant::now(),
+            finished_in: None,
+            share: Some(ShareUpload {
+                target: target.name.clone(),
+                links: Vec::new(),
+            }),
+        });
+
+        let ctx = self.ctx.clone();
+        self.spawn_long_job(move |tx| {
+            jobs.into_par_iter()
+                .for_each_with(tx.clone(), |tx, (source, output, adjustments)| {
+                    if cancel.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    let result = export_one(&source, &output, &adjustments, &options)
+                        .and_then(|()| target.upload(&output));
+                    let _ = std::fs::remove_file(&output);
+                    let error = match result {
+                        Ok(link) => {
+                            if let Some(link) = link {
+                                let _ = tx.send(LoaderMessage::SharedLink(link));
+                            }
+                            None
+                        }
+                        Err(e) => Some(e),
+                    };
+                    let _ = tx.send(LoaderMessage::BatchExportProgress(source, error));
+                    if let Some(ctx) = &ctx {
+                        ctx.request_repaint();
+                    }
+                });
+            Some(LoaderMessage::BatchExportFinished)
+        });
+    }
+
+    /// Remember the secret entered in the prompt for this session and share
+    pub fn submit_share_secret(&mut self) {
+        let Some(prompt) = self.share_secret_prompt.take() else {
+            return;
+        };
+        let target = self.settings.share_targets.get_mut(prompt.target_index);
+        match target.map(|t| &mut t.service) {
+            Some(ShareService::Http(http)) => http.headers = prompt.secret,
+            Some(ShareService::S3(s3)) => s3.secret_key = prompt.secret,
+            _ => {}
+        }
+        self.share_to(prompt.target_index, prompt.sources);
+    }
+
+    pub fn handle_shared_link(&mut self, link: String) {
+        if let Some(share) = self.batch_export.as_mut().and_then(|b| b.share.as_mut()) {
+            share.links.push(link);
+        }
+    }
+
+    /// Put the links to the shared images on the clipboard, one per line
+    pub fn copy_share_links(&self) -> bool {
+        let Some(share) = self.batch_export.as_ref().and_then(|b| b.share.as_ref()) else {
+            return false;
+        };
+        if share.links.is_empty() {
+            return false;
+        }
+        let text = share.links.join("\n");
+        match arboard::Clipboard::new().and_then(|mut c| c.set_text(text)) {
+            Ok(()) => true,
+            Err(e) => {
+                log::warn!("Couldn't copy share links: {}", e);
+                false
+            }
+        }
+    }
+
+    pub fn handle_share_finished(&mut self) {
+        let Some(batch) = &self.batch_export else {
+            return;
+        };
+        let Some(share) = &batch.share else {
+            return;
+        };
+        let message = if batch.cancelled() {
+            format!(
+                "Sharing cancelled after {} of {} images",
+                batch.processed(),
+                batch.total
+            )
+        } else if !batch.failed.is_empty() {
+            format!(
+                "Shared {} images to {}, {} failed",
+                batch.completed,
+                share.target,
+                batch.failed.len()
+            )
+        } else {
+            format!("Shared {} images to {}", batch.completed, share.target)
+        };
+        let message = if self.copy_share_links() {
+            format!("{}; links copied", message)
+        } else {
+            message
+        };
+        self.show_status(&message);
+    }
+}
@@ -0,0 +1,72 @@
+use super::{ImageViewerApp, LoaderMessage};
+use crate::exif_data::ExifInfo;
+use crate::shoot_summary::{self, ShootSummary};
+use rayon::prelude::*;
+use std::path::PathBuf;
+
+impl ImageViewerApp {
+    pub fn open_shoot_summary(&mut self) {
+        self.show_shoot_summary_dialog = true;
+        self.refresh_shoot_summary();
+    }
+
+    /// Summarize the folder in the background, reusing EXIF the search index
+    /// has already read
+    pub fn refresh_shoot_summary(&mut self) {
+        if self.shoot_summary_pending || self.image_list.is_empty() {
+            return;
+        }
+
+        let images: Vec<_> = self
+            .image_list
+            .iter()
+            .map(|path| {
+                let metadata = self.metadata_db.get(path);
+                (path.clone(), metadata, self.search_exif.get(path).cloned())
+            })
+            .collect();
+        let folder = self.current_folder.clone();
+
+        self.shoot_summary_pending = true;
+        self.spawn_loader(move |_| {
+            let images: Vec<_> = images
+                .into_par_iter()
+                .map(|(path, metadata, exif)| {
+                    let bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                    let read = exif.is_none();
+                    let exif = exif.unwrap_or_else(|| ExifInfo::from_file(&path));
+                    (path, metadata, exif, bytes, read)
+                })
+                .collect();
+            let summary = shoot_summary::summarize(
+                images
+                    .iter()
+                    .map(|(_, metadata, exif, bytes, _)| (metadata, Some(exif), *bytes)),
+            );
+            let read = images
+                .into_iter()
+                .filter(|image| image.4)
+                .map(|(path, _, exif, _, _)| (path, exif))
+                .collect();
+            Some(LoaderMessage::ShootSummaryReady(folder, summary, read))
+        });
+    }
+
+    pub fn handle_shoot_summary_ready(
+        &mut self,
+        folder: Option<PathBuf>,
+        summary: ShootSummary,
+        exif: Vec<(PathBuf, ExifInfo)>,
+    ) {
+        self.shoot_summary_pending = false;
+        self.search_exif.extend(exif);
+        // Another folder was opened while this one was being summarized
+        if folder != self.current_folder {
+            if self.show_shoot_summary_dialog {
+                self.refresh_shoot_summary();
+            }
+            return;
+        }
+        self.shoot_summary = Some(summary);
+    }
+}
@@ -0,0 +1,166 @@
+use super::{ImageViewerApp, LoaderMessage};
+use crate::slideshow::{Playlist, PLAYLIST_EXTENSION};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// A slideshow being rendered to video in the background
+pub struct VideoExport {
+    pub output: PathBuf,
+    pub frames_written: usize,
+    pub total_frames: usize,
+    pub cancel: Arc<AtomicBool>,
+    /// Set once ffmpeg has finished, with the error if it failed
+    pub result: Option<Result<(), String>>,
+    /// Slides that couldn't be loaded and were left black
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+impl VideoExport {
+    pub fn is_finished(&self) -> bool {
+        self.result.is_some()
+    }
+}
+
+impl ImageViewerApp {
+    /// Add the selection, or the image on screen, to the end of the playlist
+    pub fn add_to_playlist(&mut self) {
+        let paths = self.selected_or_current_paths();
+        if paths.is_empty() {
+            return;
+        }
+        let added = self.playlist.add(paths);
+        self.show_status(&format!(
+            "Added {} to the slideshow playlist ({} slides)",
+            added,
+            self.playlist.slides.len()
+        ));
+    }
+
+    pub fn open_playlist_dialog(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .set_title("Open slideshow playlist")
+            .add_filter("Slideshow playlist", &[PLAYLIST_EXTENSION])
+            .pick_file()
+        else {
+            return;
+        };
+        match Playlist::load(&path) {
+            Ok(playlist) => {
+                self.show_status(&format!("Opened {} slides", playlist.slides.len()));
+                self.playlist = playlist;
+            }
+            Err(e) => self.show_status(&format!("Couldn't open the playlist: {}", e)),
+        }
+    }
+
+    pub fn save_playlist_dialog(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .set_title("Save slideshow playlist")
+            .add_filter("Slideshow playlist", &[PLAYLIST_EXTENSION])
+            .set_file_name(format!("Slideshow.{}", PLAYLIST_EXTENSION))
+            .save_file()
+        else {
+            return;
+        };
+        match self.playlist.save(&path) {
+            Ok(()) => self.show_status("Playlist saved"),
+            Err(e) => self.show_status(&format!("Couldn't save the playlist: {}", e)),
+        }
+    }
+
+    /// Ask where to save, then render the playlist to an MP4 in the background
+    pub fn start_video_export(&mut self) {
+        if self.video_export.as_ref().is_some_and(|v| !v.is_finished()) {
+            return;
+        }
+        if self.playlist.slides.is_empty() {
+            self.show_status("The slideshow playlist is empty");
+            return;
+        }
+        let Some(output) = rfd::FileDialog::new()
+            .set_title("Export slideshow video")
+            .add_filter("MP4 video", &["mp4"])
+            .set_file_name("Slideshow.mp4")
+            .save_file()
+        else {
+            return;
+        };
+
+        let playlist = self.playlist.clone();
+        let options = self.settings.slideshow_video.clone();
+        let slides: Vec<_> = playlist
+            .slides
+            .iter()
+            .map(|path| (path.clone(), self.export_adjustments(path)))
+            .collect();
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.video_export = Some(VideoExport {
+            output: output.clone(),
+            frames_written: 0,
+            total_frames: playlist.frame_count(options.fps),
+            cancel: Arc::clone(&cancel),
+            result: None,
+            failed: Vec::new(),
+        });
+
+        let ctx = self.ctx.clone();
+        self.spawn_long_job(move |tx| {
+            let mut reported = 0;
+            let result = crate::slideshow::render_video(
+                &playlist,
+                slides,
+                &options,
+                &output,
+                &cancel,
+                |written, total| {
+                    // About a hundred updates over the whole render
+                    if written - reported >= (total / 100).max(1) || written == total {
+                        reported = written;
+                        let _ = tx.send(LoaderMessage::VideoExportProgress(written));
+                        if let Some(ctx) = &ctx {
+                            ctx.request_repaint();
+                        }
+                    }
+                },
+            )
+            .map_err(|e| e.to_string());
+            Some(LoaderMessage::VideoExportFinished(result))
+        });
+    }
+
+    pub fn cancel_video_export(&mut self) {
+        if let Some(export) = &self.video_export {
+            export.cancel.store(true, Ordering::Relaxed);
+        }
+    }
+
+    pub fn handle_video_export_progress(&mut self, frames_written: usize) {
+        if let Some(export) = &mut self.video_export {
+            export.frames_written = frames_written;
+        }
+    }
+
+    pub fn handle_video_export_finished(&mut self, result: Result<Vec<(PathBuf, String)>, String>) {
+        let Some(export) = &mut self.video_export else {
+            return;
+        };
+        let status = match result {
+            Ok(failed) => {
+                for (path, error) in &failed {
+                    log::warn!("Slideshow video left out {}: {}", path.display(), error);
+                }
+                export.failed = failed;
+                export.result = Some(Ok(()));
+                "Slideshow video exported".to_string()
+            }
+            Err(e) => {
+                log::error!("Slideshow video failed: {}", e);
+                let status = format!("Slideshow video failed: {}", e);
+                export.result = Some(Err(e));
+                status
+            }
+        };
+        self.show_status(&status);
+    }
+}
@@ -0,0 +1,60 @@
+use super::ImageViewerApp;
+use crate::color_management::{SoftProof, SoftProofSettings};
+use std::path::Path;
+use std::sync::Arc;
+
+impl ImageViewerApp {
+    /// Turn the print simulation on or off for the main view
+    pub fn toggle_soft_proof(&mut self) {
+        if !self.soft_proof_enabled && self.settings.soft_proof.profile.is_none() {
+            self.show_status("Choose a printer profile in the Soft Proofing panel first");
+            return;
+        }
+        self.soft_proof_enabled = !self.soft_proof_enabled;
+        self.soft_proof_cache.clear();
+        self.show_status(if self.soft_proof_enabled {
+            "Soft proofing on"
+        } else {
+            "Soft proofing off"
+        });
+        self.refresh_adjustments_internal(true);
+    }
+
+    pub fn set_soft_proof_settings(&mut self, settings: SoftProofSettings) {
+        if settings == self.settings.soft_proof {
+            return;
+        }
+        self.settings.soft_proof = settings;
+        self.settings.save();
+        if self.soft_proof_enabled {
+            self.refresh_adjustments_internal(true);
+        }
+    }
+
+    /// The soft proof to show `path` through, or None when proofing is off.
+    /// The display transform still applies inside the proof.
+    pub fn display_soft_proof(&mut self, path: &Path) -> Option<Arc<SoftProof>> {
+        if !self.soft_proof_enabled {
+            return None;
+        }
+        let to_display = self.display_transform(path);
+        let display_profile = self
+            .settings
+            .color_management_enabled
+            .then_some(self.settings.display_icc_profile.as_deref())
+            .flatten();
+        match self.soft_proof_cache.get(
+            path,
+            &self.settings.soft_proof,
+            display_profile,
+            to_display,
+        ) {
+            Ok(proof) => proof,
+            Err(e) => {
+                log::warn!("Soft proof unavailable: {}", e);
+                self.show_status(&format!("Soft proof unavailable: {}", e));
+                None
+            }
+        }
+    }
+}
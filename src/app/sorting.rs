@@ -1,5 +1,35 @@
-use super::ImageViewerApp;
-use std::path::Path;
+use super::raw_pairs::raw_jpeg_pairs;
+use super::{ImageViewerApp, LoaderMessage};
+use crate::exif_data::ExifInfo;
+use crate::metadata::{CaptureDate, ImageMetadata};
+use crate::search::SearchQuery;
+use crate::settings::Settings;
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Whether an image gets through the rating, color label and file type
+/// choices in the filter bar
+pub fn passes_filter_bar(settings: &Settings, path: &Path, metadata: &ImageMetadata) -> bool {
+    if metadata.rating < settings.filter_by_rating {
+        return false;
+    }
+    if !settings.filter_by_color.is_empty()
+        && !settings.filter_by_color.contains(&metadata.color_label)
+    {
+        return false;
+    }
+    if !settings.filter_extensions.is_empty() {
+        let ext = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if !settings.filter_extensions.contains(&ext) {
+            return false;
+        }
+    }
+    true
+}
 
 fn compare_paths_by_mode(
     a: &Path,
@@ -18,7 +48,7 @@ fn compare_paths_by_mode(
                 .unwrap_or_default();
             natord::compare(&a_name, &b_name)
         }
-        crate::settings::SortMode::Date | crate::settings::SortMode::DateTaken => {
+        crate::settings::SortMode::Date => {
             let a_time = a.metadata().and_then(|m| m.modified()).ok();
             let b_time = b.metadata().and_then(|m| m.modified()).ok();
             a_time.cmp(&b_time)
@@ -53,15 +83,90 @@ fn compare_paths_by_mode(
     }
 }
 
+/// Numeric sort key from an image's EXIF, None when it doesn't have the value
+pub fn exif_sort_key(exif: &ExifInfo, sort_mode: crate::settings::SortMode) -> Option<f64> {
+    use crate::search::leading_number;
+    use crate::settings::SortMode;
+    match sort_mode {
+        SortMode::Iso => leading_number(exif.iso.as_deref()?),
+        SortMode::FocalLength => leading_number(exif.focal_length.as_deref()?),
+        SortMode::Dimensions | SortMode::AspectRatio => {
+            // Stored as "6000 × 4000"
+            let (width, height) = exif.dimensions.as_deref()?.split_once('×')?;
+            let (mut width, mut height) = (leading_number(width)?, leading_number(height)?);
+            // Orientations 5 to 8 turn the picture on its side
+            if exif.orientation.is_some_and(|o| (5..=8).contains(&o)) {
+                std::mem::swap(&mut width, &mut height);
+            }
+            if height == 0.0 {
+                return None;
+            }
+            Some(if sort_mode == SortMode::Dimensions {
+                width * height
+            } else {
+                width / height
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Sort by an EXIF value, ties by name. Images missing it, or not indexed
+/// yet, go last; returns how many of those there are.
+pub fn sort_by_exif(
+    paths: &mut [PathBuf],
+    exif: &HashMap<PathBuf, ExifInfo>,
+    sort_mode: crate::settings::SortMode,
+) -> usize {
+    let key = |path: &PathBuf| exif.get(path).and_then(|e| exif_sort_key(e, sort_mode));
+    paths.sort_by(|a, b| match (key(a), key(b)) {
+        (Some(x), Some(y)) => x
+            .total_cmp(&y)
+            .then_with(|| compare_paths_by_mode(a, b, crate::settings::SortMode::Name)),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => compare_paths_by_mode(a, b, crate::settings::SortMode::Name),
+    });
+    paths.iter().filter(|p| key(p).is_none()).count()
+}
+
+/// When a photo was taken by its cached EXIF date. Files without one, or
+/// not read yet, go by their modification time instead.
+pub fn date_taken(
+    path: &Path,
+    dates: &HashMap<PathBuf, CaptureDate>,
+) -> Option<chrono::NaiveDateTime> {
+    let file = path.metadata().ok()?;
+    let taken = dates
+        .get(path)
+        .filter(|date| date.size == file.len())
+        .and_then(|date| date.taken);
+    taken.or_else(|| {
+        let modified: chrono::DateTime<chrono::Local> = file.modified().ok()?.into();
+        Some(modified.naive_local())
+    })
+}
+
+pub fn sort_by_date_taken(paths: &mut [PathBuf], dates: &HashMap<PathBuf, CaptureDate>) {
+    paths.sort_by_cached_key(|path| date_taken(path, dates));
+}
+
 impl ImageViewerApp {
     pub fn sort_images(&mut self) {
         let current_path = self.get_current_path();
         let sort_mode = self.settings.sort_mode;
+        let mut missing_exif = 0;
 
         if matches!(sort_mode, crate::settings::SortMode::Random) {
             use rand::seq::SliceRandom;
             let mut rng = rand::thread_rng();
             self.image_list.shuffle(&mut rng);
+        } else if matches!(sort_mode, crate::settings::SortMode::DateTaken) {
+            self.request_capture_dates();
+            sort_by_date_taken(&mut self.image_list, &self.metadata_db.capture_dates);
+        } else if sort_mode.needs_exif() {
+            self.request_search_exif();
+            missing_exif = sort_by_exif(&mut self.image_list, &self.search_exif, sort_mode);
         } else {
             self.image_list
                 .sort_by(|a, b| compare_paths_by_mode(a, b, sort_mode));
@@ -69,6 +174,8 @@ impl ImageViewerApp {
 
         if !self.settings.sort_ascending {
             self.image_list.reverse();
+            // Images without the EXIF value stay at the end either way
+            self.image_list.rotate_left(missing_exif);
         }
 
         // Restore selection
@@ -82,20 +189,68 @@ impl ImageViewerApp {
     pub fn apply_filter(&mut self) {
         self.filtered_list.clear();
 
+        let query = SearchQuery::parse(&self.search_query);
+        let collection = self
+            .active_collection
+            .and_then(|i| self.settings.smart_collections.get(i))
+            .cloned();
+        if query.needs_exif() || collection.as_ref().is_some_and(|c| c.needs_exif()) {
+            self.request_search_exif();
+        }
+        let no_metadata = ImageMetadata::default();
+        self.raw_pairs = if self.settings.pair_raw_jpeg {
+            raw_jpeg_pairs(&self.image_list, self.settings.pair_preference)
+        } else {
+            HashMap::new()
+        };
+        let paired: HashSet<&PathBuf> = self.raw_pairs.values().collect();
+
         for (idx, path) in self.image_list.iter().enumerate() {
-            let _metadata = self.metadata_db.get(path);
+            // The other half of a RAW+JPEG pair is listed instead
+            if paired.contains(path) {
+                continue;
+            }
 
-            // Filter by search query
-            if !self.search_query.is_empty() {
-                let filename = path
-                    .file_name()
-                    .map(|n| n.to_string_lossy().to_lowercase())
-                    .unwrap_or_default();
-                if !filename.contains(&self.search_query.to_lowercase()) {
+            // Filter by keyword
+            if let Some(keyword) = &self.keyword_filter {
+                if !self.metadata_db.has_keyword(path, keyword) {
                     continue;
                 }
             }
 
+            let metadata = self.metadata_db.images.get(path).unwrap_or(&no_metadata);
+            let exif = self.search_exif.get(path);
+
+            // Filter by pick/reject flag
+            if self.flag_filter.is_some_and(|flag| metadata.flag() != flag) {
+                continue;
+            }
+
+            // Filter by the day picked in the timeline
+            if let Some(day) = self.date_filter {
+                let taken = date_taken(path, &self.metadata_db.capture_dates);
+                if taken.map(|t| t.date()) != Some(day) {
+                    continue;
+                }
+            }
+
+            // Filter by rating, color label and file type
+            if !passes_filter_bar(&self.settings, path, metadata) {
+                continue;
+            }
+
+            // Filter by smart collection
+            if let Some(collection) = &collection {
+                if !collection.matches(metadata, exif) {
+                    continue;
+                }
+            }
+
+            // Filter by search query
+            if !query.is_empty() && !query.matches(path, metadata, exif) {
+                continue;
+            }
+
             self.filtered_list.push(idx);
         }
 
@@ -105,6 +260,158 @@ impl ImageViewerApp {
         }
     }
 
+    /// Whether any of the filter bar's rating, label, flag or file type filters is set
+    pub fn filter_bar_active(&self) -> bool {
+        self.settings.filter_by_rating > 0
+            || !self.settings.filter_by_color.is_empty()
+            || !self.settings.filter_extensions.is_empty()
+            || self.flag_filter.is_some()
+            || self.date_filter.is_some()
+    }
+
+    pub fn clear_filter_bar(&mut self) {
+        self.settings.filter_by_rating = 0;
+        self.settings.filter_by_color.clear();
+        self.settings.filter_extensions.clear();
+        self.flag_filter = None;
+        self.date_filter = None;
+        self.refilter_keeping_current();
+    }
+
+    /// Lowercase extensions of the images in the folder, for the file type filter
+    pub fn folder_extensions(&self) -> Vec<String> {
+        let mut extensions: Vec<String> = self
+            .image_list
+            .iter()
+            .filter_map(|p| p.extension())
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .collect();
+        extensions.sort();
+        extensions.dedup();
+        extensions
+    }
+
+    /// Re-run the filter, staying on the current image if it still matches
+    pub fn refilter_keeping_current(&mut self) {
+        let previous = self.get_current_path();
+        self.refilter_keeping(previous);
+    }
+
+    /// Re-run the filter and move back to `previous` if it is still listed,
+    /// loading whatever image ends up current otherwise
+    pub fn refilter_keeping(&mut self, previous: Option<PathBuf>) {
+        self.apply_filter();
+        if let Some(previous) = previous {
+            if let Some(pos) = self
+                .filtered_list
+                .iter()
+                .position(|&i| self.image_list[i] == previous)
+            {
+                self.current_index = pos;
+            }
+        }
+        if self.get_current_path() != previous {
+            self.load_current_image();
+        }
+    }
+
+    /// Read EXIF in the background for images the search index hasn't seen yet
+    fn request_search_exif(&mut self) {
+        if self.search_exif_pending {
+            return;
+        }
+        let missing: Vec<PathBuf> = self
+            .image_list
+            .iter()
+            .filter(|p| !self.search_exif.contains_key(*p))
+            .cloned()
+            .collect();
+        if missing.is_empty() {
+            return;
+        }
+
+        self.search_exif_pending = true;
+        self.show_status(&format!("Indexing EXIF for {} images...", missing.len()));
+        self.spawn_loader(move |_| {
+            let entries = missing
+                .into_par_iter()
+                .map(|path| {
+                    let exif = ExifInfo::from_file(&path);
+                    (path, exif)
+                })
+                .collect();
+            Some(LoaderMessage::SearchExifIndexed(entries))
+        });
+    }
+
+    pub fn handle_search_exif_indexed(&mut self, entries: Vec<(PathBuf, ExifInfo)>) {
+        self.search_exif_pending = false;
+        self.search_exif.extend(entries);
+        let collection_needs_exif = self
+            .active_collection
+            .and_then(|i| self.settings.smart_collections.get(i))
+            .is_some_and(|c| c.needs_exif());
+        if self.settings.sort_mode.needs_exif() {
+            // Also picks up images the folder scan found while these were read
+            let current = self.get_current_path();
+            self.sort_images();
+            self.refilter_keeping(current);
+        } else if SearchQuery::parse(&self.search_query).needs_exif() || collection_needs_exif {
+            self.refilter_keeping_current();
+        }
+    }
+
+    /// Read EXIF dates in the background for images without an up to date
+    /// entry in the catalog's cache
+    pub fn request_capture_dates(&mut self) {
+        if self.capture_dates_pending {
+            return;
+        }
+        let dates = &self.metadata_db.capture_dates;
+        let missing: Vec<PathBuf> = self
+            .image_list
+            .iter()
+            .filter(|path| {
+                let size = path.metadata().map(|m| m.len()).ok();
+                size.is_some() && dates.get(*path).map(|date| date.size) != size
+            })
+            .cloned()
+            .collect();
+        if missing.is_empty() {
+            return;
+        }
+
+        self.capture_dates_pending = true;
+        self.spawn_loader(move |_| {
+            let entries = missing
+                .into_par_iter()
+                .filter_map(|path| {
+                    let size = path.metadata().ok()?.len();
+                    let taken = crate::exif_data::capture_date(&path);
+                    Some((path, CaptureDate { size, taken }))
+                })
+                .collect();
+            Some(LoaderMessage::CaptureDatesRead(entries))
+        });
+    }
+
+    pub fn handle_capture_dates_read(&mut self, entries: Vec<(PathBuf, CaptureDate)>) {
+        self.capture_dates_pending = false;
+        self.metadata_db.capture_dates.extend(entries);
+        self.metadata_db.save();
+        if self.settings.sort_mode == crate::settings::SortMode::DateTaken {
+            // Also picks up images the folder scan found while these were read
+            let current = self.get_current_path();
+            self.sort_images();
+            self.refilter_keeping(current);
+        } else if self.date_filter.is_some() {
+            self.refilter_keeping_current();
+        }
+        if self.show_timeline_dialog {
+            self.refresh_timeline();
+        }
+    }
+
     pub fn sort_file_list(&mut self) {
         self.sort_images();
         self.apply_filter();
@@ -1,6 +1,8 @@
+use crate::cli::CliArgs;
+use crate::color_management::{DisplayTransform, SoftProofCache, TransformCache};
 use crate::exif_data::ExifInfo;
 use crate::image_cache::ImageCache;
-use crate::image_loader::ImageAdjustments;
+use crate::image_loader::{AnimationFrame, ImageAdjustments};
 use crate::metadata::{MetadataDb, UndoHistory};
 use crate::profiler::{CacheStats, LoadingDiagnostics};
 use crate::settings::Settings;
@@ -11,33 +13,152 @@ use image::DynamicImage;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
 use std::sync::mpsc::{channel, Receiver, Sender};
 use std::sync::Arc;
 
+use crate::gpu::tiles::{TilePyramid, TiledImage};
 use crate::gpu::types::GpuProcessor;
 
+use super::animation::AnimationPlayback;
+use super::batch_export::BatchExport;
+use super::batch_metadata::MetadataSession;
+use super::compare::MAX_COMPARE;
+use super::export_preview::{EncodedPreview, ExportPreview};
+use super::folder_scan::FolderScan;
+use super::fullscreen::FullscreenMove;
+use super::mini_viewer::MiniViewer;
+use super::recently_deleted::DeletedFile;
+use super::reference_overlay::ReferenceOverlay;
+use super::rename::RenameSession;
+use super::slideshow::VideoExport;
+use super::tether::Tether;
+use super::tray::TrayState;
+use super::url_open::UrlDownload;
+use super::web_gallery::WebGalleryResult;
+
 pub enum LoaderMessage {
     ImageLoaded(PathBuf, DynamicImage),
     PreviewLoaded(PathBuf, DynamicImage),
-    ProgressiveLoaded(PathBuf, DynamicImage),
+    /// A low resolution pass of a file still loading, with its full size
+    ProgressiveLoaded(PathBuf, DynamicImage, [u32; 2]),
     ThumbnailLoaded(PathBuf, DynamicImage),
+    CompareTextureLoaded(PathBuf, egui::TextureHandle),
+    /// Alignment of the second image to the first, for compare mode
+    CompareAligned(PathBuf, PathBuf, Option<crate::image_loader::Alignment>),
+    /// PSNR and SSIM of the second image against the first, for compare mode
+    CompareQuality(
+        PathBuf,
+        PathBuf,
+        Result<crate::image_loader::QualityScores, String>,
+    ),
+    OriginalTextureLoaded(PathBuf, [u32; 2], egui::TextureHandle),
+    /// Reference picked for the overlay, downscaled, with its texture
+    OverlayReferenceLoaded(PathBuf, Result<(DynamicImage, egui::TextureHandle), String>),
+    /// Difference of the image with the given size and the overlay reference
+    OverlayDifferenceReady(PathBuf, [u32; 2], egui::TextureHandle),
+    /// File size and preview crops for the export dialog's options
+    ExportPreviewEncoded(Box<Result<EncodedPreview, String>>),
     ThumbnailRequestComplete(PathBuf),
     LoadError(PathBuf, String),
     ExifLoaded(PathBuf, Box<ExifInfo>),
     TextureCreated(PathBuf, egui::TextureHandle, DynamicImage),
+    TilesReady(PathBuf, Arc<TilePyramid>),
+    /// Display pass pixels at the given scale of the image (see display_proxy)
+    DisplaySourceReady(PathBuf, f32, Arc<crate::gpu::display::DisplayUpload>),
     HistogramUpdated(Vec<Vec<u32>>),
+    /// Histogram of the preview adjustments, computed mid-drag
+    LiveHistogramUpdated(Vec<Vec<u32>>),
+    /// Scopes computed for the request with the given generation
+    ScopesUpdated(u64, Box<crate::image_loader::ScopeData>),
+    AnimationLoaded(PathBuf, Vec<AnimationFrame>),
+    VideoLoaded(PathBuf, crate::image_loader::VideoInfo),
+    /// A single frame decoded after scrubbing a paused video
+    VideoFrame(PathBuf, std::time::Duration, DynamicImage),
+    XmpLoaded(Vec<(PathBuf, crate::xmp::XmpData)>),
+    SearchExifIndexed(Vec<(PathBuf, ExifInfo)>),
+    /// Catalogued images matching the smart collection at the given index
+    CollectionMatched(usize, Vec<(PathBuf, Option<ExifInfo>)>),
+    /// Images created or rewritten in a watched folder, or found there at startup
+    WatchedFilesArrived(Vec<PathBuf>),
+    WatchedFilesImported(
+        Vec<(
+            PathBuf,
+            crate::metadata::CaptureDate,
+            Option<crate::xmp::XmpData>,
+        )>,
+    ),
+    AllMetadataLoaded(PathBuf, Vec<crate::exif_data::MetadataTag>),
+    /// Summary of the given folder, with the EXIF read to compute it
+    ShootSummaryReady(
+        Option<PathBuf>,
+        crate::shoot_summary::ShootSummary,
+        Vec<(PathBuf, ExifInfo)>,
+    ),
+    FolderChanged(Vec<PathBuf>),
+    /// Images found so far by the folder scan with the given id
+    FolderScanned(u64, Vec<PathBuf>),
+    FolderScanFinished(u64),
+    BatchExportProgress(PathBuf, Option<String>),
+    /// The export of this image already existed and was left alone
+    BatchExportSkipped(PathBuf),
+    BatchExportFinished,
+    /// Link to an image uploaded from the Share menu
+    SharedLink(String),
+    /// A command from a phone, tablet or button box
+    RemoteCommand(crate::remote_control::RemoteRequest),
+    /// Title of a script that ended, with the changes it asked for
+    ScriptFinished(String, Result<Vec<crate::scripting::ScriptEffect>, String>),
+    ContactSheetFinished(Result<Vec<PathBuf>, String>),
+    WebGalleryFinished(Result<WebGalleryResult, String>),
+    /// Frames of the slideshow video written so far
+    VideoExportProgress(usize),
+    /// Slides left out of the finished video, or why it failed
+    VideoExportFinished(Result<Vec<(PathBuf, String)>, String>),
+    /// Estimated tempo of a slideshow music track
+    MusicTempo(PathBuf, Option<f32>),
+    RenameInfoLoaded(Vec<(PathBuf, crate::rename::RenameInfo)>),
+    /// Path given to a later launch of rView, or None to just raise the
+    /// window, and the options it was given
+    OpenedFromLaunch(Option<PathBuf>, crate::cli::CliArgs),
+    /// Item picked in the tray icon's menu
+    TrayCommand(crate::tray::TrayCommand),
+    /// Bytes received so far and the size, for the URL being downloaded
+    UrlDownloadProgress(String, u64, Option<u64>),
+    UrlDownloaded(String, Result<PathBuf, String>),
+    /// EXIF dates for sorting by date taken
+    CaptureDatesRead(Vec<(PathBuf, crate::metadata::CaptureDate)>),
+    MetadataInfoLoaded(Vec<(PathBuf, crate::rename::RenameInfo)>),
+    /// Files the batch metadata editor wrote, with the error for each failure
+    MetadataWritten(Vec<(PathBuf, Option<String>)>),
+    AutoEnhanced(Vec<(PathBuf, crate::image_loader::AutoEnhance)>),
     MoveCompleted {
-        from: PathBuf,
+        moved: Vec<(PathBuf, PathBuf)>,
         dest_folder: PathBuf,
-        success: bool,
         error: Option<String>,
     },
+    DngConversionFinished {
+        converted: Vec<(PathBuf, PathBuf)>,
+        failed: Vec<(PathBuf, String)>,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ViewMode {
     Single,
     Compare,
+    /// The filtered list stacked in one vertical strip
+    Scroll,
+}
+
+/// Wipe between the original and the adjusted image in the single view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitView {
+    Off,
+    /// Original on the left
+    Vertical,
+    /// Original on top
+    Horizontal,
 }
 
 pub struct ImageViewerApp {
@@ -47,9 +168,11 @@ pub struct ImageViewerApp {
     // Metadata database
     pub metadata_db: MetadataDb,
 
-    // Tabs removed
+    // Open tabs; the active tab's data lives in the fields below
+    pub tabs: Vec<super::tabs::ImageTab>,
+    pub active_tab: usize,
 
-    // Current tab's data (for backward compatibility)
+    // Current tab's data
     pub image_list: Vec<PathBuf>,
     pub filtered_list: Vec<usize>, // Indices into image_list
     pub current_index: usize,
@@ -60,20 +183,113 @@ pub struct ImageViewerApp {
 
     // Current image state
     pub current_texture: Option<TextureHandle>,
-    pub current_image: Option<DynamicImage>,
+    pub current_image: Option<Arc<DynamicImage>>,
     pub current_exif: Option<ExifInfo>,
     pub histogram_data: Option<Vec<Vec<u32>>>,
+    /// When the last histogram was started during a slider drag, and whether
+    /// it's still running
+    pub live_histogram_time: std::time::Instant,
+    pub live_histogram_pending: bool,
+    pub scope_data: Option<crate::image_loader::ScopeData>,
+    pub scope_texture: Option<super::scopes::ScopeTexture>,
+    /// Bumped with every scopes request so late results are dropped
+    pub scope_generation: u64,
+    pub scopes_pending: bool,
 
     // EXIF data cached for arbitrary paths (used for compare and overlays)
     pub compare_exifs: std::collections::HashMap<PathBuf, ExifInfo>,
+    // EXIF for every image in the folder, read once when a search first needs it
+    pub search_exif: HashMap<PathBuf, ExifInfo>,
+    pub search_exif_pending: bool,
+    // Imports from the watched folders in settings
+    pub catalog_watcher: Option<notify::RecommendedWatcher>,
+    pub pending_imports: HashSet<PathBuf>,
+    pub imports_due: Option<std::time::Instant>,
+    /// Set while capture dates are read for sorting by date taken
+    pub capture_dates_pending: bool,
+    // Background listing of the current folder
+    pub folder_scan: Option<FolderScan>,
+    pub folder_scan_generation: u64,
+    // RAW+JPEG pairs in the folder: listed file -> the file hidden behind it
+    pub raw_pairs: HashMap<PathBuf, PathBuf>,
+    pub dng_converting: bool,
+    // Live updates for the current folder
+    pub folder_watcher: Option<notify::RecommendedWatcher>,
+    pub pending_folder_changes: HashSet<PathBuf>,
+    pub folder_changes_due: Option<std::time::Instant>,
+    /// Files rView is rewriting itself, with when the change events that
+    /// causes stop being ignored; None while the write is still running
+    pub own_writes: Arc<std::sync::Mutex<HashMap<PathBuf, Option<std::time::Instant>>>>,
     pub is_loading: bool,
     pub load_error: Option<String>,
     /// Tracks if we're showing a preview (not full resolution)
     pub showing_preview: bool,
+    /// Full size of the image a progressive pass in `current_texture` stands in for
+    pub progressive_size: Option<Vec2>,
+    /// Set to stop the progressive decode of a file that is no longer wanted
+    pub progressive_cancel: Option<Arc<AtomicBool>>,
+    /// Frame textures and timing when the current file is an animated GIF/APNG/WebP
+    pub animation: Option<AnimationPlayback>,
+    /// Length, position and decoded frames when the current file is a video
+    pub video: Option<super::video::VideoPlayback>,
+    /// Background export of several images; kept after finishing to show the summary
+    pub batch_export: Option<BatchExport>,
+    pub share_secret_prompt: Option<super::share::SecretPrompt>,
+    pub show_contact_sheet_dialog: bool,
+    /// Set while a contact sheet renders in the background
+    pub contact_sheet_running: bool,
+    pub show_web_gallery_dialog: bool,
+    pub web_gallery_running: bool,
+    /// Outcome of the last web gallery export, shown until the dialog is closed
+    pub web_gallery_result: Option<WebGalleryResult>,
+    /// Slideshow being put together across folders
+    pub playlist: crate::slideshow::Playlist,
+    pub show_playlist_dialog: bool,
+    /// Playlist being rendered to video; kept after finishing to show the outcome
+    pub video_export: Option<VideoExport>,
+    /// Open batch rename dialog
+    pub rename_session: Option<RenameSession>,
+    /// Open batch metadata dialog
+    pub metadata_session: Option<MetadataSession>,
+    /// Files deleted this session, oldest first, for undo and the restore dialog
+    pub recently_deleted: Vec<DeletedFile>,
+    pub show_recently_deleted_dialog: bool,
+    pub show_shoot_summary_dialog: bool,
+    pub show_all_metadata_dialog: bool,
+    pub show_timeline_dialog: bool,
+    /// Whether the timeline covers every dated image in the catalog instead of the folder
+    pub timeline_catalog: bool,
+    pub timeline: crate::timeline::Timeline,
+    pub show_open_url_dialog: bool,
+    pub open_url_text: String,
+    pub url_download: Option<UrlDownload>,
+    pub show_reference_overlay_dialog: bool,
+    /// The current image laid semi-transparently over another or the desktop
+    pub reference_overlay: Option<ReferenceOverlay>,
+    /// Open export dialog with its before and after preview
+    pub export_preview: Option<ExportPreview>,
+    /// Every tag of the image the metadata browser was last filled for
+    pub all_metadata: Option<(PathBuf, Vec<crate::exif_data::MetadataTag>)>,
+    pub all_metadata_pending: Option<PathBuf>,
+    pub all_metadata_query: String,
+    /// Show tag identifiers and stored values instead of names and formatted values
+    pub all_metadata_raw: bool,
+    /// Summary of the current folder, None until computed
+    pub shoot_summary: Option<crate::shoot_summary::ShootSummary>,
+    pub shoot_summary_pending: bool,
+    /// Command whose shortcut the settings dialog is waiting to record
+    pub keymap_recording: Option<String>,
+    /// Bumped when a workspace is applied so docked panels pick up its sizes
+    pub layout_generation: u64,
 
     // Overlays
-    pub focus_peaking_texture: Option<TextureHandle>,
-    pub zebra_texture: Option<TextureHandle>,
+    pub focus_peaking_texture: Option<super::overlays::OverlayTexture>,
+    pub zebra_texture: Option<super::overlays::OverlayTexture>,
+    /// Focus peaking and zebra shaders on eframe's wgpu device, when it has one
+    pub gpu_overlays: Option<crate::gpu::overlays::OverlayRenderer>,
+    /// Compare difference shader on eframe's wgpu device, when it has one
+    pub gpu_difference: Option<crate::gpu::difference::DifferenceRenderer>,
+    pub clipping_texture: Option<TextureHandle>,
     pub custom_overlay_texture: Option<TextureHandle>,
     pub frame_texture: Option<TextureHandle>,
 
@@ -90,13 +306,35 @@ pub struct ImageViewerApp {
     pub crop_rect: Option<egui::Rect>,      // Current crop rectangle in image coordinates
     pub crop_start_pos: Option<egui::Pos2>, // Starting position for crop drag
 
+    // Straighten state
+    pub straighten_mode: bool, // Whether the horizon line tool is active
+    pub straighten_line: Option<(egui::Pos2, egui::Pos2)>, // Line being drawn, in screen coordinates
+
+    // Local adjustment state
+    pub local_mask_tool: Option<crate::image_loader::LocalMaskKind>, // Gradient the next drag draws
+    pub local_mask_drag: Option<([f32; 2], ImageAdjustments)>, // Drag start (image-relative) and the adjustments before it
+    pub pen_pressure: f32, // Last pen pressure of the brush stroke, 1.0 without a pen
+    pub selected_local_adjustment: Option<usize>, // Mask shown on the image and edited in the panel
+
     // Adjustments
     pub adjustments: ImageAdjustments,
     pub current_film_preset: crate::image_loader::FilmPreset,
     pub show_original: bool, // Before/After toggle
+    pub split_view: SplitView,
+    pub split_position: f32, // Fraction of the view the original covers
+    // Unadjusted texture for the split view, keyed by path and source size so
+    // rotating or cropping rebuilds it
+    pub original_texture: Option<(PathBuf, [u32; 2], TextureHandle)>,
+    pub original_texture_pending: Option<(PathBuf, [u32; 2])>,
     pub last_adjustment_time: std::time::Instant,
     pub adjustments_dirty: bool, // Flag to indicate adjustments need to be applied
     pub slider_dragging: bool,   // True while user is actively dragging a slider
+    pub copied_adjustments: Option<ImageAdjustments>, // Adjustments clipboard for copy/paste
+    /// Bitmaps pasted from the clipboard, by the name they're listed under until saved
+    pub pasted_images: HashMap<PathBuf, DynamicImage>,
+    /// Files an in-app drag carries if it leaves the window
+    pub drag_out: Option<Vec<PathBuf>>,
+    pub adjustments_path: Option<PathBuf>, // Image the in-memory adjustments belong to
     pub pre_drag_adjustments: Option<ImageAdjustments>, // Adjustments before drag started (for undo)
 
     // Cached data
@@ -105,7 +343,24 @@ pub struct ImageViewerApp {
     pub texture_access_order: VecDeque<String>, // LRU order tracking
     pub thumbnail_textures: HashMap<PathBuf, egui::TextureHandle>,
     pub thumbnail_requests: HashSet<PathBuf>,
-    pub compare_large_preview_requests: HashSet<PathBuf>,
+    /// Thumbnails whose histogram shows significant clipping
+    pub thumbnail_exposure: HashMap<PathBuf, crate::image_loader::ExposureWarning>,
+    /// Full-resolution textures for the images shown in compare mode
+    pub compare_textures: HashMap<PathBuf, egui::TextureHandle>,
+    pub compare_requests: HashSet<PathBuf>,
+    /// Sizes of the images seen in the scroll strip, kept after they unload
+    /// so the strip doesn't jump around
+    pub strip_sizes: HashMap<PathBuf, Vec2>,
+    /// Index the scroll strip last showed as current
+    pub strip_index: Option<usize>,
+    /// Alignment of each compared image to the first, keyed by (first, other);
+    /// None when the frames didn't match
+    pub compare_alignments: HashMap<(PathBuf, PathBuf), Option<crate::image_loader::Alignment>>,
+    pub compare_alignment_requests: HashSet<(PathBuf, PathBuf)>,
+    /// Quality of each compared image against the first, keyed by (first, other)
+    pub compare_quality:
+        HashMap<(PathBuf, PathBuf), Result<crate::image_loader::QualityScores, String>>,
+    pub compare_quality_requests: HashSet<(PathBuf, PathBuf)>,
 
     // File tree state
     pub expanded_dirs: HashSet<PathBuf>,
@@ -116,6 +371,23 @@ pub struct ImageViewerApp {
 
     // Fullscreen
     pub is_fullscreen: bool,
+    /// Window moving to another monitor to go fullscreen there
+    pub fullscreen_move: Option<FullscreenMove>,
+    /// Frameless always-on-top window showing just the image
+    pub mini_viewer: Option<MiniViewer>,
+
+    // Slideshow started from the command line: time per image and when the
+    // current one was shown
+    pub slideshow_interval: Option<std::time::Duration>,
+    pub slideshow_advanced: std::time::Instant,
+    /// Slideshow paused on the current image without being stopped
+    pub slideshow_held: bool,
+    /// Images shown by slideshows this session, for shuffling without repeats
+    pub slideshow_shown: HashSet<PathBuf>,
+    /// Music playing along with the slideshow
+    pub music: Option<crate::music::MusicPlayer>,
+    /// Which slide of the music's schedule is showing, when the music sets the timing
+    pub music_slot: Option<u64>,
 
     // View modes
     pub view_mode: ViewMode,
@@ -124,11 +396,32 @@ pub struct ImageViewerApp {
     pub show_settings_dialog: bool,
     pub show_go_to_dialog: bool,
     pub show_move_dialog: bool,
+    pub show_presets_dialog: bool,
+    pub show_film_stocks_dialog: bool,
+    /// Smart collection being edited, with its index when it already exists
+    pub collection_draft: Option<(Option<usize>, crate::search::SmartCollection)>,
+    pub preset_name_input: String,
+    pub film_stock_name_input: String,
     pub go_to_input: String,
     pub search_query: String,
     pub search_visible: bool,
+    pub keyword_input: String,
+    pub focus_keyword_input: bool,
+    pub keyword_filter: Option<String>,
+    pub flag_filter: Option<crate::metadata::Flag>,
+    /// Day picked in the timeline; only images taken that day are listed
+    pub date_filter: Option<chrono::NaiveDate>,
+    pub active_collection: Option<usize>,
     pub command_palette_open: bool,
     pub command_palette_query: String,
+    /// Lua scripts offered in the command palette
+    pub scripts: Vec<crate::scripting::Script>,
+    /// HTTP server for remotes, while enabled in settings
+    pub remote_server: Option<crate::remote_control::RemoteServer>,
+    /// Hot folder followed in tether mode
+    pub tether: Option<Tether>,
+    /// Icon in the system tray, when turned on
+    pub tray: Option<TrayState>,
 
     // Pending navigation actions (deferred to avoid UI blocking)
     pub pending_navigate_next: bool,
@@ -144,7 +437,11 @@ pub struct ImageViewerApp {
 
     // Mouse state
     pub loupe_position: Option<egui::Pos2>,
+    /// Travel and start time of a one-finger drag that may be a swipe
+    pub touch_swipe: Option<(egui::Vec2, f64)>,
     pub picked_color: Option<(u8, u8, u8)>,
+    /// Pixel of `current_image` under the cursor while inspecting pixels
+    pub hovered_pixel: Option<(u32, u32)>,
 
     // Context for repaint requests
     pub ctx: Option<egui::Context>,
@@ -152,6 +449,28 @@ pub struct ImageViewerApp {
     // GPU processor (optional)
     pub gpu_processor: Option<Arc<GpuProcessor>>,
 
+    // wgpu display pass for the main view (None when eframe isn't running on wgpu)
+    pub gpu_display_max_dim: Option<u32>,
+    pub gpu_display_upload: Option<Arc<crate::gpu::display::DisplayUpload>>,
+    /// Size of the image in the display pass relative to the original, below
+    /// 1.0 for a zoomed-out proxy, and of a proxy still being built
+    pub gpu_display_scale: f32,
+    pub gpu_display_pending: Option<f32>,
+    /// Tiles for the current image when it is too big for one texture;
+    /// `current_texture` then only holds a downscaled overview
+    pub tiled_image: Option<(PathBuf, TiledImage)>,
+    /// Main textures are built with nearest filtering (see `texture_options`)
+    pub nearest_textures: bool,
+
+    // ICC transform for the current image (see color_management)
+    pub color_transform: TransformCache,
+    /// Show the main view as it would print with `settings.soft_proof`
+    pub soft_proof_enabled: bool,
+    pub soft_proof_cache: SoftProofCache,
+
+    // .cube files found in settings.lut_folder
+    pub user_luts: Vec<PathBuf>,
+
     // Status message
     pub status_message: Option<(String, std::time::Instant)>,
 
@@ -163,9 +482,11 @@ pub struct ImageViewerApp {
     // Panel visibility
     pub panels_hidden: bool,
 
-    // Compare view interaction state (zoom per side)
-    pub compare_zoom: [f32; 2],
-    pub compare_pan: [egui::Vec2; 2],
+    // Compare view interaction state (zoom per pane)
+    pub compare_zoom: [f32; MAX_COMPARE],
+    pub compare_pan: [egui::Vec2; MAX_COMPARE],
+    /// Show |A−B| of the first two compared images instead of the panes
+    pub compare_difference: bool,
 
     // GPU initialization state
     pub gpu_initialization_attempted: bool,
@@ -179,6 +500,11 @@ pub struct ImageViewerApp {
 
     // Performance optimizations
     pub task_scheduler: TaskScheduler,
+    /// Runs the jobs of `spawn_loader`, apart from the file loads above so
+    /// a long export doesn't hold up decoding
+    pub loader_pool: TaskScheduler,
+    /// Runs the jobs of `spawn_long_job`
+    pub long_job_pool: TaskScheduler,
     pub memory_pool: MemoryPool,
 }
 
@@ -254,6 +580,34 @@ impl ImageViewerApp {
             .retain(|name| used_texture_names.contains(name));
     }
 
+    /// ICC transform to apply before texture upload, or None when color management is off
+    pub fn display_transform(&mut self, path: &Path) -> Option<DisplayTransform> {
+        if !self.settings.color_management_enabled {
+            return None;
+        }
+        self.color_transform
+            .get(path, self.settings.display_icc_profile.as_deref())
+    }
+
+    /// Drop cached textures and rebuild the current one after a color management change
+    pub fn reload_color_management(&mut self) {
+        self.color_transform.clear();
+        self.soft_proof_cache.clear();
+        self.texture_cache.clear();
+        self.texture_access_order.clear();
+        self.refresh_adjustments_internal(true);
+    }
+
+    /// Rescan the configured LUT folder
+    pub fn refresh_user_luts(&mut self) {
+        self.user_luts = self
+            .settings
+            .lut_folder
+            .as_deref()
+            .map(crate::image_loader::list_cube_luts)
+            .unwrap_or_default();
+    }
+
     pub fn refresh_adjustments_if_dirty(&mut self) {
         if !self.adjustments_dirty {
             return;
@@ -264,14 +618,29 @@ impl ImageViewerApp {
 
         // Use longer debounce while dragging for smoother feel, shorter on release
         // Reduced from 200ms -> 80ms to improve responsiveness while dragging
-        let debounce_ms = if self.slider_dragging { 80 } else { 16 };
+        let previewing = self.slider_dragging || self.drawing_local_mask();
+        let debounce_ms = if previewing { 80 } else { 16 };
+
+        if previewing {
+            self.request_live_histogram();
+        }
+
+        // The wgpu display pass previews basic adjustments live, so skip rebuilding
+        // the full-size texture until the drag ends
+        if self.slider_dragging
+            && self.gpu_display_max_dim.is_some()
+            && crate::gpu::display::can_preview(&self.adjustments)
+            && !self.soft_proof_enabled
+        {
+            return;
+        }
 
         if elapsed.as_millis() >= debounce_ms {
             self.adjustments_dirty = false;
             self.last_adjustment_time = now;
             // Use lightweight refresh while dragging (skip histogram/overlays)
             crate::profiler::with_profiler(|p| p.start_timer("refresh_adjustments_if_dirty"));
-            self.refresh_adjustments_internal(!self.slider_dragging);
+            self.refresh_adjustments_internal(!previewing);
             crate::profiler::with_profiler(|p| p.end_timer("refresh_adjustments_if_dirty"));
         } else {
             // Schedule another repaint to process later
@@ -284,18 +653,22 @@ impl ImageViewerApp {
 }
 
 impl ImageViewerApp {
-    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>, cli: &CliArgs) -> Self {
         configure_style(&cc.egui_ctx);
 
         let (tx, rx) = channel();
 
-        let settings = Settings::load();
+        let mut settings = Settings::load();
+        cli.apply_to(&mut settings);
+        crate::i18n::set_language(settings.language);
         let telemetry_enabled = settings.telemetry_enabled;
         let metadata_db = MetadataDb::load();
 
         let mut app = Self {
             settings,
             metadata_db,
+            tabs: vec![super::tabs::ImageTab::default()],
+            active_tab: 0,
             image_list: Vec::new(),
             filtered_list: Vec::new(),
             current_index: 0,
@@ -305,15 +678,88 @@ impl ImageViewerApp {
             current_image: None,
             current_exif: None,
             histogram_data: None,
+            live_histogram_time: std::time::Instant::now(),
+            live_histogram_pending: false,
+            scope_data: None,
+            scope_texture: None,
+            scope_generation: 0,
+            scopes_pending: false,
             is_loading: false,
             load_error: None,
             showing_preview: false,
+            progressive_size: None,
+            progressive_cancel: None,
+            animation: None,
+            video: None,
+            batch_export: None,
+            share_secret_prompt: None,
+            show_contact_sheet_dialog: false,
+            contact_sheet_running: false,
+            show_web_gallery_dialog: false,
+            web_gallery_running: false,
+            web_gallery_result: None,
+            playlist: Default::default(),
+            show_playlist_dialog: false,
+            video_export: None,
+            rename_session: None,
+            metadata_session: None,
+            recently_deleted: Vec::new(),
+            show_recently_deleted_dialog: false,
+            show_shoot_summary_dialog: false,
+            show_all_metadata_dialog: false,
+            show_timeline_dialog: false,
+            timeline_catalog: false,
+            timeline: Default::default(),
+            show_open_url_dialog: false,
+            open_url_text: String::new(),
+            url_download: None,
+            show_reference_overlay_dialog: false,
+            reference_overlay: None,
+            export_preview: None,
+            all_metadata: None,
+            all_metadata_pending: None,
+            all_metadata_query: String::new(),
+            all_metadata_raw: false,
+            shoot_summary: None,
+            shoot_summary_pending: false,
+            keymap_recording: None,
+            layout_generation: 0,
             focus_peaking_texture: None,
             zebra_texture: None,
+            gpu_overlays: cc
+                .wgpu_render_state
+                .as_ref()
+                .map(crate::gpu::overlays::OverlayRenderer::new),
+            gpu_difference: cc
+                .wgpu_render_state
+                .as_ref()
+                .map(crate::gpu::difference::DifferenceRenderer::new),
+            clipping_texture: None,
             custom_overlay_texture: None,
             frame_texture: None,
             compare_exifs: HashMap::new(),
-            compare_large_preview_requests: HashSet::new(),
+            search_exif: HashMap::new(),
+            search_exif_pending: false,
+            catalog_watcher: None,
+            pending_imports: HashSet::new(),
+            imports_due: None,
+            capture_dates_pending: false,
+            folder_scan: None,
+            folder_scan_generation: 0,
+            raw_pairs: HashMap::new(),
+            dng_converting: false,
+            folder_watcher: None,
+            pending_folder_changes: HashSet::new(),
+            folder_changes_due: None,
+            own_writes: Arc::default(),
+            compare_textures: HashMap::new(),
+            compare_requests: HashSet::new(),
+            strip_sizes: HashMap::new(),
+            strip_index: None,
+            compare_alignments: HashMap::new(),
+            compare_alignment_requests: HashSet::new(),
+            compare_quality: HashMap::new(),
+            compare_quality_requests: HashSet::new(),
             zoom: 1.0,
             target_zoom: 1.0,
             pan_offset: Vec2::ZERO,
@@ -323,31 +769,69 @@ impl ImageViewerApp {
             crop_mode: false,
             crop_rect: None,
             crop_start_pos: None,
+            straighten_mode: false,
+            straighten_line: None,
+            local_mask_tool: None,
+            local_mask_drag: None,
+            pen_pressure: 1.0,
+            selected_local_adjustment: None,
             adjustments: ImageAdjustments::default(),
             current_film_preset: crate::image_loader::FilmPreset::None,
             show_original: false,
+            split_view: SplitView::Off,
+            split_position: 0.5,
+            original_texture: None,
+            original_texture_pending: None,
             last_adjustment_time: std::time::Instant::now(),
             adjustments_dirty: false,
             slider_dragging: false,
+            copied_adjustments: None,
+            pasted_images: HashMap::new(),
+            drag_out: None,
+            adjustments_path: None,
             pre_drag_adjustments: None,
             image_cache: Arc::new(ImageCache::new(1024)),
             texture_cache: HashMap::new(),
             texture_access_order: VecDeque::new(),
             thumbnail_textures: HashMap::new(),
             thumbnail_requests: HashSet::new(),
+            thumbnail_exposure: HashMap::new(),
             expanded_dirs: HashSet::new(),
             loader_tx: tx,
             loader_rx: rx,
-            is_fullscreen: false,
+            is_fullscreen: cli.fullscreen,
+            fullscreen_move: None,
+            mini_viewer: None,
+            slideshow_interval: None,
+            slideshow_advanced: std::time::Instant::now(),
+            slideshow_held: false,
+            slideshow_shown: HashSet::new(),
+            music: None,
+            music_slot: None,
             view_mode: ViewMode::Single,
             show_settings_dialog: false,
             show_go_to_dialog: false,
             show_move_dialog: false,
+            show_presets_dialog: false,
+            show_film_stocks_dialog: false,
+            collection_draft: None,
+            preset_name_input: String::new(),
+            film_stock_name_input: String::new(),
             go_to_input: String::new(),
             search_query: String::new(),
+            keyword_input: String::new(),
+            focus_keyword_input: false,
+            keyword_filter: None,
+            flag_filter: None,
+            date_filter: None,
+            active_collection: None,
             search_visible: false,
             command_palette_open: false,
             command_palette_query: String::new(),
+            scripts: Vec::new(),
+            remote_server: None,
+            tether: None,
+            tray: None,
             pending_navigate_next: false,
             pending_navigate_prev: false,
             pending_navigate_first: false,
@@ -357,11 +841,27 @@ impl ImageViewerApp {
             pending_fit_to_window: false,
             undo_history: UndoHistory::new(50),
             loupe_position: None,
+            touch_swipe: None,
             picked_color: None,
+            hovered_pixel: None,
             ctx: Some(cc.egui_ctx.clone()),
             gpu_processor: None,
-            compare_zoom: [1.0, 1.0],
-            compare_pan: [Vec2::ZERO, Vec2::ZERO],
+            gpu_display_max_dim: cc
+                .wgpu_render_state
+                .as_ref()
+                .map(crate::gpu::display::install),
+            gpu_display_upload: None,
+            gpu_display_scale: 1.0,
+            gpu_display_pending: None,
+            tiled_image: None,
+            nearest_textures: false,
+            color_transform: TransformCache::default(),
+            soft_proof_enabled: false,
+            soft_proof_cache: SoftProofCache::default(),
+            user_luts: Vec::new(),
+            compare_zoom: [1.0; MAX_COMPARE],
+            compare_pan: [Vec2::ZERO; MAX_COMPARE],
+            compare_difference: false,
             status_message: None,
             profiler_enabled: cfg!(debug_assertions), // Enabled in debug mode
             cache_stats: CacheStats::default(),
@@ -371,39 +871,60 @@ impl ImageViewerApp {
             thumbnail_scroll_offset: Vec2::ZERO,
             telemetry: Some(crate::telemetry::Telemetry::new(telemetry_enabled)),
             task_scheduler: TaskScheduler::default(),
+            loader_pool: TaskScheduler::new(num_cpus::get().max(4)),
+            long_job_pool: TaskScheduler::new(2),
             memory_pool: MemoryPool::default(),
         };
 
         // (Update checking removed)
 
-        // Restore session
-        if app.settings.restore_session {
+        app.refresh_user_luts();
+
+        // Trim thumbnails left over from earlier sessions to the configured size
+        app.image_cache.set_max_size_mb(app.settings.cache_size_mb);
+        crate::image_cache::disk_thumbnails().set_max_size_mb(app.settings.thumbnail_disk_cache_mb);
+        crate::image_loader::set_raw_decode_options(app.settings.raw_decode.clone());
+        std::thread::spawn(|| crate::image_cache::disk_thumbnails().prune());
+        // Files deleted without the trash before a crash
+        std::thread::spawn(super::recently_deleted::purge_holding_dirs);
+
+        // Restore session, unless started in safe mode
+        if app.settings.restore_session && !cli.safe_mode && !app.restore_session_tabs() {
             if let Some(ref folder) = app.settings.last_folder.clone() {
                 if folder.exists() {
-                    app.load_folder(folder.clone());
-                    if let Some(ref file) = app.settings.last_file.clone() {
-                        if let Some(idx) = app.image_list.iter().position(|p| p == file) {
-                            app.current_index = idx;
-                            // Load adjustments for restored session
-                            app.load_adjustments_for_current();
-                            app.load_current_image();
-                        }
-                    }
+                    let last_file = app.settings.last_file.clone();
+                    app.load_folder_at(folder.clone(), last_file);
                 }
             }
         }
 
-        // Check command line arguments
-        let args: Vec<String> = std::env::args().collect();
-        if args.len() > 1 {
-            let path = PathBuf::from(&args[1]);
-            if path.is_file() && crate::image_loader::is_supported_image(&path) {
+        // Open the path given on the command line
+        if let Some(path) = cli.path.clone() {
+            let url = path.to_string_lossy().to_string();
+            let is_url = crate::download::is_url(&url);
+            let is_image = path.is_file() && crate::image_loader::is_supported_image(&path);
+            // Keep the restored tabs and open the argument next to them
+            let is_folder = crate::image_loader::opens_as_folder(&path);
+            if app.current_folder.is_some() && (is_url || is_image || is_folder) {
+                app.push_tab();
+            }
+            if is_url {
+                app.open_url(url);
+            } else if is_image {
                 app.load_image_file(path);
-            } else if path.is_dir() {
+            } else if is_folder {
                 app.load_folder(path);
             }
         }
 
+        // Projector setups start fullscreen on the monitor used last time
+        if cli.fullscreen && app.settings.fullscreen_monitor.is_some() {
+            app.set_fullscreen(true);
+        }
+        if let Some(seconds) = cli.slideshow {
+            app.start_slideshow(seconds);
+        }
+
         // GPU processor will be initialized asynchronously later
         app.gpu_processor = None;
 
@@ -416,4 +937,6 @@ fn configure_style(ctx: &egui::Context) {
     style.visuals.window_shadow = egui::epaint::Shadow::NONE;
     style.visuals.popup_shadow = egui::epaint::Shadow::NONE;
     ctx.set_style(style);
+    // Ctrl +/- zoom the image; the interface scale is a setting
+    ctx.options_mut(|options| options.zoom_with_keyboard = false);
 }
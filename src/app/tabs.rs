@@ -0,0 +1,222 @@
+use super::ImageViewerApp;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// A folder open in its own tab. The active tab's images live on the app
+/// itself; this keeps what's needed to bring an inactive tab back.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImageTab {
+    pub folder: Option<PathBuf>,
+    pub current_file: Option<PathBuf>,
+    pub search_query: String,
+    /// The folder's files, kept while rView runs so switching back skips the rescan
+    #[serde(skip)]
+    pub image_list: Vec<PathBuf>,
+}
+
+impl ImageTab {
+    pub fn title(&self) -> String {
+        Self::title_for(self.folder.as_deref())
+    }
+
+    pub fn title_for(folder: Option<&Path>) -> String {
+        folder
+            .and_then(|f| f.file_name())
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "New Tab".to_string())
+    }
+}
+
+/// Move the tab at `from` to `to` and return where the active tab ends up
+pub fn move_tab(tabs: &mut Vec<ImageTab>, active: usize, from: usize, to: usize) -> usize {
+    if from >= tabs.len() || to >= tabs.len() || from == to {
+        return active;
+    }
+    let tab = tabs.remove(from);
+    tabs.insert(to, tab);
+    if active == from {
+        to
+    } else if from < active && to >= active {
+        active - 1
+    } else if from > active && to <= active {
+        active + 1
+    } else {
+        active
+    }
+}
+
+impl ImageViewerApp {
+    /// Snapshot of the folder, image and search shown right now
+    fn capture_tab(&self) -> ImageTab {
+        ImageTab {
+            folder: self.current_folder.clone(),
+            current_file: self.get_current_path(),
+            search_query: self.search_query.clone(),
+            // A list still being scanned is incomplete; scan it again on return
+            image_list: if self.folder_scan.is_some() {
+                Vec::new()
+            } else {
+                self.image_list.clone()
+            },
+        }
+    }
+
+    /// Write the active tab's state back into the tab list
+    pub fn store_active_tab(&mut self) {
+        let tab = self.capture_tab();
+        match self.tabs.get_mut(self.active_tab) {
+            Some(slot) => *slot = tab,
+            None => {
+                self.tabs.push(tab);
+                self.active_tab = self.tabs.len() - 1;
+            }
+        }
+    }
+
+    pub fn switch_tab(&mut self, index: usize) {
+        if index == self.active_tab || index >= self.tabs.len() {
+            return;
+        }
+        self.save_current_adjustments();
+        self.store_active_tab();
+        self.active_tab = index;
+        let tab = self.tabs[index].clone();
+        self.restore_tab(tab);
+    }
+
+    pub fn next_tab(&mut self) {
+        if self.tabs.len() > 1 {
+            self.switch_tab((self.active_tab + 1) % self.tabs.len());
+        }
+    }
+
+    pub fn previous_tab(&mut self) {
+        if self.tabs.len() > 1 {
+            self.switch_tab((self.active_tab + self.tabs.len() - 1) % self.tabs.len());
+        }
+    }
+
+    /// Open an empty tab and ask for the folder to show in it
+    pub fn new_tab(&mut self) {
+        self.push_tab();
+        self.open_folder_dialog();
+    }
+
+    /// Switch to a new, empty tab
+    pub fn push_tab(&mut self) {
+        self.save_current_adjustments();
+        self.store_active_tab();
+        self.tabs.push(ImageTab::default());
+        self.active_tab = self.tabs.len() - 1;
+        self.restore_tab(ImageTab::default());
+    }
+
+    /// Close a tab; closing the last one leaves a single empty tab
+    pub fn close_tab(&mut self, index: usize) {
+        if index >= self.tabs.len() {
+            return;
+        }
+        if index != self.active_tab {
+            self.tabs.remove(index);
+            if index < self.active_tab {
+                self.active_tab -= 1;
+            }
+            return;
+        }
+
+        self.save_current_adjustments();
+        self.tabs.remove(index);
+        if self.tabs.is_empty() {
+            self.tabs.push(ImageTab::default());
+        }
+        self.active_tab = index.min(self.tabs.len() - 1);
+        let tab = self.tabs[self.active_tab].clone();
+        self.restore_tab(tab);
+    }
+
+    pub fn reorder_tab(&mut self, from: usize, to: usize) {
+        self.active_tab = move_tab(&mut self.tabs, self.active_tab, from, to);
+    }
+
+    /// Show a tab's folder, reusing its file list when it was open this session
+    fn restore_tab(&mut self, tab: ImageTab) {
+        self.search_query = tab.search_query.clone();
+        self.selected_indices.clear();
+        self.current_index = 0;
+        self.cancel_folder_scan();
+
+        match tab.folder {
+            Some(folder) if !tab.image_list.is_empty() => {
+                self.current_folder = Some(folder);
+                self.image_list = tab.image_list;
+                self.apply_filter();
+                self.watch_current_folder();
+            }
+            Some(folder) if crate::image_loader::opens_as_folder(&folder) => {
+                // Shows the tab's image itself once the scan finds it
+                self.load_folder_at(folder, tab.current_file);
+                return;
+            }
+            _ => {
+                self.current_folder = None;
+                self.image_list.clear();
+                self.filtered_list.clear();
+                self.folder_watcher = None;
+            }
+        }
+
+        if let Some(pos) = tab.current_file.and_then(|file| {
+            self.filtered_list
+                .iter()
+                .position(|&i| self.image_list[i] == file)
+        }) {
+            self.current_index = pos;
+        }
+        if self.filtered_list.is_empty() {
+            self.current_texture = None;
+            self.current_image = None;
+            self.current_exif = None;
+            self.animation = None;
+        } else {
+            self.load_current_image();
+        }
+    }
+
+    /// Remember every open tab so the next launch can bring them all back
+    pub fn save_session_tabs(&mut self) {
+        self.store_active_tab();
+        self.settings.open_tabs = self.tabs.clone();
+        self.settings.active_tab = self.active_tab;
+        self.settings.last_folder = self.current_folder.clone();
+    }
+
+    /// Reopen the tabs from the last session, dropping any whose folder is gone.
+    /// Returns false when there were none to restore.
+    pub fn restore_session_tabs(&mut self) -> bool {
+        let active = self.settings.active_tab;
+        let mut tabs = Vec::new();
+        let mut new_active = 0;
+        for (index, tab) in self.settings.open_tabs.iter().enumerate() {
+            if tab
+                .folder
+                .as_ref()
+                .is_some_and(|f| crate::image_loader::opens_as_folder(f))
+            {
+                if index <= active {
+                    new_active = tabs.len();
+                }
+                tabs.push(tab.clone());
+            }
+        }
+        if tabs.is_empty() {
+            return false;
+        }
+
+        self.tabs = tabs;
+        self.active_tab = new_active;
+        let tab = self.tabs[new_active].clone();
+        self.restore_tab(tab);
+        true
+    }
+}
@@ -0,0 +1,105 @@
+use super::ImageViewerApp;
+use crate::image_loader::ImageAdjustments;
+use std::path::PathBuf;
+
+/// A hot folder the viewer follows, showing each shot as it arrives
+pub struct Tether {
+    pub folder: PathBuf,
+    /// Shots received since tethering started
+    pub arrived: usize,
+    /// Whether tethering switched to fullscreen, so stopping switches back
+    entered_fullscreen: bool,
+}
+
+impl ImageViewerApp {
+    /// Ask for a hot folder and start tethering to it
+    pub fn tether_folder_dialog(&mut self) {
+        if let Some(folder) = rfd::FileDialog::new()
+            .set_title("Tether to folder")
+            .pick_folder()
+        {
+            self.start_tether(folder);
+        }
+    }
+
+    pub fn start_tether(&mut self, folder: PathBuf) {
+        self.stop_tether();
+        let entered_fullscreen = self.settings.tether.fullscreen && !self.is_fullscreen;
+        if entered_fullscreen {
+            self.set_fullscreen(true);
+        }
+        self.tether = Some(Tether {
+            folder: folder.clone(),
+            arrived: 0,
+            entered_fullscreen,
+        });
+        // The watcher starts once the scan finishes, even with watching turned off
+        self.load_folder(folder);
+    }
+
+    pub fn stop_tether(&mut self) {
+        let Some(tether) = self.tether.take() else {
+            return;
+        };
+        if tether.entered_fullscreen && self.is_fullscreen {
+            self.set_fullscreen(false);
+        }
+        if !self.settings.watch_folder {
+            self.folder_watcher = None;
+        }
+        self.show_status(&format!("Tethering stopped after {} shots", tether.arrived));
+    }
+
+    pub fn toggle_tether(&mut self) {
+        if self.tether.is_some() {
+            self.stop_tether();
+        } else {
+            self.tether_folder_dialog();
+        }
+    }
+
+    /// Whether the folder on screen is the tethered one
+    pub fn is_tethered(&self) -> bool {
+        self.tether
+            .as_ref()
+            .is_some_and(|t| self.current_folder.as_ref() == Some(&t.folder))
+    }
+
+    /// Jump to a shot that just arrived, giving it the tether film preset
+    /// unless it already has adjustments
+    pub fn show_tethered_arrival(&mut self, path: PathBuf) {
+        let film = self
+            .settings
+            .tether
+            .film()
+            .filter(|_| self.metadata_db.get_adjustments(&path).is_none());
+        if let Some(film) = film {
+            let mut adjustments = ImageAdjustments::default();
+            adjustments.apply_preset(film);
+            self.metadata_db.set_adjustments(path.clone(), &adjustments);
+            self.metadata_db.save();
+        }
+        if let Some(tether) = &mut self.tether {
+            tether.arrived += 1;
+        }
+
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let listed = self
+            .filtered_list
+            .iter()
+            .position(|&i| self.image_list.get(i) == Some(&path));
+        match listed {
+            Some(index) => {
+                self.go_to_index(index);
+                if let Some(film) = film {
+                    self.current_film_preset = film;
+                }
+                self.show_status(&format!("New shot: {}", name));
+            }
+            None => self.show_status(&format!("New shot {} is hidden by the filter", name)),
+        }
+    }
+}
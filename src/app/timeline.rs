@@ -0,0 +1,62 @@
+use super::sorting::date_taken;
+use super::ImageViewerApp;
+use crate::timeline::Timeline;
+use chrono::NaiveDate;
+
+impl ImageViewerApp {
+    pub fn open_timeline(&mut self) {
+        self.show_timeline_dialog = true;
+        self.refresh_timeline();
+    }
+
+    /// Regroup the folder, or every dated image in the catalog, by day taken.
+    /// Folder images whose EXIF date isn't cached yet count by file date until
+    /// it has been read.
+    pub fn refresh_timeline(&mut self) {
+        let dates = &self.metadata_db.capture_dates;
+        self.timeline = if self.timeline_catalog {
+            Timeline::from_dates(
+                dates
+                    .iter()
+                    .filter_map(|(path, date)| Some((path.clone(), date.taken?.date()))),
+            )
+        } else {
+            Timeline::from_dates(
+                self.image_list
+                    .iter()
+                    .filter_map(|path| Some((path.clone(), date_taken(path, dates)?.date()))),
+            )
+        };
+        if !self.timeline_catalog {
+            self.request_capture_dates();
+        }
+    }
+
+    /// List only the images taken on `day`, opening their folder first when
+    /// the catalog timeline points elsewhere. `None` lists everything again.
+    pub fn show_timeline_day(&mut self, day: Option<NaiveDate>) {
+        let Some(day) = day else {
+            self.date_filter = None;
+            self.refilter_keeping_current();
+            return;
+        };
+
+        let in_folder = |path: &std::path::Path| {
+            path.parent()
+                .is_some_and(|p| Some(p) == self.current_folder.as_deref())
+        };
+        let paths = self.timeline.days.get(&day).cloned().unwrap_or_default();
+        if self.timeline_catalog && !paths.iter().any(|p| in_folder(p)) {
+            let Some(first) = paths.into_iter().find(|p| p.is_file()) else {
+                self.show_status("The images from that day are no longer on disk");
+                return;
+            };
+            let Some(folder) = first.parent().map(|p| p.to_path_buf()) else {
+                return;
+            };
+            self.load_folder_at(folder, Some(first));
+        }
+        self.date_filter = Some(day);
+        self.refilter_keeping_current();
+    }
+}
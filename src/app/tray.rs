@@ -0,0 +1,127 @@
+use super::{ImageViewerApp, LoaderMessage};
+use crate::i18n::tr;
+use crate::tray::{Tray, TrayCommand, TrayMenu, TRAY_FOLDERS};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// The tray icon with what its menu was last built from
+pub struct TrayState {
+    tray: Tray,
+    menu: TrayMenu,
+    /// Whether the window is hidden in the tray; read from the menu's thread
+    hidden: Arc<AtomicBool>,
+    /// Set by Quit so closing the window isn't turned into hiding it
+    quitting: bool,
+}
+
+impl ImageViewerApp {
+    fn tray_menu(&self) -> TrayMenu {
+        TrayMenu {
+            show_hide: tr("tray-show-hide"),
+            recent: tr("tray-recent-folders"),
+            slideshow: tr("tray-slideshow"),
+            quit: tr("tray-quit"),
+            folders: self
+                .settings
+                .recent_folders
+                .iter()
+                .take(TRAY_FOLDERS)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Show or remove the tray icon to match the settings
+    pub fn apply_tray_settings(&mut self) {
+        if !self.settings.tray_icon {
+            if let Some(state) = self.tray.take() {
+                self.show_hidden_window(&state);
+            }
+            return;
+        }
+        if self.tray.is_some() {
+            return;
+        }
+
+        let menu = self.tray_menu();
+        let hidden = Arc::new(AtomicBool::new(false));
+        let tx = self.loader_tx.clone();
+        let ctx = self.ctx.clone();
+        let window_hidden = Arc::clone(&hidden);
+        let result = Tray::create(&menu, crate::load_icon(), move |command| {
+            let Some(ctx) = &ctx else {
+                return;
+            };
+            // A hidden window may not update, so it's brought back from here
+            // before anything else, even quitting
+            if window_hidden.swap(false, Ordering::Relaxed) {
+                ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+                ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+                if command == TrayCommand::ToggleWindow {
+                    return;
+                }
+            }
+            let _ = tx.send(LoaderMessage::TrayCommand(command));
+            ctx.request_repaint();
+        });
+        match result {
+            Ok(tray) => {
+                self.tray = Some(TrayState {
+                    tray,
+                    menu,
+                    hidden,
+                    quitting: false,
+                })
+            }
+            Err(e) => {
+                log::warn!("Tray icon couldn't be shown: {}", e);
+                self.show_status(&format!("Tray icon couldn't be shown: {}", e));
+            }
+        }
+    }
+
+    /// Keep the menu's recent folders current and hide the window to the tray
+    /// instead of closing it
+    pub fn update_tray(&mut self, ctx: &egui::Context) {
+        let menu = self.tray_menu();
+        let Some(state) = &mut self.tray else {
+            return;
+        };
+        if state.menu != menu {
+            state.tray.set_menu(&menu);
+            state.menu = menu;
+        }
+        if !state.quitting && ctx.input(|i| i.viewport().close_requested()) {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            ctx.send_viewport_cmd(egui::ViewportCommand::Visible(false));
+            state.hidden.store(true, Ordering::Relaxed);
+        }
+    }
+
+    pub fn handle_tray_command(&mut self, command: TrayCommand, ctx: &egui::Context) {
+        match command {
+            TrayCommand::ToggleWindow => {
+                ctx.send_viewport_cmd(egui::ViewportCommand::Visible(false));
+                if let Some(state) = &self.tray {
+                    state.hidden.store(true, Ordering::Relaxed);
+                }
+            }
+            TrayCommand::OpenFolder(folder) => self.load_folder(folder),
+            TrayCommand::ToggleSlideshow => self.toggle_slideshow(),
+            TrayCommand::Quit => {
+                if let Some(state) = &mut self.tray {
+                    state.quitting = true;
+                }
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            }
+        }
+    }
+
+    fn show_hidden_window(&self, state: &TrayState) {
+        if state.hidden.swap(false, Ordering::Relaxed) {
+            if let Some(ctx) = &self.ctx {
+                ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+            }
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use super::{ImageViewerApp, LoaderMessage};
+use crate::download;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Send progress at most this often, so a fast download doesn't flood the channel
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// An image being downloaded to open it
+pub struct UrlDownload {
+    pub url: String,
+    pub received: u64,
+    /// Size from the server, when it gave one
+    pub total: Option<u64>,
+    pub cancel: Arc<AtomicBool>,
+}
+
+impl UrlDownload {
+    pub fn fraction(&self) -> Option<f32> {
+        self.total
+            .filter(|&total| total > 0)
+            .map(|total| (self.received as f32 / total as f32).min(1.0))
+    }
+}
+
+impl ImageViewerApp {
+    /// Ask for a URL, starting with the one on the clipboard if there is one
+    pub fn open_url_dialog(&mut self) {
+        let copied = arboard::Clipboard::new().and_then(|mut c| c.get_text());
+        if let Ok(text) = copied {
+            if download::is_url(&text) {
+                self.open_url_text = text.trim().to_string();
+            }
+        }
+        self.show_open_url_dialog = true;
+    }
+
+    /// Download an image and open it like a local file; a URL opened before
+    /// is shown from the download folder straight away
+    pub fn open_url(&mut self, url: String) {
+        let url = url.trim().to_string();
+        if !download::is_url(&url) {
+            self.show_status("Only http and https addresses can be opened");
+            return;
+        }
+        if let Some(path) = download::cached_download(&url) {
+            self.show_open_url_dialog = false;
+            self.load_image_file(path);
+            return;
+        }
+        self.cancel_url_download();
+
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.url_download = Some(UrlDownload {
+            url: url.clone(),
+            received: 0,
+            total: None,
+            cancel: Arc::clone(&cancel),
+        });
+        self.show_open_url_dialog = true;
+
+        let ctx = self.ctx.clone();
+        self.spawn_long_job(move |tx| {
+            let mut last_sent: Option<Instant> = None;
+            let result = download::download(
+                &url,
+                &download::download_folder(),
+                &cancel,
+                |received, total| {
+                    if last_sent.is_some_and(|t| t.elapsed() < PROGRESS_INTERVAL) {
+                        return;
+                    }
+                    last_sent = Some(Instant::now());
+                    let _ = tx.send(LoaderMessage::UrlDownloadProgress(
+                        url.clone(),
+                        received,
+                        total,
+                    ));
+                    if let Some(ctx) = &ctx {
+                        ctx.request_repaint();
+                    }
+                },
+            );
+            Some(LoaderMessage::UrlDownloaded(url, result))
+        });
+    }
+
+    pub fn cancel_url_download(&mut self) {
+        if let Some(download) = self.url_download.take() {
+            download.cancel.store(true, Ordering::Relaxed);
+        }
+    }
+
+    pub fn handle_url_download_progress(&mut self, url: String, received: u64, total: Option<u64>) {
+        if let Some(download) = self.url_download.as_mut().filter(|d| d.url == url) {
+            download.received = received;
+            download.total = total;
+        }
+    }
+
+    pub fn handle_url_downloaded(&mut self, url: String, result: Result<PathBuf, String>) {
+        // A cancelled or replaced download finishing late
+        if !self.url_download.as_ref().is_some_and(|d| d.url == url) {
+            return;
+        }
+        self.url_download = None;
+        match result {
+            Ok(path) => {
+                self.show_open_url_dialog = false;
+                self.load_image_file(path);
+            }
+            Err(e) => {
+                log::warn!("Download of {} failed: {}", url, e);
+                self.show_status(&format!("Download failed: {}", e));
+            }
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use super::{ImageViewerApp, LoaderMessage};
+use crate::image_loader::{VideoInfo, VideoStream, PLAYBACK_MAX_SIDE};
+use egui::TextureHandle;
+use image::{DynamicImage, GenericImageView};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::TryRecvError;
+use std::time::{Duration, Instant};
+
+/// Playback state for a video clip shown in the single view. The first frame
+/// is loaded as the file's image; playback draws decoded frames over it.
+pub struct VideoPlayback {
+    pub path: PathBuf,
+    pub info: VideoInfo,
+    pub playing: bool,
+    /// Time of the frame in `texture`
+    pub position: Duration,
+    stream: Option<VideoStream>,
+    /// Time of the next frame the stream will deliver
+    next_frame: Duration,
+    /// When playback (re)started, and the position it started from
+    clock: Instant,
+    clock_start: Duration,
+    texture: Option<TextureHandle>,
+}
+
+impl VideoPlayback {
+    fn show_frame(&mut self, ctx: &egui::Context, frame: &DynamicImage) {
+        let rgba = frame.to_rgba8();
+        let image = egui::ColorImage::from_rgba_unmultiplied(
+            [rgba.width() as usize, rgba.height() as usize],
+            rgba.as_raw(),
+        );
+        match &mut self.texture {
+            Some(texture) => texture.set(image, egui::TextureOptions::LINEAR),
+            None => {
+                self.texture = Some(ctx.load_texture(
+                    format!("video_{}", self.path.display()),
+                    image,
+                    egui::TextureOptions::LINEAR,
+                ))
+            }
+        }
+    }
+
+    /// Take the frames that are due by now, returning the newest
+    fn due_frame(&mut self) -> Option<DynamicImage> {
+        let target = self.clock_start + self.clock.elapsed();
+        let interval = self.info.frame_interval();
+        let mut latest = None;
+        while self.playing && self.next_frame <= target {
+            let Some(stream) = &self.stream else {
+                break;
+            };
+            match stream.frames.try_recv() {
+                Ok(frame) => {
+                    self.position = self.next_frame;
+                    self.next_frame += interval;
+                    latest = Some(frame);
+                }
+                // The decoder is behind; show the frame when it arrives
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => self.stop(),
+            }
+        }
+        latest.map(DynamicImage::ImageRgba8)
+    }
+
+    fn stop(&mut self) {
+        self.playing = false;
+        self.stream = None;
+    }
+}
+
+impl ImageViewerApp {
+    /// Read the length and frame rate of a video in the background
+    pub fn request_video(&mut self, path: &Path) {
+        self.video = None;
+        if !crate::image_loader::is_video_file(path) {
+            return;
+        }
+
+        let path = path.to_path_buf();
+        self.spawn_loader(move |_| match crate::image_loader::probe_video(&path) {
+            Ok(info) => Some(LoaderMessage::VideoLoaded(path, info)),
+            Err(e) => {
+                log::warn!("Failed to probe video {}: {}", path.display(), e);
+                None
+            }
+        });
+    }
+
+    pub fn handle_video_loaded(&mut self, path: PathBuf, info: VideoInfo) {
+        if self.get_current_path().as_ref() != Some(&path) {
+            return;
+        }
+
+        self.video = Some(VideoPlayback {
+            path,
+            info,
+            playing: false,
+            position: Duration::ZERO,
+            stream: None,
+            next_frame: Duration::ZERO,
+            clock: Instant::now(),
+            clock_start: Duration::ZERO,
+            texture: None,
+        });
+    }
+
+    pub fn handle_video_frame(
+        &mut self,
+        path: PathBuf,
+        at: Duration,
+        frame: DynamicImage,
+        ctx: &egui::Context,
+    ) {
+        if let Some(video) = &mut self.video {
+            // Ignore frames from an earlier scrub position or a started playback
+            if video.path == path && video.position == at && !video.playing {
+                video.show_frame(ctx, &frame);
+            }
+        }
+    }
+
+    pub fn toggle_video_playback(&mut self) {
+        let Some(video) = &self.video else {
+            return;
+        };
+        if video.playing {
+            if let Some(video) = &mut self.video {
+                video.stop();
+            }
+            return;
+        }
+
+        // Play again from the start once the end was reached
+        let at = if video.position + video.info.frame_interval() >= video.info.duration {
+            Duration::ZERO
+        } else {
+            video.position
+        };
+        self.start_video_stream(at);
+    }
+
+    /// Jump to `to`, continuing playback from there or showing the single frame
+    pub fn seek_video(&mut self, to: Duration) {
+        let Some(video) = &mut self.video else {
+            return;
+        };
+        let to = to.min(video.info.duration);
+        if video.playing {
+            self.start_video_stream(to);
+            return;
+        }
+
+        video.position = to;
+        let path = video.path.clone();
+        self.spawn_loader(move |_| {
+            match crate::image_loader::video_frame(&path, to, PLAYBACK_MAX_SIDE) {
+                Ok(frame) => Some(LoaderMessage::VideoFrame(path, to, frame)),
+                Err(e) => {
+                    log::warn!("Failed to decode video frame {}: {}", path.display(), e);
+                    None
+                }
+            }
+        });
+    }
+
+    fn start_video_stream(&mut self, at: Duration) {
+        // Frames are decoded at the size of the first frame so they line up with it
+        let Some((width, height)) = self.current_image.as_ref().map(|img| img.dimensions()) else {
+            return;
+        };
+        let Some(video) = &mut self.video else {
+            return;
+        };
+
+        match VideoStream::start(&video.path, at, width, height) {
+            Ok(stream) => {
+                video.stream = Some(stream);
+                video.playing = true;
+                video.position = at;
+                video.next_frame = at;
+                video.clock = Instant::now();
+                video.clock_start = at;
+            }
+            Err(e) => {
+                video.stop();
+                self.set_status_message(format!("Can't play video: {}", e));
+            }
+        }
+    }
+
+    /// Texture for the current video frame, pulling decoded frames in time and
+    /// scheduling the next repaint. `None` until playback or scrubbing moves
+    /// away from the first frame.
+    pub fn current_video_texture(&mut self, ctx: &egui::Context) -> Option<TextureHandle> {
+        let current_path = self.get_current_path();
+        let video = self.video.as_mut()?;
+        if current_path.as_ref() != Some(&video.path) {
+            return None;
+        }
+
+        if let Some(frame) = video.due_frame() {
+            video.show_frame(ctx, &frame);
+        }
+        if video.playing {
+            ctx.request_repaint_after(video.info.frame_interval());
+        }
+        video.texture.clone()
+    }
+}
@@ -0,0 +1,166 @@
+use super::recently_deleted::is_held;
+use super::{ImageViewerApp, LoaderMessage};
+use crate::image_loader::is_supported_image;
+use crate::metadata::CaptureDate;
+use crate::xmp::XmpData;
+use notify::event::ModifyKind;
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use walkdir::WalkDir;
+
+/// Quiet period before importing, so a card copy is imported in one pass
+/// instead of once for every file
+const IMPORT_DEBOUNCE: Duration = Duration::from_millis(500);
+
+impl ImageViewerApp {
+    /// Import what arrived in the watched folders while rView was closed, then
+    /// watch them for new images, replacing any previous watcher
+    pub fn watch_catalog_folders(&mut self) {
+        self.catalog_watcher = None;
+        let folders: Vec<PathBuf> = self
+            .settings
+            .watched_folders
+            .iter()
+            .filter(|folder| folder.is_dir())
+            .cloned()
+            .collect();
+        if folders.is_empty() {
+            return;
+        }
+
+        let tx = self.loader_tx.clone();
+        let ctx = self.ctx.clone();
+        let handler = move |result: notify::Result<notify::Event>| match result {
+            Ok(event) => {
+                // Creates, writes and renames into place; a deleted file needs no import
+                let arrived = matches!(
+                    event.kind,
+                    EventKind::Create(_)
+                        | EventKind::Modify(ModifyKind::Data(_) | ModifyKind::Name(_))
+                );
+                let paths: Vec<PathBuf> = event
+                    .paths
+                    .into_iter()
+                    .filter(|path| is_supported_image(path) && !is_held(path))
+                    .collect();
+                if !arrived || paths.is_empty() {
+                    return;
+                }
+                let _ = tx.send(LoaderMessage::WatchedFilesArrived(paths));
+                if let Some(ctx) = &ctx {
+                    ctx.request_repaint();
+                }
+            }
+            Err(e) => log::warn!("Watched folder error: {}", e),
+        };
+
+        match notify::recommended_watcher(handler) {
+            Ok(mut watcher) => {
+                for folder in &folders {
+                    if let Err(e) = watcher.watch(folder, RecursiveMode::Recursive) {
+                        log::warn!("Failed to watch {}: {}", folder.display(), e);
+                    }
+                }
+                self.catalog_watcher = Some(watcher);
+            }
+            Err(e) => log::warn!("Failed to create folder watcher: {}", e),
+        }
+
+        // Started after the watcher so nothing copied in between is missed
+        self.spawn_loader(move |_| {
+            let found: Vec<PathBuf> = folders
+                .iter()
+                .flat_map(|folder| WalkDir::new(folder).follow_links(true))
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_type().is_file())
+                .map(|entry| entry.into_path())
+                .filter(|path| is_supported_image(path) && !is_held(path))
+                .collect();
+            (!found.is_empty()).then_some(LoaderMessage::WatchedFilesArrived(found))
+        });
+    }
+
+    pub fn handle_watched_files_arrived(&mut self, paths: Vec<PathBuf>) {
+        self.pending_imports.extend(paths);
+        self.imports_due = Some(Instant::now() + IMPORT_DEBOUNCE);
+        if let Some(ctx) = &self.ctx {
+            ctx.request_repaint_after(IMPORT_DEBOUNCE);
+        }
+    }
+
+    /// Read the capture date and sidecar of images that arrived in a watched
+    /// folder, once it has been quiet for the debounce period
+    pub fn import_pending_files(&mut self) {
+        let Some(due) = self.imports_due else {
+            return;
+        };
+        let now = Instant::now();
+        if now < due {
+            if let Some(ctx) = &self.ctx {
+                ctx.request_repaint_after(due - now);
+            }
+            return;
+        }
+        self.imports_due = None;
+
+        // A file still catalogued at its current size was imported already
+        let dates = &self.metadata_db.capture_dates;
+        let paths: Vec<PathBuf> = std::mem::take(&mut self.pending_imports)
+            .into_iter()
+            .filter(|path| {
+                let size = path
+                    .metadata()
+                    .ok()
+                    .filter(|m| m.is_file())
+                    .map(|m| m.len());
+                size.is_some() && dates.get(path).map(|date| date.size) != size
+            })
+            .collect();
+        if paths.is_empty() {
+            return;
+        }
+
+        self.spawn_loader(move |_| {
+            let entries = paths
+                .into_iter()
+                .filter_map(|path| {
+                    let size = path.metadata().ok()?.len();
+                    let taken = crate::exif_data::capture_date(&path);
+                    let xmp = crate::xmp::read_sidecar(&path);
+                    Some((path, CaptureDate { size, taken }, xmp))
+                })
+                .collect();
+            Some(LoaderMessage::WatchedFilesImported(entries))
+        });
+    }
+
+    pub fn handle_watched_files_imported(
+        &mut self,
+        entries: Vec<(PathBuf, CaptureDate, Option<XmpData>)>,
+    ) {
+        if entries.is_empty() {
+            return;
+        }
+        let count = entries.len();
+        let mut paths = Vec::with_capacity(count);
+        for (path, date, xmp) in entries {
+            self.metadata_db
+                .import_file(path.clone(), date, xmp.as_ref());
+            paths.push(path);
+        }
+        self.metadata_db.save();
+        log::info!("Imported {} images from watched folders", count);
+
+        // Images landing in the open folder join the list even with its own watcher off
+        if let Some(folder) = self.current_folder.clone() {
+            paths.retain(|path| self.in_watched_folder(&folder, path));
+            if !paths.is_empty() {
+                self.handle_folder_changed(paths);
+            }
+        }
+        if self.show_timeline_dialog {
+            self.refresh_timeline();
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use super::recently_deleted::is_held;
+use super::{ImageViewerApp, LoaderMessage};
+use crate::image_loader::is_supported_image;
+use notify::event::ModifyKind;
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Quiet period before applying file system changes, so a large copy
+/// is picked up in one pass instead of re-sorting for every file
+const FOLDER_CHANGE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// How long after rView rewrote a file its change events are still its own
+pub const OWN_WRITE_GRACE: Duration = Duration::from_secs(2);
+
+impl ImageViewerApp {
+    /// Watch the current folder for additions, deletions and renames, replacing any previous watcher
+    pub fn watch_current_folder(&mut self) {
+        self.folder_watcher = None;
+        self.pending_folder_changes.clear();
+        self.folder_changes_due = None;
+
+        // Tethering needs the watcher to see new shots
+        if !self.settings.watch_folder && !self.is_tethered() {
+            return;
+        }
+        let Some(folder) = self.current_folder.clone() else {
+            return;
+        };
+        // Archives are read-only snapshots
+        if crate::image_loader::is_archive_file(&folder) {
+            return;
+        }
+
+        let tx = self.loader_tx.clone();
+        let ctx = self.ctx.clone();
+        let handler = move |result: notify::Result<notify::Event>| match result {
+            Ok(event) => {
+                // Attribute changes, such as Finder tags, leave the image as it was
+                let unchanged = matches!(
+                    event.kind,
+                    EventKind::Access(_) | EventKind::Modify(ModifyKind::Metadata(_))
+                );
+                if unchanged || event.paths.is_empty() {
+                    return;
+                }
+                let _ = tx.send(LoaderMessage::FolderChanged(event.paths));
+                if let Some(ctx) = &ctx {
+                    ctx.request_repaint();
+                }
+            }
+            Err(e) => log::warn!("Folder watcher error: {}", e),
+        };
+
+        let mode = if self.settings.include_subfolders {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+        match notify::recommended_watcher(handler) {
+            Ok(mut watcher) => match watcher.watch(&folder, mode) {
+                Ok(()) => self.folder_watcher = Some(watcher),
+                Err(e) => log::warn!("Failed to watch {}: {}", folder.display(), e),
+            },
+            Err(e) => log::warn!("Failed to create folder watcher: {}", e),
+        }
+    }
+
+    pub fn handle_folder_changed(&mut self, paths: Vec<PathBuf>) {
+        self.pending_folder_changes.extend(paths);
+        self.folder_changes_due = Some(Instant::now() + FOLDER_CHANGE_DEBOUNCE);
+        if let Some(ctx) = &self.ctx {
+            ctx.request_repaint_after(FOLDER_CHANGE_DEBOUNCE);
+        }
+    }
+
+    /// Apply queued file system changes once the folder has been quiet for the debounce period
+    pub fn apply_pending_folder_changes(&mut self) {
+        let Some(due) = self.folder_changes_due else {
+            return;
+        };
+        let now = Instant::now();
+        if now < due {
+            if let Some(ctx) = &self.ctx {
+                ctx.request_repaint_after(due - now);
+            }
+            return;
+        }
+        self.folder_changes_due = None;
+
+        let Some(folder) = self.current_folder.clone() else {
+            self.pending_folder_changes.clear();
+            return;
+        };
+
+        let previous = self.get_current_path();
+        let own_writes = self.own_writes();
+        let mut arrived = Vec::new();
+        let mut removed = 0;
+        let mut modified = false;
+
+        for path in std::mem::take(&mut self.pending_folder_changes) {
+            let listed = self.image_list.contains(&path);
+            // Checking what is on disk now handles creates, deletes and both halves of a rename
+            if path.is_file() {
+                if listed && own_writes.contains(&path) {
+                    // Only the tags rView wrote changed, not the pixels
+                    continue;
+                } else if listed {
+                    // Rewritten in place: drop the stale thumbnail and cached decode
+                    self.thumbnail_textures.remove(&path);
+                    self.thumbnail_requests.remove(&path);
+                    self.image_cache.invalidate_path(&path);
+                    modified |= previous.as_ref() == Some(&path);
+                } else if is_supported_image(&path) && self.in_watched_folder(&folder, &path) {
+                    self.image_list.push(path.clone());
+                    arrived.push(path);
+                }
+            } else if listed {
+                self.image_list.retain(|p| p != &path);
+                self.thumbnail_textures.remove(&path);
+                self.thumbnail_requests.remove(&path);
+                self.image_cache.remove(&path);
+                removed += 1;
+            }
+        }
+
+        let added = arrived.len();
+        if added > 0 || removed > 0 {
+            // Keep a shuffled order stable instead of reshuffling on every change
+            if !matches!(self.settings.sort_mode, crate::settings::SortMode::Random) {
+                self.sort_images();
+            }
+            self.selected_indices.clear();
+            self.refilter_keeping(previous);
+
+            let mut parts = Vec::new();
+            if added > 0 {
+                parts.push(format!("{} added", added));
+            }
+            if removed > 0 {
+                parts.push(format!("{} removed", removed));
+            }
+            self.show_status(&format!("Folder updated: {}", parts.join(", ")));
+
+            if self.is_tethered() {
+                if let Some(path) = crate::tether::newest_arrival(&arrived) {
+                    self.show_tethered_arrival(path.to_path_buf());
+                }
+            }
+        } else if modified {
+            self.load_current_image();
+        }
+    }
+
+    /// Files whose change events come from rView's own writes, forgetting
+    /// writes finished long enough ago that later events are someone else's
+    fn own_writes(&self) -> HashSet<PathBuf> {
+        let now = Instant::now();
+        let mut writes = self.own_writes.lock().unwrap();
+        writes.retain(|_, until| !until.is_some_and(|until| until <= now));
+        writes.keys().cloned().collect()
+    }
+
+    pub fn in_watched_folder(&self, folder: &Path, path: &Path) -> bool {
+        if is_held(path) {
+            return false;
+        }
+        if self.settings.include_subfolders {
+            path.starts_with(folder)
+        } else {
+            path.parent() == Some(folder)
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use super::{ImageViewerApp, LoaderMessage};
+use std::path::PathBuf;
+
+/// A finished web gallery export
+pub struct WebGalleryResult {
+    pub index: PathBuf,
+    pub exported: usize,
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+impl ImageViewerApp {
+    /// Ask for an output folder, then export the selection (or everything shown) in the background
+    pub fn start_web_gallery(&mut self) {
+        if self.web_gallery_running {
+            return;
+        }
+
+        let sources = self.selected_or_filtered_paths();
+        if sources.is_empty() {
+            self.show_status("No images for a web gallery");
+            return;
+        }
+
+        let Some(folder) = rfd::FileDialog::new()
+            .set_title(format!("Web gallery of {} images", sources.len()))
+            .pick_folder()
+        else {
+            return;
+        };
+
+        let options = self.settings.web_gallery.clone();
+        let jobs: Vec<_> = sources
+            .into_iter()
+            .map(|path| {
+                let adjustments = self.export_adjustments(&path);
+                (path, adjustments)
+            })
+            .collect();
+        let total = jobs.len();
+
+        self.web_gallery_running = true;
+        self.web_gallery_result = None;
+        self.spawn_long_job(move |_| {
+            let result = crate::web_gallery::generate(jobs, &options, &folder)
+                .map(|(index, failed)| WebGalleryResult {
+                    index,
+                    exported: total - failed.len(),
+                    failed,
+                })
+                .map_err(|e| e.to_string());
+            Some(LoaderMessage::WebGalleryFinished(result))
+        });
+    }
+
+    pub fn handle_web_gallery_finished(&mut self, result: Result<WebGalleryResult, String>) {
+        self.web_gallery_running = false;
+        match result {
+            Ok(gallery) => {
+                for (path, error) in &gallery.failed {
+                    log::warn!("Web gallery skipped {}: {}", path.display(), error);
+                }
+                self.show_status(&format!(
+                    "Web gallery created with {} images",
+                    gallery.exported
+                ));
+                self.web_gallery_result = Some(gallery);
+            }
+            Err(e) => {
+                log::error!("Web gallery failed: {}", e);
+                self.show_status(&format!("Web gallery failed: {}", e));
+            }
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use super::ImageViewerApp;
+use crate::workspace::Workspace;
+
+impl ImageViewerApp {
+    /// Switch to the saved workspace with this name, falling back to the built-in one
+    pub fn apply_workspace(&mut self, name: &str) {
+        let workspace = self
+            .settings
+            .workspaces
+            .iter()
+            .find(|w| w.name == name)
+            .cloned()
+            .or_else(|| Workspace::builtin().into_iter().find(|w| w.name == name));
+        let Some(workspace) = workspace else {
+            self.show_status(&format!("No workspace named {}", name));
+            return;
+        };
+
+        workspace.apply(&mut self.settings);
+        self.panels_hidden = false;
+        self.layout_generation += 1;
+        self.pending_fit_to_window = true;
+        self.show_status(&format!("Workspace: {}", workspace.name));
+    }
+
+    /// Save the current layout under `name`, replacing a workspace with the same name
+    pub fn save_workspace(&mut self, name: &str) {
+        let name = name.trim();
+        if name.is_empty() {
+            return;
+        }
+        let workspace = Workspace::capture(name, &self.settings);
+        match self.settings.workspaces.iter_mut().find(|w| w.name == name) {
+            Some(existing) => *existing = workspace,
+            None => self.settings.workspaces.push(workspace),
+        }
+        self.settings.save();
+        self.show_status(&format!("Saved workspace {}", name));
+    }
+
+    pub fn delete_workspace(&mut self, name: &str) {
+        self.settings.workspaces.retain(|w| w.name != name);
+        self.settings.save();
+    }
+}
@@ -1,7 +1,100 @@
 use super::ImageViewerApp;
-use eframe::egui::Vec2;
+use crate::settings::MagnificationFilter;
+use eframe::egui::{self, Vec2};
+
+/// Zoom from which single pixels are drawn unfiltered with a grid between
+/// them, and the value under the cursor is shown in the status bar
+pub const PIXEL_GRID_ZOOM: f32 = 8.0;
 
 impl ImageViewerApp {
+    /// Size of the current image at 100% zoom. Differs from the texture size
+    /// for low-res previews and for tiled images, whose texture is an overview.
+    pub fn image_display_size(&self) -> Option<Vec2> {
+        let texture = self.current_texture.as_ref()?;
+        if let Some(size) = self.progressive_size {
+            return Some(size);
+        }
+        if let Some(tiles) = self.current_tiles() {
+            return Some(tiles.size());
+        }
+        if let Some(image) = &self.current_image {
+            let max_side = self
+                .ctx
+                .as_ref()
+                .map_or(u32::MAX, |ctx| ctx.input(|i| i.max_texture_side) as u32);
+            if self.showing_preview
+                || crate::gpu::tiles::needs_tiling(image.width(), image.height(), max_side)
+            {
+                return Some(Vec2::new(image.width() as f32, image.height() as f32));
+            }
+        }
+        Some(texture.size_vec2())
+    }
+
+    /// Tiles for the current image, once they are built
+    pub fn current_tiles(&self) -> Option<&crate::gpu::tiles::TiledImage> {
+        let (path, tiles) = self.tiled_image.as_ref()?;
+        (self.get_current_path().as_ref() == Some(path)).then_some(tiles)
+    }
+
+    /// Whether the view is zoomed in far enough to inspect single pixels
+    pub fn pixel_inspection(&self) -> bool {
+        self.zoom >= PIXEL_GRID_ZOOM
+    }
+
+    /// Filtering for image textures. Magnified pixels are drawn hard-edged
+    /// when `nearest` or when the user prefers it, and mipmaps keep
+    /// zoomed-out images from shimmering.
+    pub fn texture_options_with(&self, nearest: bool) -> egui::TextureOptions {
+        let nearest = nearest || self.settings.magnification_filter == MagnificationFilter::Nearest;
+        egui::TextureOptions {
+            magnification: if nearest {
+                egui::TextureFilter::Nearest
+            } else {
+                egui::TextureFilter::Linear
+            },
+            mipmap_mode: self
+                .settings
+                .texture_mipmaps
+                .then_some(egui::TextureFilter::Linear),
+            ..egui::TextureOptions::LINEAR
+        }
+    }
+
+    /// Filtering for the main image texture, hard-edged while inspecting pixels
+    pub fn texture_options(&self) -> egui::TextureOptions {
+        self.texture_options_with(self.nearest_textures)
+    }
+
+    /// Rebuild image textures after the filtering settings changed
+    pub fn reload_textures(&mut self) {
+        self.texture_cache.clear();
+        self.texture_access_order.clear();
+        self.refresh_adjustments_internal(true);
+    }
+
+    /// Rebuild the current texture after zooming across `PIXEL_GRID_ZOOM`, so
+    /// it is drawn with the filtering that suits the zoom
+    pub fn sync_texture_filter(&mut self) {
+        let nearest = self.pixel_inspection();
+        if nearest == self.nearest_textures {
+            return;
+        }
+        // Previews and tiled overviews are only ever magnified in passing
+        let full_size = self.current_texture.as_ref().map(|tex| tex.size_vec2());
+        if full_size.is_none() || full_size != self.image_display_size() {
+            return;
+        }
+
+        let previous = self.texture_options();
+        self.nearest_textures = nearest;
+        if self.texture_options() != previous {
+            self.texture_cache.clear();
+            self.texture_access_order.clear();
+            self.refresh_adjustments_internal(false);
+        }
+    }
+
     pub fn reset_view(&mut self) {
         // Reset to 100% zoom and center the image
         self.target_zoom = 1.0;
@@ -11,8 +104,7 @@ impl ImageViewerApp {
     }
 
     pub fn fit_to_window_internal(&mut self) {
-        if let Some(texture) = &self.current_texture {
-            let image_size = texture.size_vec2();
+        if let Some(image_size) = self.image_display_size() {
             // Use the actual available view size from the UI
             let available = self.available_view_size;
 
@@ -34,6 +126,20 @@ impl ImageViewerApp {
         }
     }
 
+    /// Zoom at which the whole image fits the view, never above 100%
+    pub fn fit_zoom(&self) -> Option<f32> {
+        let image_size = self.image_display_size()?;
+        let available = self.available_view_size;
+        let scale = (available.x / image_size.x).min(available.y / image_size.y);
+        Some(scale.min(1.0))
+    }
+
+    /// Whether the whole image is in view, so a swipe pages rather than pans
+    pub fn is_fit_to_window(&self) -> bool {
+        self.fit_zoom()
+            .is_some_and(|fit| self.target_zoom <= fit * 1.01)
+    }
+
     // Zoom
     pub fn zoom_in(&mut self) {
         self.set_zoom(self.target_zoom * 1.25);
@@ -55,8 +161,7 @@ impl ImageViewerApp {
     }
 
     pub fn fit_to_window(&mut self) {
-        if let Some(texture) = &self.current_texture {
-            let image_size = texture.size_vec2();
+        if let Some(image_size) = self.image_display_size() {
             let available = self.available_view_size;
 
             let scale_x = available.x / image_size.x;
@@ -72,8 +177,7 @@ impl ImageViewerApp {
     }
 
     pub fn fill_window(&mut self) {
-        if let Some(texture) = &self.current_texture {
-            let image_size = texture.size_vec2();
+        if let Some(image_size) = self.image_display_size() {
             let available = self.available_view_size;
 
             let scale_x = available.x / image_size.x;
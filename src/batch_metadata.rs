@@ -0,0 +1,160 @@
+use crate::rename::RenameInfo;
+use crate::xmp::DescriptiveMetadata;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+lazy_static::lazy_static! {
+    static ref TOKEN: Regex = Regex::new(r"\{(me|year|date|filename)\}").unwrap();
+}
+
+/// Tokens offered in the batch metadata dialog, with a short description
+pub const TOKENS: &[(&str, &str)] = &[
+    ("{me}", "Your name, as entered above"),
+    ("{year}", "Capture year, or the file year without EXIF"),
+    ("{date}", "Capture date (YYYY-MM-DD)"),
+    ("{filename}", "File name without extension"),
+];
+
+/// Where the batch metadata editor writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum MetadataTarget {
+    /// XMP sidecar next to each image; works for every format
+    #[default]
+    Sidecar,
+    /// XMP packet inside JPEG files, sidecars for everything else
+    EmbedInJpeg,
+}
+
+/// Last values used in the batch metadata dialog. Empty fields are left
+/// untouched in the files.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetadataTemplate {
+    /// Fills `{me}`
+    pub me: String,
+    pub creator: String,
+    pub copyright: String,
+    pub title: String,
+    /// Comma separated; added to the keywords each file already has
+    pub keywords: String,
+    pub target: MetadataTarget,
+}
+
+impl Default for MetadataTemplate {
+    fn default() -> Self {
+        Self {
+            me: String::new(),
+            creator: "{me}".to_string(),
+            copyright: "© {year} {me}".to_string(),
+            title: String::new(),
+            keywords: String::new(),
+            target: MetadataTarget::default(),
+        }
+    }
+}
+
+impl MetadataTemplate {
+    /// Whether rendering needs EXIF or file dates
+    pub fn needs_info(&self) -> bool {
+        [&self.creator, &self.copyright, &self.title]
+            .iter()
+            .any(|field| field.contains("{year}") || field.contains("{date}"))
+    }
+
+    /// Fields for one image; unknown tokens are kept as written
+    pub fn render(&self, path: &Path, info: &RenameInfo) -> DescriptiveMetadata {
+        let field = |template: &str| {
+            let template = template.trim();
+            if template.is_empty() {
+                return None;
+            }
+            let value = TOKEN.replace_all(template, |caps: &Captures| match &caps[1] {
+                "me" => self.me.trim().to_string(),
+                "year" => info
+                    .date
+                    .as_deref()
+                    .and_then(|d| d.get(..4))
+                    .unwrap_or_default()
+                    .to_string(),
+                "date" => info.date.clone().unwrap_or_default(),
+                "filename" => path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                _ => caps[0].to_string(),
+            });
+            // A field left empty by its tokens, like `{me}` without a name, isn't written
+            let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+            (!value.is_empty()).then_some(value)
+        };
+        DescriptiveMetadata {
+            creator: field(&self.creator),
+            copyright: field(&self.copyright),
+            title: field(&self.title),
+            keywords: self.keyword_list(),
+        }
+    }
+
+    pub fn keyword_list(&self) -> Vec<String> {
+        let mut keywords: Vec<String> = Vec::new();
+        for keyword in self.keywords.split(',').map(str::trim) {
+            if !keyword.is_empty() && !keywords.iter().any(|k| k.eq_ignore_ascii_case(keyword)) {
+                keywords.push(keyword.to_string());
+            }
+        }
+        keywords
+    }
+}
+
+/// What the dialog will write for one file
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedMetadata {
+    pub path: PathBuf,
+    /// The image itself when embedding, otherwise its sidecar
+    pub destination: PathBuf,
+    pub embedded: bool,
+    pub fields: DescriptiveMetadata,
+}
+
+/// Only JPEG files get an embedded packet; rewriting RAW files is too risky
+pub fn can_embed(path: &Path) -> bool {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .is_some_and(|e| e == "jpg" || e == "jpeg")
+}
+
+pub fn plan_metadata(
+    sources: &[PathBuf],
+    template: &MetadataTemplate,
+    infos: &[RenameInfo],
+) -> Vec<PlannedMetadata> {
+    let default_info = RenameInfo::default();
+    sources
+        .iter()
+        .enumerate()
+        .map(|(i, path)| {
+            let embedded = template.target == MetadataTarget::EmbedInJpeg && can_embed(path);
+            let destination = if embedded {
+                path.clone()
+            } else {
+                crate::xmp::find_sidecar(path).unwrap_or_else(|| crate::xmp::sidecar_path(path))
+            };
+            PlannedMetadata {
+                path: path.clone(),
+                destination,
+                embedded,
+                fields: template.render(path, infos.get(i).unwrap_or(&default_info)),
+            }
+        })
+        .collect()
+}
+
+/// Write one planned file
+pub fn write_metadata(plan: &PlannedMetadata) -> crate::errors::Result<()> {
+    if plan.embedded {
+        crate::xmp::embed_descriptive(&plan.path, &plan.fields)
+    } else {
+        crate::xmp::write_descriptive_sidecar(&plan.path, &plan.fields)
+    }
+}
@@ -0,0 +1,81 @@
+use crate::image_loader::BrushDab;
+use serde::{Deserialize, Serialize};
+
+/// What pen pressure changes about brush dabs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PressureMapping {
+    Off,
+    Opacity,
+    Size,
+    Both,
+}
+
+impl PressureMapping {
+    pub fn all() -> &'static [PressureMapping] {
+        &[
+            PressureMapping::Off,
+            PressureMapping::Opacity,
+            PressureMapping::Size,
+            PressureMapping::Both,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PressureMapping::Off => "Off",
+            PressureMapping::Opacity => "Opacity",
+            PressureMapping::Size => "Size",
+            PressureMapping::Both => "Size and Opacity",
+        }
+    }
+}
+
+/// The local adjustment brush
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BrushSettings {
+    /// Radius as a fraction of the image height
+    pub size: f32,
+    pub opacity: f32,
+    pub pressure: PressureMapping,
+}
+
+impl Default for BrushSettings {
+    fn default() -> Self {
+        Self {
+            size: 0.05,
+            opacity: 1.0,
+            pressure: PressureMapping::Opacity,
+        }
+    }
+}
+
+impl BrushSettings {
+    /// The dab at image-relative `center` for a pen pressed with `pressure`,
+    /// from 0 to 1. Mice and fingers paint at full pressure.
+    pub fn dab(&self, center: [f32; 2], pressure: f32) -> BrushDab {
+        let pressure = pressure.clamp(0.0, 1.0);
+        // Even the lightest touch leaves a mark
+        let size_pressure = pressure.max(0.1);
+        let (size, opacity) = match self.pressure {
+            PressureMapping::Off => (1.0, 1.0),
+            PressureMapping::Opacity => (1.0, pressure),
+            PressureMapping::Size => (size_pressure, 1.0),
+            PressureMapping::Both => (size_pressure, pressure),
+        };
+        BrushDab {
+            center,
+            radius: self.size * size,
+            opacity: self.opacity * opacity,
+        }
+    }
+}
+
+/// Pressure of the latest pen or touch event this frame, for devices that
+/// report it
+pub fn pen_pressure(input: &egui::InputState) -> Option<f32> {
+    input.events.iter().rev().find_map(|event| match event {
+        egui::Event::Touch { force, .. } => *force,
+        _ => None,
+    })
+}
@@ -0,0 +1,186 @@
+use crate::settings::{Settings, SortMode};
+use std::path::PathBuf;
+
+/// Seconds per image when `--slideshow` is given without an interval
+pub const DEFAULT_SLIDESHOW_SECONDS: f32 = 5.0;
+
+pub const USAGE: &str = "\
+Usage: rview [OPTIONS] [PATH | URL]
+       rview convert --help
+
+Options:
+  --fullscreen              Start in fullscreen
+  --slideshow [SECONDS]     Advance through the folder automatically (default 5)
+  --recursive               Include images in subfolders
+  --sort MODE[:desc]        Sort by name, date, taken, size, type, rating, random,
+                            megapixels, aspect, iso or focal
+  --filter-rating STARS     Only show images rated at least 1 to 5 stars
+  --safe-mode               Don't restore the previous session
+  --settings FILE           Read and save settings in FILE
+  --new-window              Don't hand the path to a window that's already open
+  -d, --debug               Verbose logging
+  -h, --help                Show this help";
+
+/// What the viewer was launched with
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CliArgs {
+    /// Image, folder or http(s) URL to open
+    pub path: Option<PathBuf>,
+    pub debug: bool,
+    pub help: bool,
+    pub new_window: bool,
+    pub fullscreen: bool,
+    /// Seconds per image
+    pub slideshow: Option<f32>,
+    pub recursive: bool,
+    /// Sort mode and whether it's ascending
+    pub sort: Option<(SortMode, bool)>,
+    pub filter_rating: Option<u8>,
+    pub safe_mode: bool,
+    pub settings: Option<PathBuf>,
+}
+
+impl CliArgs {
+    /// Parse the arguments after the program name
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut cli = CliArgs::default();
+        let mut args = args.into_iter().peekable();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-d" | "--debug" => cli.debug = true,
+                "-h" | "--help" => cli.help = true,
+                "--new-window" => cli.new_window = true,
+                "--fullscreen" => cli.fullscreen = true,
+                "--recursive" => cli.recursive = true,
+                "--safe-mode" => cli.safe_mode = true,
+                "--slideshow" => {
+                    // The interval is optional, so only take the next argument if it's a number
+                    let interval = args.peek().and_then(|next| next.parse::<f32>().ok());
+                    if interval.is_some() {
+                        args.next();
+                    }
+                    let seconds = interval.unwrap_or(DEFAULT_SLIDESHOW_SECONDS);
+                    if !seconds.is_finite() || seconds <= 0.0 {
+                        return Err(format!("Invalid slideshow interval '{}'", seconds));
+                    }
+                    cli.slideshow = Some(seconds);
+                }
+                "--sort" => {
+                    let value = value_for(&arg, args.next())?;
+                    cli.sort = Some(parse_sort(&value)?);
+                }
+                "--filter-rating" => {
+                    let value = value_for(&arg, args.next())?;
+                    match value.parse::<u8>() {
+                        Ok(stars) if (1..=5).contains(&stars) => cli.filter_rating = Some(stars),
+                        _ => return Err(format!("Invalid rating '{}', expected 1 to 5", value)),
+                    }
+                }
+                "--settings" => {
+                    let value = value_for(&arg, args.next())?;
+                    cli.settings = Some(PathBuf::from(value));
+                }
+                flag if flag.starts_with('-') && flag.len() > 1 => {
+                    return Err(format!("Unknown option '{}'", flag));
+                }
+                _ => {
+                    if cli.path.is_some() {
+                        return Err(format!("Unexpected argument '{}'", arg));
+                    }
+                    cli.path = Some(PathBuf::from(arg));
+                }
+            }
+        }
+
+        Ok(cli)
+    }
+
+    /// The options that change what a window shows, as arguments `parse`
+    /// reads back, so a window already open can apply them too
+    pub fn window_options(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.fullscreen {
+            args.push("--fullscreen".to_string());
+        }
+        if let Some(seconds) = self.slideshow {
+            args.extend(["--slideshow".to_string(), seconds.to_string()]);
+        }
+        if self.recursive {
+            args.push("--recursive".to_string());
+        }
+        if let Some((mode, ascending)) = self.sort {
+            let order = if ascending { "asc" } else { "desc" };
+            args.extend([
+                "--sort".to_string(),
+                format!("{}:{}", sort_name(mode), order),
+            ]);
+        }
+        if let Some(stars) = self.filter_rating {
+            args.extend(["--filter-rating".to_string(), stars.to_string()]);
+        }
+        args
+    }
+
+    /// Override the saved choices with the ones given on the command line
+    pub fn apply_to(&self, settings: &mut Settings) {
+        if self.recursive {
+            settings.include_subfolders = true;
+        }
+        if let Some((mode, ascending)) = self.sort {
+            settings.sort_mode = mode;
+            settings.sort_ascending = ascending;
+        }
+        if let Some(stars) = self.filter_rating {
+            settings.filter_by_rating = stars;
+        }
+    }
+}
+
+fn value_for(flag: &str, value: Option<String>) -> Result<String, String> {
+    value
+        .filter(|v| !v.starts_with("--"))
+        .ok_or_else(|| format!("{} needs a value", flag))
+}
+
+/// The name `--sort` takes for `mode`
+fn sort_name(mode: SortMode) -> &'static str {
+    match mode {
+        SortMode::Name => "name",
+        SortMode::Date => "date",
+        SortMode::DateTaken => "taken",
+        SortMode::Size => "size",
+        SortMode::Type => "type",
+        SortMode::Rating => "rating",
+        SortMode::Random => "random",
+        SortMode::Dimensions => "megapixels",
+        SortMode::AspectRatio => "aspect",
+        SortMode::Iso => "iso",
+        SortMode::FocalLength => "focal",
+    }
+}
+
+/// `date`, `rating:desc` and the like
+fn parse_sort(value: &str) -> Result<(SortMode, bool), String> {
+    let (mode, order) = value.split_once(':').unwrap_or((value, "asc"));
+    let ascending = match order.to_lowercase().as_str() {
+        "asc" => true,
+        "desc" => false,
+        _ => return Err(format!("Sort order must be asc or desc, not '{}'", order)),
+    };
+    let mode = match mode.to_lowercase().as_str() {
+        "name" => SortMode::Name,
+        "date" | "modified" => SortMode::Date,
+        "taken" | "date-taken" => SortMode::DateTaken,
+        "size" => SortMode::Size,
+        "type" => SortMode::Type,
+        "rating" => SortMode::Rating,
+        "random" => SortMode::Random,
+        "megapixels" | "dimensions" => SortMode::Dimensions,
+        "aspect" | "aspect-ratio" => SortMode::AspectRatio,
+        "iso" => SortMode::Iso,
+        "focal" | "focal-length" => SortMode::FocalLength,
+        _ => return Err(format!("Unknown sort mode '{}'", mode)),
+    };
+    Ok((mode, ascending))
+}
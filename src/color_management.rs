@@ -0,0 +1,509 @@
+use image::{DynamicImage, ImageDecoder, ImageReader, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Color transform from an image's embedded ICC profile to the display profile
+pub type DisplayTransform = Arc<qcms::Transform>;
+
+/// Read the embedded ICC profile from a JPEG/PNG/TIFF/WebP file without decoding pixels
+pub fn read_embedded_profile(path: &Path) -> Option<Vec<u8>> {
+    if crate::image_loader::is_raw_file(path) {
+        return None;
+    }
+
+    let mut decoder = ImageReader::open(path)
+        .ok()?
+        .with_guessed_format()
+        .ok()?
+        .into_decoder()
+        .ok()?;
+    decoder.icc_profile().ok().flatten()
+}
+
+/// Load the configured display profile, falling back to sRGB when none is set or it can't be read
+fn load_display_profile(display_profile: Option<&Path>) -> Box<qcms::Profile> {
+    let custom = display_profile.and_then(|p| match std::fs::read(p) {
+        Ok(data) => qcms::Profile::new_from_slice(&data, false),
+        Err(e) => {
+            log::warn!("Failed to read display profile {}: {}", p.display(), e);
+            None
+        }
+    });
+
+    let mut profile = custom.unwrap_or_else(qcms::Profile::new_sRGB);
+    profile.precache_output_transform();
+    profile
+}
+
+/// Build the transform for an image. Returns `None` when no conversion is needed,
+/// i.e. an untagged (assumed sRGB) image shown on an sRGB display.
+pub fn build_transform(path: &Path, display_profile: Option<&Path>) -> Option<DisplayTransform> {
+    let embedded = read_embedded_profile(path);
+    if embedded.is_none() && display_profile.is_none() {
+        return None;
+    }
+
+    let input = image_profile(path, embedded.as_deref())?;
+    let output = load_display_profile(display_profile);
+
+    qcms::Transform::new(
+        &input,
+        &output,
+        qcms::DataType::RGBA8,
+        qcms::Intent::Perceptual,
+    )
+    .map(Arc::new)
+}
+
+/// The profile an image's pixels are in: its embedded one, or sRGB when untagged.
+/// `None` when the embedded profile is invalid.
+fn image_profile(path: &Path, embedded: Option<&[u8]>) -> Option<Box<qcms::Profile>> {
+    match embedded {
+        Some(data) => qcms::Profile::new_from_slice(data, false).or_else(|| {
+            log::warn!("Ignoring invalid ICC profile in {}", path.display());
+            None
+        }),
+        None => Some(qcms::Profile::new_sRGB()),
+    }
+}
+
+/// Convert an image into display space
+pub fn apply_transform(transform: &qcms::Transform, image: &DynamicImage) -> DynamicImage {
+    let mut rgba = image.to_rgba8();
+    transform.apply(&mut rgba);
+    DynamicImage::ImageRgba8(rgba)
+}
+
+/// Grid points along each axis of a display lookup table
+pub const DISPLAY_LUT_SIZE: u32 = 33;
+
+/// `transform` sampled on a grid of DISPLAY_LUT_SIZE points per channel, for
+/// the display shader to convert adjusted pixels with. Red varies along each
+/// row, green from row to row and blue from one slice of rows to the next.
+pub fn display_lut(transform: &qcms::Transform) -> RgbaImage {
+    let size = DISPLAY_LUT_SIZE;
+    let level = |i: u32| (i as f32 * 255.0 / (size - 1) as f32).round() as u8;
+    let mut lut = RgbaImage::from_fn(size, size * size, |x, y| {
+        image::Rgba([level(x), level(y % size), level(y / size), 255])
+    });
+    transform.apply(&mut lut);
+    lut
+}
+
+/// Caches the transform for the current image so navigation-free refreshes
+/// (adjustment changes, overlays) don't re-read the ICC profile from disk.
+#[derive(Default)]
+pub struct TransformCache {
+    key: Option<(PathBuf, Option<PathBuf>)>,
+    transform: Option<DisplayTransform>,
+}
+
+impl TransformCache {
+    pub fn get(&mut self, path: &Path, display_profile: Option<&Path>) -> Option<DisplayTransform> {
+        let key = (path.to_path_buf(), display_profile.map(Path::to_path_buf));
+        if self.key.as_ref() != Some(&key) {
+            self.transform = build_transform(path, display_profile);
+            self.key = Some(key);
+        }
+        self.transform.clone()
+    }
+
+    pub fn clear(&mut self) {
+        self.key = None;
+        self.transform = None;
+    }
+}
+
+/// Colour shown over pixels the printer can't reproduce
+const GAMUT_WARNING_COLOR: [u8; 3] = [255, 0, 255];
+/// Largest change in any channel, after a round trip through the printer
+/// profile, for a colour still to count as printable
+const GAMUT_TOLERANCE: u8 = 6;
+
+/// How colours outside the printer's gamut are brought in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ProofIntent {
+    /// Compress the whole image to keep relationships between colours
+    #[default]
+    Perceptual,
+    /// Keep in-gamut colours exact and clip the rest
+    RelativeColorimetric,
+}
+
+impl ProofIntent {
+    pub const ALL: [ProofIntent; 2] = [ProofIntent::Perceptual, ProofIntent::RelativeColorimetric];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProofIntent::Perceptual => "Perceptual",
+            ProofIntent::RelativeColorimetric => "Relative",
+        }
+    }
+
+    fn qcms(self) -> qcms::Intent {
+        match self {
+            ProofIntent::Perceptual => qcms::Intent::Perceptual,
+            ProofIntent::RelativeColorimetric => qcms::Intent::RelativeColorimetric,
+        }
+    }
+}
+
+/// Printer or paper profile simulated by soft proofing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct SoftProofSettings {
+    /// Output ICC profile, usually supplied by the print lab
+    pub profile: Option<PathBuf>,
+    pub intent: ProofIntent,
+    /// Paint colours outside the printer's gamut in `GAMUT_WARNING_COLOR`
+    pub gamut_warning: bool,
+}
+
+/// Transforms that show an image as it would print. Colours go to the
+/// printer profile and back, so they pick up its gamut and rendering.
+pub struct SoftProof {
+    to_printer: qcms::Transform,
+    from_printer: qcms::Transform,
+    /// Relative colorimetric round trip that finds out-of-gamut colours
+    gamut_check: Option<(qcms::Transform, qcms::Transform)>,
+    /// From the image's space to the display, when colour management is on
+    to_display: Option<DisplayTransform>,
+}
+
+/// Build the soft proof for an image. `to_display` is the transform normally
+/// applied for display, which the proof still goes through.
+pub fn build_soft_proof(
+    path: &Path,
+    settings: &SoftProofSettings,
+    to_display: Option<DisplayTransform>,
+) -> Result<SoftProof, String> {
+    let printer_path = settings
+        .profile
+        .as_deref()
+        .ok_or_else(|| "No printer profile chosen".to_string())?;
+    let data = std::fs::read(printer_path)
+        .map_err(|e| format!("Failed to read {}: {}", printer_path.display(), e))?;
+    let mut printer = qcms::Profile::new_from_slice(&data, false)
+        .ok_or_else(|| format!("Invalid ICC profile: {}", printer_path.display()))?;
+    printer.precache_output_transform();
+
+    let embedded = read_embedded_profile(path);
+    let mut image = image_profile(path, embedded.as_deref())
+        .ok_or_else(|| format!("Invalid ICC profile in {}", path.display()))?;
+    image.precache_output_transform();
+
+    let transform = |input: &qcms::Profile, output: &qcms::Profile, intent: qcms::Intent| {
+        qcms::Transform::new(input, output, qcms::DataType::RGBA8, intent)
+            .ok_or_else(|| "Only RGB printer profiles can be soft proofed".to_string())
+    };
+    let back = qcms::Intent::RelativeColorimetric;
+    let gamut_check = if settings.gamut_warning {
+        Some((
+            transform(&image, &printer, qcms::Intent::RelativeColorimetric)?,
+            transform(&printer, &image, back)?,
+        ))
+    } else {
+        None
+    };
+
+    Ok(SoftProof {
+        to_printer: transform(&image, &printer, settings.intent.qcms())?,
+        from_printer: transform(&printer, &image, back)?,
+        gamut_check,
+        to_display,
+    })
+}
+
+/// Show an image as it would print, converted for display
+pub fn apply_soft_proof(proof: &SoftProof, image: &DynamicImage) -> DynamicImage {
+    let original = image.to_rgba8();
+    let mut proofed = original.clone();
+    proof.to_printer.apply(&mut proofed);
+    proof.from_printer.apply(&mut proofed);
+    if let Some(transform) = &proof.to_display {
+        transform.apply(&mut proofed);
+    }
+
+    if let Some((to_printer, from_printer)) = &proof.gamut_check {
+        let mut round_trip = original.clone();
+        to_printer.apply(&mut round_trip);
+        from_printer.apply(&mut round_trip);
+        for ((shown, before), after) in proofed
+            .pixels_mut()
+            .zip(original.pixels())
+            .zip(round_trip.pixels())
+        {
+            if out_of_gamut(before.0, after.0) {
+                shown.0[..3].copy_from_slice(&GAMUT_WARNING_COLOR);
+            }
+        }
+    }
+
+    DynamicImage::ImageRgba8(proofed)
+}
+
+/// Whether a colour moved by more than the tolerance on its round trip
+pub fn out_of_gamut(before: [u8; 4], after: [u8; 4]) -> bool {
+    (0..3).any(|c| before[c].abs_diff(after[c]) > GAMUT_TOLERANCE)
+}
+
+/// Caches the soft proof for the current image, like `TransformCache`
+#[derive(Default)]
+pub struct SoftProofCache {
+    key: Option<(PathBuf, SoftProofSettings, Option<PathBuf>)>,
+    proof: Option<Arc<SoftProof>>,
+}
+
+impl SoftProofCache {
+    /// The proof for `path`, rebuilt when the image or settings change.
+    /// Errors are only returned when the proof is first built.
+    pub fn get(
+        &mut self,
+        path: &Path,
+        settings: &SoftProofSettings,
+        display_profile: Option<&Path>,
+        to_display: Option<DisplayTransform>,
+    ) -> Result<Option<Arc<SoftProof>>, String> {
+        let key = (
+            path.to_path_buf(),
+            settings.clone(),
+            display_profile.map(Path::to_path_buf),
+        );
+        if self.key.as_ref() != Some(&key) {
+            self.key = Some(key);
+            self.proof = None;
+            self.proof = Some(Arc::new(build_soft_proof(path, settings, to_display)?));
+        }
+        Ok(self.proof.clone())
+    }
+
+    pub fn clear(&mut self) {
+        self.key = None;
+        self.proof = None;
+    }
+}
+
+/// ICC connection space white, D50 in XYZ
+const D50: [f64; 3] = [0.9642, 1.0, 0.8249];
+
+/// Cone response matrix of the Bradford chromatic adaptation
+const BRADFORD: Matrix = [
+    [0.8951, 0.2664, -0.1614],
+    [-0.7502, 1.7135, 0.0367],
+    [0.0389, -0.0685, 1.0296],
+];
+
+type Matrix = [[f64; 3]; 3];
+
+/// Color space exports are converted to and tagged with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum OutputProfile {
+    /// What browsers assume for untagged images
+    #[default]
+    Srgb,
+    AdobeRgb,
+    DisplayP3,
+}
+
+impl OutputProfile {
+    pub const ALL: [OutputProfile; 3] = [
+        OutputProfile::Srgb,
+        OutputProfile::AdobeRgb,
+        OutputProfile::DisplayP3,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            OutputProfile::Srgb => "sRGB",
+            OutputProfile::AdobeRgb => "Adobe RGB (1998)",
+            OutputProfile::DisplayP3 => "Display P3",
+        }
+    }
+
+    /// Look a profile up by a loose name such as `adobergb` or `p3`
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+        match name.as_str() {
+            "srgb" => Some(OutputProfile::Srgb),
+            "adobergb" | "adobergb1998" => Some(OutputProfile::AdobeRgb),
+            "p3" | "displayp3" => Some(OutputProfile::DisplayP3),
+            _ => None,
+        }
+    }
+
+    /// Red, green and blue primaries and the white point, as CIE xy
+    fn chromaticities(&self) -> [[f64; 2]; 4] {
+        const D65: [f64; 2] = [0.3127, 0.3290];
+        match self {
+            OutputProfile::Srgb => [[0.64, 0.33], [0.30, 0.60], [0.15, 0.06], D65],
+            OutputProfile::AdobeRgb => [[0.64, 0.33], [0.21, 0.71], [0.15, 0.06], D65],
+            OutputProfile::DisplayP3 => [[0.680, 0.320], [0.265, 0.690], [0.150, 0.060], D65],
+        }
+    }
+
+    /// XYZ of the red, green and blue primaries, adapted to D50 as ICC
+    /// profiles store them
+    pub fn colorants(&self) -> [[f64; 3]; 3] {
+        let [red, green, blue, white] = self.chromaticities();
+        let xyz = |[x, y]: [f64; 2]| [x / y, 1.0, (1.0 - x - y) / y];
+        let (red, green, blue, white) = (xyz(red), xyz(green), xyz(blue), xyz(white));
+        let primaries: Matrix = [0, 1, 2].map(|row| [red[row], green[row], blue[row]]);
+        let scale = apply(&invert(&primaries), white);
+
+        let (from, to) = (apply(&BRADFORD, white), apply(&BRADFORD, D50));
+        let cones: Matrix = [0, 1, 2].map(|row| {
+            let mut diagonal = [0.0; 3];
+            diagonal[row] = to[row] / from[row];
+            diagonal
+        });
+        let adapt = multiply(&invert(&BRADFORD), &multiply(&cones, &BRADFORD));
+        [0, 1, 2].map(|column| {
+            let primary = [0, 1, 2].map(|row| primaries[row][column] * scale[column]);
+            apply(&adapt, primary)
+        })
+    }
+
+    /// Tone curve as an ICC `curv` table, or a single gamma in 8.8 fixed point
+    fn curve(&self) -> Vec<u16> {
+        match self {
+            // 563/256, the gamma Adobe specifies
+            OutputProfile::AdobeRgb => vec![563],
+            OutputProfile::Srgb | OutputProfile::DisplayP3 => (0..1024)
+                .map(|i| {
+                    let c = i as f64 / 1023.0;
+                    let linear = if c <= 0.04045 {
+                        c / 12.92
+                    } else {
+                        ((c + 0.055) / 1.055).powf(2.4)
+                    };
+                    (linear * 65535.0).round() as u16
+                })
+                .collect(),
+        }
+    }
+
+    /// A version 2 matrix/TRC ICC profile for embedding in exported files
+    pub fn icc_profile(&self) -> Vec<u8> {
+        let s15_fixed16 = |v: f64| ((v * 65536.0).round() as i32).to_be_bytes();
+        let xyz_tag = |xyz: [f64; 3]| {
+            let mut tag = b"XYZ \0\0\0\0".to_vec();
+            xyz.iter().for_each(|&v| tag.extend(s15_fixed16(v)));
+            tag
+        };
+        let curve = self.curve();
+        let mut curv = b"curv\0\0\0\0".to_vec();
+        curv.extend((curve.len() as u32).to_be_bytes());
+        curve.iter().for_each(|v| curv.extend(v.to_be_bytes()));
+        let mut desc = b"desc\0\0\0\0".to_vec();
+        desc.extend((self.label().len() as u32 + 1).to_be_bytes());
+        desc.extend(self.label().as_bytes());
+        // Terminator, then empty Unicode and ScriptCode descriptions
+        desc.extend([0; 1 + 8 + 3 + 67]);
+        let mut cprt = b"text\0\0\0\0".to_vec();
+        cprt.extend(b"No copyright, use freely\0");
+
+        let [red, green, blue] = self.colorants();
+        let tags: [(&[u8; 4], Vec<u8>); 9] = [
+            (b"desc", desc),
+            (b"cprt", cprt),
+            (b"wtpt", xyz_tag(D50)),
+            (b"rXYZ", xyz_tag(red)),
+            (b"gXYZ", xyz_tag(green)),
+            (b"bXYZ", xyz_tag(blue)),
+            (b"rTRC", curv.clone()),
+            (b"gTRC", curv.clone()),
+            (b"bTRC", curv),
+        ];
+
+        let data_start = 128 + 4 + 12 * tags.len();
+        let mut table = Vec::new();
+        let mut data = Vec::new();
+        for (signature, tag) in &tags {
+            table.extend(*signature);
+            table.extend(((data_start + data.len()) as u32).to_be_bytes());
+            table.extend((tag.len() as u32).to_be_bytes());
+            data.extend(tag);
+            data.resize(data.len().next_multiple_of(4), 0);
+        }
+
+        let size = data_start + data.len();
+        let mut icc = Vec::with_capacity(size);
+        icc.extend((size as u32).to_be_bytes());
+        icc.extend([0; 4]); // preferred CMM
+        icc.extend([2, 0x10, 0, 0]); // version 2.1
+        icc.extend(b"mntrRGB XYZ ");
+        icc.extend([0; 12]); // creation date
+        icc.extend(b"acsp");
+        icc.extend([0; 24]); // platform, flags, manufacturer, model, attributes
+        icc.extend([0; 4]); // perceptual intent
+        D50.iter().for_each(|&v| icc.extend(s15_fixed16(v)));
+        icc.extend([0; 48]); // creator, profile ID, reserved
+        icc.extend((tags.len() as u32).to_be_bytes());
+        icc.extend(table);
+        icc.extend(data);
+        icc
+    }
+}
+
+fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+    [0, 1, 2].map(|row| [0, 1, 2].map(|column| (0..3).map(|k| a[row][k] * b[k][column]).sum()))
+}
+
+fn apply(m: &Matrix, v: [f64; 3]) -> [f64; 3] {
+    [0, 1, 2].map(|row| (0..3).map(|k| m[row][k] * v[k]).sum())
+}
+
+fn invert(m: &Matrix) -> Matrix {
+    let cofactor = |row: usize, column: usize| {
+        let (r1, r2) = ((row + 1) % 3, (row + 2) % 3);
+        let (c1, c2) = ((column + 1) % 3, (column + 2) % 3);
+        m[r1][c1] * m[r2][c2] - m[r1][c2] * m[r2][c1]
+    };
+    let determinant: f64 = (0..3).map(|k| m[0][k] * cofactor(0, k)).sum();
+    // The adjugate is the transposed cofactor matrix
+    [0, 1, 2].map(|row| [0, 1, 2].map(|column| cofactor(column, row) / determinant))
+}
+
+/// Convert an image for export from the profile of the file it came from to
+/// `output`. Untagged images are taken to be sRGB and left alone for sRGB
+/// output; conversions go through 8 bits per channel.
+pub fn convert_for_export(
+    image: DynamicImage,
+    source: Option<&Path>,
+    output: OutputProfile,
+) -> DynamicImage {
+    let embedded = source.and_then(read_embedded_profile);
+    if embedded.is_none() && output == OutputProfile::Srgb {
+        return image;
+    }
+    let input = match &embedded {
+        Some(data) => qcms::Profile::new_from_slice(data, false),
+        None => Some(qcms::Profile::new_sRGB()),
+    };
+    let transform = input
+        .zip(qcms::Profile::new_from_slice(&output.icc_profile(), false))
+        .and_then(|(input, mut target)| {
+            target.precache_output_transform();
+            qcms::Transform::new(
+                &input,
+                &target,
+                qcms::DataType::RGBA8,
+                qcms::Intent::Perceptual,
+            )
+        });
+    let Some(transform) = transform else {
+        log::warn!("Exporting without converting to {}", output.label());
+        return image;
+    };
+    let converted = apply_transform(&transform, &image);
+    if image.color().has_alpha() {
+        converted
+    } else {
+        DynamicImage::ImageRgb8(converted.to_rgb8())
+    }
+}
@@ -0,0 +1,467 @@
+use crate::errors::{Result, ViewerError};
+use crate::exif_data::ExifInfo;
+use crate::image_loader::ImageAdjustments;
+use ab_glyph::{FontVec, PxScale};
+use image::{imageops, Rgb, RgbImage};
+use imageproc::drawing::{draw_text_mut, text_size};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+/// Pages are rendered at print resolution
+const DPI: f32 = 300.0;
+const BACKGROUND: Rgb<u8> = Rgb([255, 255, 255]);
+const CAPTION_COLOR: Rgb<u8> = Rgb([40, 40, 40]);
+const EXIF_COLOR: Rgb<u8> = Rgb([110, 110, 110]);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ContactSheetFormat {
+    #[default]
+    Pdf,
+    Jpeg,
+    Png,
+}
+
+impl ContactSheetFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ContactSheetFormat::Pdf => "PDF",
+            ContactSheetFormat::Jpeg => "JPEG",
+            ContactSheetFormat::Png => "PNG",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ContactSheetFormat::Pdf => "pdf",
+            ContactSheetFormat::Jpeg => "jpg",
+            ContactSheetFormat::Png => "png",
+        }
+    }
+
+    pub fn all() -> &'static [ContactSheetFormat] {
+        &[
+            ContactSheetFormat::Pdf,
+            ContactSheetFormat::Jpeg,
+            ContactSheetFormat::Png,
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum PaperSize {
+    #[default]
+    A4,
+    A3,
+    Letter,
+}
+
+impl PaperSize {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PaperSize::A4 => "A4",
+            PaperSize::A3 => "A3",
+            PaperSize::Letter => "Letter",
+        }
+    }
+
+    /// Portrait size in millimetres
+    pub fn size_mm(&self) -> (f32, f32) {
+        match self {
+            PaperSize::A4 => (210.0, 297.0),
+            PaperSize::A3 => (297.0, 420.0),
+            PaperSize::Letter => (215.9, 279.4),
+        }
+    }
+
+    pub fn all() -> &'static [PaperSize] {
+        &[PaperSize::A4, PaperSize::A3, PaperSize::Letter]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContactSheetOptions {
+    pub columns: u32,
+    pub rows: u32,
+    pub paper: PaperSize,
+    pub landscape: bool,
+    /// File name under each image
+    pub captions: bool,
+    /// Aperture, shutter speed, ISO and focal length under the file name
+    pub exif_line: bool,
+    pub format: ContactSheetFormat,
+}
+
+impl Default for ContactSheetOptions {
+    fn default() -> Self {
+        Self {
+            columns: 4,
+            rows: 5,
+            paper: PaperSize::A4,
+            landscape: false,
+            captions: true,
+            exif_line: false,
+            format: ContactSheetFormat::Pdf,
+        }
+    }
+}
+
+impl ContactSheetOptions {
+    pub fn page_size_mm(&self) -> (f32, f32) {
+        let (w, h) = self.paper.size_mm();
+        if self.landscape {
+            (h, w)
+        } else {
+            (w, h)
+        }
+    }
+
+    pub fn per_page(&self) -> usize {
+        (self.columns.max(1) * self.rows.max(1)) as usize
+    }
+
+    pub fn page_count(&self, images: usize) -> usize {
+        images.div_ceil(self.per_page())
+    }
+}
+
+/// Pixel geometry of one page, shared by every cell
+#[derive(Debug, Clone, PartialEq)]
+pub struct SheetLayout {
+    pub page_width: u32,
+    pub page_height: u32,
+    pub margin: u32,
+    pub gap: u32,
+    pub cell_width: u32,
+    pub cell_height: u32,
+    /// Height left for the image once the caption lines are reserved
+    pub image_height: u32,
+    pub font_size: f32,
+    pub line_height: u32,
+}
+
+impl SheetLayout {
+    pub fn new(options: &ContactSheetOptions) -> Self {
+        let (width_mm, height_mm) = options.page_size_mm();
+        let page_width = (width_mm / 25.4 * DPI).round() as u32;
+        let page_height = (height_mm / 25.4 * DPI).round() as u32;
+        let columns = options.columns.max(1);
+        let rows = options.rows.max(1);
+
+        let margin = page_width.min(page_height) / 20;
+        let gap = margin / 2;
+        let cell_width = (page_width - 2 * margin - gap * (columns - 1)) / columns;
+        let cell_height = (page_height - 2 * margin - gap * (rows - 1)) / rows;
+
+        let font_size = (cell_width as f32 / 16.0).clamp(18.0, 42.0);
+        let line_height = (font_size * 1.3).ceil() as u32;
+        let lines = options.captions as u32 + options.exif_line as u32;
+        let image_height = cell_height.saturating_sub(lines * line_height).max(1);
+
+        Self {
+            page_width,
+            page_height,
+            margin,
+            gap,
+            cell_width,
+            cell_height,
+            image_height,
+            font_size,
+            line_height,
+        }
+    }
+
+    /// Top-left corner of a cell, filling rows left to right
+    pub fn cell_origin(&self, slot: usize, columns: u32) -> (u32, u32) {
+        let columns = columns.max(1) as usize;
+        let col = (slot % columns) as u32;
+        let row = (slot / columns) as u32;
+        (
+            self.margin + col * (self.cell_width + self.gap),
+            self.margin + row * (self.cell_height + self.gap),
+        )
+    }
+}
+
+/// One output file per page for image formats: `sheet.jpg`, `sheet_2.jpg`, ...
+pub fn page_paths(output: &Path, pages: usize) -> Vec<PathBuf> {
+    let stem = output
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "contact_sheet".to_string());
+    let extension = output
+        .extension()
+        .map(|e| e.to_string_lossy().to_string())
+        .unwrap_or_default();
+    (0..pages)
+        .map(|page| {
+            if page == 0 {
+                output.to_path_buf()
+            } else {
+                output.with_file_name(format!("{}_{}.{}", stem, page + 1, extension))
+            }
+        })
+        .collect()
+}
+
+struct SheetCell {
+    image: Option<RgbImage>,
+    caption: String,
+    exif: String,
+}
+
+/// Render the contact sheet and write it to `output`, returning every file written
+pub fn generate(
+    sources: Vec<(PathBuf, ImageAdjustments)>,
+    options: &ContactSheetOptions,
+    output: &Path,
+) -> Result<Vec<PathBuf>> {
+    let layout = SheetLayout::new(options);
+    let font = caption_font()?;
+
+    let cells: Vec<SheetCell> = sources
+        .into_par_iter()
+        .map(|(path, adjustments)| load_cell(&path, &adjustments, &layout, options))
+        .collect();
+
+    // Pages are rendered one at a time; a full page at print resolution is tens of megabytes
+    let pages = cells
+        .chunks(options.per_page())
+        .map(|chunk| render_page(chunk, &layout, options, &font));
+
+    match options.format {
+        ContactSheetFormat::Pdf => {
+            save_pdf(pages, options, output)?;
+            Ok(vec![output.to_path_buf()])
+        }
+        ContactSheetFormat::Jpeg | ContactSheetFormat::Png => {
+            let paths = page_paths(output, options.page_count(cells.len()));
+            for (page, path) in pages.zip(&paths) {
+                page.save(path).map_err(|e| ViewerError::ExportError {
+                    path: path.clone(),
+                    message: e.to_string(),
+                })?;
+            }
+            Ok(paths)
+        }
+    }
+}
+
+/// Captions use egui's bundled UI font so no system font lookup is needed
+pub(crate) fn caption_font() -> Result<FontVec> {
+    let definitions = egui::FontDefinitions::default();
+    let data = definitions
+        .families
+        .get(&egui::FontFamily::Proportional)
+        .and_then(|names| names.first())
+        .and_then(|name| definitions.font_data.get(name))
+        .ok_or_else(|| ViewerError::ImageProcessingError {
+            message: "No font available for captions".to_string(),
+        })?;
+    FontVec::try_from_vec(data.font.to_vec()).map_err(|e| ViewerError::ImageProcessingError {
+        message: format!("Invalid caption font: {}", e),
+    })
+}
+
+fn load_cell(
+    path: &Path,
+    adjustments: &ImageAdjustments,
+    layout: &SheetLayout,
+    options: &ContactSheetOptions,
+) -> SheetCell {
+    let max_size = layout.cell_width.max(layout.image_height);
+    let image = match crate::image_loader::load_thumbnail(path, max_size) {
+        Ok(image) => {
+            let image = if adjustments.is_default() {
+                image
+            } else {
+                crate::image_loader::apply_adjustments_thumbnail(&image, adjustments)
+            };
+            let fitted = image.resize(
+                layout.cell_width,
+                layout.image_height,
+                imageops::FilterType::Lanczos3,
+            );
+            Some(fitted.to_rgb8())
+        }
+        Err(e) => {
+            log::warn!("Contact sheet: failed to load {}: {}", path.display(), e);
+            None
+        }
+    };
+
+    let caption = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let exif = if options.exif_line {
+        exif_summary(&ExifInfo::from_file(path))
+    } else {
+        String::new()
+    };
+
+    SheetCell {
+        image,
+        caption,
+        exif,
+    }
+}
+
+/// Short exposure line such as `f/2.8  1/250 s  ISO 200  50 mm`
+pub fn exif_summary(exif: &ExifInfo) -> String {
+    [
+        &exif.aperture,
+        &exif.shutter_speed,
+        &exif.iso,
+        &exif.focal_length,
+    ]
+    .into_iter()
+    .flatten()
+    .map(|s| s.trim())
+    .filter(|s| !s.is_empty())
+    .collect::<Vec<_>>()
+    .join("  ")
+}
+
+fn render_page(
+    cells: &[SheetCell],
+    layout: &SheetLayout,
+    options: &ContactSheetOptions,
+    font: &FontVec,
+) -> RgbImage {
+    let mut page = RgbImage::from_pixel(layout.page_width, layout.page_height, BACKGROUND);
+    let scale = PxScale::from(layout.font_size);
+
+    for (slot, cell) in cells.iter().enumerate() {
+        let (x, y) = layout.cell_origin(slot, options.columns);
+
+        match &cell.image {
+            Some(image) => {
+                // Centre the image horizontally and sit it on the caption
+                let dx = (layout.cell_width - image.width().min(layout.cell_width)) / 2;
+                let dy = layout.image_height - image.height().min(layout.image_height);
+                imageops::overlay(&mut page, image, (x + dx) as i64, (y + dy) as i64);
+            }
+            None => {
+                let placeholder = RgbImage::from_pixel(
+                    layout.cell_width,
+                    layout.image_height,
+                    Rgb([225, 225, 225]),
+                );
+                imageops::overlay(&mut page, &placeholder, x as i64, y as i64);
+            }
+        }
+
+        let mut text_y = y + layout.image_height + layout.line_height / 6;
+        if options.captions {
+            draw_caption(
+                &mut page,
+                &cell.caption,
+                CAPTION_COLOR,
+                x,
+                text_y,
+                layout,
+                scale,
+                font,
+            );
+            text_y += layout.line_height;
+        }
+        if options.exif_line && !cell.exif.is_empty() {
+            draw_caption(
+                &mut page, &cell.exif, EXIF_COLOR, x, text_y, layout, scale, font,
+            );
+        }
+    }
+
+    page
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_caption(
+    page: &mut RgbImage,
+    text: &str,
+    color: Rgb<u8>,
+    x: u32,
+    y: u32,
+    layout: &SheetLayout,
+    scale: PxScale,
+    font: &FontVec,
+) {
+    let text = fit_text(text, layout.cell_width, scale, font);
+    let (width, _) = text_size(scale, font, &text);
+    let dx = layout.cell_width.saturating_sub(width) / 2;
+    draw_text_mut(page, color, (x + dx) as i32, y as i32, scale, font, &text);
+}
+
+/// Shorten text with a trailing ellipsis until it fits the cell
+pub(crate) fn fit_text(text: &str, max_width: u32, scale: PxScale, font: &FontVec) -> String {
+    if text_size(scale, font, text).0 <= max_width {
+        return text.to_string();
+    }
+    let mut chars: Vec<char> = text.chars().collect();
+    while !chars.is_empty() {
+        chars.pop();
+        let candidate = format!("{}...", chars.iter().collect::<String>());
+        if text_size(scale, font, &candidate).0 <= max_width {
+            return candidate;
+        }
+    }
+    String::new()
+}
+
+fn save_pdf(
+    pages: impl Iterator<Item = RgbImage>,
+    options: &ContactSheetOptions,
+    output: &Path,
+) -> Result<()> {
+    use printpdf::{
+        ColorBits, ColorSpace, Image, ImageTransform, ImageXObject, Mm, PdfDocument, Px,
+    };
+
+    let (width_mm, height_mm) = options.page_size_mm();
+    let title = output
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "Contact Sheet".to_string());
+    let (doc, first_page, first_layer) =
+        PdfDocument::new(title, Mm(width_mm), Mm(height_mm), "Contact Sheet");
+
+    for (i, page) in pages.enumerate() {
+        let (page_index, layer_index) = if i == 0 {
+            (first_page, first_layer)
+        } else {
+            doc.add_page(Mm(width_mm), Mm(height_mm), "Contact Sheet")
+        };
+        let layer = doc.get_page(page_index).get_layer(layer_index);
+        let (width, height) = page.dimensions();
+        let image = Image::from(ImageXObject {
+            width: Px(width as usize),
+            height: Px(height as usize),
+            color_space: ColorSpace::Rgb,
+            bits_per_component: ColorBits::Bit8,
+            interpolate: true,
+            image_data: page.into_raw(),
+            image_filter: None,
+            smask: None,
+            clipping_bbox: None,
+        });
+        // At the render DPI the page image covers the page exactly
+        image.add_to_layer(
+            layer,
+            ImageTransform {
+                dpi: Some(DPI),
+                ..Default::default()
+            },
+        );
+    }
+
+    let file = File::create(output)?;
+    doc.save(&mut BufWriter::new(file))
+        .map_err(|e| ViewerError::ExportError {
+            path: output.to_path_buf(),
+            message: e.to_string(),
+        })
+}
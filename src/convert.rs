@@ -0,0 +1,319 @@
+use crate::app::batch_export::batch_output_paths;
+use crate::color_management::{convert_for_export, OutputProfile};
+use crate::image_loader::{FilmPreset, ImageAdjustments, ResizeFilter};
+use crate::metadata::MetadataDb;
+use crate::settings::ExportFormat;
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+
+pub const CONVERT_USAGE: &str = "\
+Usage: rview convert [OPTIONS] -o DIR INPUT...
+
+INPUT is an image, a folder, or a file name pattern with * and ? like photos/*.CR3.
+
+Options:
+  -o, --output DIR          Folder for the converted images
+  -f, --format FORMAT       jpeg, png, tiff, webp or jxl (default jpeg)
+  -q, --quality N           JPEG quality from 1 to 100 (default 90)
+  --resize PIXELS           Fit within PIXELS on the long edge, never enlarging
+  --filter FILTER           nearest, bilinear, lanczos3 or mitchell (default lanczos3)
+  --profile PROFILE         Convert to and embed srgb, adobergb or p3 (default srgb)
+  --film PRESET             Film preset such as \"Portra 400\" or tri-x-400
+  --recursive               Include images in subfolders of folder inputs
+  --ignore-edits            Don't apply the edits saved in the viewer
+  -h, --help                Show this help";
+
+const DEFAULT_QUALITY: u8 = 90;
+
+/// What `rview convert` was asked to do
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConvertOptions {
+    pub inputs: Vec<String>,
+    pub output: PathBuf,
+    pub format: ExportFormat,
+    pub quality: u8,
+    /// Long edge in pixels
+    pub resize: Option<u32>,
+    pub filter: ResizeFilter,
+    pub profile: OutputProfile,
+    pub film: Option<FilmPreset>,
+    pub recursive: bool,
+    pub ignore_edits: bool,
+    pub help: bool,
+}
+
+impl Default for ConvertOptions {
+    fn default() -> Self {
+        Self {
+            inputs: Vec::new(),
+            output: PathBuf::new(),
+            format: ExportFormat::Jpeg,
+            quality: DEFAULT_QUALITY,
+            resize: None,
+            filter: ResizeFilter::default(),
+            profile: OutputProfile::default(),
+            film: None,
+            recursive: false,
+            ignore_edits: false,
+            help: false,
+        }
+    }
+}
+
+impl ConvertOptions {
+    /// Parse the arguments after `convert`
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = ConvertOptions::default();
+        let mut output = None;
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .filter(|v| !v.starts_with("--"))
+                    .ok_or_else(|| format!("{} needs a value", arg))
+            };
+            match arg.as_str() {
+                "-h" | "--help" => options.help = true,
+                "--recursive" => options.recursive = true,
+                "--ignore-edits" => options.ignore_edits = true,
+                "-o" | "--output" => output = Some(PathBuf::from(value()?)),
+                "-f" | "--format" => {
+                    let name = value()?;
+                    options.format =
+                        parse_format(&name).ok_or_else(|| format!("Unknown format '{}'", name))?;
+                }
+                "-q" | "--quality" => {
+                    let quality = value()?;
+                    options.quality = match quality.parse::<u8>() {
+                        Ok(q) if (1..=100).contains(&q) => q,
+                        _ => return Err(format!("Quality must be 1 to 100, not '{}'", quality)),
+                    };
+                }
+                "--resize" => {
+                    let pixels = value()?;
+                    options.resize = match pixels.parse::<u32>() {
+                        Ok(p) if p > 0 => Some(p),
+                        _ => return Err(format!("Invalid size '{}'", pixels)),
+                    };
+                }
+                "--filter" => {
+                    let name = value()?;
+                    options.filter = ResizeFilter::from_name(&name)
+                        .ok_or_else(|| format!("Unknown filter '{}'", name))?;
+                }
+                "--profile" => {
+                    let name = value()?;
+                    options.profile = OutputProfile::from_name(&name)
+                        .ok_or_else(|| format!("Unknown color profile '{}'", name))?;
+                }
+                "--film" => {
+                    let name = value()?;
+                    options.film = Some(
+                        parse_film_preset(&name)
+                            .ok_or_else(|| format!("Unknown film preset '{}'", name))?,
+                    );
+                }
+                flag if flag.starts_with('-') && flag.len() > 1 => {
+                    return Err(format!("Unknown option '{}'", flag));
+                }
+                _ => options.inputs.push(arg),
+            }
+        }
+
+        if options.help {
+            return Ok(options);
+        }
+        options.output = output.ok_or("An output folder is needed (-o DIR)")?;
+        if options.inputs.is_empty() {
+            return Err("No input images given".to_string());
+        }
+        Ok(options)
+    }
+}
+
+fn parse_format(name: &str) -> Option<ExportFormat> {
+    let name = name.to_lowercase();
+    ExportFormat::all()
+        .iter()
+        .copied()
+        .find(|f| f.extensions().contains(&name.as_str()) || f.name().to_lowercase() == name)
+}
+
+/// Match preset names ignoring case, spaces and punctuation, so `tri-x-400`
+/// finds "Tri-X 400"
+pub fn parse_film_preset(name: &str) -> Option<FilmPreset> {
+    let simplify = |s: &str| -> String {
+        s.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let wanted = simplify(name);
+    FilmPreset::all()
+        .iter()
+        .copied()
+        .filter(|preset| *preset != FilmPreset::None)
+        .find(|preset| simplify(preset.name()) == wanted)
+}
+
+/// Whether `name` matches a pattern where `*` is any run of characters and
+/// `?` any single one. Case-insensitive, like file names on most cameras' cards.
+pub fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Last `*` seen and the name position it is currently standing in for
+    let mut star: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                // Let the star swallow one more character and try again
+                Some((star_p, star_n)) => {
+                    p = star_p + 1;
+                    n = star_n + 1;
+                    star = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Images named by the inputs: files as given, every supported image in
+/// folders, and the matching supported images for patterns. Sorted, without
+/// duplicates.
+pub fn expand_inputs(inputs: &[String], recursive: bool) -> Vec<PathBuf> {
+    let mut images = Vec::new();
+    let never = AtomicBool::new(false);
+    for input in inputs {
+        let path = Path::new(input);
+        if path.is_dir() {
+            crate::app::folder_scan::scan_folder(path, recursive, &never, |batch| {
+                images.extend(batch)
+            });
+            continue;
+        }
+        let file_name = path.file_name().map(|n| n.to_string_lossy().to_string());
+        match file_name {
+            Some(pattern) if pattern.contains(['*', '?']) => {
+                let folder = match path.parent() {
+                    Some(parent) if !parent.as_os_str().is_empty() => parent,
+                    _ => Path::new("."),
+                };
+                crate::app::folder_scan::scan_folder(folder, recursive, &never, |batch| {
+                    images.extend(batch.into_iter().filter(|image| {
+                        image
+                            .file_name()
+                            .is_some_and(|n| wildcard_match(&pattern, &n.to_string_lossy()))
+                    }))
+                });
+            }
+            _ => images.push(path.to_path_buf()),
+        }
+    }
+    images.sort();
+    images.dedup();
+    images
+}
+
+/// Run `rview convert`, printing a line per image. Returns the process exit code.
+pub fn run(args: impl IntoIterator<Item = String>) -> i32 {
+    let options = match ConvertOptions::parse(args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, CONVERT_USAGE);
+            return 2;
+        }
+    };
+    if options.help {
+        println!("{}", CONVERT_USAGE);
+        return 0;
+    }
+
+    let sources = expand_inputs(&options.inputs, options.recursive);
+    if sources.is_empty() {
+        eprintln!("No images match {}", options.inputs.join(" "));
+        return 1;
+    }
+    if let Err(e) = std::fs::create_dir_all(&options.output) {
+        eprintln!("Can't create {}: {}", options.output.display(), e);
+        return 1;
+    }
+
+    let metadata_db = if options.ignore_edits {
+        MetadataDb::default()
+    } else {
+        MetadataDb::load()
+    };
+    let outputs = batch_output_paths(&sources, &options.output, options.format);
+    let jobs: Vec<(PathBuf, PathBuf, ImageAdjustments)> = sources
+        .into_iter()
+        .zip(outputs)
+        .map(|(source, output)| {
+            let mut adjustments = metadata_db.get_adjustments(&source).unwrap_or_default();
+            if let Some(film) = options.film {
+                adjustments.apply_preset(film);
+            }
+            (source, output, adjustments)
+        })
+        .collect();
+
+    let total = jobs.len();
+    let failed = jobs
+        .into_par_iter()
+        .filter(|(source, output, adjustments)| {
+            match convert_one(source, output, adjustments, &options) {
+                Ok(()) => {
+                    println!("{} -> {}", source.display(), output.display());
+                    false
+                }
+                Err(e) => {
+                    eprintln!("{}: {}", source.display(), e);
+                    true
+                }
+            }
+        })
+        .count();
+
+    println!("Converted {} of {} images", total - failed, total);
+    if failed > 0 {
+        1
+    } else {
+        0
+    }
+}
+
+fn convert_one(
+    source: &Path,
+    output: &Path,
+    adjustments: &ImageAdjustments,
+    options: &ConvertOptions,
+) -> Result<(), String> {
+    let image = crate::image_loader::load_image(source).map_err(|e| e.to_string())?;
+    // Shrink first so the adjustments run on fewer pixels
+    let image = match options.resize {
+        Some(max) => crate::image_loader::resize_to_fit(image, max, options.filter),
+        None => image,
+    };
+    let image = if adjustments.is_default() {
+        image
+    } else {
+        crate::image_loader::apply_adjustments_for_export(&image, adjustments, source)
+    };
+
+    let profile = options.profile;
+    let image = convert_for_export(image, Some(source), profile);
+    crate::app::file_ops::save_exported_image(&image, output, options.quality, Some(profile))
+}
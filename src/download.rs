@@ -0,0 +1,184 @@
+use crate::image_loader::{is_supported_image, MAX_FILE_SIZE};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// How long to wait for the server to accept the connection
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+/// How long the server may go without sending anything before giving up
+const STALL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Whether the text is an http(s) address rather than a file path
+pub fn is_url(text: &str) -> bool {
+    let text = text.trim().to_lowercase();
+    text.starts_with("http://") || text.starts_with("https://")
+}
+
+/// Where downloaded images are kept, so opening a URL again skips the download
+pub fn download_folder() -> PathBuf {
+    std::env::temp_dir().join("rView downloads")
+}
+
+/// Extension for an image MIME type, for URLs whose path doesn't end in one
+pub fn extension_for_content_type(content_type: &str) -> Option<&'static str> {
+    let mime = content_type.split(';').next()?.trim().to_lowercase();
+    let extension = match mime.as_str() {
+        "image/jpeg" | "image/jpg" => "jpg",
+        "image/png" => "png",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/bmp" => "bmp",
+        "image/tiff" => "tif",
+        "image/avif" => "avif",
+        "image/heic" => "heic",
+        "image/jxl" => "jxl",
+        "image/svg+xml" => "svg",
+        "image/x-icon" | "image/vnd.microsoft.icon" => "ico",
+        _ => return None,
+    };
+    Some(extension)
+}
+
+/// File name for a URL's download: a hash of the whole URL, so different
+/// queries don't collide, and the last path segment for a readable name
+pub fn cached_file_name(url: &str) -> String {
+    // FNV-1a, which stays the same between builds unlike the std hasher
+    let hash = url.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let segment: String = path
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .filter(|s| !s.contains(':'))
+        .unwrap_or("")
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(80)
+        .collect();
+    if segment.is_empty() {
+        format!("{:016x}", hash)
+    } else {
+        format!("{:016x}-{}", hash, segment)
+    }
+}
+
+/// A file downloaded for this URL earlier, if it is still there
+pub fn cached_download(url: &str) -> Option<PathBuf> {
+    let name = cached_file_name(url);
+    let folder = download_folder();
+    let exact = folder.join(&name);
+    if exact.is_file() && is_supported_image(&exact) {
+        return Some(exact);
+    }
+    // The extension may have come from the Content-Type
+    std::fs::read_dir(&folder)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| {
+            path.file_stem()
+                .is_some_and(|stem| stem.to_string_lossy() == name)
+                && is_supported_image(path)
+        })
+}
+
+/// Download `url` into `folder`, reporting bytes received and the total when
+/// the server gives one. Written to a partial file first, so a cancelled or
+/// failed download never looks cached. Stops when the server stalls or sends
+/// more than the loader would open.
+pub fn download(
+    url: &str,
+    folder: &Path,
+    cancel: &AtomicBool,
+    mut progress: impl FnMut(u64, Option<u64>),
+) -> Result<PathBuf, String> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    runtime.block_on(async {
+        let client = reqwest::Client::builder()
+            .user_agent(concat!("rView/", env!("CARGO_PKG_VERSION")))
+            .connect_timeout(CONNECT_TIMEOUT)
+            .build()
+            .map_err(|e| e.to_string())?;
+        let mut response = tokio::time::timeout(STALL_TIMEOUT, client.get(url).send())
+            .await
+            .map_err(|_| "Timed out".to_string())?
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| e.to_string())?;
+        let too_large = || format!("Too large (max {}MB)", MAX_FILE_SIZE / (1024 * 1024));
+        if response
+            .content_length()
+            .is_some_and(|len| len > MAX_FILE_SIZE)
+        {
+            return Err(too_large());
+        }
+
+        let mut name = cached_file_name(url);
+        if !is_supported_image(Path::new(&name)) {
+            let content_type = response
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("");
+            match extension_for_content_type(content_type) {
+                Some(extension) => name = format!("{}.{}", name, extension),
+                None => return Err(format!("Not an image ({})", content_type)),
+            }
+        }
+
+        std::fs::create_dir_all(folder).map_err(|e| e.to_string())?;
+        let destination = folder.join(&name);
+        let partial = folder.join(format!("{}.part", name));
+        let mut file = std::fs::File::create(&partial).map_err(|e| e.to_string())?;
+        let total = response.content_length();
+        let mut received = 0u64;
+        progress(received, total);
+
+        let result = async {
+            loop {
+                let chunk = tokio::time::timeout(STALL_TIMEOUT, response.chunk())
+                    .await
+                    .map_err(|_| "Timed out".to_string())?
+                    .map_err(|e| e.to_string())?;
+                let Some(chunk) = chunk else {
+                    break;
+                };
+                if cancel.load(Ordering::Relaxed) {
+                    return Err("Cancelled".to_string());
+                }
+                received += chunk.len() as u64;
+                // The Content-Length can be missing or wrong
+                if received > MAX_FILE_SIZE {
+                    return Err(too_large());
+                }
+                file.write_all(&chunk).map_err(|e| e.to_string())?;
+                progress(received, total);
+            }
+            file.flush().map_err(|e| e.to_string())
+        }
+        .await;
+        drop(file);
+
+        match result
+            .and_then(|()| std::fs::rename(&partial, &destination).map_err(|e| e.to_string()))
+        {
+            Ok(()) => Ok(destination),
+            Err(e) => {
+                let _ = std::fs::remove_file(&partial);
+                Err(e)
+            }
+        }
+    })
+}
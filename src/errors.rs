@@ -72,6 +72,9 @@ pub enum ViewerError {
 
     #[error("Invalid operation: {message}")]
     InvalidOperation { message: String },
+
+    #[error("Audio error: {message}")]
+    AudioError { message: String },
 }
 
 pub type Result<T> = std::result::Result<T, ViewerError>;
@@ -139,6 +142,7 @@ impl ViewerError {
             ViewerError::Cancelled => "CANCELLED",
             ViewerError::Timeout { .. } => "TIMEOUT",
             ViewerError::InvalidOperation { .. } => "INVALID_OPERATION",
+            ViewerError::AudioError { .. } => "AUDIO_ERROR",
         }
     }
 
@@ -26,6 +26,9 @@ pub struct ExifInfo {
     pub copyright: Option<String>,
     pub artist: Option<String>,
     pub software: Option<String>,
+    // Set for layered documents (PSD, Krita, OpenRaster)
+    pub layers: Option<usize>,
+    pub color_mode: Option<String>,
 }
 
 #[allow(dead_code)]
@@ -38,6 +41,11 @@ impl ExifInfo {
             info.file_size = Some(format_file_size(size));
         }
 
+        if let Some(document) = crate::image_loader::document_info(path) {
+            info.layers = Some(document.layers);
+            info.color_mode = Some(document.color_mode);
+        }
+
         if let Ok(file) = File::open(path) {
             let mut bufreader = BufReader::new(file);
             match Reader::new().read_from_container(&mut bufreader) {
@@ -137,6 +145,13 @@ impl ExifInfo {
             }
         }
 
+        // PNGs, screenshots and edited exports often carry no size in EXIF
+        if info.dimensions.is_none() {
+            if let Ok((width, height)) = image::image_dimensions(path) {
+                info.dimensions = Some(format!("{} × {}", width, height));
+            }
+        }
+
         info
     }
 
@@ -188,11 +203,179 @@ impl ExifInfo {
     }
 }
 
+/// One tag for the full metadata browser
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataTag {
+    /// Where the tag came from, e.g. "EXIF", "GPS", "IPTC" or "XMP"
+    pub group: &'static str,
+    /// Identifier as stored: EXIF tag number, IPTC record:dataset or XMP property
+    pub key: String,
+    /// Readable tag name
+    pub name: String,
+    /// Value formatted for reading, with units where EXIF defines them
+    pub value: String,
+    /// Value as stored in the file
+    pub raw: String,
+}
+
+/// Every EXIF, IPTC and XMP tag in the file, plus properties from its XMP
+/// sidecar. Maker notes are vendor specific and only listed by size.
+pub fn all_metadata(path: &Path) -> Vec<MetadataTag> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            log::warn!("Failed to read metadata from {}: {}", path.display(), e);
+            return Vec::new();
+        }
+    };
+
+    let mut tags = Vec::new();
+    if let Ok(exif) = Reader::new().read_from_container(&mut std::io::Cursor::new(&bytes)) {
+        for field in exif.fields() {
+            tags.push(exif_tag(&exif, field));
+        }
+    }
+
+    for dataset in crate::iptc::read_iptc(&bytes) {
+        let key = format!("{}:{}", dataset.record, dataset.dataset);
+        let name = dataset
+            .name()
+            .map(str::to_string)
+            .unwrap_or_else(|| key.clone());
+        tags.push(MetadataTag {
+            group: "IPTC",
+            name,
+            value: dataset.text(),
+            raw: hex_bytes(&dataset.data),
+            key,
+        });
+    }
+
+    let embedded = crate::xmp::embedded_xmp(&bytes).map(|text| ("XMP", text));
+    let sidecar = crate::xmp::find_sidecar(path)
+        .and_then(|sidecar| std::fs::read_to_string(sidecar).ok())
+        .map(|text| ("XMP sidecar", text));
+    for (group, text) in embedded.into_iter().chain(sidecar) {
+        for (key, value) in crate::xmp::properties(&text) {
+            let local = key.split_once(':').map_or(key.as_str(), |(_, name)| name);
+            tags.push(MetadataTag {
+                group,
+                name: split_camel_case(local),
+                raw: value.clone(),
+                value,
+                key,
+            });
+        }
+    }
+    tags
+}
+
+fn exif_tag(exif: &exif::Exif, field: &exif::Field) -> MetadataTag {
+    let context = field.tag.context();
+    let group = if field.ifd_num == In::THUMBNAIL {
+        "EXIF thumbnail"
+    } else if context == exif::Context::Gps {
+        "GPS"
+    } else if context == exif::Context::Interop {
+        "Interoperability"
+    } else {
+        "EXIF"
+    };
+
+    let (value, raw) = match &field.value {
+        exif::Value::Undefined(bytes, _) if field.tag == Tag::MakerNote => (
+            format!("{} bytes (camera specific)", bytes.len()),
+            hex_bytes(bytes),
+        ),
+        exif::Value::Byte(bytes) | exif::Value::Undefined(bytes, _) => (
+            field.display_value().with_unit(exif).to_string(),
+            hex_bytes(bytes),
+        ),
+        exif::Value::Ascii(strings) => (
+            clean_string(&field.display_value().to_string()),
+            strings
+                .iter()
+                .map(|s| String::from_utf8_lossy(s).into_owned())
+                .collect::<Vec<_>>()
+                .join("; "),
+        ),
+        other => (
+            field.display_value().with_unit(exif).to_string(),
+            format!("{:?}", other),
+        ),
+    };
+
+    MetadataTag {
+        group,
+        key: format!("0x{:04X}", field.tag.number()),
+        name: split_camel_case(&field.tag.to_string()),
+        value,
+        raw,
+    }
+}
+
+/// Hex dump of the first bytes, noting the full length of longer values
+fn hex_bytes(bytes: &[u8]) -> String {
+    const SHOWN: usize = 64;
+    let hex: Vec<String> = bytes
+        .iter()
+        .take(SHOWN)
+        .map(|b| format!("{:02X}", b))
+        .collect();
+    if bytes.len() > SHOWN {
+        format!("{} … ({} bytes)", hex.join(" "), bytes.len())
+    } else {
+        hex.join(" ")
+    }
+}
+
+/// `DateTimeOriginal` -> `Date Time Original`, keeping acronyms like `ISO` together
+pub fn split_camel_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut words = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        let previous = i.checked_sub(1).map(|p| chars[p]);
+        let next = chars.get(i + 1);
+        let starts_word = c.is_uppercase()
+            && previous.is_some_and(|p| {
+                p.is_lowercase()
+                    || p.is_ascii_digit()
+                    || (p.is_uppercase() && next.is_some_and(|n| n.is_lowercase()))
+            });
+        if starts_word {
+            words.push(' ');
+        }
+        words.push(c);
+    }
+    words
+}
+
+/// Capture time from `DateTimeOriginal`, falling back to `DateTime`
+pub fn capture_date(path: &Path) -> Option<chrono::NaiveDateTime> {
+    let file = File::open(path).ok()?;
+    let exif = Reader::new()
+        .read_from_container(&mut BufReader::new(file))
+        .ok()?;
+    let field = exif
+        .get_field(Tag::DateTimeOriginal, In::PRIMARY)
+        .or_else(|| exif.get_field(Tag::DateTime, In::PRIMARY))?;
+    parse_exif_date(&clean_string(&field.display_value().to_string()))
+}
+
+/// Parse an EXIF date, written either as stored (`2024:05:01 14:23:05`) or
+/// as displayed (`2024-05-01 14:23:05`)
+pub fn parse_exif_date(text: &str) -> Option<chrono::NaiveDateTime> {
+    let text = text.trim();
+    ["%Y:%m:%d %H:%M:%S", "%Y-%m-%d %H:%M:%S"]
+        .iter()
+        .find_map(|format| chrono::NaiveDateTime::parse_from_str(text, format).ok())
+}
+
 fn clean_string(s: &str) -> String {
     s.trim_matches('"').trim().to_string()
 }
 
-fn format_file_size(bytes: u64) -> String {
+pub fn format_file_size(bytes: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = KB * 1024;
     const GB: u64 = MB * 1024;
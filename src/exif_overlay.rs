@@ -0,0 +1,178 @@
+use crate::exif_data::ExifInfo;
+use crate::metadata::ImageMetadata;
+use crate::settings::ColorLabel;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+lazy_static::lazy_static! {
+    static ref TOKEN: Regex = Regex::new(r"\{([a-z]+)\}").unwrap();
+}
+
+/// Placeholders offered in the overlay settings, with a short description
+pub const TOKENS: &[(&str, &str)] = &[
+    ("{camera}", "Camera model"),
+    ("{make}", "Camera maker"),
+    ("{lens}", "Lens model"),
+    ("{focal}", "Focal length"),
+    ("{aperture}", "Aperture as f/2.8"),
+    ("{shutter}", "Shutter speed"),
+    ("{iso}", "ISO sensitivity"),
+    ("{ev}", "Exposure compensation"),
+    ("{date}", "Capture date and time"),
+    ("{filename}", "File name"),
+    ("{dimensions}", "Width × height"),
+    ("{size}", "File size"),
+    ("{rating}", "Star rating"),
+    ("{label}", "Color label"),
+];
+
+const DEFAULT_TEMPLATE: &str = "{camera}\n{focal} • {aperture} • ISO {iso} — {date}";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum OverlayCorner {
+    TopLeft,
+    TopRight,
+    #[default]
+    BottomLeft,
+    BottomRight,
+}
+
+impl OverlayCorner {
+    pub fn all() -> &'static [OverlayCorner] {
+        &[
+            OverlayCorner::TopLeft,
+            OverlayCorner::TopRight,
+            OverlayCorner::BottomLeft,
+            OverlayCorner::BottomRight,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            OverlayCorner::TopLeft => "Top left",
+            OverlayCorner::TopRight => "Top right",
+            OverlayCorner::BottomLeft => "Bottom left",
+            OverlayCorner::BottomRight => "Bottom right",
+        }
+    }
+
+    pub fn align(&self) -> egui::Align2 {
+        match self {
+            OverlayCorner::TopLeft => egui::Align2::LEFT_TOP,
+            OverlayCorner::TopRight => egui::Align2::RIGHT_TOP,
+            OverlayCorner::BottomLeft => egui::Align2::LEFT_BOTTOM,
+            OverlayCorner::BottomRight => egui::Align2::RIGHT_BOTTOM,
+        }
+    }
+}
+
+/// What the EXIF overlay on the image shows and where
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExifOverlaySettings {
+    /// One overlay line per template line; the first is drawn larger
+    pub template: String,
+    pub corner: OverlayCorner,
+    /// Point size of the first line
+    pub font_size: f32,
+    /// Extra text scale for each monitor, keyed by its size in points
+    /// (e.g. "2560x1440"), for displays where the system scaling doesn't suit
+    pub monitor_scales: HashMap<String, f32>,
+}
+
+impl Default for ExifOverlaySettings {
+    fn default() -> Self {
+        Self {
+            template: DEFAULT_TEMPLATE.to_string(),
+            corner: OverlayCorner::default(),
+            font_size: 13.0,
+            monitor_scales: HashMap::new(),
+        }
+    }
+}
+
+/// What an overlay is rendered from
+pub struct OverlayInfo<'a> {
+    pub path: &'a Path,
+    pub exif: &'a ExifInfo,
+    pub metadata: &'a ImageMetadata,
+}
+
+impl ExifOverlaySettings {
+    pub fn monitor_scale(&self, monitor: Option<&str>) -> f32 {
+        monitor
+            .and_then(|m| self.monitor_scales.get(m))
+            .copied()
+            .unwrap_or(1.0)
+    }
+
+    /// Overlay lines for one image. Placeholders without a value render
+    /// empty, lines left with nothing are dropped, and unknown placeholders
+    /// are kept as written.
+    pub fn render(&self, info: &OverlayInfo) -> Vec<String> {
+        self.template
+            .lines()
+            .filter_map(|line| {
+                let mut any_value = !TOKEN.is_match(line);
+                let rendered =
+                    TOKEN.replace_all(line, |caps: &Captures| match token_value(&caps[1], info) {
+                        Some(value) => {
+                            any_value |= !value.is_empty();
+                            value
+                        }
+                        None => {
+                            any_value = true;
+                            caps[0].to_string()
+                        }
+                    });
+                let rendered = rendered.trim();
+                (any_value && !rendered.is_empty()).then(|| rendered.to_string())
+            })
+            .collect()
+    }
+}
+
+/// Value for a placeholder, empty when the image doesn't have it and None
+/// for unknown placeholders
+fn token_value(token: &str, info: &OverlayInfo) -> Option<String> {
+    let exif = info.exif;
+    let text = |value: &Option<String>| value.clone().unwrap_or_default();
+    let value = match token {
+        "camera" => text(&exif.camera_model),
+        "make" => text(&exif.camera_make),
+        "lens" => text(&exif.lens),
+        "focal" => exif.focal_length_formatted(),
+        "aperture" => exif.aperture_formatted(),
+        "shutter" => text(&exif.shutter_speed),
+        // Stored as "ISO 100"
+        "iso" => exif
+            .iso
+            .as_deref()
+            .map(|iso| iso.trim_start_matches("ISO").trim().to_string())
+            .unwrap_or_default(),
+        "ev" => text(&exif.exposure_compensation),
+        "date" => text(&exif.date_taken),
+        "filename" => info
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        "dimensions" => text(&exif.dimensions),
+        "size" => text(&exif.file_size),
+        "rating" => "★".repeat(info.metadata.rating.min(5) as usize),
+        "label" => match info.metadata.color_label {
+            ColorLabel::None => String::new(),
+            label => label.name().to_string(),
+        },
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Key for the monitor the window is on, for `monitor_scales`
+pub fn monitor_key(ctx: &egui::Context) -> Option<String> {
+    let size = ctx.input(|i| i.viewport().monitor_size)?;
+    Some(format!("{}x{}", size.x.round(), size.y.round()))
+}
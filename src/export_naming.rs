@@ -0,0 +1,190 @@
+use crate::image_loader::split_archive_path;
+use crate::rename::{RenameInfo, RenamePattern};
+use crate::settings::ExportFormat;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Tokens offered for export subfolders and file names, with a short description
+pub const TOKENS: &[(&str, &str)] = &[
+    ("{orig}", "Original file name"),
+    ("{seq}", "Position in the export; {seq:4} pads to 4 digits"),
+    (
+        "{date}",
+        "Capture date (YYYY-MM-DD), or the file date without EXIF",
+    ),
+    ("{camera}", "Camera model"),
+    ("{rating}", "Star rating, 0 to 5"),
+];
+
+/// Where exported files go
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ExportDestination {
+    /// A save dialog for one image, a folder picker for several
+    #[default]
+    Ask,
+    NextToSource,
+    Folder(PathBuf),
+}
+
+impl ExportDestination {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportDestination::Ask => "Ask every time",
+            ExportDestination::NextToSource => "Next to the original",
+            ExportDestination::Folder(_) => "Folder",
+        }
+    }
+}
+
+/// What happens when an exported file already exists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ExportConflict {
+    Overwrite,
+    Skip,
+    /// Add _2, _3, ... to the name
+    #[default]
+    UniqueSuffix,
+}
+
+impl ExportConflict {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportConflict::Overwrite => "Overwrite",
+            ExportConflict::Skip => "Skip",
+            ExportConflict::UniqueSuffix => "Add a number",
+        }
+    }
+
+    pub fn all() -> &'static [ExportConflict] {
+        &[
+            ExportConflict::Overwrite,
+            ExportConflict::Skip,
+            ExportConflict::UniqueSuffix,
+        ]
+    }
+}
+
+/// Destination and naming rules for exports
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportNaming {
+    pub destination: ExportDestination,
+    /// Folders under the destination, separated by `/`, such as `{date}/{rating}`
+    pub subfolder: String,
+    /// File name without the extension
+    pub filename: String,
+    pub conflict: ExportConflict,
+}
+
+impl Default for ExportNaming {
+    fn default() -> Self {
+        Self {
+            destination: ExportDestination::Ask,
+            subfolder: String::new(),
+            filename: "{orig}_rView".to_string(),
+            conflict: ExportConflict::UniqueSuffix,
+        }
+    }
+}
+
+/// Per-file values for the templates
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExportNameInfo {
+    pub file: RenameInfo,
+    pub rating: u8,
+}
+
+impl ExportNaming {
+    /// Whether the templates need EXIF or file dates
+    pub fn needs_info(&self) -> bool {
+        let pattern = |template: &str| RenamePattern {
+            template: template.to_string(),
+            ..Default::default()
+        };
+        pattern(&self.subfolder).needs_info() || pattern(&self.filename).needs_info()
+    }
+
+    /// Folder exports of `source` go under, or None when it has to be asked
+    /// for, as for images without a folder of their own
+    pub fn base_folder(&self, source: &Path) -> Option<PathBuf> {
+        match &self.destination {
+            ExportDestination::Ask => None,
+            // Next to the archive for images inside one
+            ExportDestination::NextToSource => split_archive_path(source)
+                .map_or(source, |(archive, _)| archive)
+                .parent()
+                .filter(|folder| !folder.as_os_str().is_empty())
+                .map(Path::to_path_buf),
+            ExportDestination::Folder(folder) => Some(folder.clone()),
+        }
+    }
+
+    /// Templated path of `source` under `base`, before conflicts are resolved
+    pub fn target(
+        &self,
+        base: &Path,
+        source: &Path,
+        seq: u32,
+        info: &ExportNameInfo,
+        format: ExportFormat,
+    ) -> PathBuf {
+        let original = source
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "exported_image".to_string());
+        let render = |template: &str| {
+            RenamePattern {
+                template: template.replace("{rating}", &info.rating.to_string()),
+                ..Default::default()
+            }
+            .render(&original, seq, &info.file)
+        };
+
+        let mut path = base.to_path_buf();
+        for segment in self.subfolder.split(['/', '\\']) {
+            let name = render(segment);
+            // Never climb out of the destination
+            if !name.is_empty() && name != "." && name != ".." {
+                path.push(name);
+            }
+        }
+        let stem = Some(render(&self.filename))
+            .filter(|stem| !stem.is_empty())
+            .unwrap_or(original);
+        path.join(format!("{}.{}", stem, format.extension()))
+    }
+}
+
+/// Resolve every export's path against the conflict policy: None for files
+/// skipped because they exist. Files from the same export never overwrite
+/// each other, whatever the policy; names are compared case-insensitively.
+pub fn resolve_export_paths(
+    targets: Vec<PathBuf>,
+    conflict: ExportConflict,
+) -> Vec<Option<PathBuf>> {
+    let key = |path: &Path| path.to_string_lossy().to_lowercase();
+    let mut taken = HashSet::new();
+    targets
+        .into_iter()
+        .map(|target| {
+            if conflict == ExportConflict::Skip && target.exists() {
+                return None;
+            }
+            let free = |path: &Path, taken: &HashSet<String>| {
+                !taken.contains(&key(path))
+                    && (conflict == ExportConflict::Overwrite || !path.exists())
+            };
+            let mut candidate = target.clone();
+            let mut n = 2;
+            while !free(&candidate, &taken) {
+                let stem = target.file_stem().unwrap_or_default().to_string_lossy();
+                let ext = target.extension().unwrap_or_default().to_string_lossy();
+                candidate = target.with_file_name(format!("{}_{}.{}", stem, n, ext));
+                n += 1;
+            }
+            taken.insert(key(&candidate));
+            Some(candidate)
+        })
+        .collect()
+}
@@ -64,100 +64,7 @@ impl GpuProcessor {
         });
 
         // Params uniform buffer with film emulation
-        #[repr(C)]
-        #[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
-        struct Params {
-            exposure: f32,
-            saturation: f32,
-            temperature: f32,
-            width: u32,
-            height: u32,
-            // Film emulation
-            film_enabled: u32,
-            film_is_bw: u32,
-            tone_curve_shadows: f32,
-            tone_curve_midtones: f32,
-            tone_curve_highlights: f32,
-            s_curve_strength: f32,
-            grain_amount: f32,
-            grain_size: f32,
-            grain_roughness: f32,
-            halation_amount: f32,
-            vignette_amount: f32,
-            vignette_softness: f32,
-            latitude: f32,
-            red_gamma: f32,
-            green_gamma: f32,
-            blue_gamma: f32,
-            black_point: f32,
-            white_point: f32,
-            // Color crossover matrix
-            red_in_green: f32,
-            red_in_blue: f32,
-            green_in_red: f32,
-            green_in_blue: f32,
-            blue_in_red: f32,
-            blue_in_green: f32,
-            // Shadow/highlight tints
-            shadow_tint_r: f32,
-            shadow_tint_g: f32,
-            shadow_tint_b: f32,
-            highlight_tint_r: f32,
-            highlight_tint_g: f32,
-            highlight_tint_b: f32,
-            // Halation color
-            halation_color_r: f32,
-            halation_color_g: f32,
-            halation_color_b: f32,
-            halation_radius: f32,
-        }
-
-        let film = &adj.film;
-        let params = Params {
-            exposure: adj.exposure,
-            saturation: adj.saturation,
-            temperature: adj.temperature,
-            width,
-            height,
-            // Film emulation
-            film_enabled: if film.enabled { 1 } else { 0 },
-            film_is_bw: if film.is_bw { 1 } else { 0 },
-            tone_curve_shadows: film.tone.shadows,
-            tone_curve_midtones: film.tone.midtones,
-            tone_curve_highlights: film.tone.highlights,
-            s_curve_strength: film.tone.s_curve_strength,
-            grain_amount: film.grain.amount,
-            grain_size: film.grain.size,
-            grain_roughness: film.grain.roughness,
-            halation_amount: film.halation.amount,
-            vignette_amount: film.vignette.amount,
-            vignette_softness: film.vignette.softness,
-            latitude: film.latitude,
-            red_gamma: film.color_gamma.red,
-            green_gamma: film.color_gamma.green,
-            blue_gamma: film.color_gamma.blue,
-            black_point: film.black_point,
-            white_point: film.white_point,
-            // Color crossover matrix
-            red_in_green: film.color_crossover.red_in_green,
-            red_in_blue: film.color_crossover.red_in_blue,
-            green_in_red: film.color_crossover.green_in_red,
-            green_in_blue: film.color_crossover.green_in_blue,
-            blue_in_red: film.color_crossover.blue_in_red,
-            blue_in_green: film.color_crossover.blue_in_green,
-            // Shadow/highlight tints
-            shadow_tint_r: film.shadow_tint[0],
-            shadow_tint_g: film.shadow_tint[1],
-            shadow_tint_b: film.shadow_tint[2],
-            highlight_tint_r: film.highlight_tint[0],
-            highlight_tint_g: film.highlight_tint[1],
-            highlight_tint_b: film.highlight_tint[2],
-            // Halation color
-            halation_color_r: film.halation.color[0],
-            halation_color_g: film.halation.color[1],
-            halation_color_b: film.halation.color[2],
-            halation_radius: film.halation.radius,
-        };
+        let params = Self::create_adjustment_params(adj, width, height);
 
         let params_buf = self
             .device
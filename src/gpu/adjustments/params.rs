@@ -1,5 +1,5 @@
-use crate::gpu::types::AdjustmentParams;
-use crate::image_loader::ImageAdjustments;
+use crate::gpu::types::{AdjustmentParams, LocalMaskParams};
+use crate::image_loader::{ImageAdjustments, LocalMask, MAX_LOCAL_ADJUSTMENTS};
 
 impl crate::gpu::types::GpuProcessor {
     pub fn create_adjustment_params(
@@ -8,6 +8,7 @@ impl crate::gpu::types::GpuProcessor {
         height: u32,
     ) -> AdjustmentParams {
         let film = &adj.film;
+        let (local_count, local_masks) = local_mask_params(adj);
         AdjustmentParams {
             exposure: adj.exposure,
             saturation: adj.saturation,
@@ -51,6 +52,42 @@ impl crate::gpu::types::GpuProcessor {
             halation_color_g: film.halation.color[1],
             halation_color_b: film.halation.color[2],
             halation_radius: film.halation.radius,
+            contrast: adj.contrast,
+            tint: adj.tint,
+            local_count,
+            _local_padding: [0; 2],
+            local_masks,
         }
     }
 }
+
+/// Local adjustments packed for the shaders' uniform arrays
+pub fn local_mask_params(
+    adj: &ImageAdjustments,
+) -> (u32, [LocalMaskParams; MAX_LOCAL_ADJUSTMENTS]) {
+    let mut masks = [LocalMaskParams::default(); MAX_LOCAL_ADJUSTMENTS];
+    for (params, local) in masks.iter_mut().zip(&adj.local) {
+        let (kind, geometry, feather) = match &local.mask {
+            LocalMask::Radial {
+                center,
+                radius,
+                feather,
+            } => (0.0, [center[0], center[1], radius[0], radius[1]], *feather),
+            LocalMask::Linear { start, end } => (1.0, [start[0], start[1], end[0], end[1]], 0.0),
+            // Images with brush masks are adjusted on the CPU instead
+            LocalMask::Brush { .. } => continue,
+        };
+        *params = LocalMaskParams {
+            geometry,
+            shape: [kind, feather, if local.invert { 1.0 } else { 0.0 }, 0.0],
+            amount: [
+                local.exposure,
+                local.saturation,
+                local.temperature,
+                local.tint,
+            ],
+        };
+    }
+    let count = adj.local.len().min(MAX_LOCAL_ADJUSTMENTS) as u32;
+    (count, masks)
+}
@@ -0,0 +1,161 @@
+use eframe::egui_wgpu::{self, wgpu};
+use wgpu::util::DeviceExt;
+
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct DifferenceParams {
+    gain: f32,
+    heatmap: u32, // 0 = per channel, 1 = heatmap
+    width: u32,
+    height: u32,
+}
+
+/// Two textures egui holds and how to show their difference
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DifferenceRequest {
+    pub first: egui::TextureId,
+    pub second: egui::TextureId,
+    /// Multiplier that makes faint differences visible
+    pub gain: f32,
+    /// Color by how much each pixel changed instead of showing it per channel
+    pub heatmap: bool,
+}
+
+struct DifferenceOutput {
+    texture: wgpu::Texture,
+    id: egui::TextureId,
+}
+
+/// |A−B| of two images already uploaded for compare mode, computed on
+/// eframe's wgpu device straight from egui's textures into one egui draws
+pub struct DifferenceRenderer {
+    render_state: egui_wgpu::RenderState,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::ComputePipeline,
+    output: Option<DifferenceOutput>,
+    /// Request the output was last rendered for
+    rendered: Option<DifferenceRequest>,
+}
+
+impl DifferenceRenderer {
+    pub fn new(render_state: &egui_wgpu::RenderState) -> Self {
+        let (bind_group_layout, pipeline) =
+            super::pipelines::create_difference_pipeline(&render_state.device);
+        Self {
+            render_state: render_state.clone(),
+            bind_group_layout,
+            pipeline,
+            output: None,
+            rendered: None,
+        }
+    }
+
+    /// The difference texture, the size of the first image. None until egui
+    /// has both textures on the device.
+    pub fn render(&mut self, request: &DifferenceRequest) -> Option<egui::TextureId> {
+        if self.rendered.as_ref() == Some(request) {
+            return self.output.as_ref().map(|output| output.id);
+        }
+        let device = &self.render_state.device;
+
+        let (first, second, size) = {
+            let renderer = self.render_state.renderer.read();
+            let first = renderer.texture(&request.first)?.texture.as_ref()?;
+            let second = renderer.texture(&request.second)?.texture.as_ref()?;
+            let view = |texture: &wgpu::Texture| {
+                texture.create_view(&wgpu::TextureViewDescriptor::default())
+            };
+            (view(first), view(second), first.size())
+        };
+
+        if !self
+            .output
+            .as_ref()
+            .is_some_and(|out| out.texture.size() == size)
+        {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("difference_output"),
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            });
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            let mut renderer = self.render_state.renderer.write();
+            let id = match self.output.take() {
+                Some(old) => {
+                    renderer.update_egui_texture_from_wgpu_texture(
+                        device,
+                        &view,
+                        wgpu::FilterMode::Linear,
+                        old.id,
+                    );
+                    old.id
+                }
+                None => renderer.register_native_texture(device, &view, wgpu::FilterMode::Linear),
+            };
+            self.output = Some(DifferenceOutput { texture, id });
+        }
+        let output = self.output.as_ref()?;
+
+        let params = DifferenceParams {
+            gain: request.gain,
+            heatmap: request.heatmap as u32,
+            width: size.width,
+            height: size.height,
+        };
+        let param_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("difference_params"),
+            contents: bytemuck::bytes_of(&params),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("difference_bind_group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&first),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&second),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(
+                        &output
+                            .texture
+                            .create_view(&wgpu::TextureViewDescriptor::default()),
+                    ),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: param_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("difference_encoder"),
+        });
+        {
+            let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("difference_pass"),
+                timestamp_writes: None,
+            });
+            cpass.set_pipeline(&self.pipeline);
+            cpass.set_bind_group(0, &bind_group, &[]);
+            cpass.dispatch_workgroups(size.width.div_ceil(16), size.height.div_ceil(16), 1);
+        }
+        // Submitted ahead of egui's own frame, so the result is there when drawn
+        self.render_state.queue.submit(Some(encoder.finish()));
+
+        self.rendered = Some(*request);
+        Some(output.id)
+    }
+}
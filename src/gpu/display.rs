@@ -0,0 +1,433 @@
+use eframe::egui_wgpu::{self, wgpu};
+use image::RgbaImage;
+use std::sync::Arc;
+use wgpu::util::DeviceExt;
+
+use crate::gpu::adjustments::params::local_mask_params;
+use crate::gpu::types::LocalMaskParams;
+use crate::image_loader::{hdr, HdrDisplay, ImageAdjustments, MAX_LOCAL_ADJUSTMENTS};
+
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct DisplayParams {
+    exposure: f32,
+    saturation: f32,
+    temperature: f32,
+    adjustments_enabled: u32,
+    scene_linear: u32,
+    hdr_exposure: f32,
+    tone_map: u32,
+    contrast: f32,
+    // Local adjustments; the array starts on a 16-byte boundary in WGSL
+    local_count: u32,
+    tint: f32,
+    // Show source pixels unfiltered, for inspecting them at high zoom
+    nearest: u32,
+    // Convert to the display profile through the lookup table
+    display_lut: u32,
+    local_masks: [LocalMaskParams; MAX_LOCAL_ADJUSTMENTS],
+}
+
+impl DisplayParams {
+    fn new(
+        adj: &ImageAdjustments,
+        hdr: &HdrDisplay,
+        scene_linear: bool,
+        nearest: bool,
+        display_lut: bool,
+    ) -> Self {
+        let (local_count, local_masks) = local_mask_params(adj);
+        Self {
+            exposure: adj.exposure,
+            saturation: adj.saturation,
+            temperature: adj.temperature,
+            adjustments_enabled: (!adj.is_default()) as u32,
+            scene_linear: scene_linear as u32,
+            hdr_exposure: hdr.exposure,
+            tone_map: hdr.tone_map as u32,
+            contrast: adj.contrast,
+            local_count,
+            tint: adj.tint,
+            nearest: nearest as u32,
+            display_lut: display_lut as u32,
+            local_masks,
+        }
+    }
+}
+
+/// GPU resources for the main image view, stored in egui's callback resources
+pub struct ImageDisplayResources {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    /// Fill the mip levels of 8-bit and half-float source textures
+    mipmap_pipelines: [(wgpu::TextureFormat, wgpu::RenderPipeline); 2],
+    params_buffer: wgpu::Buffer,
+    bind_group: Option<wgpu::BindGroup>,
+    /// Whether the resident texture holds scene-linear HDR values
+    scene_linear: bool,
+    /// Bound in place of a display lookup table when the image needs none
+    no_lut: wgpu::TextureView,
+    /// Whether the resident image has a display lookup table
+    display_lut: bool,
+}
+
+/// Register the display pipeline with eframe's wgpu renderer.
+/// Returns the largest texture dimension the device accepts.
+pub fn install(render_state: &egui_wgpu::RenderState) -> u32 {
+    let device = &render_state.device;
+    let (bind_group_layout, pipeline) =
+        super::pipelines::create_display_pipeline(device, render_state.target_format);
+
+    let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+        label: Some("display_sampler"),
+        mag_filter: wgpu::FilterMode::Linear,
+        min_filter: wgpu::FilterMode::Linear,
+        mipmap_filter: wgpu::FilterMode::Linear,
+        ..Default::default()
+    });
+    let mipmap_pipeline = |format| {
+        (
+            format,
+            super::pipelines::create_mipmap_pipeline(device, format),
+        )
+    };
+    let mipmap_pipelines = [
+        mipmap_pipeline(wgpu::TextureFormat::Rgba8Unorm),
+        mipmap_pipeline(wgpu::TextureFormat::Rgba16Float),
+    ];
+
+    let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("display_params"),
+        contents: bytemuck::bytes_of(&DisplayParams::new(
+            &ImageAdjustments::default(),
+            &HdrDisplay::default(),
+            false,
+            false,
+            false,
+        )),
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+    });
+
+    render_state
+        .renderer
+        .write()
+        .callback_resources
+        .insert(ImageDisplayResources {
+            pipeline,
+            bind_group_layout,
+            sampler,
+            mipmap_pipelines,
+            params_buffer,
+            bind_group: None,
+            scene_linear: false,
+            no_lut: lut_texture_view(device, &render_state.queue, &RgbaImage::new(1, 1)),
+            display_lut: false,
+        });
+
+    device.limits().max_texture_dimension_2d
+}
+
+/// Pixels for the display texture
+pub enum DisplaySource {
+    Rgba8(RgbaImage),
+    /// Half floats for 16-bit and float images, so the shader sees their full
+    /// precision. Scene-linear sources are tone mapped in the shader.
+    Rgba16Float {
+        width: u32,
+        height: u32,
+        pixels: Vec<u16>,
+        scene_linear: bool,
+    },
+}
+
+/// What the display pass uploads when the image or its proxy changes
+pub struct DisplayUpload {
+    pub source: DisplaySource,
+    /// Conversion to the display profile, applied after the adjustments; see
+    /// `color_management::display_lut` for the layout
+    pub lut: Option<RgbaImage>,
+}
+
+/// A 3D texture of a display lookup table laid out as slices of rows
+fn lut_texture_view(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    lut: &RgbaImage,
+) -> wgpu::TextureView {
+    let size = lut.width().max(1);
+    let texture = device.create_texture_with_data(
+        queue,
+        &wgpu::TextureDescriptor {
+            label: Some("display_lut_texture"),
+            size: wgpu::Extent3d {
+                width: size,
+                height: size,
+                depth_or_array_layers: (lut.height() / size).max(1),
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D3,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        },
+        wgpu::util::TextureDataOrder::LayerMajor,
+        lut.as_raw(),
+    );
+    texture.create_view(&wgpu::TextureViewDescriptor::default())
+}
+
+/// Prepare an image for upload, downscaling if it exceeds the device texture limit
+pub fn prepare_source(image: &image::DynamicImage, max_dimension: u32) -> DisplaySource {
+    if !hdr::is_high_precision(image) {
+        return DisplaySource::Rgba8(prepare_rgba8(image, max_dimension));
+    }
+    let image = if image.width() > max_dimension || image.height() > max_dimension {
+        std::borrow::Cow::Owned(image.thumbnail(max_dimension, max_dimension))
+    } else {
+        std::borrow::Cow::Borrowed(image)
+    };
+    let rgba = image.to_rgba32f();
+    DisplaySource::Rgba16Float {
+        width: rgba.width(),
+        height: rgba.height(),
+        pixels: rgba.iter().map(|&v| hdr::f32_to_f16(v)).collect(),
+        scene_linear: hdr::is_float(&image),
+    }
+}
+
+/// The 8-bit version of `prepare_source`, for pixels that still need an
+/// 8-bit pass such as an ICC transform
+pub fn prepare_rgba8(image: &image::DynamicImage, max_dimension: u32) -> RgbaImage {
+    if image.width() > max_dimension || image.height() > max_dimension {
+        image.thumbnail(max_dimension, max_dimension).to_rgba8()
+    } else {
+        image.to_rgba8()
+    }
+}
+
+/// Paint callback drawing the resident image texture with live adjustments.
+/// `upload` is only set when the source image changed; every other frame just
+/// rewrites the small params uniform.
+pub struct ImageDisplayCallback {
+    pub upload: Option<Arc<DisplayUpload>>,
+    pub adjustments: ImageAdjustments,
+    pub hdr: HdrDisplay,
+    /// Nearest-neighbour sampling, for pixel inspection and pixel art
+    pub nearest: bool,
+    /// Build mipmaps for an uploaded source, so zoomed-out views don't shimmer
+    pub mipmaps: bool,
+}
+
+impl ImageDisplayResources {
+    fn upload_texture(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        upload: &DisplayUpload,
+        mipmaps: bool,
+    ) {
+        let (width, height, format, data, scene_linear) = match &upload.source {
+            DisplaySource::Rgba8(image) => (
+                image.width(),
+                image.height(),
+                wgpu::TextureFormat::Rgba8Unorm,
+                image.as_raw().as_slice(),
+                false,
+            ),
+            DisplaySource::Rgba16Float {
+                width,
+                height,
+                pixels,
+                scene_linear,
+            } => (
+                *width,
+                *height,
+                wgpu::TextureFormat::Rgba16Float,
+                bytemuck::cast_slice(pixels.as_slice()),
+                *scene_linear,
+            ),
+        };
+        self.scene_linear = scene_linear;
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let mip_level_count = if mipmaps {
+            size.max_mips(wgpu::TextureDimension::D2)
+        } else {
+            1
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("display_source_texture"),
+            size,
+            mip_level_count,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_DST
+                | wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            data,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(data.len() as u32 / height),
+                rows_per_image: Some(height),
+            },
+            size,
+        );
+        self.generate_mipmaps(device, encoder, &texture);
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let lut = upload
+            .lut
+            .as_ref()
+            .map(|lut| lut_texture_view(device, queue, lut));
+        self.display_lut = lut.is_some();
+        let lut = lut.as_ref().unwrap_or(&self.no_lut);
+
+        self.bind_group = Some(device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("display_bind_group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.params_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::TextureView(lut),
+                },
+            ],
+        }));
+    }
+
+    /// Draw each mip level of `texture` from the one above it
+    fn generate_mipmaps(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        texture: &wgpu::Texture,
+    ) {
+        let Some((_, pipeline)) = self
+            .mipmap_pipelines
+            .iter()
+            .find(|(format, _)| *format == texture.format())
+        else {
+            return;
+        };
+        let bind_group_layout = pipeline.get_bind_group_layout(0);
+        let level_view = |level: u32| {
+            texture.create_view(&wgpu::TextureViewDescriptor {
+                label: Some("display_mip_view"),
+                base_mip_level: level,
+                mip_level_count: Some(1),
+                ..Default::default()
+            })
+        };
+
+        for level in 1..texture.mip_level_count() {
+            let source = level_view(level - 1);
+            let target = level_view(level);
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("mipmap_bind_group"),
+                layout: &bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&source),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&self.sampler),
+                    },
+                ],
+            });
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("mipmap_pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &target,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                ..Default::default()
+            });
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.draw(0..3, 0..1);
+        }
+    }
+}
+
+impl egui_wgpu::CallbackTrait for ImageDisplayCallback {
+    fn prepare(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        _screen_descriptor: &egui_wgpu::ScreenDescriptor,
+        egui_encoder: &mut wgpu::CommandEncoder,
+        callback_resources: &mut egui_wgpu::CallbackResources,
+    ) -> Vec<wgpu::CommandBuffer> {
+        if let Some(resources) = callback_resources.get_mut::<ImageDisplayResources>() {
+            if let Some(upload) = &self.upload {
+                resources.upload_texture(device, queue, egui_encoder, upload, self.mipmaps);
+            }
+            let params = DisplayParams::new(
+                &self.adjustments,
+                &self.hdr,
+                resources.scene_linear,
+                self.nearest,
+                resources.display_lut,
+            );
+            queue.write_buffer(&resources.params_buffer, 0, bytemuck::bytes_of(&params));
+        }
+        Vec::new()
+    }
+
+    fn paint(
+        &self,
+        _info: egui::PaintCallbackInfo,
+        render_pass: &mut wgpu::RenderPass<'static>,
+        callback_resources: &egui_wgpu::CallbackResources,
+    ) {
+        if let Some(resources) = callback_resources.get::<ImageDisplayResources>() {
+            if let Some(bind_group) = &resources.bind_group {
+                render_pass.set_pipeline(&resources.pipeline);
+                render_pass.set_bind_group(0, bind_group, &[]);
+                render_pass.draw(0..6, 0..1);
+            }
+        }
+    }
+}
+
+/// Whether the display shader can reproduce the adjustment pipeline for these settings.
+/// Film emulation, frames, straightening and LUTs still go through the full CPU/compute path.
+pub fn can_preview(adj: &ImageAdjustments) -> bool {
+    !adj.film.enabled
+        && !adj.frame_enabled
+        && adj.straighten_angle == 0.0
+        && adj.lut_path.is_none()
+        && !adj.has_brush_masks()
+}
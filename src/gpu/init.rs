@@ -133,7 +133,6 @@ impl GpuProcessor {
             Self::create_adjustment_pipeline(&device);
         let (histogram_bind_group_layout, histogram_pipeline) =
             Self::create_histogram_pipeline(&device);
-        let overlay_pipeline = Self::create_overlay_pipeline(&device, &texture_bind_group_layout);
         let raw_demosaic_pipeline =
             Self::create_raw_demosaic_pipeline(&device, &buffer_bind_group_layout);
 
@@ -146,7 +145,6 @@ impl GpuProcessor {
             adjustment_pipeline,
             histogram_bind_group_layout,
             histogram_pipeline,
-            overlay_pipeline,
             raw_demosaic_pipeline,
             adapter_info,
         })
@@ -1,8 +1,11 @@
 pub mod adjustments;
+pub mod difference;
+pub mod display;
 pub mod histogram;
 pub mod info;
 pub mod init;
 pub mod overlays;
 pub mod pipelines;
 pub mod raw;
+pub mod tiles;
 pub mod types;
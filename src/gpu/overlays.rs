@@ -1,311 +1,176 @@
-use super::types::GpuProcessor;
-use anyhow::Result;
+use eframe::egui_wgpu::{self, wgpu};
 use image::DynamicImage;
-use tokio::sync::oneshot;
 use wgpu::util::DeviceExt;
 
-impl GpuProcessor {
-    /// Generate focus peaking overlay using GPU
-    pub async fn generate_focus_peaking_overlay(
-        &self,
-        image: &DynamicImage,
-        threshold: f32,
-    ) -> Result<DynamicImage> {
-        let rgba = image.to_rgba8();
-        let (width, height) = rgba.dimensions();
-
-        // Create input texture
-        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("focus_peaking_input"),
-            size: wgpu::Extent3d {
-                width,
-                height,
-                depth_or_array_layers: 1,
-            },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8Unorm,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
-            view_formats: &[],
-        });
-
-        self.queue.write_texture(
-            wgpu::ImageCopyTexture {
-                texture: &texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
-            &rgba,
-            wgpu::ImageDataLayout {
-                offset: 0,
-                bytes_per_row: Some(4 * width),
-                rows_per_image: Some(height),
-            },
-            wgpu::Extent3d {
-                width,
-                height,
-                depth_or_array_layers: 1,
-            },
-        );
-
-        // Create output texture
-        let output_texture = self.device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("focus_peaking_output"),
-            size: wgpu::Extent3d {
-                width,
-                height,
-                depth_or_array_layers: 1,
-            },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8Unorm,
-            usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::COPY_SRC,
-            view_formats: &[],
-        });
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct OverlayParams {
+    mode: u32,           // 0 = focus peaking, 1 = zebra
+    threshold: f32,      // focus peaking threshold
+    high_threshold: f32, // zebra high threshold
+    low_threshold: f32,  // zebra low threshold
+    width: u32,
+    height: u32,
+    _padding: [u32; 2],
+    color: [f32; 4], // focus peaking color
+}
 
-        // Create parameter buffer
-        #[repr(C)]
-        #[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
-        struct OverlayParams {
-            mode: u32,           // 0 = focus peaking, 1 = zebra
-            threshold: f32,      // focus peaking threshold
-            high_threshold: f32, // zebra high threshold
-            low_threshold: f32,  // zebra low threshold
-            width: u32,
-            height: u32,
-        }
+/// Overlays the compute shader can produce
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuOverlay {
+    FocusPeaking,
+    Zebra,
+}
 
-        let params = OverlayParams {
-            mode: 0, // focus peaking
-            threshold,
-            high_threshold: 0.0,
-            low_threshold: 0.0,
-            width,
-            height,
-        };
+impl GpuOverlay {
+    fn index(self) -> usize {
+        self as usize
+    }
+}
 
-        let param_buffer = self
-            .device
-            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: Some("focus_peaking_params"),
-                contents: bytemuck::bytes_of(&params),
-                usage: wgpu::BufferUsages::UNIFORM,
-            });
+/// What to draw and with which thresholds, all normalized to 0-1
+#[derive(Debug, Clone, Copy)]
+pub struct OverlayRequest {
+    pub overlay: GpuOverlay,
+    pub threshold: f32,
+    pub high_threshold: f32,
+    pub low_threshold: f32,
+    pub color: egui::Color32,
+}
 
-        // Create bind group
-        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("focus_peaking_bind_group"),
-            layout: &self.texture_bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(
-                        &texture.create_view(&wgpu::TextureViewDescriptor::default()),
-                    ),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::TextureView(
-                        &output_texture.create_view(&wgpu::TextureViewDescriptor::default()),
-                    ),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 2,
-                    resource: param_buffer.as_entire_binding(),
-                },
-            ],
-        });
+struct OverlayOutput {
+    texture: wgpu::Texture,
+    id: egui::TextureId,
+}
 
-        // Execute
-        let mut encoder = self
-            .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                label: Some("focus_peaking_encoder"),
-            });
+/// Focus peaking and zebras computed on eframe's own wgpu device and written
+/// straight into textures egui draws, so no pixels come back to the CPU. The
+/// source is uploaded once per image; turning an overlay on is one dispatch.
+pub struct OverlayRenderer {
+    render_state: egui_wgpu::RenderState,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::ComputePipeline,
+    source: Option<wgpu::Texture>,
+    outputs: [Option<OverlayOutput>; 2],
+}
 
-        {
-            let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
-                label: Some("focus_peaking_pass"),
-                timestamp_writes: None,
-            });
-            cpass.set_pipeline(&self.overlay_pipeline);
-            cpass.set_bind_group(0, &bind_group, &[]);
-            cpass.dispatch_workgroups(width.div_ceil(16), height.div_ceil(16), 1);
+impl OverlayRenderer {
+    pub fn new(render_state: &egui_wgpu::RenderState) -> Self {
+        let (bind_group_layout, pipeline) =
+            super::pipelines::create_display_overlay_pipeline(&render_state.device);
+        Self {
+            render_state: render_state.clone(),
+            bind_group_layout,
+            pipeline,
+            source: None,
+            outputs: [None, None],
         }
+    }
 
-        // Download result
-        let bytes_per_row = (4 * width).div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
-            * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
-        let output_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("focus_peaking_output_buffer"),
-            size: (bytes_per_row * height) as u64,
-            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
-            mapped_at_creation: false,
-        });
-
-        encoder.copy_texture_to_buffer(
-            wgpu::ImageCopyTexture {
-                texture: &output_texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
-            wgpu::ImageCopyBuffer {
-                buffer: &output_buffer,
-                layout: wgpu::ImageDataLayout {
-                    offset: 0,
-                    bytes_per_row: Some(bytes_per_row),
-                    rows_per_image: Some(height),
+    /// Upload the image the overlays are computed from, downscaled to the
+    /// device's texture limit
+    pub fn set_source(&mut self, image: &DynamicImage) {
+        let device = &self.render_state.device;
+        let max_dimension = device.limits().max_texture_dimension_2d;
+        let rgba = super::display::prepare_rgba8(image, max_dimension);
+        self.source = Some(device.create_texture_with_data(
+            &self.render_state.queue,
+            &wgpu::TextureDescriptor {
+                label: Some("overlay_source"),
+                size: wgpu::Extent3d {
+                    width: rgba.width(),
+                    height: rgba.height(),
+                    depth_or_array_layers: 1,
                 },
-            },
-            wgpu::Extent3d {
-                width,
-                height,
-                depth_or_array_layers: 1,
-            },
-        );
-
-        self.queue.submit(Some(encoder.finish()));
-
-        // Read back result
-        let buffer_slice = output_buffer.slice(..);
-        let (tx, rx) = oneshot::channel();
-        buffer_slice.map_async(wgpu::MapMode::Read, move |res| {
-            let _ = tx.send(res);
-        });
-
-        self.device.poll(wgpu::Maintain::Wait);
-        rx.await??;
-
-        // Process the data and drop the view before unmapping
-        let result = {
-            let data = buffer_slice.get_mapped_range();
-            let bytes_per_row = (4 * width).div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
-                * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
-            let mut valid_data = Vec::with_capacity((width * height * 4) as usize);
-            for row in 0..height {
-                let start = (row * bytes_per_row) as usize;
-                let end = start + (width * 4) as usize;
-                valid_data.extend_from_slice(&data[start..end]);
-            }
-            image::ImageBuffer::from_raw(width, height, valid_data)
-                .ok_or_else(|| anyhow::anyhow!("Failed to create focus peaking overlay"))?
-        };
-
-        output_buffer.unmap();
-
-        Ok(DynamicImage::ImageRgba8(result))
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                view_formats: &[],
+            },
+            wgpu::util::TextureDataOrder::LayerMajor,
+            rgba.as_raw(),
+        ));
     }
 
-    /// Generate zebra overlay using GPU acceleration
-    pub async fn generate_zebra_overlay(
-        &self,
-        image: &DynamicImage,
-        high_threshold: f32,
-        low_threshold: f32,
-    ) -> Result<DynamicImage> {
-        let rgba = image.to_rgba8();
-        let (width, height) = rgba.dimensions();
-
-        // Create input texture
-        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("zebra_input"),
-            size: wgpu::Extent3d {
-                width,
-                height,
-                depth_or_array_layers: 1,
-            },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8Unorm,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
-            view_formats: &[],
-        });
-
-        self.queue.write_texture(
-            wgpu::ImageCopyTexture {
-                texture: &texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
-            &rgba,
-            wgpu::ImageDataLayout {
-                offset: 0,
-                bytes_per_row: Some(4 * width),
-                rows_per_image: Some(height),
-            },
-            wgpu::Extent3d {
-                width,
-                height,
-                depth_or_array_layers: 1,
-            },
-        );
+    pub fn clear_source(&mut self) {
+        self.source = None;
+    }
 
-        // Create output texture
-        let output_texture = self.device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("zebra_output"),
-            size: wgpu::Extent3d {
-                width,
-                height,
-                depth_or_array_layers: 1,
-            },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8Unorm,
-            usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::COPY_SRC,
-            view_formats: &[],
-        });
+    pub fn has_source(&self) -> bool {
+        self.source.is_some()
+    }
 
-        // Create parameter buffer
-        #[repr(C)]
-        #[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
-        struct OverlayParams {
-            mode: u32,           // 0 = focus peaking, 1 = zebra
-            threshold: f32,      // focus peaking threshold
-            high_threshold: f32, // zebra high threshold
-            low_threshold: f32,  // zebra low threshold
-            width: u32,
-            height: u32,
+    /// Run the overlay shader over the source and return the egui texture
+    /// holding the result. Each overlay keeps one texture id for its lifetime.
+    pub fn render(&mut self, request: &OverlayRequest) -> Option<egui::TextureId> {
+        let source = self.source.as_ref()?;
+        let size = source.size();
+        let device = &self.render_state.device;
+
+        let slot = &mut self.outputs[request.overlay.index()];
+        if !slot.as_ref().is_some_and(|out| out.texture.size() == size) {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("overlay_output"),
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            });
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            let mut renderer = self.render_state.renderer.write();
+            let id = match slot.take() {
+                Some(old) => {
+                    renderer.update_egui_texture_from_wgpu_texture(
+                        device,
+                        &view,
+                        wgpu::FilterMode::Linear,
+                        old.id,
+                    );
+                    old.id
+                }
+                None => renderer.register_native_texture(device, &view, wgpu::FilterMode::Linear),
+            };
+            *slot = Some(OverlayOutput { texture, id });
         }
+        let output = slot.as_ref()?;
 
         let params = OverlayParams {
-            mode: 1, // zebra
-            threshold: 0.0,
-            high_threshold,
-            low_threshold,
-            width,
-            height,
+            mode: request.overlay as u32,
+            threshold: request.threshold,
+            high_threshold: request.high_threshold,
+            low_threshold: request.low_threshold,
+            width: size.width,
+            height: size.height,
+            _padding: [0; 2],
+            color: request.color.to_normalized_gamma_f32(),
         };
+        let param_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("overlay_params"),
+            contents: bytemuck::bytes_of(&params),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
 
-        let param_buffer = self
-            .device
-            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: Some("zebra_params"),
-                contents: bytemuck::bytes_of(&params),
-                usage: wgpu::BufferUsages::UNIFORM,
-            });
-
-        // Create bind group
-        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("zebra_bind_group"),
-            layout: &self.texture_bind_group_layout,
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("overlay_bind_group"),
+            layout: &self.bind_group_layout,
             entries: &[
                 wgpu::BindGroupEntry {
                     binding: 0,
                     resource: wgpu::BindingResource::TextureView(
-                        &texture.create_view(&wgpu::TextureViewDescriptor::default()),
+                        &source.create_view(&wgpu::TextureViewDescriptor::default()),
                     ),
                 },
                 wgpu::BindGroupEntry {
                     binding: 1,
                     resource: wgpu::BindingResource::TextureView(
-                        &output_texture.create_view(&wgpu::TextureViewDescriptor::default()),
+                        &output
+                            .texture
+                            .create_view(&wgpu::TextureViewDescriptor::default()),
                     ),
                 },
                 wgpu::BindGroupEntry {
@@ -315,84 +180,21 @@ impl GpuProcessor {
             ],
         });
 
-        // Execute
-        let mut encoder = self
-            .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                label: Some("zebra_encoder"),
-            });
-
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("overlay_encoder"),
+        });
         {
             let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
-                label: Some("zebra_pass"),
+                label: Some("overlay_pass"),
                 timestamp_writes: None,
             });
-            cpass.set_pipeline(&self.overlay_pipeline);
+            cpass.set_pipeline(&self.pipeline);
             cpass.set_bind_group(0, &bind_group, &[]);
-            cpass.dispatch_workgroups(width.div_ceil(16), height.div_ceil(16), 1);
+            cpass.dispatch_workgroups(size.width.div_ceil(16), size.height.div_ceil(16), 1);
         }
+        // Submitted ahead of egui's own frame, so the result is there when drawn
+        self.render_state.queue.submit(Some(encoder.finish()));
 
-        // Download result
-        let bytes_per_row = (4 * width).div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
-            * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
-        let output_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("zebra_output_buffer"),
-            size: (bytes_per_row * height) as u64,
-            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
-            mapped_at_creation: false,
-        });
-
-        encoder.copy_texture_to_buffer(
-            wgpu::ImageCopyTexture {
-                texture: &output_texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
-            wgpu::ImageCopyBuffer {
-                buffer: &output_buffer,
-                layout: wgpu::ImageDataLayout {
-                    offset: 0,
-                    bytes_per_row: Some(bytes_per_row),
-                    rows_per_image: Some(height),
-                },
-            },
-            wgpu::Extent3d {
-                width,
-                height,
-                depth_or_array_layers: 1,
-            },
-        );
-
-        self.queue.submit(Some(encoder.finish()));
-
-        // Read back result
-        let buffer_slice = output_buffer.slice(..);
-        let (tx, rx) = oneshot::channel();
-        buffer_slice.map_async(wgpu::MapMode::Read, move |res| {
-            let _ = tx.send(res);
-        });
-
-        self.device.poll(wgpu::Maintain::Wait);
-        rx.await??;
-
-        // Process the data and drop the view before unmapping
-        let result = {
-            let data = buffer_slice.get_mapped_range();
-            let bytes_per_row = (4 * width).div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
-                * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
-            let mut valid_data = Vec::with_capacity((width * height * 4) as usize);
-            for row in 0..height {
-                let start = (row * bytes_per_row) as usize;
-                let end = start + (width * 4) as usize;
-                valid_data.extend_from_slice(&data[start..end]);
-            }
-            image::ImageBuffer::from_raw(width, height, valid_data)
-                .ok_or_else(|| anyhow::anyhow!("Failed to create zebra overlay"))?
-        };
-
-        output_buffer.unmap();
-
-        Ok(DynamicImage::ImageRgba8(result))
+        Some(output.id)
     }
 }
@@ -1,4 +1,5 @@
 use super::types::GpuProcessor;
+use eframe::egui_wgpu::wgpu as display_wgpu;
 
 impl GpuProcessor {
     pub fn create_adjustment_pipeline(
@@ -131,31 +132,6 @@ impl GpuProcessor {
         (bind_group_layout, pipeline)
     }
 
-    pub fn create_overlay_pipeline(
-        device: &wgpu::Device,
-        layout: &wgpu::BindGroupLayout,
-    ) -> wgpu::ComputePipeline {
-        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("overlay_shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/overlays.wgsl").into()),
-        });
-
-        device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
-            label: Some("overlay_pipeline"),
-            layout: Some(
-                &device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                    label: Some("overlay_pipeline_layout"),
-                    bind_group_layouts: &[layout],
-                    push_constant_ranges: &[],
-                }),
-            ),
-            module: &shader,
-            entry_point: Some("main"),
-            compilation_options: wgpu::PipelineCompilationOptions::default(),
-            cache: None,
-        })
-    }
-
     pub fn create_raw_demosaic_pipeline(
         device: &wgpu::Device,
         layout: &wgpu::BindGroupLayout,
@@ -191,3 +167,277 @@ impl GpuProcessor {
         )
     }
 }
+
+/// Create the render pipeline used to draw the main image inside an egui paint callback.
+///
+/// This runs on eframe's own wgpu device (not the compute device owned by `GpuProcessor`),
+/// so the source texture stays resident where egui renders and never round-trips through
+/// an egui texture upload.
+pub fn create_display_pipeline(
+    device: &display_wgpu::Device,
+    target_format: display_wgpu::TextureFormat,
+) -> (display_wgpu::BindGroupLayout, display_wgpu::RenderPipeline) {
+    let shader = device.create_shader_module(display_wgpu::ShaderModuleDescriptor {
+        label: Some("display_shader"),
+        source: display_wgpu::ShaderSource::Wgsl(include_str!("../shaders/display.wgsl").into()),
+    });
+
+    let bind_group_layout =
+        device.create_bind_group_layout(&display_wgpu::BindGroupLayoutDescriptor {
+            label: Some("display_bind_group_layout"),
+            entries: &[
+                // Source image
+                display_wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: display_wgpu::ShaderStages::FRAGMENT,
+                    ty: display_wgpu::BindingType::Texture {
+                        sample_type: display_wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: display_wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // Sampler
+                display_wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: display_wgpu::ShaderStages::FRAGMENT,
+                    ty: display_wgpu::BindingType::Sampler(
+                        display_wgpu::SamplerBindingType::Filtering,
+                    ),
+                    count: None,
+                },
+                // Adjustment params
+                display_wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: display_wgpu::ShaderStages::FRAGMENT,
+                    ty: display_wgpu::BindingType::Buffer {
+                        ty: display_wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // Display profile lookup table
+                display_wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: display_wgpu::ShaderStages::FRAGMENT,
+                    ty: display_wgpu::BindingType::Texture {
+                        sample_type: display_wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: display_wgpu::TextureViewDimension::D3,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+    let pipeline_layout = device.create_pipeline_layout(&display_wgpu::PipelineLayoutDescriptor {
+        label: Some("display_pipeline_layout"),
+        bind_group_layouts: &[&bind_group_layout],
+        push_constant_ranges: &[],
+    });
+
+    let pipeline = device.create_render_pipeline(&display_wgpu::RenderPipelineDescriptor {
+        label: Some("display_pipeline"),
+        layout: Some(&pipeline_layout),
+        vertex: display_wgpu::VertexState {
+            module: &shader,
+            entry_point: Some("vs_main"),
+            buffers: &[],
+            compilation_options: display_wgpu::PipelineCompilationOptions::default(),
+        },
+        fragment: Some(display_wgpu::FragmentState {
+            module: &shader,
+            entry_point: Some("fs_main"),
+            targets: &[Some(display_wgpu::ColorTargetState {
+                format: target_format,
+                blend: Some(display_wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: display_wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: display_wgpu::PipelineCompilationOptions::default(),
+        }),
+        primitive: display_wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: display_wgpu::MultisampleState::default(),
+        multiview: None,
+        cache: None,
+    });
+
+    (bind_group_layout, pipeline)
+}
+
+/// Create the pipeline that fills a display texture's mip levels, for textures of `format`.
+/// Its bind group layout (source view and sampler) is derived from the shader.
+pub fn create_mipmap_pipeline(
+    device: &display_wgpu::Device,
+    format: display_wgpu::TextureFormat,
+) -> display_wgpu::RenderPipeline {
+    let shader = device.create_shader_module(display_wgpu::ShaderModuleDescriptor {
+        label: Some("mipmap_shader"),
+        source: display_wgpu::ShaderSource::Wgsl(include_str!("../shaders/mipmap.wgsl").into()),
+    });
+
+    device.create_render_pipeline(&display_wgpu::RenderPipelineDescriptor {
+        label: Some("mipmap_pipeline"),
+        layout: None,
+        vertex: display_wgpu::VertexState {
+            module: &shader,
+            entry_point: Some("vs_main"),
+            buffers: &[],
+            compilation_options: display_wgpu::PipelineCompilationOptions::default(),
+        },
+        fragment: Some(display_wgpu::FragmentState {
+            module: &shader,
+            entry_point: Some("fs_main"),
+            targets: &[Some(display_wgpu::ColorTargetState {
+                format,
+                blend: None,
+                write_mask: display_wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: display_wgpu::PipelineCompilationOptions::default(),
+        }),
+        primitive: display_wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: display_wgpu::MultisampleState::default(),
+        multiview: None,
+        cache: None,
+    })
+}
+
+/// Create the focus peaking / zebra compute pipeline on eframe's wgpu device,
+/// so its output textures can be handed to egui directly.
+pub fn create_display_overlay_pipeline(
+    device: &display_wgpu::Device,
+) -> (display_wgpu::BindGroupLayout, display_wgpu::ComputePipeline) {
+    let shader = device.create_shader_module(display_wgpu::ShaderModuleDescriptor {
+        label: Some("overlay_shader"),
+        source: display_wgpu::ShaderSource::Wgsl(include_str!("../shaders/overlays.wgsl").into()),
+    });
+
+    let bind_group_layout =
+        device.create_bind_group_layout(&display_wgpu::BindGroupLayoutDescriptor {
+            label: Some("overlay_bind_group_layout"),
+            entries: &[
+                // Source image
+                display_wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: display_wgpu::ShaderStages::COMPUTE,
+                    ty: display_wgpu::BindingType::Texture {
+                        sample_type: display_wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: display_wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // Overlay output
+                display_wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: display_wgpu::ShaderStages::COMPUTE,
+                    ty: display_wgpu::BindingType::StorageTexture {
+                        access: display_wgpu::StorageTextureAccess::WriteOnly,
+                        format: display_wgpu::TextureFormat::Rgba8Unorm,
+                        view_dimension: display_wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+                // Overlay params
+                display_wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: display_wgpu::ShaderStages::COMPUTE,
+                    ty: display_wgpu::BindingType::Buffer {
+                        ty: display_wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+    let pipeline = device.create_compute_pipeline(&display_wgpu::ComputePipelineDescriptor {
+        label: Some("overlay_pipeline"),
+        layout: Some(
+            &device.create_pipeline_layout(&display_wgpu::PipelineLayoutDescriptor {
+                label: Some("overlay_pipeline_layout"),
+                bind_group_layouts: &[&bind_group_layout],
+                push_constant_ranges: &[],
+            }),
+        ),
+        module: &shader,
+        entry_point: Some("main"),
+        compilation_options: display_wgpu::PipelineCompilationOptions::default(),
+        cache: None,
+    });
+
+    (bind_group_layout, pipeline)
+}
+
+/// Create the compare difference compute pipeline on eframe's wgpu device; it
+/// reads egui's own textures and writes one egui draws.
+pub fn create_difference_pipeline(
+    device: &display_wgpu::Device,
+) -> (display_wgpu::BindGroupLayout, display_wgpu::ComputePipeline) {
+    let shader = device.create_shader_module(display_wgpu::ShaderModuleDescriptor {
+        label: Some("difference_shader"),
+        source: display_wgpu::ShaderSource::Wgsl(include_str!("../shaders/difference.wgsl").into()),
+    });
+
+    let image_entry = |binding| display_wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: display_wgpu::ShaderStages::COMPUTE,
+        ty: display_wgpu::BindingType::Texture {
+            sample_type: display_wgpu::TextureSampleType::Float { filterable: false },
+            view_dimension: display_wgpu::TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    };
+    let bind_group_layout =
+        device.create_bind_group_layout(&display_wgpu::BindGroupLayoutDescriptor {
+            label: Some("difference_bind_group_layout"),
+            entries: &[
+                // The two images
+                image_entry(0),
+                image_entry(1),
+                // Difference output
+                display_wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: display_wgpu::ShaderStages::COMPUTE,
+                    ty: display_wgpu::BindingType::StorageTexture {
+                        access: display_wgpu::StorageTextureAccess::WriteOnly,
+                        format: display_wgpu::TextureFormat::Rgba8Unorm,
+                        view_dimension: display_wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+                // Difference params
+                display_wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: display_wgpu::ShaderStages::COMPUTE,
+                    ty: display_wgpu::BindingType::Buffer {
+                        ty: display_wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+    let pipeline = device.create_compute_pipeline(&display_wgpu::ComputePipelineDescriptor {
+        label: Some("difference_pipeline"),
+        layout: Some(
+            &device.create_pipeline_layout(&display_wgpu::PipelineLayoutDescriptor {
+                label: Some("difference_pipeline_layout"),
+                bind_group_layouts: &[&bind_group_layout],
+                push_constant_ranges: &[],
+            }),
+        ),
+        module: &shader,
+        entry_point: Some("main"),
+        compilation_options: display_wgpu::PipelineCompilationOptions::default(),
+        cache: None,
+    });
+
+    (bind_group_layout, pipeline)
+}
@@ -0,0 +1,235 @@
+use eframe::egui::{self, Color32, Rect, TextureHandle, Vec2};
+use image::{imageops, RgbaImage};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Side of a square tile, in pixels of its own level
+pub const TILE_SIZE: u32 = 1024;
+/// Images with a longer side than this are drawn from tiles
+pub const TILING_THRESHOLD: u32 = 8192;
+/// Tile textures created per frame, so a fast pan doesn't stall a single frame
+const UPLOADS_PER_FRAME: usize = 4;
+/// Tile textures kept around after they scroll out of view
+const MAX_RESIDENT_TILES: usize = 96;
+
+/// Whether an image is too big to show as one texture
+pub fn needs_tiling(width: u32, height: u32, max_texture_side: u32) -> bool {
+    width.max(height) > TILING_THRESHOLD.min(max_texture_side)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TileKey {
+    pub level: usize,
+    pub x: u32,
+    pub y: u32,
+}
+
+/// An image at full resolution and at successive halvings, down to a level
+/// that fits in a single tile
+pub struct TilePyramid {
+    levels: Vec<RgbaImage>,
+}
+
+impl TilePyramid {
+    pub fn new(image: RgbaImage) -> Self {
+        let mut levels = vec![image];
+        while let Some(last) = levels.last() {
+            let (width, height) = last.dimensions();
+            if width.max(height) <= TILE_SIZE {
+                break;
+            }
+            let next = imageops::resize(
+                last,
+                (width / 2).max(1),
+                (height / 2).max(1),
+                imageops::FilterType::Triangle,
+            );
+            levels.push(next);
+        }
+        Self { levels }
+    }
+
+    /// Full resolution size
+    pub fn size(&self) -> Vec2 {
+        let (width, height) = self.levels[0].dimensions();
+        Vec2::new(width as f32, height as f32)
+    }
+
+    pub fn level_count(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// The coarsest level that still has a pixel for every screen pixel at `zoom`
+    pub fn level_for_zoom(&self, zoom: f32) -> usize {
+        if zoom >= 1.0 || zoom <= 0.0 {
+            return 0;
+        }
+        ((1.0 / zoom).log2().floor() as usize).min(self.levels.len() - 1)
+    }
+
+    /// How many full resolution pixels one pixel of `level` covers, per axis
+    fn level_scale(&self, level: usize) -> Vec2 {
+        let (width, height) = self.levels[level].dimensions();
+        self.size() / Vec2::new(width as f32, height as f32)
+    }
+
+    /// Tiles of `level` overlapping `region`, given in full resolution pixels
+    pub fn tiles_in(&self, level: usize, region: Rect) -> Vec<TileKey> {
+        let image = &self.levels[level];
+        let tile = self.level_scale(level) * TILE_SIZE as f32;
+        let cols = image.width().div_ceil(TILE_SIZE);
+        let rows = image.height().div_ceil(TILE_SIZE);
+
+        let x0 = (region.min.x / tile.x).floor().max(0.0) as u32;
+        let y0 = (region.min.y / tile.y).floor().max(0.0) as u32;
+        let x1 = ((region.max.x / tile.x).ceil().max(0.0) as u32).min(cols);
+        let y1 = ((region.max.y / tile.y).ceil().max(0.0) as u32).min(rows);
+
+        (y0..y1)
+            .flat_map(|y| (x0..x1).map(move |x| TileKey { level, x, y }))
+            .collect()
+    }
+
+    /// The area a tile covers, in full resolution pixels
+    pub fn tile_rect(&self, key: TileKey) -> Rect {
+        let image = &self.levels[key.level];
+        let scale = self.level_scale(key.level);
+        let min_x = key.x * TILE_SIZE;
+        let min_y = key.y * TILE_SIZE;
+        let max_x = (min_x + TILE_SIZE).min(image.width());
+        let max_y = (min_y + TILE_SIZE).min(image.height());
+        Rect::from_min_max(
+            egui::pos2(min_x as f32 * scale.x, min_y as f32 * scale.y),
+            egui::pos2(max_x as f32 * scale.x, max_y as f32 * scale.y),
+        )
+    }
+
+    fn tile_image(&self, key: TileKey) -> egui::ColorImage {
+        let image = &self.levels[key.level];
+        let x = key.x * TILE_SIZE;
+        let y = key.y * TILE_SIZE;
+        let width = TILE_SIZE.min(image.width() - x);
+        let height = TILE_SIZE.min(image.height() - y);
+        let tile = imageops::crop_imm(image, x, y, width, height).to_image();
+        egui::ColorImage::from_rgba_unmultiplied([width as usize, height as usize], tile.as_raw())
+    }
+}
+
+/// Draws a tile pyramid, uploading only the tiles in view at the level the
+/// zoom calls for and dropping ones that have been out of view the longest
+pub struct TiledImage {
+    pyramid: Arc<TilePyramid>,
+    textures: HashMap<TileKey, (TextureHandle, u64)>,
+    frame: u64,
+    /// Filtering the resident textures were created with
+    options: egui::TextureOptions,
+}
+
+impl TiledImage {
+    pub fn new(pyramid: Arc<TilePyramid>) -> Self {
+        Self {
+            pyramid,
+            textures: HashMap::new(),
+            frame: 0,
+            options: egui::TextureOptions::LINEAR,
+        }
+    }
+
+    pub fn size(&self) -> Vec2 {
+        self.pyramid.size()
+    }
+
+    /// Paint the part of the image inside `clip`, where `image_rect` is the
+    /// whole image on screen, with textures filtered by `options`.
+    /// Returns true while visible tiles are still to upload.
+    pub fn paint(
+        &mut self,
+        ctx: &egui::Context,
+        painter: &egui::Painter,
+        image_rect: Rect,
+        clip: Rect,
+        options: egui::TextureOptions,
+    ) -> bool {
+        self.frame += 1;
+        if options != self.options {
+            self.textures.clear();
+            self.options = options;
+        }
+        let visible = image_rect.intersect(clip);
+        if !visible.is_positive() {
+            return false;
+        }
+        let zoom = image_rect.width() / self.size().x;
+        let to_image = |p: egui::Pos2| ((p - image_rect.min) / zoom).to_pos2();
+        let region = Rect::from_min_max(to_image(visible.min), to_image(visible.max));
+
+        // The coarsest level is a single tile, so it always covers the view
+        // while the sharper tiles arrive on top of it
+        let coarsest = self.pyramid.level_count() - 1;
+        let wanted = self.pyramid.level_for_zoom(zoom);
+        let mut levels = vec![coarsest];
+        if wanted != coarsest {
+            levels.push(wanted);
+        }
+
+        let mut uploads = 0;
+        let mut pending = false;
+        for level in levels {
+            for key in self.pyramid.tiles_in(level, region) {
+                let texture_id = match self.textures.get_mut(&key) {
+                    Some((texture, last_used)) => {
+                        *last_used = self.frame;
+                        texture.id()
+                    }
+                    None if level == coarsest || uploads < UPLOADS_PER_FRAME => {
+                        uploads += 1;
+                        let texture = ctx.load_texture(
+                            format!("tile_{}_{}_{}", key.level, key.x, key.y),
+                            self.pyramid.tile_image(key),
+                            options,
+                        );
+                        let id = texture.id();
+                        self.textures.insert(key, (texture, self.frame));
+                        id
+                    }
+                    None => {
+                        pending = true;
+                        continue;
+                    }
+                };
+
+                let tile = self.pyramid.tile_rect(key);
+                let screen = Rect::from_min_max(
+                    image_rect.min + tile.min.to_vec2() * zoom,
+                    image_rect.min + tile.max.to_vec2() * zoom,
+                );
+                painter.image(
+                    texture_id,
+                    screen,
+                    Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                    Color32::WHITE,
+                );
+            }
+        }
+
+        self.evict();
+        pending
+    }
+
+    fn evict(&mut self) {
+        if self.textures.len() <= MAX_RESIDENT_TILES {
+            return;
+        }
+        let mut by_age: Vec<(TileKey, u64)> = self
+            .textures
+            .iter()
+            .filter(|(_, (_, last_used))| *last_used < self.frame)
+            .map(|(key, (_, last_used))| (*key, *last_used))
+            .collect();
+        by_age.sort_by_key(|(_, last_used)| *last_used);
+        let excess = self.textures.len() - MAX_RESIDENT_TILES;
+        for (key, _) in by_age.into_iter().take(excess) {
+            self.textures.remove(&key);
+        }
+    }
+}
@@ -20,7 +20,6 @@ pub struct GpuProcessor {
     pub adjustment_pipeline: wgpu::ComputePipeline,
     pub histogram_bind_group_layout: wgpu::BindGroupLayout,
     pub histogram_pipeline: wgpu::ComputePipeline,
-    pub overlay_pipeline: wgpu::ComputePipeline,
     pub raw_demosaic_pipeline: Option<wgpu::ComputePipeline>,
     pub adapter_info: wgpu::AdapterInfo,
 }
@@ -70,4 +69,23 @@ pub struct AdjustmentParams {
     pub halation_color_g: f32,
     pub halation_color_b: f32,
     pub halation_radius: f32,
+    pub contrast: f32,
+    pub tint: f32,
+    // Local adjustments; the padding puts the array on the 16-byte boundary
+    // WGSL requires
+    pub local_count: u32,
+    pub _local_padding: [u32; 2],
+    pub local_masks: [LocalMaskParams; crate::image_loader::MAX_LOCAL_ADJUSTMENTS],
+}
+
+/// One local adjustment as the shaders read it, three `vec4<f32>`s
+#[repr(C)]
+#[derive(Copy, Clone, Default, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct LocalMaskParams {
+    /// Radial: centre x, y and radius x, y. Linear: start x, y and end x, y.
+    pub geometry: [f32; 4],
+    /// Kind (0 radial, 1 linear), feather, invert, unused
+    pub shape: [f32; 4],
+    /// Exposure, saturation, temperature and tint offsets
+    pub amount: [f32; 4],
 }
@@ -0,0 +1,112 @@
+use fluent_bundle::{FluentArgs, FluentBundle, FluentResource, FluentValue};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+
+/// Languages the interface is translated into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+impl Language {
+    pub fn all() -> &'static [Language] {
+        &[Language::English, Language::German]
+    }
+
+    /// Name in the language itself, as listed in the selector
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+        }
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            Language::English => include_str!("../locales/en.ftl"),
+            Language::German => include_str!("../locales/de.ftl"),
+        }
+    }
+}
+
+fn load_bundle(language: Language) -> FluentBundle<FluentResource> {
+    let id = language
+        .code()
+        .parse()
+        .expect("language codes are valid identifiers");
+    let mut bundle = FluentBundle::new(vec![id]);
+    // Direction marks around arguments show up as boxes in egui's fonts
+    bundle.set_use_isolating(false);
+    let resource = FluentResource::try_new(language.source().to_string()).unwrap_or_else(
+        |(resource, errors)| {
+            log::warn!("Errors in the {} locale: {:?}", language.code(), errors);
+            resource
+        },
+    );
+    if let Err(errors) = bundle.add_resource(resource) {
+        log::warn!(
+            "Duplicate messages in the {} locale: {:?}",
+            language.code(),
+            errors
+        );
+    }
+    bundle
+}
+
+thread_local! {
+    /// The chosen language, then English for messages it lacks. Text is only
+    /// looked up on the UI thread.
+    static BUNDLES: RefCell<Vec<FluentBundle<FluentResource>>> =
+        RefCell::new(vec![load_bundle(Language::English)]);
+}
+
+pub fn set_language(language: Language) {
+    let mut bundles = Vec::new();
+    if language != Language::English {
+        bundles.push(load_bundle(language));
+    }
+    bundles.push(load_bundle(Language::English));
+    BUNDLES.with(|b| *b.borrow_mut() = bundles);
+}
+
+fn format(key: &str, args: Option<&FluentArgs>) -> Option<String> {
+    BUNDLES.with(|bundles| {
+        bundles.borrow().iter().find_map(|bundle| {
+            let pattern = bundle.get_message(key)?.value()?;
+            let mut errors = Vec::new();
+            let text = bundle.format_pattern(pattern, args, &mut errors);
+            if !errors.is_empty() {
+                log::warn!("Couldn't format {}: {:?}", key, errors);
+            }
+            Some(text.into_owned())
+        })
+    })
+}
+
+/// Text for `key` in the current language; the key itself if no locale has it
+pub fn tr(key: &str) -> String {
+    format(key, None).unwrap_or_else(|| key.to_string())
+}
+
+/// Text for `key`, or `fallback` when no locale has it
+pub fn tr_or(key: &str, fallback: &str) -> String {
+    format(key, None).unwrap_or_else(|| fallback.to_string())
+}
+
+/// Text for `key` with its `{ $name }` placeables filled from `args`
+pub fn tr_args(key: &str, args: &[(&str, FluentValue)]) -> String {
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, value.clone());
+    }
+    format(key, Some(&fluent_args)).unwrap_or_else(|| key.to_string())
+}
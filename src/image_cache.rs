@@ -3,16 +3,210 @@ use image::DynamicImage;
 use rayon::prelude::*;
 use std::collections::HashMap;
 use std::fs;
+use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
 
+/// Longest side of thumbnails kept on disk; bigger previews are only cached in memory
+const MAX_DISK_THUMBNAIL_SIDE: u32 = 512;
+/// How much of the start of a file goes into its cache key
+const KEY_SAMPLE_BYTES: usize = 64 * 1024;
+/// Check the size cap after this many thumbnails are written
+const PRUNE_EVERY: usize = 200;
+/// Part of every key; bump it when thumbnails start being made differently
+/// so ones saved by older versions aren't used
+const THUMBNAIL_FORMAT_VERSION: u32 = 2;
+
+lazy_static::lazy_static! {
+    static ref DISK_THUMBNAILS: DiskThumbnailCache = DiskThumbnailCache::new(
+        directories::ProjectDirs::from("com", "imageviewer", "ImageViewer")
+            .map(|dirs| dirs.cache_dir().join("thumbnails")),
+    );
+}
+
+/// The thumbnail cache shared by the viewer and the background loaders
+pub fn disk_thumbnails() -> &'static DiskThumbnailCache {
+    &DISK_THUMBNAILS
+}
+
+/// Thumbnails saved as PNG files, so a folder opens quickly the next time.
+/// Files are keyed by their contents and modification time rather than their
+/// path, so renamed or moved images keep their thumbnails and edited ones
+/// get new ones. The least recently used files go once the cap is reached.
+pub struct DiskThumbnailCache {
+    dir: Option<PathBuf>,
+    max_bytes: AtomicU64,
+    writes: AtomicUsize,
+    /// Keys already worked out, with the modification time they were taken at,
+    /// so repeated lookups don't read the file again
+    keys: Mutex<HashMap<PathBuf, (std::time::SystemTime, String)>>,
+}
+
+impl DiskThumbnailCache {
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        let dir = dir.filter(|d| fs::create_dir_all(d).is_ok());
+        Self {
+            dir,
+            max_bytes: AtomicU64::new(512 * 1024 * 1024),
+            writes: AtomicUsize::new(0),
+            keys: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn set_max_size_mb(&self, mb: usize) {
+        self.max_bytes
+            .store(mb as u64 * 1024 * 1024, Ordering::Relaxed);
+    }
+
+    /// Key from the file's size, modification time and first bytes
+    pub fn cache_key(path: &Path) -> Option<String> {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let metadata = path.metadata().ok()?;
+        let mut sample = Vec::with_capacity(KEY_SAMPLE_BYTES);
+        fs::File::open(path)
+            .ok()?
+            .take(KEY_SAMPLE_BYTES as u64)
+            .read_to_end(&mut sample)
+            .ok()?;
+
+        let mut hasher = DefaultHasher::new();
+        THUMBNAIL_FORMAT_VERSION.hash(&mut hasher);
+        metadata.len().hash(&mut hasher);
+        metadata.modified().ok()?.hash(&mut hasher);
+        sample.hash(&mut hasher);
+        Some(format!("{:016x}", hasher.finish()))
+    }
+
+    fn entry_path(&self, path: &Path) -> Option<PathBuf> {
+        let dir = self.dir.as_ref()?;
+        let modified = path.metadata().ok()?.modified().ok()?;
+        let known = match self.keys.lock().unwrap().get(path) {
+            Some((at, key)) if *at == modified => Some(key.clone()),
+            _ => None,
+        };
+        let key = match known {
+            Some(key) => key,
+            None => {
+                let key = Self::cache_key(path)?;
+                self.keys
+                    .lock()
+                    .unwrap()
+                    .insert(path.to_path_buf(), (modified, key.clone()));
+                key
+            }
+        };
+        Some(dir.join(format!("{}.png", key)))
+    }
+
+    /// The saved thumbnail for `path` if its longest side is at least `min_side`
+    pub fn get(&self, path: &Path, min_side: u32) -> Option<DynamicImage> {
+        let cache_path = self.entry_path(path)?;
+        let data = fs::read(&cache_path).ok()?;
+        match image::load_from_memory(&data) {
+            Ok(image) if image.width().max(image.height()) >= min_side => {
+                // Mark it recently used so pruning keeps it
+                if let Ok(file) = fs::File::options().append(true).open(&cache_path) {
+                    let _ = file.set_modified(std::time::SystemTime::now());
+                }
+                tracing::debug!(path = %path.display(), cache = %cache_path.display(), "loaded thumbnail from disk");
+                Some(image)
+            }
+            Ok(_) => None,
+            Err(_) => {
+                tracing::warn!(path = %path.display(), cache = %cache_path.display(), "failed to decode thumbnail from disk");
+                let _ = fs::remove_file(&cache_path);
+                None
+            }
+        }
+    }
+
+    pub fn put(&self, path: &Path, image: &DynamicImage) {
+        if image.width().max(image.height()) > MAX_DISK_THUMBNAIL_SIDE {
+            return;
+        }
+        let Some(cache_path) = self.entry_path(path) else {
+            return;
+        };
+        let mut buffer = std::io::Cursor::new(Vec::new());
+        if image
+            .to_rgba8()
+            .write_to(&mut buffer, image::ImageFormat::Png)
+            .is_err()
+        {
+            return;
+        }
+        if fs::write(&cache_path, buffer.into_inner()).is_ok() {
+            tracing::debug!(path = %path.display(), cache = %cache_path.display(), "saved thumbnail to disk");
+        } else {
+            tracing::warn!(path = %path.display(), cache = %cache_path.display(), "failed to write thumbnail to disk");
+        }
+
+        if self.writes.fetch_add(1, Ordering::Relaxed) % PRUNE_EVERY == PRUNE_EVERY - 1 {
+            self.prune();
+        }
+    }
+
+    /// Delete the least recently used thumbnails until the cache fits its cap
+    pub fn prune(&self) {
+        let Some(dir) = &self.dir else {
+            return;
+        };
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        let mut files: Vec<(PathBuf, u64, std::time::SystemTime)> = entries
+            .flatten()
+            .filter_map(|e| {
+                let metadata = e.metadata().ok()?;
+                let modified = metadata.modified().ok()?;
+                metadata
+                    .is_file()
+                    .then(|| (e.path(), metadata.len(), modified))
+            })
+            .collect();
+
+        let mut total: u64 = files.iter().map(|(_, len, _)| len).sum();
+        let max_bytes = self.max_bytes.load(Ordering::Relaxed);
+        if total <= max_bytes {
+            return;
+        }
+        files.sort_by_key(|(_, _, modified)| *modified);
+        for (file, len, _) in files {
+            if total <= max_bytes {
+                break;
+            }
+            if fs::remove_file(&file).is_ok() {
+                total = total.saturating_sub(len);
+            }
+        }
+        tracing::info!(bytes = total, "pruned disk thumbnail cache");
+    }
+
+    pub fn clear(&self) {
+        if let Some(dir) = &self.dir {
+            if let Ok(entries) = fs::read_dir(dir) {
+                for entry in entries.flatten() {
+                    let _ = fs::remove_file(entry.path());
+                }
+            }
+        }
+    }
+}
+
+/// Memory allowed for thumbnails held in RAM, on top of the image cache
+const THUMBNAIL_MEMORY_BYTES: usize = 256 * 1024 * 1024;
+
 pub struct ImageCache {
-    cache: Arc<Mutex<HashMap<PathBuf, CachedImage>>>,
-    thumbnail_cache: Arc<Mutex<HashMap<PathBuf, CachedImage>>>,
-    max_cache_size: usize,
-    max_cache_items: usize,
-    disk_cache_dir: Option<PathBuf>,
+    cache: Arc<Mutex<CacheEntries>>,
+    thumbnail_cache: Arc<Mutex<CacheEntries>>,
+    /// Ceiling on the decoded size of cached images, shared with the preload thread
+    max_bytes: Arc<AtomicUsize>,
+    hits: AtomicU64,
+    misses: AtomicU64,
 }
 
 #[derive(Clone)]
@@ -29,43 +223,120 @@ pub enum CachePriority {
     Medium = 1, // Adjacent images
 }
 
-impl ImageCache {
-    pub fn new(max_cache_size_mb: usize) -> Self {
-        let disk_cache_dir = if let Some(proj_dirs) =
-            directories::ProjectDirs::from("com", "imageviewer", "ImageViewer")
-        {
-            let cache_dir = proj_dirs.cache_dir().join("thumbnails");
-            if fs::create_dir_all(&cache_dir).is_err() {
-                None
-            } else {
-                Some(cache_dir)
+/// Cached images with a running total of their decoded size, so the ceiling
+/// can be checked without summing every entry
+#[derive(Default)]
+struct CacheEntries {
+    entries: HashMap<PathBuf, CachedImage>,
+    used_bytes: usize,
+    evictions: u64,
+}
+
+impl CacheEntries {
+    fn get(&mut self, path: &Path) -> Option<DynamicImage> {
+        let cached = self.entries.get_mut(path)?;
+        cached.last_access = std::time::Instant::now();
+        Some(cached.image.clone())
+    }
+
+    fn contains(&self, path: &Path) -> bool {
+        self.entries.contains_key(path)
+    }
+
+    fn insert(&mut self, path: PathBuf, image: DynamicImage, priority: CachePriority) {
+        let size_bytes = estimate_image_size(&image);
+        self.used_bytes += size_bytes;
+        let replaced = self.entries.insert(
+            path,
+            CachedImage {
+                image,
+                last_access: std::time::Instant::now(),
+                size_bytes,
+                priority,
+            },
+        );
+        if let Some(old) = replaced {
+            self.used_bytes -= old.size_bytes;
+        }
+    }
+
+    fn remove(&mut self, path: &Path) {
+        if let Some(old) = self.entries.remove(path) {
+            self.used_bytes -= old.size_bytes;
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.used_bytes = 0;
+    }
+
+    /// Drop entries until the total fits in `max_bytes`: lower priority first,
+    /// then least recently used
+    fn evict_to(&mut self, max_bytes: usize) {
+        if self.used_bytes <= max_bytes {
+            return;
+        }
+        tracing::info!(
+            current_size = self.used_bytes,
+            max_size = max_bytes,
+            items = self.entries.len(),
+            "evicting entries from cache"
+        );
+        let mut entries: Vec<_> = self
+            .entries
+            .iter()
+            .map(|(k, v)| (v.priority, v.last_access, k.clone()))
+            .collect();
+        entries.sort_by_key(|(priority, time, _)| (*priority, *time));
+
+        for (_, _, path) in entries {
+            if self.used_bytes <= max_bytes {
+                break;
             }
-        } else {
-            None
-        };
+            tracing::info!(evicted = %path.display(), "evicted entry");
+            self.remove(&path);
+            self.evictions += 1;
+        }
+    }
+}
 
+impl ImageCache {
+    pub fn new(max_cache_size_mb: usize) -> Self {
         Self {
-            cache: Arc::new(Mutex::new(HashMap::new())),
-            thumbnail_cache: Arc::new(Mutex::new(HashMap::new())),
-            max_cache_size: max_cache_size_mb * 1024 * 1024,
-            max_cache_items: 100,
-            disk_cache_dir,
+            cache: Arc::new(Mutex::new(CacheEntries::default())),
+            thumbnail_cache: Arc::new(Mutex::new(CacheEntries::default())),
+            max_bytes: Arc::new(AtomicUsize::new(max_cache_size_mb * 1024 * 1024)),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
         }
     }
 
+    /// Change the memory ceiling, evicting straight away if it was lowered
+    pub fn set_max_size_mb(&self, mb: usize) {
+        let max_bytes = mb * 1024 * 1024;
+        self.max_bytes.store(max_bytes, Ordering::Relaxed);
+        self.cache.lock().unwrap().evict_to(max_bytes);
+    }
+
+    pub fn max_size_bytes(&self) -> usize {
+        self.max_bytes.load(Ordering::Relaxed)
+    }
+
     fn get_from_cache(
         &self,
-        cache: &Arc<Mutex<HashMap<PathBuf, CachedImage>>>,
+        cache: &Arc<Mutex<CacheEntries>>,
         path: &Path,
     ) -> Option<DynamicImage> {
-        let mut cache = cache.lock().unwrap();
-        if let Some(cached) = cache.get_mut(path) {
+        let image = cache.lock().unwrap().get(path);
+        if image.is_some() {
             tracing::trace!(path = %path.display(), "cache hit");
-            cached.last_access = std::time::Instant::now();
-            return Some(cached.image.clone());
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            tracing::trace!(path = %path.display(), "cache miss");
+            self.misses.fetch_add(1, Ordering::Relaxed);
         }
-        tracing::trace!(path = %path.display(), "cache miss");
-        None
+        image
     }
 
     pub fn get<P: AsRef<Path>>(&self, path: P) -> Option<DynamicImage> {
@@ -81,68 +352,16 @@ impl ImageCache {
         }
 
         // Try to load from disk cache
-        if let Some(image) = self.load_thumbnail_from_disk(path) {
-            let size_bytes = estimate_image_size(&image);
+        if let Some(image) = disk_thumbnails().get(path, 0) {
             let mut cache = self.thumbnail_cache.lock().unwrap();
-            cache.insert(
-                path.to_path_buf(),
-                CachedImage {
-                    image: image.clone(),
-                    last_access: std::time::Instant::now(),
-                    size_bytes,
-                    priority: CachePriority::Low,
-                },
-            );
+            cache.insert(path.to_path_buf(), image.clone(), CachePriority::Low);
+            cache.evict_to(THUMBNAIL_MEMORY_BYTES);
             return Some(image);
         }
 
         None
     }
 
-    fn cache_key_path(&self, path: &Path) -> Option<PathBuf> {
-        if let Some(cache_dir) = &self.disk_cache_dir {
-            if let Some(key) = self.get_cache_key(path) {
-                return Some(cache_dir.join(format!("{}.png", key)));
-            }
-        }
-        None
-    }
-
-    fn save_thumbnail_to_disk(&self, path: &Path, image: &DynamicImage) {
-        if let Some(cache_path) = self.cache_key_path(path) {
-            let mut buffer = std::io::Cursor::new(Vec::new());
-            if image
-                .to_rgba8()
-                .write_to(&mut buffer, image::ImageFormat::Png)
-                .is_ok()
-            {
-                if fs::write(&cache_path, buffer.into_inner()).is_ok() {
-                    tracing::debug!(path = %path.display(), cache = %cache_path.display(), "saved thumbnail to disk");
-                } else {
-                    tracing::warn!(path = %path.display(), cache = %cache_path.display(), "failed to write thumbnail to disk");
-                }
-            }
-        }
-    }
-
-    fn load_thumbnail_from_disk(&self, path: &Path) -> Option<DynamicImage> {
-        if let Some(cache_path) = self.cache_key_path(path) {
-            if cache_path.exists() {
-                if let Ok(data) = fs::read(&cache_path) {
-                    if let Ok(img) = image::load_from_memory(&data) {
-                        tracing::debug!(path = %path.display(), cache = %cache_path.display(), "loaded thumbnail from disk");
-                        return Some(img);
-                    } else {
-                        tracing::warn!(path = %path.display(), cache = %cache_path.display(), "failed to decode thumbnail from disk");
-                    }
-                } else {
-                    tracing::warn!(path = %path.display(), cache = %cache_path.display(), "failed to read thumbnail from disk");
-                }
-            }
-        }
-        None
-    }
-
     #[allow(dead_code)]
     pub fn put<P: Into<PathBuf>>(&self, path: P, image: DynamicImage) {
         self.insert(path.into(), image);
@@ -153,95 +372,21 @@ impl ImageCache {
         self.get_stats()
     }
     pub fn insert(&self, path: PathBuf, image: DynamicImage) {
-        let size_bytes = estimate_image_size(&image);
         let mut cache = self.cache.lock().unwrap();
-
-        // Insert first, then ensure we evict if the cache grew too large.
-        cache.insert(
-            path.clone(),
-            CachedImage {
-                image,
-                last_access: std::time::Instant::now(),
-                size_bytes,
-                priority: CachePriority::Medium, // Default priority for inserted images
-            },
-        );
-
-        self.evict_if_needed(&mut cache);
+        // Default priority for inserted images
+        cache.insert(path, image, CachePriority::Medium);
+        cache.evict_to(self.max_size_bytes());
     }
 
     pub fn insert_thumbnail(&self, path: PathBuf, image: DynamicImage) {
-        let size_bytes = estimate_image_size(&image);
-        let mut cache = self.thumbnail_cache.lock().unwrap();
-
-        // Simple size limit for thumbnails
-        if cache.len() > 500 {
-            let mut entries: Vec<_> = cache
-                .iter()
-                .map(|(k, v)| (k.clone(), v.last_access))
-                .collect();
-            entries.sort_by_key(|(_, t)| *t);
-
-            for (key, _) in entries.iter().take(100) {
-                cache.remove(key);
-            }
+        {
+            let mut cache = self.thumbnail_cache.lock().unwrap();
+            cache.insert(path.clone(), image.clone(), CachePriority::Low);
+            cache.evict_to(THUMBNAIL_MEMORY_BYTES);
         }
 
-        cache.insert(
-            path.clone(),
-            CachedImage {
-                image: image.clone(),
-                last_access: std::time::Instant::now(),
-                size_bytes,
-                priority: CachePriority::Low,
-            },
-        );
-
         // Also save to disk cache
-        self.save_thumbnail_to_disk(&path, &image);
-    }
-
-    fn evict_if_needed(&self, cache: &mut HashMap<PathBuf, CachedImage>) {
-        let total_size: usize = cache.values().map(|c| c.size_bytes).sum();
-
-        if total_size > self.max_cache_size || cache.len() > self.max_cache_items {
-            tracing::info!(
-                current_size = total_size,
-                items = cache.len(),
-                "evicting entries from cache"
-            );
-            let mut entries: Vec<_> = cache
-                .iter()
-                .map(|(k, v)| (k.clone(), v.last_access, v.size_bytes, v.priority))
-                .collect();
-            // Sort by priority (higher priority first), then by access time (older first)
-            entries.sort_by_key(|(_, time, _, priority)| (std::cmp::Reverse(*priority), *time));
-
-            let mut current_size = total_size;
-            let target_size = self.max_cache_size / 2;
-            let target_count = self.max_cache_items / 2;
-
-            let to_remove: Vec<PathBuf> = entries
-                .iter()
-                .rev() // Start from lowest priority/oldest
-                .take_while(|(_, _, size, _)| {
-                    if current_size <= target_size && cache.len() <= target_count {
-                        return false;
-                    }
-                    current_size = current_size.saturating_sub(*size);
-                    true
-                })
-                .map(|(path, _, _, _)| path.clone())
-                .collect();
-
-            for path in to_remove.iter() {
-                tracing::info!(evicted = %path.display(), "evicted entry");
-            }
-
-            for path in to_remove {
-                cache.remove(&path);
-            }
-        }
+        disk_thumbnails().put(&path, &image);
     }
 
     pub fn invalidate_path(&self, path: &Path) {
@@ -251,31 +396,22 @@ impl ImageCache {
 
     pub fn preload(&self, paths: Vec<PathBuf>) {
         let cache = Arc::clone(&self.cache);
+        let max_bytes = Arc::clone(&self.max_bytes);
 
         thread::spawn(move || {
             tracing::debug!(count = paths.len(), "starting preload of images");
             for path in paths {
-                {
-                    let c = cache.lock().unwrap();
-                    if c.contains_key(&path) {
-                        tracing::trace!(path = %path.display(), "already cached, skipping preload");
-                        continue;
-                    }
+                if cache.lock().unwrap().contains(&path) {
+                    tracing::trace!(path = %path.display(), "already cached, skipping preload");
+                    continue;
                 }
 
                 if let Ok(image) = image_loader::load_image(&path) {
                     tracing::debug!(path = %path.display(), "preloaded image");
-                    let size_bytes = estimate_image_size(&image);
                     let mut c = cache.lock().unwrap();
-                    c.insert(
-                        path,
-                        CachedImage {
-                            image,
-                            last_access: std::time::Instant::now(),
-                            size_bytes,
-                            priority: CachePriority::Medium, // Default priority for preloaded images
-                        },
-                    );
+                    // Default priority for preloaded images
+                    c.insert(path, image, CachePriority::Medium);
+                    c.evict_to(max_bytes.load(Ordering::Relaxed));
                 }
             }
         });
@@ -291,37 +427,23 @@ impl ImageCache {
                 "starting parallel thumbnail preload"
             );
             paths.par_iter().for_each(|path| {
-                {
-                    let c = cache.lock().unwrap();
-                    if c.contains_key(path) {
-                        tracing::trace!(path = %path.display(), "thumbnail already cached, skipping");
-                        return;
-                    }
+                if cache.lock().unwrap().contains(path) {
+                    tracing::trace!(path = %path.display(), "thumbnail already cached, skipping");
+                    return;
                 }
 
                 // For RAW files, only attempt to extract embedded JPEG thumbnails to avoid heavy RAW decoding here
-                if image_loader::is_raw_file(path) {
-                    if let Ok(thumb) = image_loader::load_raw_embedded_thumbnail(path, size) {
-                        tracing::debug!(path = %path.display(), "preloaded embedded raw thumbnail");
-                        let size_bytes = estimate_image_size(&thumb);
-                        let mut c = cache.lock().unwrap();
-                        c.insert(path.clone(), CachedImage {
-                            image: thumb,
-                            last_access: std::time::Instant::now(),
-                            size_bytes,
-                            priority: CachePriority::Low, // Thumbnails have lower priority
-                        });
-                    }
-                } else if let Ok(thumb) = image_loader::load_thumbnail(path, size) {
+                let thumb = if image_loader::is_raw_file(path) {
+                    image_loader::load_raw_embedded_thumbnail(path, size)
+                } else {
+                    image_loader::load_thumbnail(path, size)
+                };
+                if let Ok(thumb) = thumb {
                     tracing::debug!(path = %path.display(), "preloaded thumbnail");
-                    let size_bytes = estimate_image_size(&thumb);
                     let mut c = cache.lock().unwrap();
-                    c.insert(path.clone(), CachedImage {
-                        image: thumb,
-                        last_access: std::time::Instant::now(),
-                        size_bytes,
-                        priority: CachePriority::Low, // Thumbnails have lower priority
-                    });
+                    // Thumbnails have lower priority
+                    c.insert(path.clone(), thumb, CachePriority::Low);
+                    c.evict_to(THUMBNAIL_MEMORY_BYTES);
                 }
             });
         });
@@ -342,35 +464,32 @@ impl ImageCache {
         let thumb_cache = self.thumbnail_cache.lock().unwrap();
 
         CacheStats {
-            image_count: cache.len(),
-            image_size_bytes: cache.values().map(|c| c.size_bytes).sum(),
-            thumbnail_count: thumb_cache.len(),
-            thumbnail_size_bytes: thumb_cache.values().map(|c| c.size_bytes).sum(),
+            image_count: cache.entries.len(),
+            image_size_bytes: cache.used_bytes,
+            thumbnail_count: thumb_cache.entries.len(),
+            thumbnail_size_bytes: thumb_cache.used_bytes,
+            max_size_bytes: self.max_size_bytes(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: cache.evictions + thumb_cache.evictions,
         }
     }
-
-    fn get_cache_key(&self, path: &Path) -> Option<String> {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-
-        let mut hasher = DefaultHasher::new();
-        path.hash(&mut hasher);
-        path.metadata().ok()?.modified().ok()?.hash(&mut hasher);
-        Some(format!("{:x}", hasher.finish()))
-    }
 }
 
 #[derive(Debug)]
 pub struct CacheStats {
     pub image_count: usize,
     pub image_size_bytes: usize,
-    #[allow(dead_code)]
     pub thumbnail_count: usize,
-    #[allow(dead_code)]
     pub thumbnail_size_bytes: usize,
+    pub max_size_bytes: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
 }
 
+/// Bytes the decoded pixels take up, which depends on the pixel format:
+/// 16-bit and float images from RAW and HDR files are several times bigger
 fn estimate_image_size(image: &DynamicImage) -> usize {
-    let (w, h) = (image.width() as usize, image.height() as usize);
-    w * h * 4
+    image.as_bytes().len()
 }
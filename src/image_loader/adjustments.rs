@@ -1,8 +1,13 @@
 use image::{imageops, DynamicImage, GenericImageView, ImageBuffer, Rgba};
 use num_cpus;
 use rayon::prelude::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 
 use super::film_emulation::ImageAdjustments;
+use crate::errors::{Result, ViewerError};
 
 // ============ ACES FILMIC TONE MAPPING ============
 // Based on the ACES (Academy Color Encoding System) RRT+ODT approximation
@@ -11,7 +16,7 @@ use super::film_emulation::ImageAdjustments;
 /// ACES filmic tone mapping curve (approximation of RRT + ODT)
 /// This provides natural highlight compression and shadow lift
 #[inline]
-fn aces_tonemap(x: f32) -> f32 {
+pub(super) fn aces_tonemap(x: f32) -> f32 {
     // Attempt to simulate Stephen Hill's fit of ACES
     let a = 2.51;
     let b = 0.03;
@@ -97,7 +102,7 @@ fn srgb_to_linear(x: f32) -> f32 {
 
 /// Linear to sRGB gamma conversion
 #[inline]
-fn linear_to_srgb(x: f32) -> f32 {
+pub(super) fn linear_to_srgb(x: f32) -> f32 {
     if x <= 0.0031308 {
         x * 12.92
     } else {
@@ -174,28 +179,330 @@ fn apply_oklab_vibrance(r: f32, g: f32, b: f32, vibrance: f32) -> (f32, f32, f32
     )
 }
 
+// ============ 3D LUT ============
+// Adobe/Resolve .cube format: a size³ grid of RGB outputs, red index varying fastest
+
+/// Largest grid accepted from a .cube file (256³ entries is already ~200MB)
+const MAX_LUT_SIZE: usize = 256;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lut3d {
+    pub title: Option<String>,
+    pub size: usize,
+    pub domain_min: [f32; 3],
+    pub domain_max: [f32; 3],
+    data: Vec<[f32; 3]>,
+}
+
+fn parse_cube_triplet(parts: &[&str], line_no: usize) -> std::result::Result<[f32; 3], String> {
+    if parts.len() != 3 {
+        return Err(format!("line {}: expected 3 values", line_no));
+    }
+    let mut out = [0.0; 3];
+    for (value, part) in out.iter_mut().zip(parts) {
+        *value = part
+            .parse()
+            .map_err(|_| format!("line {}: invalid number '{}'", line_no, part))?;
+    }
+    Ok(out)
+}
+
+impl Lut3d {
+    /// Parse the text of a .cube file
+    pub fn parse_cube(text: &str) -> std::result::Result<Self, String> {
+        let mut title = None;
+        let mut size = None;
+        let mut domain_min = [0.0; 3];
+        let mut domain_max = [1.0; 3];
+        let mut data = Vec::new();
+
+        for (i, line) in text.lines().enumerate() {
+            let line_no = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            match parts[0] {
+                "TITLE" => {
+                    title = Some(line["TITLE".len()..].trim().trim_matches('"').to_string());
+                }
+                "LUT_3D_SIZE" => {
+                    let n: usize = parts
+                        .get(1)
+                        .and_then(|s| s.parse().ok())
+                        .ok_or_else(|| format!("line {}: invalid LUT_3D_SIZE", line_no))?;
+                    if !(2..=MAX_LUT_SIZE).contains(&n) {
+                        return Err(format!("unsupported LUT_3D_SIZE {}", n));
+                    }
+                    size = Some(n);
+                }
+                "LUT_1D_SIZE" => return Err("1D LUTs are not supported".to_string()),
+                "DOMAIN_MIN" => domain_min = parse_cube_triplet(&parts[1..], line_no)?,
+                "DOMAIN_MAX" => domain_max = parse_cube_triplet(&parts[1..], line_no)?,
+                // Other keywords (e.g. LUT_IN_VIDEO_RANGE) don't affect the table
+                keyword if keyword.starts_with(|c: char| c.is_ascii_alphabetic()) => {}
+                _ => data.push(parse_cube_triplet(&parts, line_no)?),
+            }
+        }
+
+        let size = size.ok_or("missing LUT_3D_SIZE")?;
+        if data.len() != size * size * size {
+            return Err(format!(
+                "expected {} entries for size {}, found {}",
+                size * size * size,
+                size,
+                data.len()
+            ));
+        }
+        if (0..3).any(|c| domain_max[c] <= domain_min[c]) {
+            return Err("DOMAIN_MAX must be greater than DOMAIN_MIN".to_string());
+        }
+
+        Ok(Self {
+            title,
+            size,
+            domain_min,
+            domain_max,
+            data,
+        })
+    }
+
+    #[inline]
+    fn entry(&self, r: usize, g: usize, b: usize) -> [f32; 3] {
+        self.data[r + g * self.size + b * self.size * self.size]
+    }
+
+    /// Look up an RGB value (0-1) with trilinear interpolation
+    pub fn sample(&self, rgb: [f32; 3]) -> [f32; 3] {
+        let max_index = (self.size - 1) as f32;
+        let mut base = [0usize; 3];
+        let mut frac = [0.0f32; 3];
+        for c in 0..3 {
+            let range = self.domain_max[c] - self.domain_min[c];
+            let t = ((rgb[c] - self.domain_min[c]) / range).clamp(0.0, 1.0) * max_index;
+            let i = (t.floor() as usize).min(self.size - 2);
+            base[c] = i;
+            frac[c] = t - i as f32;
+        }
+
+        let [r0, g0, b0] = base;
+        let [fr, fg, fb] = frac;
+        let lerp = |a: [f32; 3], b: [f32; 3], t: f32| {
+            [
+                a[0] + (b[0] - a[0]) * t,
+                a[1] + (b[1] - a[1]) * t,
+                a[2] + (b[2] - a[2]) * t,
+            ]
+        };
+
+        let c00 = lerp(self.entry(r0, g0, b0), self.entry(r0 + 1, g0, b0), fr);
+        let c10 = lerp(
+            self.entry(r0, g0 + 1, b0),
+            self.entry(r0 + 1, g0 + 1, b0),
+            fr,
+        );
+        let c01 = lerp(
+            self.entry(r0, g0, b0 + 1),
+            self.entry(r0 + 1, g0, b0 + 1),
+            fr,
+        );
+        let c11 = lerp(
+            self.entry(r0, g0 + 1, b0 + 1),
+            self.entry(r0 + 1, g0 + 1, b0 + 1),
+            fr,
+        );
+        lerp(lerp(c00, c10, fg), lerp(c01, c11, fg), fb)
+    }
+}
+
+/// Modification time and size of a file, to tell when it was rewritten
+type FileStamp = (Option<SystemTime>, u64);
+
+lazy_static::lazy_static! {
+    static ref LUT_CACHE: Mutex<HashMap<PathBuf, (FileStamp, Arc<Lut3d>)>> =
+        Mutex::new(HashMap::new());
+}
+
+/// Load a .cube file, reusing the parsed table if the file hasn't changed
+/// since it was loaded
+pub fn load_cube_lut(path: &Path) -> Result<Arc<Lut3d>> {
+    let metadata = std::fs::metadata(path)?;
+    let stamp = (metadata.modified().ok(), metadata.len());
+    if let Some((cached, lut)) = LUT_CACHE.lock().unwrap().get(path) {
+        if *cached == stamp {
+            return Ok(Arc::clone(lut));
+        }
+    }
+
+    let text = std::fs::read_to_string(path)?;
+    let lut = Lut3d::parse_cube(&text).map_err(|message| ViewerError::DecodingError {
+        path: path.to_path_buf(),
+        message,
+    })?;
+    let lut = Arc::new(lut);
+    LUT_CACHE
+        .lock()
+        .unwrap()
+        .insert(path.to_path_buf(), (stamp, Arc::clone(&lut)));
+    Ok(lut)
+}
+
+/// List .cube files in a folder, sorted by name
+pub fn list_cube_luts(folder: &Path) -> Vec<PathBuf> {
+    let mut luts: Vec<PathBuf> = std::fs::read_dir(folder)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| {
+                    p.extension()
+                        .and_then(|e| e.to_str())
+                        .is_some_and(|e| e.eq_ignore_ascii_case("cube"))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    luts.sort();
+    luts
+}
+
+/// Channel types the per-pixel passes run on, so 16-bit and float images are
+/// adjusted without a trip through 8 bits
+trait Sample: Copy + Send + Sync {
+    /// The value in 0-1
+    fn to_unit(self) -> f32;
+    fn from_unit(value: f32) -> Self;
+}
+
+impl Sample for u8 {
+    fn to_unit(self) -> f32 {
+        self as f32 / 255.0
+    }
+
+    fn from_unit(value: f32) -> Self {
+        (value * 255.0).round().clamp(0.0, 255.0) as u8
+    }
+}
+
+impl Sample for u16 {
+    fn to_unit(self) -> f32 {
+        self as f32 / 65535.0
+    }
+
+    fn from_unit(value: f32) -> Self {
+        (value * 65535.0).round().clamp(0.0, 65535.0) as u16
+    }
+}
+
+impl Sample for f32 {
+    fn to_unit(self) -> f32 {
+        self
+    }
+
+    fn from_unit(value: f32) -> Self {
+        value.clamp(0.0, 1.0)
+    }
+}
+
+/// Apply a LUT in place, blending with the input by `intensity` (0-1)
+pub fn apply_lut(img: &mut ImageBuffer<Rgba<u8>, Vec<u8>>, lut: &Lut3d, intensity: f32) {
+    apply_lut_samples(&mut **img, lut, intensity);
+}
+
+fn apply_lut_samples<P: Sample>(pixels: &mut [P], lut: &Lut3d, intensity: f32) {
+    let intensity = intensity.clamp(0.0, 1.0);
+    if intensity == 0.0 {
+        return;
+    }
+
+    pixels.par_chunks_mut(4).for_each(|pixel| {
+        let input = [pixel[0].to_unit(), pixel[1].to_unit(), pixel[2].to_unit()];
+        let output = lut.sample(input);
+        for c in 0..3 {
+            let blended = input[c] + (output[c] - input[c]) * intensity;
+            pixel[c] = P::from_unit(blended);
+        }
+    });
+}
+
+/// Apply the LUT selected in the adjustments, if any
+fn apply_lut_if_needed<P: Sample>(pixels: &mut [P], adj: &ImageAdjustments) {
+    if let Some(path) = &adj.lut_path {
+        match load_cube_lut(path) {
+            Ok(lut) => apply_lut_samples(pixels, &lut, adj.lut_intensity),
+            Err(e) => log::warn!("Failed to load LUT {}: {}", path.display(), e),
+        }
+    }
+}
+
 pub fn apply_adjustments(image: &DynamicImage, adj: &ImageAdjustments) -> DynamicImage {
     if adj.is_default() {
         return image.clone();
     }
 
-    let mut img = image.to_rgba8();
-    let (width, height) = img.dimensions();
-
-    // Pre-calculate common values
-    let exposure_mult = 2.0_f32.powf(adj.exposure);
-    let sat_factor = adj.saturation;
-    let temp_r_add = if adj.temperature > 0.0 {
-        adj.temperature * 25.5
+    // Geometry first so the per-pixel pass only touches the cropped result
+    let straightened;
+    let image = if adj.straighten_angle != 0.0 {
+        straightened = straighten_image(image, adj.straighten_angle);
+        &straightened
     } else {
-        adj.temperature * 15.3
+        image
     };
-    let temp_b_sub = if adj.temperature > 0.0 {
-        adj.temperature * 15.3
+
+    if !adj.has_tonal_adjustments() {
+        return apply_frame_keeping_precision(image.clone(), adj);
+    }
+
+    // 16-bit and float images keep their precision through the per-pixel pass
+    let adjusted = if super::hdr::is_float(image) {
+        let mut img = image.to_rgba32f();
+        adjust_pixels(&mut *img, image.width(), image.height(), adj);
+        DynamicImage::ImageRgba32F(img)
+    } else if super::hdr::is_high_precision(image) {
+        let mut img = image.to_rgba16();
+        adjust_pixels(&mut *img, image.width(), image.height(), adj);
+        DynamicImage::ImageRgba16(img)
     } else {
-        adj.temperature * 25.5
+        let mut img = image.to_rgba8();
+        adjust_pixels(&mut *img, image.width(), image.height(), adj);
+        DynamicImage::ImageRgba8(img)
     };
 
+    apply_frame_keeping_precision(adjusted, adj)
+}
+
+/// Frames are drawn at 8 bits; without one the image keeps its precision
+fn apply_frame_keeping_precision(image: DynamicImage, adj: &ImageAdjustments) -> DynamicImage {
+    if adj.frame_enabled {
+        super::apply_frame(&image, adj, None)
+    } else {
+        image
+    }
+}
+
+/// [`apply_adjustments`] for exports, which also bake in the frame caption
+/// read from `source`'s EXIF
+pub fn apply_adjustments_for_export(
+    image: &DynamicImage,
+    adj: &ImageAdjustments,
+    source: &Path,
+) -> DynamicImage {
+    if !(adj.frame_enabled && adj.frame_caption) {
+        return apply_adjustments(image, adj);
+    }
+    let caption = super::frame_caption(&crate::exif_data::ExifInfo::from_file(source));
+    let adjusted = apply_adjustments(image, &adj.without_frame());
+    super::apply_frame(&adjusted, adj, Some(&caption))
+}
+
+/// The per-pixel adjustments and LUT over interleaved RGBA samples
+fn adjust_pixels<P: Sample>(pixels: &mut [P], width: u32, height: u32, adj: &ImageAdjustments) {
+    // Pre-calculate common values
+    let exposure_mult = 2.0_f32.powf(adj.exposure);
+    let sat_factor = adj.saturation;
+    let wb_gains = super::white_balance_gains(adj.temperature, adj.tint);
+
     // Pre-generate grain texture for consistent grain pattern
     let grain_seed = 12345u64;
 
@@ -203,49 +510,48 @@ pub fn apply_adjustments(image: &DynamicImage, adj: &ImageAdjustments) -> Dynami
     let center_x = width as f32 / 2.0;
     let center_y = height as f32 / 2.0;
     let max_dist = (center_x * center_x + center_y * center_y).sqrt();
+    let masks = super::LocalMasks::new(&adj.local, width, height);
 
     // Process pixels in parallel
     process_pixels_parallel(
-        &mut img,
+        pixels,
+        width,
         adj,
+        &masks,
         exposure_mult,
         sat_factor,
-        temp_r_add,
-        temp_b_sub,
+        wb_gains,
         grain_seed,
         center_x,
         center_y,
         max_dist,
     );
 
-    // Apply frame if enabled
-    apply_frame_if_needed(img, adj)
+    // LUT goes after the built-in adjustments so it grades the adjusted image
+    apply_lut_if_needed(pixels, adj);
 }
 
 #[allow(clippy::too_many_arguments)]
-fn process_pixels_parallel(
-    img: &mut ImageBuffer<Rgba<u8>, Vec<u8>>,
+fn process_pixels_parallel<P: Sample>(
+    raw_pixels: &mut [P],
+    width: u32,
     adj: &ImageAdjustments,
+    masks: &super::LocalMasks,
     exposure_mult: f32,
     sat_factor: f32,
-    temp_r_add: f32,
-    temp_b_sub: f32,
+    wb_gains: [f32; 3],
     grain_seed: u64,
     center_x: f32,
     center_y: f32,
     max_dist: f32,
 ) {
-    let (width, height) = img.dimensions();
-    let mut samples = img.as_flat_samples_mut();
-    let raw_pixels = samples.as_mut_slice();
-
-    // Calculate bytes per chunk, ensuring it's aligned to 4-byte pixel boundaries
-    let pixel_count = (width * height) as usize;
+    // Calculate samples per chunk, ensuring it's aligned to 4-sample pixel boundaries
+    let pixel_count = raw_pixels.len() / 4;
     let pixels_per_thread = (pixel_count / num_cpus::get()).max(1);
-    let bytes_per_chunk = pixels_per_thread * 4; // 4 bytes per RGBA pixel
+    let samples_per_chunk = pixels_per_thread * 4; // 4 samples per RGBA pixel
 
     raw_pixels
-        .par_chunks_mut(bytes_per_chunk)
+        .par_chunks_mut(samples_per_chunk)
         .enumerate()
         .for_each(|(chunk_idx, chunk)| {
             let chunk_start_pixel = chunk_idx * pixels_per_thread;
@@ -264,10 +570,10 @@ fn process_pixels_parallel(
                     px,
                     py,
                     adj,
+                    masks,
                     exposure_mult,
                     sat_factor,
-                    temp_r_add,
-                    temp_b_sub,
+                    wb_gains,
                     grain_seed,
                     center_x,
                     center_y,
@@ -278,24 +584,23 @@ fn process_pixels_parallel(
 }
 
 #[allow(clippy::too_many_arguments)]
-fn process_single_pixel(
-    pixel: &mut [u8],
+fn process_single_pixel<P: Sample>(
+    pixel: &mut [P],
     px: f32,
     py: f32,
     adj: &ImageAdjustments,
+    masks: &super::LocalMasks,
     exposure_mult: f32,
     sat_factor: f32,
-    temp_r_add: f32,
-    temp_b_sub: f32,
+    wb_gains: [f32; 3],
     grain_seed: u64,
     center_x: f32,
     center_y: f32,
     max_dist: f32,
 ) {
-    let mut r = pixel[0] as f32 / 255.0;
-    let mut g = pixel[1] as f32 / 255.0;
-    let mut b = pixel[2] as f32 / 255.0;
-    let a = pixel[3] as f32;
+    let mut r = pixel[0].to_unit();
+    let mut g = pixel[1].to_unit();
+    let mut b = pixel[2].to_unit();
 
     // Apply film emulation first
     if adj.film.enabled {
@@ -307,6 +612,18 @@ fn process_single_pixel(
     g *= 255.0;
     b *= 255.0;
 
+    // Local adjustments add to the global values under their masks
+    let (exposure_mult, sat_factor, wb_gains) = if adj.local.is_empty() {
+        (exposure_mult, sat_factor, wb_gains)
+    } else {
+        local_factors(
+            adj,
+            masks,
+            (px + 0.5) / (center_x * 2.0),
+            (py + 0.5) / (center_y * 2.0),
+        )
+    };
+
     // Apply standard adjustments
     apply_standard_adjustments(
         &mut r,
@@ -314,8 +631,7 @@ fn process_single_pixel(
         &mut b,
         exposure_mult,
         sat_factor,
-        temp_r_add,
-        temp_b_sub,
+        wb_gains,
         adj,
     );
 
@@ -326,11 +642,10 @@ fn process_single_pixel(
         );
     }
 
-    // Clamp values
-    pixel[0] = r.clamp(0.0, 255.0) as u8;
-    pixel[1] = g.clamp(0.0, 255.0) as u8;
-    pixel[2] = b.clamp(0.0, 255.0) as u8;
-    pixel[3] = a as u8; // Alpha unchanged
+    // Clamp values; alpha is unchanged
+    pixel[0] = P::from_unit(r / 255.0);
+    pixel[1] = P::from_unit(g / 255.0);
+    pixel[2] = P::from_unit(b / 255.0);
 }
 
 fn apply_film_emulation(
@@ -435,8 +750,7 @@ fn apply_standard_adjustments(
     b: &mut f32,
     exposure_mult: f32,
     sat_factor: f32,
-    temp_r_add: f32,
-    temp_b_sub: f32,
+    wb_gains: [f32; 3],
     adj: &ImageAdjustments,
 ) {
     // Apply exposure
@@ -444,19 +758,40 @@ fn apply_standard_adjustments(
     *g *= exposure_mult;
     *b *= exposure_mult;
 
-    // Temperature
-    *r += temp_r_add;
-    *b -= temp_b_sub;
+    // White balance
+    *r *= wb_gains[0];
+    *g *= wb_gains[1];
+    *b *= wb_gains[2];
 
     // ACES tone mapping
     apply_aces_tone_mapping(r, g, b, exposure_mult);
 
+    if adj.contrast != 0.0 {
+        apply_contrast(r, g, b, adj.contrast);
+    }
+
     // Saturation (skip for B&W film)
     if (!adj.film.enabled || !adj.film.is_bw) && (sat_factor - 1.0).abs() > 0.001 {
         apply_saturation(r, g, b, sat_factor);
     }
 }
 
+/// Exposure multiplier, saturation and white balance gains at image-relative
+/// `x`, `y`, with the local adjustments there added to the global ones
+fn local_factors(
+    adj: &ImageAdjustments,
+    masks: &super::LocalMasks,
+    x: f32,
+    y: f32,
+) -> (f32, f32, [f32; 3]) {
+    let [exposure, saturation, temperature, tint] = masks.offsets(x, y);
+    (
+        2.0_f32.powf(adj.exposure + exposure),
+        (adj.saturation + saturation).max(0.0),
+        super::white_balance_gains(adj.temperature + temperature, adj.tint + tint),
+    )
+}
+
 fn apply_aces_tone_mapping(r: &mut f32, g: &mut f32, b: &mut f32, exposure_mult: f32) {
     let r_norm = (*r / 255.0).max(0.0);
     let g_norm = (*g / 255.0).max(0.0);
@@ -471,6 +806,14 @@ fn apply_aces_tone_mapping(r: &mut f32, g: &mut f32, b: &mut f32, exposure_mult:
     *b = b_tm * 255.0;
 }
 
+/// Stretch (or flatten) tones away from mid-grey, in 0-255 units
+fn apply_contrast(r: &mut f32, g: &mut f32, b: &mut f32, contrast: f32) {
+    let factor = 1.0 + contrast;
+    *r = 127.5 + (*r - 127.5) * factor;
+    *g = 127.5 + (*g - 127.5) * factor;
+    *b = 127.5 + (*b - 127.5) * factor;
+}
+
 fn apply_saturation(r: &mut f32, g: &mut f32, b: &mut f32, sat_factor: f32) {
     let r_norm = (*r / 255.0).clamp(0.0, 1.0);
     let g_norm = (*g / 255.0).clamp(0.0, 1.0);
@@ -537,36 +880,6 @@ fn apply_film_post_processing(
     }
 }
 
-fn apply_frame_if_needed(
-    img: ImageBuffer<Rgba<u8>, Vec<u8>>,
-    adj: &ImageAdjustments,
-) -> DynamicImage {
-    if adj.frame_enabled && adj.frame_thickness > 0.0 {
-        let (width, height) = img.dimensions();
-        let thickness = adj.frame_thickness as u32;
-        let new_width = width + 2 * thickness;
-        let new_height = height + 2 * thickness;
-
-        let mut framed = ImageBuffer::new(new_width, new_height);
-
-        // Fill with frame color
-        let frame_r = (adj.frame_color[0] * 255.0) as u8;
-        let frame_g = (adj.frame_color[1] * 255.0) as u8;
-        let frame_b = (adj.frame_color[2] * 255.0) as u8;
-
-        for pixel in framed.pixels_mut() {
-            *pixel = Rgba([frame_r, frame_g, frame_b, 255]);
-        }
-
-        // Copy original image to center
-        imageops::overlay(&mut framed, &img, thickness as i64, thickness as i64);
-
-        DynamicImage::ImageRgba8(framed)
-    } else {
-        DynamicImage::ImageRgba8(img)
-    }
-}
-
 /// Apply S-curve contrast enhancement (film characteristic curve)
 #[inline]
 fn apply_s_curve(x: f32, strength: f32) -> f32 {
@@ -644,22 +957,18 @@ pub fn apply_adjustments_thumbnail(image: &DynamicImage, adj: &ImageAdjustments)
         return image.clone();
     }
 
-    let mut img = image.to_rgba8();
+    let mut img = if adj.straighten_angle != 0.0 {
+        straighten_image(image, adj.straighten_angle).to_rgba8()
+    } else {
+        image.to_rgba8()
+    };
     let (width, height) = img.dimensions();
 
     // Pre-calculate adjustment factors
     let exposure_mult = 2.0_f32.powf(adj.exposure);
     let sat_factor = adj.saturation;
-    let temp_r_add = if adj.temperature > 0.0 {
-        adj.temperature * 25.5
-    } else {
-        adj.temperature * 15.3
-    };
-    let temp_b_sub = if adj.temperature > 0.0 {
-        adj.temperature * 15.3
-    } else {
-        adj.temperature * 25.5
-    };
+    let wb_gains = super::white_balance_gains(adj.temperature, adj.tint);
+    let masks = super::LocalMasks::new(&adj.local, width, height);
 
     // Process each pixel sequentially (safe for small images)
     for y in 0..height {
@@ -670,14 +979,30 @@ pub fn apply_adjustments_thumbnail(image: &DynamicImage, adj: &ImageAdjustments)
             let mut b = pixel[2] as f32;
             let a = pixel[3];
 
+            let (exposure_mult, sat_factor, wb_gains) = if adj.local.is_empty() {
+                (exposure_mult, sat_factor, wb_gains)
+            } else {
+                local_factors(
+                    adj,
+                    &masks,
+                    (x as f32 + 0.5) / width as f32,
+                    (y as f32 + 0.5) / height as f32,
+                )
+            };
+
             // Apply exposure
             r *= exposure_mult;
             g *= exposure_mult;
             b *= exposure_mult;
 
-            // Temperature
-            r += temp_r_add;
-            b -= temp_b_sub;
+            // White balance
+            r *= wb_gains[0];
+            g *= wb_gains[1];
+            b *= wb_gains[2];
+
+            if adj.contrast != 0.0 {
+                apply_contrast(&mut r, &mut g, &mut b, adj.contrast);
+            }
 
             // Saturation
             let gray = 0.299 * r + 0.587 * g + 0.114 * b;
@@ -702,6 +1027,8 @@ pub fn apply_adjustments_thumbnail(image: &DynamicImage, adj: &ImageAdjustments)
         }
     }
 
+    apply_lut_if_needed(&mut *img, adj);
+
     DynamicImage::ImageRgba8(img)
 }
 
@@ -715,6 +1042,44 @@ pub fn rotate_image(image: &DynamicImage, degrees: i32) -> DynamicImage {
     }
 }
 
+/// Scale factor of the largest same-aspect rectangle that fits inside a w×h image
+/// rotated by `degrees`, used to crop away the empty corners after straightening
+pub fn straighten_crop_scale(width: u32, height: u32, degrees: f32) -> f32 {
+    let theta = degrees.to_radians().abs();
+    let (sin, cos) = theta.sin_cos();
+    let w = width as f32;
+    let h = height as f32;
+    let scale_w = w / (w * cos + h * sin);
+    let scale_h = h / (w * sin + h * cos);
+    scale_w.min(scale_h).min(1.0)
+}
+
+// Rotate by an arbitrary angle (degrees clockwise) and crop to remove the blank corners
+pub fn straighten_image(image: &DynamicImage, degrees: f32) -> DynamicImage {
+    use imageproc::geometric_transformations::{rotate_about_center, Interpolation};
+
+    if degrees == 0.0 {
+        return image.clone();
+    }
+
+    let rgba = image.to_rgba8();
+    let (width, height) = rgba.dimensions();
+    let rotated = rotate_about_center(
+        &rgba,
+        degrees.to_radians(),
+        Interpolation::Bilinear,
+        Rgba([0, 0, 0, 0]),
+    );
+
+    let scale = straighten_crop_scale(width, height, degrees);
+    let crop_w = ((width as f32 * scale).floor() as u32).max(1);
+    let crop_h = ((height as f32 * scale).floor() as u32).max(1);
+    let x = (width - crop_w) / 2;
+    let y = (height - crop_h) / 2;
+
+    DynamicImage::ImageRgba8(imageops::crop_imm(&rotated, x, y, crop_w, crop_h).to_image())
+}
+
 // Crop image to specified rectangle (x, y, width, height)
 pub fn crop_image(image: &DynamicImage, x: u32, y: u32, width: u32, height: u32) -> DynamicImage {
     let (img_width, img_height) = image.dimensions();
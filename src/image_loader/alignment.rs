@@ -0,0 +1,128 @@
+use image::{imageops::FilterType, DynamicImage, GrayImage};
+use rayon::prelude::*;
+
+/// Searches from coarse to fine: the longest side frames are reduced to, how
+/// many pixels either way the offset is searched, and the rotations searched
+/// either side of the best so far (span and step, in degrees)
+const SEARCH_LEVELS: [(u32, i32, f32, f32); 3] = [
+    (64, 12, 3.0, 1.0),
+    (128, 2, 0.75, 0.25),
+    (256, 2, 0.25, 0.125),
+];
+/// Share of the reference that must overlap the other frame
+const MIN_OVERLAP: f32 = 0.5;
+/// Mean difference (0-255) above which the frames aren't the same scene
+const MAX_DIFFERENCE: f32 = 40.0;
+
+/// How a second frame sits relative to a reference: the point `p` of the
+/// reference shows up at `rotate(p - centre, rotation) + centre + offset` in
+/// the other frame. Positions are in units of the reference's width, so an
+/// alignment holds at any resolution.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Alignment {
+    pub offset: [f32; 2],
+    /// Radians, clockwise on screen
+    pub rotation: f32,
+}
+
+impl Alignment {
+    /// Where point `p` of the reference lies in the other frame, for frames
+    /// `height` widths tall
+    pub fn to_other(&self, p: [f32; 2], height: f32) -> [f32; 2] {
+        let centre = [0.5, height / 2.0];
+        let rotated = rotate([p[0] - centre[0], p[1] - centre[1]], self.rotation);
+        [
+            rotated[0] + centre[0] + self.offset[0],
+            rotated[1] + centre[1] + self.offset[1],
+        ]
+    }
+
+    /// Where point `q` of the other frame lies in the reference
+    pub fn to_reference(&self, q: [f32; 2], height: f32) -> [f32; 2] {
+        let centre = [0.5, height / 2.0];
+        let shifted = [
+            q[0] - centre[0] - self.offset[0],
+            q[1] - centre[1] - self.offset[1],
+        ];
+        let rotated = rotate(shifted, -self.rotation);
+        [rotated[0] + centre[0], rotated[1] + centre[1]]
+    }
+}
+
+fn rotate(p: [f32; 2], angle: f32) -> [f32; 2] {
+    let (sin, cos) = angle.sin_cos();
+    [p[0] * cos - p[1] * sin, p[0] * sin + p[1] * cos]
+}
+
+/// Find the translation and rotation that line `other` up with `reference`,
+/// for similar frames such as a burst. None when they don't match well enough.
+pub fn estimate_alignment(reference: &DynamicImage, other: &DynamicImage) -> Option<Alignment> {
+    if reference.width() == 0 || reference.height() == 0 || other.width() == 0 {
+        return None;
+    }
+
+    let mut best = Alignment::default();
+    let mut best_cost = f32::MAX;
+    for (side, shift, span, step) in SEARCH_LEVELS {
+        let a = reference.thumbnail(side, side).to_luma8();
+        let b = other
+            .resize_exact(a.width(), a.height(), FilterType::Triangle)
+            .to_luma8();
+        let width = a.width() as f32;
+
+        let steps = (span / step).round() as i32;
+        let candidates: Vec<Alignment> = (-shift..=shift)
+            .flat_map(|dy| (-shift..=shift).map(move |dx| (dx, dy)))
+            .flat_map(|(dx, dy)| {
+                (-steps..=steps).map(move |k| Alignment {
+                    offset: [
+                        best.offset[0] + dx as f32 / width,
+                        best.offset[1] + dy as f32 / width,
+                    ],
+                    rotation: best.rotation + (k as f32 * step).to_radians(),
+                })
+            })
+            .collect();
+        let (cost, found) = candidates
+            .into_par_iter()
+            .filter_map(|candidate| Some((alignment_cost(&a, &b, &candidate)?, candidate)))
+            .min_by(|x, y| x.0.total_cmp(&y.0))?;
+        best = found;
+        best_cost = cost;
+    }
+
+    (best_cost <= MAX_DIFFERENCE).then_some(best)
+}
+
+/// Mean absolute difference between the reference and the other frame
+/// sampled through `alignment`, or None when too little of them overlaps
+fn alignment_cost(reference: &GrayImage, other: &GrayImage, alignment: &Alignment) -> Option<f32> {
+    let width = reference.width() as f32;
+    let height = reference.height() as f32 / width;
+    let mut total = 0.0;
+    let mut count = 0usize;
+    for (x, y, pixel) in reference.enumerate_pixels() {
+        let p = [(x as f32 + 0.5) / width, (y as f32 + 0.5) / width];
+        let q = alignment.to_other(p, height);
+        if let Some(value) = sample_bilinear(other, q[0] * width - 0.5, q[1] * width - 0.5) {
+            total += (pixel.0[0] as f32 - value).abs();
+            count += 1;
+        }
+    }
+    let pixels = (reference.width() * reference.height()) as f32;
+    (count as f32 >= pixels * MIN_OVERLAP).then(|| total / count as f32)
+}
+
+fn sample_bilinear(image: &GrayImage, x: f32, y: f32) -> Option<f32> {
+    let (width, height) = image.dimensions();
+    if x < 0.0 || y < 0.0 || x > (width - 1) as f32 || y > (height - 1) as f32 {
+        return None;
+    }
+    let (x0, y0) = (x as u32, y as u32);
+    let (x1, y1) = ((x0 + 1).min(width - 1), (y0 + 1).min(height - 1));
+    let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+    let value = |x, y| image.get_pixel(x, y).0[0] as f32;
+    let top = value(x0, y0) * (1.0 - fx) + value(x1, y0) * fx;
+    let bottom = value(x0, y1) * (1.0 - fx) + value(x1, y1) * fx;
+    Some(top * (1.0 - fy) + bottom * fy)
+}
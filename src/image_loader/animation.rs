@@ -0,0 +1,101 @@
+use crate::errors::{Result, ViewerError};
+use image::codecs::gif::GifDecoder;
+use image::codecs::png::PngDecoder;
+use image::codecs::webp::WebPDecoder;
+use image::{AnimationDecoder, DynamicImage, Frames};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::time::Duration;
+
+/// Memory decoded frames may take, so long or large animations can't exhaust it
+pub const MAX_ANIMATION_BYTES: usize = 512 * 1024 * 1024;
+
+/// Browsers clamp near-zero GIF delays to this value; do the same so these files don't spin
+const MIN_FRAME_DELAY: Duration = Duration::from_millis(20);
+
+pub struct AnimationFrame {
+    pub image: DynamicImage,
+    pub delay: Duration,
+}
+
+/// Whether the file extension belongs to a format that may carry multiple frames
+pub fn is_animation_candidate(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| matches!(e.to_lowercase().as_str(), "gif" | "png" | "apng" | "webp"))
+        .unwrap_or(false)
+}
+
+/// Decode all frames of an animated GIF, APNG or WebP.
+/// Returns `Ok(None)` for still images so callers can keep the regular single-frame path.
+/// Decoding stops once the frames would take more than `max_bytes`, and the
+/// animation then also returns `Ok(None)`, showing its first frame as a still.
+pub fn load_animation(path: &Path, max_bytes: usize) -> Result<Option<Vec<AnimationFrame>>> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+
+    let reader = BufReader::new(File::open(path)?);
+    let decode_error = |e: image::ImageError| ViewerError::DecodingError {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    };
+
+    let frames = match ext.as_str() {
+        "gif" => GifDecoder::new(reader).map_err(decode_error)?.into_frames(),
+        "png" | "apng" => {
+            let decoder = PngDecoder::new(reader).map_err(decode_error)?;
+            if !decoder.is_apng().map_err(decode_error)? {
+                return Ok(None);
+            }
+            decoder.apng().map_err(decode_error)?.into_frames()
+        }
+        "webp" => {
+            let decoder = WebPDecoder::new(reader).map_err(decode_error)?;
+            if !decoder.has_animation() {
+                return Ok(None);
+            }
+            decoder.into_frames()
+        }
+        _ => return Ok(None),
+    };
+
+    let Some(frames) = collect_frames(frames, max_bytes).map_err(decode_error)? else {
+        log::info!(
+            "{} needs over {} MB to animate; showing its first frame",
+            path.display(),
+            max_bytes / (1024 * 1024)
+        );
+        return Ok(None);
+    };
+    if frames.len() < 2 {
+        return Ok(None);
+    }
+    Ok(Some(frames))
+}
+
+/// The decoded frames, or None once they'd take more than `max_bytes`
+fn collect_frames(
+    frames: Frames<'_>,
+    max_bytes: usize,
+) -> image::ImageResult<Option<Vec<AnimationFrame>>> {
+    let mut result = Vec::new();
+    let mut bytes = 0usize;
+    for frame in frames {
+        let frame = frame?;
+        let (width, height) = frame.buffer().dimensions();
+        bytes = bytes.saturating_add(width as usize * height as usize * 4);
+        if bytes > max_bytes {
+            return Ok(None);
+        }
+        let delay = Duration::from(frame.delay()).max(MIN_FRAME_DELAY);
+        result.push(AnimationFrame {
+            image: DynamicImage::ImageRgba8(frame.into_buffer()),
+            delay,
+        });
+    }
+    Ok(Some(result))
+}
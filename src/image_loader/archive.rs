@@ -0,0 +1,128 @@
+use super::loader::MAX_FILE_SIZE;
+use crate::errors::{Result, ViewerError};
+use image::DynamicImage;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Archives opened like folders; .cbz is a zip of comic pages
+pub const ARCHIVE_EXTENSIONS: &[&str] = &["zip", "cbz"];
+
+/// Formats that can be decoded from bytes in memory. RAW files, videos and
+/// layered documents are read from their own path, so they aren't listed.
+const ARCHIVE_IMAGE_EXTENSIONS: &[&str] = &[
+    "jpg", "jpeg", "png", "gif", "bmp", "tiff", "tif", "webp", "ico", "pnm", "pbm", "pgm", "ppm",
+    "jxl", "exr", "hdr",
+];
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| extensions.contains(&e.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+pub fn is_archive_file(path: &Path) -> bool {
+    has_extension(path, ARCHIVE_EXTENSIONS)
+}
+
+/// Whether `path` is a folder or an archive that can be browsed like one
+pub fn opens_as_folder(path: &Path) -> bool {
+    path.is_dir() || (is_archive_file(path) && path.is_file())
+}
+
+/// Split a path such as `comics/issue1.cbz/pages/001.jpg` into the archive
+/// file and the name of the entry inside it
+pub fn split_archive_path(path: &Path) -> Option<(&Path, String)> {
+    let archive = path
+        .ancestors()
+        .skip(1)
+        .find(|a| is_archive_file(a) && a.is_file())?;
+    let entry = path.strip_prefix(archive).ok()?;
+    let name = entry
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    Some((archive, name))
+}
+
+/// Whether `path` names an image inside an archive rather than a real file
+pub fn is_archive_entry(path: &Path) -> bool {
+    split_archive_path(path).is_some()
+}
+
+/// Paths of the images in `archive`, under the archive's own path
+pub fn list_archive_images(archive: &Path) -> io::Result<Vec<PathBuf>> {
+    let zip = zip::ZipArchive::new(File::open(archive)?)?;
+    Ok(zip
+        .file_names()
+        // Resource forks macOS adds when zipping
+        .filter(|name| !name.ends_with('/') && !name.starts_with("__MACOSX/"))
+        .filter_map(|name| {
+            let mut path = archive.to_path_buf();
+            for part in name.split('/') {
+                // Never climb out of the archive
+                if part.is_empty() || part == "." || part == ".." {
+                    return None;
+                }
+                path.push(part);
+            }
+            has_extension(&path, ARCHIVE_IMAGE_EXTENSIONS).then_some(path)
+        })
+        .collect())
+}
+
+/// The bytes of an image inside an archive
+pub fn read_archive_entry(path: &Path) -> io::Result<Vec<u8>> {
+    let (archive, name) = split_archive_path(path)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Not inside an archive"))?;
+    let mut zip = zip::ZipArchive::new(File::open(archive)?)?;
+    let entry = zip.by_name(&name)?;
+    let too_large = || {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Entry too large (max {}MB)", MAX_FILE_SIZE / (1024 * 1024)),
+        )
+    };
+    // The declared size comes from the archive and can't be trusted either
+    if entry.size() > MAX_FILE_SIZE {
+        return Err(too_large());
+    }
+    let mut data = Vec::new();
+    entry.take(MAX_FILE_SIZE + 1).read_to_end(&mut data)?;
+    if data.len() as u64 > MAX_FILE_SIZE {
+        return Err(too_large());
+    }
+    Ok(data)
+}
+
+/// Decode an image straight from its archive, turned upright by its EXIF
+pub fn load_archive_image(path: &Path) -> Result<DynamicImage> {
+    let error = |message: String| ViewerError::ImageLoadError {
+        path: path.to_path_buf(),
+        message,
+    };
+    let data = read_archive_entry(path).map_err(|e| error(e.to_string()))?;
+    let image = if super::jxl::is_jxl_file(path) {
+        let decoder = jxl_oxide::integration::JxlDecoder::new(io::Cursor::new(&data))
+            .map_err(|e| error(e.to_string()))?;
+        DynamicImage::from_decoder(decoder)
+    } else {
+        image::load_from_memory(&data)
+    }
+    .map_err(|e| error(e.to_string()))?;
+
+    let orientation = exif::Reader::new()
+        .read_from_container(&mut io::Cursor::new(&data))
+        .ok()
+        .and_then(|exif| {
+            exif.get_field(exif::Tag::Orientation, exif::In::PRIMARY)?
+                .value
+                .get_uint(0)
+        });
+    Ok(match orientation {
+        Some(orientation) => super::loader::apply_orientation(image, orientation),
+        None => image,
+    })
+}
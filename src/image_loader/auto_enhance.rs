@@ -0,0 +1,130 @@
+use super::{
+    calculate_histogram, calculate_luminance_histogram, white_balance_gains, ImageAdjustments,
+};
+use image::DynamicImage;
+
+/// Longest side a photo is reduced to before it is measured
+const ANALYSIS_SIZE: u32 = 256;
+/// Luminance (0-255) the median tone is brought to
+const TARGET_MEDIAN: f32 = 110.0;
+/// Spread from the darkest to the brightest 1% of a well-exposed photo
+const TARGET_RANGE: f32 = 220.0;
+
+/// Exposure, contrast and white balance picked from a photo's histogram
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AutoEnhance {
+    pub exposure: f32,
+    pub contrast: f32,
+    pub temperature: f32,
+    pub tint: f32,
+}
+
+impl AutoEnhance {
+    /// Set the analysed values, leaving the look (saturation, film, LUT) and
+    /// any geometry, frame or local adjustments alone
+    pub fn apply_to(&self, adjustments: &mut ImageAdjustments) {
+        adjustments.exposure = self.exposure;
+        adjustments.contrast = self.contrast;
+        adjustments.temperature = self.temperature;
+        adjustments.tint = self.tint;
+    }
+
+    /// Short description for the status bar
+    pub fn summary(&self) -> String {
+        format!(
+            "{:+.2} EV, contrast {:+.0}, {:.0}K, tint {:+.0}",
+            self.exposure,
+            self.contrast * 100.0,
+            super::temperature_to_kelvin(self.temperature),
+            self.tint * 100.0
+        )
+    }
+}
+
+/// Analyse the unadjusted `image`: exposure brings the median tone to
+/// mid-grey, contrast stretches the tonal range, and white balance makes the
+/// average colour neutral (grey world)
+pub fn auto_enhance(image: &DynamicImage) -> AutoEnhance {
+    let small = image.thumbnail(ANALYSIS_SIZE, ANALYSIS_SIZE);
+    let luminance = calculate_luminance_histogram(&small);
+    let total: u64 = luminance.iter().map(|&count| count as u64).sum();
+    if total == 0 {
+        return AutoEnhance::default();
+    }
+
+    let median = histogram_percentile(&luminance, total, 0.5).max(1.0);
+    let exposure = (TARGET_MEDIAN / median).log2().clamp(-2.0, 2.0);
+    let exposure_mult = 2.0_f32.powf(exposure);
+
+    // Range after the exposure change, which clips the brightest tones
+    let low = (histogram_percentile(&luminance, total, 0.01) * exposure_mult).min(255.0);
+    let high = (histogram_percentile(&luminance, total, 0.99) * exposure_mult).min(255.0);
+    let contrast = (TARGET_RANGE / (high - low).max(1.0) - 1.0).clamp(-0.25, 0.5);
+
+    let rgb = calculate_histogram(&small);
+    let mean = |channel: &[u32]| {
+        let sum: u64 = channel
+            .iter()
+            .enumerate()
+            .map(|(value, &count)| value as u64 * count as u64)
+            .sum();
+        sum as f32 / total as f32
+    };
+    let [red, green, blue] = [mean(&rgb[0]), mean(&rgb[1]), mean(&rgb[2])];
+
+    // Warming raises red against blue, and positive tint lowers green against
+    // both, so each can be found by bisection
+    let temperature = bisect(|t| {
+        let gains = white_balance_gains(t, 0.0);
+        red * gains[0] - blue * gains[2]
+    });
+    let tint = -bisect(|t| {
+        let gains = white_balance_gains(temperature, -t);
+        green * gains[1] - (red * gains[0] + blue * gains[2]) / 2.0
+    });
+
+    AutoEnhance {
+        exposure: round_hundredths(exposure),
+        contrast: round_hundredths(contrast),
+        temperature: round_hundredths(temperature),
+        tint: round_hundredths(tint),
+    }
+}
+
+/// Where the increasing `f` crosses zero between -1 and 1, or the nearer end
+fn bisect(f: impl Fn(f32) -> f32) -> f32 {
+    let (mut low, mut high) = (-1.0_f32, 1.0_f32);
+    if f(low) >= 0.0 {
+        return low;
+    }
+    if f(high) <= 0.0 {
+        return high;
+    }
+    for _ in 0..24 {
+        let mid = (low + high) / 2.0;
+        if f(mid) < 0.0 {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    (low + high) / 2.0
+}
+
+/// Value below which `fraction` of the counted pixels fall, at bin centres
+fn histogram_percentile(histogram: &[u32], total: u64, fraction: f32) -> f32 {
+    let target = (total as f32 * fraction).ceil() as u64;
+    let mut seen = 0u64;
+    for (value, &count) in histogram.iter().enumerate() {
+        seen += count as u64;
+        if seen >= target.max(1) {
+            return value as f32 + 0.5;
+        }
+    }
+    histogram.len() as f32 - 0.5
+}
+
+/// Keep slider values tidy
+fn round_hundredths(value: f32) -> f32 {
+    (value * 100.0).round() / 100.0
+}
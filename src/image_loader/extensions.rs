@@ -1,6 +1,8 @@
 pub const SUPPORTED_EXTENSIONS: &[&str] = &[
     // Standard formats
     "jpg", "jpeg", "png", "gif", "bmp", "tiff", "tif", "webp", "ico", "pnm", "pbm", "pgm", "ppm",
+    "jxl", "exr", "hdr", "psd", "psb", "kra", "ora",
+    "mp4", "mov", "m4v", "avi", "mkv", "webm", "mts",
     // RAW formats
     "cr2", "cr3", "nef", "arw", "orf", "rw2", "dng", "raf", "raw", "srw", "pef", "x3f", "3fr",
     "mef", "mrw", "nrw", "rwl", "sr2", "srf", "erf", "kdc", "dcr",
@@ -1,4 +1,6 @@
+use super::{FrameStyle, LocalAdjustment};
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FilmEmulation {
@@ -170,9 +172,37 @@ pub struct ImageAdjustments {
     pub saturation: f32,     // 0.0 to 2.0 (multiplier)
     pub temperature: f32,    // -1.0 to +1.0 (cool to warm)
     pub film: FilmEmulation, // Film emulation parameters
+    #[serde(default)]
+    pub tint: f32, // -1.0 to +1.0 (green to magenta)
+    #[serde(default)]
+    pub contrast: f32, // -1.0 to +1.0, around mid-grey
     pub frame_enabled: bool,
     pub frame_color: [f32; 3], // RGB 0-1
     pub frame_thickness: f32,  // pixels
+    #[serde(default)]
+    pub frame_style: FrameStyle,
+    #[serde(default = "default_frame_aspect")]
+    pub frame_aspect: [f32; 2], // width:height for FrameStyle::Aspect
+    #[serde(default)]
+    pub frame_corner_radius: f32, // pixels, rounds the photo's corners
+    #[serde(default)]
+    pub frame_caption: bool, // EXIF line in the bottom border of exports
+    #[serde(default)]
+    pub straighten_angle: f32, // degrees clockwise, -45 to +45
+    #[serde(default)]
+    pub lut_path: Option<PathBuf>, // .cube file applied after the other adjustments
+    #[serde(default = "default_lut_intensity")]
+    pub lut_intensity: f32, // 0.0 to 1.0
+    #[serde(default)]
+    pub local: Vec<LocalAdjustment>, // gradient-masked edits, on top of the global ones
+}
+
+fn default_lut_intensity() -> f32 {
+    1.0
+}
+
+fn default_frame_aspect() -> [f32; 2] {
+    [4.0, 5.0]
 }
 
 impl Default for ImageAdjustments {
@@ -181,10 +211,20 @@ impl Default for ImageAdjustments {
             exposure: 0.0,
             saturation: 1.0,
             temperature: 0.0,
+            tint: 0.0,
+            contrast: 0.0,
             film: FilmEmulation::default(),
             frame_enabled: false,
             frame_color: [1.0, 1.0, 1.0], // white
             frame_thickness: 80.0,
+            frame_style: FrameStyle::default(),
+            frame_aspect: default_frame_aspect(),
+            frame_corner_radius: 0.0,
+            frame_caption: false,
+            straighten_angle: 0.0,
+            lut_path: None,
+            lut_intensity: default_lut_intensity(),
+            local: Vec::new(),
         }
     }
 }
@@ -239,6 +279,8 @@ impl FilmCharacteristics {
             exposure: 0.0,
             saturation: self.saturation,
             temperature: self.temperature,
+            tint: 0.0,
+            contrast: 0.0,
             film: FilmEmulation {
                 enabled: true,
                 is_bw: self.is_bw,
@@ -257,6 +299,14 @@ impl FilmCharacteristics {
             frame_enabled: false,
             frame_color: [1.0, 1.0, 1.0],
             frame_thickness: 80.0,
+            frame_style: FrameStyle::default(),
+            frame_aspect: default_frame_aspect(),
+            frame_corner_radius: 0.0,
+            frame_caption: false,
+            straighten_angle: 0.0,
+            lut_path: None,
+            lut_intensity: default_lut_intensity(),
+            local: Vec::new(),
         }
     }
 }
@@ -266,12 +316,72 @@ impl ImageAdjustments {
         self.exposure == 0.0
             && self.saturation == 1.0
             && self.temperature == 0.0
+            && self.tint == 0.0
+            && self.contrast == 0.0
             && !self.film.enabled
             && !self.frame_enabled
+            && self.straighten_angle == 0.0
+            && self.lut_path.is_none()
+            && self.local.is_empty()
+    }
+
+    /// Whether any per-pixel (tone/color/film) adjustment is active, ignoring geometry and frame
+    pub fn has_tonal_adjustments(&self) -> bool {
+        self.exposure != 0.0
+            || self.saturation != 1.0
+            || self.temperature != 0.0
+            || self.tint != 0.0
+            || self.contrast != 0.0
+            || self.film.enabled
+            || self.lut_path.is_some()
+            || !self.local.is_empty()
+    }
+
+    /// Whether a local adjustment is painted with the brush, which only the
+    /// CPU renders
+    pub fn has_brush_masks(&self) -> bool {
+        self.local.iter().any(|local| local.mask.is_brush())
+    }
+
+    /// Copy without geometric adjustments, for pipelines that straighten separately
+    pub fn without_geometry(&self) -> Self {
+        Self {
+            straighten_angle: 0.0,
+            ..self.clone()
+        }
+    }
+
+    /// The look alone, for presets and copying: straightening and local
+    /// adjustments are placed for one photo, so they're left out
+    pub fn look(&self) -> Self {
+        Self {
+            straighten_angle: 0.0,
+            local: Vec::new(),
+            ..self.clone()
+        }
+    }
+
+    /// `look` applied to this image, which keeps its own straightening and
+    /// local adjustments
+    pub fn with_look(&self, look: &Self) -> Self {
+        Self {
+            straighten_angle: self.straighten_angle,
+            local: self.local.clone(),
+            ..look.clone()
+        }
+    }
+
+    /// Copy without the frame, for exports that draw it with a caption
+    pub fn without_frame(&self) -> Self {
+        Self {
+            frame_enabled: false,
+            ..self.clone()
+        }
     }
 
     /// Create a lightweight version of the adjustments for fast previews while dragging sliders.
     /// This disables expensive effects like film grain, halation, S-curve and sharpening.
+    /// Only the CPU path needs it; the GPU previews the full film look.
     pub fn preview(&self) -> Self {
         let mut p = self.clone();
         // Disable film emulation for preview to skip heavy multi-pass operations
@@ -286,10 +396,18 @@ impl ImageAdjustments {
     }
 
     pub fn apply_preset(&mut self, preset: FilmPreset) {
-        // Preserve frame settings across preset changes
+        // Preserve frame, geometry, LUT and local settings across preset changes
         let frame_enabled = self.frame_enabled;
         let frame_color = self.frame_color;
         let frame_thickness = self.frame_thickness;
+        let frame_style = self.frame_style;
+        let frame_aspect = self.frame_aspect;
+        let frame_corner_radius = self.frame_corner_radius;
+        let frame_caption = self.frame_caption;
+        let straighten_angle = self.straighten_angle;
+        let lut_path = self.lut_path.take();
+        let lut_intensity = self.lut_intensity;
+        let local = std::mem::take(&mut self.local);
 
         if preset == FilmPreset::None {
             // Reset all adjustments to default when None is selected
@@ -299,10 +417,72 @@ impl ImageAdjustments {
             *self = preset.characteristics().to_adjustments();
         }
 
-        // Restore frame settings
+        // Restore frame, geometry, LUT and local settings
         self.frame_enabled = frame_enabled;
         self.frame_color = frame_color;
         self.frame_thickness = frame_thickness;
+        self.frame_style = frame_style;
+        self.frame_aspect = frame_aspect;
+        self.frame_corner_radius = frame_corner_radius;
+        self.frame_caption = frame_caption;
+        self.straighten_angle = straighten_angle;
+        self.lut_path = lut_path;
+        self.lut_intensity = lut_intensity;
+        self.local = local;
+    }
+}
+
+/// A film stock made by the user, listed after the built-in presets. Stocks
+/// are shared as JSON files so a look can move between machines.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FilmStock {
+    pub name: String,
+    // Base adjustments, as in the built-in stocks
+    pub saturation: f32,
+    pub temperature: f32,
+    pub film: FilmEmulation,
+}
+
+impl FilmStock {
+    /// Extension offered by the import and export dialogs
+    pub const EXTENSION: &'static str = "json";
+
+    /// Capture the film look of `adjustments` as a stock
+    pub fn from_adjustments(name: &str, adjustments: &ImageAdjustments) -> Self {
+        Self {
+            name: name.to_string(),
+            saturation: adjustments.saturation,
+            temperature: adjustments.temperature,
+            film: FilmEmulation {
+                enabled: true,
+                ..adjustments.film.clone()
+            },
+        }
+    }
+
+    /// Apply like a built-in preset: exposure resets, while frame, geometry
+    /// and LUT settings are kept
+    pub fn apply_to(&self, adjustments: &mut ImageAdjustments) {
+        *adjustments = ImageAdjustments {
+            exposure: 0.0,
+            saturation: self.saturation,
+            temperature: self.temperature,
+            film: FilmEmulation {
+                enabled: true,
+                ..self.film.clone()
+            },
+            ..adjustments.clone()
+        };
+    }
+
+    pub fn load(path: &Path) -> crate::errors::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&text)?)
+    }
+
+    pub fn save(&self, path: &Path) -> crate::errors::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
     }
 }
 
@@ -0,0 +1,257 @@
+use super::ImageAdjustments;
+use crate::exif_data::ExifInfo;
+use ab_glyph::PxScale;
+use image::{imageops, DynamicImage, Rgba, RgbaImage};
+use imageproc::drawing::{draw_text_mut, text_size};
+use serde::{Deserialize, Serialize};
+
+/// Depth of the bottom border of a Polaroid-style frame, relative to the sides
+const POLAROID_BOTTOM: f32 = 3.5;
+
+/// How the border is laid out around a framed photo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum FrameStyle {
+    /// The same border on every side
+    #[default]
+    Even,
+    /// Instant print look with a deep bottom border
+    Polaroid,
+    /// Padded out to a fixed aspect ratio, such as 4:5 for Instagram
+    Aspect,
+}
+
+impl FrameStyle {
+    pub const ALL: [FrameStyle; 3] = [FrameStyle::Even, FrameStyle::Polaroid, FrameStyle::Aspect];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            FrameStyle::Even => "Even",
+            FrameStyle::Polaroid => "Polaroid",
+            FrameStyle::Aspect => "Pad to aspect",
+        }
+    }
+}
+
+/// Where the photo sits on the framed canvas, in pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameLayout {
+    pub width: u32,
+    pub height: u32,
+    pub photo_x: u32,
+    pub photo_y: u32,
+    pub photo_width: u32,
+    pub photo_height: u32,
+}
+
+impl FrameLayout {
+    /// Depth of the border below the photo, where the caption goes
+    pub fn bottom_border(&self) -> u32 {
+        self.height - self.photo_y - self.photo_height
+    }
+}
+
+/// Canvas size and photo position for a `width` x `height` photo
+pub fn frame_layout(width: u32, height: u32, adj: &ImageAdjustments) -> FrameLayout {
+    let border = adj.frame_thickness.max(0.0).round() as u32;
+    let (canvas_width, canvas_height, photo_x, photo_y) = match adj.frame_style {
+        FrameStyle::Even => (width + 2 * border, height + 2 * border, border, border),
+        FrameStyle::Polaroid => {
+            let bottom = (border as f32 * POLAROID_BOTTOM).round() as u32;
+            (width + 2 * border, height + border + bottom, border, border)
+        }
+        FrameStyle::Aspect => {
+            // The border is the minimum; the short side grows to reach the ratio
+            let min_width = width + 2 * border;
+            let min_height = height + 2 * border;
+            let ratio = adj.frame_aspect[0].max(0.01) / adj.frame_aspect[1].max(0.01);
+            let (canvas_width, canvas_height) = if min_width as f32 / min_height as f32 > ratio {
+                (min_width, (min_width as f32 / ratio).round() as u32)
+            } else {
+                ((min_height as f32 * ratio).round() as u32, min_height)
+            };
+            let canvas_width = canvas_width.max(min_width);
+            let canvas_height = canvas_height.max(min_height);
+            (
+                canvas_width,
+                canvas_height,
+                (canvas_width - width) / 2,
+                (canvas_height - height) / 2,
+            )
+        }
+    };
+
+    FrameLayout {
+        width: canvas_width,
+        height: canvas_height,
+        photo_x,
+        photo_y,
+        photo_width: width,
+        photo_height: height,
+    }
+}
+
+/// Where a photo of `photo_aspect` (width over height) sits on an already
+/// framed `width` x `height` canvas, as `[x, y, width, height]`
+pub fn framed_photo_area(
+    width: f32,
+    height: f32,
+    photo_aspect: f32,
+    adj: &ImageAdjustments,
+) -> [f32; 4] {
+    if !adj.frame_enabled {
+        return [0.0, 0.0, width, height];
+    }
+    let border = adj.frame_thickness.max(0.0).round();
+    let bottom = match adj.frame_style {
+        FrameStyle::Polaroid => (border * POLAROID_BOTTOM).round(),
+        FrameStyle::Even | FrameStyle::Aspect => border,
+    };
+    let box_width = (width - 2.0 * border).max(1.0);
+    let box_height = (height - border - bottom).max(1.0);
+    if adj.frame_style != FrameStyle::Aspect || photo_aspect <= 0.0 {
+        return [border, border, box_width, box_height];
+    }
+
+    // Padding only grows one axis, so the photo fills the border box on the other
+    let (photo_width, photo_height) = if box_width / box_height > photo_aspect {
+        (box_height * photo_aspect, box_height)
+    } else {
+        (box_width, box_width / photo_aspect)
+    };
+    [
+        (width - photo_width) / 2.0,
+        (height - photo_height) / 2.0,
+        photo_width,
+        photo_height,
+    ]
+}
+
+/// Put the photo on its frame, with `caption` centred in the bottom border.
+/// Frames are drawn at 8 bits.
+pub fn apply_frame(
+    image: &DynamicImage,
+    adj: &ImageAdjustments,
+    caption: Option<&str>,
+) -> DynamicImage {
+    if !adj.frame_enabled {
+        return image.clone();
+    }
+
+    let layout = frame_layout(image.width(), image.height(), adj);
+    // A zero-width plain border would only cost the image its precision
+    let unchanged = layout.width == image.width() && layout.height == image.height();
+    if unchanged && adj.frame_corner_radius <= 0.0 && caption.is_none() {
+        return image.clone();
+    }
+
+    let color = frame_color(adj);
+    let mut framed = RgbaImage::from_pixel(layout.width, layout.height, color);
+    imageops::overlay(
+        &mut framed,
+        &image.to_rgba8(),
+        layout.photo_x as i64,
+        layout.photo_y as i64,
+    );
+
+    if adj.frame_corner_radius > 0.0 {
+        round_photo_corners(&mut framed, &layout, adj.frame_corner_radius, color);
+    }
+    if let Some(caption) = caption.filter(|c| !c.is_empty()) {
+        draw_frame_caption(&mut framed, &layout, caption, color);
+    }
+
+    DynamicImage::ImageRgba8(framed)
+}
+
+/// Camera and exposure line baked under the photo on export
+pub fn frame_caption(exif: &ExifInfo) -> String {
+    let camera = match (&exif.camera_make, &exif.camera_model) {
+        // Models usually repeat the make ("Canon EOS R5")
+        (Some(make), Some(model)) if model.starts_with(make.as_str()) => model.clone(),
+        (Some(make), Some(model)) => format!("{} {}", make, model),
+        (None, Some(model)) => model.clone(),
+        (Some(make), None) => make.clone(),
+        (None, None) => String::new(),
+    };
+    let exposure = crate::contact_sheet::exif_summary(exif);
+
+    [camera.trim(), exposure.as_str()]
+        .into_iter()
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("  ")
+}
+
+fn frame_color(adj: &ImageAdjustments) -> Rgba<u8> {
+    let [r, g, b] = adj.frame_color.map(|c| (c.clamp(0.0, 1.0) * 255.0) as u8);
+    Rgba([r, g, b, 255])
+}
+
+/// Blend the photo's corners into the frame colour, antialiased over one pixel
+fn round_photo_corners(framed: &mut RgbaImage, layout: &FrameLayout, radius: f32, color: Rgba<u8>) {
+    let radius = radius.min(layout.photo_width.min(layout.photo_height) as f32 / 2.0);
+    let size = radius.ceil() as u32;
+    let right = layout.photo_x + layout.photo_width;
+    let bottom = layout.photo_y + layout.photo_height;
+
+    for dy in 0..size {
+        for dx in 0..size {
+            // Distance past the arc, measured from the centre of the pixel
+            let cx = radius - (dx as f32 + 0.5);
+            let cy = radius - (dy as f32 + 0.5);
+            let outside = (cx * cx + cy * cy).sqrt() - radius;
+            let coverage = (0.5 - outside).clamp(0.0, 1.0);
+            if coverage >= 1.0 {
+                continue;
+            }
+
+            for (x, y) in [
+                (layout.photo_x + dx, layout.photo_y + dy),
+                (right - 1 - dx, layout.photo_y + dy),
+                (layout.photo_x + dx, bottom - 1 - dy),
+                (right - 1 - dx, bottom - 1 - dy),
+            ] {
+                let pixel = framed.get_pixel_mut(x, y);
+                for c in 0..3 {
+                    pixel[c] = (pixel[c] as f32 * coverage + color[c] as f32 * (1.0 - coverage))
+                        .round() as u8;
+                }
+            }
+        }
+    }
+}
+
+fn draw_frame_caption(
+    framed: &mut RgbaImage,
+    layout: &FrameLayout,
+    caption: &str,
+    color: Rgba<u8>,
+) {
+    let border = layout.bottom_border();
+    let font_size = (border as f32 * 0.3).min(layout.width as f32 / 40.0);
+    if font_size < 8.0 {
+        return;
+    }
+    let font = match crate::contact_sheet::caption_font() {
+        Ok(font) => font,
+        Err(e) => {
+            log::warn!("Frame caption skipped: {}", e);
+            return;
+        }
+    };
+
+    // Dark text on light frames, light text on dark ones
+    let luminance = 0.299 * color[0] as f32 + 0.587 * color[1] as f32 + 0.114 * color[2] as f32;
+    let text_color = if luminance > 128.0 {
+        Rgba([60, 60, 60, 255])
+    } else {
+        Rgba([200, 200, 200, 255])
+    };
+
+    let scale = PxScale::from(font_size);
+    let text = crate::contact_sheet::fit_text(caption, layout.photo_width, scale, &font);
+    let (text_width, text_height) = text_size(scale, &font, &text);
+    let x = layout.photo_x + layout.photo_width.saturating_sub(text_width) / 2;
+    let y = layout.photo_y + layout.photo_height + border.saturating_sub(text_height) / 2;
+    draw_text_mut(framed, text_color, x as i32, y as i32, scale, &font, &text);
+}
@@ -0,0 +1,128 @@
+use image::DynamicImage;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+use super::adjustments::{aces_tonemap, linear_to_srgb};
+
+/// How scene-linear HDR values above 1.0 are squeezed into display range
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ToneMap {
+    /// Cut everything brighter than white
+    Clip,
+    /// x / (1 + x): keeps every highlight, at the cost of some contrast
+    Reinhard,
+    /// Filmic curve with a soft shoulder
+    Aces,
+}
+
+impl ToneMap {
+    pub const ALL: [ToneMap; 3] = [ToneMap::Clip, ToneMap::Reinhard, ToneMap::Aces];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ToneMap::Clip => "Clip",
+            ToneMap::Reinhard => "Reinhard",
+            ToneMap::Aces => "ACES filmic",
+        }
+    }
+
+    /// Map one linear channel value into 0-1
+    pub fn apply(&self, x: f32) -> f32 {
+        let x = x.max(0.0);
+        match self {
+            ToneMap::Clip => x.min(1.0),
+            ToneMap::Reinhard => x / (1.0 + x),
+            ToneMap::Aces => aces_tonemap(x),
+        }
+    }
+}
+
+/// How floating point (EXR, Radiance HDR) images are shown
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HdrDisplay {
+    /// Stops applied before tone mapping
+    pub exposure: f32,
+    pub tone_map: ToneMap,
+}
+
+impl Default for HdrDisplay {
+    fn default() -> Self {
+        Self {
+            exposure: 0.0,
+            tone_map: ToneMap::Aces,
+        }
+    }
+}
+
+/// Whether the image holds more than 8 bits per channel
+pub fn is_high_precision(image: &DynamicImage) -> bool {
+    !matches!(
+        image,
+        DynamicImage::ImageLuma8(_)
+            | DynamicImage::ImageLumaA8(_)
+            | DynamicImage::ImageRgb8(_)
+            | DynamicImage::ImageRgba8(_)
+    )
+}
+
+/// Whether the image holds floating point samples. These are scene-linear
+/// and may go far above 1.0, unlike integer images which are already encoded
+/// for display.
+pub fn is_float(image: &DynamicImage) -> bool {
+    matches!(
+        image,
+        DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_)
+    )
+}
+
+/// The image as display-referred values: float images are exposed, tone
+/// mapped and sRGB encoded into an `Rgba32F` image in 0-1, so nothing is lost
+/// before the final conversion to the screen; everything else is returned as is.
+pub fn display_referred<'a>(image: &'a DynamicImage, hdr: &HdrDisplay) -> Cow<'a, DynamicImage> {
+    if !is_float(image) {
+        return Cow::Borrowed(image);
+    }
+
+    let gain = 2.0_f32.powf(hdr.exposure);
+    let tone_map = hdr.tone_map;
+    let mut rgba = image.to_rgba32f();
+    rgba.par_chunks_mut(4).for_each(|pixel| {
+        for value in &mut pixel[..3] {
+            *value = linear_to_srgb(tone_map.apply(*value * gain));
+        }
+    });
+    Cow::Owned(DynamicImage::ImageRgba32F(rgba))
+}
+
+/// IEEE half precision bits for `value`, rounded to nearest
+pub fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    if exponent == 0xff {
+        let nan = if mantissa != 0 { 0x200 } else { 0 };
+        return sign | 0x7c00 | nan;
+    }
+    let exponent = exponent - 127 + 15;
+    if exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+    if exponent <= 0 {
+        // Too small for a normal half: shift the implicit bit into a subnormal
+        if exponent < -10 {
+            return sign;
+        }
+        let mantissa = mantissa | 0x80_0000;
+        let shift = (14 - exponent) as u32;
+        let round = (mantissa >> (shift - 1)) & 1;
+        return sign | ((mantissa >> shift) + round) as u16;
+    }
+    // A carry out of the mantissa correctly bumps the exponent
+    let half = ((exponent as u32) << 10) | (mantissa >> 13);
+    let round = (mantissa >> 12) & 1;
+    sign | (half + round) as u16
+}
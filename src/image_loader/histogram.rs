@@ -1,4 +1,15 @@
 use image::DynamicImage;
+use serde::{Deserialize, Serialize};
+
+/// How the histogram panel plots the counts
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistogramSettings {
+    /// Plot a single luminance curve instead of the RGB channels
+    pub luminance_only: bool,
+    /// Logarithmic heights, so sparse tones aren't flattened by a spike
+    pub log_scale: bool,
+}
 
 // Calculate histogram
 pub fn calculate_histogram(image: &DynamicImage) -> Vec<Vec<u32>> {
@@ -13,3 +24,68 @@ pub fn calculate_histogram(image: &DynamicImage) -> Vec<Vec<u32>> {
 
     histogram
 }
+
+/// Luminance counts with the same weights and binning as the GPU histogram,
+/// which returns them as its fourth channel
+pub fn calculate_luminance_histogram(image: &DynamicImage) -> Vec<u32> {
+    let rgb = image.to_rgb8();
+    let mut histogram = vec![0u32; 256];
+
+    for pixel in rgb.pixels() {
+        let luminance = 0.299 * pixel[0] as f32 + 0.587 * pixel[1] as f32 + 0.114 * pixel[2] as f32;
+        histogram[(luminance as usize).min(255)] += 1;
+    }
+
+    histogram
+}
+
+/// Height of a histogram bar relative to the tallest one, from 0 to 1. The
+/// linear view takes a square root so small counts still register.
+pub fn histogram_bar_height(count: u32, max_count: u32, log_scale: bool) -> f32 {
+    if count == 0 || max_count == 0 {
+        return 0.0;
+    }
+    if log_scale {
+        (1.0 + count as f32).ln() / (1.0 + max_count as f32).ln()
+    } else {
+        (count as f32 / max_count as f32).sqrt()
+    }
+}
+
+/// Luminance at or above which a pixel counts as blown out
+const BLOWN_LUMINANCE: usize = 250;
+/// Luminance at or below which a pixel counts as crushed to black
+const BLACK_LUMINANCE: usize = 5;
+/// Share of blown out pixels that marks an image overexposed
+const OVEREXPOSED_SHARE: f32 = 0.05;
+/// Share of black pixels that marks an image underexposed; higher than for
+/// highlights since dark backgrounds are often intended
+const UNDEREXPOSED_SHARE: f32 = 0.25;
+
+/// Significant clipping found in an image's histogram
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExposureWarning {
+    Overexposed,
+    Underexposed,
+}
+
+/// Whether enough of the image is clipped to call it over- or underexposed.
+/// When both ends clip, the one further past its threshold wins.
+pub fn exposure_warning(image: &DynamicImage) -> Option<ExposureWarning> {
+    let histogram = calculate_luminance_histogram(image);
+    let total = histogram.iter().sum::<u32>();
+    if total == 0 {
+        return None;
+    }
+
+    let share = |bins: &[u32]| bins.iter().sum::<u32>() as f32 / total as f32;
+    let over = share(&histogram[BLOWN_LUMINANCE..]) / OVEREXPOSED_SHARE;
+    let under = share(&histogram[..=BLACK_LUMINANCE]) / UNDEREXPOSED_SHARE;
+    if over.max(under) < 1.0 {
+        None
+    } else if over >= under {
+        Some(ExposureWarning::Overexposed)
+    } else {
+        Some(ExposureWarning::Underexposed)
+    }
+}
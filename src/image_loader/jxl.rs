@@ -0,0 +1,58 @@
+use crate::errors::{Result, ViewerError};
+use image::DynamicImage;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+pub fn is_jxl_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.eq_ignore_ascii_case("jxl"))
+        .unwrap_or(false)
+}
+
+/// Decode a JPEG XL file using the pure-Rust jxl-oxide decoder
+pub fn load_jxl_image(path: &Path) -> Result<DynamicImage> {
+    let file = File::open(path)?;
+    let decoder = jxl_oxide::integration::JxlDecoder::new(BufReader::new(file)).map_err(|e| {
+        ViewerError::DecodingError {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        }
+    })?;
+
+    DynamicImage::from_decoder(decoder).map_err(|e| ViewerError::DecodingError {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    })
+}
+
+/// Encode an image as lossless JPEG XL
+pub fn save_jxl(image: &DynamicImage, path: &Path) -> Result<()> {
+    let data = encode_jxl(image).map_err(|message| ViewerError::ExportError {
+        path: path.to_path_buf(),
+        message,
+    })?;
+    std::fs::write(path, data)?;
+    Ok(())
+}
+
+/// Lossless JPEG XL file contents for an image
+pub fn encode_jxl(image: &DynamicImage) -> std::result::Result<Vec<u8>, String> {
+    use zune_core::bit_depth::BitDepth;
+    use zune_core::colorspace::ColorSpace;
+    use zune_core::options::EncoderOptions;
+
+    let rgba = image.to_rgba8();
+    let (width, height) = rgba.dimensions();
+    let options = EncoderOptions::new(
+        width as usize,
+        height as usize,
+        ColorSpace::RGBA,
+        BitDepth::Eight,
+    );
+
+    zune_jpegxl::JxlSimpleEncoder::new(rgba.as_raw(), options)
+        .encode()
+        .map_err(|e| format!("{:?}", e))
+}
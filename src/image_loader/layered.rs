@@ -0,0 +1,486 @@
+use crate::errors::{Result, ViewerError};
+use image::{DynamicImage, ImageBuffer, Rgba};
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Layered document formats shown through the flattened image they store.
+/// GIMP's XCF keeps no merged copy of its layers, so it isn't among them.
+pub const LAYERED_EXTENSIONS: &[&str] = &["psd", "psb", "kra", "ora"];
+
+pub fn is_layered_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| LAYERED_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+fn is_zip_document(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("kra") || e.eq_ignore_ascii_case("ora"))
+}
+
+/// What the sidebar shows about a layered document
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentInfo {
+    pub layers: usize,
+    pub color_mode: String,
+}
+
+/// Layer count and color mode, read without decoding any pixels
+pub fn document_info(path: &Path) -> Option<DocumentInfo> {
+    if !is_layered_file(path) {
+        return None;
+    }
+    if is_zip_document(path) {
+        return zip_document_info(path);
+    }
+    let mut reader = BufReader::new(File::open(path).ok()?);
+    let header = PsdHeader::read(&mut reader).ok()?;
+    let sections = PsdSections::read(&mut reader, &header).ok()?;
+    Some(DocumentInfo {
+        layers: sections.layer_count,
+        color_mode: header.color_mode_name(),
+    })
+}
+
+/// Decode the flattened composite of a PSD, PSB, Krita or OpenRaster file
+pub fn load_layered_image(path: &Path) -> Result<DynamicImage> {
+    let result = if is_zip_document(path) {
+        load_zip_document(path)
+    } else {
+        load_psd(path)
+    };
+    result.map_err(|e| ViewerError::DecodingError {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    })
+}
+
+// ============ KRITA / OPENRASTER ============
+// Both are zip archives with the flattened image stored as mergedimage.png
+
+fn read_zip_entry(archive: &mut zip::ZipArchive<File>, name: &str) -> io::Result<Vec<u8>> {
+    let mut entry = archive
+        .by_name(name)
+        .map_err(|e| io::Error::new(io::ErrorKind::NotFound, e))?;
+    let mut data = Vec::with_capacity(entry.size() as usize);
+    entry.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+fn open_zip(path: &Path) -> io::Result<zip::ZipArchive<File>> {
+    zip::ZipArchive::new(File::open(path)?)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn load_zip_document(path: &Path) -> io::Result<DynamicImage> {
+    let mut archive = open_zip(path)?;
+    let data = read_zip_entry(&mut archive, "mergedimage.png")?;
+    image::load_from_memory(&data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn zip_document_info(path: &Path) -> Option<DocumentInfo> {
+    let mut archive = open_zip(path).ok()?;
+    let is_kra = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("kra"));
+    let manifest = if is_kra { "maindoc.xml" } else { "stack.xml" };
+    let xml = String::from_utf8(read_zip_entry(&mut archive, manifest).ok()?).ok()?;
+
+    // Krita names paint, group and filter layers all <layer>; so does OpenRaster
+    let layers = xml.matches("<layer ").count();
+    let color_mode = if is_kra {
+        attribute(&xml, "colorspacename")
+            .unwrap_or("RGBA")
+            .to_string()
+    } else {
+        "RGBA".to_string()
+    };
+    Some(DocumentInfo { layers, color_mode })
+}
+
+/// The first value of `name="..."` in `xml`
+fn attribute<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("{}=\"", name))? + name.len() + 2;
+    let len = xml[start..].find('"')?;
+    Some(&xml[start..start + len])
+}
+
+// ============ PHOTOSHOP ============
+// The image data section at the end of a PSD holds a flattened copy of the
+// document, planar and usually PackBits compressed
+
+const MODE_BITMAP: u16 = 0;
+const MODE_GRAYSCALE: u16 = 1;
+const MODE_INDEXED: u16 = 2;
+const MODE_RGB: u16 = 3;
+const MODE_CMYK: u16 = 4;
+const MODE_MULTICHANNEL: u16 = 7;
+const MODE_DUOTONE: u16 = 8;
+const MODE_LAB: u16 = 9;
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+fn read_u16(reader: &mut impl Read) -> io::Result<u16> {
+    let mut buf = [0u8; 2];
+    reader.read_exact(&mut buf)?;
+    Ok(u16::from_be_bytes(buf))
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_be_bytes(buf))
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_be_bytes(buf))
+}
+
+#[derive(Debug)]
+struct PsdHeader {
+    /// 2 for the large document format (PSB)
+    version: u16,
+    channels: u16,
+    height: u32,
+    width: u32,
+    depth: u16,
+    color_mode: u16,
+}
+
+impl PsdHeader {
+    fn read(reader: &mut impl Read) -> io::Result<Self> {
+        let mut signature = [0u8; 4];
+        reader.read_exact(&mut signature)?;
+        if &signature != b"8BPS" {
+            return Err(invalid("not a Photoshop file"));
+        }
+        let version = read_u16(reader)?;
+        if version != 1 && version != 2 {
+            return Err(invalid("unknown Photoshop file version"));
+        }
+        let mut reserved = [0u8; 6];
+        reader.read_exact(&mut reserved)?;
+        let header = Self {
+            version,
+            channels: read_u16(reader)?,
+            height: read_u32(reader)?,
+            width: read_u32(reader)?,
+            depth: read_u16(reader)?,
+            color_mode: read_u16(reader)?,
+        };
+        if header.width == 0 || header.height == 0 || header.channels == 0 {
+            return Err(invalid("empty image"));
+        }
+        if !matches!(header.depth, 1 | 8 | 16 | 32) {
+            return Err(invalid("unsupported bit depth"));
+        }
+        Ok(header)
+    }
+
+    fn is_psb(&self) -> bool {
+        self.version == 2
+    }
+
+    /// Section lengths that grew to 64 bits in PSB
+    fn read_long_length(&self, reader: &mut impl Read) -> io::Result<u64> {
+        if self.is_psb() {
+            read_u64(reader)
+        } else {
+            read_u32(reader).map(u64::from)
+        }
+    }
+
+    fn color_mode_name(&self) -> String {
+        let mode = match self.color_mode {
+            MODE_BITMAP => "Bitmap",
+            MODE_GRAYSCALE => "Grayscale",
+            MODE_INDEXED => "Indexed",
+            MODE_RGB => "RGB",
+            MODE_CMYK => "CMYK",
+            MODE_MULTICHANNEL => "Multichannel",
+            MODE_DUOTONE => "Duotone",
+            MODE_LAB => "Lab",
+            _ => "Unknown",
+        };
+        format!("{} {}-bit", mode, self.depth)
+    }
+
+    fn row_bytes(&self) -> usize {
+        (self.width as usize * self.depth as usize).div_ceil(8)
+    }
+}
+
+/// Tagged blocks whose length is 64 bits in a PSB
+const PSB_LONG_KEYS: [&[u8; 4]; 10] = [
+    b"LMsk", b"Mt16", b"Mt32", b"Mtrn", b"Alph", b"FMsk", b"lnk2", b"FEid", b"FXid", b"PxSD",
+];
+
+/// The parts of a PSD between the header and the composite
+struct PsdSections {
+    palette: Vec<u8>,
+    layer_count: usize,
+    /// The first extra channel of the composite is its transparency, which
+    /// Photoshop flags by storing the layer count negated
+    has_transparency: bool,
+}
+
+impl PsdSections {
+    /// Read up to the image data section, leaving `reader` at its start
+    fn read<R: Read + Seek>(reader: &mut R, header: &PsdHeader) -> io::Result<Self> {
+        let palette_len = read_u32(reader)?;
+        let mut palette = vec![0u8; palette_len.min(768) as usize];
+        reader.read_exact(&mut palette)?;
+        reader.seek(SeekFrom::Current(palette_len as i64 - palette.len() as i64))?;
+
+        let resources_len = read_u32(reader)?;
+        reader.seek(SeekFrom::Current(resources_len as i64))?;
+
+        let layers_len = header.read_long_length(reader)?;
+        let layers_start = reader.stream_position()?;
+        let layers_end = layers_start + layers_len;
+        let mut raw_count = 0i16;
+        if layers_len > 0 {
+            let info_len = header.read_long_length(reader)?;
+            if info_len > 0 {
+                raw_count = read_u16(reader)? as i16;
+            } else {
+                // 16 and 32-bit documents keep their layers in a tagged block instead
+                let mask_len = read_u32(reader)?;
+                reader.seek(SeekFrom::Current(mask_len as i64))?;
+                raw_count = Self::tagged_layer_count(reader, header, layers_end)?.unwrap_or(0);
+            }
+        }
+        reader.seek(SeekFrom::Start(layers_end))?;
+
+        Ok(Self {
+            palette,
+            layer_count: raw_count.unsigned_abs() as usize,
+            has_transparency: raw_count < 0,
+        })
+    }
+
+    /// Layer count from the Lr16/Lr32/Layr block among the additional layer info
+    fn tagged_layer_count<R: Read + Seek>(
+        reader: &mut R,
+        header: &PsdHeader,
+        end: u64,
+    ) -> io::Result<Option<i16>> {
+        while reader.stream_position()? + 12 <= end {
+            let mut signature = [0u8; 4];
+            let mut key = [0u8; 4];
+            reader.read_exact(&mut signature)?;
+            reader.read_exact(&mut key)?;
+            if &signature != b"8BIM" && &signature != b"8B64" {
+                return Ok(None);
+            }
+            let is_layers = matches!(&key, b"Lr16" | b"Lr32" | b"Layr");
+            let len = if is_layers || PSB_LONG_KEYS.contains(&&key) {
+                header.read_long_length(reader)?
+            } else {
+                read_u32(reader)? as u64
+            };
+            if is_layers {
+                return Ok(Some(read_u16(reader)? as i16));
+            }
+            // Blocks are padded to four bytes
+            reader.seek(SeekFrom::Current(len.div_ceil(4) as i64 * 4))?;
+        }
+        Ok(None)
+    }
+}
+
+/// Expand one PackBits compressed row into `out`
+fn unpack_bits(data: &[u8], out: &mut [u8]) -> io::Result<()> {
+    let (mut i, mut o) = (0, 0);
+    while o < out.len() && i < data.len() {
+        let n = data[i] as i8;
+        i += 1;
+        if n >= 0 {
+            let count = n as usize + 1;
+            let src = data
+                .get(i..i + count)
+                .ok_or_else(|| invalid("truncated run"))?;
+            let dst = out
+                .get_mut(o..o + count)
+                .ok_or_else(|| invalid("run past end of row"))?;
+            dst.copy_from_slice(src);
+            i += count;
+            o += count;
+        } else if n != -128 {
+            let count = 1 - n as isize;
+            let value = *data.get(i).ok_or_else(|| invalid("truncated run"))?;
+            let dst = out
+                .get_mut(o..o + count as usize)
+                .ok_or_else(|| invalid("run past end of row"))?;
+            dst.fill(value);
+            i += 1;
+            o += count as usize;
+        }
+    }
+    Ok(())
+}
+
+/// Read the planes of the composite, one `Vec` per channel
+fn read_planes(
+    reader: &mut impl Read,
+    header: &PsdHeader,
+    wanted: usize,
+) -> io::Result<Vec<Vec<u8>>> {
+    let rows = header.height as usize;
+    let row_bytes = header.row_bytes();
+    let channels = header.channels as usize;
+    let compression = read_u16(reader)?;
+
+    let mut planes = Vec::with_capacity(wanted);
+    match compression {
+        0 => {
+            for _ in 0..wanted {
+                let mut plane = vec![0u8; rows * row_bytes];
+                reader.read_exact(&mut plane)?;
+                planes.push(plane);
+            }
+        }
+        1 => {
+            // Byte counts for every row of every channel come first
+            let mut counts = Vec::with_capacity(rows * channels);
+            for _ in 0..rows * channels {
+                counts.push(if header.is_psb() {
+                    read_u32(reader)? as usize
+                } else {
+                    read_u16(reader)? as usize
+                });
+            }
+            let mut packed = Vec::new();
+            for channel in 0..wanted {
+                let mut plane = vec![0u8; rows * row_bytes];
+                for row in 0..rows {
+                    packed.resize(counts[channel * rows + row], 0);
+                    reader.read_exact(&mut packed)?;
+                    unpack_bits(&packed, &mut plane[row * row_bytes..(row + 1) * row_bytes])?;
+                }
+                planes.push(plane);
+            }
+        }
+        _ => return Err(invalid("compressed composite data isn't supported")),
+    }
+    Ok(planes)
+}
+
+/// Channel `value` at pixel `i` of `plane`, scaled to 0-1 (float planes are
+/// returned as stored)
+fn sample(plane: &[u8], depth: u16, i: usize) -> f32 {
+    match depth {
+        1 => {
+            // Bitmap mode stores 1 for black
+            let bit = (plane[i / 8] >> (7 - i % 8)) & 1;
+            (1 - bit) as f32
+        }
+        8 => plane[i] as f32 / 255.0,
+        16 => u16::from_be_bytes([plane[i * 2], plane[i * 2 + 1]]) as f32 / 65535.0,
+        _ => f32::from_be_bytes([
+            plane[i * 4],
+            plane[i * 4 + 1],
+            plane[i * 4 + 2],
+            plane[i * 4 + 3],
+        ]),
+    }
+}
+
+/// CIE L*a*b* (D50, as Photoshop uses) to sRGB
+fn lab_to_srgb(l: f32, a: f32, b: f32) -> [f32; 3] {
+    let fy = (l + 16.0) / 116.0;
+    let fx = fy + a / 500.0;
+    let fz = fy - b / 200.0;
+    let inverse = |t: f32| {
+        if t > 6.0 / 29.0 {
+            t * t * t
+        } else {
+            3.0 * (6.0f32 / 29.0).powi(2) * (t - 4.0 / 29.0)
+        }
+    };
+    let (x, y, z) = (0.9642 * inverse(fx), inverse(fy), 0.8249 * inverse(fz));
+    let linear = [
+        3.1338561 * x - 1.6168667 * y - 0.4906146 * z,
+        -0.9787684 * x + 1.9161415 * y + 0.033454 * z,
+        0.0719453 * x - 0.2289914 * y + 1.4052427 * z,
+    ];
+    linear.map(|v| super::adjustments::linear_to_srgb(v.clamp(0.0, 1.0)))
+}
+
+fn load_psd(path: &Path) -> io::Result<DynamicImage> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let header = PsdHeader::read(&mut reader)?;
+    let sections = PsdSections::read(&mut reader, &header)?;
+
+    let colors = match header.color_mode {
+        MODE_RGB | MODE_LAB => 3,
+        MODE_CMYK => 4,
+        MODE_BITMAP | MODE_GRAYSCALE | MODE_INDEXED | MODE_DUOTONE | MODE_MULTICHANNEL => 1,
+        _ => return Err(invalid("unsupported color mode")),
+    };
+    let channels = header.channels as usize;
+    if channels < colors {
+        return Err(invalid("too few channels for the color mode"));
+    }
+    let has_alpha = sections.has_transparency && channels > colors;
+    let planes = read_planes(&mut reader, &header, colors + has_alpha as usize)?;
+
+    let depth = header.depth;
+    let palette = &sections.palette;
+    let pixel = |i: usize| -> [f32; 4] {
+        let s = |c: usize| sample(&planes[c], depth, i);
+        let alpha = if has_alpha { s(colors) } else { 1.0 };
+        let [r, g, b] = match header.color_mode {
+            MODE_RGB => [s(0), s(1), s(2)],
+            // Ink amounts are stored inverted, 1.0 meaning none
+            MODE_CMYK => {
+                let k = s(3);
+                [s(0) * k, s(1) * k, s(2) * k]
+            }
+            MODE_LAB => lab_to_srgb(s(0) * 100.0, s(1) * 255.0 - 128.0, s(2) * 255.0 - 128.0),
+            MODE_INDEXED => {
+                let index = planes[0][i] as usize;
+                let entry =
+                    |c: usize| palette.get(c * 256 + index).copied().unwrap_or(0) as f32 / 255.0;
+                [entry(0), entry(1), entry(2)]
+            }
+            _ => {
+                let v = s(0);
+                [v, v, v]
+            }
+        };
+        [r, g, b, alpha]
+    };
+
+    let (width, height) = (header.width, header.height);
+    let index = |x: u32, y: u32| y as usize * width as usize + x as usize;
+    // Keep the document's precision: 16-bit stays 16-bit and 32-bit stays
+    // float, which Photoshop stores linear
+    let image = match depth {
+        16 => DynamicImage::ImageRgba16(ImageBuffer::from_fn(width, height, |x, y| {
+            Rgba(pixel(index(x, y)).map(|v| (v * 65535.0).round().clamp(0.0, 65535.0) as u16))
+        })),
+        32 => DynamicImage::ImageRgba32F(ImageBuffer::from_fn(width, height, |x, y| {
+            Rgba(pixel(index(x, y)))
+        })),
+        // Bitmap rows are padded to whole bytes
+        1 => {
+            let row_bits = header.row_bytes() * 8;
+            DynamicImage::ImageRgba8(ImageBuffer::from_fn(width, height, |x, y| {
+                let i = y as usize * row_bits + x as usize;
+                Rgba(pixel(i).map(|v| (v * 255.0).round() as u8))
+            }))
+        }
+        _ => DynamicImage::ImageRgba8(ImageBuffer::from_fn(width, height, |x, y| {
+            Rgba(pixel(index(x, y)).map(|v| (v * 255.0).round().clamp(0.0, 255.0) as u8))
+        })),
+    };
+    Ok(image)
+}
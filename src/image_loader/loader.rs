@@ -15,7 +15,14 @@ lazy_static::lazy_static! {
     };
 }
 
+/// Largest file read into memory; bigger ones could exhaust it
+pub const MAX_FILE_SIZE: u64 = 500 * 1024 * 1024;
+
 pub fn load_image(path: &Path) -> Result<DynamicImage> {
+    // Images inside a zip or cbz have no file of their own
+    if super::archive::is_archive_entry(path) {
+        return super::archive::load_archive_image(path);
+    }
     if !path.exists() {
         return Err(ViewerError::FileNotFound {
             path: path.to_path_buf(),
@@ -25,8 +32,7 @@ pub fn load_image(path: &Path) -> Result<DynamicImage> {
     // Check file size to prevent loading extremely large images that could cause crashes
     if let Ok(metadata) = std::fs::metadata(path) {
         let file_size = metadata.len();
-        // Limit to 500MB to prevent memory issues
-        if file_size > 500 * 1024 * 1024 {
+        if file_size > MAX_FILE_SIZE {
             return Err(ViewerError::ImageLoadError {
                 path: path.to_path_buf(),
                 message: format!(
@@ -38,10 +44,11 @@ pub fn load_image(path: &Path) -> Result<DynamicImage> {
     }
 
     crate::profiler::with_profiler(|p| p.start_timer("image_load"));
+    // RAW decoding already turns the image upright
     let result = if super::extensions::is_raw_file(path) {
         load_raw_image(path)
     } else {
-        load_standard_image(path)
+        load_standard_image(path).map(|img| orient_from_exif(path, img))
     };
     crate::profiler::with_profiler(|p| p.end_timer("image_load"));
 
@@ -60,7 +67,54 @@ pub fn load_image(path: &Path) -> Result<DynamicImage> {
     result
 }
 
+/// The EXIF orientation tag (1-8), if the file has one
+pub fn read_orientation(path: &Path) -> Option<u32> {
+    let file = std::fs::File::open(path).ok()?;
+    let exif = exif::Reader::new()
+        .read_from_container(&mut std::io::BufReader::new(file))
+        .ok()?;
+    exif.get_field(exif::Tag::Orientation, exif::In::PRIMARY)?
+        .value
+        .get_uint(0)
+}
+
+/// Rotate and flip the pixels the way an EXIF orientation tag says the image
+/// should be shown, so portrait shots aren't displayed or exported sideways
+pub fn apply_orientation(image: DynamicImage, orientation: u32) -> DynamicImage {
+    match orientation {
+        2 => image.fliph(),
+        3 => image.rotate180(),
+        4 => image.flipv(),
+        5 => image.rotate90().fliph(),
+        6 => image.rotate90(),
+        7 => image.rotate270().fliph(),
+        8 => image.rotate270(),
+        _ => image,
+    }
+}
+
+/// Apply the orientation tag of the file the image was decoded from
+pub fn orient_from_exif(path: &Path, image: DynamicImage) -> DynamicImage {
+    match read_orientation(path) {
+        Some(orientation) => apply_orientation(image, orientation),
+        None => image,
+    }
+}
+
 fn load_standard_image(path: &Path) -> Result<DynamicImage> {
+    // The image crate has no JPEG XL decoder
+    if super::jxl::is_jxl_file(path) {
+        return super::jxl::load_jxl_image(path);
+    }
+    // Layered documents are shown through the flattened copy they store
+    if super::layered::is_layered_file(path) {
+        return super::layered::load_layered_image(path);
+    }
+    // Videos are shown as their first frame until played
+    if super::video::is_video_file(path) {
+        return super::video::load_video_frame(path);
+    }
+
     // For large files (>50MB), use memory mapping to avoid loading entire file into RAM
     if let Ok(metadata) = std::fs::metadata(path) {
         if metadata.len() > 50 * 1024 * 1024 {
@@ -120,6 +174,16 @@ pub fn load_raw_image(path: &Path) -> Result<DynamicImage> {
                 }
             };
 
+            // Bayer sensors are developed with the RAW options from settings;
+            // X-Trans and linear files go through imagepipe
+            if let Some(mosaic) = super::raw_develop::Mosaic::from_raw(&raw) {
+                log::debug!("Developing {:?}", path);
+                let options = super::raw_develop::raw_decode_options();
+                let image = super::raw_develop::develop(mosaic, &options);
+                log::info!("Successfully loaded RAW image: {:?}", path);
+                return Ok(orient_from_exif(path, image));
+            }
+
             log::debug!("Creating pipeline for {:?}", path);
             let source = imagepipe::ImageSource::Raw(raw);
             let mut pipeline = imagepipe::Pipeline::new_from_source(source)
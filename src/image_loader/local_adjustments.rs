@@ -0,0 +1,310 @@
+use serde::{Deserialize, Serialize};
+
+/// Most local adjustments per image; the GPU uniforms have room for this many
+pub const MAX_LOCAL_ADJUSTMENTS: usize = 8;
+
+/// Gap between brush dabs, as a fraction of their radius
+const BRUSH_SPACING: f32 = 0.25;
+/// Most dabs filled in between two points of a stroke
+const MAX_STROKE_STEPS: usize = 256;
+/// Longest side of the grid brush masks are drawn into before rendering
+const BRUSH_RASTER_SIZE: u32 = 1024;
+
+/// One stamp of the brush, in coordinates relative to the image
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BrushDab {
+    pub center: [f32; 2],
+    /// Radius as a fraction of the image height
+    pub radius: f32,
+    pub opacity: f32,
+}
+
+impl BrushDab {
+    /// Strength at image-relative `x`, `y`, softening over the outer half
+    /// of the radius
+    fn weight(&self, x: f32, y: f32, aspect: f32) -> f32 {
+        let radius = self.radius.max(0.0001);
+        let dx = (x - self.center[0]) * aspect;
+        let dy = y - self.center[1];
+        if dx.abs() > radius || dy.abs() > radius {
+            return 0.0;
+        }
+        let distance = (dx * dx + dy * dy).sqrt() / radius;
+        self.opacity * (1.0 - smoothstep(0.5, 1.0, distance))
+    }
+}
+
+/// Shape of a local adjustment, in coordinates relative to the image
+/// (0 to 1 across its width and height)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum LocalMask {
+    /// Full strength inside the ellipse, fading out over the outer `feather`
+    /// fraction of its radius
+    Radial {
+        center: [f32; 2],
+        radius: [f32; 2],
+        feather: f32,
+    },
+    /// Full strength behind `start`, fading to nothing at `end`
+    Linear { start: [f32; 2], end: [f32; 2] },
+    /// Painted by hand; each point takes its strongest dab. Only rendered on
+    /// the CPU.
+    Brush { dabs: Vec<BrushDab> },
+}
+
+impl Default for LocalMask {
+    fn default() -> Self {
+        LocalMask::Radial {
+            center: [0.5, 0.5],
+            radius: [0.25, 0.25],
+            feather: 0.5,
+        }
+    }
+}
+
+/// Which mask the mask tool draws
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocalMaskKind {
+    Radial,
+    Linear,
+    Brush,
+}
+
+impl LocalMask {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LocalMask::Radial { .. } => "Radial",
+            LocalMask::Linear { .. } => "Linear",
+            LocalMask::Brush { .. } => "Brush",
+        }
+    }
+
+    pub fn is_brush(&self) -> bool {
+        matches!(self, LocalMask::Brush { .. })
+    }
+
+    /// The mask for a drag on the image from `start` to `end`. A radial
+    /// gradient is a circle centred on `start`; a linear one fades along
+    /// the drag. A brush mask starts empty and is painted dab by dab.
+    pub fn from_drag(kind: LocalMaskKind, start: [f32; 2], end: [f32; 2], aspect: f32) -> Self {
+        match kind {
+            LocalMaskKind::Radial => {
+                let dx = (end[0] - start[0]) * aspect;
+                let dy = end[1] - start[1];
+                let radius = (dx * dx + dy * dy).sqrt().max(0.01);
+                LocalMask::Radial {
+                    center: start,
+                    radius: [radius / aspect.max(0.0001), radius],
+                    feather: 0.5,
+                }
+            }
+            LocalMaskKind::Linear => LocalMask::Linear { start, end },
+            LocalMaskKind::Brush => LocalMask::Brush { dabs: Vec::new() },
+        }
+    }
+
+    /// Paint `dab` onto a brush mask. With `connect` the stroke is filled in
+    /// from the last dab, so quick strokes have no gaps. Returns whether
+    /// anything was painted; other masks are left alone.
+    pub fn paint(&mut self, dab: BrushDab, connect: bool, aspect: f32) -> bool {
+        let LocalMask::Brush { dabs } = self else {
+            return false;
+        };
+        let Some(last) = dabs.last().copied().filter(|_| connect) else {
+            dabs.push(dab);
+            return true;
+        };
+        let dx = (dab.center[0] - last.center[0]) * aspect;
+        let dy = dab.center[1] - last.center[1];
+        let spacing = last.radius.min(dab.radius).max(0.001) * BRUSH_SPACING;
+        let steps = ((dx * dx + dy * dy).sqrt() / spacing) as usize;
+        let steps = steps.min(MAX_STROKE_STEPS);
+        let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+        for step in 1..=steps {
+            let t = step as f32 / steps as f32;
+            dabs.push(BrushDab {
+                center: [
+                    lerp(last.center[0], dab.center[0], t),
+                    lerp(last.center[1], dab.center[1], t),
+                ],
+                radius: lerp(last.radius, dab.radius, t),
+                opacity: lerp(last.opacity, dab.opacity, t),
+            });
+        }
+        steps > 0
+    }
+}
+
+/// Exposure, saturation and white balance changes confined to a gradient mask.
+/// They add to the global values of the same adjustments.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LocalAdjustment {
+    pub mask: LocalMask,
+    /// Apply outside the mask instead of inside
+    pub invert: bool,
+    pub exposure: f32,    // -3.0 to +3.0 (stops)
+    pub saturation: f32,  // -1.0 to +1.0, added to the saturation multiplier
+    pub temperature: f32, // -1.0 to +1.0
+    pub tint: f32,        // -1.0 to +1.0
+}
+
+impl Default for LocalAdjustment {
+    fn default() -> Self {
+        Self {
+            mask: LocalMask::default(),
+            invert: false,
+            exposure: 0.0,
+            saturation: 0.0,
+            temperature: 0.0,
+            tint: 0.0,
+        }
+    }
+}
+
+impl LocalAdjustment {
+    /// Mask strength from 0 to 1 at image-relative `x`, `y`. `aspect` is the
+    /// image's width over its height, so linear gradients stay perpendicular
+    /// to the line they're drawn along.
+    pub fn weight(&self, x: f32, y: f32, aspect: f32) -> f32 {
+        let weight = match &self.mask {
+            LocalMask::Radial {
+                center,
+                radius,
+                feather,
+            } => {
+                let dx = (x - center[0]) / radius[0].max(0.0001);
+                let dy = (y - center[1]) / radius[1].max(0.0001);
+                let distance = (dx * dx + dy * dy).sqrt();
+                1.0 - smoothstep(1.0 - feather.max(0.001), 1.0, distance)
+            }
+            LocalMask::Linear { start, end } => {
+                let dir = [(end[0] - start[0]) * aspect, end[1] - start[1]];
+                let to_point = [(x - start[0]) * aspect, y - start[1]];
+                let length_sq = (dir[0] * dir[0] + dir[1] * dir[1]).max(0.000001);
+                let t = (to_point[0] * dir[0] + to_point[1] * dir[1]) / length_sq;
+                1.0 - smoothstep(0.0, 1.0, t)
+            }
+            LocalMask::Brush { dabs } => dabs
+                .iter()
+                .map(|dab| dab.weight(x, y, aspect))
+                .fold(0.0, f32::max),
+        };
+        if self.invert {
+            1.0 - weight
+        } else {
+            weight
+        }
+    }
+}
+
+/// A brush mask drawn once into a grid of weights, so rendering samples
+/// the grid instead of testing every dab at every pixel
+pub struct BrushRaster {
+    width: usize,
+    height: usize,
+    weights: Vec<f32>,
+}
+
+impl BrushRaster {
+    /// Draw `dabs` into a grid of `width` by `height` cells, each dab only
+    /// over the cells it covers
+    pub fn new(dabs: &[BrushDab], width: u32, height: u32) -> Self {
+        let (width, height) = (width.max(1) as usize, height.max(1) as usize);
+        let aspect = width as f32 / height as f32;
+        let mut weights = vec![0.0f32; width * height];
+        // Cells whose centres lie between `low` and `high` along an axis
+        let cells = |low: f32, high: f32, count: usize| {
+            let first = (low * count as f32 - 0.5).ceil().max(0.0) as usize;
+            let last = (high * count as f32 - 0.5).floor().min(count as f32 - 1.0);
+            first..(last + 1.0).max(0.0) as usize
+        };
+        for dab in dabs {
+            let radius = dab.radius.max(0.0001);
+            let [cx, cy] = dab.center;
+            let columns = cells(cx - radius / aspect, cx + radius / aspect, width);
+            for row in cells(cy - radius, cy + radius, height) {
+                let y = (row as f32 + 0.5) / height as f32;
+                for column in columns.clone() {
+                    let x = (column as f32 + 0.5) / width as f32;
+                    let cell = &mut weights[row * width + column];
+                    *cell = cell.max(dab.weight(x, y, aspect));
+                }
+            }
+        }
+        Self {
+            width,
+            height,
+            weights,
+        }
+    }
+
+    /// Weight at image-relative `x`, `y`, interpolated between cell centres
+    pub fn sample(&self, x: f32, y: f32) -> f32 {
+        let gx = (x * self.width as f32 - 0.5).clamp(0.0, (self.width - 1) as f32);
+        let gy = (y * self.height as f32 - 0.5).clamp(0.0, (self.height - 1) as f32);
+        let (x0, y0) = (gx as usize, gy as usize);
+        let (x1, y1) = ((x0 + 1).min(self.width - 1), (y0 + 1).min(self.height - 1));
+        let (fx, fy) = (gx - x0 as f32, gy - y0 as f32);
+        let at = |x: usize, y: usize| self.weights[y * self.width + x];
+        let top = at(x0, y0) + (at(x1, y0) - at(x0, y0)) * fx;
+        let bottom = at(x0, y1) + (at(x1, y1) - at(x0, y1)) * fx;
+        top + (bottom - top) * fy
+    }
+}
+
+/// The local adjustments of an image about to be rendered at `width` by
+/// `height`, with brush masks rasterized up front
+pub struct LocalMasks<'a> {
+    local: &'a [LocalAdjustment],
+    rasters: Vec<Option<BrushRaster>>,
+    aspect: f32,
+}
+
+impl<'a> LocalMasks<'a> {
+    pub fn new(local: &'a [LocalAdjustment], width: u32, height: u32) -> Self {
+        let local = &local[..local.len().min(MAX_LOCAL_ADJUSTMENTS)];
+        // Large images share a capped grid; interpolation hides the steps
+        // of a soft brush
+        let scale = (BRUSH_RASTER_SIZE as f32 / width.max(height).max(1) as f32).min(1.0);
+        let grid = |size: u32| ((size as f32 * scale).round() as u32).max(1);
+        let rasters = local
+            .iter()
+            .map(|adjustment| match &adjustment.mask {
+                LocalMask::Brush { dabs } => {
+                    Some(BrushRaster::new(dabs, grid(width), grid(height)))
+                }
+                _ => None,
+            })
+            .collect();
+        Self {
+            local,
+            rasters,
+            aspect: width as f32 / height.max(1) as f32,
+        }
+    }
+
+    /// Exposure, saturation, temperature and tint offsets of every local
+    /// adjustment at image-relative `x`, `y`
+    pub fn offsets(&self, x: f32, y: f32) -> [f32; 4] {
+        let mut offsets = [0.0; 4];
+        for (adjustment, raster) in self.local.iter().zip(&self.rasters) {
+            let weight = match raster {
+                Some(raster) if adjustment.invert => 1.0 - raster.sample(x, y),
+                Some(raster) => raster.sample(x, y),
+                None => adjustment.weight(x, y, self.aspect),
+            };
+            offsets[0] += adjustment.exposure * weight;
+            offsets[1] += adjustment.saturation * weight;
+            offsets[2] += adjustment.temperature * weight;
+            offsets[3] += adjustment.tint * weight;
+        }
+        offsets
+    }
+}
+
+/// Same as WGSL's `smoothstep`
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
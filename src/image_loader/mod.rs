@@ -1,15 +1,49 @@
 pub mod adjustments;
+pub mod alignment;
+pub mod animation;
+pub mod archive;
+pub mod auto_enhance;
 pub mod extensions;
 pub mod film_emulation;
+pub mod frame;
+pub mod hdr;
 pub mod histogram;
+pub mod jxl;
+pub mod layered;
 pub mod loader;
+pub mod local_adjustments;
 pub mod overlays;
+pub mod pixel_info;
+pub mod progressive;
+pub mod quality;
+pub mod raw_develop;
+pub mod resample;
+pub mod scopes;
 pub mod thumbnail;
+pub mod video;
+pub mod white_balance;
 
 pub use adjustments::*;
+pub use alignment::*;
+pub use animation::*;
+pub use archive::*;
+pub use auto_enhance::*;
 pub use extensions::*;
 pub use film_emulation::*;
+pub use frame::*;
+pub use hdr::*;
 pub use histogram::*;
+pub use jxl::*;
+pub use layered::*;
 pub use loader::*;
+pub use local_adjustments::*;
 pub use overlays::*;
+pub use pixel_info::*;
+pub use progressive::*;
+pub use quality::*;
+pub use raw_develop::*;
+pub use resample::*;
+pub use scopes::*;
 pub use thumbnail::*;
+pub use video::*;
+pub use white_balance::*;
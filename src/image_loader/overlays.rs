@@ -1,7 +1,12 @@
 use image::{DynamicImage, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
 
 // Focus peaking - detect edges/sharp areas
-pub fn generate_focus_peaking_overlay(image: &DynamicImage, threshold: f32) -> RgbaImage {
+pub fn generate_focus_peaking_overlay(
+    image: &DynamicImage,
+    threshold: f32,
+    color: [u8; 4],
+) -> RgbaImage {
     let gray = image.to_luma8();
     let (width, height) = gray.dimensions();
     let mut overlay = RgbaImage::new(width, height);
@@ -26,7 +31,7 @@ pub fn generate_focus_peaking_overlay(image: &DynamicImage, threshold: f32) -> R
             let magnitude = (gx * gx + gy * gy).sqrt();
 
             if magnitude > threshold {
-                overlay.put_pixel(x, y, Rgba([255, 0, 0, 200]));
+                overlay.put_pixel(x, y, Rgba([color[0], color[1], color[2], 200]));
             } else {
                 overlay.put_pixel(x, y, Rgba([0, 0, 0, 0]));
             }
@@ -69,3 +74,63 @@ pub fn generate_zebra_overlay(
 
     overlay
 }
+
+/// Thresholds and look of the clipping warning
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClippingWarning {
+    /// Channel value at and above which highlights count as clipped
+    pub high_threshold: u8,
+    /// Channel value at and below which shadows count as clipped
+    pub low_threshold: u8,
+    /// Also mark areas where only some of the channels clip
+    pub per_channel: bool,
+    /// Flash the warning on and off instead of showing it steadily
+    pub blink: bool,
+}
+
+impl Default for ClippingWarning {
+    fn default() -> Self {
+        Self {
+            high_threshold: 255,
+            low_threshold: 0,
+            per_channel: true,
+            blink: true,
+        }
+    }
+}
+
+/// Every channel clipped to white
+pub const CLIPPED_HIGHLIGHT: Rgba<u8> = Rgba([255, 0, 0, 255]);
+/// Every channel crushed to black
+pub const CLIPPED_SHADOW: Rgba<u8> = Rgba([0, 64, 255, 255]);
+/// Some channels clipped to white, others still holding detail
+pub const CLIPPED_CHANNEL_HIGHLIGHT: Rgba<u8> = Rgba([255, 200, 0, 255]);
+/// Some channels crushed to black, others still holding detail
+pub const CLIPPED_CHANNEL_SHADOW: Rgba<u8> = Rgba([0, 220, 255, 255]);
+
+// Clipping warning: solid colours over blown highlights and crushed shadows
+pub fn generate_clipping_overlay(image: &DynamicImage, warning: &ClippingWarning) -> RgbaImage {
+    let rgb = image.to_rgb8();
+    let mut overlay = RgbaImage::new(rgb.width(), rgb.height());
+    let (high_threshold, low_threshold) = (warning.high_threshold, warning.low_threshold);
+
+    for (pixel, out) in rgb.pixels().zip(overlay.pixels_mut()) {
+        let high = pixel.0.iter().filter(|&&v| v >= high_threshold).count();
+        let low = pixel.0.iter().filter(|&&v| v <= low_threshold).count();
+
+        *out = if high == 3 {
+            CLIPPED_HIGHLIGHT
+        } else if low == 3 {
+            CLIPPED_SHADOW
+        } else if warning.per_channel && high > 0 {
+            CLIPPED_CHANNEL_HIGHLIGHT
+        } else if warning.per_channel && low > 0 {
+            CLIPPED_CHANNEL_SHADOW
+        } else {
+            Rgba([0, 0, 0, 0])
+        };
+    }
+
+    overlay
+}
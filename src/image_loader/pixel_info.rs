@@ -0,0 +1,50 @@
+use image::{DynamicImage, GenericImageView};
+
+/// Channel values of the pixel at (`x`, `y`) as stored in the image, e.g.
+/// "R 12  G 200  B 31  A 255". 16-bit images show raw 0-65535 values and
+/// float images their linear values.
+pub fn pixel_values(image: &DynamicImage, x: u32, y: u32) -> Option<String> {
+    if x >= image.width() || y >= image.height() {
+        return None;
+    }
+
+    let values = match image {
+        DynamicImage::ImageLuma8(img) => integers(&img.get_pixel(x, y).0),
+        DynamicImage::ImageLumaA8(img) => integers(&img.get_pixel(x, y).0),
+        DynamicImage::ImageRgb8(img) => integers(&img.get_pixel(x, y).0),
+        DynamicImage::ImageRgba8(img) => integers(&img.get_pixel(x, y).0),
+        DynamicImage::ImageLuma16(img) => integers(&img.get_pixel(x, y).0),
+        DynamicImage::ImageLumaA16(img) => integers(&img.get_pixel(x, y).0),
+        DynamicImage::ImageRgb16(img) => integers(&img.get_pixel(x, y).0),
+        DynamicImage::ImageRgba16(img) => integers(&img.get_pixel(x, y).0),
+        DynamicImage::ImageRgb32F(img) => floats(&img.get_pixel(x, y).0),
+        DynamicImage::ImageRgba32F(img) => floats(&img.get_pixel(x, y).0),
+        _ => integers(&image.get_pixel(x, y).0),
+    };
+    let labels: &[&str] = match values.len() {
+        1 => &["L"],
+        2 => &["L", "A"],
+        3 => &["R", "G", "B"],
+        _ => &["R", "G", "B", "A"],
+    };
+
+    Some(
+        labels
+            .iter()
+            .zip(values)
+            .map(|(label, value)| format!("{} {}", label, value))
+            .collect::<Vec<_>>()
+            .join("  "),
+    )
+}
+
+fn integers<T: ToString>(channels: &[T]) -> Vec<String> {
+    channels.iter().map(|value| value.to_string()).collect()
+}
+
+fn floats(channels: &[f32]) -> Vec<String> {
+    channels
+        .iter()
+        .map(|value| format!("{:.4}", value))
+        .collect()
+}
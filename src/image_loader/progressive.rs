@@ -0,0 +1,205 @@
+use image::{DynamicImage, GrayImage, RgbImage};
+use std::io::Read;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Files smaller than this decode in full quickly enough that a first pass
+/// only adds work
+pub const PROGRESSIVE_MIN_BYTES: u64 = 2 * 1024 * 1024;
+/// Longest side of the passes shown while the full image loads
+pub const PROGRESSIVE_MAX_SIDE: u32 = 1920;
+/// Bytes read from disk between looks at what has arrived
+const READ_CHUNK: usize = 256 * 1024;
+/// Least time between two passes, so a fast disk doesn't decode every scan
+const PASS_INTERVAL: Duration = Duration::from_millis(150);
+
+/// Whether `path` is a JPEG large enough to be worth showing in passes
+pub fn wants_progressive_passes(path: &Path) -> bool {
+    let is_jpeg = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("jpg") || e.eq_ignore_ascii_case("jpeg"));
+    is_jpeg && std::fs::metadata(path).is_ok_and(|m| m.len() >= PROGRESSIVE_MIN_BYTES)
+}
+
+/// Follows the marker structure of a JPEG as its bytes arrive, recording
+/// where each complete scan ends
+#[derive(Debug, Default)]
+pub struct ScanTracker {
+    /// Next byte not yet looked at
+    pos: usize,
+    /// Whether `pos` is inside entropy-coded scan data
+    in_scan: bool,
+    finished: bool,
+    pub progressive: bool,
+    /// Offset just past each complete scan
+    pub scan_ends: Vec<usize>,
+}
+
+impl ScanTracker {
+    /// Look at the bytes of `data` not seen yet. `data` must start with the
+    /// bytes given to earlier calls.
+    pub fn update(&mut self, data: &[u8]) {
+        while !self.finished {
+            if self.in_scan {
+                // Scan data runs until a marker that isn't a stuffed 0xFF or a restart
+                let mut i = self.pos;
+                loop {
+                    if i + 1 >= data.len() {
+                        self.pos = i;
+                        return;
+                    }
+                    if data[i] != 0xFF {
+                        i += 1;
+                        continue;
+                    }
+                    match data[i + 1] {
+                        0x00 | 0xD0..=0xD7 => i += 2,
+                        0xFF => i += 1,
+                        _ => break,
+                    }
+                }
+                self.scan_ends.push(i);
+                self.pos = i;
+                self.in_scan = false;
+                continue;
+            }
+
+            if self.pos + 2 > data.len() {
+                return;
+            }
+            if data[self.pos] != 0xFF {
+                self.finished = true;
+                return;
+            }
+            let marker = data[self.pos + 1];
+            match marker {
+                0xFF => {
+                    self.pos += 1;
+                    continue;
+                }
+                0xD8 | 0x01 | 0xD0..=0xD7 => {
+                    self.pos += 2;
+                    continue;
+                }
+                0xD9 => {
+                    self.finished = true;
+                    return;
+                }
+                _ => {}
+            }
+
+            if self.pos + 4 > data.len() {
+                return;
+            }
+            let length = u16::from_be_bytes([data[self.pos + 2], data[self.pos + 3]]) as usize;
+            if length < 2 {
+                self.finished = true;
+                return;
+            }
+            let end = self.pos + 2 + length;
+            if end > data.len() {
+                return;
+            }
+            if matches!(marker, 0xC2 | 0xC6 | 0xCA | 0xCE) {
+                self.progressive = true;
+            }
+            self.pos = end;
+            self.in_scan = marker == 0xDA;
+        }
+    }
+}
+
+/// Read a JPEG and hand `emit` low resolution passes of it before the full
+/// decode is done: one per batch of scans for progressive files as they come
+/// off the disk, or a single DCT-downscaled pass for baseline ones. `emit`
+/// also gets the full size of the image. Stops early once `cancel` is set.
+pub fn decode_progressive_passes(
+    path: &Path,
+    max_side: u32,
+    cancel: &AtomicBool,
+    mut emit: impl FnMut(DynamicImage, [u32; 2]),
+) -> std::io::Result<()> {
+    let mut file = std::fs::File::open(path)?;
+    let orientation = super::read_orientation(path);
+    let mut emit = |image: DynamicImage, size: [u32; 2]| match orientation {
+        Some(o) if o > 1 => {
+            let size = if o >= 5 { [size[1], size[0]] } else { size };
+            emit(super::apply_orientation(image, o), size);
+        }
+        _ => emit(image, size),
+    };
+
+    let mut data = Vec::new();
+    let mut chunk = vec![0u8; READ_CHUNK];
+    let mut tracker = ScanTracker::default();
+    let mut shown_scans = 0;
+    let mut last_pass: Option<Instant> = None;
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        let read = file.read(&mut chunk)?;
+        if read == 0 {
+            break;
+        }
+        data.extend_from_slice(&chunk[..read]);
+        tracker.update(&data);
+
+        let scans = tracker.scan_ends.len();
+        if tracker.progressive
+            && scans > shown_scans
+            && !last_pass.is_some_and(|t| t.elapsed() < PASS_INTERVAL)
+        {
+            // Closing the file after the scans read so far gives a complete,
+            // coarser JPEG
+            let mut partial = data[..tracker.scan_ends[scans - 1]].to_vec();
+            partial.extend_from_slice(&[0xFF, 0xD9]);
+            if let Some((image, size)) = decode_scaled(&partial, max_side) {
+                emit(image, size);
+            }
+            shown_scans = scans;
+            last_pass = Some(Instant::now());
+        }
+    }
+
+    // Baseline files can't be shown until the last byte, but a scaled decode
+    // still beats the full one by a wide margin
+    if shown_scans == 0 && !cancel.load(Ordering::Relaxed) {
+        if let Some((image, size)) = decode_scaled(&data, max_side) {
+            emit(image, size);
+        }
+    }
+    Ok(())
+}
+
+/// Decode a JPEG at the smallest DCT scale that still covers `max_side`,
+/// returning the image and the full size
+pub fn decode_scaled(data: &[u8], max_side: u32) -> Option<(DynamicImage, [u32; 2])> {
+    let mut decoder = jpeg_decoder::Decoder::new(data);
+    decoder.read_info().ok()?;
+    let info = decoder.info()?;
+    let full = [info.width as u32, info.height as u32];
+    let scale = (max_side as f32 / full[0].max(full[1]) as f32).min(1.0);
+    let (width, height) = decoder
+        .scale(
+            ((full[0] as f32 * scale).ceil() as u16).max(1),
+            ((full[1] as f32 * scale).ceil() as u16).max(1),
+        )
+        .ok()?;
+    let pixels = decoder.decode().ok()?;
+
+    let (width, height) = (width as u32, height as u32);
+    let image = match info.pixel_format {
+        jpeg_decoder::PixelFormat::L8 => {
+            DynamicImage::ImageLuma8(GrayImage::from_raw(width, height, pixels)?)
+        }
+        jpeg_decoder::PixelFormat::RGB24 => {
+            DynamicImage::ImageRgb8(RgbImage::from_raw(width, height, pixels)?)
+        }
+        // Rare enough to leave to the full decode
+        _ => return None,
+    };
+    Some((image, full))
+}
@@ -0,0 +1,109 @@
+use image::{imageops::FilterType, DynamicImage, GrayImage, RgbImage};
+use rayon::prelude::*;
+
+/// Side of the square windows SSIM is measured over, and the step between them
+const SSIM_WINDOW: u32 = 8;
+const SSIM_STEP: u32 = 4;
+
+/// How closely one image reproduces another
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QualityScores {
+    /// Peak signal-to-noise ratio in dB; infinite for identical pixels
+    pub psnr: f64,
+    /// Structural similarity of the luminance, 1.0 for identical images
+    pub ssim: f64,
+}
+
+impl QualityScores {
+    pub fn summary(&self) -> String {
+        let psnr = if self.psnr.is_finite() {
+            format!("{:.2} dB", self.psnr)
+        } else {
+            "∞".to_string()
+        };
+        format!("PSNR {} · SSIM {:.4}", psnr, self.ssim)
+    }
+}
+
+/// PSNR and SSIM of `other` against `reference`, with `other` stretched to
+/// the reference's size when they differ
+pub fn compare_quality(reference: &DynamicImage, other: &DynamicImage) -> QualityScores {
+    let reference = reference.to_rgb8();
+    let mut other = other.to_rgb8();
+    if other.dimensions() != reference.dimensions() {
+        other = image::imageops::resize(
+            &other,
+            reference.width(),
+            reference.height(),
+            FilterType::Triangle,
+        );
+    }
+    QualityScores {
+        psnr: psnr(&reference, &other),
+        ssim: ssim(
+            &DynamicImage::ImageRgb8(reference).to_luma8(),
+            &DynamicImage::ImageRgb8(other).to_luma8(),
+        ),
+    }
+}
+
+fn psnr(a: &RgbImage, b: &RgbImage) -> f64 {
+    let squared: u64 = a
+        .as_raw()
+        .par_iter()
+        .zip(b.as_raw().par_iter())
+        .map(|(&x, &y)| (x as i64 - y as i64).pow(2) as u64)
+        .sum();
+    let samples = a.as_raw().len().max(1) as f64;
+    let mse = squared as f64 / samples;
+    if mse == 0.0 {
+        f64::INFINITY
+    } else {
+        10.0 * (255.0 * 255.0 / mse).log10()
+    }
+}
+
+/// Mean SSIM over overlapping windows
+fn ssim(a: &GrayImage, b: &GrayImage) -> f64 {
+    const C1: f64 = (0.01 * 255.0) * (0.01 * 255.0);
+    const C2: f64 = (0.03 * 255.0) * (0.03 * 255.0);
+
+    let (width, height) = a.dimensions();
+    // Too small for a window: compare the whole image as one
+    let window_width = SSIM_WINDOW.min(width);
+    let window_height = SSIM_WINDOW.min(height);
+    if window_width == 0 || window_height == 0 {
+        return 1.0;
+    }
+    let positions = |size: u32, window: u32| (0..=size - window).step_by(SSIM_STEP as usize);
+
+    let windows: Vec<(u32, u32)> = positions(height, window_height)
+        .flat_map(|y| positions(width, window_width).map(move |x| (x, y)))
+        .collect();
+    let total: f64 = windows
+        .par_iter()
+        .map(|&(x0, y0)| {
+            let (mut sum_a, mut sum_b, mut sum_aa, mut sum_bb, mut sum_ab) =
+                (0.0, 0.0, 0.0, 0.0, 0.0);
+            for y in y0..y0 + window_height {
+                for x in x0..x0 + window_width {
+                    let pa = a.get_pixel(x, y)[0] as f64;
+                    let pb = b.get_pixel(x, y)[0] as f64;
+                    sum_a += pa;
+                    sum_b += pb;
+                    sum_aa += pa * pa;
+                    sum_bb += pb * pb;
+                    sum_ab += pa * pb;
+                }
+            }
+            let n = (window_width * window_height) as f64;
+            let (mean_a, mean_b) = (sum_a / n, sum_b / n);
+            let var_a = sum_aa / n - mean_a * mean_a;
+            let var_b = sum_bb / n - mean_b * mean_b;
+            let covariance = sum_ab / n - mean_a * mean_b;
+            ((2.0 * mean_a * mean_b + C1) * (2.0 * covariance + C2))
+                / ((mean_a * mean_a + mean_b * mean_b + C1) * (var_a + var_b + C2))
+        })
+        .sum();
+    total / windows.len() as f64
+}
@@ -0,0 +1,421 @@
+use image::{DynamicImage, RgbImage};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+
+/// How the missing two colours of each photosite are filled in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RawDemosaic {
+    /// Average of the nearest photosites of each colour; fast but soft
+    Bilinear,
+    /// Bilinear corrected by the gradient of the photosite's own colour
+    /// (Malvar-He-Cutler), which keeps edges sharp without colour fringes
+    HighQuality,
+}
+
+impl RawDemosaic {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RawDemosaic::Bilinear => "Bilinear (faster)",
+            RawDemosaic::HighQuality => "High quality",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RawWhiteBalance {
+    /// The multipliers the camera recorded when the shot was taken
+    Camera,
+    /// Balanced so the scene averages to grey
+    Auto,
+}
+
+impl RawWhiteBalance {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RawWhiteBalance::Camera => "As shot",
+            RawWhiteBalance::Auto => "Auto",
+        }
+    }
+}
+
+/// Options for turning a RAW file's sensor data into an image
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RawDecodeOptions {
+    pub demosaic: RawDemosaic,
+    /// Build each pixel from one 2x2 block of photosites: half the width and
+    /// height, but several times faster and with no interpolation at all
+    pub half_size: bool,
+    pub white_balance: RawWhiteBalance,
+    /// 0 clips blown highlights to white; higher values roll the brightest
+    /// tones off more gently so more highlight detail survives
+    pub highlight_recovery: f32,
+}
+
+impl Default for RawDecodeOptions {
+    fn default() -> Self {
+        Self {
+            demosaic: RawDemosaic::HighQuality,
+            half_size: false,
+            white_balance: RawWhiteBalance::Camera,
+            highlight_recovery: 0.0,
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref RAW_DECODE_OPTIONS: RwLock<RawDecodeOptions> =
+        RwLock::new(RawDecodeOptions::default());
+}
+
+/// The options every RAW decode uses, whichever thread it runs on
+pub fn raw_decode_options() -> RawDecodeOptions {
+    RAW_DECODE_OPTIONS
+        .read()
+        .map(|o| o.clone())
+        .unwrap_or_default()
+}
+
+pub fn set_raw_decode_options(options: RawDecodeOptions) {
+    if let Ok(mut current) = RAW_DECODE_OPTIONS.write() {
+        *current = options;
+    }
+}
+
+const XYZ_FROM_SRGB: [[f32; 3]; 3] = [
+    [0.412_456_4, 0.357_576_1, 0.180_437_5],
+    [0.212_672_9, 0.715_152_2, 0.072_175],
+    [0.019_333_9, 0.119_192, 0.950_304_1],
+];
+
+/// Sensor data from a camera with a 2x2 colour filter pattern
+pub struct Mosaic {
+    pub width: usize,
+    pub height: usize,
+    /// Photosite values scaled to 0..1 between black and white level
+    pub samples: Vec<f32>,
+    /// Colour of each photosite in the repeating 2x2 pattern: 0 red, 1 green, 2 blue
+    pub pattern: [[usize; 2]; 2],
+    /// Red, green and blue multipliers the camera recorded, if any
+    pub camera_wb: Option<[f32; 3]>,
+    /// Converts white-balanced camera colour to linear sRGB
+    pub cam_to_srgb: [[f32; 3]; 3],
+}
+
+impl Mosaic {
+    /// Unpack a decoded RAW file. None for layouts other than a Bayer pattern
+    /// of integer samples, such as X-Trans or linear DNGs.
+    pub fn from_raw(raw: &rawloader::RawImage) -> Option<Self> {
+        let rawloader::RawImageData::Integer(data) = &raw.data else {
+            return None;
+        };
+        if raw.cpp != 1 || raw.cfa.width != 2 || raw.cfa.height != 2 {
+            return None;
+        }
+        let [top, right, bottom, left] = raw.crops;
+        let width = raw.width.checked_sub(left + right)?;
+        let height = raw.height.checked_sub(top + bottom)?;
+        if width < 4 || height < 4 {
+            return None;
+        }
+
+        // Second greens are reported as a fourth colour on some cameras
+        let site_color = |row: usize, col: usize| raw.cfa.color_at(row + top, col + left);
+        let mut pattern = [[0; 2]; 2];
+        for (row, colors) in pattern.iter_mut().enumerate() {
+            for (col, color) in colors.iter_mut().enumerate() {
+                *color = match site_color(row, col) {
+                    3 => 1,
+                    c if c < 3 => c,
+                    _ => return None,
+                };
+            }
+        }
+
+        let levels: Vec<(f32, f32)> = (0..4)
+            .map(|c| {
+                let black = raw.blacklevels[c] as f32;
+                let range = raw.whitelevels[c] as f32 - black;
+                (black, if range > 0.0 { 1.0 / range } else { 0.0 })
+            })
+            .collect();
+        let site_levels = [
+            [levels[site_color(0, 0)], levels[site_color(0, 1)]],
+            [levels[site_color(1, 0)], levels[site_color(1, 1)]],
+        ];
+        let mut samples = vec![0.0; width * height];
+        samples
+            .par_chunks_mut(width)
+            .enumerate()
+            .for_each(|(row, out)| {
+                let source = &data[(row + top) * raw.width + left..][..width];
+                for (col, (value, &raw_value)) in out.iter_mut().zip(source).enumerate() {
+                    let (black, scale) = site_levels[row & 1][col & 1];
+                    *value = ((raw_value as f32 - black) * scale).clamp(0.0, 1.0);
+                }
+            });
+
+        let wb = raw.wb_coeffs;
+        let camera_wb = wb[..3]
+            .iter()
+            .all(|c| c.is_finite() && *c > 0.0)
+            .then(|| [wb[0] / wb[1], 1.0, wb[2] / wb[1]]);
+
+        let xyz_to_cam = [raw.xyz_to_cam[0], raw.xyz_to_cam[1], raw.xyz_to_cam[2]];
+        Some(Self {
+            width,
+            height,
+            samples,
+            pattern,
+            camera_wb,
+            cam_to_srgb: camera_to_srgb(&xyz_to_cam),
+        })
+    }
+
+    fn color_at(&self, row: usize, col: usize) -> usize {
+        self.pattern[row & 1][col & 1]
+    }
+
+    /// Multipliers that make a grey scene average to grey, ignoring clipped photosites
+    fn grey_world_wb(&self) -> [f32; 3] {
+        let mut sums = [0.0f64; 3];
+        let mut counts = [0u64; 3];
+        for (row, line) in self.samples.chunks(self.width).enumerate() {
+            for (col, &value) in line.iter().enumerate() {
+                if value < 0.95 {
+                    let c = self.color_at(row, col);
+                    sums[c] += value as f64;
+                    counts[c] += 1;
+                }
+            }
+        }
+        let means: Vec<f64> = (0..3).map(|c| sums[c] / counts[c].max(1) as f64).collect();
+        if means.iter().any(|m| *m <= 0.0) {
+            return [1.0; 3];
+        }
+        [
+            (means[1] / means[0]) as f32,
+            1.0,
+            (means[1] / means[2]) as f32,
+        ]
+    }
+}
+
+/// Camera-to-sRGB matrix from the camera's XYZ-to-camera matrix, scaled so
+/// sRGB white comes out as equal camera channels. Identity when the camera
+/// has no usable matrix.
+pub fn camera_to_srgb(xyz_to_cam: &[[f32; 3]; 3]) -> [[f32; 3]; 3] {
+    const IDENTITY: [[f32; 3]; 3] = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    let mut cam_from_srgb = [[0.0f32; 3]; 3];
+    for (i, row) in cam_from_srgb.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            *value = (0..3).map(|k| xyz_to_cam[i][k] * XYZ_FROM_SRGB[k][j]).sum();
+        }
+        let sum: f32 = row.iter().sum();
+        if sum.abs() < 1e-6 {
+            return IDENTITY;
+        }
+        for value in row.iter_mut() {
+            *value /= sum;
+        }
+    }
+    invert3(&cam_from_srgb).unwrap_or(IDENTITY)
+}
+
+fn invert3(m: &[[f32; 3]; 3]) -> Option<[[f32; 3]; 3]> {
+    let cofactor =
+        |r0: usize, r1: usize, c0: usize, c1: usize| m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0];
+    let det = m[0][0] * cofactor(1, 2, 1, 2) - m[0][1] * cofactor(1, 2, 0, 2)
+        + m[0][2] * cofactor(1, 2, 0, 1);
+    if det.abs() < 1e-9 {
+        return None;
+    }
+    let inv = 1.0 / det;
+    Some([
+        [
+            cofactor(1, 2, 1, 2) * inv,
+            -cofactor(0, 2, 1, 2) * inv,
+            cofactor(0, 1, 1, 2) * inv,
+        ],
+        [
+            -cofactor(1, 2, 0, 2) * inv,
+            cofactor(0, 2, 0, 2) * inv,
+            -cofactor(0, 1, 0, 2) * inv,
+        ],
+        [
+            cofactor(1, 2, 0, 1) * inv,
+            -cofactor(0, 2, 0, 1) * inv,
+            cofactor(0, 1, 0, 1) * inv,
+        ],
+    ])
+}
+
+/// Fold an index that runs off either edge back inside, two at a time so it
+/// lands on a photosite of the same colour
+fn reflect(i: isize, n: usize) -> usize {
+    let n = n as isize;
+    let i = if i < 0 { -i } else { i };
+    let i = if i >= n { 2 * (n - 1) - i } else { i };
+    i.clamp(0, n - 1) as usize
+}
+
+/// Neutralise clipped channels and fit the range above white back under it.
+/// `clip` is the level each white-balanced channel saturates at.
+pub fn recover_highlights(rgb: [f32; 3], clip: [f32; 3], strength: f32) -> [f32; 3] {
+    let max = rgb[0].max(rgb[1]).max(rgb[2]);
+    let mut out = rgb;
+    // A saturated channel has lost its true value; taking it to be as bright
+    // as the brightest channel turns blown areas white instead of magenta
+    for ((value, &original), &limit) in out.iter_mut().zip(&rgb).zip(&clip) {
+        if original >= limit * 0.999 {
+            *value = max;
+        }
+    }
+    if strength <= 0.0 {
+        return out.map(|v| v.min(1.0));
+    }
+    let top = clip[0].max(clip[1]).max(clip[2]).max(1.0 + f32::EPSILON);
+    let knee = 1.0 - 0.5 * strength.min(1.0);
+    out.map(|v| {
+        if v <= knee {
+            v
+        } else {
+            (knee + (v - knee) * (1.0 - knee) / (top - knee)).min(1.0)
+        }
+    })
+}
+
+/// Turn sensor data into an 8-bit sRGB image
+pub fn develop(mut mosaic: Mosaic, options: &RawDecodeOptions) -> DynamicImage {
+    let wb = match options.white_balance {
+        RawWhiteBalance::Camera => mosaic.camera_wb.unwrap_or_else(|| mosaic.grey_world_wb()),
+        RawWhiteBalance::Auto => mosaic.grey_world_wb(),
+    };
+    // Scaled so no channel is pulled down; each then clips at its own multiplier
+    let min_wb = wb[0].min(wb[1]).min(wb[2]);
+    let wb = wb.map(|c| c / min_wb);
+    let pattern = mosaic.pattern;
+    mosaic
+        .samples
+        .par_chunks_mut(mosaic.width)
+        .enumerate()
+        .for_each(|(row, line)| {
+            for (col, value) in line.iter_mut().enumerate() {
+                *value *= wb[pattern[row & 1][col & 1]];
+            }
+        });
+    let mosaic = &mosaic;
+    let balanced = &mosaic.samples;
+
+    let gamma: Vec<u8> = (0..4096)
+        .map(|i| {
+            let v = i as f32 / 4095.0;
+            let encoded = if v <= 0.003_130_8 {
+                v * 12.92
+            } else {
+                1.055 * v.powf(1.0 / 2.4) - 0.055
+            };
+            (encoded * 255.0).round() as u8
+        })
+        .collect();
+    let m = mosaic.cam_to_srgb;
+    let finish = |rgb: [f32; 3]| -> [u8; 3] {
+        let rgb = recover_highlights(rgb, wb, options.highlight_recovery);
+        let mut out = [0u8; 3];
+        for (i, value) in out.iter_mut().enumerate() {
+            let linear = m[i][0] * rgb[0] + m[i][1] * rgb[1] + m[i][2] * rgb[2];
+            *value = gamma[(linear.clamp(0.0, 1.0) * 4095.0) as usize];
+        }
+        out
+    };
+
+    let (width, height) = if options.half_size {
+        (mosaic.width / 2, mosaic.height / 2)
+    } else {
+        (mosaic.width, mosaic.height)
+    };
+    let mut pixels = vec![0u8; width * height * 3];
+    pixels
+        .par_chunks_mut(width * 3)
+        .enumerate()
+        .for_each(|(y, line)| {
+            for (x, pixel) in line.chunks_exact_mut(3).enumerate() {
+                let rgb = if options.half_size {
+                    half_size_pixel(mosaic, balanced, x * 2, y * 2)
+                } else {
+                    match options.demosaic {
+                        RawDemosaic::Bilinear => bilinear_pixel(mosaic, balanced, x, y),
+                        RawDemosaic::HighQuality => malvar_pixel(mosaic, balanced, x, y),
+                    }
+                };
+                pixel.copy_from_slice(&finish(rgb));
+            }
+        });
+
+    DynamicImage::ImageRgb8(
+        RgbImage::from_raw(width as u32, height as u32, pixels)
+            .expect("buffer sized from the image dimensions"),
+    )
+}
+
+fn half_size_pixel(mosaic: &Mosaic, data: &[f32], x: usize, y: usize) -> [f32; 3] {
+    let mut sums = [0.0; 3];
+    let mut counts = [0.0; 3];
+    for (dy, dx) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+        let c = mosaic.color_at(y + dy, x + dx);
+        sums[c] += data[(y + dy) * mosaic.width + x + dx];
+        counts[c] += 1.0;
+    }
+    [0, 1, 2].map(|c| sums[c] / f32::max(counts[c], 1.0))
+}
+
+fn bilinear_pixel(mosaic: &Mosaic, data: &[f32], x: usize, y: usize) -> [f32; 3] {
+    let own = mosaic.color_at(y, x);
+    let mut sums = [0.0; 3];
+    let mut counts = [0.0; 3];
+    for dy in -1..=1 {
+        for dx in -1..=1 {
+            let sy = reflect(y as isize + dy, mosaic.height);
+            let sx = reflect(x as isize + dx, mosaic.width);
+            let c = mosaic.color_at(sy, sx);
+            if c != own {
+                sums[c] += data[sy * mosaic.width + sx];
+                counts[c] += 1.0;
+            }
+        }
+    }
+    let mut rgb = [0, 1, 2].map(|c| sums[c] / f32::max(counts[c], 1.0));
+    rgb[own] = data[y * mosaic.width + x];
+    rgb
+}
+
+fn malvar_pixel(mosaic: &Mosaic, data: &[f32], x: usize, y: usize) -> [f32; 3] {
+    let at = |dx: isize, dy: isize| {
+        let sy = reflect(y as isize + dy, mosaic.height);
+        let sx = reflect(x as isize + dx, mosaic.width);
+        data[sy * mosaic.width + sx]
+    };
+    let center = at(0, 0);
+    let cross = at(-1, 0) + at(1, 0) + at(0, -1) + at(0, 1);
+    let diagonal = at(-1, -1) + at(1, -1) + at(-1, 1) + at(1, 1);
+    let far_h = at(-2, 0) + at(2, 0);
+    let far_v = at(0, -2) + at(0, 2);
+
+    let own = mosaic.color_at(y, x);
+    let mut rgb = [0.0; 3];
+    rgb[own] = center;
+    if own == 1 {
+        // Green site: one of red/blue sits left and right, the other above and below
+        let horizontal = mosaic.color_at(y, x + 1);
+        let vertical = mosaic.color_at(y + 1, x);
+        let h = at(-1, 0) + at(1, 0);
+        let v = at(0, -1) + at(0, 1);
+        rgb[horizontal] = (5.0 * center + 4.0 * h - diagonal - far_h + 0.5 * far_v) / 8.0;
+        rgb[vertical] = (5.0 * center + 4.0 * v - diagonal - far_v + 0.5 * far_h) / 8.0;
+    } else {
+        rgb[1] = (4.0 * center + 2.0 * cross - far_h - far_v) / 8.0;
+        rgb[2 - own] = (6.0 * center + 2.0 * diagonal - 1.5 * (far_h + far_v)) / 8.0;
+    }
+    rgb.map(|v| v.max(0.0))
+}
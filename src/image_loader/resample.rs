@@ -0,0 +1,163 @@
+use image::imageops::FilterType;
+use image::{DynamicImage, Rgba32FImage};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Filter used when an image is shrunk for export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ResizeFilter {
+    /// Hard-edged pixels, for pixel art and screenshots
+    Nearest,
+    Bilinear,
+    /// Sharpest, with faint halos on hard edges
+    #[default]
+    Lanczos3,
+    /// Between bilinear and Lanczos, without the halos
+    Mitchell,
+}
+
+impl ResizeFilter {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ResizeFilter::Nearest => "Nearest",
+            ResizeFilter::Bilinear => "Bilinear",
+            ResizeFilter::Lanczos3 => "Lanczos3",
+            ResizeFilter::Mitchell => "Mitchell",
+        }
+    }
+
+    pub fn all() -> &'static [ResizeFilter] {
+        &[
+            ResizeFilter::Nearest,
+            ResizeFilter::Bilinear,
+            ResizeFilter::Lanczos3,
+            ResizeFilter::Mitchell,
+        ]
+    }
+
+    /// Look a filter up by name, ignoring case
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|f| f.name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// Fit within `max` pixels on the long edge without enlarging
+pub fn resize_to_fit(image: DynamicImage, max: u32, filter: ResizeFilter) -> DynamicImage {
+    let long_edge = image.width().max(image.height());
+    if long_edge <= max {
+        return image;
+    }
+    let scale = max as f64 / long_edge as f64;
+    let fit = |side: u32| ((side as f64 * scale).round() as u32).max(1);
+    let (width, height) = (fit(image.width()), fit(image.height()));
+    let filter = match filter {
+        ResizeFilter::Nearest => FilterType::Nearest,
+        ResizeFilter::Bilinear => FilterType::Triangle,
+        ResizeFilter::Lanczos3 => FilterType::Lanczos3,
+        // The image crate has no Mitchell filter
+        ResizeFilter::Mitchell => {
+            let resized = resample_mitchell(&image.to_rgba32f(), width, height);
+            return like(&image, DynamicImage::ImageRgba32F(resized));
+        }
+    };
+    image.resize_exact(width, height, filter)
+}
+
+/// `image` converted back to the bit depth and alpha of `source`
+fn like(source: &DynamicImage, image: DynamicImage) -> DynamicImage {
+    let color = source.color();
+    let depth = color.bytes_per_pixel() / color.channel_count();
+    match (depth, color.has_alpha()) {
+        (1, true) => DynamicImage::ImageRgba8(image.to_rgba8()),
+        (1, false) => DynamicImage::ImageRgb8(image.to_rgb8()),
+        (2, true) => DynamicImage::ImageRgba16(image.to_rgba16()),
+        (2, false) => DynamicImage::ImageRgb16(image.to_rgb16()),
+        (_, true) => image,
+        (_, false) => DynamicImage::ImageRgb32F(image.to_rgb32f()),
+    }
+}
+
+/// Mitchell-Netravali cubic with B = C = 1/3
+fn mitchell(x: f32) -> f32 {
+    const B: f32 = 1.0 / 3.0;
+    const C: f32 = 1.0 / 3.0;
+    let x = x.abs();
+    if x < 1.0 {
+        ((12.0 - 9.0 * B - 6.0 * C) * x * x * x
+            + (-18.0 + 12.0 * B + 6.0 * C) * x * x
+            + (6.0 - 2.0 * B))
+            / 6.0
+    } else if x < 2.0 {
+        ((-B - 6.0 * C) * x * x * x
+            + (6.0 * B + 30.0 * C) * x * x
+            + (-12.0 * B - 48.0 * C) * x
+            + (8.0 * B + 24.0 * C))
+            / 6.0
+    } else {
+        0.0
+    }
+}
+
+/// First source pixel and the weights of the pixels from it that make up
+/// each output pixel along one axis
+fn axis_weights(source: u32, target: u32) -> Vec<(usize, Vec<f32>)> {
+    let scale = source as f32 / target as f32;
+    // Widened when shrinking so every source pixel contributes
+    let stretch = scale.max(1.0);
+    let support = 2.0 * stretch;
+    (0..target)
+        .map(|i| {
+            let center = (i as f32 + 0.5) * scale;
+            let start = (center - support).floor().max(0.0) as u32;
+            let end = ((center + support).ceil() as u32).min(source);
+            let mut weights: Vec<f32> = (start..end)
+                .map(|s| mitchell((s as f32 + 0.5 - center) / stretch))
+                .collect();
+            let total: f32 = weights.iter().sum();
+            if total != 0.0 {
+                weights.iter_mut().for_each(|w| *w /= total);
+            }
+            (start as usize, weights)
+        })
+        .collect()
+}
+
+/// Separable Mitchell resampling, rows first
+fn resample_mitchell(image: &Rgba32FImage, width: u32, height: u32) -> Rgba32FImage {
+    let source_width = image.width() as usize;
+    let columns = axis_weights(image.width(), width);
+    let rows = axis_weights(image.height(), height);
+    let row_len = width as usize * 4;
+
+    let mut horizontal = vec![0.0f32; row_len * image.height() as usize];
+    horizontal
+        .par_chunks_mut(row_len)
+        .zip(image.as_raw().par_chunks(source_width * 4))
+        .for_each(|(row, line)| {
+            for (pixel, (start, weights)) in row.chunks_mut(4).zip(&columns) {
+                for (offset, weight) in weights.iter().enumerate() {
+                    let source = &line[(start + offset) * 4..(start + offset + 1) * 4];
+                    for (out, value) in pixel.iter_mut().zip(source) {
+                        *out += value * weight;
+                    }
+                }
+            }
+        });
+
+    let mut resized = vec![0.0f32; row_len * height as usize];
+    resized
+        .par_chunks_mut(row_len)
+        .zip(rows.par_iter())
+        .for_each(|(row, (start, weights))| {
+            for (offset, weight) in weights.iter().enumerate() {
+                let line = &horizontal[(start + offset) * row_len..(start + offset + 1) * row_len];
+                for (out, value) in row.iter_mut().zip(line) {
+                    *out += value * weight;
+                }
+            }
+        });
+    Rgba32FImage::from_raw(width, height, resized).expect("buffer matches the image size")
+}
@@ -0,0 +1,131 @@
+use image::DynamicImage;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Columns and levels of every scope plot
+pub const SCOPE_SIZE: usize = 256;
+
+/// Longest side images are sampled at; scopes show distribution, not detail
+const SAMPLE_SIDE: u32 = 512;
+
+/// Video-style scopes shown below the histogram
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScopeType {
+    /// Luma of every column, bottom to top
+    Waveform,
+    /// Red, green and blue waveforms side by side
+    Parade,
+    /// Hue and saturation on a colour wheel
+    Vectorscope,
+}
+
+impl ScopeType {
+    pub const ALL: [ScopeType; 3] = [
+        ScopeType::Waveform,
+        ScopeType::Parade,
+        ScopeType::Vectorscope,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ScopeType::Waveform => "Luma waveform",
+            ScopeType::Parade => "RGB parade",
+            ScopeType::Vectorscope => "Vectorscope",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScopeSettings {
+    pub scope: ScopeType,
+    /// Brightness of the plotted traces
+    pub intensity: f32,
+}
+
+impl Default for ScopeSettings {
+    fn default() -> Self {
+        Self {
+            scope: ScopeType::Waveform,
+            intensity: 1.0,
+        }
+    }
+}
+
+/// Sample counts behind each scope, `SCOPE_SIZE` x `SCOPE_SIZE` each and
+/// indexed `[y * SCOPE_SIZE + x]`. Waveform rows are levels from 0 up, the
+/// vectorscope is Cb across and Cr down from the top.
+#[derive(Debug, Clone)]
+pub struct ScopeData {
+    pub luma: Vec<u32>,
+    pub parade: [Vec<u32>; 3],
+    pub vectorscope: Vec<u32>,
+}
+
+impl ScopeData {
+    fn empty() -> Self {
+        let plane = || vec![0u32; SCOPE_SIZE * SCOPE_SIZE];
+        Self {
+            luma: plane(),
+            parade: [plane(), plane(), plane()],
+            vectorscope: plane(),
+        }
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        let planes = self
+            .parade
+            .iter_mut()
+            .chain([&mut self.luma, &mut self.vectorscope])
+            .zip(other.parade.iter().chain([&other.luma, &other.vectorscope]));
+        for (into, from) in planes {
+            for (a, b) in into.iter_mut().zip(from) {
+                *a += b;
+            }
+        }
+        self
+    }
+}
+
+/// Vectorscope cell of an sRGB colour: BT.709 Cb and Cr, which span the
+/// plot from -0.5 to 0.5
+pub fn vectorscope_position(r: f32, g: f32, b: f32) -> (usize, usize) {
+    let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+    let cb = (b - y) / 1.8556;
+    let cr = (r - y) / 1.5748;
+    let cell = |v: f32| {
+        ((v + 0.5) * (SCOPE_SIZE - 1) as f32)
+            .round()
+            .clamp(0.0, 255.0) as usize
+    };
+    (cell(cb), SCOPE_SIZE - 1 - cell(cr))
+}
+
+/// Gather waveform, parade and vectorscope counts from a downsampled copy
+pub fn calculate_scopes(image: &DynamicImage) -> ScopeData {
+    let sample = if image.width().max(image.height()) > SAMPLE_SIDE {
+        image.thumbnail(SAMPLE_SIDE, SAMPLE_SIDE).to_rgb8()
+    } else {
+        image.to_rgb8()
+    };
+    let width = sample.width().max(1) as usize;
+
+    sample
+        .par_chunks(width * 3)
+        .fold(ScopeData::empty, |mut data, row| {
+            for (x, pixel) in row.chunks_exact(3).enumerate() {
+                let column = x * SCOPE_SIZE / width;
+                let [r, g, b] = [pixel[0], pixel[1], pixel[2]];
+                let luma = (0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32).round();
+                data.luma[luma as usize * SCOPE_SIZE + column] += 1;
+                for (channel, value) in [r, g, b].into_iter().enumerate() {
+                    data.parade[channel][value as usize * SCOPE_SIZE + column] += 1;
+                }
+                let (u, v) =
+                    vectorscope_position(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+                data.vectorscope[v * SCOPE_SIZE + u] += 1;
+            }
+            data
+        })
+        .reduce(ScopeData::empty, ScopeData::merge)
+}
@@ -1,4 +1,5 @@
 use crate::errors::{Result, ViewerError};
+use image::imageops::FilterType;
 use image::DynamicImage;
 use std::path::Path;
 
@@ -6,6 +7,19 @@ pub fn generate_thumbnail(image: &DynamicImage, max_size: u32) -> DynamicImage {
     image.thumbnail(max_size, max_size)
 }
 
+/// Reduce to exactly `width`×`height` for on-screen display: a fast area
+/// reduction to twice the size, then Lanczos for the last step
+pub fn downscale_lanczos(image: &DynamicImage, width: u32, height: u32) -> DynamicImage {
+    let reduced;
+    let image = if image.width() > width * 2 && image.height() > height * 2 {
+        reduced = image.thumbnail_exact(width * 2, height * 2);
+        &reduced
+    } else {
+        image
+    };
+    image.resize_exact(width, height, FilterType::Lanczos3)
+}
+
 pub fn load_thumbnail(path: &Path, max_size: u32) -> Result<DynamicImage> {
     crate::profiler::with_profiler(|p| p.start_timer("thumbnail_load"));
     let result = load_thumbnail_impl(path, max_size);
@@ -42,8 +56,20 @@ fn load_thumbnail_impl(path: &Path, max_size: u32) -> Result<DynamicImage> {
         }
     }
 
+    // Seek straight to a small first frame instead of decoding a full size one
+    if super::video::is_video_file(path) {
+        return super::video::video_frame(path, std::time::Duration::ZERO, max_size);
+    }
+
     let image = super::loader::load_image(path)?;
-    Ok(generate_thumbnail(&image, max_size))
+    let thumbnail = generate_thumbnail(&image, max_size);
+    // Float images are tone mapped with the default curve; thumbnails don't
+    // follow the viewer's HDR exposure
+    if super::hdr::is_float(&thumbnail) {
+        let display = super::hdr::display_referred(&thumbnail, &Default::default());
+        return Ok(DynamicImage::ImageRgba8(display.to_rgba8()));
+    }
+    Ok(thumbnail)
 }
 
 /// Load embedded JPEG thumbnail from RAW file (very fast). This version attempts to extract an embedded JPEG via EXIF tags
@@ -93,6 +119,8 @@ pub fn load_raw_embedded_thumbnail(path: &Path, max_size: u32) -> Result<Dynamic
                     message: e.to_string(),
                 })?;
             if let Ok(img) = image::load_from_memory(&buf) {
+                // Embedded previews are stored as the sensor saw them
+                let img = super::loader::orient_from_exif(path, img);
                 return Ok(img.thumbnail(max_size, max_size));
             }
         }
@@ -138,6 +166,7 @@ pub fn load_raw_embedded_thumbnail(path: &Path, max_size: u32) -> Result<Dynamic
                 path,
                 slice.len()
             );
+            let img = super::loader::orient_from_exif(path, img);
             return Ok(img.thumbnail(max_size, max_size));
         }
     }
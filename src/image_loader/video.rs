@@ -0,0 +1,190 @@
+use crate::errors::{Result, ViewerError};
+use image::{DynamicImage, RgbaImage};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{sync_channel, Receiver};
+use std::time::Duration;
+
+/// Video containers listed alongside images; decoding goes through ffmpeg
+pub const VIDEO_EXTENSIONS: &[&str] = &["mp4", "mov", "m4v", "avi", "mkv", "webm", "mts"];
+
+/// Longest side frames are decoded at during playback
+pub const PLAYBACK_MAX_SIDE: u32 = 1920;
+
+/// Frames decoded ahead of the one on screen
+const FRAMES_AHEAD: usize = 4;
+
+pub fn is_video_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| VIDEO_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// `ffmpeg` or `ffprobe` from the PATH
+pub fn find_tool(name: &str) -> Option<PathBuf> {
+    let exe = if cfg!(windows) {
+        format!("{}.exe", name)
+    } else {
+        name.to_string()
+    };
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(&exe))
+        .find(|p| p.is_file())
+}
+
+fn tool(path: &Path, name: &str) -> Result<Command> {
+    let exe = find_tool(name).ok_or_else(|| ViewerError::DecodingError {
+        path: path.to_path_buf(),
+        message: format!("{} not found; install FFmpeg to play videos", name),
+    })?;
+    let mut command = Command::new(exe);
+    command.stdin(Stdio::null()).stderr(Stdio::null());
+    Ok(command)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VideoInfo {
+    pub duration: Duration,
+    pub fps: f64,
+}
+
+/// A frame rate written as a fraction such as `30000/1001`
+fn parse_rate(value: &str) -> Option<f64> {
+    let fps = match value.split_once('/') {
+        Some((num, den)) => num.parse::<f64>().ok()? / den.parse::<f64>().ok()?,
+        None => value.parse().ok()?,
+    };
+    (fps.is_finite() && fps > 0.0).then_some(fps)
+}
+
+impl VideoInfo {
+    /// Read `key=value` lines as printed by ffprobe
+    pub fn parse(probe: &str) -> Option<Self> {
+        let mut duration = None;
+        let mut fps = None;
+        for line in probe.lines() {
+            match line.trim().split_once('=') {
+                Some(("duration", value)) => duration = value.parse::<f64>().ok(),
+                Some(("avg_frame_rate", value)) => fps = parse_rate(value),
+                _ => {}
+            }
+        }
+        Some(Self {
+            duration: Duration::from_secs_f64(duration?.max(0.0)),
+            fps: fps.unwrap_or(30.0),
+        })
+    }
+
+    pub fn frame_interval(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.fps)
+    }
+}
+
+/// Length and frame rate of the first video stream
+pub fn probe_video(path: &Path) -> Result<VideoInfo> {
+    let output = tool(path, "ffprobe")?
+        .args(["-v", "error", "-select_streams", "v:0"])
+        .args(["-show_entries", "stream=avg_frame_rate:format=duration"])
+        .args(["-of", "default=noprint_wrappers=1"])
+        .arg(path)
+        .output()?;
+    VideoInfo::parse(&String::from_utf8_lossy(&output.stdout)).ok_or_else(|| {
+        ViewerError::DecodingError {
+            path: path.to_path_buf(),
+            message: "No video stream".to_string(),
+        }
+    })
+}
+
+/// Scale filter fitting frames within `max_side`, keeping the sides even as
+/// most pixel formats need
+fn scale_filter(max_side: u32) -> String {
+    format!(
+        "scale=w='min({0},iw)':h='min({0},ih)':force_original_aspect_ratio=decrease:force_divisible_by=2",
+        max_side
+    )
+}
+
+/// One frame at `at`, no larger than `max_side`. ffmpeg turns it upright
+/// from the rotation phones record.
+pub fn video_frame(path: &Path, at: Duration, max_side: u32) -> Result<DynamicImage> {
+    let output = tool(path, "ffmpeg")?
+        .args([
+            "-v",
+            "error",
+            "-ss",
+            &format!("{:.3}", at.as_secs_f64()),
+            "-i",
+        ])
+        .arg(path)
+        .args(["-frames:v", "1", "-vf", &scale_filter(max_side)])
+        .args(["-f", "image2pipe", "-vcodec", "png", "-"])
+        .output()?;
+    image::load_from_memory(&output.stdout).map_err(|e| ViewerError::DecodingError {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    })
+}
+
+/// The first frame, shown as the file's image
+pub fn load_video_frame(path: &Path) -> Result<DynamicImage> {
+    video_frame(path, Duration::ZERO, PLAYBACK_MAX_SIDE)
+}
+
+/// A running ffmpeg decode from some position on, a few frames ahead of
+/// playback. Dropping it stops ffmpeg.
+pub struct VideoStream {
+    child: Child,
+    pub frames: Receiver<RgbaImage>,
+}
+
+impl VideoStream {
+    /// Decode from `start` at `width`x`height`, the size of the first frame
+    pub fn start(path: &Path, start: Duration, width: u32, height: u32) -> Result<Self> {
+        let mut child = tool(path, "ffmpeg")?
+            .args([
+                "-v",
+                "error",
+                "-ss",
+                &format!("{:.3}", start.as_secs_f64()),
+                "-i",
+            ])
+            .arg(path)
+            .args(["-an", "-vf", &format!("scale={}:{}", width, height)])
+            .args(["-f", "rawvideo", "-pix_fmt", "rgba", "-"])
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let mut stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| ViewerError::DecodingError {
+                path: path.to_path_buf(),
+                message: "ffmpeg produced no output".to_string(),
+            })?;
+
+        // The bounded channel holds the decoder back to the playback rate
+        let (tx, frames) = sync_channel(FRAMES_AHEAD);
+        std::thread::spawn(move || loop {
+            let mut data = vec![0u8; width as usize * height as usize * 4];
+            if stdout.read_exact(&mut data).is_err() {
+                break;
+            }
+            let Some(frame) = RgbaImage::from_raw(width, height, data) else {
+                break;
+            };
+            if tx.send(frame).is_err() {
+                break;
+            }
+        });
+        Ok(Self { child, frames })
+    }
+}
+
+impl Drop for VideoStream {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
@@ -0,0 +1,64 @@
+/// Colour temperature at which white balance leaves an image unchanged
+pub const NEUTRAL_KELVIN: f32 = 6500.0;
+/// Range covered by the Planckian locus fit in `kelvin_to_linear_rgb`
+const MIN_KELVIN: f32 = 1667.0;
+const MAX_KELVIN: f32 = 25000.0;
+/// Green gain at full tint, in stops
+const TINT_STOPS: f32 = 1.0;
+
+/// Light temperature a `temperature` value (-1 to +1) corrects for. Each unit
+/// doubles the Kelvin, so the slider spans 3250K to 13000K.
+pub fn temperature_to_kelvin(temperature: f32) -> f32 {
+    (NEUTRAL_KELVIN * 2.0_f32.powf(temperature)).clamp(MIN_KELVIN, MAX_KELVIN)
+}
+
+pub fn kelvin_to_temperature(kelvin: f32) -> f32 {
+    (kelvin.clamp(MIN_KELVIN, MAX_KELVIN) / NEUTRAL_KELVIN).log2()
+}
+
+/// Linear sRGB colour of a black body at `kelvin`, with a luminance of 1.
+/// Uses Kang et al.'s cubic fit of the Planckian locus.
+pub fn kelvin_to_linear_rgb(kelvin: f32) -> [f32; 3] {
+    let t = kelvin.clamp(MIN_KELVIN, MAX_KELVIN);
+    let (t2, t3) = (t * t, t * t * t);
+    let x = if t <= 4000.0 {
+        -0.266_123_9e9 / t3 - 0.234_358_9e6 / t2 + 0.877_695_6e3 / t + 0.179_910
+    } else {
+        -3.025_846_9e9 / t3 + 2.107_037_9e6 / t2 + 0.222_634_7e3 / t + 0.240_390
+    };
+    let (x2, x3) = (x * x, x * x * x);
+    let y = if t <= 2222.0 {
+        -1.106_381 * x3 - 1.348_110 * x2 + 2.185_558 * x - 0.202_196_8
+    } else if t <= 4000.0 {
+        -0.954_947_6 * x3 - 1.374_186 * x2 + 2.091_370 * x - 0.167_488_7
+    } else {
+        3.081_758 * x3 - 5.873_387 * x2 + 3.751_130 * x - 0.370_014_8
+    };
+
+    // xyY to XYZ to linear sRGB
+    let big_x = x / y;
+    let big_z = (1.0 - x - y) / y;
+    [
+        3.2406 * big_x - 1.5372 - 0.4986 * big_z,
+        -0.9689 * big_x + 1.8758 + 0.0415 * big_z,
+        0.0557 * big_x - 0.2040 + 1.0570 * big_z,
+    ]
+}
+
+/// Per-channel multipliers for gamma-encoded values that white balance an
+/// image shot under `temperature` light, tinted toward magenta by positive
+/// `tint`. Gains are worked out in linear light with the brightness of white
+/// kept, then raised to 1/2.2 to apply to encoded values.
+pub fn white_balance_gains(temperature: f32, tint: f32) -> [f32; 3] {
+    if temperature == 0.0 && tint == 0.0 {
+        return [1.0; 3];
+    }
+
+    let neutral = kelvin_to_linear_rgb(NEUTRAL_KELVIN);
+    let light = kelvin_to_linear_rgb(temperature_to_kelvin(temperature));
+    let mut gains: [f32; 3] = std::array::from_fn(|c| neutral[c] / light[c].max(0.01));
+    gains[1] *= 2.0_f32.powf(-tint * TINT_STOPS);
+
+    let luminance = 0.2126 * gains[0] + 0.7152 * gains[1] + 0.0722 * gains[2];
+    gains.map(|gain| (gain / luminance).powf(1.0 / 2.2))
+}
@@ -0,0 +1,138 @@
+/// One IPTC dataset: record number, dataset number and its raw bytes
+#[derive(Debug, Clone, PartialEq)]
+pub struct IptcDataset {
+    pub record: u8,
+    pub dataset: u8,
+    pub data: Vec<u8>,
+}
+
+impl IptcDataset {
+    /// Name from the IPTC-IIM specification, for the datasets photo tools write
+    pub fn name(&self) -> Option<&'static str> {
+        let name = match (self.record, self.dataset) {
+            (1, 90) => "Coded Character Set",
+            (2, 0) => "Record Version",
+            (2, 5) => "Object Name",
+            (2, 10) => "Urgency",
+            (2, 15) => "Category",
+            (2, 20) => "Supplemental Category",
+            (2, 25) => "Keywords",
+            (2, 40) => "Special Instructions",
+            (2, 55) => "Date Created",
+            (2, 60) => "Time Created",
+            (2, 62) => "Digital Creation Date",
+            (2, 63) => "Digital Creation Time",
+            (2, 65) => "Originating Program",
+            (2, 70) => "Program Version",
+            (2, 80) => "By-line",
+            (2, 85) => "By-line Title",
+            (2, 90) => "City",
+            (2, 92) => "Sub-location",
+            (2, 95) => "Province/State",
+            (2, 100) => "Country Code",
+            (2, 101) => "Country",
+            (2, 103) => "Original Transmission Reference",
+            (2, 105) => "Headline",
+            (2, 110) => "Credit",
+            (2, 115) => "Source",
+            (2, 116) => "Copyright Notice",
+            (2, 118) => "Contact",
+            (2, 120) => "Caption/Abstract",
+            (2, 122) => "Writer/Editor",
+            _ => return None,
+        };
+        Some(name)
+    }
+
+    /// The data as text; IPTC strings are UTF-8 or Latin-1 in practice
+    pub fn text(&self) -> String {
+        match std::str::from_utf8(&self.data) {
+            Ok(text) => text.trim_end_matches('\0').to_string(),
+            Err(_) => self.data.iter().map(|&b| b as char).collect(),
+        }
+    }
+}
+
+/// Photoshop image resource holding the IPTC-IIM block
+const IPTC_RESOURCE: u16 = 0x0404;
+
+/// IPTC-IIM datasets of a JPEG file's contents, which are carried in a
+/// Photoshop APP13 segment. Empty when it has none.
+pub fn read_iptc(bytes: &[u8]) -> Vec<IptcDataset> {
+    let mut datasets = Vec::new();
+    for segment in app13_segments(bytes) {
+        if let Some(block) = segment
+            .strip_prefix(b"Photoshop 3.0\0")
+            .and_then(iptc_block)
+        {
+            datasets.extend(parse_datasets(block));
+        }
+    }
+    datasets
+}
+
+/// Payloads of the APP13 segments before the image data starts
+fn app13_segments(bytes: &[u8]) -> Vec<&[u8]> {
+    let mut segments = Vec::new();
+    if !bytes.starts_with(&[0xFF, 0xD8]) {
+        return segments;
+    }
+    let mut pos = 2;
+    while pos + 4 <= bytes.len() && bytes[pos] == 0xFF {
+        let marker = bytes[pos + 1];
+        // Start of scan: only compressed image data follows
+        if marker == 0xDA {
+            break;
+        }
+        let length = u16::from_be_bytes([bytes[pos + 2], bytes[pos + 3]]) as usize;
+        let end = (pos + 2 + length).min(bytes.len());
+        if marker == 0xED && length >= 2 {
+            segments.push(&bytes[pos + 4..end]);
+        }
+        pos = end;
+    }
+    segments
+}
+
+/// Find the IPTC resource among Photoshop's `8BIM` image resources
+fn iptc_block(mut resources: &[u8]) -> Option<&[u8]> {
+    while resources.len() >= 12 && resources.starts_with(b"8BIM") {
+        let id = u16::from_be_bytes([resources[4], resources[5]]);
+        // Pascal string name, padded to an even length
+        let name_length = resources[6] as usize;
+        let mut pos = 6 + name_length + 1;
+        pos += pos % 2;
+        let size_bytes = resources.get(pos..pos + 4)?;
+        let size = u32::from_be_bytes(size_bytes.try_into().ok()?) as usize;
+        pos += 4;
+        let data = resources.get(pos..pos + size)?;
+        if id == IPTC_RESOURCE {
+            return Some(data);
+        }
+        pos += size + size % 2;
+        resources = resources.get(pos..)?;
+    }
+    None
+}
+
+fn parse_datasets(block: &[u8]) -> Vec<IptcDataset> {
+    let mut datasets = Vec::new();
+    let mut pos = 0;
+    while pos + 5 <= block.len() && block[pos] == 0x1C {
+        let size = u16::from_be_bytes([block[pos + 3], block[pos + 4]]) as usize;
+        // Extended sizes (top bit set) are only used for huge objects
+        if size & 0x8000 != 0 {
+            break;
+        }
+        let Some(data) = block.get(pos + 5..pos + 5 + size) else {
+            break;
+        };
+        datasets.push(IptcDataset {
+            record: block[pos + 1],
+            dataset: block[pos + 2],
+            data: data.to_vec(),
+        });
+        pos += 5 + size;
+    }
+    datasets
+}
@@ -0,0 +1,158 @@
+use crate::app::actions::{find_action, Action, ACTIONS};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// A key plus the modifiers that must be held, written like `Ctrl+Shift+E`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    pub key: egui::Key,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl KeyBinding {
+    pub fn parse(text: &str) -> Option<Self> {
+        let parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        let (key, modifiers) = parts.split_last()?;
+        let mut binding = Self {
+            key: egui::Key::from_name(key)?,
+            ctrl: false,
+            shift: false,
+            alt: false,
+        };
+        for modifier in modifiers {
+            match modifier.to_lowercase().as_str() {
+                "ctrl" => binding.ctrl = true,
+                "shift" => binding.shift = true,
+                "alt" => binding.alt = true,
+                _ => return None,
+            }
+        }
+        Some(binding)
+    }
+
+    pub fn new(key: egui::Key, modifiers: egui::Modifiers) -> Self {
+        Self {
+            key,
+            ctrl: modifiers.ctrl,
+            shift: modifiers.shift,
+            alt: modifiers.alt,
+        }
+    }
+
+    /// Whether the key went down this frame with exactly these modifiers held
+    pub fn pressed(&self, input: &egui::InputState) -> bool {
+        let modifiers_match = input.modifiers.ctrl == self.ctrl
+            && input.modifiers.shift == self.shift
+            && input.modifiers.alt == self.alt;
+        // egui turns Ctrl+C, Ctrl+X and Ctrl+V into clipboard events without a
+        // key press, so those shortcuts fire when the key is let go instead
+        if self.is_clipboard_shortcut() {
+            modifiers_match && input.key_released(self.key)
+        } else {
+            modifiers_match && input.key_pressed(self.key)
+        }
+    }
+
+    fn is_clipboard_shortcut(&self) -> bool {
+        self.ctrl
+            && !self.shift
+            && !self.alt
+            && matches!(self.key, egui::Key::C | egui::Key::X | egui::Key::V)
+    }
+
+    /// Short form for menus and the palette, e.g. `Ctrl+←`
+    pub fn label(&self) -> String {
+        format!("{}{}", self.modifier_prefix(), self.key.symbol_or_name())
+    }
+
+    fn modifier_prefix(&self) -> String {
+        let mut prefix = String::new();
+        if self.ctrl {
+            prefix.push_str("Ctrl+");
+        }
+        if self.shift {
+            prefix.push_str("Shift+");
+        }
+        if self.alt {
+            prefix.push_str("Alt+");
+        }
+        prefix
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.modifier_prefix(), self.key.name())
+    }
+}
+
+/// User changes to the default bindings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Keymap {
+    /// Bindings by action id for actions the user rebound; an empty list unbinds.
+    /// Kept as text so an unknown key name can't stop the settings from loading.
+    pub overrides: BTreeMap<String, Vec<String>>,
+}
+
+impl Keymap {
+    pub fn bindings(&self, id: &str) -> Vec<KeyBinding> {
+        match self.overrides.get(id) {
+            Some(keys) => keys.iter().filter_map(|k| KeyBinding::parse(k)).collect(),
+            None => find_action(id)
+                .map(|a| a.keys.iter().filter_map(|k| KeyBinding::parse(k)).collect())
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn set_bindings(&mut self, id: &str, bindings: &[KeyBinding]) {
+        let keys: Vec<String> = bindings.iter().map(|b| b.to_string()).collect();
+        let is_default = find_action(id).is_some_and(|a| {
+            a.keys
+                .iter()
+                .filter_map(|k| KeyBinding::parse(k))
+                .eq(bindings.iter().copied())
+        });
+        if is_default {
+            self.overrides.remove(id);
+        } else {
+            self.overrides.insert(id.to_string(), keys);
+        }
+    }
+
+    pub fn is_default(&self, id: &str) -> bool {
+        !self.overrides.contains_key(id)
+    }
+
+    /// Bindings for display, e.g. `Ctrl+Y / Ctrl+Shift+Z`
+    pub fn shortcut_label(&self, id: &str) -> String {
+        self.bindings(id)
+            .iter()
+            .map(KeyBinding::label)
+            .collect::<Vec<_>>()
+            .join(" / ")
+    }
+
+    /// Bindings used by more than one action, with the actions sharing each
+    pub fn conflicts(&self) -> HashMap<KeyBinding, Vec<&'static str>> {
+        let mut users: HashMap<KeyBinding, Vec<&'static str>> = HashMap::new();
+        for action in ACTIONS {
+            for binding in self.bindings(action.id) {
+                users.entry(binding).or_default().push(action.id);
+            }
+        }
+        users.retain(|_, ids| ids.len() > 1);
+        users
+    }
+
+    /// Actions whose binding was pressed this frame
+    pub fn pressed(&self, input: &egui::InputState) -> Vec<&'static Action> {
+        ACTIONS
+            .iter()
+            .filter(|a| self.bindings(a.id).iter().any(|b| b.pressed(input)))
+            .collect()
+    }
+}
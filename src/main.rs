@@ -1,24 +1,57 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod app;
+mod batch_metadata;
+mod brush;
+mod cli;
+mod color_management;
+mod contact_sheet;
+mod convert;
+mod download;
 mod errors;
 mod exif_data;
+mod exif_overlay;
+mod export_naming;
 mod gpu;
+mod i18n;
 mod image_cache;
 mod image_loader;
+mod iptc;
+mod keymap;
 mod logging;
 mod metadata;
+mod monitors;
+mod music;
+mod os_tags;
 mod profiler;
+mod remote_control;
+mod rename;
+mod scripting;
+mod search;
 mod settings;
+mod share;
+mod shoot_summary;
+mod single_instance;
+mod slideshow;
 mod task_scheduler;
 mod telemetry;
 mod tests;
+mod tether;
+mod timeline;
+mod touch;
+mod tray;
 mod ui;
+mod web_gallery;
+mod workspace;
+mod xmp;
 
 use app::ImageViewerApp;
 use eframe::egui::{self, FontData, FontDefinitions, FontFamily};
 use std::sync::Arc;
 
+/// Smallest size of the main window outside the mini viewer
+const MIN_WINDOW_SIZE: [f32; 2] = [800.0, 600.0];
+
 /// Install icon fonts from iconflow (Lucide icons)
 fn install_icon_fonts(ctx: &egui::Context) {
     let mut definitions = FontDefinitions::default();
@@ -47,27 +80,49 @@ fn install_icon_fonts(ctx: &egui::Context) {
 }
 
 fn main() -> eframe::Result<()> {
-    // Get command line arguments for opening files/folders and detect debug flag
-    let args: Vec<String> = std::env::args().collect();
-    let debug_flag = args.iter().any(|a| a == "--debug" || a == "-d");
-    logging::init_tracing(debug_flag);
-
-    // Determine initial path (first non-flag argument that's not the program name)
-    let initial_path = args
-        .iter()
-        .skip(1)
-        .find(|a| !a.starts_with('-'))
-        .map(std::path::PathBuf::from);
+    // `rview convert ...` runs without a window
+    if std::env::args().nth(1).as_deref() == Some("convert") {
+        std::process::exit(convert::run(std::env::args().skip(2)));
+    }
+
+    // Parse command line options before anything reads the settings
+    let cli = match cli::CliArgs::parse(std::env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+    if cli.help {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+    logging::init_tracing(cli.debug);
+    if let Some(path) = cli.settings.clone() {
+        settings::use_settings_file(path);
+    }
+
+    // Hand the path to a window that's already open, unless asked for a new one
+    let use_running = settings::Settings::load().single_instance && !cli.new_window;
+    if use_running
+        && single_instance::forward_to_running(cli.path.as_deref(), &cli.window_options())
+    {
+        return Ok(());
+    }
 
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_title("rView")
             .with_inner_size([1400.0, 900.0])
-            .with_min_inner_size([800.0, 600.0])
+            .with_min_inner_size(MIN_WINDOW_SIZE)
             .with_icon(load_icon())
             .with_drag_and_drop(true)
-            .with_maximized(true),
+            // See-through only where the reference overlay shows the desktop
+            .with_transparent(true)
+            .with_maximized(true)
+            .with_fullscreen(cli.fullscreen),
         vsync: true,
+        renderer: eframe::Renderer::Wgpu,
         multisampling: 0,
         ..Default::default()
     };
@@ -82,17 +137,14 @@ fn main() -> eframe::Result<()> {
             // Install Lucide icon fonts
             install_icon_fonts(&cc.egui_ctx);
 
-            // Create app
-            let mut app = ImageViewerApp::new(cc);
-
-            // Load initial path if provided
-            if let Some(path) = initial_path {
-                if path.is_dir() {
-                    app.load_folder(path);
-                } else {
-                    app.load_image_file(path);
-                }
+            // Create app; it opens the path given on the command line
+            let mut app = ImageViewerApp::new(cc, &cli);
+            app.watch_catalog_folders();
+            if use_running {
+                app.listen_for_other_launches();
             }
+            app.apply_remote_control_settings();
+            app.apply_tray_settings();
 
             Ok(Box::new(app))
         }),
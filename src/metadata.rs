@@ -1,4 +1,5 @@
 use crate::image_loader::ImageAdjustments;
+use crate::settings::ColorLabel;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -6,18 +7,67 @@ use std::path::PathBuf;
 /// Metadata stored for each image (ratings, labels, etc.)
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ImageMetadata {
-    pub tags: Vec<String>,
+    #[serde(default, alias = "tags")]
+    pub keywords: Vec<String>,
     pub notes: String,
     pub flagged: bool,
     pub rejected: bool,
     #[serde(default)]
+    pub rating: u8, // 0-5 stars
+    #[serde(default)]
+    pub color_label: ColorLabel,
+    #[serde(default)]
     pub adjustments: Option<ImageAdjustments>,
 }
 
+/// Culling flag; stored as the `flagged` and `rejected` fields of `ImageMetadata`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Flag {
+    #[default]
+    None,
+    Pick,
+    Reject,
+}
+
+impl Flag {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Flag::None => "Unflagged",
+            Flag::Pick => "Picked",
+            Flag::Reject => "Rejected",
+        }
+    }
+}
+
+impl ImageMetadata {
+    pub fn flag(&self) -> Flag {
+        if self.rejected {
+            Flag::Reject
+        } else if self.flagged {
+            Flag::Pick
+        } else {
+            Flag::None
+        }
+    }
+}
+
+/// When a photo was taken according to its EXIF, cached so sorting by date
+/// taken doesn't have to open every file again
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptureDate {
+    /// File size when the date was read, to notice a replaced file; the
+    /// modification time is no use since copying changes it
+    pub size: u64,
+    /// None for files without an EXIF date
+    pub taken: Option<chrono::NaiveDateTime>,
+}
+
 /// Database of image metadata
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct MetadataDb {
     pub images: HashMap<PathBuf, ImageMetadata>,
+    #[serde(default)]
+    pub capture_dates: HashMap<PathBuf, CaptureDate>,
 }
 
 impl MetadataDb {
@@ -63,6 +113,20 @@ impl MetadataDb {
         self.images.get(path.as_ref()).cloned().unwrap_or_default()
     }
 
+    pub fn set_rating<P: AsRef<std::path::Path>>(&mut self, path: P, rating: u8) {
+        self.get_entry_mut(path).rating = rating.min(5);
+    }
+
+    pub fn set_color_label<P: AsRef<std::path::Path>>(&mut self, path: P, label: ColorLabel) {
+        self.get_entry_mut(path).color_label = label;
+    }
+
+    pub fn set_flag<P: AsRef<std::path::Path>>(&mut self, path: P, flag: Flag) {
+        let entry = self.get_entry_mut(path);
+        entry.flagged = flag == Flag::Pick;
+        entry.rejected = flag == Flag::Reject;
+    }
+
     #[allow(dead_code)]
     pub fn toggle_flag<P: AsRef<std::path::Path>>(&mut self, path: P) {
         let entry = self.get_entry_mut(path);
@@ -75,24 +139,63 @@ impl MetadataDb {
         entry.rejected = !entry.rejected;
     }
 
-    #[allow(dead_code)]
-    pub fn add_tag<P: AsRef<std::path::Path>>(&mut self, path: P, tag: String) {
+    /// Add a keyword, ignoring case when checking for duplicates. Returns false if it was already set.
+    pub fn add_keyword<P: AsRef<std::path::Path>>(&mut self, path: P, keyword: &str) -> bool {
+        let keyword = keyword.trim();
         let entry = self.get_entry_mut(path);
-        if !entry.tags.contains(&tag) {
-            entry.tags.push(tag);
+        if keyword.is_empty()
+            || entry
+                .keywords
+                .iter()
+                .any(|k| k.eq_ignore_ascii_case(keyword))
+        {
+            return false;
         }
+        entry.keywords.push(keyword.to_string());
+        true
     }
 
-    #[allow(dead_code)]
-    pub fn remove_tag<P: AsRef<std::path::Path>>(&mut self, path: P, tag: &str) {
+    pub fn remove_keyword<P: AsRef<std::path::Path>>(&mut self, path: P, keyword: &str) {
         let entry = self.get_entry_mut(path);
-        entry.tags.retain(|t| t != tag);
+        entry.keywords.retain(|k| k != keyword);
+    }
+
+    /// Every keyword used in the catalog, sorted case-insensitively for autocomplete
+    pub fn all_keywords(&self) -> Vec<String> {
+        let mut keywords: Vec<String> = self
+            .images
+            .values()
+            .flat_map(|m| m.keywords.iter().cloned())
+            .collect();
+        keywords.sort_by_key(|k| k.to_lowercase());
+        keywords.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+        keywords
+    }
+
+    pub fn has_keyword<P: AsRef<std::path::Path>>(&self, path: P, keyword: &str) -> bool {
+        self.images
+            .get(path.as_ref())
+            .is_some_and(|m| m.keywords.iter().any(|k| k.eq_ignore_ascii_case(keyword)))
     }
 
     pub fn restore_metadata(&mut self, path: PathBuf, metadata: ImageMetadata) {
         self.images.insert(path, metadata);
     }
 
+    /// Catalog an image that arrived in a watched folder: its capture date, and
+    /// the rating, label and keywords from its sidecar if it has one
+    pub fn import_file(
+        &mut self,
+        path: PathBuf,
+        date: CaptureDate,
+        xmp: Option<&crate::xmp::XmpData>,
+    ) {
+        if let Some(xmp) = xmp {
+            xmp.apply_to(self.get_entry_mut(&path));
+        }
+        self.capture_dates.insert(path, date);
+    }
+
     /// Get adjustments for an image, returns None if no adjustments are stored
     pub fn get_adjustments<P: AsRef<std::path::Path>>(&self, path: P) -> Option<ImageAdjustments> {
         self.images
@@ -115,6 +218,9 @@ impl MetadataDb {
         if let Some(metadata) = self.images.remove(&old_path.to_path_buf()) {
             self.images.insert(new_path.to_path_buf(), metadata);
         }
+        if let Some(date) = self.capture_dates.remove(old_path) {
+            self.capture_dates.insert(new_path.to_path_buf(), date);
+        }
     }
 }
 
@@ -138,11 +244,19 @@ pub enum FileOperation {
         from: PathBuf,
         to: PathBuf,
     },
+    Copy {
+        from: PathBuf,
+        to: PathBuf,
+    },
     #[allow(dead_code)]
     Rename {
         from: PathBuf,
         to: PathBuf,
     },
+    /// Several files renamed together, including any XMP sidecars; undone as one step
+    BatchRename {
+        renames: Vec<(PathBuf, PathBuf)>,
+    },
     Rotate {
         path: PathBuf,
         degrees: i32,
@@ -163,6 +277,20 @@ pub enum FileOperation {
         adjustments: crate::image_loader::ImageAdjustments,
         previous_adjustments: Box<crate::image_loader::ImageAdjustments>,
     },
+    /// Rating and color label before and after a change
+    Rate {
+        path: PathBuf,
+        before: (u8, ColorLabel),
+        after: (u8, ColorLabel),
+    },
+    /// Pick/reject flag before and after a change
+    Flag {
+        path: PathBuf,
+        before: Flag,
+        after: Flag,
+    },
+    /// Operations on several selected images, undone and redone as one step
+    Batch(Vec<FileOperation>),
 }
 
 #[derive(Debug, Default)]
@@ -239,6 +367,13 @@ impl UndoHistory {
                             to.parent().unwrap_or(to).display()
                         )
                     }
+                    FileOperation::Copy { from, to } => {
+                        format!(
+                            "Copy {} to {}",
+                            file_name(from),
+                            to.parent().unwrap_or(to).display()
+                        )
+                    }
                     FileOperation::Rename { from, to } => {
                         format!(
                             "Rename {} to {}",
@@ -246,6 +381,9 @@ impl UndoHistory {
                             file_name(to)
                         )
                     }
+                    FileOperation::BatchRename { renames } => {
+                        format!("Rename {} files", renames.len())
+                    }
                     FileOperation::Rotate { path, degrees, .. } => {
                         format!("Rotate {} by {}°", file_name(path), degrees)
                     }
@@ -260,6 +398,13 @@ impl UndoHistory {
                     FileOperation::Adjust { path, .. } => {
                         format!("Adjust {}", file_name(path))
                     }
+                    FileOperation::Rate { path, .. } => {
+                        format!("Rate {}", file_name(path))
+                    }
+                    FileOperation::Flag { path, after, .. } => {
+                        format!("Mark {} {}", file_name(path), after.name().to_lowercase())
+                    }
+                    FileOperation::Batch(ops) => format!("{} changes", ops.len()),
                 })
         } else {
             None
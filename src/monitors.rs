@@ -0,0 +1,77 @@
+/// A connected display, in the desktop's coordinates
+#[derive(Debug, Clone, PartialEq)]
+pub struct Monitor {
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub primary: bool,
+}
+
+impl Monitor {
+    /// Name with the size, as listed in settings
+    pub fn label(&self) -> String {
+        let primary = if self.primary { ", primary" } else { "" };
+        format!("{} ({}×{}{})", self.name, self.width, self.height, primary)
+    }
+
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.x as f32
+            && y >= self.y as f32
+            && x < self.x as f32 + self.width as f32
+            && y < self.y as f32 + self.height as f32
+    }
+
+    /// Where to put a window's corner so it lands on this monitor, in the
+    /// logical units of a window with `window_scale` pixels per point
+    pub fn window_position(&self, window_scale: f32) -> egui::Pos2 {
+        let scale = desktop_scale(window_scale);
+        egui::pos2(
+            (self.x as f32 + 40.0) / scale,
+            (self.y as f32 + 40.0) / scale,
+        )
+    }
+}
+
+/// Pixels of the desktop's coordinates per logical point. macOS reports
+/// monitors in points already.
+pub fn desktop_scale(window_scale: f32) -> f32 {
+    if cfg!(target_os = "macos") {
+        1.0
+    } else {
+        window_scale.max(0.1)
+    }
+}
+
+/// Connected monitors, primary first
+pub fn list() -> Vec<Monitor> {
+    let mut monitors: Vec<Monitor> = match display_info::DisplayInfo::all() {
+        Ok(displays) => displays
+            .into_iter()
+            .map(|display| Monitor {
+                name: display.name,
+                x: display.x,
+                y: display.y,
+                width: display.width,
+                height: display.height,
+                primary: display.is_primary,
+            })
+            .collect(),
+        Err(e) => {
+            log::warn!("Couldn't list monitors: {}", e);
+            Vec::new()
+        }
+    };
+    monitors.sort_by_key(|m| (!m.primary, m.x, m.y));
+    monitors
+}
+
+/// The monitor after the one named `current`, wrapping around; the first
+/// when `current` isn't connected
+pub fn next_monitor<'a>(monitors: &'a [Monitor], current: Option<&str>) -> Option<&'a Monitor> {
+    let next = current
+        .and_then(|name| monitors.iter().position(|m| m.name == name))
+        .map_or(0, |index| index + 1);
+    monitors.get(next % monitors.len().max(1))
+}
@@ -0,0 +1,285 @@
+use crate::errors::{Result, ViewerError};
+#[cfg(feature = "audio")]
+use rodio::{Decoder, OutputStream, Sink, Source};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "audio")]
+use std::fs::File;
+#[cfg(feature = "audio")]
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+/// Audio files that can be played during slideshows
+pub const AUDIO_EXTENSIONS: &[&str] = &["mp3", "flac", "wav", "ogg"];
+
+/// Tempo search range for beat snapping
+const MIN_BPM: f32 = 60.0;
+const MAX_BPM: f32 = 180.0;
+/// Seconds of each track listened to when estimating its tempo
+const ANALYSIS_SECONDS: u32 = 60;
+
+/// How the slideshow's timing follows the music
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SlideTiming {
+    /// The slideshow's own interval
+    #[default]
+    Interval,
+    /// The interval rounded to whole beats, changing slides on the beat
+    Beats,
+    /// Each track's own seconds per slide
+    PerTrack,
+}
+
+impl SlideTiming {
+    pub fn all() -> &'static [SlideTiming] {
+        &[
+            SlideTiming::Interval,
+            SlideTiming::Beats,
+            SlideTiming::PerTrack,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SlideTiming::Interval => "Slideshow interval",
+            SlideTiming::Beats => "Snap to beats",
+            SlideTiming::PerTrack => "Per-track schedule",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MusicTrack {
+    pub path: PathBuf,
+    /// Tempo, estimated when the track is added and editable after
+    pub bpm: Option<f32>,
+    /// Seconds per slide while this track plays, for the per-track schedule
+    pub slide_seconds: f32,
+}
+
+impl MusicTrack {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            bpm: None,
+            slide_seconds: 5.0,
+        }
+    }
+}
+
+/// Background music played while a slideshow runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MusicSettings {
+    pub enabled: bool,
+    /// Played in order, looping back to the first
+    pub tracks: Vec<MusicTrack>,
+    pub volume: f32,
+    pub muted: bool,
+    pub timing: SlideTiming,
+}
+
+impl Default for MusicSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            tracks: Vec::new(),
+            volume: 0.7,
+            muted: false,
+            timing: SlideTiming::Interval,
+        }
+    }
+}
+
+impl MusicSettings {
+    /// Seconds between slides while `track` plays, or None when the
+    /// slideshow keeps to its own `interval`
+    pub fn slide_seconds(&self, track: &Path, interval: f32) -> Option<f32> {
+        let track = self.tracks.iter().find(|t| t.path == track)?;
+        match self.timing {
+            SlideTiming::Interval => None,
+            SlideTiming::Beats => track.bpm.map(|bpm| snap_to_beats(interval, bpm)),
+            SlideTiming::PerTrack => Some(track.slide_seconds.max(0.5)),
+        }
+    }
+
+    pub fn output_volume(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.volume.clamp(0.0, 1.0)
+        }
+    }
+}
+
+/// `seconds` rounded to a whole number of beats at `bpm`, at least one
+pub fn snap_to_beats(seconds: f32, bpm: f32) -> f32 {
+    let beat = 60.0 / bpm.clamp(1.0, 1000.0);
+    (seconds / beat).round().max(1.0) * beat
+}
+
+/// Estimate the tempo of mono `samples` from how the onsets of loud sounds
+/// repeat. None for silence or no steady beat.
+pub fn estimate_bpm(samples: &[f32], sample_rate: u32) -> Option<f32> {
+    // Energy rise over 10ms hops
+    let hop = (sample_rate / 100).max(1) as usize;
+    let energy: Vec<f32> = samples
+        .chunks(hop)
+        .map(|chunk| chunk.iter().map(|s| s * s).sum())
+        .collect();
+    let onsets: Vec<f32> = energy
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).max(0.0))
+        .collect();
+
+    // The most self-similar beat length. Unnormalized sums favour the
+    // shorter of two lags that are multiples of each other.
+    let hops_per_minute = 60.0 * 100.0;
+    let shortest = (hops_per_minute / MAX_BPM).floor() as usize;
+    let longest = (hops_per_minute / MIN_BPM).ceil() as usize;
+    let (lag, score) = (shortest..=longest.min(onsets.len().saturating_sub(1)))
+        .map(|lag| {
+            let score: f32 = onsets.iter().zip(&onsets[lag..]).map(|(a, b)| a * b).sum();
+            (lag, score)
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+    if score <= f32::EPSILON {
+        return None;
+    }
+    Some((hops_per_minute / lag as f32 * 10.0).round() / 10.0)
+}
+
+#[cfg(feature = "audio")]
+fn open_track(path: &Path) -> Result<Decoder<BufReader<File>>> {
+    let file = File::open(path)?;
+    Decoder::new(BufReader::new(file)).map_err(|e| ViewerError::AudioError {
+        message: format!("{}: {}", path.display(), e),
+    })
+}
+
+/// Decode the start of an audio file and estimate its tempo
+#[cfg(feature = "audio")]
+pub fn analyze_track(path: &Path) -> Result<Option<f32>> {
+    let decoder = open_track(path)?;
+    let channels = decoder.channels().max(1) as usize;
+    let sample_rate = decoder.sample_rate();
+    let limit = (sample_rate * ANALYSIS_SECONDS) as usize * channels;
+    let interleaved: Vec<f32> = decoder
+        .take(limit)
+        .map(|s| s as f32 / i16::MAX as f32)
+        .collect();
+    let mono: Vec<f32> = interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect();
+    Ok(estimate_bpm(&mono, sample_rate))
+}
+
+#[cfg(not(feature = "audio"))]
+pub fn analyze_track(_path: &Path) -> Result<Option<f32>> {
+    Err(no_audio())
+}
+
+#[cfg(not(feature = "audio"))]
+fn no_audio() -> ViewerError {
+    ViewerError::AudioError {
+        message: "rView was built without the audio feature".to_string(),
+    }
+}
+
+/// Plays the slideshow's tracks one after another on the default output
+#[cfg(feature = "audio")]
+pub struct MusicPlayer {
+    // Dropping the stream stops playback, so it lives as long as the sink
+    _stream: OutputStream,
+    sink: Sink,
+    tracks: Vec<PathBuf>,
+    current: usize,
+    /// Set once no track could be opened, so it isn't retried every frame
+    exhausted: bool,
+}
+
+#[cfg(feature = "audio")]
+impl MusicPlayer {
+    pub fn start(tracks: Vec<PathBuf>, volume: f32) -> Result<Self> {
+        let audio_error = |message: String| ViewerError::AudioError { message };
+        let (stream, handle) =
+            OutputStream::try_default().map_err(|e| audio_error(e.to_string()))?;
+        let sink = Sink::try_new(&handle).map_err(|e| audio_error(e.to_string()))?;
+        sink.set_volume(volume);
+        let mut player = Self {
+            _stream: stream,
+            sink,
+            current: tracks.len().saturating_sub(1),
+            tracks,
+            exhausted: false,
+        };
+        // Begins with the first track that can be played
+        if !player.update() {
+            return Err(audio_error("none of the tracks could be played".into()));
+        }
+        Ok(player)
+    }
+
+    /// Move on to the next track, looping, once the current one has ended.
+    /// True when a new track started.
+    pub fn update(&mut self) -> bool {
+        if self.exhausted || !self.sink.empty() {
+            return false;
+        }
+        for step in 1..=self.tracks.len() {
+            let index = (self.current + step) % self.tracks.len();
+            match open_track(&self.tracks[index]) {
+                Ok(source) => {
+                    self.sink.append(source);
+                    self.current = index;
+                    return true;
+                }
+                Err(e) => log::warn!("Skipping slideshow music: {}", e),
+            }
+        }
+        self.exhausted = true;
+        false
+    }
+
+    pub fn current_track(&self) -> &Path {
+        &self.tracks[self.current]
+    }
+
+    /// Seconds into the current track
+    pub fn position(&self) -> f32 {
+        self.sink.get_pos().as_secs_f32()
+    }
+
+    pub fn set_volume(&self, volume: f32) {
+        self.sink.set_volume(volume);
+    }
+}
+
+/// Without the audio feature no player can be started
+#[cfg(not(feature = "audio"))]
+pub struct MusicPlayer {
+    never: std::convert::Infallible,
+}
+
+#[cfg(not(feature = "audio"))]
+impl MusicPlayer {
+    pub fn start(_tracks: Vec<PathBuf>, _volume: f32) -> Result<Self> {
+        Err(no_audio())
+    }
+
+    pub fn update(&mut self) -> bool {
+        match self.never {}
+    }
+
+    pub fn current_track(&self) -> &Path {
+        match self.never {}
+    }
+
+    pub fn position(&self) -> f32 {
+        match self.never {}
+    }
+
+    pub fn set_volume(&self, _volume: f32) {
+        match self.never {}
+    }
+}
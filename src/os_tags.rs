@@ -0,0 +1,125 @@
+use crate::errors::Result;
+use crate::metadata::ImageMetadata;
+use crate::settings::ColorLabel;
+use std::path::Path;
+
+/// Name of the file manager that shows what `write_tags` writes, on systems that have one
+pub fn file_manager_name() -> Option<&'static str> {
+    if cfg!(windows) {
+        Some("Explorer")
+    } else if cfg!(target_os = "macos") {
+        Some("Finder")
+    } else {
+        None
+    }
+}
+
+/// Explorer's `System.Rating` value for a star rating
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn explorer_rating(stars: u8) -> u32 {
+    match stars {
+        0 => 0,
+        1 => 1,
+        2 => 25,
+        3 => 50,
+        4 => 75,
+        _ => 99,
+    }
+}
+
+/// Finder's color number for a label
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn finder_color(label: ColorLabel) -> Option<u8> {
+    match label {
+        ColorLabel::None => None,
+        ColorLabel::Green => Some(2),
+        ColorLabel::Purple => Some(3),
+        ColorLabel::Blue => Some(4),
+        ColorLabel::Yellow => Some(5),
+        ColorLabel::Red => Some(6),
+    }
+}
+
+/// Finder tags with the color label swapped in. Tags are stored as
+/// `name\ncolor`; tags other than the label colors are kept.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub fn finder_tags(existing: &[String], label: ColorLabel) -> Vec<String> {
+    let is_label = |tag: &String| {
+        let name = tag.split('\n').next().unwrap_or_default();
+        ColorLabel::all()
+            .iter()
+            .any(|l| finder_color(*l).is_some() && l.name() == name)
+    };
+    let mut tags: Vec<String> = existing.iter().filter(|t| !is_label(t)).cloned().collect();
+    if let Some(color) = finder_color(label) {
+        tags.push(format!("{}\n{}", label.name(), color));
+    }
+    tags
+}
+
+/// Mirror the rating to Explorer's star rating on Windows, or the color
+/// label to a Finder tag on macOS. Does nothing elsewhere.
+pub fn write_tags(image: &Path, metadata: &ImageMetadata) -> Result<()> {
+    #[cfg(windows)]
+    write_explorer_rating(image, metadata.rating)?;
+    #[cfg(target_os = "macos")]
+    write_finder_tags(image, metadata.color_label)?;
+    #[cfg(not(any(windows, target_os = "macos")))]
+    let _ = (image, metadata);
+    Ok(())
+}
+
+#[cfg(windows)]
+fn write_explorer_rating(image: &Path, stars: u8) -> Result<()> {
+    use crate::errors::ViewerError;
+    use windows::core::{HSTRING, PROPVARIANT};
+    use windows::Win32::Storage::EnhancedStorage::PKEY_Rating;
+    use windows::Win32::System::Com::{CoInitializeEx, COINIT_MULTITHREADED};
+    use windows::Win32::UI::Shell::PropertiesSystem::{
+        IPropertyStore, SHGetPropertyStoreFromParsingName, GPS_READWRITE,
+    };
+
+    let failed = |e: windows::core::Error| ViewerError::MetadataError {
+        message: format!("{}: {}", image.display(), e.message()),
+    };
+    let value = PROPVARIANT::from(explorer_rating(stars));
+    // SAFETY: COM is initialized for this thread before the property store is
+    // used, and the store is released before the thread ends
+    unsafe {
+        // Already initialized threads keep their model; either way COM is usable
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+        let store: IPropertyStore =
+            SHGetPropertyStoreFromParsingName(&HSTRING::from(image), None, GPS_READWRITE)
+                .map_err(failed)?;
+        store.SetValue(&PKEY_Rating, &value).map_err(failed)?;
+        store.Commit().map_err(failed)?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn write_finder_tags(image: &Path, label: ColorLabel) -> Result<()> {
+    use crate::errors::ViewerError;
+
+    const TAGS_ATTRIBUTE: &str = "com.apple.metadata:_kMDItemUserTags";
+
+    let invalid = |message: String| ViewerError::MetadataError {
+        message: format!("{}: {}", image.display(), message),
+    };
+    let existing: Vec<String> = match xattr::get(image, TAGS_ATTRIBUTE)? {
+        Some(bytes) => plist::from_bytes(&bytes).map_err(|e| invalid(e.to_string()))?,
+        None => Vec::new(),
+    };
+    let tags = finder_tags(&existing, label);
+    if tags == existing {
+        return Ok(());
+    }
+    if tags.is_empty() {
+        xattr::remove(image, TAGS_ATTRIBUTE)?;
+        return Ok(());
+    }
+    let mut bytes = Vec::new();
+    plist::to_writer_binary(&mut bytes, &tags).map_err(|e| invalid(e.to_string()))?;
+    xattr::set(image, TAGS_ATTRIBUTE, &bytes)?;
+    Ok(())
+}
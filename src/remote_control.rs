@@ -0,0 +1,387 @@
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+pub const DEFAULT_PORT: u16 = 8686;
+
+/// How long a request waits for the window to carry out its command
+const REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Requests are a line and a few headers; anything longer is refused
+const MAX_REQUEST_BYTES: usize = 16 * 1024;
+
+/// Connections handled at once; more are closed until one finishes, so a
+/// client that never sends its request can't hold up the others
+const MAX_CONNECTIONS: usize = 8;
+
+/// Buttons for a phone or tablet browser, served at `/`
+const REMOTE_PAGE: &str = r#"<!DOCTYPE html>
+<html><head><meta charset="utf-8"><meta name="viewport" content="width=device-width, initial-scale=1">
+<title>rView Remote</title>
+<style>
+body { margin: 0; padding: 16px; background: #1b1b1b; color: #ddd; font-family: sans-serif; text-align: center; }
+#name { font-size: 18px; margin: 12px 0; word-break: break-all; }
+button { font-size: 22px; margin: 6px; padding: 14px 22px; border: 0; border-radius: 8px; background: #333; color: #eee; }
+</style></head>
+<body>
+<div id="name">&nbsp;</div>
+<div><button onclick="send('prev')">&larr;</button><button onclick="send('next')">&rarr;</button></div>
+<div id="stars"></div>
+<div><button onclick="send('slideshow/toggle')">Slideshow</button></div>
+<script>
+const token = new URLSearchParams(location.search).get('token');
+const query = token ? '?token=' + encodeURIComponent(token) : '';
+function show(s) {
+  document.getElementById('name').textContent = s.total ? s.index + ' / ' + s.total + '  ' + s.name : 'No image';
+  document.getElementById('stars').innerHTML = [1, 2, 3, 4, 5].map(n =>
+    '<button onclick="send(\'rate/' + (n == s.rating ? 0 : n) + '\')">' + (n <= s.rating ? '&#9733;' : '&#9734;') + '</button>').join('');
+}
+function send(path) { fetch('/' + path + query, { method: 'POST' }).then(r => r.json()).then(show); }
+function poll() { fetch('/current' + query).then(r => r.json()).then(show).catch(() => {}); }
+poll();
+setInterval(poll, 2000);
+</script>
+</body></html>
+"#;
+
+/// Settings for the HTTP server that lets other devices drive the viewer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RemoteControlSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// Listen on every network interface instead of only this computer
+    pub allow_lan: bool,
+    /// Requests must carry it as `?token=` or a bearer token; one is
+    /// generated when the server starts without it
+    pub token: String,
+}
+
+impl Default for RemoteControlSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_PORT,
+            allow_lan: false,
+            token: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RemoteCommand {
+    Next,
+    Previous,
+    First,
+    Last,
+    /// Position in the filtered list, from 0
+    GoTo(usize),
+    Rate(u8),
+    /// Seconds per image, or the usual interval
+    StartSlideshow(Option<f32>),
+    StopSlideshow,
+    ToggleSlideshow,
+    /// Change nothing, just report the current image
+    Status,
+}
+
+/// What a request asks for
+#[derive(Debug, Clone, PartialEq)]
+pub enum Route {
+    Page,
+    /// The current image file
+    Image,
+    Command(RemoteCommand),
+}
+
+/// The current image, as reported to remotes after every command
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RemoteStatus {
+    pub path: Option<PathBuf>,
+    pub name: String,
+    /// Position in the filtered list, from 1; 0 when nothing is shown
+    pub index: usize,
+    pub total: usize,
+    pub rating: u8,
+    pub color_label: String,
+    pub flag: String,
+    pub slideshow: bool,
+}
+
+/// A command from a remote, answered with the status once it is carried out
+pub struct RemoteRequest {
+    pub command: RemoteCommand,
+    pub reply: mpsc::Sender<RemoteStatus>,
+}
+
+/// A running server; dropping it stops listening and frees the port
+pub struct RemoteServer {
+    pub address: SocketAddr,
+    stop: Arc<AtomicBool>,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl Drop for RemoteServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Route for a request line's method and target. Commands that change
+/// anything have to be POSTed, so a link or image tag can't trigger them.
+pub fn parse_route(method: &str, target: &str) -> Result<Route, (u16, &'static str)> {
+    let route = route_for(target)?;
+    let read_only = matches!(
+        route,
+        Route::Page | Route::Image | Route::Command(RemoteCommand::Status)
+    );
+    match method {
+        "POST" => Ok(route),
+        "GET" if read_only => Ok(route),
+        _ => Err((405, "Method Not Allowed")),
+    }
+}
+
+fn route_for(target: &str) -> Result<Route, (u16, &'static str)> {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let number = |text: &str| text.parse::<usize>().map_err(|_| (400, "Bad Request"));
+
+    let command = match segments.as_slice() {
+        [] => return Ok(Route::Page),
+        ["current", "image"] => return Ok(Route::Image),
+        ["current"] => RemoteCommand::Status,
+        ["next"] => RemoteCommand::Next,
+        ["prev"] | ["previous"] => RemoteCommand::Previous,
+        ["first"] => RemoteCommand::First,
+        ["last"] => RemoteCommand::Last,
+        ["goto", n] => match number(n)? {
+            0 => return Err((400, "Bad Request")),
+            n => RemoteCommand::GoTo(n - 1),
+        },
+        ["rate", n] => match number(n)? {
+            n @ 0..=5 => RemoteCommand::Rate(n as u8),
+            _ => return Err((400, "Bad Request")),
+        },
+        ["slideshow", "start"] => {
+            let seconds = match query_param(query, "seconds") {
+                Some(s) => match s.parse::<f32>() {
+                    Ok(s) if s > 0.0 => Some(s),
+                    _ => return Err((400, "Bad Request")),
+                },
+                None => None,
+            };
+            RemoteCommand::StartSlideshow(seconds)
+        }
+        ["slideshow", "stop"] => RemoteCommand::StopSlideshow,
+        ["slideshow"] | ["slideshow", "toggle"] => RemoteCommand::ToggleSlideshow,
+        _ => return Err((404, "Not Found")),
+    };
+    Ok(Route::Command(command))
+}
+
+/// Value of `name` in a URL query, without decoding
+pub fn query_param<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+/// Whether a request carries the token, as a query parameter or a bearer
+/// `Authorization` header. Nothing is let in without a token.
+pub fn authorized(token: &str, target: &str, headers: &str) -> bool {
+    if token.is_empty() {
+        return false;
+    }
+    let query = target.split_once('?').map_or("", |(_, q)| q);
+    if query_param(query, "token").is_some_and(|given| tokens_match(given, token)) {
+        return true;
+    }
+    headers.lines().any(|line| {
+        line.split_once(':').is_some_and(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("authorization")
+                && value
+                    .trim()
+                    .strip_prefix("Bearer ")
+                    .is_some_and(|given| tokens_match(given, token))
+        })
+    })
+}
+
+/// Compare tokens in time that doesn't depend on where they first differ,
+/// so the token can't be guessed a character at a time
+fn tokens_match(given: &str, token: &str) -> bool {
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Listen for remotes on a background thread, passing each command to
+/// `on_command` to carry out on the UI thread
+pub fn serve<F>(settings: &RemoteControlSettings, on_command: F) -> std::io::Result<RemoteServer>
+where
+    F: Fn(RemoteRequest) + Send + Sync + 'static,
+{
+    if settings.token.is_empty() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "a token is required",
+        ));
+    }
+    let host = if settings.allow_lan {
+        Ipv4Addr::UNSPECIFIED
+    } else {
+        Ipv4Addr::LOCALHOST
+    };
+    let listener = TcpListener::bind((host, settings.port))?;
+    let address = listener.local_addr()?;
+    // Polled, so the thread notices when the server is dropped
+    listener.set_nonblocking(true)?;
+    let stop = Arc::new(AtomicBool::new(false));
+    let token: Arc<str> = settings.token.as_str().into();
+    let on_command = Arc::new(on_command);
+    let connections = Arc::new(AtomicUsize::new(0));
+
+    let stopped = Arc::clone(&stop);
+    let thread = std::thread::spawn(move || {
+        while !stopped.load(Ordering::Relaxed) {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    if connections.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                        connections.fetch_sub(1, Ordering::SeqCst);
+                        continue;
+                    }
+                    // Each on its own thread, so a slow client doesn't block the rest
+                    let (token, on_command) = (Arc::clone(&token), Arc::clone(&on_command));
+                    let connections = Arc::clone(&connections);
+                    std::thread::spawn(move || {
+                        if let Err(e) = handle_connection(stream, &token, &*on_command) {
+                            log::debug!("Remote control request failed: {}", e);
+                        }
+                        connections.fetch_sub(1, Ordering::SeqCst);
+                    });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    std::thread::sleep(Duration::from_millis(50));
+                }
+                Err(e) => {
+                    log::warn!("Remote control stopped: {}", e);
+                    break;
+                }
+            }
+        }
+    });
+    Ok(RemoteServer {
+        address,
+        stop,
+        thread: Some(thread),
+    })
+}
+
+fn handle_connection<F>(mut stream: TcpStream, token: &str, on_command: &F) -> std::io::Result<()>
+where
+    F: Fn(RemoteRequest),
+{
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(REPLY_TIMEOUT))?;
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 2048];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+        let read = stream.read(&mut buffer)?;
+        if read == 0 || request.len() + read > MAX_REQUEST_BYTES {
+            return respond(
+                &mut stream,
+                400,
+                "Bad Request",
+                "text/plain",
+                b"Bad Request",
+            );
+        }
+        request.extend_from_slice(&buffer[..read]);
+    }
+    let request = String::from_utf8_lossy(&request);
+    let (line, headers) = request.split_once("\r\n").unwrap_or((&request, ""));
+    let mut parts = line.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or("/"));
+
+    if !authorized(token, target, headers) {
+        return respond(
+            &mut stream,
+            401,
+            "Unauthorized",
+            "text/plain",
+            b"Unauthorized",
+        );
+    }
+    let route = match parse_route(method, target) {
+        Ok(route) => route,
+        Err((code, reason)) => {
+            return respond(&mut stream, code, reason, "text/plain", reason.as_bytes())
+        }
+    };
+
+    let command = match &route {
+        Route::Page => {
+            let page = REMOTE_PAGE.as_bytes();
+            return respond(&mut stream, 200, "OK", "text/html; charset=utf-8", page);
+        }
+        Route::Image => RemoteCommand::Status,
+        Route::Command(command) => command.clone(),
+    };
+    let (reply, replies) = mpsc::channel();
+    on_command(RemoteRequest { command, reply });
+    let Ok(status) = replies.recv_timeout(REPLY_TIMEOUT) else {
+        return respond(
+            &mut stream,
+            503,
+            "Service Unavailable",
+            "text/plain",
+            b"Busy",
+        );
+    };
+
+    if route == Route::Image {
+        let image = status
+            .path
+            .as_ref()
+            .and_then(|p| Some((std::fs::read(p).ok()?, crate::share::content_type(p))));
+        let Some((bytes, content_type)) = image else {
+            return respond(&mut stream, 404, "Not Found", "text/plain", b"No image");
+        };
+        return respond(&mut stream, 200, "OK", content_type, &bytes);
+    }
+    let json = serde_json::to_vec(&status).unwrap_or_default();
+    respond(&mut stream, 200, "OK", "application/json", &json)
+}
+
+fn respond(
+    stream: &mut TcpStream,
+    code: u16,
+    reason: &str,
+    content_type: &str,
+    body: &[u8],
+) -> std::io::Result<()> {
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n\
+         Cache-Control: no-store\r\nConnection: close\r\n\r\n",
+        code,
+        reason,
+        content_type,
+        body.len()
+    );
+    stream.write_all(head.as_bytes())?;
+    stream.write_all(body)?;
+    stream.flush()
+}
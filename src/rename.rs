@@ -0,0 +1,230 @@
+use crate::exif_data::ExifInfo;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+lazy_static::lazy_static! {
+    static ref TOKEN: Regex = Regex::new(r"\{(orig|seq|date|camera)(?::(\d{1,2}))?\}").unwrap();
+}
+
+/// Tokens offered in the rename dialog, with a short description
+pub const TOKENS: &[(&str, &str)] = &[
+    ("{orig}", "Original file name"),
+    ("{seq}", "Sequence number; {seq:4} pads to 4 digits"),
+    (
+        "{date}",
+        "Capture date (YYYY-MM-DD), or the file date without EXIF",
+    ),
+    ("{camera}", "Camera model"),
+];
+
+/// Characters that aren't allowed in file names on at least one platform
+const INVALID_CHARS: &[char] = &['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RenamePattern {
+    pub template: String,
+    /// First sequence number
+    pub start: u32,
+    /// Digits for `{seq}` unless the token gives its own
+    pub padding: usize,
+}
+
+impl Default for RenamePattern {
+    fn default() -> Self {
+        Self {
+            template: "{orig}".to_string(),
+            start: 1,
+            padding: 3,
+        }
+    }
+}
+
+/// Per-file values for the `{date}` and `{camera}` tokens
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RenameInfo {
+    pub date: Option<String>,
+    pub camera: Option<String>,
+}
+
+impl RenameInfo {
+    pub fn from_file(path: &Path) -> Self {
+        let exif = ExifInfo::from_file(path);
+        // EXIF dates look like `2024:05:01 12:00:00`
+        let date = exif
+            .date_taken
+            .as_deref()
+            .filter(|d| d.len() >= 10)
+            .map(|d| d[..10].replace(':', "-"))
+            .or_else(|| {
+                let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+                let modified: chrono::DateTime<chrono::Local> = modified.into();
+                Some(modified.format("%Y-%m-%d").to_string())
+            });
+        Self {
+            date,
+            camera: exif.camera_model.filter(|c| !c.trim().is_empty()),
+        }
+    }
+}
+
+impl RenamePattern {
+    /// Whether rendering needs EXIF or file dates
+    pub fn needs_info(&self) -> bool {
+        self.template.contains("{date") || self.template.contains("{camera")
+    }
+
+    /// New file stem for one image; unknown tokens are kept as written
+    pub fn render(&self, original: &str, seq: u32, info: &RenameInfo) -> String {
+        let name = TOKEN.replace_all(&self.template, |caps: &Captures| match &caps[1] {
+            "orig" => original.to_string(),
+            "seq" => {
+                let width = caps
+                    .get(2)
+                    .and_then(|w| w.as_str().parse().ok())
+                    .unwrap_or(self.padding);
+                format!("{:0width$}", seq, width = width)
+            }
+            "date" => info.date.clone().unwrap_or_else(|| "undated".to_string()),
+            "camera" => info
+                .camera
+                .as_deref()
+                .map(|c| c.trim().replace(' ', "_"))
+                .unwrap_or_else(|| "unknown".to_string()),
+            _ => caps[0].to_string(),
+        });
+        name.chars()
+            .map(|c| if INVALID_CHARS.contains(&c) { '_' } else { c })
+            .collect::<String>()
+            .trim()
+            .to_string()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenameConflict {
+    EmptyName,
+    /// Another file in the batch gets the same name
+    Duplicate,
+    /// A file outside the batch already has this name
+    Exists,
+}
+
+impl RenameConflict {
+    pub fn description(&self) -> &'static str {
+        match self {
+            RenameConflict::EmptyName => "empty name",
+            RenameConflict::Duplicate => "duplicate name",
+            RenameConflict::Exists => "file already exists",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedRename {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    pub conflict: Option<RenameConflict>,
+}
+
+impl PlannedRename {
+    pub fn is_change(&self) -> bool {
+        self.from != self.to
+    }
+}
+
+/// Work out the new name of every source, flagging names that would collide.
+/// Names are compared case-insensitively since Windows and macOS file systems are.
+pub fn plan_renames(
+    sources: &[PathBuf],
+    pattern: &RenamePattern,
+    infos: &[RenameInfo],
+) -> Vec<PlannedRename> {
+    let default_info = RenameInfo::default();
+    let mut plans: Vec<PlannedRename> = sources
+        .iter()
+        .enumerate()
+        .map(|(i, from)| {
+            let original = from
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let info = infos.get(i).unwrap_or(&default_info);
+            let stem = pattern.render(&original, pattern.start.saturating_add(i as u32), info);
+            let name = match from.extension() {
+                Some(ext) => format!("{}.{}", stem, ext.to_string_lossy()),
+                None => stem.clone(),
+            };
+            PlannedRename {
+                from: from.clone(),
+                to: from.with_file_name(name),
+                conflict: stem.is_empty().then_some(RenameConflict::EmptyName),
+            }
+        })
+        .collect();
+
+    let key = |path: &Path| path.to_string_lossy().to_lowercase();
+    let moving: HashSet<String> = sources.iter().map(|p| key(p.as_path())).collect();
+    let mut targets: HashMap<String, usize> = HashMap::new();
+    for plan in &plans {
+        *targets.entry(key(&plan.to)).or_default() += 1;
+    }
+
+    for plan in plans.iter_mut().filter(|p| p.conflict.is_none()) {
+        let target = key(&plan.to);
+        if targets[&target] > 1 {
+            plan.conflict = Some(RenameConflict::Duplicate);
+        } else if plan.to.exists() && !moving.contains(&target) {
+            plan.conflict = Some(RenameConflict::Exists);
+        }
+    }
+    plans
+}
+
+/// Rename every `(from, to)` pair, going through temporary names so that
+/// swaps and chains like a→b, b→c work. On failure everything is put back.
+pub fn execute_renames(pairs: &[(PathBuf, PathBuf)]) -> Result<(), String> {
+    let pairs: Vec<&(PathBuf, PathBuf)> = pairs.iter().filter(|(from, to)| from != to).collect();
+    let temps: Vec<PathBuf> = pairs
+        .iter()
+        .enumerate()
+        .map(|(i, (from, _))| {
+            let name = from.file_name().unwrap_or_default().to_string_lossy();
+            from.with_file_name(format!(".rview-rename-{}-{}", i, name))
+        })
+        .collect();
+
+    // Phase one: every source to its temporary name
+    for (i, ((from, _), temp)) in pairs.iter().zip(&temps).enumerate() {
+        if let Err(e) = std::fs::rename(from, temp) {
+            for ((from, _), temp) in pairs[..i].iter().zip(&temps) {
+                let _ = std::fs::rename(temp, from);
+            }
+            return Err(format!("{}: {}", from.display(), e));
+        }
+    }
+
+    // Phase two: temporary names to the targets, refusing to overwrite anything
+    for (i, ((_, to), temp)) in pairs.iter().zip(&temps).enumerate() {
+        let result = if to.exists() {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                "file already exists",
+            ))
+        } else {
+            std::fs::rename(temp, to)
+        };
+        if let Err(e) = result {
+            for ((_, to), temp) in pairs[..i].iter().zip(&temps) {
+                let _ = std::fs::rename(to, temp);
+            }
+            for ((from, _), temp) in pairs.iter().zip(&temps) {
+                let _ = std::fs::rename(temp, from);
+            }
+            return Err(format!("{}: {}", to.display(), e));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,211 @@
+use crate::image_loader::ImageAdjustments;
+use crate::metadata::ImageMetadata;
+use mlua::{HookTriggers, Lua, LuaOptions, LuaSerdeExt, StdLib, VmState};
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// How long a script may run before it is stopped
+pub const SCRIPT_TIME_LIMIT: Duration = Duration::from_secs(10);
+
+/// Memory a script may allocate
+const SCRIPT_MEMORY_LIMIT: usize = 256 * 1024 * 1024;
+
+/// A Lua script from the scripts folder, run from the command palette
+#[derive(Debug, Clone, PartialEq)]
+pub struct Script {
+    pub path: PathBuf,
+    pub title: String,
+}
+
+/// What a script can see of the viewer
+#[derive(Debug, Clone, Default)]
+pub struct ScriptContext {
+    pub path: Option<PathBuf>,
+    pub folder: Option<PathBuf>,
+    pub selection: Vec<PathBuf>,
+    /// Rating, label, keywords and notes of the current image
+    pub metadata: ImageMetadata,
+    /// EXIF fields of the current image that have a value
+    pub exif: Vec<(&'static str, String)>,
+    pub adjustments: ImageAdjustments,
+}
+
+/// A change a script asked for, made once it has finished
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptEffect {
+    Status(String),
+    SetAdjustments(PathBuf, ImageAdjustments),
+    SetRating(PathBuf, u8),
+    AddKeyword(PathBuf, String),
+    Rename(PathBuf, PathBuf),
+    Open(PathBuf),
+    CopyText(String),
+}
+
+/// Where users keep their `.lua` scripts
+pub fn scripts_folder() -> Option<PathBuf> {
+    directories::ProjectDirs::from("com", "imageviewer", "ImageViewer")
+        .map(|proj_dirs| proj_dirs.config_dir().join("scripts"))
+}
+
+/// Title from a `-- title: ...` line in the script's opening comments,
+/// otherwise the file name
+pub fn script_title(source: &str, path: &Path) -> String {
+    source
+        .lines()
+        .map(str::trim)
+        .take_while(|line| line.starts_with("--"))
+        .find_map(|line| {
+            let (key, value) = line.trim_start_matches('-').split_once(':')?;
+            (key.trim().eq_ignore_ascii_case("title") && !value.trim().is_empty())
+                .then(|| value.trim().to_string())
+        })
+        .unwrap_or_else(|| {
+            path.file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default()
+        })
+}
+
+/// Scripts in `folder`, sorted by title
+pub fn find_scripts(folder: &Path) -> Vec<Script> {
+    let Ok(entries) = std::fs::read_dir(folder) else {
+        return Vec::new();
+    };
+    let mut scripts: Vec<Script> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case("lua"))
+        })
+        .filter_map(|path| {
+            let source = std::fs::read_to_string(&path).ok()?;
+            let title = script_title(&source, &path);
+            Some(Script { path, title })
+        })
+        .collect();
+    scripts.sort_by_key(|s| s.title.to_lowercase());
+    scripts
+}
+
+/// Run a script with the `rview` table describing `context`, returning the
+/// changes it asked for. Edits to `rview.adjustments` apply to the current image.
+///
+/// Scripts only get the table, string and math libraries, so they can't touch
+/// files or run programs except through `rview`, and are stopped after `limit`.
+pub fn run_script(
+    source: &str,
+    name: &str,
+    context: &ScriptContext,
+    limit: Duration,
+) -> Result<Vec<ScriptEffect>, String> {
+    let libs = StdLib::TABLE | StdLib::STRING | StdLib::MATH;
+    let lua = Lua::new_with(libs, LuaOptions::default()).map_err(|e| e.to_string())?;
+    lua.set_memory_limit(SCRIPT_MEMORY_LIMIT)
+        .map_err(|e| e.to_string())?;
+    // The base library is always loaded; these two read files
+    for unsafe_global in ["dofile", "loadfile"] {
+        lua.globals()
+            .set(unsafe_global, mlua::Nil)
+            .map_err(|e| e.to_string())?;
+    }
+    let deadline = Instant::now() + limit;
+    lua.set_hook(
+        HookTriggers::new().every_nth_instruction(10_000),
+        move |_, _| {
+            if Instant::now() < deadline {
+                Ok(VmState::Continue)
+            } else {
+                Err(mlua::Error::runtime(format!(
+                    "stopped after {} seconds",
+                    limit.as_secs_f32()
+                )))
+            }
+        },
+    );
+
+    let effects = Rc::new(RefCell::new(Vec::new()));
+    let rview = build_api(&lua, context, &effects).map_err(|e| e.to_string())?;
+    lua.globals()
+        .set("rview", rview.clone())
+        .map_err(|e| e.to_string())?;
+    lua.load(source)
+        .set_name(name)
+        .exec()
+        .map_err(|e| e.to_string())?;
+
+    let mut effects = effects.take();
+    if let Some(path) = &context.path {
+        let value: mlua::Value = rview.get("adjustments").map_err(|e| e.to_string())?;
+        let adjustments: ImageAdjustments = lua
+            .from_value(value)
+            .map_err(|e| format!("rview.adjustments: {}", e))?;
+        if adjustments != context.adjustments {
+            effects.push(ScriptEffect::SetAdjustments(path.clone(), adjustments));
+        }
+    }
+    Ok(effects)
+}
+
+fn build_api(
+    lua: &Lua,
+    context: &ScriptContext,
+    effects: &Rc<RefCell<Vec<ScriptEffect>>>,
+) -> mlua::Result<mlua::Table> {
+    let path_string = |path: &Path| path.to_string_lossy().to_string();
+    let rview = lua.create_table()?;
+    rview.set("path", context.path.as_deref().map(path_string))?;
+    rview.set("folder", context.folder.as_deref().map(path_string))?;
+    let selection: Vec<String> = context.selection.iter().map(|p| path_string(p)).collect();
+    rview.set("selection", selection)?;
+
+    let mut metadata = context.metadata.clone();
+    metadata.adjustments = None;
+    rview.set("metadata", lua.to_value(&metadata)?)?;
+    let exif = lua.create_table()?;
+    for (key, value) in &context.exif {
+        exif.set(*key, value.as_str())?;
+    }
+    rview.set("exif", exif)?;
+    rview.set("adjustments", lua.to_value(&context.adjustments)?)?;
+
+    let push = |effect: fn(String, String) -> Option<ScriptEffect>| {
+        let effects = Rc::clone(effects);
+        lua.create_function(move |_, (a, b): (String, Option<String>)| {
+            if let Some(effect) = effect(a, b.unwrap_or_default()) {
+                effects.borrow_mut().push(effect);
+            }
+            Ok(())
+        })
+    };
+    rview.set("status", push(|text, _| Some(ScriptEffect::Status(text)))?)?;
+    rview.set("copy", push(|text, _| Some(ScriptEffect::CopyText(text)))?)?;
+    rview.set(
+        "open",
+        push(|path, _| Some(ScriptEffect::Open(PathBuf::from(path))))?,
+    )?;
+    rview.set(
+        "add_keyword",
+        push(|path, keyword| Some(ScriptEffect::AddKeyword(PathBuf::from(path), keyword)))?,
+    )?;
+    rview.set(
+        "set_rating",
+        push(|path, stars| {
+            let stars = stars.trim().parse::<f32>().ok()?.round().clamp(0.0, 5.0);
+            Some(ScriptEffect::SetRating(PathBuf::from(path), stars as u8))
+        })?,
+    )?;
+    // A bare or relative name stays beside the file
+    rview.set(
+        "rename",
+        push(|path, name| {
+            let from = PathBuf::from(path);
+            let to = from.with_file_name(name.trim());
+            (!name.trim().is_empty()).then_some(ScriptEffect::Rename(from, to))
+        })?,
+    )?;
+    Ok(rview)
+}
@@ -0,0 +1,313 @@
+use crate::exif_data::ExifInfo;
+use crate::metadata::ImageMetadata;
+use crate::settings::ColorLabel;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Field a `name:value` search term applies to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SearchField {
+    Iso,
+    FocalLength,
+    Aperture,
+    Lens,
+    Camera,
+    Keyword,
+    Rating,
+}
+
+impl SearchField {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "iso" => Some(Self::Iso),
+            "focal" | "fl" => Some(Self::FocalLength),
+            "aperture" | "f" => Some(Self::Aperture),
+            "lens" => Some(Self::Lens),
+            "camera" | "cam" => Some(Self::Camera),
+            "keyword" | "kw" | "tag" => Some(Self::Keyword),
+            "rating" | "stars" => Some(Self::Rating),
+            _ => None,
+        }
+    }
+
+    fn needs_exif(self) -> bool {
+        !matches!(self, Self::Keyword | Self::Rating)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+impl Comparison {
+    fn matches(self, actual: f64, expected: f64) -> bool {
+        match self {
+            Self::Less => actual < expected,
+            Self::LessOrEqual => actual <= expected,
+            Self::Equal => (actual - expected).abs() < 0.05,
+            Self::GreaterOrEqual => actual >= expected,
+            Self::Greater => actual > expected,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchTerm {
+    /// Plain word, matched against the filename and keywords
+    Text(String),
+    Field {
+        field: SearchField,
+        comparison: Comparison,
+        value: String,
+    },
+}
+
+/// Parsed filter bar query. Terms are separated by whitespace and must all match,
+/// e.g. `iso:>1600 lens:35mm beach`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchQuery {
+    pub terms: Vec<SearchTerm>,
+}
+
+impl SearchQuery {
+    pub fn parse(query: &str) -> Self {
+        let terms = query
+            .split_whitespace()
+            .map(|word| {
+                let word = word.to_lowercase();
+                if let Some((name, value)) = word.split_once(':') {
+                    if let Some(field) = SearchField::from_name(name) {
+                        let (comparison, value) = split_comparison(value);
+                        return SearchTerm::Field {
+                            field,
+                            comparison,
+                            value: value.to_string(),
+                        };
+                    }
+                }
+                SearchTerm::Text(word)
+            })
+            .collect();
+        Self { terms }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Whether matching needs EXIF, which is read once per image and cached by the caller
+    pub fn needs_exif(&self) -> bool {
+        self.terms.iter().any(|t| match t {
+            SearchTerm::Field { field, .. } => field.needs_exif(),
+            SearchTerm::Text(_) => false,
+        })
+    }
+
+    /// Images whose EXIF hasn't been indexed yet never match EXIF terms
+    pub fn matches(&self, path: &Path, metadata: &ImageMetadata, exif: Option<&ExifInfo>) -> bool {
+        self.terms
+            .iter()
+            .all(|term| term_matches(term, path, metadata, exif))
+    }
+}
+
+fn split_comparison(value: &str) -> (Comparison, &str) {
+    for (prefix, comparison) in [
+        (">=", Comparison::GreaterOrEqual),
+        ("<=", Comparison::LessOrEqual),
+        (">", Comparison::Greater),
+        ("<", Comparison::Less),
+        ("=", Comparison::Equal),
+    ] {
+        if let Some(rest) = value.strip_prefix(prefix) {
+            return (comparison, rest);
+        }
+    }
+    (Comparison::Equal, value)
+}
+
+/// First number in an EXIF display string such as "ISO 1600", "35 mm" or "f/1.8"
+pub fn leading_number(text: &str) -> Option<f64> {
+    let start = text.find(|c: char| c.is_ascii_digit())?;
+    let number: String = text[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    number.parse().ok()
+}
+
+fn contains(haystack: Option<&str>, needle: &str) -> bool {
+    // "35mm" should match a lens named "EF 35 mm f/1.4", so spaces are ignored
+    let squash = |s: &str| s.to_lowercase().replace(' ', "");
+    haystack.is_some_and(|h| squash(h).contains(&squash(needle)))
+}
+
+fn compare_number(actual: Option<&str>, comparison: Comparison, expected: &str) -> bool {
+    match (actual.and_then(leading_number), leading_number(expected)) {
+        (Some(actual), Some(expected)) => comparison.matches(actual, expected),
+        _ => false,
+    }
+}
+
+fn term_matches(
+    term: &SearchTerm,
+    path: &Path,
+    metadata: &ImageMetadata,
+    exif: Option<&ExifInfo>,
+) -> bool {
+    match term {
+        SearchTerm::Text(text) => {
+            let filename = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            filename.contains(text.as_str())
+                || metadata
+                    .keywords
+                    .iter()
+                    .any(|k| k.to_lowercase().contains(text.as_str()))
+        }
+        SearchTerm::Field {
+            field,
+            comparison,
+            value,
+        } => match field {
+            SearchField::Keyword => metadata.keywords.iter().any(|k| k.to_lowercase() == *value),
+            SearchField::Rating => leading_number(value)
+                .is_some_and(|expected| comparison.matches(metadata.rating as f64, expected)),
+            SearchField::Iso => {
+                exif.is_some_and(|e| compare_number(e.iso.as_deref(), *comparison, value))
+            }
+            SearchField::FocalLength => {
+                exif.is_some_and(|e| compare_number(e.focal_length.as_deref(), *comparison, value))
+            }
+            SearchField::Aperture => {
+                exif.is_some_and(|e| compare_number(e.aperture.as_deref(), *comparison, value))
+            }
+            SearchField::Lens => exif.is_some_and(|e| contains(e.lens.as_deref(), value)),
+            SearchField::Camera => exif.is_some_and(|e| {
+                contains(e.camera_model.as_deref(), value)
+                    || contains(e.camera_make.as_deref(), value)
+            }),
+        },
+    }
+}
+
+/// One condition of a smart collection
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CollectionRule {
+    MinRating(u8),
+    Label(ColorLabel),
+    KeywordContains(String),
+    /// Inclusive `YYYY`, `YYYY-MM` or `YYYY-MM-DD` bounds; an empty bound is open
+    DateRange {
+        from: String,
+        to: String,
+    },
+    CameraModel(String),
+}
+
+impl CollectionRule {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::MinRating(_) => "Rating at least",
+            Self::Label(_) => "Label is",
+            Self::KeywordContains(_) => "Keyword contains",
+            Self::DateRange { .. } => "Captured between",
+            Self::CameraModel(_) => "Camera model contains",
+        }
+    }
+
+    /// One default instance of each rule, for the "add rule" menu
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::MinRating(3),
+            Self::Label(ColorLabel::Red),
+            Self::KeywordContains(String::new()),
+            Self::DateRange {
+                from: String::new(),
+                to: String::new(),
+            },
+            Self::CameraModel(String::new()),
+        ]
+    }
+
+    fn needs_exif(&self) -> bool {
+        matches!(self, Self::DateRange { .. } | Self::CameraModel(_))
+    }
+
+    fn matches(&self, metadata: &ImageMetadata, exif: Option<&ExifInfo>) -> bool {
+        match self {
+            Self::MinRating(min) => metadata.rating >= *min,
+            Self::Label(label) => metadata.color_label == *label,
+            Self::KeywordContains(text) => {
+                let text = text.trim().to_lowercase();
+                metadata
+                    .keywords
+                    .iter()
+                    .any(|k| k.to_lowercase().contains(&text))
+            }
+            Self::DateRange { from, to } => exif
+                .and_then(|e| e.date_taken.as_deref())
+                .is_some_and(|date| date_in_range(date, from.trim(), to.trim())),
+            Self::CameraModel(text) => exif.is_some_and(|e| {
+                contains(e.camera_model.as_deref(), text.trim())
+                    || contains(e.camera_make.as_deref(), text.trim())
+            }),
+        }
+    }
+}
+
+/// EXIF dates look like `2024:05:01 12:00:00`; bounds may be shorter prefixes,
+/// so comparing the normalized strings lexicographically is enough
+fn date_in_range(date: &str, from: &str, to: &str) -> bool {
+    let date: String = date
+        .chars()
+        .take(10)
+        .map(|c| if c == ':' { '-' } else { c })
+        .collect();
+    let after_from = from.is_empty() || date.as_str() >= from;
+    let before_to = to.is_empty() || date[..to.len().min(date.len())] <= *to;
+    after_from && before_to
+}
+
+/// A saved, rule-based view of the library that updates as ratings, labels and keywords change
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SmartCollection {
+    pub name: String,
+    pub rules: Vec<CollectionRule>,
+    /// Require every rule to match rather than any of them
+    pub match_all: bool,
+}
+
+impl Default for SmartCollection {
+    fn default() -> Self {
+        Self {
+            name: "New collection".to_string(),
+            rules: vec![CollectionRule::MinRating(3)],
+            match_all: true,
+        }
+    }
+}
+
+impl SmartCollection {
+    pub fn needs_exif(&self) -> bool {
+        self.rules.iter().any(CollectionRule::needs_exif)
+    }
+
+    pub fn matches(&self, metadata: &ImageMetadata, exif: Option<&ExifInfo>) -> bool {
+        if self.rules.is_empty() {
+            return true;
+        }
+        if self.match_all {
+            self.rules.iter().all(|r| r.matches(metadata, exif))
+        } else {
+            self.rules.iter().any(|r| r.matches(metadata, exif))
+        }
+    }
+}
@@ -1,6 +1,23 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// Settings file given on the command line, used instead of the config directory
+static SETTINGS_FILE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Read and save settings in `path` for the rest of the run
+pub fn use_settings_file(path: PathBuf) {
+    let _ = SETTINGS_FILE.set(path);
+}
+
+fn settings_path() -> Option<PathBuf> {
+    if let Some(path) = SETTINGS_FILE.get() {
+        return Some(path.clone());
+    }
+    directories::ProjectDirs::from("com", "imageviewer", "ImageViewer")
+        .map(|proj_dirs| proj_dirs.config_dir().join("settings.json"))
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
@@ -8,6 +25,20 @@ pub struct Settings {
     pub theme: Theme,
     pub background_color: BackgroundColor,
     pub accent_color: AccentColor,
+    #[serde(default)]
+    pub language: crate::i18n::Language,
+    /// Size of the whole interface, on top of the system's display scaling
+    #[serde(default = "default_ui_scale")]
+    pub ui_scale: f32,
+    /// Text size of the EXIF and metadata panel
+    #[serde(default = "default_exif_font_size")]
+    pub exif_font_size: f32,
+    /// Text size of the status bar
+    #[serde(default = "default_status_font_size")]
+    pub status_font_size: f32,
+    /// Larger controls, and one-finger swipes page through images
+    #[serde(default)]
+    pub touch_mode: bool,
 
     // Panels
     pub show_sidebar: bool,
@@ -15,14 +46,36 @@ pub struct Settings {
     pub thumbnail_size: f32,
     pub thumbnail_position: ThumbnailPosition,
     pub show_thumbnail_labels: bool,
+    /// What the labels under thumbnails show
+    #[serde(default)]
+    pub thumbnail_labels: ThumbnailLabels,
+    /// Badge thumbnails whose histogram shows heavy clipping
+    #[serde(default)]
+    pub thumbnail_exposure_warnings: bool,
     pub show_exif: bool,
     /// Whether the small EXIF overlay on the image is visible (separate from the sidebar)
     pub show_exif_overlay: bool,
+    #[serde(default)]
+    pub exif_overlay: crate::exif_overlay::ExifOverlaySettings,
     pub show_histogram: bool,
+    // Waveform, parade or vectorscope below the histogram
+    #[serde(default)]
+    pub show_scopes: bool,
+    #[serde(default)]
+    pub scopes: crate::image_loader::ScopeSettings,
+    // RGB or luminance, linear or log
+    #[serde(default)]
+    pub histogram: crate::image_loader::HistogramSettings,
     pub show_adjustments: bool,
     pub show_overlays: bool,
     pub show_toolbar: bool,
     pub show_statusbar: bool,
+    // Docking sides and sizes of the navigator, sidebar and histogram
+    #[serde(default)]
+    pub panel_layout: crate::workspace::PanelLayout,
+    // Named layouts offered in the toolbar's layout menu
+    #[serde(default = "crate::workspace::Workspace::builtin")]
+    pub workspaces: Vec<crate::workspace::Workspace>,
 
     // Zoom behavior
     pub zoom_increment: f32,
@@ -30,10 +83,28 @@ pub struct Settings {
     pub zoom_animation_speed: f32,
     pub maintain_zoom_on_navigate: bool,
     pub maintain_pan_on_navigate: bool,
+    // Texture sampling: how magnified pixels are drawn, and mipmaps so
+    // zoomed-out images don't shimmer
+    #[serde(default)]
+    pub magnification_filter: MagnificationFilter,
+    #[serde(default = "default_texture_mipmaps")]
+    pub texture_mipmaps: bool,
 
     // View modes
     pub fit_mode: FitMode,
     pub auto_rotate_exif: bool,
+    // Zoom and pan every compare pane together
+    #[serde(default = "default_compare_link_zoom")]
+    pub compare_link_zoom: bool,
+    // Line compared frames up with the first one (burst shots)
+    #[serde(default)]
+    pub compare_auto_align: bool,
+    // Multiplier that makes faint compare differences visible
+    #[serde(default = "default_compare_difference_gain")]
+    pub compare_difference_gain: f32,
+    // Color compare differences by size instead of per channel
+    #[serde(default)]
+    pub compare_difference_heatmap: bool,
 
     // Overlays
     pub show_focus_peaking: bool,
@@ -42,6 +113,10 @@ pub struct Settings {
     pub show_zebras: bool,
     pub zebra_high_threshold: u8,
     pub zebra_low_threshold: u8,
+    #[serde(default)]
+    pub show_clipping: bool,
+    #[serde(default)]
+    pub clipping: crate::image_loader::ClippingWarning,
     pub show_grid_overlay: bool,
     pub grid_type: GridType,
     pub show_custom_overlay: bool,
@@ -56,6 +131,24 @@ pub struct Settings {
     pub sort_order: SortOrder,
     pub sort_ascending: bool,
     pub include_subfolders: bool,
+    /// Folders whose new images are added to the catalog even when not open,
+    /// such as a tethering app's output or a card copy destination
+    #[serde(default)]
+    pub watched_folders: Vec<PathBuf>,
+    // Pick up files added, removed or renamed in the open folder
+    #[serde(default = "default_watch_folder")]
+    pub watch_folder: bool,
+    // Filter strip above the thumbnails; an empty list lets everything through
+    #[serde(default = "default_show_filter_bar")]
+    pub show_filter_bar: bool,
+    /// Minimum star rating shown; 0 shows unrated images too
+    #[serde(default)]
+    pub filter_by_rating: u8,
+    #[serde(default)]
+    pub filter_by_color: Vec<ColorLabel>,
+    /// Lowercase file extensions shown
+    #[serde(default)]
+    pub filter_extensions: Vec<String>,
 
     // File management
     pub recent_folders: Vec<PathBuf>,
@@ -70,6 +163,9 @@ pub struct Settings {
     pub preload_adjacent: usize,
     pub cache_size_mb: usize,
     pub thumbnail_cache_size: usize,
+    // Cap on thumbnails kept on disk between launches
+    #[serde(default = "default_thumbnail_disk_cache_mb")]
+    pub thumbnail_disk_cache_mb: usize,
     pub use_embedded_thumbnails: bool,
     pub parallel_thumbnail_threads: usize,
 
@@ -79,7 +175,106 @@ pub struct Settings {
     // Telemetry
     pub telemetry_enabled: bool,
 
-    // Export presets
+    // Export
+    #[serde(default)]
+    pub export_format: ExportFormat,
+    /// JPEG quality, 1-100
+    #[serde(default = "default_export_quality")]
+    pub export_quality: u8,
+    /// Long edge exports are shrunk to fit; None keeps the full size
+    #[serde(default)]
+    pub export_resize: Option<u32>,
+    #[serde(default)]
+    pub export_resize_filter: crate::image_loader::ResizeFilter,
+    /// Color space exports are converted to and tagged with
+    #[serde(default)]
+    pub export_profile: crate::color_management::OutputProfile,
+    /// Destination folder, subfolder and file name templates
+    #[serde(default)]
+    pub export_naming: crate::export_naming::ExportNaming,
+
+    // Color management
+    #[serde(default)]
+    pub color_management_enabled: bool,
+    /// ICC profile of the monitor; None means sRGB
+    #[serde(default)]
+    pub display_icc_profile: Option<PathBuf>,
+    /// Printer profile and options for soft proofing
+    #[serde(default)]
+    pub soft_proof: crate::color_management::SoftProofSettings,
+
+    // LUTs
+    /// Folder scanned for user .cube LUTs shown in the adjustments panel
+    #[serde(default)]
+    pub lut_folder: Option<PathBuf>,
+
+    // Metadata
+    /// Write ratings, labels and keywords to .xmp sidecars next to the images
+    #[serde(default)]
+    pub write_xmp_sidecars: bool,
+    /// Mirror ratings to Explorer stars on Windows and labels to Finder tags on macOS
+    #[serde(default)]
+    pub write_os_tags: bool,
+
+    // User-defined adjustment presets, in display order
+    #[serde(default)]
+    pub user_presets: Vec<UserPreset>,
+
+    // Film stocks made in the film emulation panel, after the built-in ones
+    #[serde(default)]
+    pub film_stocks: Vec<crate::image_loader::FilmStock>,
+
+    // Rule-based smart collections shown in the navigator panel
+    #[serde(default)]
+    pub smart_collections: Vec<crate::search::SmartCollection>,
+
+    // Grid, paper and caption choices for contact sheet export
+    #[serde(default)]
+    pub contact_sheet: crate::contact_sheet::ContactSheetOptions,
+
+    // Title, sizes and captions for web gallery export
+    #[serde(default)]
+    pub web_gallery: crate::web_gallery::WebGalleryOptions,
+
+    // Order of slideshows: sequential or shuffled
+    #[serde(default)]
+    pub slideshow_order: crate::slideshow::SlideshowOrder,
+
+    // Size and frame rate of slideshow videos
+    #[serde(default)]
+    pub slideshow_video: crate::slideshow::VideoOptions,
+
+    // Tracks, volume and beat timing for slideshow music
+    #[serde(default)]
+    pub music: crate::music::MusicSettings,
+
+    // Upload destinations listed in the Share menu
+    #[serde(default)]
+    pub share_targets: Vec<crate::share::ShareTarget>,
+
+    // HTTP server a tablet or button box can drive the viewer through
+    #[serde(default)]
+    pub remote_control: crate::remote_control::RemoteControlSettings,
+
+    // Film preset, fullscreen and histogram for tethered shooting
+    #[serde(default)]
+    pub tether: crate::tether::TetherSettings,
+
+    // Size, opacity and pen pressure response of the local adjustment brush
+    #[serde(default)]
+    pub brush: crate::brush::BrushSettings,
+
+    // Last pattern used in the batch rename dialog
+    #[serde(default)]
+    pub rename_pattern: crate::rename::RenamePattern,
+
+    // Last creator, copyright, title and keywords used in the batch metadata dialog
+    #[serde(default)]
+    pub metadata_template: crate::batch_metadata::MetadataTemplate,
+
+    // Shortcuts the user changed from the defaults
+    #[serde(default)]
+    pub keymap: crate::keymap::Keymap,
 
     // Window state
     pub window_maximized: bool,
@@ -88,14 +283,45 @@ pub struct Settings {
 
     // Session
     pub restore_session: bool,
+    // Hand files opened later to the running window instead of starting another
+    #[serde(default = "default_single_instance")]
+    pub single_instance: bool,
+    // Monitor fullscreen goes to, by name; None for the one with the window
+    #[serde(default)]
+    pub fullscreen_monitor: Option<String>,
+    // Icon in the system tray; closing the window hides it there
+    #[serde(default)]
+    pub tray_icon: bool,
     pub last_folder: Option<PathBuf>,
     pub last_file: Option<PathBuf>,
+    // Images viewed across sessions, most recent first
+    #[serde(default)]
+    pub recent_images: Vec<RecentImage>,
+    // Tabs open when rView last closed
+    #[serde(default)]
+    pub open_tabs: Vec<crate::app::tabs::ImageTab>,
+    #[serde(default)]
+    pub active_tab: usize,
 
     // Loupe
     pub loupe_size: f32,
     pub loupe_zoom: f32,
     /// If false, RAW files will not be decoded to full resolution; only embedded JPEG previews will be used
     pub load_raw_full_size: bool,
+    // Demosaic, white balance and highlight handling for RAW files
+    #[serde(default)]
+    pub raw_decode: crate::image_loader::RawDecodeOptions,
+    // Show a RAW file and the JPEG shot alongside it as one item
+    #[serde(default)]
+    pub pair_raw_jpeg: bool,
+    #[serde(default)]
+    pub pair_preference: PairPreference,
+    // dnglab or Adobe DNG Converter; looked for in the usual places when unset
+    #[serde(default)]
+    pub dng_converter: Option<PathBuf>,
+    // Exposure and tone mapping for float (EXR, HDR) images
+    #[serde(default)]
+    pub hdr_display: crate::image_loader::HdrDisplay,
     pub loupe_enabled: bool,
 }
 
@@ -105,28 +331,47 @@ impl Default for Settings {
             theme: Theme::Dark,
             background_color: BackgroundColor::Dark,
             accent_color: AccentColor::Blue,
+            language: crate::i18n::Language::English,
+            ui_scale: default_ui_scale(),
+            exif_font_size: default_exif_font_size(),
+            status_font_size: default_status_font_size(),
+            touch_mode: false,
 
             show_sidebar: true,
             show_thumbnails: true,
             thumbnail_size: 100.0,
             thumbnail_position: ThumbnailPosition::Bottom,
             show_thumbnail_labels: false,
+            thumbnail_labels: ThumbnailLabels::default(),
+            thumbnail_exposure_warnings: false,
             show_exif: true,
             show_exif_overlay: true,
+            exif_overlay: Default::default(),
             show_histogram: true,
+            show_scopes: false,
+            scopes: Default::default(),
+            histogram: Default::default(),
             show_adjustments: true,
             show_overlays: true,
             show_toolbar: true,
             show_statusbar: true,
+            panel_layout: Default::default(),
+            workspaces: crate::workspace::Workspace::builtin(),
 
             zoom_increment: 0.1,
             smooth_zoom: true,
             zoom_animation_speed: 8.0,
             maintain_zoom_on_navigate: true,
             maintain_pan_on_navigate: true,
+            magnification_filter: MagnificationFilter::default(),
+            texture_mipmaps: true,
 
             fit_mode: FitMode::Fit,
             auto_rotate_exif: true,
+            compare_link_zoom: true,
+            compare_auto_align: false,
+            compare_difference_gain: default_compare_difference_gain(),
+            compare_difference_heatmap: false,
 
             show_focus_peaking: false,
             focus_peaking_color: FocusPeakingColor::Red,
@@ -134,6 +379,8 @@ impl Default for Settings {
             show_zebras: false,
             zebra_high_threshold: 250,
             zebra_low_threshold: 5,
+            show_clipping: false,
+            clipping: Default::default(),
             show_grid_overlay: false,
             grid_type: GridType::RuleOfThirds,
             show_custom_overlay: false,
@@ -147,6 +394,12 @@ impl Default for Settings {
             sort_order: SortOrder::Ascending,
             sort_ascending: true,
             include_subfolders: false,
+            watched_folders: Vec::new(),
+            watch_folder: true,
+            show_filter_bar: true,
+            filter_by_rating: 0,
+            filter_by_color: Vec::new(),
+            filter_extensions: Vec::new(),
 
             recent_folders: Vec::new(),
             max_recent_folders: 20,
@@ -159,6 +412,7 @@ impl Default for Settings {
             preload_adjacent: 3,
             cache_size_mb: 1024,
             thumbnail_cache_size: 1000,
+            thumbnail_disk_cache_mb: default_thumbnail_disk_cache_mb(),
             use_embedded_thumbnails: true,
             parallel_thumbnail_threads: 4,
 
@@ -168,17 +422,60 @@ impl Default for Settings {
             // Telemetry (disabled by default)
             telemetry_enabled: false,
 
+            export_format: ExportFormat::default(),
+            export_quality: default_export_quality(),
+            export_resize: None,
+            export_resize_filter: Default::default(),
+            export_profile: Default::default(),
+            export_naming: Default::default(),
+
+            color_management_enabled: false,
+            display_icc_profile: None,
+            soft_proof: Default::default(),
+
+            lut_folder: None,
+
+            write_xmp_sidecars: false,
+            write_os_tags: false,
+
+            user_presets: Vec::new(),
+            film_stocks: Vec::new(),
+            smart_collections: Vec::new(),
+            contact_sheet: Default::default(),
+            web_gallery: Default::default(),
+            slideshow_order: Default::default(),
+            slideshow_video: Default::default(),
+            music: Default::default(),
+            share_targets: Vec::new(),
+            remote_control: Default::default(),
+            tether: Default::default(),
+            brush: Default::default(),
+            rename_pattern: Default::default(),
+            metadata_template: Default::default(),
+            keymap: Default::default(),
+
             window_maximized: false,
             window_size: (1400.0, 900.0),
             window_position: None,
 
             restore_session: true,
+            single_instance: true,
+            fullscreen_monitor: None,
+            tray_icon: false,
             last_folder: None,
             last_file: None,
+            recent_images: Vec::new(),
+            open_tabs: Vec::new(),
+            active_tab: 0,
 
             loupe_size: 200.0,
             loupe_zoom: 2.0,
             load_raw_full_size: true,
+            raw_decode: Default::default(),
+            pair_raw_jpeg: false,
+            pair_preference: PairPreference::Raw,
+            dng_converter: None,
+            hdr_display: Default::default(),
             loupe_enabled: false,
         }
     }
@@ -255,6 +552,16 @@ pub enum FitMode {
     FitHeight,
 }
 
+/// How pixels are drawn when the image is magnified
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MagnificationFilter {
+    /// Interpolated, for photos
+    #[default]
+    Linear,
+    /// Hard-edged pixels, for pixel art and screenshots
+    Nearest,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SortMode {
     Name,
@@ -264,6 +571,38 @@ pub enum SortMode {
     Type,
     Rating,
     Random,
+    /// Pixel count
+    Dimensions,
+    /// Width over height, so panoramas come last when ascending
+    AspectRatio,
+    Iso,
+    FocalLength,
+}
+
+impl SortMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SortMode::Name => "Name",
+            SortMode::Date => "Date",
+            SortMode::DateTaken => "Date Taken",
+            SortMode::Size => "Size",
+            SortMode::Type => "Type",
+            SortMode::Rating => "Rating",
+            SortMode::Random => "Random",
+            SortMode::Dimensions => "Megapixels",
+            SortMode::AspectRatio => "Aspect Ratio",
+            SortMode::Iso => "ISO",
+            SortMode::FocalLength => "Focal Length",
+        }
+    }
+
+    /// Whether the sort key comes from the EXIF index
+    pub fn needs_exif(&self) -> bool {
+        matches!(
+            self,
+            SortMode::Dimensions | SortMode::AspectRatio | SortMode::Iso | SortMode::FocalLength
+        )
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -272,6 +611,14 @@ pub enum SortOrder {
     Descending,
 }
 
+/// Which file of a RAW+JPEG pair is listed; the other rides along with it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PairPreference {
+    #[default]
+    Raw,
+    Jpeg,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ThumbnailPosition {
     Bottom,
@@ -280,6 +627,69 @@ pub enum ThumbnailPosition {
     Top,
 }
 
+/// Thumbnail cell sizes offered by the slider and Ctrl+scroll, in pixels
+pub const THUMBNAIL_SIZES: std::ops::RangeInclusive<f32> = 50.0..=300.0;
+
+/// Fields shown under each thumbnail: the file name on the first line, the
+/// rest on a second
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThumbnailLabels {
+    pub file_name: bool,
+    pub date: bool,
+    pub rating: bool,
+    pub dimensions: bool,
+}
+
+impl Default for ThumbnailLabels {
+    fn default() -> Self {
+        Self {
+            file_name: true,
+            date: false,
+            rating: false,
+            dimensions: true,
+        }
+    }
+}
+
+impl ThumbnailLabels {
+    /// Label lines for one image; `date` is an EXIF date and time
+    pub fn lines(
+        &self,
+        file_name: &str,
+        date: Option<&str>,
+        rating: u8,
+        dimensions: Option<&str>,
+    ) -> Vec<String> {
+        let mut details = Vec::new();
+        if let Some(date) = date.filter(|_| self.date) {
+            // Just the day, as YYYY-MM-DD
+            let day = date.split_whitespace().next().unwrap_or(date);
+            details.push(day.replace(':', "-"));
+        }
+        if self.rating && rating > 0 {
+            details.push("★".repeat(rating.min(5) as usize));
+        }
+        if let Some(dimensions) = dimensions.filter(|_| self.dimensions) {
+            details.push(dimensions.to_string());
+        }
+
+        let mut lines = Vec::new();
+        if self.file_name {
+            lines.push(file_name.to_string());
+        }
+        if !details.is_empty() {
+            lines.push(details.join(" • "));
+        }
+        lines
+    }
+
+    /// Most lines a label can take, to reserve room for under every cell
+    pub fn max_lines(&self) -> usize {
+        self.file_name as usize + (self.date || self.rating || self.dimensions) as usize
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FocusPeakingColor {
     Red,
@@ -290,7 +700,6 @@ pub enum FocusPeakingColor {
 }
 
 impl FocusPeakingColor {
-    #[allow(dead_code)]
     pub fn to_color(self) -> egui::Color32 {
         match self {
             FocusPeakingColor::Red => egui::Color32::from_rgb(255, 0, 0),
@@ -312,6 +721,75 @@ pub enum GridType {
     Off,
 }
 
+/// A named set of adjustments saved by the user
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserPreset {
+    pub name: String,
+    pub adjustments: crate::image_loader::ImageAdjustments,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ExportFormat {
+    #[default]
+    Jpeg,
+    Png,
+    Tiff,
+    WebP,
+    Jxl,
+}
+
+impl ExportFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportFormat::Jpeg => "JPEG",
+            ExportFormat::Png => "PNG",
+            ExportFormat::Tiff => "TIFF",
+            ExportFormat::WebP => "WebP",
+            ExportFormat::Jxl => "JPEG XL",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Jpeg => "jpg",
+            ExportFormat::Png => "png",
+            ExportFormat::Tiff => "tif",
+            ExportFormat::WebP => "webp",
+            ExportFormat::Jxl => "jxl",
+        }
+    }
+
+    /// All extensions accepted for this format in the save dialog
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            ExportFormat::Jpeg => &["jpg", "jpeg"],
+            ExportFormat::Png => &["png"],
+            ExportFormat::Tiff => &["tif", "tiff"],
+            ExportFormat::WebP => &["webp"],
+            ExportFormat::Jxl => &["jxl"],
+        }
+    }
+
+    /// Infer the format from a file extension
+    pub fn from_path(path: &std::path::Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        Self::all()
+            .iter()
+            .copied()
+            .find(|f| f.extensions().contains(&ext.as_str()))
+    }
+
+    pub fn all() -> &'static [ExportFormat] {
+        &[
+            ExportFormat::Jpeg,
+            ExportFormat::Png,
+            ExportFormat::Tiff,
+            ExportFormat::WebP,
+            ExportFormat::Jxl,
+        ]
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash, Default)]
 pub enum ColorLabel {
     #[default]
@@ -335,7 +813,6 @@ impl ColorLabel {
         }
     }
 
-    #[allow(dead_code)]
     pub fn name(&self) -> &'static str {
         match self {
             ColorLabel::None => "None",
@@ -347,7 +824,6 @@ impl ColorLabel {
         }
     }
 
-    #[allow(dead_code)]
     pub fn all() -> &'static [ColorLabel] {
         &[
             ColorLabel::None,
@@ -360,6 +836,34 @@ impl ColorLabel {
     }
 }
 
+/// How many viewed images the recent images list remembers
+const MAX_RECENT_IMAGES: usize = 50;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentImage {
+    pub path: PathBuf,
+    pub viewed: chrono::DateTime<chrono::Local>,
+}
+
+impl RecentImage {
+    /// When it was last viewed relative to `now`, like "5 min ago" or "Yesterday 14:03"
+    pub fn viewed_ago(&self, now: chrono::DateTime<chrono::Local>) -> String {
+        let elapsed = now.signed_duration_since(self.viewed);
+        let days = (now.date_naive() - self.viewed.date_naive()).num_days();
+        if elapsed.num_minutes() < 1 {
+            "Just now".to_string()
+        } else if elapsed.num_hours() < 1 {
+            format!("{} min ago", elapsed.num_minutes())
+        } else if days == 0 {
+            self.viewed.format("Today %H:%M").to_string()
+        } else if days == 1 {
+            self.viewed.format("Yesterday %H:%M").to_string()
+        } else {
+            self.viewed.format("%Y-%m-%d %H:%M").to_string()
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExternalEditor {
     pub name: String,
@@ -377,6 +881,50 @@ pub enum PanelPosition {
     Hidden,
 }
 
+fn default_watch_folder() -> bool {
+    true
+}
+
+fn default_single_instance() -> bool {
+    true
+}
+
+fn default_compare_link_zoom() -> bool {
+    true
+}
+
+fn default_compare_difference_gain() -> f32 {
+    4.0
+}
+
+fn default_export_quality() -> u8 {
+    90
+}
+
+fn default_texture_mipmaps() -> bool {
+    true
+}
+
+fn default_show_filter_bar() -> bool {
+    true
+}
+
+fn default_thumbnail_disk_cache_mb() -> usize {
+    512
+}
+
+fn default_ui_scale() -> f32 {
+    1.0
+}
+
+fn default_exif_font_size() -> f32 {
+    10.0
+}
+
+fn default_status_font_size() -> f32 {
+    11.0
+}
+
 #[allow(dead_code)]
 fn default_panel_positions() -> HashMap<String, PanelPosition> {
     let mut positions = HashMap::new();
@@ -387,11 +935,21 @@ fn default_panel_positions() -> HashMap<String, PanelPosition> {
     positions
 }
 
+pub const UI_SCALE_RANGE: std::ops::RangeInclusive<f32> = 0.75..=2.5;
+
 impl Settings {
+    /// Interface scale kept within the range the settings slider offers
+    pub fn ui_scale(&self) -> f32 {
+        if self.ui_scale.is_finite() {
+            self.ui_scale
+                .clamp(*UI_SCALE_RANGE.start(), *UI_SCALE_RANGE.end())
+        } else {
+            default_ui_scale()
+        }
+    }
+
     pub fn load() -> Self {
-        if let Some(proj_dirs) = directories::ProjectDirs::from("com", "imageviewer", "ImageViewer")
-        {
-            let config_path = proj_dirs.config_dir().join("settings.json");
+        if let Some(config_path) = settings_path() {
             if config_path.exists() {
                 if let Ok(content) = std::fs::read_to_string(&config_path) {
                     if let Ok(settings) = serde_json::from_str(&content) {
@@ -404,11 +962,10 @@ impl Settings {
     }
 
     pub fn save(&self) {
-        if let Some(proj_dirs) = directories::ProjectDirs::from("com", "imageviewer", "ImageViewer")
-        {
-            let config_dir = proj_dirs.config_dir();
-            let _ = std::fs::create_dir_all(config_dir);
-            let config_path = config_dir.join("settings.json");
+        if let Some(config_path) = settings_path() {
+            if let Some(config_dir) = config_path.parent() {
+                let _ = std::fs::create_dir_all(config_dir);
+            }
             if let Ok(content) = serde_json::to_string_pretty(self) {
                 let _ = std::fs::write(config_path, content);
             }
@@ -423,6 +980,18 @@ impl Settings {
         }
     }
 
+    pub fn add_recent_image(&mut self, path: PathBuf) {
+        self.recent_images.retain(|r| r.path != path);
+        self.recent_images.insert(
+            0,
+            RecentImage {
+                path,
+                viewed: chrono::Local::now(),
+            },
+        );
+        self.recent_images.truncate(MAX_RECENT_IMAGES);
+    }
+
     pub fn add_quick_move_folder(&mut self, path: PathBuf) {
         self.quick_move_folders.retain(|p| p != &path);
         self.quick_move_folders.insert(0, path);
@@ -0,0 +1,62 @@
+// Pixel difference of two images for compare mode
+// Reads two of egui's own textures and writes |A - B| into a texture egui
+// draws, either per channel or as a heatmap of how much each pixel changed.
+@group(0) @binding(0) var first_texture: texture_2d<f32>;
+@group(0) @binding(1) var second_texture: texture_2d<f32>;
+@group(0) @binding(2) var output_texture: texture_storage_2d<rgba8unorm, write>;
+@group(0) @binding(3) var<uniform> params: DifferenceParams;
+
+struct DifferenceParams {
+    gain: f32,
+    heatmap: u32,   // 0 = per channel, 1 = heatmap
+    width: u32,
+    height: u32,
+};
+
+// egui's textures are sRGB, so reads come back linear; the difference is
+// taken on the encoded values people see
+fn encode_srgb(linear: vec3<f32>) -> vec3<f32> {
+    let low = linear * 12.92;
+    let high = 1.055 * pow(linear, vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(high, low, linear <= vec3<f32>(0.0031308));
+}
+
+// The second image is stretched over the first when their sizes differ
+fn load_scaled(texture: texture_2d<f32>, pos: vec2<u32>) -> vec3<f32> {
+    let size = textureDimensions(texture);
+    let uv = (vec2<f32>(pos) + 0.5) / vec2<f32>(f32(params.width), f32(params.height));
+    let texel = min(vec2<u32>(uv * vec2<f32>(size)), size - 1u);
+    return encode_srgb(textureLoad(texture, texel, 0).rgb);
+}
+
+// Black through blue, red and yellow to white as the difference grows
+fn heat(amount: f32) -> vec3<f32> {
+    let t = clamp(amount, 0.0, 1.0);
+    let blue = vec3<f32>(0.1, 0.1, 0.8);
+    let red = vec3<f32>(0.9, 0.1, 0.1);
+    let yellow = vec3<f32>(1.0, 0.9, 0.1);
+    if (t < 0.25) {
+        return mix(vec3<f32>(0.0), blue, t / 0.25);
+    } else if (t < 0.5) {
+        return mix(blue, red, (t - 0.25) / 0.25);
+    } else if (t < 0.75) {
+        return mix(red, yellow, (t - 0.5) / 0.25);
+    }
+    return mix(yellow, vec3<f32>(1.0), (t - 0.75) / 0.25);
+}
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    if (global_id.x >= params.width || global_id.y >= params.height) {
+        return;
+    }
+
+    let pos = global_id.xy;
+    let difference = abs(load_scaled(first_texture, pos) - load_scaled(second_texture, pos));
+    var color = clamp(difference * params.gain, vec3<f32>(0.0), vec3<f32>(1.0));
+    if (params.heatmap == 1u) {
+        color = heat(max(difference.r, max(difference.g, difference.b)) * params.gain);
+    }
+
+    textureStore(output_texture, vec2<i32>(pos), vec4<f32>(color, 1.0));
+}
@@ -0,0 +1,248 @@
+// Main image display pass
+// Draws the resident source texture into the callback viewport and applies the
+// basic adjustments (exposure, white balance, contrast, saturation) per fragment, so slider
+// changes only update the uniform buffer instead of re-uploading the bitmap.
+// Float (HDR) sources arrive scene-linear and are tone mapped here first.
+// Colour-managed images are converted to the display profile last, through a
+// lookup table, so the adjustments work in the image's own space.
+
+// Mirrors LocalMask in adjustments.wgsl
+struct LocalMask {
+    geometry: vec4<f32>,
+    shape: vec4<f32>,
+    amount: vec4<f32>,
+}
+
+struct DisplayParams {
+    exposure: f32,
+    saturation: f32,
+    temperature: f32,
+    adjustments_enabled: u32,
+    scene_linear: u32,
+    hdr_exposure: f32,
+    // 0 clip, 1 Reinhard, 2 ACES; matches ToneMap on the CPU
+    tone_map: u32,
+    contrast: f32,
+    local_count: u32,
+    tint: f32,
+    nearest: u32,
+    display_lut: u32,
+    local_masks: array<LocalMask, 8>,
+}
+
+@group(0) @binding(0) var source_texture: texture_2d<f32>;
+@group(0) @binding(1) var source_sampler: sampler;
+@group(0) @binding(2) var<uniform> params: DisplayParams;
+@group(0) @binding(3) var display_lut_texture: texture_3d<f32>;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    // Two triangles covering the viewport; egui sets the viewport to the image rect
+    var positions = array<vec2<f32>, 6>(
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>(1.0, -1.0),
+        vec2<f32>(-1.0, 1.0),
+        vec2<f32>(-1.0, 1.0),
+        vec2<f32>(1.0, -1.0),
+        vec2<f32>(1.0, 1.0),
+    );
+    let pos = positions[vertex_index];
+
+    var out: VertexOutput;
+    out.position = vec4<f32>(pos, 0.0, 1.0);
+    out.uv = vec2<f32>((pos.x + 1.0) * 0.5, (1.0 - pos.y) * 0.5);
+    return out;
+}
+
+fn aces_tonemap(x: vec3<f32>) -> vec3<f32> {
+    let a = 2.51;
+    let b = 0.03;
+    let c = 2.43;
+    let d = 0.59;
+    let e = 0.14;
+    let v = max(x, vec3<f32>(0.0));
+    return clamp((v * (a * v + b)) / (v * (c * v + d) + e), vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+fn srgb_to_linear(x: vec3<f32>) -> vec3<f32> {
+    let low = x / 12.92;
+    let high = pow((x + 0.055) / 1.055, vec3<f32>(2.4));
+    return select(high, low, x <= vec3<f32>(0.04045));
+}
+
+fn linear_to_srgb(x: vec3<f32>) -> vec3<f32> {
+    let low = x * 12.92;
+    let high = 1.055 * pow(x, vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(high, low, x <= vec3<f32>(0.0031308));
+}
+
+// OKLab chroma scaling, mirrors apply_oklab_saturation on the CPU
+fn oklab_saturation(rgb: vec3<f32>, saturation: f32) -> vec3<f32> {
+    let lin = srgb_to_linear(clamp(rgb, vec3<f32>(0.0), vec3<f32>(1.0)));
+
+    let l = 0.41222146 * lin.r + 0.53633255 * lin.g + 0.051445995 * lin.b;
+    let m = 0.2119035 * lin.r + 0.6806995 * lin.g + 0.10739696 * lin.b;
+    let s = 0.08830246 * lin.r + 0.28171885 * lin.g + 0.6299787 * lin.b;
+
+    let l_ = pow(max(l, 0.0), 1.0 / 3.0);
+    let m_ = pow(max(m, 0.0), 1.0 / 3.0);
+    let s_ = pow(max(s, 0.0), 1.0 / 3.0);
+
+    let lab_l = 0.21045426 * l_ + 0.7936178 * m_ - 0.004072047 * s_;
+    let lab_a = (1.9779985 * l_ - 2.4285922 * m_ + 0.4505937 * s_) * saturation;
+    let lab_b = (0.025904037 * l_ + 0.78277177 * m_ - 0.80867577 * s_) * saturation;
+
+    let l2 = lab_l + 0.39633778 * lab_a + 0.21580376 * lab_b;
+    let m2 = lab_l - 0.105561346 * lab_a - 0.06385417 * lab_b;
+    let s2 = lab_l - 0.08948418 * lab_a - 1.2914855 * lab_b;
+
+    let l3 = l2 * l2 * l2;
+    let m3 = m2 * m2 * m2;
+    let s3 = s2 * s2 * s2;
+
+    let out = vec3<f32>(
+        4.0767417 * l3 - 3.3077116 * m3 + 0.23096994 * s3,
+        -1.268438 * l3 + 2.6097574 * m3 - 0.34131938 * s3,
+        -0.0041960863 * l3 - 0.7034186 * m3 + 1.7076147 * s3,
+    );
+    return linear_to_srgb(clamp(out, vec3<f32>(0.0), vec3<f32>(1.0)));
+}
+
+// Exposure, tone map and sRGB encode, mirrors hdr::display_referred on the CPU
+fn display_hdr(rgb: vec3<f32>) -> vec3<f32> {
+    let v = max(rgb * exp2(params.hdr_exposure), vec3<f32>(0.0));
+    var mapped = min(v, vec3<f32>(1.0));
+    if (params.tone_map == 1u) {
+        mapped = v / (v + 1.0);
+    } else if (params.tone_map == 2u) {
+        mapped = aces_tonemap(v);
+    }
+    return linear_to_srgb(mapped);
+}
+
+// Same white balance as adjustments.wgsl and image_loader::white_balance
+fn kelvin_to_linear_rgb(kelvin: f32) -> vec3<f32> {
+    let t = clamp(kelvin, 1667.0, 25000.0);
+    let t2 = t * t;
+    let t3 = t2 * t;
+    var x = -3.0258469e9 / t3 + 2.1070379e6 / t2 + 0.2226347e3 / t + 0.240390;
+    if (t <= 4000.0) {
+        x = -0.2661239e9 / t3 - 0.2343589e6 / t2 + 0.8776956e3 / t + 0.179910;
+    }
+    let x2 = x * x;
+    let x3 = x2 * x;
+    var y = 3.081758 * x3 - 5.873387 * x2 + 3.751130 * x - 0.3700148;
+    if (t <= 2222.0) {
+        y = -1.106381 * x3 - 1.348110 * x2 + 2.185558 * x - 0.2021968;
+    } else if (t <= 4000.0) {
+        y = -0.9549476 * x3 - 1.374186 * x2 + 2.091370 * x - 0.1674887;
+    }
+    let big_x = x / y;
+    let big_z = (1.0 - x - y) / y;
+    return vec3<f32>(
+        3.2406 * big_x - 1.5372 - 0.4986 * big_z,
+        -0.9689 * big_x + 1.8758 + 0.0415 * big_z,
+        0.0557 * big_x - 0.2040 + 1.0570 * big_z,
+    );
+}
+
+// Multipliers for gamma-encoded values; temperature doubles Kelvin per unit from 6500K
+fn white_balance_gains(temperature: f32, tint: f32) -> vec3<f32> {
+    if (temperature == 0.0 && tint == 0.0) {
+        return vec3<f32>(1.0);
+    }
+    let neutral = kelvin_to_linear_rgb(6500.0);
+    let light = kelvin_to_linear_rgb(6500.0 * exp2(temperature));
+    var gains = neutral / max(light, vec3<f32>(0.01));
+    gains.y = gains.y * exp2(-tint);
+    let luminance = dot(gains, vec3<f32>(0.2126, 0.7152, 0.0722));
+    return pow(gains / luminance, vec3<f32>(1.0 / 2.2));
+}
+
+// Same gradients as local_mask_weight in adjustments.wgsl
+fn local_mask_weight(mask: LocalMask, uv: vec2<f32>, aspect: f32) -> f32 {
+    var weight = 0.0;
+    if (mask.shape.x < 0.5) {
+        let d = length((uv - mask.geometry.xy) / max(mask.geometry.zw, vec2<f32>(0.0001)));
+        weight = 1.0 - smoothstep(1.0 - max(mask.shape.y, 0.001), 1.0, d);
+    } else {
+        let scale = vec2<f32>(aspect, 1.0);
+        let dir = (mask.geometry.zw - mask.geometry.xy) * scale;
+        let t = dot((uv - mask.geometry.xy) * scale, dir) / max(dot(dir, dir), 0.000001);
+        weight = 1.0 - smoothstep(0.0, 1.0, t);
+    }
+    if (mask.shape.z > 0.5) {
+        weight = 1.0 - weight;
+    }
+    return weight;
+}
+
+// Image colours to display colours, sampling between the lookup table's grid points
+fn to_display(rgb: vec3<f32>) -> vec3<f32> {
+    if (params.display_lut == 0u) {
+        return rgb;
+    }
+    let size = f32(textureDimensions(display_lut_texture).x);
+    let coord = clamp(rgb, vec3<f32>(0.0), vec3<f32>(1.0)) * ((size - 1.0) / size) + 0.5 / size;
+    return textureSampleLevel(display_lut_texture, source_sampler, coord, 0.0).rgb;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    var color = textureSample(source_texture, source_sampler, in.uv);
+    if (params.nearest == 1u) {
+        // Exact source pixel, so single pixels stay sharp at high zoom
+        let dims = vec2<i32>(textureDimensions(source_texture));
+        let texel = clamp(vec2<i32>(floor(in.uv * vec2<f32>(dims))), vec2<i32>(0), dims - 1);
+        color = textureLoad(source_texture, texel, 0);
+    }
+    if (params.scene_linear == 1u) {
+        color = vec4<f32>(display_hdr(color.rgb), color.a);
+    }
+    if (params.adjustments_enabled == 0u) {
+        return vec4<f32>(to_display(color.rgb), color.a);
+    }
+
+    var rgb = color.rgb;
+
+    // Local adjustments add to the global values under their masks
+    let size = vec2<f32>(textureDimensions(source_texture));
+    var local_offset = vec4<f32>(0.0);
+    for (var i = 0u; i < min(params.local_count, 8u); i = i + 1u) {
+        let mask = params.local_masks[i];
+        local_offset = local_offset + mask.amount * local_mask_weight(mask, in.uv, size.x / size.y);
+    }
+    let exposure = params.exposure + local_offset.x;
+    let saturation = max(params.saturation + local_offset.y, 0.0);
+    let temperature = params.temperature + local_offset.z;
+    let tint = params.tint + local_offset.w;
+
+    // Exposure
+    let exposure_mult = exp2(exposure);
+    rgb = rgb * exposure_mult;
+
+    // White balance
+    rgb = rgb * white_balance_gains(temperature, tint);
+
+    // ACES tone mapping blended by exposure strength
+    let strength = clamp(0.5 + abs(exposure_mult - 1.0) * 0.3, 0.3, 0.9);
+    let base = max(rgb, vec3<f32>(0.0));
+    rgb = base * (1.0 - strength) + aces_tonemap(base) * strength;
+
+    // Contrast around mid-grey
+    if (params.contrast != 0.0) {
+        rgb = vec3<f32>(0.5) + (rgb - vec3<f32>(0.5)) * (1.0 + params.contrast);
+    }
+
+    // Saturation
+    if (abs(saturation - 1.0) > 0.001) {
+        rgb = oklab_saturation(rgb, saturation);
+    }
+
+    return vec4<f32>(to_display(clamp(rgb, vec3<f32>(0.0), vec3<f32>(1.0))), color.a);
+}
@@ -0,0 +1,451 @@
+use crate::download::is_url;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// An upload destination offered in the Share menu
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShareTarget {
+    pub name: String,
+    pub service: ShareService,
+    /// Link copied after an upload, with `{name}` standing for the uploaded
+    /// file name; empty uses the link the service answers with
+    #[serde(default)]
+    pub link_template: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ShareService {
+    Http(HttpTarget),
+    S3(S3Target),
+    Sftp(SftpTarget),
+}
+
+/// A multipart form POST, e.g. to an image host or a webhook
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpTarget {
+    pub url: String,
+    /// Form field holding the file
+    pub field: String,
+    /// Extra headers, one `Name: value` per line, e.g. for an API key. Only
+    /// the names are saved; the values are asked for once per session.
+    #[serde(serialize_with = "serialize_header_names")]
+    pub headers: String,
+    /// Dotted path to the link in a JSON reply, like `data.link`; empty
+    /// takes a reply that is just a URL
+    pub link_field: String,
+}
+
+impl Default for HttpTarget {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            field: "file".to_string(),
+            headers: String::new(),
+            link_field: String::new(),
+        }
+    }
+}
+
+/// Any S3-compatible object storage, addressed path-style
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct S3Target {
+    pub endpoint: String,
+    pub region: String,
+    pub bucket: String,
+    /// Key prefix, like `photos/`
+    pub prefix: String,
+    pub access_key: String,
+    /// Kept for the session only, so it never lands in the settings file
+    #[serde(skip)]
+    pub secret_key: String,
+}
+
+impl Default for S3Target {
+    fn default() -> Self {
+        Self {
+            endpoint: "https://s3.amazonaws.com".to_string(),
+            region: "us-east-1".to_string(),
+            bucket: String::new(),
+            prefix: String::new(),
+            access_key: String::new(),
+            secret_key: String::new(),
+        }
+    }
+}
+
+/// Copied with the system `sftp`, which signs in with ssh keys or the agent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SftpTarget {
+    pub host: String,
+    pub port: u16,
+    pub user: String,
+    pub folder: String,
+}
+
+impl Default for SftpTarget {
+    fn default() -> Self {
+        Self {
+            host: String::new(),
+            port: 22,
+            user: String::new(),
+            folder: String::new(),
+        }
+    }
+}
+
+impl ShareService {
+    /// One unconfigured service of each kind, for the settings' Add buttons
+    pub fn all() -> [ShareService; 3] {
+        [
+            ShareService::Http(HttpTarget::default()),
+            ShareService::S3(S3Target::default()),
+            ShareService::Sftp(SftpTarget::default()),
+        ]
+    }
+
+    /// Whether an upload needs a secret that hasn't been entered this session
+    pub fn needs_secret(&self) -> bool {
+        match self {
+            ShareService::Http(http) => missing_header_values(&http.headers),
+            ShareService::S3(s3) => s3.secret_key.is_empty(),
+            ShareService::Sftp(_) => false,
+        }
+    }
+
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            ShareService::Http(_) => "HTTP POST",
+            ShareService::S3(_) => "S3",
+            ShareService::Sftp(_) => "SFTP",
+        }
+    }
+}
+
+impl ShareTarget {
+    pub fn new(service: ShareService) -> Self {
+        Self {
+            name: service.kind_name().to_string(),
+            service,
+            link_template: String::new(),
+        }
+    }
+
+    /// Upload one file, returning the link to it when there is one
+    pub fn upload(&self, file: &Path) -> Result<Option<String>, String> {
+        let name = file
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .ok_or("No file name")?;
+        let link = match &self.service {
+            ShareService::Http(http) => http.upload(file, &name)?,
+            ShareService::S3(s3) => s3.upload(file, &name)?,
+            ShareService::Sftp(sftp) => sftp.upload(file, &name)?,
+        };
+        if self.link_template.trim().is_empty() {
+            Ok(link)
+        } else {
+            Ok(Some(fill_link_template(&self.link_template, &name)))
+        }
+    }
+}
+
+impl HttpTarget {
+    fn upload(&self, file: &Path, name: &str) -> Result<Option<String>, String> {
+        let bytes = std::fs::read(file).map_err(|e| e.to_string())?;
+        block_on(async {
+            let part = reqwest::multipart::Part::bytes(bytes)
+                .file_name(name.to_string())
+                .mime_str(content_type(file))
+                .map_err(|e| e.to_string())?;
+            let form = reqwest::multipart::Form::new().part(self.field.clone(), part);
+            let mut request = client()?.post(self.url.trim()).multipart(form);
+            for (header, value) in parse_headers(&self.headers) {
+                request = request.header(header, value);
+            }
+            let reply = request
+                .send()
+                .await
+                .and_then(|r| r.error_for_status())
+                .map_err(|e| e.to_string())?
+                .text()
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(link_from_reply(&reply, &self.link_field))
+        })
+    }
+}
+
+impl S3Target {
+    /// Path-style address of an object: endpoint, bucket, then the key
+    pub fn object_url(&self, key: &str) -> String {
+        format!(
+            "{}/{}/{}",
+            self.endpoint.trim().trim_end_matches('/'),
+            self.bucket.trim(),
+            uri_encode(key, false)
+        )
+    }
+
+    fn upload(&self, file: &Path, name: &str) -> Result<Option<String>, String> {
+        let key = format!("{}{}", self.prefix.trim(), name);
+        let url = self.object_url(&key);
+        let parsed = reqwest::Url::parse(&url).map_err(|e| e.to_string())?;
+        let host = match (parsed.host_str(), parsed.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => return Err(format!("No host in {}", self.endpoint)),
+        };
+
+        let bytes = std::fs::read(file).map_err(|e| e.to_string())?;
+        let payload_hash = hex(&Sha256::digest(&bytes));
+        let amz_date = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let content_type = content_type(file);
+        // Sorted by name, as signing needs
+        let headers = [
+            ("content-type", content_type),
+            ("host", host.as_str()),
+            ("x-amz-content-sha256", payload_hash.as_str()),
+            ("x-amz-date", amz_date.as_str()),
+        ];
+        let authorization = sigv4_authorization(
+            "PUT",
+            parsed.path(),
+            &headers,
+            (&self.access_key, &self.secret_key),
+            self.region.trim(),
+            &amz_date,
+        );
+
+        block_on(async {
+            let mut request = client()?.put(parsed).header("authorization", authorization);
+            // reqwest sends the host itself
+            for (header, value) in headers.iter().filter(|(h, _)| *h != "host") {
+                request = request.header(*header, *value);
+            }
+            request
+                .body(bytes)
+                .send()
+                .await
+                .and_then(|r| r.error_for_status())
+                .map_err(|e| e.to_string())?;
+            Ok(Some(url))
+        })
+    }
+}
+
+impl SftpTarget {
+    fn upload(&self, file: &Path, name: &str) -> Result<Option<String>, String> {
+        let folder = self.folder.trim().trim_end_matches('/');
+        let remote = if folder.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", folder, name)
+        };
+        let destination = if self.user.trim().is_empty() {
+            self.host.trim().to_string()
+        } else {
+            format!("{}@{}", self.user.trim(), self.host.trim())
+        };
+
+        // Batch mode never asks for a password, so a missing key fails instead of hanging
+        let mut child = Command::new("sftp")
+            .args(["-b", "-", "-P", &self.port.to_string(), &destination])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Couldn't run sftp: {}", e))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            let command = format!(
+                "put {} {}\n",
+                sftp_quote(&file.to_string_lossy()),
+                sftp_quote(&remote)
+            );
+            stdin
+                .write_all(command.as_bytes())
+                .map_err(|e| e.to_string())?;
+        }
+        let output = child.wait_with_output().map_err(|e| e.to_string())?;
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            let error = error.lines().rfind(|l| !l.trim().is_empty());
+            return Err(error.unwrap_or("sftp failed").trim().to_string());
+        }
+        Ok(None)
+    }
+}
+
+/// Link for an uploaded file, percent-encoding the name into `{name}`
+pub fn fill_link_template(template: &str, file_name: &str) -> String {
+    template
+        .trim()
+        .replace("{name}", &uri_encode(file_name, true))
+}
+
+/// The link in a service's reply: the string at a dotted path of a JSON
+/// reply, or the whole reply when it is just a URL
+pub fn link_from_reply(reply: &str, link_field: &str) -> Option<String> {
+    let link_field = link_field.trim();
+    if link_field.is_empty() {
+        let reply = reply.trim();
+        return is_url(reply).then(|| reply.to_string());
+    }
+    let json: serde_json::Value = serde_json::from_str(reply).ok()?;
+    let value = link_field
+        .split('.')
+        .try_fold(&json, |value, key| match key.parse::<usize>() {
+            Ok(index) if value.is_array() => value.get(index),
+            _ => value.get(key),
+        })?;
+    value.as_str().map(str::to_string)
+}
+
+/// `Name: value` lines, skipping blank and malformed ones
+pub fn parse_headers(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .filter(|(name, _)| !name.is_empty())
+        .collect()
+}
+
+/// The headers with their values removed, as `Name:` lines
+pub fn header_names(text: &str) -> String {
+    parse_headers(text)
+        .into_iter()
+        .map(|(name, _)| format!("{}: \n", name))
+        .collect()
+}
+
+/// Whether any header is still waiting for its value
+pub fn missing_header_values(text: &str) -> bool {
+    parse_headers(text)
+        .iter()
+        .any(|(_, value)| value.is_empty())
+}
+
+fn serialize_header_names<S: serde::Serializer>(
+    text: &str,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&header_names(text))
+}
+
+/// AWS Signature Version 4 `Authorization` header for an S3 request without
+/// a query string. `headers` are lowercase and sorted by name, and include
+/// `x-amz-content-sha256` and `x-amz-date`.
+pub fn sigv4_authorization(
+    method: &str,
+    path: &str,
+    headers: &[(&str, &str)],
+    (access_key, secret_key): (&str, &str),
+    region: &str,
+    amz_date: &str,
+) -> String {
+    let date = amz_date.get(..8).unwrap_or(amz_date);
+    let payload_hash = headers
+        .iter()
+        .find(|(name, _)| *name == "x-amz-content-sha256")
+        .map_or("UNSIGNED-PAYLOAD", |(_, value)| *value);
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{}:{}\n", name, value.trim()))
+        .collect();
+    let signed_headers = headers
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(";");
+    let canonical_request = format!(
+        "{}\n{}\n\n{}\n{}\n{}",
+        method, path, canonical_headers, signed_headers, payload_hash
+    );
+
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+    let signing_key = [date, region, "s3", "aws4_request"]
+        .iter()
+        .fold(format!("AWS4{}", secret_key).into_bytes(), |key, part| {
+            hmac_sha256(&key, part)
+        });
+    let signature = hex(&hmac_sha256(&signing_key, &string_to_sign));
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        access_key, scope, signed_headers, signature
+    )
+}
+
+fn hmac_sha256(key: &[u8], message: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(message.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Percent-encode everything but unreserved characters, and slashes unless
+/// `encode_slash`, the way S3 signing expects
+fn uri_encode(text: &str, encode_slash: bool) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            b'/' if !encode_slash => "/".to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Double-quoted argument for an sftp batch command
+fn sftp_quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// MIME type for an image file, from its extension
+pub fn content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "tif" | "tiff" => "image/tiff",
+        "webp" => "image/webp",
+        "jxl" => "image/jxl",
+        _ => "application/octet-stream",
+    }
+}
+
+fn client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .user_agent(concat!("rView/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+fn block_on<T>(future: impl std::future::Future<Output = Result<T, String>>) -> Result<T, String> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| e.to_string())?
+        .block_on(future)
+}
@@ -0,0 +1,85 @@
+use crate::exif_data::ExifInfo;
+use crate::metadata::ImageMetadata;
+use crate::settings::ColorLabel;
+use std::collections::HashMap;
+
+/// Counts for a folder of images: how they were rated and labelled, what
+/// they were shot with, when, and how much disk space they take
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ShootSummary {
+    pub images: usize,
+    pub total_bytes: u64,
+    /// Images with 0 to 5 stars
+    pub ratings: [usize; 6],
+    /// Images per color label, in `ColorLabel::all()` order
+    pub labels: Vec<(ColorLabel, usize)>,
+    /// Most used first; images without the EXIF field count as "Unknown"
+    pub cameras: Vec<(String, usize)>,
+    pub lenses: Vec<(String, usize)>,
+    /// Shots per hour taken, oldest first, keyed like `2024-05-01 14:00`
+    pub hours: Vec<(String, usize)>,
+    /// Images without a capture date
+    pub undated: usize,
+}
+
+/// Summarize images from their metadata, EXIF (when read) and file size
+pub fn summarize<'a>(
+    images: impl IntoIterator<Item = (&'a ImageMetadata, Option<&'a ExifInfo>, u64)>,
+) -> ShootSummary {
+    let mut summary = ShootSummary::default();
+    let mut labels: HashMap<ColorLabel, usize> = HashMap::new();
+    let mut cameras: HashMap<String, usize> = HashMap::new();
+    let mut lenses: HashMap<String, usize> = HashMap::new();
+    let mut hours: HashMap<String, usize> = HashMap::new();
+
+    for (metadata, exif, bytes) in images {
+        summary.images += 1;
+        summary.total_bytes += bytes;
+        summary.ratings[metadata.rating.min(5) as usize] += 1;
+        *labels.entry(metadata.color_label).or_default() += 1;
+
+        let camera = exif.and_then(|e| e.camera_model.as_deref().or(e.camera_make.as_deref()));
+        *cameras.entry(known(camera)).or_default() += 1;
+        *lenses
+            .entry(known(exif.and_then(|e| e.lens.as_deref())))
+            .or_default() += 1;
+
+        match exif.and_then(|e| capture_hour(e.date_taken.as_deref()?)) {
+            Some(hour) => *hours.entry(hour).or_default() += 1,
+            None => summary.undated += 1,
+        }
+    }
+
+    summary.labels = ColorLabel::all()
+        .iter()
+        .map(|label| (*label, labels.get(label).copied().unwrap_or(0)))
+        .collect();
+    summary.cameras = most_used(cameras);
+    summary.lenses = most_used(lenses);
+    summary.hours = hours.into_iter().collect();
+    summary.hours.sort();
+    summary
+}
+
+fn known(value: Option<&str>) -> String {
+    value
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .unwrap_or("Unknown")
+        .to_string()
+}
+
+fn most_used(counts: HashMap<String, usize>) -> Vec<(String, usize)> {
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+/// EXIF dates look like `2024:05:01 14:23:05`; this gives `2024-05-01 14:00`
+fn capture_hour(date: &str) -> Option<String> {
+    let date = date.trim();
+    if date.len() < 13 || !date.is_char_boundary(13) {
+        return None;
+    }
+    Some(format!("{}:00", date[..13].replacen(':', "-", 2)))
+}
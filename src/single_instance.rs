@@ -0,0 +1,145 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// First line of every handoff, so stray connections are ignored
+const GREETING: &str = "rview-open";
+
+/// How long a second launch waits for the running window before starting its own
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(300);
+
+/// Token of this process's listener, to recognise its own instance file
+static TOKEN: OnceLock<String> = OnceLock::new();
+
+/// Where the running instance advertises its port and the token a handoff
+/// has to present. A loopback socket works the same on every platform,
+/// unlike named pipes and Unix sockets.
+fn instance_file() -> Option<PathBuf> {
+    directories::ProjectDirs::from("com", "imageviewer", "ImageViewer")
+        .map(|dirs| dirs.data_dir().join("instance"))
+}
+
+/// Contents of the instance file: port and token on their own lines
+pub fn parse_instance_file(text: &str) -> Option<(u16, String)> {
+    let mut lines = text.lines();
+    let port = lines.next()?.trim().parse().ok()?;
+    let token = lines.next()?.trim();
+    (!token.is_empty()).then(|| (port, token.to_string()))
+}
+
+/// Handoff message for `path`, or for just raising the window when None,
+/// with the command line options the window should apply
+pub fn handoff_message(token: &str, path: Option<&Path>, options: &[String]) -> String {
+    let path = path.map(|p| p.to_string_lossy().into_owned());
+    format!(
+        "{} {}\n{}\n{}\n",
+        GREETING,
+        token,
+        path.unwrap_or_default(),
+        options.join("\t")
+    )
+}
+
+/// Path and options carried by a handoff message, if its token matches
+pub fn parse_handoff(message: &str, token: &str) -> Option<(Option<PathBuf>, Vec<String>)> {
+    let mut lines = message.lines();
+    let greeting = lines.next()?.strip_prefix(GREETING)?.trim();
+    if greeting != token {
+        return None;
+    }
+    let path = lines.next().map(str::trim).unwrap_or_default();
+    let options = lines
+        .next()
+        .unwrap_or_default()
+        .split('\t')
+        .filter(|option| !option.is_empty())
+        .map(str::to_string)
+        .collect();
+    Some(((!path.is_empty()).then(|| PathBuf::from(path)), options))
+}
+
+/// Pass `path` and `options` to an rView that's already running. Returns
+/// false when none answers, in which case this process should open its own
+/// window.
+pub fn forward_to_running(path: Option<&Path>, options: &[String]) -> bool {
+    let Some(file) = instance_file() else {
+        return false;
+    };
+    let Some((port, token)) = std::fs::read_to_string(file)
+        .ok()
+        .and_then(|text| parse_instance_file(&text))
+    else {
+        return false;
+    };
+
+    // The running instance may have a different working directory
+    let path = path.map(|p| std::fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf()));
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let Ok(mut stream) = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT) else {
+        // Left behind by an instance that crashed
+        return false;
+    };
+    let _ = stream.set_read_timeout(Some(CONNECT_TIMEOUT));
+    if stream
+        .write_all(handoff_message(&token, path.as_deref(), options).as_bytes())
+        .is_err()
+    {
+        return false;
+    }
+    // Wait for the acknowledgement, so a port reused by another program doesn't count
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply).is_ok() && reply.trim() == "ok"
+}
+
+/// Accept handoffs from later launches on a background thread, calling
+/// `on_open` with each one's path and options
+pub fn listen<F>(on_open: F) -> std::io::Result<()>
+where
+    F: Fn(Option<PathBuf>, Vec<String>) + Send + 'static,
+{
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let port = listener.local_addr()?.port();
+    let token = TOKEN
+        .get_or_init(|| uuid::Uuid::new_v4().simple().to_string())
+        .clone();
+
+    let file = instance_file().ok_or_else(|| std::io::Error::other("no data directory"))?;
+    if let Some(dir) = file.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&file, format!("{}\n{}\n", port, token))?;
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let _ = stream.set_read_timeout(Some(Duration::from_secs(2)));
+            let mut reader = BufReader::new(&stream);
+            let mut message = String::new();
+            for _ in 0..3 {
+                if reader.read_line(&mut message).unwrap_or(0) == 0 {
+                    break;
+                }
+            }
+            if let Some((path, options)) = parse_handoff(&message, &token) {
+                let _ = (&stream).write_all(b"ok\n");
+                on_open(path, options);
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Stop advertising this instance, unless a newer one has taken over the file
+pub fn release() {
+    let (Some(file), Some(token)) = (instance_file(), TOKEN.get()) else {
+        return;
+    };
+    let ours = std::fs::read_to_string(&file)
+        .ok()
+        .and_then(|text| parse_instance_file(&text))
+        .is_some_and(|(_, t)| t == *token);
+    if ours {
+        let _ = std::fs::remove_file(file);
+    }
+}
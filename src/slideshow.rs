@@ -0,0 +1,326 @@
+use crate::errors::{Result, ViewerError};
+use crate::image_loader::ImageAdjustments;
+use image::imageops::FilterType;
+use image::{DynamicImage, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// File extension of saved playlists
+pub const PLAYLIST_EXTENSION: &str = "rvslides";
+
+/// An ordered slideshow, gathered from any number of folders
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Playlist {
+    pub slides: Vec<PathBuf>,
+    /// Seconds each slide is shown on its own
+    pub slide_seconds: f32,
+    /// Seconds of crossfade from one slide to the next
+    pub transition_seconds: f32,
+}
+
+impl Default for Playlist {
+    fn default() -> Self {
+        Self {
+            slides: Vec::new(),
+            slide_seconds: 4.0,
+            transition_seconds: 1.0,
+        }
+    }
+}
+
+impl Playlist {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Append the paths that aren't in the playlist yet; returns how many
+    pub fn add(&mut self, paths: impl IntoIterator<Item = PathBuf>) -> usize {
+        let before = self.slides.len();
+        for path in paths {
+            if !self.slides.contains(&path) {
+                self.slides.push(path);
+            }
+        }
+        self.slides.len() - before
+    }
+
+    /// Move the slide at `from` to `to`, shifting the ones between
+    pub fn move_slide(&mut self, from: usize, to: usize) {
+        if from < self.slides.len() && to < self.slides.len() {
+            let slide = self.slides.remove(from);
+            self.slides.insert(to, slide);
+        }
+    }
+
+    /// Running time of the whole slideshow in seconds
+    pub fn duration(&self) -> f32 {
+        let count = self.slides.len() as f32;
+        if count == 0.0 {
+            return 0.0;
+        }
+        count * self.slide_seconds + (count - 1.0) * self.transition_seconds
+    }
+
+    /// What frame `frame` of a video at `fps` shows: a slide, and the next
+    /// one with how far the crossfade to it has got
+    pub fn frame_slides(&self, frame: usize, fps: u32) -> (usize, Option<(usize, f32)>) {
+        let time = frame as f32 / fps.max(1) as f32;
+        let period = (self.slide_seconds + self.transition_seconds).max(0.001);
+        let last = self.slides.len().saturating_sub(1);
+        let slide = ((time / period) as usize).min(last);
+        let into_transition = time - slide as f32 * period - self.slide_seconds;
+        if slide < last && into_transition >= 0.0 && self.transition_seconds > 0.0 {
+            let progress = (into_transition / self.transition_seconds).clamp(0.0, 1.0);
+            (slide, Some((slide + 1, progress)))
+        } else {
+            (slide, None)
+        }
+    }
+
+    pub fn frame_count(&self, fps: u32) -> usize {
+        (self.duration() * fps as f32).round() as usize
+    }
+}
+
+/// Order slides are shown in, apart from how the folder is sorted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SlideshowOrder {
+    #[default]
+    Sequential,
+    Shuffle,
+    /// Shuffle without showing any image twice until all have been shown
+    ShuffleNoRepeat,
+}
+
+impl SlideshowOrder {
+    pub fn all() -> &'static [SlideshowOrder] {
+        &[
+            SlideshowOrder::Sequential,
+            SlideshowOrder::Shuffle,
+            SlideshowOrder::ShuffleNoRepeat,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SlideshowOrder::Sequential => "Sequential",
+            SlideshowOrder::Shuffle => "Shuffle",
+            SlideshowOrder::ShuffleNoRepeat => "Shuffle without repeats",
+        }
+    }
+}
+
+/// The slide to show after `current` of `count`. For a shuffle without
+/// repeats, `shown` tells which have been seen; once all have, a new round
+/// starts and the second value is true.
+pub fn next_slide(
+    order: SlideshowOrder,
+    current: usize,
+    count: usize,
+    shown: impl Fn(usize) -> bool,
+    rng: &mut impl rand::Rng,
+) -> (usize, bool) {
+    use rand::seq::SliceRandom;
+
+    let others: Vec<usize> = (0..count).filter(|&i| i != current).collect();
+    match order {
+        SlideshowOrder::Sequential => ((current + 1) % count.max(1), false),
+        SlideshowOrder::Shuffle => (others.choose(rng).copied().unwrap_or(current), false),
+        SlideshowOrder::ShuffleNoRepeat => {
+            let unseen: Vec<usize> = others.iter().copied().filter(|&i| !shown(i)).collect();
+            match unseen.choose(rng) {
+                Some(&next) => (next, false),
+                None => (others.choose(rng).copied().unwrap_or(current), true),
+            }
+        }
+    }
+}
+
+/// Size and frame rate of rendered slideshow videos
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VideoOptions {
+    pub width: u32,
+    pub height: u32,
+    pub fps: u32,
+}
+
+impl Default for VideoOptions {
+    fn default() -> Self {
+        Self {
+            width: 1920,
+            height: 1080,
+            fps: 30,
+        }
+    }
+}
+
+impl VideoOptions {
+    pub const SIZES: &'static [(u32, u32, &'static str)] = &[
+        (1280, 720, "720p"),
+        (1920, 1080, "1080p"),
+        (3840, 2160, "4K"),
+        (1080, 1080, "Square"),
+        (1080, 1920, "Vertical"),
+    ];
+
+    pub const FRAME_RATES: &'static [u32] = &[24, 25, 30, 60];
+}
+
+/// `image` scaled to fit a `width` by `height` frame, centred on black
+pub fn fit_to_frame(image: &DynamicImage, width: u32, height: u32) -> RgbaImage {
+    let scaled = image.resize(width, height, FilterType::Lanczos3).to_rgba8();
+    let mut frame = RgbaImage::from_pixel(width, height, Rgba([0, 0, 0, 255]));
+    let x = (width - scaled.width().min(width)) / 2;
+    let y = (height - scaled.height().min(height)) / 2;
+    image::imageops::overlay(&mut frame, &scaled, x as i64, y as i64);
+    frame
+}
+
+/// Crossfade from `from` to `to`, `progress` of the way through
+pub fn blend_frames(from: &RgbaImage, to: &RgbaImage, progress: f32) -> RgbaImage {
+    let mut frame = from.clone();
+    for (out, next) in frame.iter_mut().zip(to.iter()) {
+        *out = (*out as f32 + (*next as f32 - *out as f32) * progress).round() as u8;
+    }
+    frame
+}
+
+fn load_slide(
+    source: &Path,
+    adjustments: &ImageAdjustments,
+    options: &VideoOptions,
+) -> std::result::Result<RgbaImage, String> {
+    let image = crate::image_loader::load_image(source).map_err(|e| e.to_string())?;
+    let image = if adjustments.is_default() {
+        image
+    } else {
+        crate::image_loader::apply_adjustments_for_export(&image, adjustments, source)
+    };
+    Ok(fit_to_frame(&image, options.width, options.height))
+}
+
+/// Render the playlist to an H.264 MP4 through ffmpeg. `slides` are the
+/// playlist's images, in order, with the adjustments to show them with; ones
+/// that can't be loaded are shown as black. `progress` is told the frames
+/// written so far.
+pub fn render_video(
+    playlist: &Playlist,
+    slides: Vec<(PathBuf, ImageAdjustments)>,
+    options: &VideoOptions,
+    output: &Path,
+    cancel: &AtomicBool,
+    mut progress: impl FnMut(usize, usize),
+) -> Result<Vec<(PathBuf, String)>> {
+    let failed_export = |message: String| ViewerError::ExportError {
+        path: output.to_path_buf(),
+        message,
+    };
+    if !slides.iter().map(|(path, _)| path).eq(&playlist.slides) {
+        return Err(failed_export(
+            "The slides don't match the playlist".to_string(),
+        ));
+    }
+    let ffmpeg = crate::image_loader::find_tool("ffmpeg")
+        .ok_or_else(|| failed_export("ffmpeg not found; install FFmpeg to export video".into()))?;
+    // H.264 in 4:2:0 needs even dimensions
+    let (width, height) = (options.width & !1, options.height & !1);
+    let options = VideoOptions {
+        width,
+        height,
+        ..options.clone()
+    };
+
+    let mut child = Command::new(ffmpeg)
+        .args(["-y", "-loglevel", "error"])
+        .args(["-f", "rawvideo", "-pix_fmt", "rgba"])
+        .args(["-s", &format!("{}x{}", width, height)])
+        .args(["-r", &options.fps.to_string(), "-i", "-"])
+        .args(["-c:v", "libx264", "-pix_fmt", "yuv420p"])
+        .args(["-movflags", "+faststart"])
+        .arg(output)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+
+    let mut failed = Vec::new();
+    let black = || RgbaImage::from_pixel(width, height, Rgba([0, 0, 0, 255]));
+    let mut load = |index: usize| {
+        let (source, adjustments) = &slides[index];
+        load_slide(source, adjustments, &options).unwrap_or_else(|e| {
+            failed.push((source.clone(), e));
+            black()
+        })
+    };
+
+    // Only the slide on screen and the one fading in are kept in memory
+    let total = playlist.frame_count(options.fps);
+    let mut loaded: Vec<(usize, RgbaImage)> = Vec::new();
+    let mut written = Ok(());
+    for frame in 0..total {
+        if cancel.load(Ordering::Relaxed) {
+            written = Err(ViewerError::Cancelled);
+            break;
+        }
+        let (slide, next) = playlist.frame_slides(frame, options.fps);
+        loaded.retain(|(index, _)| *index >= slide);
+        for index in std::iter::once(slide).chain(next.map(|(n, _)| n)) {
+            if !loaded.iter().any(|(i, _)| *i == index) {
+                loaded.push((index, load(index)));
+            }
+        }
+        let image = |index: usize| {
+            loaded
+                .iter()
+                .find(|(i, _)| *i == index)
+                .map(|(_, image)| image)
+                .ok_or_else(|| failed_export(format!("Slide {} was not loaded", index + 1)))
+        };
+        let result = (|| -> Result<()> {
+            let data = match next {
+                Some((next, t)) => Cow::Owned(blend_frames(image(slide)?, image(next)?, t)),
+                None => Cow::Borrowed(image(slide)?),
+            };
+            Ok(stdin.write_all(&data)?)
+        })();
+        if let Err(e) = result {
+            written = Err(e);
+            break;
+        }
+        progress(frame + 1, total);
+    }
+
+    drop(stdin);
+    if written.is_err() {
+        let _ = child.kill();
+    }
+    let finished = child.wait_with_output()?;
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(output);
+        // A broken pipe means ffmpeg stopped; its own message says why
+        let message = String::from_utf8_lossy(&finished.stderr).trim().to_string();
+        return Err(match e {
+            ViewerError::Cancelled => e,
+            _ if !message.is_empty() => failed_export(message),
+            _ => e,
+        });
+    }
+    if !finished.status.success() {
+        let message = String::from_utf8_lossy(&finished.stderr).trim().to_string();
+        return Err(failed_export(message));
+    }
+    Ok(failed)
+}
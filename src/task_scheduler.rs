@@ -1,8 +1,9 @@
-use std::collections::BinaryHeap;
+use std::collections::{BinaryHeap, HashMap};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{self, Receiver, Sender};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Condvar, Mutex};
 use std::thread;
-use std::time::Duration;
 
 /// Priority levels for different types of image loading tasks
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -37,9 +38,9 @@ impl<T> PartialOrd for PrioritizedTask<T> {
 
 impl<T> Ord for PrioritizedTask<T> {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        // Higher priority first, then lower task_id (earlier tasks) first
-        other.priority.cmp(&self.priority)
-            .then_with(|| self.task_id.cmp(&other.task_id))
+        // BinaryHeap pops the greatest: higher priority first, then lower task_id (earlier tasks) first
+        self.priority.cmp(&other.priority)
+            .then_with(|| other.task_id.cmp(&self.task_id))
     }
 }
 
@@ -72,6 +73,52 @@ pub enum ImageTask {
         adjustments: crate::image_loader::ImageAdjustments,
         priority: TaskPriority,
     },
+    /// Any other background work; it reports back on its own
+    Run { job: Job, priority: TaskPriority },
+}
+
+/// A closure run by a worker
+pub struct Job(pub Box<dyn FnOnce() + Send>);
+
+impl std::fmt::Debug for Job {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Job")
+    }
+}
+
+/// The work a task does, so a second request for it can be recognised
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TaskKey {
+    Image(PathBuf),
+    Thumbnail(PathBuf, u32),
+    Exif(PathBuf),
+}
+
+impl ImageTask {
+    pub fn priority(&self) -> TaskPriority {
+        match self {
+            ImageTask::LoadImage { priority, .. } => *priority,
+            ImageTask::LoadThumbnail { priority, .. } => *priority,
+            ImageTask::LoadExif { priority, .. } => *priority,
+            ImageTask::ComputeHistogram { priority, .. } => *priority,
+            ImageTask::ApplyAdjustments { priority, .. } => *priority,
+            ImageTask::Run { priority, .. } => *priority,
+        }
+    }
+
+    /// Identity of file loads; other tasks are never merged
+    pub fn key(&self) -> Option<TaskKey> {
+        match self {
+            ImageTask::LoadImage { path, .. } => Some(TaskKey::Image(path.clone())),
+            ImageTask::LoadThumbnail { path, size, .. } => {
+                Some(TaskKey::Thumbnail(path.clone(), *size))
+            }
+            ImageTask::LoadExif { path, .. } => Some(TaskKey::Exif(path.clone())),
+            ImageTask::ComputeHistogram { .. }
+            | ImageTask::ApplyAdjustments { .. }
+            | ImageTask::Run { .. } => None,
+        }
+    }
 }
 
 /// Result types for completed tasks
@@ -84,6 +131,7 @@ pub enum TaskResult {
     ThumbnailLoaded {
         path: std::path::PathBuf,
         image: image::DynamicImage,
+        exposure: Option<crate::image_loader::ExposureWarning>,
     },
     ExifLoaded {
         path: std::path::PathBuf,
@@ -101,34 +149,44 @@ pub enum TaskResult {
     },
 }
 
-/// Priority-based task scheduler for image operations
+/// Priority-based task scheduler for image operations. A file already queued
+/// or being loaded isn't loaded twice, and queued loads can be cancelled when
+/// navigation moves on before a worker reaches them.
 pub struct TaskScheduler {
     task_queue: Arc<Mutex<BinaryHeap<PrioritizedTask<ImageTask>>>>,
+    /// Wakes idle workers when a task is queued or on shutdown
+    available: Arc<Condvar>,
+    /// Loads a worker has started, by the id they were submitted under
+    in_flight: Arc<Mutex<HashMap<TaskKey, u64>>>,
     result_tx: Sender<TaskResult>,
     result_rx: Receiver<TaskResult>,
     next_task_id: Arc<Mutex<u64>>,
     workers: Vec<thread::JoinHandle<()>>,
-    running: Arc<Mutex<bool>>,
+    running: Arc<AtomicBool>,
 }
 
 impl TaskScheduler {
     pub fn new(num_workers: usize) -> Self {
         let (result_tx, result_rx) = mpsc::channel();
         let task_queue = Arc::new(Mutex::new(BinaryHeap::new()));
+        let available = Arc::new(Condvar::new());
+        let in_flight = Arc::new(Mutex::new(HashMap::new()));
         let next_task_id = Arc::new(Mutex::new(0));
-        let running = Arc::new(Mutex::new(true));
+        let running = Arc::new(AtomicBool::new(true));
 
         let mut workers = Vec::with_capacity(num_workers);
 
         for i in 0..num_workers {
             let task_queue = Arc::clone(&task_queue);
+            let available = Arc::clone(&available);
+            let in_flight = Arc::clone(&in_flight);
             let result_tx = result_tx.clone();
             let running = Arc::clone(&running);
 
             let worker = thread::Builder::new()
                 .name(format!("image-worker-{}", i))
                 .spawn(move || {
-                    Self::worker_loop(task_queue, result_tx, running);
+                    Self::worker_loop(task_queue, available, in_flight, result_tx, running);
                 })
                 .expect("Failed to spawn worker thread");
 
@@ -137,6 +195,8 @@ impl TaskScheduler {
 
         Self {
             task_queue,
+            available,
+            in_flight,
             result_tx,
             result_rx,
             next_task_id,
@@ -147,21 +207,42 @@ impl TaskScheduler {
 
     fn worker_loop(
         task_queue: Arc<Mutex<BinaryHeap<PrioritizedTask<ImageTask>>>>,
+        available: Arc<Condvar>,
+        in_flight: Arc<Mutex<HashMap<TaskKey, u64>>>,
         result_tx: Sender<TaskResult>,
-        running: Arc<Mutex<bool>>,
+        running: Arc<AtomicBool>,
     ) {
-        while *running.lock().unwrap() {
-            let task = {
-                let mut queue = task_queue.lock().unwrap();
-                queue.pop()
+        loop {
+            // Mark the load as started while the queue is still locked, so a
+            // request submitted meanwhile sees it in one place or the other
+            let (task, key) = {
+                let queue = task_queue.lock().unwrap();
+                let mut queue = available
+                    .wait_while(queue, |queue| {
+                        queue.is_empty() && running.load(Ordering::Acquire)
+                    })
+                    .unwrap();
+                if !running.load(Ordering::Acquire) {
+                    return;
+                }
+                let Some(task) = queue.pop() else {
+                    continue;
+                };
+                let key = task.data.key();
+                if let Some(key) = &key {
+                    in_flight.lock().unwrap().insert(key.clone(), task.task_id);
+                }
+                (task, key)
             };
 
-            if let Some(prioritized_task) = task {
-                let result = Self::execute_task(prioritized_task.data);
-                let _ = result_tx.send(result);
-            } else {
-                // No tasks available, sleep briefly to avoid busy waiting
-                thread::sleep(Duration::from_millis(1));
+            match task.data {
+                ImageTask::Run { job, .. } => (job.0)(),
+                task => {
+                    let _ = result_tx.send(Self::execute_task(task));
+                }
+            }
+            if let Some(key) = key {
+                in_flight.lock().unwrap().remove(&key);
             }
         }
     }
@@ -178,8 +259,17 @@ impl TaskScheduler {
                 }
             }
             ImageTask::LoadThumbnail { path, size, .. } => {
+                let disk = crate::image_cache::disk_thumbnails();
+                if let Some(image) = disk.get(path, *size) {
+                    let exposure = crate::image_loader::exposure_warning(&image);
+                    return TaskResult::ThumbnailLoaded { path: path.clone(), image, exposure };
+                }
                 match crate::image_loader::load_thumbnail(path, *size) {
-                    Ok(image) => TaskResult::ThumbnailLoaded { path: path.clone(), image },
+                    Ok(image) => {
+                        disk.put(path, &image);
+                        let exposure = crate::image_loader::exposure_warning(&image);
+                        TaskResult::ThumbnailLoaded { path: path.clone(), image, exposure }
+                    }
                     Err(e) => TaskResult::Error {
                         task,
                         error: format!("Failed to load thumbnail: {}", e),
@@ -218,32 +308,67 @@ impl TaskScheduler {
                     }
                 }
             }
+            // The worker loop runs jobs itself
+            ImageTask::Run { .. } => TaskResult::Error {
+                task,
+                error: "Jobs have no result".to_string(),
+            },
         }
     }
 
+    /// Queue a task and return its id. Asking again for a load that is queued
+    /// or running returns the existing id, raising its priority if needed.
     pub fn submit_task(&self, task: ImageTask) -> u64 {
+        let priority = task.priority();
+        let mut queue = self.task_queue.lock().unwrap();
+
+        if let Some(key) = task.key() {
+            if let Some(&task_id) = self.in_flight.lock().unwrap().get(&key) {
+                return task_id;
+            }
+            let queued = queue
+                .iter()
+                .find(|t| t.data.key().as_ref() == Some(&key))
+                .map(|t| (t.task_id, t.priority));
+            match queued {
+                Some((task_id, queued_priority)) if queued_priority >= priority => {
+                    return task_id;
+                }
+                Some((task_id, _)) => queue.retain(|t| t.task_id != task_id),
+                None => {}
+            }
+        }
+
         let mut next_id = self.next_task_id.lock().unwrap();
         let task_id = *next_id;
         *next_id += 1;
 
-        let priority = match &task {
-            ImageTask::LoadImage { priority, .. } => *priority,
-            ImageTask::LoadThumbnail { priority, .. } => *priority,
-            ImageTask::LoadExif { priority, .. } => *priority,
-            ImageTask::ComputeHistogram { priority, .. } => *priority,
-            ImageTask::ApplyAdjustments { priority, .. } => *priority,
-        };
-
-        let prioritized_task = PrioritizedTask {
+        queue.push(PrioritizedTask {
             priority,
             task_id,
             data: task,
-        };
-
-        self.task_queue.lock().unwrap().push(prioritized_task);
+        });
+        self.available.notify_one();
         task_id
     }
 
+    /// Run `job` on a worker once the tasks queued ahead of it are taken
+    pub fn run<F: FnOnce() + Send + 'static>(&self, priority: TaskPriority, job: F) -> u64 {
+        self.submit_task(ImageTask::Run {
+            job: Job(Box::new(job)),
+            priority,
+        })
+    }
+
+    /// Drop queued tasks that are no longer wanted and return how many went.
+    /// Loads a worker already started still finish.
+    pub fn cancel_where<F: FnMut(&ImageTask) -> bool>(&self, mut stale: F) -> usize {
+        let mut queue = self.task_queue.lock().unwrap();
+        let before = queue.len();
+        queue.retain(|task| !stale(&task.data));
+        before - queue.len()
+    }
+
     pub fn try_recv_result(&self) -> Option<TaskResult> {
         self.result_rx.try_recv().ok()
     }
@@ -266,7 +391,11 @@ impl TaskScheduler {
     }
 
     pub fn shutdown(self) {
-        *self.running.lock().unwrap() = false;
+        self.running.store(false, Ordering::Release);
+        // Taking the lock makes sure no worker is between checking `running`
+        // and going to sleep, so none misses the wake-up
+        drop(self.task_queue.lock().unwrap());
+        self.available.notify_all();
         for worker in self.workers {
             let _ = worker.join();
         }
@@ -0,0 +1,41 @@
+use crate::image_loader::FilmPreset;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// How the viewer behaves while tethered to a camera's hot folder
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TetherSettings {
+    /// Film preset given to each arriving shot, by name; empty for none
+    pub film_preset: String,
+    pub fullscreen: bool,
+    /// Draw a large histogram over the image
+    pub histogram: bool,
+}
+
+impl Default for TetherSettings {
+    fn default() -> Self {
+        Self {
+            film_preset: String::new(),
+            fullscreen: true,
+            histogram: true,
+        }
+    }
+}
+
+impl TetherSettings {
+    pub fn film(&self) -> Option<FilmPreset> {
+        crate::convert::parse_film_preset(&self.film_preset)
+    }
+}
+
+/// The most recently written of newly arrived files; the last one listed
+/// when their times can't be read
+pub fn newest_arrival(paths: &[PathBuf]) -> Option<&Path> {
+    let modified = |path: &PathBuf| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    paths
+        .iter()
+        .enumerate()
+        .max_by_key(|(index, path)| (modified(path), *index))
+        .map(|(_, path)| path.as_path())
+}
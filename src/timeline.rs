@@ -0,0 +1,68 @@
+use chrono::{Datelike, NaiveDate};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Images grouped by the day they were taken, for the timeline's calendar
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Timeline {
+    pub days: BTreeMap<NaiveDate, Vec<PathBuf>>,
+}
+
+impl Timeline {
+    pub fn from_dates(entries: impl IntoIterator<Item = (PathBuf, NaiveDate)>) -> Self {
+        let mut days: BTreeMap<NaiveDate, Vec<PathBuf>> = BTreeMap::new();
+        for (path, date) in entries {
+            days.entry(date).or_default().push(path);
+        }
+        Self { days }
+    }
+
+    pub fn count(&self, date: NaiveDate) -> usize {
+        self.days.get(&date).map_or(0, Vec::len)
+    }
+
+    pub fn total(&self) -> usize {
+        self.days.values().map(Vec::len).sum()
+    }
+
+    /// Images on the busiest day, which gets the strongest shade
+    pub fn busiest(&self) -> usize {
+        self.days.values().map(Vec::len).max().unwrap_or(0)
+    }
+
+    /// Months with at least one image, oldest first, each with its image
+    /// count for every day of the month
+    pub fn months(&self) -> Vec<(NaiveDate, Vec<usize>)> {
+        let mut months: Vec<(NaiveDate, Vec<usize>)> = Vec::new();
+        for (date, paths) in &self.days {
+            let Some(first) = date.with_day(1) else {
+                continue;
+            };
+            if months.last().map(|(month, _)| *month) != Some(first) {
+                months.push((first, vec![0; days_in_month(first)]));
+            }
+            if let Some((_, counts)) = months.last_mut() {
+                counts[date.day0() as usize] = paths.len();
+            }
+        }
+        months
+    }
+}
+
+fn days_in_month(first: NaiveDate) -> usize {
+    let next = if first.month() == 12 {
+        NaiveDate::from_ymd_opt(first.year() + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(first.year(), first.month() + 1, 1)
+    };
+    next.map_or(31, |next| (next - first).num_days() as usize)
+}
+
+/// How strongly to shade a day, from 0 for none to 1 for the busiest day.
+/// Square-root scaled so a few frames still show up next to a big shoot.
+pub fn heat(count: usize, busiest: usize) -> f32 {
+    if count == 0 || busiest == 0 {
+        return 0.0;
+    }
+    (count as f32 / busiest as f32).sqrt()
+}
@@ -0,0 +1,53 @@
+use egui::style::{ScrollStyle, Spacing};
+use egui::Vec2;
+
+/// Shortest horizontal travel of a one-finger drag taken as a swipe
+pub const SWIPE_MIN_DISTANCE: f32 = 80.0;
+/// Longest a swipe may take; slower drags are pans
+pub const SWIPE_MAX_SECONDS: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Swipe {
+    Previous,
+    Next,
+}
+
+/// The swipe made by a one-finger drag that moved `travel` in `seconds`, if
+/// it was quick, long and mostly sideways
+pub fn classify_swipe(travel: Vec2, seconds: f64) -> Option<Swipe> {
+    let sideways = travel.x.abs() >= SWIPE_MIN_DISTANCE && travel.x.abs() > travel.y.abs() * 2.0;
+    if !sideways || seconds > SWIPE_MAX_SECONDS {
+        return None;
+    }
+    Some(if travel.x > 0.0 {
+        Swipe::Previous
+    } else {
+        Swipe::Next
+    })
+}
+
+/// Pan that keeps the image point under `anchor` in place while the zoom
+/// changes by `zoom_change`, then moves it by `translation`. `anchor` is
+/// relative to the view center.
+pub fn anchored_pan(pan: Vec2, anchor: Vec2, zoom_change: f32, translation: Vec2) -> Vec2 {
+    pan - (anchor - pan) * (zoom_change - 1.0) + translation
+}
+
+/// Spacing with hit targets large enough for fingers
+pub fn touch_spacing() -> Spacing {
+    Spacing {
+        item_spacing: Vec2::new(10.0, 8.0),
+        button_padding: Vec2::new(10.0, 8.0),
+        interact_size: Vec2::new(48.0, 36.0),
+        icon_width: 24.0,
+        icon_width_inner: 14.0,
+        icon_spacing: 8.0,
+        slider_width: 160.0,
+        combo_height: 320.0,
+        scroll: ScrollStyle {
+            bar_width: 16.0,
+            ..ScrollStyle::default()
+        },
+        ..Spacing::default()
+    }
+}
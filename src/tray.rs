@@ -0,0 +1,220 @@
+use crate::errors::{Result, ViewerError};
+use std::path::PathBuf;
+#[cfg(feature = "tray")]
+use tray_icon::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu};
+#[cfg(feature = "tray")]
+use tray_icon::{Icon, TrayIcon, TrayIconBuilder};
+
+/// Recent folders listed in the tray menu
+pub const TRAY_FOLDERS: usize = 8;
+
+const FOLDER_ID_PREFIX: &str = "folder:";
+
+/// What a tray menu item asks the viewer to do
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrayCommand {
+    ToggleWindow,
+    OpenFolder(PathBuf),
+    ToggleSlideshow,
+    Quit,
+}
+
+impl TrayCommand {
+    /// Menu item id standing for the command
+    pub fn id(&self) -> String {
+        match self {
+            TrayCommand::ToggleWindow => "window".to_string(),
+            TrayCommand::OpenFolder(folder) => {
+                format!("{}{}", FOLDER_ID_PREFIX, folder.to_string_lossy())
+            }
+            TrayCommand::ToggleSlideshow => "slideshow".to_string(),
+            TrayCommand::Quit => "quit".to_string(),
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "window" => Some(TrayCommand::ToggleWindow),
+            "slideshow" => Some(TrayCommand::ToggleSlideshow),
+            "quit" => Some(TrayCommand::Quit),
+            _ => id
+                .strip_prefix(FOLDER_ID_PREFIX)
+                .map(|folder| TrayCommand::OpenFolder(PathBuf::from(folder))),
+        }
+    }
+}
+
+/// Tray menu contents, put together on the UI thread where translations live
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrayMenu {
+    pub show_hide: String,
+    pub recent: String,
+    pub slideshow: String,
+    pub quit: String,
+    pub folders: Vec<PathBuf>,
+}
+
+#[cfg(feature = "tray")]
+fn build_menu(contents: &TrayMenu) -> Menu {
+    let recent = Submenu::new(&contents.recent, !contents.folders.is_empty());
+    for folder in &contents.folders {
+        let name = folder.file_name().map_or_else(
+            || folder.to_string_lossy().to_string(),
+            |name| name.to_string_lossy().to_string(),
+        );
+        let id = TrayCommand::OpenFolder(folder.clone()).id();
+        let _ = recent.append(&MenuItem::with_id(id, name, true, None));
+    }
+    let item =
+        |command: TrayCommand, label: &str| MenuItem::with_id(command.id(), label, true, None);
+
+    let menu = Menu::new();
+    let result = menu.append_items(&[
+        &item(TrayCommand::ToggleWindow, &contents.show_hide),
+        &recent,
+        &item(TrayCommand::ToggleSlideshow, &contents.slideshow),
+        &PredefinedMenuItem::separator(),
+        &item(TrayCommand::Quit, &contents.quit),
+    ]);
+    if let Err(e) = result {
+        log::warn!("Couldn't fill the tray menu: {}", e);
+    }
+    menu
+}
+
+#[cfg(feature = "tray")]
+fn build_icon(contents: &TrayMenu, image: egui::IconData) -> Result<TrayIcon> {
+    let tray_error = |message: String| ViewerError::InvalidOperation { message };
+    let icon = Icon::from_rgba(image.rgba, image.width, image.height)
+        .map_err(|e| tray_error(e.to_string()))?;
+    TrayIconBuilder::new()
+        .with_menu(Box::new(build_menu(contents)))
+        .with_tooltip("rView")
+        .with_icon(icon)
+        .build()
+        .map_err(|e| tray_error(e.to_string()))
+}
+
+/// The viewer's icon in the system tray, removed when dropped
+#[cfg(feature = "tray")]
+pub struct Tray {
+    #[cfg(not(target_os = "linux"))]
+    icon: TrayIcon,
+}
+
+#[cfg(feature = "tray")]
+impl Tray {
+    /// Show the icon; `on_command` is called from the menu's thread
+    pub fn create(
+        contents: &TrayMenu,
+        image: egui::IconData,
+        on_command: impl Fn(TrayCommand) + Send + Sync + 'static,
+    ) -> Result<Self> {
+        MenuEvent::set_event_handler(Some(move |event: MenuEvent| {
+            if let Some(command) = TrayCommand::from_id(&event.id.0) {
+                on_command(command);
+            }
+        }));
+
+        #[cfg(target_os = "linux")]
+        {
+            let contents = contents.clone();
+            gtk_thread::run(move |icon| match build_icon(&contents, image) {
+                Ok(tray) => *icon = Some(tray),
+                Err(e) => log::warn!("Couldn't show the tray icon: {}", e),
+            })?;
+            Ok(Self {})
+        }
+        #[cfg(not(target_os = "linux"))]
+        Ok(Self {
+            icon: build_icon(contents, image)?,
+        })
+    }
+
+    pub fn set_menu(&self, contents: &TrayMenu) {
+        #[cfg(target_os = "linux")]
+        {
+            let contents = contents.clone();
+            let _ = gtk_thread::run(move |icon| {
+                if let Some(icon) = icon {
+                    icon.set_menu(Some(Box::new(build_menu(&contents))));
+                }
+            });
+        }
+        #[cfg(not(target_os = "linux"))]
+        self.icon.set_menu(Some(Box::new(build_menu(contents))));
+    }
+}
+
+#[cfg(all(feature = "tray", target_os = "linux"))]
+impl Drop for Tray {
+    fn drop(&mut self) {
+        let _ = gtk_thread::run(|icon| *icon = None);
+    }
+}
+
+/// Without the tray feature no icon can be shown
+#[cfg(not(feature = "tray"))]
+pub struct Tray {
+    never: std::convert::Infallible,
+}
+
+#[cfg(not(feature = "tray"))]
+impl Tray {
+    pub fn create(
+        _contents: &TrayMenu,
+        _image: egui::IconData,
+        _on_command: impl Fn(TrayCommand) + Send + Sync + 'static,
+    ) -> Result<Self> {
+        Err(ViewerError::InvalidOperation {
+            message: "rView was built without the tray feature".to_string(),
+        })
+    }
+
+    pub fn set_menu(&self, _contents: &TrayMenu) {
+        match self.never {}
+    }
+}
+
+/// On Linux the tray belongs to GTK, which runs on a thread of its own for
+/// the life of the process
+#[cfg(all(feature = "tray", target_os = "linux"))]
+mod gtk_thread {
+    use crate::errors::{Result, ViewerError};
+    use std::cell::RefCell;
+    use std::sync::OnceLock;
+    use tray_icon::TrayIcon;
+
+    static STARTED: OnceLock<std::result::Result<(), String>> = OnceLock::new();
+
+    thread_local! {
+        static ICON: RefCell<Option<TrayIcon>> = const { RefCell::new(None) };
+    }
+
+    /// Run `f` on the GTK thread with the tray icon slot
+    pub fn run(f: impl FnOnce(&mut Option<TrayIcon>) + Send + 'static) -> Result<()> {
+        let started = STARTED.get_or_init(|| {
+            let (tx, rx) = std::sync::mpsc::channel();
+            std::thread::spawn(move || match gtk::init() {
+                Ok(()) => {
+                    let _ = tx.send(Ok(()));
+                    gtk::main();
+                }
+                Err(e) => {
+                    let _ = tx.send(Err(e.to_string()));
+                }
+            });
+            rx.recv()
+                .unwrap_or_else(|_| Err("the GTK thread stopped".to_string()))
+        });
+        if let Err(message) = started {
+            return Err(ViewerError::InvalidOperation {
+                message: format!("GTK isn't available: {}", message),
+            });
+        }
+        gtk::glib::MainContext::default().invoke(move || {
+            ICON.with(|icon| f(&mut icon.borrow_mut()));
+        });
+        Ok(())
+    }
+}
@@ -1,5 +1,10 @@
 use crate::app::ImageViewerApp;
-use crate::image_loader::{FilmPreset, ImageAdjustments};
+use crate::brush::{BrushSettings, PressureMapping};
+use crate::color_management::ProofIntent;
+use crate::image_loader::{
+    is_float, kelvin_to_temperature, temperature_to_kelvin, FilmPreset, FrameStyle,
+    ImageAdjustments, LocalMask, LocalMaskKind, ToneMap, NEUTRAL_KELVIN,
+};
 use crate::metadata::FileOperation;
 use crate::ui::common;
 use egui::{self, Color32, CornerRadius, RichText, Stroke};
@@ -8,6 +13,15 @@ const LR_BG_INPUT: Color32 = Color32::from_rgb(34, 34, 34);
 const LR_TEXT_LABEL: Color32 = Color32::from_rgb(180, 180, 180);
 const LR_TEXT_SECONDARY: Color32 = Color32::from_rgb(140, 140, 140);
 
+/// Canvas ratios offered by the pad-to-aspect frame
+const FRAME_ASPECTS: [(&str, [f32; 2]); 5] = [
+    ("4:5 portrait", [4.0, 5.0]),
+    ("1:1 square", [1.0, 1.0]),
+    ("9:16 story", [9.0, 16.0]),
+    ("3:2", [3.0, 2.0]),
+    ("16:9", [16.0, 9.0]),
+];
+
 pub fn render_basic_panel(app: &mut ImageViewerApp, ui: &mut egui::Ui) {
     let was_dragging = app.slider_dragging;
     let mut adjustments_changed = false;
@@ -19,16 +33,29 @@ pub fn render_basic_panel(app: &mut ImageViewerApp, ui: &mut egui::Ui) {
         // WB: White Balance section
         // 'As Shot' removed — no preset label shown here
 
-        // Temperature
+        // Temperature, shown as the Kelvin of the light being corrected for
+        let mut kelvin = temperature_to_kelvin(app.adjustments.temperature);
         let (changed, dragging) = common::lr_slider_ex(
             ui,
             "Temp",
-            &mut app.adjustments.temperature,
-            -1.0..=1.0,
-            "",
-            0.0,
+            &mut kelvin,
+            temperature_to_kelvin(-1.0)..=temperature_to_kelvin(1.0),
+            "K",
+            NEUTRAL_KELVIN,
         );
         if changed {
+            app.adjustments.temperature = kelvin_to_temperature(kelvin);
+            adjustments_changed = true;
+            app.mark_adjustments_dirty();
+        }
+        any_slider_dragging |= dragging;
+
+        // Tint (convert from -1..1 to -100 to +100, green to magenta)
+        let mut tint_display = app.adjustments.tint * 100.0;
+        let (changed, dragging) =
+            common::lr_slider_ex(ui, "Tint", &mut tint_display, -100.0..=100.0, "", 0.0);
+        if changed {
+            app.adjustments.tint = tint_display / 100.0;
             adjustments_changed = true;
             app.mark_adjustments_dirty();
         }
@@ -57,6 +84,27 @@ pub fn render_basic_panel(app: &mut ImageViewerApp, ui: &mut egui::Ui) {
         common::lr_separator(ui);
         ui.add_space(4.0);
 
+        // Contrast (convert from -1..1 to -100 to +100)
+        let mut contrast_display = app.adjustments.contrast * 100.0;
+        let (changed, dragging) = common::lr_slider_ex(
+            ui,
+            "Contrast",
+            &mut contrast_display,
+            -100.0..=100.0,
+            "",
+            0.0,
+        );
+        if changed {
+            app.adjustments.contrast = contrast_display / 100.0;
+            adjustments_changed = true;
+            app.mark_adjustments_dirty();
+        }
+        any_slider_dragging |= dragging;
+
+        ui.add_space(4.0);
+        common::lr_separator(ui);
+        ui.add_space(4.0);
+
         // Saturation (convert from 0-2 to -100 to +100)
         let mut sat_display = (app.adjustments.saturation - 1.0) * 100.0;
         let (changed, dragging) =
@@ -68,10 +116,42 @@ pub fn render_basic_panel(app: &mut ImageViewerApp, ui: &mut egui::Ui) {
         }
         any_slider_dragging |= dragging;
 
+        ui.add_space(4.0);
+        common::lr_separator(ui);
+        ui.add_space(4.0);
+
+        // Straighten (degrees clockwise, image is auto-cropped)
+        let (changed, dragging) = common::lr_slider_ex(
+            ui,
+            "Straighten",
+            &mut app.adjustments.straighten_angle,
+            -45.0..=45.0,
+            "°",
+            0.0,
+        );
+        if changed {
+            adjustments_changed = true;
+            app.mark_adjustments_dirty();
+        }
+        any_slider_dragging |= dragging;
+
         ui.add_space(8.0);
 
-        // Reset button
+        // Auto and Reset buttons
         ui.horizontal(|ui| {
+            if ui
+                .add(
+                    egui::Button::new(RichText::new("Auto").size(10.0).color(LR_TEXT_SECONDARY))
+                        .fill(LR_BG_INPUT)
+                        .stroke(Stroke::new(1.0, common::LR_BORDER))
+                        .corner_radius(CornerRadius::same(2)),
+                )
+                .on_hover_text("Set exposure, contrast and white balance from the histogram")
+                .clicked()
+            {
+                app.pre_drag_adjustments = None;
+                app.auto_enhance();
+            }
             if ui
                 .add(
                     egui::Button::new(RichText::new("Reset").size(10.0).color(LR_TEXT_SECONDARY))
@@ -106,6 +186,8 @@ pub fn render_basic_panel(app: &mut ImageViewerApp, ui: &mut egui::Ui) {
         });
     });
 
+    render_local_adjustments_panel(app, ui, &mut adjustments_changed, &mut any_slider_dragging);
+
     // Update slider dragging state
     app.slider_dragging = any_slider_dragging;
 
@@ -114,7 +196,11 @@ pub fn render_basic_panel(app: &mut ImageViewerApp, ui: &mut egui::Ui) {
         app.pre_drag_adjustments = Some(app.adjustments.clone());
     }
 
+    render_hdr_panel(app, ui);
+    render_user_presets_panel(app, ui);
     render_film_emulation_panel(app, ui, &mut adjustments_changed);
+    render_lut_panel(app, ui, &mut adjustments_changed);
+    render_soft_proof_panel(app, ui);
 
     // When drag ends, finalize: save undo, metadata, and invalidate thumbnail
     if was_dragging && !any_slider_dragging {
@@ -161,6 +247,257 @@ pub fn render_basic_panel(app: &mut ImageViewerApp, ui: &mut egui::Ui) {
     }
 }
 
+/// Gradient-masked exposure, saturation and white balance. Masks are drawn on
+/// the image with the Radial and Linear tools, then tuned here.
+pub fn render_local_adjustments_panel(
+    app: &mut ImageViewerApp,
+    ui: &mut egui::Ui,
+    adjustments_changed: &mut bool,
+    any_slider_dragging: &mut bool,
+) {
+    common::lr_collapsible_panel(ui, "Local Adjustments", false, |ui| {
+        ui.spacing_mut().slider_width = ui.available_width() - 80.0;
+
+        ui.horizontal(|ui| {
+            for (kind, label, hint) in [
+                (
+                    LocalMaskKind::Radial,
+                    "Radial",
+                    "Drag out from the centre of the area",
+                ),
+                (
+                    LocalMaskKind::Linear,
+                    "Linear",
+                    "Drag from full effect to none",
+                ),
+                (
+                    LocalMaskKind::Brush,
+                    "Brush",
+                    "Paint over the area; strokes add to the selected brush mask",
+                ),
+            ] {
+                let armed = app.local_mask_tool == Some(kind);
+                if ui
+                    .selectable_label(armed, RichText::new(label).size(10.0))
+                    .on_hover_text(hint)
+                    .clicked()
+                {
+                    app.toggle_local_mask_tool(kind);
+                }
+            }
+        });
+
+        if app.local_mask_tool == Some(LocalMaskKind::Brush) {
+            render_brush_settings(ui, &mut app.settings.brush);
+        }
+
+        if app.adjustments.local.is_empty() {
+            ui.label(
+                RichText::new("Pick a tool, then drag on the image")
+                    .size(10.0)
+                    .color(LR_TEXT_SECONDARY),
+            );
+            return;
+        }
+
+        ui.add_space(4.0);
+        let mut delete = None;
+        for (i, local) in app.adjustments.local.iter().enumerate() {
+            ui.horizontal(|ui| {
+                let selected = app.selected_local_adjustment == Some(i);
+                let label = format!("{} {}", local.mask.label(), i + 1);
+                if ui
+                    .selectable_label(selected, RichText::new(label).size(10.0))
+                    .clicked()
+                {
+                    app.selected_local_adjustment = (!selected).then_some(i);
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.small_button("🗑").on_hover_text("Delete").clicked() {
+                        delete = Some(i);
+                    }
+                });
+            });
+        }
+        if let Some(i) = delete {
+            app.delete_local_adjustment(i);
+        }
+
+        let Some(index) = app
+            .selected_local_adjustment
+            .filter(|&i| i < app.adjustments.local.len())
+        else {
+            return;
+        };
+
+        ui.add_space(4.0);
+        common::lr_separator(ui);
+        ui.add_space(4.0);
+
+        let mut changed = false;
+        let local = &mut app.adjustments.local[index];
+
+        let (c, dragging) =
+            common::lr_slider_ex(ui, "Exposure", &mut local.exposure, -3.0..=3.0, " EV", 0.0);
+        changed |= c;
+        *any_slider_dragging |= dragging;
+
+        let mut sat_display = local.saturation * 100.0;
+        let (c, dragging) =
+            common::lr_slider_ex(ui, "Saturation", &mut sat_display, -100.0..=100.0, "", 0.0);
+        if c {
+            local.saturation = sat_display / 100.0;
+            changed = true;
+        }
+        *any_slider_dragging |= dragging;
+
+        let (c, dragging) =
+            common::lr_slider_ex(ui, "Temp", &mut local.temperature, -1.0..=1.0, "", 0.0);
+        changed |= c;
+        *any_slider_dragging |= dragging;
+
+        let (c, dragging) = common::lr_slider_ex(ui, "Tint", &mut local.tint, -1.0..=1.0, "", 0.0);
+        changed |= c;
+        *any_slider_dragging |= dragging;
+
+        if let LocalMask::Radial { feather, .. } = &mut local.mask {
+            let mut feather_display = *feather * 100.0;
+            let (c, dragging) =
+                common::lr_slider_ex(ui, "Feather", &mut feather_display, 0.0..=100.0, "", 50.0);
+            if c {
+                *feather = feather_display / 100.0;
+                changed = true;
+            }
+            *any_slider_dragging |= dragging;
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(RichText::new("Invert").size(10.0).color(LR_TEXT_LABEL));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.checkbox(&mut local.invert, "").changed() {
+                    changed = true;
+                }
+            });
+        });
+
+        if changed {
+            *adjustments_changed = true;
+            app.mark_adjustments_dirty();
+        }
+    });
+}
+
+/// Size, opacity and pen pressure response of the armed brush
+fn render_brush_settings(ui: &mut egui::Ui, brush: &mut BrushSettings) {
+    let mut size_display = brush.size * 100.0;
+    if common::lr_slider(ui, "Size", &mut size_display, 0.5..=30.0, "%", 5.0) {
+        brush.size = size_display / 100.0;
+    }
+    let mut opacity_display = brush.opacity * 100.0;
+    if common::lr_slider(ui, "Opacity", &mut opacity_display, 0.0..=100.0, "%", 100.0) {
+        brush.opacity = opacity_display / 100.0;
+    }
+    ui.horizontal(|ui| {
+        ui.label(RichText::new("Pressure:").size(11.0).color(LR_TEXT_LABEL))
+            .on_hover_text("What pressing harder with a pen changes");
+        ui.add_space(8.0);
+        egui::ComboBox::from_id_salt("brush_pressure")
+            .width(ui.available_width() - 8.0)
+            .selected_text(brush.pressure.name())
+            .show_ui(ui, |ui| {
+                for mapping in PressureMapping::all() {
+                    ui.selectable_value(&mut brush.pressure, *mapping, mapping.name());
+                }
+            });
+    });
+    ui.add_space(4.0);
+}
+
+/// Exposure and tone mapping for float (EXR, Radiance HDR) images. Only shown
+/// while one is open; these are view settings, not edits saved with the image.
+pub fn render_hdr_panel(app: &mut ImageViewerApp, ui: &mut egui::Ui) {
+    if !app.current_image.as_deref().is_some_and(is_float) {
+        return;
+    }
+
+    let mut changed = false;
+    let mut dragging = false;
+    common::lr_collapsible_panel(ui, "HDR", true, |ui| {
+        ui.spacing_mut().slider_width = ui.available_width() - 80.0;
+        let hdr = &mut app.settings.hdr_display;
+
+        let (exposure_changed, exposure_dragging) =
+            common::lr_slider_ex(ui, "Exposure", &mut hdr.exposure, -8.0..=8.0, " EV", 0.0);
+        changed |= exposure_changed;
+        dragging = exposure_dragging;
+
+        ui.add_space(4.0);
+        ui.horizontal(|ui| {
+            ui.label(RichText::new("Tone map:").size(11.0).color(LR_TEXT_LABEL));
+            ui.add_space(8.0);
+            egui::ComboBox::from_id_salt("hdr_tone_map_panel")
+                .width(ui.available_width() - 8.0)
+                .selected_text(hdr.tone_map.label())
+                .show_ui(ui, |ui| {
+                    for tone_map in ToneMap::ALL {
+                        changed |= ui
+                            .selectable_value(&mut hdr.tone_map, tone_map, tone_map.label())
+                            .changed();
+                    }
+                });
+        });
+    });
+
+    if changed {
+        app.refresh_adjustments_internal(!dragging);
+    }
+}
+
+pub fn render_user_presets_panel(app: &mut ImageViewerApp, ui: &mut egui::Ui) {
+    common::lr_collapsible_panel(ui, "Presets", false, |ui| {
+        let mut apply = None;
+        ui.horizontal(|ui| {
+            ui.label(RichText::new("User:").size(11.0).color(LR_TEXT_LABEL));
+            ui.add_space(8.0);
+            egui::ComboBox::from_id_salt("user_preset")
+                .width(ui.available_width() - 8.0)
+                .selected_text(if app.settings.user_presets.is_empty() {
+                    "No presets"
+                } else {
+                    "Apply preset..."
+                })
+                .show_ui(ui, |ui| {
+                    for (i, preset) in app.settings.user_presets.iter().enumerate() {
+                        if ui.selectable_label(false, preset.name.as_str()).clicked() {
+                            apply = Some(i);
+                        }
+                    }
+                });
+        });
+        if let Some(i) = apply {
+            app.apply_user_preset(i);
+        }
+
+        ui.add_space(4.0);
+        ui.horizontal(|ui| {
+            // Saving and managing both happen in the presets dialog
+            for label in ["Save Current...", "Manage..."] {
+                if ui
+                    .add(
+                        egui::Button::new(RichText::new(label).size(10.0).color(LR_TEXT_SECONDARY))
+                            .fill(LR_BG_INPUT)
+                            .stroke(Stroke::new(1.0, common::LR_BORDER))
+                            .corner_radius(CornerRadius::same(2)),
+                    )
+                    .clicked()
+                {
+                    app.show_presets_dialog = true;
+                }
+            }
+        });
+    });
+}
+
 pub fn render_film_emulation_panel(
     app: &mut ImageViewerApp,
     ui: &mut egui::Ui,
@@ -169,12 +506,28 @@ pub fn render_film_emulation_panel(
     common::lr_collapsible_panel(ui, "Film Emulation", false, |ui| {
         ui.spacing_mut().slider_width = ui.available_width() - 80.0;
 
+        // A saved stock with exactly the current look names the profile
+        let custom_stock = (app.current_film_preset == FilmPreset::None
+            && app.adjustments.film.enabled)
+            .then(|| {
+                app.settings
+                    .film_stocks
+                    .iter()
+                    .position(|s| s.film == app.adjustments.film)
+            })
+            .flatten();
+        let profile_name = match custom_stock {
+            Some(i) => app.settings.film_stocks[i].name.clone(),
+            None => app.current_film_preset.name().to_string(),
+        };
+        let mut apply_stock = None;
+
         ui.horizontal(|ui| {
             ui.label(RichText::new("Profile:").size(11.0).color(LR_TEXT_LABEL));
             ui.add_space(8.0);
             egui::ComboBox::from_id_salt("film_preset")
                 .width(ui.available_width() - 8.0)
-                .selected_text(app.current_film_preset.name())
+                .selected_text(profile_name)
                 .show_ui(ui, |ui| {
                     for preset in FilmPreset::all() {
                         let selected = *preset == app.current_film_preset;
@@ -202,8 +555,41 @@ pub fn render_film_emulation_panel(
                             }
                         }
                     }
+
+                    // User stocks after the built-in ones
+                    if !app.settings.film_stocks.is_empty() {
+                        ui.separator();
+                    }
+                    for (i, stock) in app.settings.film_stocks.iter().enumerate() {
+                        let selected = custom_stock == Some(i);
+                        if ui.selectable_label(selected, stock.name.as_str()).clicked() {
+                            apply_stock = Some(i);
+                        }
+                    }
                 });
         });
+        if let Some(i) = apply_stock {
+            app.apply_film_stock(i);
+        }
+
+        ui.add_space(4.0);
+        if ui
+            .add(
+                egui::Button::new(
+                    RichText::new("Film Stocks...")
+                        .size(10.0)
+                        .color(LR_TEXT_SECONDARY),
+                )
+                .fill(LR_BG_INPUT)
+                .stroke(Stroke::new(1.0, common::LR_BORDER))
+                .corner_radius(CornerRadius::same(2)),
+            )
+            .on_hover_text("Save this look as a film stock, or import and export stocks")
+            .clicked()
+        {
+            app.show_film_stocks_dialog = true;
+        }
+        ui.add_space(4.0);
 
         if app.adjustments.film.enabled {
             // Grain
@@ -538,6 +924,55 @@ pub fn render_film_emulation_panel(
             });
 
             if app.adjustments.frame_enabled {
+                // Frame Style
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new("Style").size(10.0).color(LR_TEXT_LABEL));
+                    egui::ComboBox::from_id_salt("frame_style")
+                        .selected_text(app.adjustments.frame_style.label())
+                        .show_ui(ui, |ui| {
+                            for style in FrameStyle::ALL {
+                                if ui
+                                    .selectable_value(
+                                        &mut app.adjustments.frame_style,
+                                        style,
+                                        style.label(),
+                                    )
+                                    .changed()
+                                {
+                                    *adjustments_changed = true;
+                                    app.mark_adjustments_dirty();
+                                }
+                            }
+                        });
+                });
+
+                if app.adjustments.frame_style == FrameStyle::Aspect {
+                    let current = FRAME_ASPECTS
+                        .iter()
+                        .find(|(_, ratio)| *ratio == app.adjustments.frame_aspect)
+                        .map_or("Custom", |(label, _)| *label);
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new("Aspect").size(10.0).color(LR_TEXT_LABEL));
+                        egui::ComboBox::from_id_salt("frame_aspect")
+                            .selected_text(current)
+                            .show_ui(ui, |ui| {
+                                for (label, ratio) in FRAME_ASPECTS {
+                                    if ui
+                                        .selectable_value(
+                                            &mut app.adjustments.frame_aspect,
+                                            ratio,
+                                            label,
+                                        )
+                                        .changed()
+                                    {
+                                        *adjustments_changed = true;
+                                        app.mark_adjustments_dirty();
+                                    }
+                                }
+                            });
+                    });
+                }
+
                 // Frame Thickness
                 let mut thickness_display = app.adjustments.frame_thickness;
                 if common::lr_slider(
@@ -553,6 +988,38 @@ pub fn render_film_emulation_panel(
                     app.mark_adjustments_dirty();
                 }
 
+                let mut radius_display = app.adjustments.frame_corner_radius;
+                if common::lr_slider(
+                    ui,
+                    "Corner Radius",
+                    &mut radius_display,
+                    0.0..=200.0,
+                    "",
+                    0.0,
+                ) {
+                    app.adjustments.frame_corner_radius = radius_display;
+                    *adjustments_changed = true;
+                    app.mark_adjustments_dirty();
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label(
+                        RichText::new("EXIF Caption")
+                            .size(10.0)
+                            .color(LR_TEXT_LABEL),
+                    )
+                    .on_hover_text("Camera and exposure under the photo, added when exporting");
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui
+                            .checkbox(&mut app.adjustments.frame_caption, "")
+                            .changed()
+                        {
+                            *adjustments_changed = true;
+                            app.mark_adjustments_dirty();
+                        }
+                    });
+                });
+
                 // Frame Color
                 ui.label(
                     RichText::new("Frame Color")
@@ -593,3 +1060,166 @@ pub fn render_film_emulation_panel(
         }
     });
 }
+
+/// Print simulation through a printer or paper ICC profile. The settings are
+/// global; they aren't part of the image's adjustments.
+pub fn render_soft_proof_panel(app: &mut ImageViewerApp, ui: &mut egui::Ui) {
+    common::lr_collapsible_panel(ui, "Soft Proofing", false, |ui| {
+        let mut settings = app.settings.soft_proof.clone();
+        let mut toggle = false;
+
+        ui.horizontal(|ui| {
+            let mut enabled = app.soft_proof_enabled;
+            toggle = ui.checkbox(&mut enabled, "Proof").changed();
+            ui.add_space(8.0);
+            ui.checkbox(&mut settings.gamut_warning, "Gamut warning")
+                .on_hover_text("Highlight colours the printer can't reproduce");
+        });
+
+        ui.add_space(4.0);
+        ui.horizontal(|ui| {
+            let profile_name = settings
+                .profile
+                .as_ref()
+                .and_then(|p| p.file_name())
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| "No profile".to_string());
+            ui.label(RichText::new(profile_name).size(11.0).color(LR_TEXT_LABEL));
+            if ui.small_button("Profile...").clicked() {
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("ICC profiles", &["icc", "icm"])
+                    .pick_file()
+                {
+                    settings.profile = Some(path);
+                }
+            }
+        });
+
+        ui.add_space(4.0);
+        ui.horizontal(|ui| {
+            ui.label(RichText::new("Intent:").size(11.0).color(LR_TEXT_LABEL));
+            ui.add_space(8.0);
+            egui::ComboBox::from_id_salt("soft_proof_intent")
+                .width(ui.available_width() - 8.0)
+                .selected_text(settings.intent.label())
+                .show_ui(ui, |ui| {
+                    for intent in ProofIntent::ALL {
+                        ui.selectable_value(&mut settings.intent, intent, intent.label());
+                    }
+                });
+        });
+
+        app.set_soft_proof_settings(settings);
+        if toggle {
+            app.toggle_soft_proof();
+        }
+    });
+}
+
+pub fn render_lut_panel(
+    app: &mut ImageViewerApp,
+    ui: &mut egui::Ui,
+    adjustments_changed: &mut bool,
+) {
+    common::lr_collapsible_panel(ui, "LUT", false, |ui| {
+        ui.spacing_mut().slider_width = ui.available_width() - 80.0;
+
+        // LUT folder
+        ui.horizontal(|ui| {
+            let folder_name = app
+                .settings
+                .lut_folder
+                .as_ref()
+                .and_then(|p| p.file_name())
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| "No folder".to_string());
+            ui.label(RichText::new(folder_name).size(11.0).color(LR_TEXT_LABEL));
+            if ui.small_button("Folder...").clicked() {
+                if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                    app.settings.lut_folder = Some(folder);
+                    app.refresh_user_luts();
+                }
+            }
+            if app.settings.lut_folder.is_some()
+                && ui
+                    .small_button("↻")
+                    .on_hover_text("Rescan folder")
+                    .clicked()
+            {
+                app.refresh_user_luts();
+            }
+        });
+
+        ui.add_space(4.0);
+
+        // LUT selection: user LUTs plus any .cube picked from elsewhere
+        let lut_name = |path: &std::path::Path| {
+            path.file_stem()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default()
+        };
+        let selected_text = app
+            .adjustments
+            .lut_path
+            .as_deref()
+            .map(lut_name)
+            .unwrap_or_else(|| "None".to_string());
+
+        let mut new_lut: Option<Option<std::path::PathBuf>> = None;
+        ui.horizontal(|ui| {
+            ui.label(RichText::new("LUT:").size(11.0).color(LR_TEXT_LABEL));
+            ui.add_space(8.0);
+            egui::ComboBox::from_id_salt("lut_select")
+                .width(ui.available_width() - 8.0)
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    if ui
+                        .selectable_label(app.adjustments.lut_path.is_none(), "None")
+                        .clicked()
+                    {
+                        new_lut = Some(None);
+                    }
+                    for path in &app.user_luts {
+                        let selected = app.adjustments.lut_path.as_ref() == Some(path);
+                        if ui.selectable_label(selected, lut_name(path)).clicked() {
+                            new_lut = Some(Some(path.clone()));
+                        }
+                    }
+                    ui.separator();
+                    if ui.selectable_label(false, "Open .cube file...").clicked() {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("Cube LUT", &["cube"])
+                            .pick_file()
+                        {
+                            new_lut = Some(Some(path));
+                        }
+                    }
+                });
+        });
+
+        if let Some(lut_path) = new_lut {
+            if lut_path != app.adjustments.lut_path {
+                // Validate before applying so a broken file doesn't silently do nothing
+                if let Some(path) = &lut_path {
+                    if let Err(e) = crate::image_loader::load_cube_lut(path) {
+                        app.show_status(&format!("Failed to load LUT: {}", e));
+                        return;
+                    }
+                }
+
+                let prev_adj = app.adjustments.clone();
+                app.adjustments.lut_path = lut_path;
+                app.commit_adjustment_change(prev_adj);
+            }
+        }
+
+        if app.adjustments.lut_path.is_some() {
+            let mut intensity = app.adjustments.lut_intensity * 100.0;
+            if common::lr_slider(ui, "Intensity", &mut intensity, 0.0..=100.0, "%", 100.0) {
+                app.adjustments.lut_intensity = intensity / 100.0;
+                *adjustments_changed = true;
+                app.mark_adjustments_dirty();
+            }
+        }
+    });
+}
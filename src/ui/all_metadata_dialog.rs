@@ -0,0 +1,137 @@
+use crate::app::ImageViewerApp;
+use crate::exif_data::MetadataTag;
+use egui::{self, RichText};
+
+impl ImageViewerApp {
+    /// Searchable table of every EXIF, IPTC and XMP tag of the current image
+    pub fn render_all_metadata_dialog(&mut self, ctx: &egui::Context) {
+        if !self.show_all_metadata_dialog {
+            return;
+        }
+
+        // Close on escape
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.show_all_metadata_dialog = false;
+            return;
+        }
+
+        // Follow the current image while the browser is open
+        self.request_all_metadata();
+
+        let mut open = true;
+        let mut copy = None;
+        let title = match &self.all_metadata {
+            Some((path, _)) => format!(
+                "Metadata – {}",
+                path.file_name().unwrap_or_default().to_string_lossy()
+            ),
+            None => "Metadata".to_string(),
+        };
+
+        egui::Window::new(title)
+            .id(egui::Id::new("all_metadata_dialog"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_size([620.0, 480.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.all_metadata_query)
+                            .hint_text("Search tags and values")
+                            .desired_width(260.0),
+                    );
+                    ui.checkbox(&mut self.all_metadata_raw, "Raw")
+                        .on_hover_text("Tag identifiers and values as stored in the file");
+                    if self.all_metadata_pending.is_some() {
+                        ui.spinner();
+                    }
+                });
+                ui.add_space(4.0);
+
+                let Some((_, tags)) = &self.all_metadata else {
+                    return;
+                };
+                let query = self.all_metadata_query.trim().to_lowercase();
+                let raw = self.all_metadata_raw;
+                let shown: Vec<&MetadataTag> = tags
+                    .iter()
+                    .filter(|tag| query.is_empty() || matches_query(tag, &query))
+                    .collect();
+                if shown.is_empty() {
+                    let message = if tags.is_empty() {
+                        "No metadata in this file"
+                    } else {
+                        "No tags match"
+                    };
+                    ui.label(RichText::new(message).weak());
+                    return;
+                }
+
+                egui::ScrollArea::both()
+                    .auto_shrink([false, false])
+                    .max_height((ui.available_height() - 36.0).max(80.0))
+                    .show(ui, |ui| {
+                        egui::Grid::new("all_metadata_table")
+                            .num_columns(4)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for tag in &shown {
+                                    let (name, value) = columns(tag, raw);
+                                    ui.label(RichText::new(tag.group).weak());
+                                    ui.label(name);
+                                    ui.add(egui::Label::new(value).truncate());
+                                    if ui.small_button("Copy").clicked() {
+                                        copy = Some(value.to_string());
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(format!("{} tags", shown.len())).weak());
+                    if ui
+                        .button("Copy All")
+                        .on_hover_text("Copy the listed tags as tab-separated text")
+                        .clicked()
+                    {
+                        let text = shown
+                            .iter()
+                            .map(|tag| {
+                                let (name, value) = columns(tag, raw);
+                                format!("{}\t{}\t{}", tag.group, name, value)
+                            })
+                            .collect::<Vec<_>>()
+                            .join("\n");
+                        copy = Some(text);
+                    }
+                });
+            });
+
+        if let Some(text) = copy {
+            ctx.copy_text(text);
+            self.show_status("Copied to clipboard");
+        }
+        if !open {
+            self.show_all_metadata_dialog = false;
+        }
+    }
+}
+
+/// Tag and value columns for the friendly or raw view
+fn columns(tag: &MetadataTag, raw: bool) -> (&str, &str) {
+    if raw {
+        (&tag.key, &tag.raw)
+    } else {
+        (&tag.name, &tag.value)
+    }
+}
+
+fn matches_query(tag: &MetadataTag, query: &str) -> bool {
+    let fields: [&str; 5] = [tag.group, &tag.key, &tag.name, &tag.value, &tag.raw];
+    fields
+        .iter()
+        .any(|field| field.to_lowercase().contains(query))
+}
@@ -0,0 +1,142 @@
+use crate::app::ImageViewerApp;
+use egui::{self, Color32, RichText, Vec2};
+
+impl ImageViewerApp {
+    pub fn render_batch_export_dialog(&mut self, ctx: &egui::Context) {
+        let Some(batch) = &self.batch_export else {
+            return;
+        };
+
+        let mut cancel = false;
+        let mut close = false;
+        let mut copy_links = false;
+        let title = if batch.share.is_some() {
+            "Share"
+        } else {
+            "Batch Export"
+        };
+
+        egui::Window::new(title)
+            .collapsible(false)
+            .resizable(false)
+            .default_width(380.0)
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                match &batch.share {
+                    Some(share) => ui.label(format!("Uploading to {}", share.target)),
+                    None => ui.label(format!("Exporting to {}", batch.output_folder.display())),
+                };
+                ui.add_space(8.0);
+
+                let processed = batch.processed();
+                let progress = processed as f32 / batch.total.max(1) as f32;
+                ui.add(
+                    egui::ProgressBar::new(progress)
+                        .text(format!("{} / {}", processed, batch.total))
+                        .animate(!batch.is_finished()),
+                );
+
+                ui.add_space(4.0);
+                let status = if !batch.is_finished() {
+                    if batch.cancelled() {
+                        "Cancelling...".to_string()
+                    } else {
+                        let elapsed = batch.started.elapsed().as_secs_f32();
+                        format!("{:.0}s elapsed", elapsed)
+                    }
+                } else if batch.cancelled() {
+                    format!("Cancelled after {} of {} images", processed, batch.total)
+                } else {
+                    let duration = batch.finished_in.unwrap_or_default();
+                    format!("Done in {:.1}s", duration.as_secs_f32())
+                };
+                ui.label(RichText::new(status).weak());
+                if batch.skipped > 0 {
+                    ui.label(
+                        RichText::new(format!("{} skipped, already exported", batch.skipped))
+                            .weak(),
+                    );
+                }
+
+                if !batch.failed.is_empty() {
+                    ui.add_space(8.0);
+                    ui.label(
+                        RichText::new(format!("{} failed", batch.failed.len()))
+                            .color(Color32::from_rgb(255, 120, 120)),
+                    );
+                    egui::ScrollArea::vertical()
+                        .max_height(160.0)
+                        .show(ui, |ui| {
+                            for (path, error) in &batch.failed {
+                                let name = path
+                                    .file_name()
+                                    .map(|n| n.to_string_lossy().to_string())
+                                    .unwrap_or_else(|| path.display().to_string());
+                                ui.label(RichText::new(format!("{}: {}", name, error)).size(11.0))
+                                    .on_hover_text(path.display().to_string());
+                            }
+                        });
+                }
+
+                if let Some(share) = batch.share.as_ref().filter(|s| !s.links.is_empty()) {
+                    ui.add_space(8.0);
+                    egui::ScrollArea::vertical()
+                        .id_salt("share_links")
+                        .max_height(120.0)
+                        .show(ui, |ui| {
+                            for link in &share.links {
+                                ui.hyperlink(link);
+                            }
+                        });
+                }
+
+                ui.add_space(8.0);
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if batch.is_finished() {
+                        if ui.button("Close").clicked() {
+                            close = true;
+                        }
+                        match &batch.share {
+                            Some(share) => {
+                                if ui
+                                    .add_enabled(
+                                        !share.links.is_empty(),
+                                        egui::Button::new("Copy Links"),
+                                    )
+                                    .clicked()
+                                {
+                                    copy_links = true;
+                                }
+                            }
+                            None => {
+                                if ui.button("Open Folder").clicked() {
+                                    let _ = open::that(&batch.output_folder);
+                                }
+                            }
+                        }
+                    } else if ui
+                        .add_enabled(!batch.cancelled(), egui::Button::new("Cancel"))
+                        .clicked()
+                    {
+                        cancel = true;
+                    }
+                });
+            });
+
+        // The "finished" summary stays up until dismissed; the timer needs repaints while running
+        if !batch.is_finished() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(250));
+        }
+        let finished = batch.is_finished();
+        if cancel {
+            self.cancel_batch_export();
+        }
+        if copy_links && self.copy_share_links() {
+            self.show_status("Links copied");
+        }
+        if close || (finished && ctx.input(|i| i.key_pressed(egui::Key::Escape))) {
+            self.batch_export = None;
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use crate::app::ImageViewerApp;
+use crate::batch_metadata::{MetadataTarget, TOKENS};
+use egui::{self, RichText, Vec2};
+
+impl ImageViewerApp {
+    pub fn render_batch_metadata_dialog(&mut self, ctx: &egui::Context) {
+        let Some(session) = &self.metadata_session else {
+            return;
+        };
+
+        // Close on escape
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.metadata_session = None;
+            return;
+        }
+
+        let count = session.sources.len();
+        let writing = session.writing;
+        let waiting = session.infos.is_none() && self.settings.metadata_template.needs_info();
+        let plans = self.metadata_plan();
+        let embedded = plans.iter().filter(|p| p.embedded).count();
+
+        let mut open = true;
+        let mut apply = false;
+        let mut cancel = false;
+
+        egui::Window::new(format!("Edit Metadata of {} Files", count))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(600.0)
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                let template = &mut self.settings.metadata_template;
+                let mut token_field = self.metadata_session.as_ref().map_or(2, |s| s.token_field);
+                egui::Grid::new("batch_metadata_fields")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        let fields = [
+                            ("Your name:", &mut template.me, "Fills {me}"),
+                            ("Creator:", &mut template.creator, ""),
+                            ("Copyright:", &mut template.copyright, ""),
+                            ("Title:", &mut template.title, ""),
+                            ("Add keywords:", &mut template.keywords, "Comma separated"),
+                        ];
+                        for (i, (label, value, hint)) in fields.into_iter().enumerate() {
+                            ui.label(label);
+                            let response = ui.add(
+                                egui::TextEdit::singleline(value)
+                                    .hint_text(hint)
+                                    .desired_width(360.0),
+                            );
+                            // Tokens go into the creator, copyright or title field edited last
+                            if response.gained_focus() && (1..=3).contains(&i) {
+                                token_field = i;
+                            }
+                            ui.end_row();
+                        }
+                    });
+                ui.horizontal(|ui| {
+                    ui.label("Insert:");
+                    for (token, description) in TOKENS {
+                        if ui
+                            .small_button(*token)
+                            .on_hover_text(*description)
+                            .clicked()
+                        {
+                            match token_field {
+                                1 => template.creator.push_str(token),
+                                3 => template.title.push_str(token),
+                                _ => template.copyright.push_str(token),
+                            }
+                        }
+                    }
+                });
+                if let Some(session) = &mut self.metadata_session {
+                    session.token_field = token_field;
+                }
+                ui.label(RichText::new("Empty fields are left unchanged.").weak());
+                ui.horizontal(|ui| {
+                    ui.label("Write to:");
+                    ui.radio_value(
+                        &mut template.target,
+                        MetadataTarget::Sidecar,
+                        "XMP sidecars",
+                    );
+                    ui.radio_value(
+                        &mut template.target,
+                        MetadataTarget::EmbedInJpeg,
+                        "Inside JPEG files",
+                    )
+                    .on_hover_text("Other formats still get a sidecar");
+                });
+
+                ui.add_space(8.0);
+                ui.separator();
+                ui.label(RichText::new("Preview").strong());
+                egui::ScrollArea::both()
+                    .max_height(260.0)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        egui::Grid::new("batch_metadata_preview")
+                            .num_columns(5)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for heading in ["File", "Creator", "Copyright", "Title", "Keywords"]
+                                {
+                                    ui.label(RichText::new(heading).weak());
+                                }
+                                ui.end_row();
+                                for plan in &plans {
+                                    let name = plan.path.file_name().unwrap_or_default();
+                                    let destination =
+                                        plan.destination.file_name().unwrap_or_default();
+                                    ui.label(name.to_string_lossy()).on_hover_text(format!(
+                                        "Written to {}",
+                                        destination.to_string_lossy()
+                                    ));
+                                    let fields = &plan.fields;
+                                    for value in [&fields.creator, &fields.copyright, &fields.title]
+                                    {
+                                        match value {
+                                            Some(value) => ui.label(value),
+                                            None => ui.label(RichText::new("unchanged").weak()),
+                                        };
+                                    }
+                                    if fields.keywords.is_empty() {
+                                        ui.label(RichText::new("unchanged").weak());
+                                    } else {
+                                        ui.label(format!("+ {}", fields.keywords.join(", ")));
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+
+                ui.add_space(4.0);
+                let summary = if waiting {
+                    "Reading capture dates...".to_string()
+                } else {
+                    format!(
+                        "{} files will be embedded, {} get sidecars",
+                        embedded,
+                        plans.len() - embedded
+                    )
+                };
+                ui.label(RichText::new(summary).weak());
+
+                ui.add_space(8.0);
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let enabled = !waiting && !writing;
+                    if ui
+                        .add_enabled(enabled, egui::Button::new("Write Metadata"))
+                        .clicked()
+                    {
+                        apply = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        cancel = true;
+                    }
+                    if writing {
+                        ui.spinner();
+                    }
+                });
+            });
+
+        if apply {
+            self.apply_batch_metadata();
+        } else if cancel || !open {
+            self.metadata_session = None;
+        }
+    }
+}
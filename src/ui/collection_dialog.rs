@@ -0,0 +1,130 @@
+use crate::app::ImageViewerApp;
+use crate::search::CollectionRule;
+use crate::settings::ColorLabel;
+use egui::{self, RichText, Vec2};
+
+impl ImageViewerApp {
+    pub fn render_collection_dialog(&mut self, ctx: &egui::Context) {
+        let Some((index, draft)) = self.collection_draft.as_mut() else {
+            return;
+        };
+
+        // Close on escape
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.collection_draft = None;
+            return;
+        }
+
+        let title = if index.is_some() {
+            "Edit Smart Collection"
+        } else {
+            "New Smart Collection"
+        };
+        let mut open = true;
+        let mut save = false;
+        let mut cancel = false;
+
+        egui::Window::new(title)
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .default_width(380.0)
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Name:");
+                    ui.add(egui::TextEdit::singleline(&mut draft.name).desired_width(240.0));
+                });
+
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    ui.label("Match");
+                    ui.radio_value(&mut draft.match_all, true, "all rules");
+                    ui.radio_value(&mut draft.match_all, false, "any rule");
+                });
+
+                ui.add_space(8.0);
+                ui.separator();
+                ui.add_space(4.0);
+
+                let mut remove = None;
+                for (i, rule) in draft.rules.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(rule.name());
+                        render_rule_value(ui, i, rule);
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.small_button("🗑").on_hover_text("Remove rule").clicked() {
+                                remove = Some(i);
+                            }
+                        });
+                    });
+                }
+                if let Some(i) = remove {
+                    draft.rules.remove(i);
+                }
+                if draft.rules.is_empty() {
+                    ui.label(RichText::new("No rules: every image matches").weak());
+                }
+
+                ui.add_space(4.0);
+                ui.menu_button("Add rule", |ui| {
+                    for rule in CollectionRule::all() {
+                        if ui.button(rule.name()).clicked() {
+                            draft.rules.push(rule);
+                            ui.close_menu();
+                        }
+                    }
+                });
+
+                ui.add_space(8.0);
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Save").clicked() {
+                        save = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+
+        if save {
+            self.save_collection_draft();
+        } else if cancel || !open {
+            self.collection_draft = None;
+        }
+    }
+}
+
+fn render_rule_value(ui: &mut egui::Ui, id: usize, rule: &mut CollectionRule) {
+    match rule {
+        CollectionRule::MinRating(rating) => {
+            ui.add(egui::Slider::new(rating, 0..=5).suffix(" ★"));
+        }
+        CollectionRule::Label(label) => {
+            egui::ComboBox::from_id_salt(("collection_label", id))
+                .selected_text(label.name())
+                .show_ui(ui, |ui| {
+                    for option in ColorLabel::all() {
+                        ui.selectable_value(label, *option, option.name());
+                    }
+                });
+        }
+        CollectionRule::KeywordContains(text) | CollectionRule::CameraModel(text) => {
+            ui.add(egui::TextEdit::singleline(text).desired_width(140.0));
+        }
+        CollectionRule::DateRange { from, to } => {
+            ui.add(
+                egui::TextEdit::singleline(from)
+                    .hint_text("YYYY-MM-DD")
+                    .desired_width(80.0),
+            );
+            ui.label("and");
+            ui.add(
+                egui::TextEdit::singleline(to)
+                    .hint_text("YYYY-MM-DD")
+                    .desired_width(80.0),
+            );
+        }
+    }
+}
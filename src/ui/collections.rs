@@ -0,0 +1,109 @@
+use crate::app::ImageViewerApp;
+use crate::ui::common;
+use egui::{self, Color32, RichText, Stroke};
+
+enum CollectionAction {
+    Toggle(usize),
+    OpenFromCatalog(usize),
+    Edit(usize),
+    Delete(usize),
+}
+
+pub fn render_collections_panel(app: &mut ImageViewerApp, ui: &mut egui::Ui) {
+    common::lr_collapsible_panel(ui, "Smart Collections", true, |ui| {
+        let mut action = None;
+
+        if app.settings.smart_collections.is_empty() {
+            ui.label(
+                RichText::new("No smart collections")
+                    .size(10.0)
+                    .color(common::LR_TEXT_SECONDARY),
+            );
+        }
+
+        for (i, collection) in app.settings.smart_collections.iter().enumerate() {
+            let active = app.active_collection == Some(i);
+            let (color, bg) = if active {
+                (
+                    Color32::from_rgb(200, 170, 100),
+                    Color32::from_rgba_unmultiplied(200, 170, 100, 30),
+                )
+            } else {
+                (common::LR_TEXT_SECONDARY, Color32::TRANSPARENT)
+            };
+
+            let joiner = if collection.match_all {
+                " and "
+            } else {
+                " or "
+            };
+            let response = ui
+                .add(
+                    egui::Button::new(
+                        RichText::new(format!("⚙ {}", collection.name))
+                            .size(10.0)
+                            .color(color),
+                    )
+                    .fill(bg)
+                    .stroke(Stroke::NONE)
+                    .wrap(),
+                )
+                .on_hover_text(
+                    collection
+                        .rules
+                        .iter()
+                        .map(|r| r.name())
+                        .collect::<Vec<_>>()
+                        .join(joiner),
+                );
+            if response.clicked() {
+                action = Some(CollectionAction::Toggle(i));
+            }
+            response.context_menu(|ui| {
+                if ui
+                    .button("Show from Whole Catalog")
+                    .on_hover_text("Clicking lists matches in the open folder only")
+                    .clicked()
+                {
+                    action = Some(CollectionAction::OpenFromCatalog(i));
+                    ui.close_menu();
+                }
+                if ui.button("Edit...").clicked() {
+                    action = Some(CollectionAction::Edit(i));
+                    ui.close_menu();
+                }
+                if ui.button("Delete").clicked() {
+                    action = Some(CollectionAction::Delete(i));
+                    ui.close_menu();
+                }
+            });
+        }
+
+        ui.add_space(4.0);
+        if ui
+            .add(
+                egui::Button::new(
+                    RichText::new("New Smart Collection...")
+                        .size(10.0)
+                        .color(common::LR_TEXT_SECONDARY),
+                )
+                .fill(common::LR_BG_INPUT)
+                .stroke(Stroke::new(1.0, common::LR_BORDER)),
+            )
+            .clicked()
+        {
+            app.new_smart_collection();
+        }
+
+        match action {
+            Some(CollectionAction::Toggle(i)) => {
+                let next = (app.active_collection != Some(i)).then_some(i);
+                app.set_active_collection(next);
+            }
+            Some(CollectionAction::OpenFromCatalog(i)) => app.open_collection_from_catalog(i),
+            Some(CollectionAction::Edit(i)) => app.edit_smart_collection(i),
+            Some(CollectionAction::Delete(i)) => app.delete_smart_collection(i),
+            None => {}
+        }
+    });
+}
@@ -1,5 +1,12 @@
+use crate::app::actions::{fuzzy_score, search_actions};
 use crate::app::ImageViewerApp;
+use crate::i18n::tr;
+use crate::scripting::Script;
 use egui::{self, Color32, CornerRadius, Margin, Vec2};
+use std::path::PathBuf;
+
+/// Recent images listed under the commands
+const RECENT_MATCHES: usize = 5;
 
 impl ImageViewerApp {
     pub fn render_command_palette(&mut self, ctx: &egui::Context) {
@@ -30,7 +37,7 @@ impl ImageViewerApp {
                 let response = ui.add_sized(
                     Vec2::new(ui.available_width(), 32.0),
                     egui::TextEdit::singleline(&mut self.command_palette_query)
-                        .hint_text("Type a command...")
+                        .hint_text(tr("palette-hint"))
                         .font(egui::TextStyle::Heading),
                 );
                 response.request_focus();
@@ -39,108 +46,151 @@ impl ImageViewerApp {
                 ui.separator();
                 ui.add_space(8.0);
 
-                // Commands list
-                let commands = self.get_filtered_commands();
+                // Best matches first; Enter runs the top one
+                let actions = search_actions(&self.command_palette_query);
+                let scripts = self.script_matches();
+                let recent = self.recent_image_matches();
+                let mut run = None;
+                let mut run_script = None;
+                let mut open = None;
+                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    run = actions.first().map(|a| a.id);
+                    if run.is_none() {
+                        run_script = scripts.first().cloned();
+                    }
+                    if run.is_none() && run_script.is_none() {
+                        open = recent.first().cloned();
+                    }
+                }
 
                 egui::ScrollArea::vertical()
                     .auto_shrink([false, false])
                     .max_height(300.0)
                     .show(ui, |ui| {
-                        for (name, shortcut, action) in commands {
+                        for (index, action) in actions.iter().enumerate() {
+                            let fill = if index == 0 && !self.command_palette_query.is_empty() {
+                                Color32::from_rgb(50, 50, 58)
+                            } else {
+                                Color32::TRANSPARENT
+                            };
                             let response = ui.add_sized(
                                 Vec2::new(ui.available_width(), 28.0),
-                                egui::Button::new(egui::RichText::new(&name).size(13.0))
-                                    .fill(Color32::TRANSPARENT),
+                                egui::Button::new(egui::RichText::new(action.label()).size(13.0))
+                                    .fill(fill),
                             );
 
-                            // Show shortcut
-                            if !shortcut.is_empty() {
-                                ui.painter().text(
-                                    response.rect.right_center() - Vec2::new(10.0, 0.0),
-                                    egui::Align2::RIGHT_CENTER,
-                                    &shortcut,
-                                    egui::FontId::monospace(10.0),
-                                    Color32::GRAY,
+                            // Show category and shortcut
+                            let shortcut = self.settings.keymap.shortcut_label(action.id);
+                            let hint = if shortcut.is_empty() {
+                                action.category.label()
+                            } else {
+                                format!("{}   {}", action.category.label(), shortcut)
+                            };
+                            ui.painter().text(
+                                response.rect.right_center() - Vec2::new(10.0, 0.0),
+                                egui::Align2::RIGHT_CENTER,
+                                hint,
+                                egui::FontId::monospace(10.0),
+                                Color32::GRAY,
+                            );
+
+                            if response.clicked() {
+                                run = Some(action.id);
+                            }
+                        }
+
+                        // User scripts from the scripts folder
+                        if !scripts.is_empty() {
+                            ui.add_space(6.0);
+                            ui.label(egui::RichText::new(tr("palette-scripts")).size(11.0).weak());
+                            for script in &scripts {
+                                let response = ui.add_sized(
+                                    Vec2::new(ui.available_width(), 28.0),
+                                    egui::Button::new(
+                                        egui::RichText::new(&script.title).size(13.0),
+                                    )
+                                    .fill(Color32::TRANSPARENT),
                                 );
+                                if response
+                                    .on_hover_text(script.path.display().to_string())
+                                    .clicked()
+                                {
+                                    run_script = Some(script.clone());
+                                }
                             }
+                        }
 
-                            if response.clicked() {
-                                self.execute_command(&action);
-                                self.command_palette_open = false;
+                        // Jump straight to an image viewed lately, matched by file name
+                        if !recent.is_empty() {
+                            ui.add_space(6.0);
+                            ui.label(egui::RichText::new(tr("palette-recent")).size(11.0).weak());
+                            for path in &recent {
+                                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                                let response = ui.add_sized(
+                                    Vec2::new(ui.available_width(), 24.0),
+                                    egui::Button::new(egui::RichText::new(name).size(12.0))
+                                        .fill(Color32::TRANSPARENT),
+                                );
+                                if response.on_hover_text(path.display().to_string()).clicked() {
+                                    open = Some(path.clone());
+                                }
                             }
                         }
                     });
+
+                if let Some(id) = run {
+                    self.command_palette_open = false;
+                    self.run_action(id);
+                } else if let Some(script) = run_script {
+                    self.command_palette_open = false;
+                    self.run_script(script);
+                } else if let Some(path) = open {
+                    self.command_palette_open = false;
+                    self.open_recent_image(path);
+                }
             });
     }
 
-    fn get_filtered_commands(&self) -> Vec<(String, String, String)> {
-        let all_commands = vec![
-            ("Open File", "Ctrl+O", "open_file"),
-            ("Open Folder", "Ctrl+Shift+O", "open_folder"),
-            ("Move to Folder", "M", "move"),
-            ("Next Image", "→", "next"),
-            ("Previous Image", "←", "previous"),
-            ("First Image", "Home", "first"),
-            ("Last Image", "End", "last"),
-            ("Zoom In", "+", "zoom_in"),
-            ("Zoom Out", "-", "zoom_out"),
-            ("Actual Size (100%)", "1", "actual_size"),
-            ("Rotate Left", "L", "rotate_left"),
-            ("Rotate Right", "R", "rotate_right"),
-            ("Toggle Fullscreen", "F11", "fullscreen"),
-            ("Toggle Focus Peaking", "Ctrl+F", "focus_peaking"),
-            ("Toggle Zebras", "Alt+Z", "zebras"),
-            ("Undo", "Ctrl+Z", "undo"),
-            ("Redo", "Ctrl+Y", "redo"),
-            ("Toggle Grid Overlay", "Ctrl+G", "grid"),
-            ("Toggle Loupe", "Ctrl+L", "loupe"),
-            ("Toggle Sidebar", "S", "sidebar"),
-            ("Toggle Thumbnails", "T", "thumbnails"),
-            ("Toggle EXIF Info", "I", "exif"),
-            ("Toggle Histogram", "H", "histogram"),
-            ("Delete Image", "Del", "delete"),
-            ("Set as Wallpaper", "", "wallpaper"),
-            ("Settings", "", "settings"),
-        ];
-
-        let query = self.command_palette_query.to_lowercase();
-
-        all_commands
-            .into_iter()
-            .filter(|(name, _, _)| query.is_empty() || name.to_lowercase().contains(&query))
-            .map(|(n, s, a)| (n.to_string(), s.to_string(), a.to_string()))
-            .collect()
+    /// Scripts whose title matches the palette query, best first
+    fn script_matches(&self) -> Vec<Script> {
+        let query = self.command_palette_query.trim();
+        if query.is_empty() {
+            return self.scripts.clone();
+        }
+        let mut matches: Vec<(i32, &Script)> = self
+            .scripts
+            .iter()
+            .filter_map(|s| Some((fuzzy_score(query, &s.title)?, s)))
+            .collect();
+        matches.sort_by(|a, b| b.0.cmp(&a.0));
+        matches.into_iter().map(|(_, s)| s.clone()).collect()
     }
 
-    fn execute_command(&mut self, action: &str) {
-        match action {
-            "open_file" => self.open_file_dialog(),
-            "open_folder" => self.open_folder_dialog(),
-            "move" => self.show_move_dialog = true,
-            "next" => self.next_image(),
-            "previous" => self.previous_image(),
-            "first" => self.go_to_first(),
-            "last" => self.go_to_last(),
-            "zoom_in" => self.zoom_in(),
-            "zoom_out" => self.zoom_out(),
-            "actual_size" => self.zoom_to(1.0),
-            "rotate_left" => self.rotate_left(),
-            "rotate_right" => self.rotate_right(),
-            "fullscreen" => self.is_fullscreen = !self.is_fullscreen,
-            "focus_peaking" => self.settings.show_focus_peaking = !self.settings.show_focus_peaking,
-            "zebras" => self.settings.show_zebras = !self.settings.show_zebras,
-            "undo" => self.undo_last_operation(),
-            "redo" => self.redo_last_operation(),
-            "grid" => self.settings.show_grid_overlay = !self.settings.show_grid_overlay,
-            "loupe" => self.settings.loupe_enabled = !self.settings.loupe_enabled,
-            "sidebar" => self.settings.show_sidebar = !self.settings.show_sidebar,
-            "thumbnails" => self.settings.show_thumbnails = !self.settings.show_thumbnails,
-            "exif" => self.settings.show_exif = !self.settings.show_exif,
-            "histogram" => self.settings.show_histogram = !self.settings.show_histogram,
-            "delete" => self.delete_current_image(),
-            "wallpaper" => self.set_as_wallpaper(),
-            "settings" => self.show_settings_dialog = true,
-            _ => {}
+    /// Recently viewed images whose name matches the palette query, best first;
+    /// the latest ones when the query is empty
+    fn recent_image_matches(&self) -> Vec<PathBuf> {
+        let query = self.command_palette_query.trim();
+        let recent = &self.settings.recent_images;
+        if query.is_empty() {
+            return recent
+                .iter()
+                .take(RECENT_MATCHES)
+                .map(|r| r.path.clone())
+                .collect();
         }
+        let mut matches: Vec<(i32, &PathBuf)> = recent
+            .iter()
+            .filter_map(|r| {
+                let name = r.path.file_name()?.to_string_lossy();
+                Some((fuzzy_score(query, &name)?, &r.path))
+            })
+            .collect();
+        // Stable, so equally good matches stay most recent first
+        matches.sort_by(|a, b| b.0.cmp(&a.0));
+        matches
+            .into_iter()
+            .take(RECENT_MATCHES)
+            .map(|(_, path)| path.clone())
+            .collect()
     }
 }
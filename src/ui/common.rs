@@ -148,17 +148,17 @@ pub fn lr_slider(
 }
 
 /// Common info row widget for metadata display
-pub fn lr_info_row(ui: &mut egui::Ui, label: &str, value: Option<&str>) {
+pub fn lr_info_row(ui: &mut egui::Ui, label: &str, value: Option<&str>, size: f32) {
     if let Some(v) = value {
         if !v.is_empty() && v != "Unknown" {
             ui.horizontal(|ui| {
                 ui.label(
                     RichText::new(format!("{}:", label))
-                        .size(10.0)
+                        .size(size)
                         .color(LR_TEXT_SECONDARY),
                 );
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    ui.label(RichText::new(v).size(10.0).color(LR_TEXT_PRIMARY));
+                    ui.label(RichText::new(v).size(size).color(LR_TEXT_PRIMARY));
                 });
             });
         }
@@ -0,0 +1,130 @@
+use crate::app::ImageViewerApp;
+use crate::contact_sheet::{ContactSheetFormat, PaperSize};
+use egui::{self, RichText, Vec2};
+
+impl ImageViewerApp {
+    pub fn render_contact_sheet_dialog(&mut self, ctx: &egui::Context) {
+        if !self.show_contact_sheet_dialog {
+            return;
+        }
+
+        // Close on escape
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.show_contact_sheet_dialog = false;
+            return;
+        }
+
+        let count = if self.selected_indices.is_empty() {
+            self.filtered_list.len()
+        } else {
+            self.selected_indices.len()
+        };
+        let running = self.contact_sheet_running;
+        let mut open = true;
+        let mut create = false;
+        let mut cancel = false;
+
+        egui::Window::new("Contact Sheet")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .default_width(340.0)
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                let options = &mut self.settings.contact_sheet;
+                ui.add_enabled_ui(!running, |ui| {
+                    egui::Grid::new("contact_sheet_options")
+                        .num_columns(2)
+                        .spacing([12.0, 6.0])
+                        .show(ui, |ui| {
+                            ui.label("Columns:");
+                            ui.add(egui::Slider::new(&mut options.columns, 1..=10));
+                            ui.end_row();
+
+                            ui.label("Rows:");
+                            ui.add(egui::Slider::new(&mut options.rows, 1..=12));
+                            ui.end_row();
+
+                            ui.label("Paper:");
+                            ui.horizontal(|ui| {
+                                egui::ComboBox::from_id_salt("contact_sheet_paper")
+                                    .selected_text(options.paper.name())
+                                    .show_ui(ui, |ui| {
+                                        for paper in PaperSize::all() {
+                                            ui.selectable_value(
+                                                &mut options.paper,
+                                                *paper,
+                                                paper.name(),
+                                            );
+                                        }
+                                    });
+                                ui.checkbox(&mut options.landscape, "Landscape");
+                            });
+                            ui.end_row();
+
+                            ui.label("Format:");
+                            egui::ComboBox::from_id_salt("contact_sheet_format")
+                                .selected_text(options.format.name())
+                                .show_ui(ui, |ui| {
+                                    for format in ContactSheetFormat::all() {
+                                        ui.selectable_value(
+                                            &mut options.format,
+                                            *format,
+                                            format.name(),
+                                        );
+                                    }
+                                });
+                            ui.end_row();
+                        });
+
+                    ui.add_space(4.0);
+                    ui.checkbox(&mut options.captions, "File name captions");
+                    ui.checkbox(
+                        &mut options.exif_line,
+                        "EXIF line (aperture, shutter, ISO, focal length)",
+                    );
+                });
+
+                ui.add_space(8.0);
+                let pages = options.page_count(count);
+                let summary = match (options.format, pages) {
+                    (_, 1) => format!("{} images on 1 page", count),
+                    (ContactSheetFormat::Pdf, _) => {
+                        format!("{} images on {} PDF pages", count, pages)
+                    }
+                    (format, _) => format!(
+                        "{} images on {} pages, saved as separate {} files",
+                        count,
+                        pages,
+                        format.name()
+                    ),
+                };
+                ui.label(RichText::new(summary).weak());
+
+                ui.add_space(8.0);
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if running {
+                        ui.spinner();
+                        ui.label("Creating contact sheet...");
+                    } else {
+                        if ui
+                            .add_enabled(count > 0, egui::Button::new("Save..."))
+                            .clicked()
+                        {
+                            create = true;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            cancel = true;
+                        }
+                    }
+                });
+            });
+
+        if create {
+            self.start_contact_sheet();
+        } else if cancel || !open {
+            self.show_contact_sheet_dialog = false;
+        }
+    }
+}
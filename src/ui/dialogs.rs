@@ -3,6 +3,24 @@ use crate::app::ImageViewerApp;
 impl ImageViewerApp {
     pub fn render_dialogs(&mut self, ctx: &egui::Context) {
         self.render_settings_dialog(ctx);
+        self.render_presets_dialog(ctx);
+        self.render_film_stocks_dialog(ctx);
+        self.render_collection_dialog(ctx);
+        self.render_export_dialog(ctx);
+        self.render_batch_export_dialog(ctx);
+        self.render_share_secret_dialog(ctx);
+        self.render_contact_sheet_dialog(ctx);
+        self.render_web_gallery_dialog(ctx);
+        self.render_playlist_dialog(ctx);
+        self.render_rename_dialog(ctx);
+        self.render_batch_metadata_dialog(ctx);
+        self.render_move_dialog(ctx);
+        self.render_recently_deleted_dialog(ctx);
+        self.render_shoot_summary_dialog(ctx);
+        self.render_all_metadata_dialog(ctx);
+        self.render_timeline_dialog(ctx);
+        self.render_open_url_dialog(ctx);
+        self.render_reference_overlay_dialog(ctx);
         self.render_command_palette(ctx);
     }
 }
@@ -0,0 +1,266 @@
+use crate::app::ImageViewerApp;
+use crate::color_management::OutputProfile;
+use crate::exif_data::format_file_size;
+use crate::export_naming::{ExportConflict, ExportDestination, TOKENS};
+use crate::image_loader::ResizeFilter;
+use crate::settings::ExportFormat;
+use egui::{self, RichText, Vec2};
+
+impl ImageViewerApp {
+    pub fn render_export_dialog(&mut self, ctx: &egui::Context) {
+        if self.export_preview.is_none() {
+            return;
+        }
+
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.export_preview = None;
+            return;
+        }
+
+        self.update_export_preview();
+
+        let mut open = true;
+        let mut export = false;
+        let mut cancel = false;
+
+        egui::Window::new("Export Image")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Format:");
+                    egui::ComboBox::from_id_salt("export_dialog_format")
+                        .selected_text(self.settings.export_format.name())
+                        .show_ui(ui, |ui| {
+                            for format in ExportFormat::all() {
+                                ui.selectable_value(
+                                    &mut self.settings.export_format,
+                                    *format,
+                                    format.name(),
+                                );
+                            }
+                        });
+                });
+                let jpeg = self.settings.export_format == ExportFormat::Jpeg;
+                ui.add_enabled_ui(jpeg, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Quality:");
+                        ui.add(egui::Slider::new(
+                            &mut self.settings.export_quality,
+                            1..=100,
+                        ));
+                    });
+                });
+                self.render_export_resize(ui);
+                self.render_export_profile(ui);
+                ui.add_space(4.0);
+                self.render_export_naming(ui);
+
+                let Some(preview) = &self.export_preview else {
+                    return;
+                };
+                if let Some((source, info)) = &preview.name_info {
+                    let naming = &self.settings.export_naming;
+                    let format = self.settings.export_format;
+                    // Just the name when the folder is asked for
+                    let base = naming.base_folder(source).unwrap_or_default();
+                    let path = naming.target(&base, source, 1, info, format);
+                    ui.label(RichText::new(format!("→ {}", path.display())).weak());
+                }
+                ui.add_space(8.0);
+                match &preview.encoded {
+                    Some(encoded) => {
+                        let options = encoded.options;
+                        let label = if options.format == ExportFormat::Jpeg {
+                            format!("{} {}", options.format.name(), options.quality)
+                        } else {
+                            options.format.name().to_string()
+                        };
+                        ui.horizontal(|ui| {
+                            let size = encoded.original.size_vec2();
+                            ui.vertical(|ui| {
+                                ui.label(RichText::new("Original").weak());
+                                ui.image((encoded.original.id(), size));
+                            });
+                            ui.vertical(|ui| {
+                                ui.label(RichText::new(label).weak());
+                                ui.image((encoded.texture.id(), size));
+                            });
+                        });
+                    }
+                    None => {
+                        ui.allocate_ui(Vec2::new(520.0, 280.0), |ui| {
+                            ui.centered_and_justified(|ui| ui.spinner());
+                        });
+                    }
+                }
+
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    match (&preview.error, &preview.encoded) {
+                        (Some(error), _) => {
+                            ui.colored_label(ui.visuals().error_fg_color, error);
+                        }
+                        (None, Some(encoded)) => {
+                            let [width, height] = encoded.dimensions;
+                            ui.label(format!(
+                                "{} × {} · ≈ {}",
+                                width,
+                                height,
+                                format_file_size(encoded.size)
+                            ));
+                        }
+                        (None, None) => {}
+                    }
+                    if preview.is_encoding() {
+                        ui.spinner();
+                        ui.label(RichText::new("Estimating...").weak());
+                    }
+                });
+
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    let ask = self.settings.export_naming.destination == ExportDestination::Ask;
+                    if ui
+                        .button(if ask { "Export..." } else { "Export" })
+                        .clicked()
+                    {
+                        export = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+
+        if export {
+            self.export_preview = None;
+            self.export_image();
+        }
+        if cancel || !open {
+            self.export_preview = None;
+        }
+    }
+
+    /// Destination, name templates and what to do with existing files, shared
+    /// with the settings dialog
+    pub(crate) fn render_export_naming(&mut self, ui: &mut egui::Ui) {
+        let naming = &mut self.settings.export_naming;
+        let mut pick_folder = false;
+        ui.horizontal(|ui| {
+            ui.label("Save to:");
+            egui::ComboBox::from_id_salt("export_destination")
+                .selected_text(naming.destination.name())
+                .show_ui(ui, |ui| {
+                    for destination in [ExportDestination::Ask, ExportDestination::NextToSource] {
+                        let name = destination.name();
+                        ui.selectable_value(&mut naming.destination, destination, name);
+                    }
+                    let folder = matches!(naming.destination, ExportDestination::Folder(_));
+                    if ui.selectable_label(folder, "Folder...").clicked() {
+                        pick_folder = true;
+                    }
+                });
+            if let ExportDestination::Folder(folder) = &naming.destination {
+                if ui
+                    .small_button("Change...")
+                    .on_hover_text(folder.display().to_string())
+                    .clicked()
+                {
+                    pick_folder = true;
+                }
+            }
+        });
+        if let ExportDestination::Folder(folder) = &naming.destination {
+            ui.label(RichText::new(folder.display().to_string()).weak());
+        }
+
+        let tokens = TOKENS
+            .iter()
+            .map(|(token, description)| format!("{}  {}", token, description))
+            .collect::<Vec<_>>()
+            .join("\n");
+        egui::Grid::new("export_naming_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Subfolders:").on_hover_text(&tokens);
+                ui.add(
+                    egui::TextEdit::singleline(&mut naming.subfolder)
+                        .hint_text("{date}/{rating}")
+                        .desired_width(220.0),
+                );
+                ui.end_row();
+                ui.label("File name:").on_hover_text(&tokens);
+                ui.add(egui::TextEdit::singleline(&mut naming.filename).desired_width(220.0));
+                ui.end_row();
+                ui.label("If it exists:");
+                egui::ComboBox::from_id_salt("export_conflict")
+                    .selected_text(naming.conflict.name())
+                    .show_ui(ui, |ui| {
+                        for conflict in ExportConflict::all() {
+                            ui.selectable_value(&mut naming.conflict, *conflict, conflict.name());
+                        }
+                    });
+                ui.end_row();
+            });
+
+        if pick_folder {
+            if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                naming.destination = ExportDestination::Folder(folder);
+            }
+        }
+    }
+
+    /// Color profile exports are converted to and tagged with, shared with the
+    /// settings dialog
+    pub(crate) fn render_export_profile(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Color profile:");
+            egui::ComboBox::from_id_salt("export_profile")
+                .selected_text(self.settings.export_profile.label())
+                .show_ui(ui, |ui| {
+                    for profile in OutputProfile::ALL {
+                        ui.selectable_value(
+                            &mut self.settings.export_profile,
+                            profile,
+                            profile.label(),
+                        );
+                    }
+                });
+        });
+        if self.settings.export_format == ExportFormat::Jxl {
+            ui.label(RichText::new("JPEG XL files are converted but not tagged").weak());
+        }
+    }
+
+    /// Long edge and filter for shrinking exports, shared with the settings dialog
+    pub(crate) fn render_export_resize(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let mut resize = self.settings.export_resize.is_some();
+            if ui.checkbox(&mut resize, "Fit long edge:").changed() {
+                self.settings.export_resize = resize.then_some(2048);
+            }
+            if let Some(pixels) = &mut self.settings.export_resize {
+                ui.add(egui::DragValue::new(pixels).range(16..=20000).suffix(" px"));
+            }
+        });
+        ui.add_enabled_ui(self.settings.export_resize.is_some(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Filter:");
+                egui::ComboBox::from_id_salt("export_resize_filter")
+                    .selected_text(self.settings.export_resize_filter.name())
+                    .show_ui(ui, |ui| {
+                        for filter in ResizeFilter::all() {
+                            ui.selectable_value(
+                                &mut self.settings.export_resize_filter,
+                                *filter,
+                                filter.name(),
+                            );
+                        }
+                    });
+            });
+        });
+    }
+}
@@ -0,0 +1,122 @@
+use crate::app::ImageViewerApp;
+use egui::{self, RichText, Vec2};
+
+enum StockAction {
+    Apply(usize),
+    Rename(usize, String),
+    Export(usize),
+    Delete(usize),
+}
+
+impl ImageViewerApp {
+    /// Saved film stocks. The film emulation panel is the editor: adjust the
+    /// look there, then save it here under a name.
+    pub fn render_film_stocks_dialog(&mut self, ctx: &egui::Context) {
+        if !self.show_film_stocks_dialog {
+            return;
+        }
+
+        // Close on escape
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.show_film_stocks_dialog = false;
+            return;
+        }
+
+        let mut action = None;
+        let mut save_requested = false;
+        let mut import_requested = false;
+        let mut open = true;
+
+        egui::Window::new("Film Stocks")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(380.0)
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                // Save the current film look
+                ui.horizontal(|ui| {
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut self.film_stock_name_input)
+                            .hint_text("Stock name")
+                            .desired_width(200.0),
+                    );
+                    let enter =
+                        response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    let can_save = self.adjustments.film.enabled
+                        && !self.film_stock_name_input.trim().is_empty();
+                    if (ui
+                        .add_enabled(can_save, egui::Button::new("Save current"))
+                        .on_disabled_hover_text("Turn on film emulation to save its look")
+                        .clicked()
+                        || enter)
+                        && can_save
+                    {
+                        save_requested = true;
+                    }
+                    if ui
+                        .button("Import...")
+                        .on_hover_text("Add stocks exported on another machine")
+                        .clicked()
+                    {
+                        import_requested = true;
+                    }
+                });
+
+                ui.add_space(8.0);
+                ui.separator();
+                ui.add_space(4.0);
+
+                if self.settings.film_stocks.is_empty() {
+                    ui.label(RichText::new("No saved film stocks").weak());
+                    return;
+                }
+
+                egui::ScrollArea::vertical()
+                    .max_height(320.0)
+                    .show(ui, |ui| {
+                        for (i, stock) in self.settings.film_stocks.iter_mut().enumerate() {
+                            ui.horizontal(|ui| {
+                                // Edited in place; persisted once the field loses focus
+                                let response = ui.add(
+                                    egui::TextEdit::singleline(&mut stock.name)
+                                        .desired_width(160.0),
+                                );
+                                if response.lost_focus() {
+                                    action = Some(StockAction::Rename(i, stock.name.clone()));
+                                }
+                                if ui.small_button("Apply").clicked() {
+                                    action = Some(StockAction::Apply(i));
+                                }
+                                if ui.small_button("Export...").clicked() {
+                                    action = Some(StockAction::Export(i));
+                                }
+                                if ui.small_button("🗑").on_hover_text("Delete").clicked() {
+                                    action = Some(StockAction::Delete(i));
+                                }
+                            });
+                        }
+                    });
+            });
+
+        if save_requested {
+            let name = std::mem::take(&mut self.film_stock_name_input);
+            self.save_film_stock(&name);
+        }
+        if import_requested {
+            self.import_film_stocks();
+        }
+
+        match action {
+            Some(StockAction::Apply(i)) => self.apply_film_stock(i),
+            Some(StockAction::Rename(i, name)) => self.rename_film_stock(i, &name),
+            Some(StockAction::Export(i)) => self.export_film_stock(i),
+            Some(StockAction::Delete(i)) => self.delete_film_stock(i),
+            None => {}
+        }
+
+        if !open {
+            self.show_film_stocks_dialog = false;
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use crate::app::ImageViewerApp;
+use crate::metadata::Flag;
+use crate::settings::ColorLabel;
+use egui::{self, Color32, Margin, RichText, Stroke};
+
+impl ImageViewerApp {
+    /// Strip above the thumbnails for narrowing the folder by rating, color
+    /// label, pick/reject flag and file type
+    pub fn render_filter_bar(&mut self, ctx: &egui::Context) {
+        if !self.settings.show_filter_bar || self.image_list.is_empty() {
+            return;
+        }
+
+        let mut changed = false;
+        egui::TopBottomPanel::bottom("filter_bar")
+            .resizable(false)
+            .frame(
+                egui::Frame::NONE
+                    .fill(Color32::from_rgb(30, 30, 34))
+                    .inner_margin(Margin::symmetric(8, 4)),
+            )
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    changed |= self.render_rating_filter(ui);
+                    ui.separator();
+                    changed |= self.render_color_filter(ui);
+                    ui.separator();
+                    changed |= self.render_flag_filter(ui);
+                    ui.separator();
+                    changed |= self.render_extension_filter(ui);
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if self.filter_bar_active() && ui.small_button("Clear").clicked() {
+                            self.clear_filter_bar();
+                        }
+                        ui.label(
+                            RichText::new(format!(
+                                "{} of {}",
+                                self.filtered_list.len(),
+                                self.image_list.len()
+                            ))
+                            .weak(),
+                        );
+                    });
+                });
+            });
+
+        if changed {
+            self.refilter_keeping_current();
+        }
+    }
+
+    fn render_rating_filter(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        let threshold = self.settings.filter_by_rating;
+        for stars in 1..=5u8 {
+            let color = if stars <= threshold {
+                Color32::from_rgb(255, 200, 50)
+            } else {
+                Color32::from_rgb(90, 90, 95)
+            };
+            let response = ui
+                .add(egui::Label::new(RichText::new("★").color(color)).sense(egui::Sense::click()))
+                .on_hover_text(format!("{} stars or more", stars));
+            if response.clicked() {
+                // Clicking the current threshold again turns it off
+                self.settings.filter_by_rating = if stars == threshold { 0 } else { stars };
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    fn render_color_filter(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        for &label in ColorLabel::all() {
+            let selected = self.settings.filter_by_color.contains(&label);
+            let (rect, response) =
+                ui.allocate_exact_size(egui::Vec2::splat(14.0), egui::Sense::click());
+            let painter = ui.painter();
+            if label == ColorLabel::None {
+                painter.circle_stroke(rect.center(), 5.0, Stroke::new(1.0, Color32::GRAY));
+            } else {
+                painter.circle_filled(rect.center(), 5.0, label.to_color());
+            }
+            if selected {
+                painter.circle_stroke(rect.center(), 7.0, Stroke::new(1.5, Color32::WHITE));
+            }
+            let hover = if label == ColorLabel::None {
+                "No label"
+            } else {
+                label.name()
+            };
+            if response.on_hover_text(hover).clicked() {
+                if selected {
+                    self.settings.filter_by_color.retain(|&l| l != label);
+                } else {
+                    self.settings.filter_by_color.push(label);
+                }
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    fn render_flag_filter(&mut self, ui: &mut egui::Ui) -> bool {
+        let before = self.flag_filter;
+        let text = self.flag_filter.map_or("Any flag", |f| f.name());
+        egui::ComboBox::from_id_salt("flag_filter")
+            .selected_text(text)
+            .width(90.0)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.flag_filter, None, "Any flag");
+                for flag in [Flag::Pick, Flag::Reject, Flag::None] {
+                    ui.selectable_value(&mut self.flag_filter, Some(flag), flag.name());
+                }
+            });
+        self.flag_filter != before
+    }
+
+    fn render_extension_filter(&mut self, ui: &mut egui::Ui) -> bool {
+        // Scanning the list every frame adds up in big folders, so keep the
+        // extensions until the folder or its file count changes
+        let cache_id = egui::Id::new((
+            "filter_bar_extensions",
+            &self.current_folder,
+            self.image_list.len(),
+        ));
+        let extensions = match ui.data(|d| d.get_temp::<Vec<String>>(cache_id)) {
+            Some(extensions) => extensions,
+            None => {
+                let extensions = self.folder_extensions();
+                ui.data_mut(|d| d.insert_temp(cache_id, extensions.clone()));
+                extensions
+            }
+        };
+
+        // Only worth offering when the folder mixes file types, unless one is
+        // still chosen from an earlier folder
+        if extensions.len() < 2 && self.settings.filter_extensions.is_empty() {
+            return false;
+        }
+        let mut changed = false;
+        for ext in extensions {
+            let selected = self.settings.filter_extensions.contains(&ext);
+            if ui.selectable_label(selected, ext.to_uppercase()).clicked() {
+                if selected {
+                    self.settings.filter_extensions.retain(|e| e != &ext);
+                } else {
+                    self.settings.filter_extensions.push(ext);
+                }
+                changed = true;
+            }
+        }
+        changed
+    }
+}
@@ -1,11 +1,24 @@
 use crate::app::ImageViewerApp;
+use crate::image_loader::histogram_bar_height;
 use crate::ui::common;
-use egui::{self, Color32, CornerRadius, Vec2};
+use egui::{self, Color32, CornerRadius, RichText, Vec2};
 
 const LR_BG_INPUT: Color32 = Color32::from_rgb(34, 34, 34);
 
-pub fn render_histogram_panel(app: &ImageViewerApp, ui: &mut egui::Ui) {
+pub fn render_histogram_panel(app: &mut ImageViewerApp, ui: &mut egui::Ui) {
     common::lr_collapsible_panel(ui, "Histogram", true, |ui| {
+        let settings = &mut app.settings.histogram;
+        ui.horizontal(|ui| {
+            ui.toggle_value(
+                &mut settings.luminance_only,
+                RichText::new("Luma").size(10.0),
+            )
+            .on_hover_text("Plot luminance instead of the RGB channels");
+            ui.toggle_value(&mut settings.log_scale, RichText::new("Log").size(10.0))
+                .on_hover_text("Logarithmic scale");
+        });
+        let (luminance_only, log_scale) = (settings.luminance_only, settings.log_scale);
+
         let height = 80.0;
         let (response, painter) = ui.allocate_painter(
             Vec2::new(ui.available_width() - 8.0, height),
@@ -17,53 +30,80 @@ pub fn render_histogram_panel(app: &ImageViewerApp, ui: &mut egui::Ui) {
         painter.rect_filled(rect, CornerRadius::same(2), LR_BG_INPUT);
 
         if let Some(histogram) = &app.histogram_data {
-            if histogram.len() >= 3 {
-                let w = rect.width() - 4.0;
-                let h = rect.height() - 4.0;
-                let offset = 2.0;
+            paint_histogram(&painter, rect, histogram, luminance_only, log_scale);
+        }
+    });
+}
 
-                // Find max for scaling (use log scale for better visualization)
-                let max_val = histogram[0]
-                    .iter()
-                    .chain(histogram[1].iter())
-                    .chain(histogram[2].iter())
-                    .max()
-                    .copied()
-                    .unwrap_or(1) as f32;
+/// Draw RGB or luminance histogram bars inside `rect`
+pub fn paint_histogram(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    histogram: &[Vec<u32>],
+    luminance_only: bool,
+    log_scale: bool,
+) {
+    if histogram.len() < 3 {
+        return;
+    }
+    let w = rect.width() - 4.0;
+    let h = rect.height() - 4.0;
+    let offset = 2.0;
+    let base_y = rect.bottom() - offset;
 
-                // Draw filled histograms with transparency
-                let num_bins = 256.min(histogram[0].len());
-                for (i, (&r_val, (&g_val, &b_val))) in histogram[0]
-                    .iter()
-                    .zip(histogram[1].iter().zip(histogram[2].iter()))
-                    .enumerate()
-                    .take(num_bins)
-                {
-                    let x = rect.left() + offset + (i as f32 / 255.0) * w;
-                    let base_y = rect.bottom() - offset;
+    // Histograms from the background scheduler carry no luminance
+    if luminance_only && histogram.len() >= 4 {
+        let luma = &histogram[3];
+        let max_val = luma.iter().max().copied().unwrap_or(1);
+        for (i, &val) in luma.iter().enumerate().take(256) {
+            let x = rect.left() + offset + (i as f32 / 255.0) * w;
+            let l_h = histogram_bar_height(val, max_val, log_scale) * h;
+            painter.line_segment(
+                [egui::pos2(x, base_y), egui::pos2(x, base_y - l_h)],
+                egui::Stroke::new(1.0, Color32::from_rgba_unmultiplied(220, 220, 220, 160)),
+            );
+        }
+        return;
+    }
 
-                    // Red channel
-                    let r_h = (r_val as f32 / max_val).sqrt() * h;
-                    painter.line_segment(
-                        [egui::pos2(x, base_y), egui::pos2(x, base_y - r_h)],
-                        egui::Stroke::new(1.0, Color32::from_rgba_unmultiplied(255, 80, 80, 120)),
-                    );
+    // Find max for scaling
+    let max_val = histogram[0]
+        .iter()
+        .chain(histogram[1].iter())
+        .chain(histogram[2].iter())
+        .max()
+        .copied()
+        .unwrap_or(1);
 
-                    // Green channel
-                    let g_h = (g_val as f32 / max_val).sqrt() * h;
-                    painter.line_segment(
-                        [egui::pos2(x, base_y), egui::pos2(x, base_y - g_h)],
-                        egui::Stroke::new(1.0, Color32::from_rgba_unmultiplied(80, 255, 80, 120)),
-                    );
+    // Draw filled histograms with transparency
+    let num_bins = 256.min(histogram[0].len());
+    for (i, (&r_val, (&g_val, &b_val))) in histogram[0]
+        .iter()
+        .zip(histogram[1].iter().zip(histogram[2].iter()))
+        .enumerate()
+        .take(num_bins)
+    {
+        let x = rect.left() + offset + (i as f32 / 255.0) * w;
 
-                    // Blue channel
-                    let b_h = (b_val as f32 / max_val).sqrt() * h;
-                    painter.line_segment(
-                        [egui::pos2(x, base_y), egui::pos2(x, base_y - b_h)],
-                        egui::Stroke::new(1.0, Color32::from_rgba_unmultiplied(80, 80, 255, 120)),
-                    );
-                }
-            }
-        }
-    });
+        // Red channel
+        let r_h = histogram_bar_height(r_val, max_val, log_scale) * h;
+        painter.line_segment(
+            [egui::pos2(x, base_y), egui::pos2(x, base_y - r_h)],
+            egui::Stroke::new(1.0, Color32::from_rgba_unmultiplied(255, 80, 80, 120)),
+        );
+
+        // Green channel
+        let g_h = histogram_bar_height(g_val, max_val, log_scale) * h;
+        painter.line_segment(
+            [egui::pos2(x, base_y), egui::pos2(x, base_y - g_h)],
+            egui::Stroke::new(1.0, Color32::from_rgba_unmultiplied(80, 255, 80, 120)),
+        );
+
+        // Blue channel
+        let b_h = histogram_bar_height(b_val, max_val, log_scale) * h;
+        painter.line_segment(
+            [egui::pos2(x, base_y), egui::pos2(x, base_y - b_h)],
+            egui::Stroke::new(1.0, Color32::from_rgba_unmultiplied(80, 80, 255, 120)),
+        );
+    }
 }
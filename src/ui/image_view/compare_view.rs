@@ -1,5 +1,9 @@
-use crate::app::{ImageViewerApp, LoaderMessage, ViewMode};
-use egui::{self, Color32, CornerRadius, Rect};
+use crate::app::compare::{compare_grid, MAX_COMPARE};
+use crate::app::{ImageViewerApp, ViewMode};
+use crate::gpu::difference::DifferenceRequest;
+use crate::image_loader::Alignment;
+use crate::task_scheduler::TaskPriority;
+use egui::{self, Color32, CornerRadius, Rect, Stroke};
 
 impl ImageViewerApp {
     pub(crate) fn render_compare_view_public(&mut self, ctx: &egui::Context) {
@@ -10,473 +14,364 @@ impl ImageViewerApp {
         egui::CentralPanel::default()
             .frame(egui::Frame::NONE.fill(self.settings.background_color.to_color()))
             .show(ctx, |ui| {
-                let available = ui.available_size();
+                let indices = self.compare_indices();
+                let paths: Vec<_> = indices.iter().filter_map(|&i| self.path_at(i)).collect();
+                self.retain_compare_textures(&paths);
+                if indices.is_empty() {
+                    ui.centered_and_justified(|ui| {
+                        ui.label("No images available to compare");
+                    });
+                    return;
+                }
 
-                // Determine two display indices to compare
-                let mut sel: Vec<usize> = self.selected_indices.iter().cloned().collect();
-                sel.sort_unstable();
-                if sel.len() < 2 {
-                    if self.filtered_list.is_empty() {
-                        ui.centered_and_justified(|ui| {
-                            ui.label("No images available to compare");
-                        });
-                        return;
+                // Lay the panes out in a grid, leaving room for the footer
+                let mut area = ui.available_rect_before_wrap();
+                area.max.y -= 32.0;
+                let gap = 8.0;
+                let difference = self.compare_difference && indices.len() >= 2;
+                if difference {
+                    let rect = area.shrink(gap);
+                    self.render_difference_pane(ui, indices[0], indices[1], rect);
+                } else {
+                    let (cols, rows) = compare_grid(indices.len(), area.size());
+                    let pane_size = egui::Vec2::new(
+                        (area.width() - gap * (cols + 1) as f32) / cols as f32,
+                        (area.height() - gap * (rows + 1) as f32) / rows as f32,
+                    );
+                    for (slot, &display_idx) in indices.iter().enumerate() {
+                        let offset = egui::Vec2::new(
+                            gap + (slot % cols) as f32 * (pane_size.x + gap),
+                            gap + (slot / cols) as f32 * (pane_size.y + gap),
+                        );
+                        let rect = Rect::from_min_size(area.min + offset, pane_size);
+                        self.render_compare_pane(ui, ctx, slot, display_idx, rect, indices.len());
                     }
-                    let a = self.current_index;
-                    let b = (self.current_index + 1) % self.filtered_list.len();
-                    sel = vec![a, b];
-                } else if sel.len() > 2 {
-                    sel.truncate(2);
                 }
+                ui.advance_cursor_after_rect(area);
 
-                // Helper to get path for display index
-                let get_path =
-                    |app: &ImageViewerApp, display_idx: usize| -> Option<std::path::PathBuf> {
-                        app.filtered_list
-                            .get(display_idx)
-                            .and_then(|&real_idx| app.image_list.get(real_idx).cloned())
-                    };
-
-                let left_path = get_path(self, sel[0]);
-                let right_path = get_path(self, sel[1]);
-
-                // Layout two panels side-by-side
+                // Footer controls for compare
                 ui.horizontal(|ui| {
-                    ui.add_space(8.0);
-                    // Left panel
-                    ui.vertical(|ui| {
-                        ui.group(|ui| {
-                            let size_half =
-                                egui::Vec2::new(available.x * 0.5 - 16.0, available.y - 24.0);
-                            let (left_resp, left_painter) =
-                                ui.allocate_painter(size_half, egui::Sense::click());
-                            let rect = left_resp.rect;
-                            left_painter.rect_filled(
-                                rect,
-                                CornerRadius::same(2),
-                                Color32::from_rgb(20, 20, 22),
-                            );
-
-                            if let Some(path) = &left_path {
-                                // Use current texture if comparing current image
-                                let tex_id_opt = if sel[0] == self.current_index {
-                                    self.current_texture.as_ref().map(|t| t.id())
-                                } else {
-                                    self.thumbnail_textures.get(path).map(|t| t.id())
-                                };
-                                if let Some(tex_id) = tex_id_opt {
-                                    let tex_size = self.texture_size_from_id(tex_id);
-                                    let base_scale =
-                                        (rect.width() / tex_size.x).min(rect.height() / tex_size.y);
-                                    let scaled = tex_size * base_scale * self.compare_zoom[0];
-                                    let inner_rect = Rect::from_center_size(
-                                        rect.center() + self.compare_pan[0],
-                                        scaled,
-                                    );
-                                    left_painter.image(
-                                        tex_id,
-                                        inner_rect,
-                                        Rect::from_min_max(
-                                            egui::pos2(0.0, 0.0),
-                                            egui::pos2(1.0, 1.0),
-                                        ),
-                                        Color32::WHITE,
-                                    );
-
-                                    // Zoom with scroll when hovering
-                                    if left_resp.hovered() {
-                                        let scroll = ui.input(|i| i.smooth_scroll_delta.y);
-                                        if scroll != 0.0 {
-                                            let factor = 1.0 + scroll * 0.01; // larger per-tick zoom
-                                            self.compare_zoom[0] =
-                                                (self.compare_zoom[0] * factor).clamp(0.1, 16.0);
-                                            ui.ctx().request_repaint();
-
-                                            // If we are zooming a thumbnail (not current full image), request a larger preview once
-                                            if !self.thumbnail_requests.contains(path)
-                                                && self.compare_zoom[0] > 1.5
-                                                && !self
-                                                    .compare_large_preview_requests
-                                                    .contains(path)
-                                            {
-                                                let path_clone = path.clone();
-                                                let tx = self.loader_tx.clone();
-                                                let cache = self.image_cache.clone();
-                                                self.compare_large_preview_requests
-                                                    .insert(path.clone());
-                                                rayon::spawn(move || {
-                                                    if let Ok(thumb) =
-                                                        crate::image_loader::load_thumbnail(
-                                                            &path_clone,
-                                                            2048,
-                                                        )
-                                                    {
-                                                        let thumb_clone = thumb.clone();
-                                                        cache.insert_thumbnail(
-                                                            path_clone.clone(),
-                                                            thumb_clone,
-                                                        );
-                                                        let _ = tx.send(
-                                                            LoaderMessage::ThumbnailLoaded(
-                                                                path_clone, thumb,
-                                                            ),
-                                                        );
-                                                    }
-                                                });
-                                            }
-                                        }
-
-                                        // Pan with drag
-                                        if left_resp.dragged() {
-                                            let delta = left_resp.drag_delta();
-                                            self.compare_pan[0] += delta;
-                                        }
-                                    }
-                                } else {
-                                    if !self.thumbnail_requests.contains(path) {
-                                        self.ensure_thumbnail_requested(path, ctx);
-                                    }
-                                    left_painter.text(
-                                        rect.center(),
-                                        egui::Align2::CENTER_CENTER,
-                                        self.spinner_char(ui),
-                                        egui::FontId::proportional(24.0),
-                                        Color32::from_rgb(130, 130, 130),
-                                    );
-                                }
+                    ui.add_space(gap);
+                    ui.checkbox(&mut self.settings.compare_link_zoom, "Link zoom")
+                        .on_hover_text("Zoom and pan all images together");
+                    ui.checkbox(&mut self.settings.compare_auto_align, "Auto-align")
+                        .on_hover_text(
+                            "Line each image up with the first, so burst shots show the same \
+                             subject in every pane",
+                        );
+                    ui.add_enabled(
+                        indices.len() >= 2,
+                        egui::Checkbox::new(&mut self.compare_difference, "Difference"),
+                    )
+                    .on_hover_text(
+                        "Show how the first two images differ, to spot retouching or \
+                         compression artifacts",
+                    );
+                    if difference {
+                        ui.add(
+                            egui::Slider::new(
+                                &mut self.settings.compare_difference_gain,
+                                1.0..=32.0,
+                            )
+                            .logarithmic(true)
+                            .text("Gain"),
+                        );
+                        ui.checkbox(&mut self.settings.compare_difference_heatmap, "Heatmap");
+                    }
+                    if let [first, second, ..] = &paths[..] {
+                        let quality = match self.quality_scores(first, second) {
+                            Some(Ok(scores)) => scores.summary(),
+                            Some(Err(_)) => "Quality couldn't be measured".to_string(),
+                            None => "Measuring quality...".to_string(),
+                        };
+                        ui.label(quality).on_hover_text(
+                            "How closely the second image matches the first; higher is closer",
+                        );
+                    }
+                    if ui.button("Reset Zoom").clicked() {
+                        self.compare_zoom = [1.0; MAX_COMPARE];
+                        self.compare_pan = [egui::Vec2::ZERO; MAX_COMPARE];
+                    }
+                    if ui.button("Close Compare").clicked() {
+                        self.view_mode = ViewMode::Single;
+                    }
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "Select up to {} thumbnails to compare them",
+                            MAX_COMPARE
+                        ))
+                        .weak(),
+                    );
+                });
+            });
+    }
 
-                                // EXIF overlay for this slot
-                                if self.settings.show_exif_overlay {
-                                    if let Some(exif) = self.compare_exifs.get(path) {
-                                        let overlay_pos = rect.left_bottom()
-                                            + egui::Vec2::new(12.0, -12.0 - 48.0);
-                                        let overlay_rect = Rect::from_min_size(
-                                            overlay_pos,
-                                            egui::Vec2::new(280.0, 48.0),
-                                        );
-                                        left_painter.rect_filled(
-                                            overlay_rect,
-                                            CornerRadius::same(6),
-                                            Color32::from_rgba_unmultiplied(0, 0, 0, 160),
-                                        );
-                                        let camera = exif
-                                            .camera_model
-                                            .clone()
-                                            .unwrap_or_else(|| "Unknown".to_string());
-                                        left_painter.text(
-                                            overlay_rect.left_top() + egui::Vec2::new(8.0, 6.0),
-                                            egui::Align2::LEFT_TOP,
-                                            camera,
-                                            egui::FontId::proportional(12.0),
-                                            Color32::WHITE,
-                                        );
-                                        left_painter.text(
-                                            overlay_rect.left_bottom() + egui::Vec2::new(8.0, -6.0),
-                                            egui::Align2::LEFT_BOTTOM,
-                                            format!(
-                                                "{} • {}",
-                                                exif.focal_length_formatted(),
-                                                exif.aperture_formatted()
-                                            ),
-                                            egui::FontId::proportional(11.0),
-                                            Color32::from_rgb(200, 200, 200),
-                                        );
-                                    } else {
-                                        let path_clone = path.clone();
-                                        self.spawn_loader(move |_| {
-                                            let exif =
-                                                crate::exif_data::ExifInfo::from_file(&path_clone);
-                                            Some(LoaderMessage::ExifLoaded(
-                                                path_clone,
-                                                Box::new(exif),
-                                            ))
-                                        });
-                                    }
-                                } else if !self.thumbnail_requests.contains(path) {
-                                    self.ensure_thumbnail_requested(path, ctx);
-                                }
+    fn render_compare_pane(
+        &mut self,
+        ui: &mut egui::Ui,
+        ctx: &egui::Context,
+        slot: usize,
+        display_idx: usize,
+        rect: Rect,
+        count: usize,
+    ) {
+        let response = ui.allocate_rect(rect, egui::Sense::click_and_drag());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, CornerRadius::same(2), Color32::from_rgb(20, 20, 22));
 
-                                // Click to make this the current image
-                                if left_resp.clicked() {
-                                    if let Some(dp) = left_path.clone() {
-                                        if let Some(idx) =
-                                            self.image_list.iter().position(|p| p == &dp)
-                                        {
-                                            // find display index among filtered_list
-                                            if let Some(pos) =
-                                                self.filtered_list.iter().position(|&r| r == idx)
-                                            {
-                                                self.go_to_index(pos);
-                                            }
-                                        }
-                                    }
-                                }
-                                // Context menu for left image
-                                left_resp.context_menu(|ui: &mut egui::Ui| {
-                                    if ui.button("View").clicked() {
-                                        if let Some(dp) = left_path.clone() {
-                                            if let Some(idx) =
-                                                self.image_list.iter().position(|p| p == &dp)
-                                            {
-                                                if let Some(pos) = self
-                                                    .filtered_list
-                                                    .iter()
-                                                    .position(|&r| r == idx)
-                                                {
-                                                    self.go_to_index(pos);
-                                                }
-                                            }
-                                        }
-                                        ui.close_menu();
-                                    }
+        let Some(path) = self.path_at(display_idx) else {
+            painter.text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                "No image",
+                egui::FontId::proportional(18.0),
+                Color32::GRAY,
+            );
+            return;
+        };
 
-                                    ui.separator();
-                                    if ui.button("Delete").clicked() {
-                                        if let Some(dp) = left_path.clone() {
-                                            if let Some(idx) =
-                                                self.image_list.iter().position(|p| p == &dp)
-                                            {
-                                                if let Some(pos) = self
-                                                    .filtered_list
-                                                    .iter()
-                                                    .position(|&r| r == idx)
-                                                {
-                                                    self.current_index = pos;
-                                                    self.delete_current_image();
-                                                }
-                                            }
-                                        }
-                                        ui.close_menu();
-                                    }
-                                });
-                                left_painter.text(
-                                    rect.center(),
-                                    egui::Align2::CENTER_CENTER,
-                                    "No image",
-                                    egui::FontId::proportional(18.0),
-                                    Color32::GRAY,
-                                );
-                            }
-                        });
-                    });
+        // Linked panes all share the first pane's zoom and pan
+        let view = if self.settings.compare_link_zoom {
+            0
+        } else {
+            slot
+        };
+        let is_current = display_idx == self.current_index;
 
-                    ui.add_space(8.0);
+        // The current image's texture, or a full decode loaded for this pane; the
+        // thumbnail stands in until the decode arrives
+        let full_texture = if is_current {
+            self.current_texture.as_ref().map(|t| t.id())
+        } else {
+            self.compare_texture(&path)
+        };
+        let tex_id_opt =
+            full_texture.or_else(|| self.thumbnail_textures.get(&path).map(|t| t.id()));
+        if let Some(tex_id) = tex_id_opt {
+            let tex_size = self.texture_size_from_id(tex_id);
+            let base_scale = (rect.width() / tex_size.x).min(rect.height() / tex_size.y);
+            let scaled = tex_size * base_scale * self.compare_zoom[view];
+            let inner_rect = Rect::from_center_size(rect.center() + self.compare_pan[view], scaled);
+            match self.compare_alignment(&path) {
+                Some(alignment) => paint_aligned(&painter, tex_id, inner_rect, &alignment),
+                None => {
+                    painter.image(
+                        tex_id,
+                        inner_rect,
+                        Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                        Color32::WHITE,
+                    );
+                }
+            }
+            if full_texture.is_none() {
+                painter.text(
+                    rect.right_top() + egui::Vec2::new(-8.0, 6.0),
+                    egui::Align2::RIGHT_TOP,
+                    self.spinner_char(ui),
+                    egui::FontId::proportional(14.0),
+                    Color32::from_rgb(200, 200, 200),
+                );
+                ui.ctx().request_repaint();
+            }
 
-                    // Right panel - symmetric to left
-                    ui.vertical(|ui| {
-                        ui.group(|ui| {
-                            let size_half =
-                                egui::Vec2::new(available.x * 0.5 - 16.0, available.y - 24.0);
-                            let (right_resp, right_painter) =
-                                ui.allocate_painter(size_half, egui::Sense::click());
-                            let rect = right_resp.rect;
-                            right_painter.rect_filled(
-                                rect,
-                                CornerRadius::same(2),
-                                Color32::from_rgb(20, 20, 22),
-                            );
+            self.zoom_and_pan_pane(ui, &response, view);
+        } else {
+            if !self.thumbnail_requests.contains(&path) {
+                self.ensure_thumbnail_requested(&path, ctx);
+            }
+            painter.text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                self.spinner_char(ui),
+                egui::FontId::proportional(24.0),
+                Color32::from_rgb(130, 130, 130),
+            );
+        }
 
-                            if let Some(path) = &right_path {
-                                let tex_id_opt = if sel[1] == self.current_index {
-                                    self.current_texture.as_ref().map(|t| t.id())
-                                } else {
-                                    self.thumbnail_textures.get(path).map(|t| t.id())
-                                };
-                                if let Some(tex_id) = tex_id_opt {
-                                    let tex_size = self.texture_size_from_id(tex_id);
-                                    let base_scale =
-                                        (rect.width() / tex_size.x).min(rect.height() / tex_size.y);
-                                    let scaled = tex_size * base_scale * self.compare_zoom[1];
-                                    let inner_rect = Rect::from_center_size(rect.center(), scaled);
-                                    right_painter.image(
-                                        tex_id,
-                                        inner_rect,
-                                        Rect::from_min_max(
-                                            egui::pos2(0.0, 0.0),
-                                            egui::pos2(1.0, 1.0),
-                                        ),
-                                        Color32::WHITE,
-                                    );
+        // File name, and a frame around the current image
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        painter.text(
+            rect.left_top() + egui::Vec2::new(8.0, 6.0),
+            egui::Align2::LEFT_TOP,
+            name,
+            egui::FontId::proportional(12.0),
+            Color32::from_rgb(200, 200, 200),
+        );
+        if is_current {
+            painter.rect_stroke(
+                rect,
+                CornerRadius::same(2),
+                Stroke::new(1.5, Color32::from_rgb(70, 130, 255)),
+                egui::StrokeKind::Inside,
+            );
+        }
 
-                                    if right_resp.hovered() {
-                                        let scroll = ui.input(|i| i.smooth_scroll_delta.y);
-                                        if scroll != 0.0 {
-                                            let factor = 1.0 + scroll * 0.01;
-                                            self.compare_zoom[1] =
-                                                (self.compare_zoom[1] * factor).clamp(0.1, 16.0);
-                                            ui.ctx().request_repaint();
+        // EXIF overlay for this pane
+        if self.settings.show_exif_overlay {
+            if let Some(exif) = self.compare_exifs.get(&path) {
+                self.draw_exif_overlay(ui, rect, &path, exif);
+            } else {
+                self.load_exif_data(&path, TaskPriority::Medium);
+            }
+        }
 
-                                            if !self.thumbnail_requests.contains(path)
-                                                && self.compare_zoom[1] > 1.5
-                                                && !self
-                                                    .compare_large_preview_requests
-                                                    .contains(path)
-                                            {
-                                                let path_clone = path.clone();
-                                                let tx = self.loader_tx.clone();
-                                                let cache = self.image_cache.clone();
-                                                self.compare_large_preview_requests
-                                                    .insert(path.clone());
-                                                rayon::spawn(move || {
-                                                    if let Ok(thumb) =
-                                                        crate::image_loader::load_thumbnail(
-                                                            &path_clone,
-                                                            2048,
-                                                        )
-                                                    {
-                                                        let thumb_clone = thumb.clone();
-                                                        cache.insert_thumbnail(
-                                                            path_clone.clone(),
-                                                            thumb_clone,
-                                                        );
-                                                        let _ = tx.send(
-                                                            LoaderMessage::ThumbnailLoaded(
-                                                                path_clone, thumb,
-                                                            ),
-                                                        );
-                                                    }
-                                                });
-                                            }
-                                        }
+        // Click to make this the current image
+        if response.clicked() {
+            self.go_to_index(display_idx);
+        }
+        response.context_menu(|ui: &mut egui::Ui| {
+            if ui.button("View").clicked() {
+                self.go_to_index(display_idx);
+                self.view_mode = ViewMode::Single;
+                ui.close_menu();
+            }
+            if count > 2
+                && self.selected_indices.contains(&display_idx)
+                && ui.button("Remove from Compare").clicked()
+            {
+                self.selected_indices.remove(&display_idx);
+                ui.close_menu();
+            }
 
-                                        if right_resp.dragged() {
-                                            let delta = right_resp.drag_delta();
-                                            self.compare_pan[1] += delta;
-                                        }
-                                    }
-                                } else {
-                                    if !self.thumbnail_requests.contains(path) {
-                                        self.ensure_thumbnail_requested(path, ctx);
-                                    }
-                                    right_painter.text(
-                                        rect.center(),
-                                        egui::Align2::CENTER_CENTER,
-                                        self.spinner_char(ui),
-                                        egui::FontId::proportional(24.0),
-                                        Color32::from_rgb(130, 130, 130),
-                                    );
-                                }
+            ui.separator();
+            if ui.button("Delete").clicked() {
+                self.delete_compare_image(display_idx);
+                ui.close_menu();
+            }
+        });
+    }
+}
 
-                                if let Some(exif) = self.compare_exifs.get(path) {
-                                    let overlay_pos =
-                                        rect.left_bottom() + egui::Vec2::new(12.0, -12.0 - 48.0);
-                                    let overlay_rect = Rect::from_min_size(
-                                        overlay_pos,
-                                        egui::Vec2::new(280.0, 48.0),
-                                    );
-                                    right_painter.rect_filled(
-                                        overlay_rect,
-                                        CornerRadius::same(6),
-                                        Color32::from_rgba_unmultiplied(0, 0, 0, 160),
-                                    );
-                                    right_painter.text(
-                                        overlay_rect.left_top() + egui::Vec2::new(8.0, 6.0),
-                                        egui::Align2::LEFT_TOP,
-                                        exif.camera_model
-                                            .clone()
-                                            .unwrap_or_else(|| "Unknown".to_string()),
-                                        egui::FontId::proportional(12.0),
-                                        Color32::WHITE,
-                                    );
-                                    right_painter.text(
-                                        overlay_rect.left_bottom() + egui::Vec2::new(8.0, -6.0),
-                                        egui::Align2::LEFT_BOTTOM,
-                                        format!(
-                                            "{} • ISO {}",
-                                            exif.aperture.clone().unwrap_or_default(),
-                                            exif.iso.clone().unwrap_or_default()
-                                        ),
-                                        egui::FontId::proportional(11.0),
-                                        Color32::from_rgb(200, 200, 200),
-                                    );
-                                } else {
-                                    let p2 = path.clone();
-                                    self.spawn_loader(move |_| {
-                                        let exif = crate::exif_data::ExifInfo::from_file(&p2);
-                                        Some(LoaderMessage::ExifLoaded(p2, Box::new(exif)))
-                                    });
-                                }
+impl ImageViewerApp {
+    /// Zoom with scroll when hovering, pan with drag
+    fn zoom_and_pan_pane(&mut self, ui: &egui::Ui, response: &egui::Response, view: usize) {
+        if response.hovered() {
+            let scroll = ui.input(|i| i.smooth_scroll_delta.y);
+            if scroll != 0.0 {
+                let factor = 1.0 + scroll * 0.01; // larger per-tick zoom
+                self.compare_zoom[view] = (self.compare_zoom[view] * factor).clamp(0.1, 16.0);
+                ui.ctx().request_repaint();
+            }
+        }
+        if response.dragged() {
+            self.compare_pan[view] += response.drag_delta();
+        }
+    }
 
-                                if right_resp.clicked() {
-                                    if let Some(dp) = right_path.clone() {
-                                        if let Some(idx) =
-                                            self.image_list.iter().position(|p| p == &dp)
-                                        {
-                                            if let Some(pos) =
-                                                self.filtered_list.iter().position(|&r| r == idx)
-                                            {
-                                                self.go_to_index(pos);
-                                            }
-                                        }
-                                    }
-                                }
+    /// |A−B| of two compared images, worked out on the GPU from their full
+    /// textures, in place of the panes
+    fn render_difference_pane(
+        &mut self,
+        ui: &mut egui::Ui,
+        first: usize,
+        second: usize,
+        rect: Rect,
+    ) {
+        let response = ui.allocate_rect(rect, egui::Sense::drag());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, CornerRadius::same(2), Color32::from_rgb(20, 20, 22));
 
-                                // Context menu for right image
-                                right_resp.context_menu(|ui: &mut egui::Ui| {
-                                    if ui.button("View").clicked() {
-                                        if let Some(dp) = right_path.clone() {
-                                            if let Some(idx) =
-                                                self.image_list.iter().position(|p| p == &dp)
-                                            {
-                                                if let Some(pos) = self
-                                                    .filtered_list
-                                                    .iter()
-                                                    .position(|&r| r == idx)
-                                                {
-                                                    self.go_to_index(pos);
-                                                }
-                                            }
-                                        }
-                                        ui.close_menu();
-                                    }
+        let textures = [first, second].map(|display_idx| {
+            let path = self.path_at(display_idx)?;
+            if display_idx == self.current_index {
+                self.current_texture.as_ref().map(|t| t.id())
+            } else {
+                self.compare_texture(&path)
+            }
+        });
+        let difference = match textures {
+            [Some(first), Some(second)] => {
+                let request = DifferenceRequest {
+                    first,
+                    second,
+                    gain: self.settings.compare_difference_gain,
+                    heatmap: self.settings.compare_difference_heatmap,
+                };
+                let texture = self
+                    .gpu_difference
+                    .as_mut()
+                    .and_then(|r| r.render(&request));
+                texture.map(|texture| (texture, self.texture_size_from_id(first)))
+            }
+            _ => None,
+        };
 
-                                    ui.separator();
-                                    if ui.button("Delete").clicked() {
-                                        if let Some(dp) = right_path.clone() {
-                                            if let Some(idx) =
-                                                self.image_list.iter().position(|p| p == &dp)
-                                            {
-                                                if let Some(pos) = self
-                                                    .filtered_list
-                                                    .iter()
-                                                    .position(|&r| r == idx)
-                                                {
-                                                    self.current_index = pos;
-                                                    self.delete_current_image();
-                                                }
-                                            }
-                                        }
-                                        ui.close_menu();
-                                    }
-                                });
-                            } else {
-                                right_painter.text(
-                                    rect.center(),
-                                    egui::Align2::CENTER_CENTER,
-                                    "No image",
-                                    egui::FontId::proportional(18.0),
-                                    Color32::GRAY,
-                                );
-                            }
-                        });
-                    });
+        match difference {
+            Some((texture, size)) => {
+                let base_scale = (rect.width() / size.x).min(rect.height() / size.y);
+                let scaled = size * base_scale * self.compare_zoom[0];
+                let inner_rect =
+                    Rect::from_center_size(rect.center() + self.compare_pan[0], scaled);
+                painter.image(
+                    texture,
+                    inner_rect,
+                    Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                    Color32::WHITE,
+                );
+                self.zoom_and_pan_pane(ui, &response, 0);
+            }
+            None if self.gpu_difference.is_none() => {
+                painter.text(
+                    rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    "The difference view needs GPU rendering",
+                    egui::FontId::proportional(16.0),
+                    Color32::GRAY,
+                );
+            }
+            // Full decodes still loading, or not on the GPU until egui's next frame
+            None => {
+                painter.text(
+                    rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    self.spinner_char(ui),
+                    egui::FontId::proportional(24.0),
+                    Color32::from_rgb(130, 130, 130),
+                );
+                ui.ctx().request_repaint();
+            }
+        }
 
-                    ui.add_space(8.0);
-                });
+        let name = |display_idx| {
+            self.path_at(display_idx)
+                .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+                .unwrap_or_default()
+        };
+        painter.text(
+            rect.left_top() + egui::Vec2::new(8.0, 6.0),
+            egui::Align2::LEFT_TOP,
+            format!("{} − {}", name(first), name(second)),
+            egui::FontId::proportional(12.0),
+            Color32::from_rgb(200, 200, 200),
+        );
+    }
+}
 
-                // Footer controls for compare
-                ui.horizontal(|ui| {
-                    if ui.button("Swap Sides").clicked() {
-                        sel.swap(0, 1);
-                        // Update selected_indices to reflect swap if applicable
-                        self.selected_indices.clear();
-                        self.selected_indices.insert(sel[0]);
-                        self.selected_indices.insert(sel[1]);
-                    }
-                    if ui.button("Close Compare").clicked() {
-                        self.view_mode = ViewMode::Single;
-                    }
-                });
-            });
+/// Draw a compared frame at `rect`, moved and rotated so its content lines up
+/// with the first frame drawn at the same place
+fn paint_aligned(
+    painter: &egui::Painter,
+    texture: egui::TextureId,
+    rect: Rect,
+    alignment: &Alignment,
+) {
+    let height = rect.height() / rect.width();
+    let corners = [
+        ([0.0, 0.0], egui::pos2(0.0, 0.0)),
+        ([1.0, 0.0], egui::pos2(1.0, 0.0)),
+        ([1.0, height], egui::pos2(1.0, 1.0)),
+        ([0.0, height], egui::pos2(0.0, 1.0)),
+    ];
+    let mut mesh = egui::Mesh::with_texture(texture);
+    for (corner, uv) in corners {
+        let p = alignment.to_reference(corner, height);
+        mesh.vertices.push(egui::epaint::Vertex {
+            pos: rect.min + egui::Vec2::new(p[0], p[1]) * rect.width(),
+            uv,
+            color: Color32::WHITE,
+        });
     }
+    mesh.add_triangle(0, 1, 2);
+    mesh.add_triangle(0, 2, 3);
+    painter.add(egui::Shape::mesh(mesh));
 }
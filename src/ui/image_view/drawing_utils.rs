@@ -1,6 +1,10 @@
 use crate::app::ImageViewerApp;
+use crate::exif_data::ExifInfo;
+use crate::exif_overlay::{self, OverlayInfo};
+use crate::image_loader::{LocalMask, LocalMaskKind};
 use crate::settings::GridType;
 use egui::{self, Color32, CornerRadius, Rect, Stroke, StrokeKind, Vec2};
+use std::path::Path;
 
 impl ImageViewerApp {
     pub(crate) fn draw_overlays(&self, ui: &mut egui::Ui, image_rect: Rect) {
@@ -28,6 +32,25 @@ impl ImageViewerApp {
             }
         }
 
+        // Clipping warning, blinking twice a second unless set to stay on
+        if self.settings.show_clipping {
+            if let Some(tex) = &self.clipping_texture {
+                let blink_on = ui.input(|i| i.time * 2.0) as i64 % 2 == 0;
+                if !self.settings.clipping.blink || blink_on {
+                    ui.painter().image(
+                        tex.id(),
+                        image_rect,
+                        Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                        Color32::WHITE,
+                    );
+                }
+                if self.settings.clipping.blink {
+                    ui.ctx()
+                        .request_repaint_after(std::time::Duration::from_millis(500));
+                }
+            }
+        }
+
         // Custom overlay
         if self.settings.show_custom_overlay {
             if let Some(tex) = &self.custom_overlay_texture {
@@ -59,12 +82,80 @@ impl ImageViewerApp {
             self.draw_crop_overlay(ui, image_rect);
         }
 
+        // Straighten line overlay
+        if self.straighten_mode {
+            self.draw_straighten_overlay(ui, image_rect);
+        }
+
+        // Outline of the selected local adjustment
+        if !self.show_original {
+            self.draw_local_mask_overlay(ui, image_rect);
+        }
+
         // Grid overlay
         if self.settings.show_grid_overlay {
             self.draw_grid_overlay(ui, image_rect);
         }
     }
 
+    /// Box of EXIF text in a corner of `rect`, laid out from the overlay template
+    pub(crate) fn draw_exif_overlay(
+        &self,
+        ui: &mut egui::Ui,
+        rect: Rect,
+        path: &Path,
+        exif: &ExifInfo,
+    ) {
+        let overlay = &self.settings.exif_overlay;
+        let metadata = self.metadata_db.get(path);
+        let mut lines = overlay.render(&OverlayInfo {
+            path,
+            exif,
+            metadata: &metadata,
+        });
+        if lines.is_empty() {
+            lines.push("No EXIF data".to_string());
+        }
+
+        let scale = overlay.monitor_scale(exif_overlay::monitor_key(ui.ctx()).as_deref());
+        let title_size = overlay.font_size * scale;
+        let painter = ui.painter().with_clip_rect(rect.intersect(ui.clip_rect()));
+        let galleys: Vec<_> = lines
+            .into_iter()
+            .enumerate()
+            .map(|(i, line)| {
+                let (size, color) = if i == 0 {
+                    (title_size, Color32::WHITE)
+                } else {
+                    (title_size * 0.85, Color32::from_rgb(200, 200, 200))
+                };
+                painter.layout_no_wrap(line, egui::FontId::proportional(size), color)
+            })
+            .collect();
+
+        let padding = Vec2::new(8.0, 6.0) * scale;
+        let spacing = 2.0 * scale;
+        let text_size = Vec2::new(
+            galleys.iter().map(|g| g.size().x).fold(0.0, f32::max),
+            galleys.iter().map(|g| g.size().y).sum::<f32>() + spacing * (galleys.len() - 1) as f32,
+        );
+        let align = overlay.corner.align();
+        let anchor = align.pos_in_rect(&rect.shrink(12.0));
+        let overlay_rect = align.anchor_size(anchor, text_size + padding * 2.0);
+
+        painter.rect_filled(
+            overlay_rect,
+            CornerRadius::same(6),
+            Color32::from_rgba_unmultiplied(0, 0, 0, 180),
+        );
+        let mut pos = overlay_rect.min + padding;
+        for galley in galleys {
+            let height = galley.size().y;
+            painter.galley(pos, galley, Color32::WHITE);
+            pos.y += height + spacing;
+        }
+    }
+
     pub(crate) fn draw_grid_overlay(&self, ui: &mut egui::Ui, rect: Rect) {
         let stroke = Stroke::new(1.0, Color32::from_rgba_unmultiplied(255, 255, 255, 100));
         let painter = ui.painter();
@@ -181,7 +272,7 @@ impl ImageViewerApp {
             // Calculate image rectangle (same as in render_single_view)
             let rect = ui.available_rect_before_wrap();
             let tex_size = tex.size_vec2();
-            let display_size = tex_size * self.zoom;
+            let display_size = self.image_display_size().unwrap_or(tex_size) * self.zoom;
 
             // Guard against degenerate display sizes
             if display_size.x.abs() < 1e-6 || display_size.y.abs() < 1e-6 {
@@ -244,6 +335,51 @@ impl ImageViewerApp {
         }
     }
 
+    /// Lines between the pixels of the photo and an outline around the one
+    /// under the cursor, drawn while inspecting pixels
+    pub(crate) fn draw_pixel_grid(&self, ui: &mut egui::Ui, view_rect: Rect, image_rect: Rect) {
+        let Some(image) = &self.current_image else {
+            return;
+        };
+        if self.adjustments.straighten_angle != 0.0 {
+            return;
+        }
+        let photo = self.photo_screen_rect(image_rect);
+        let visible = photo.intersect(view_rect);
+        if !visible.is_positive() {
+            return;
+        }
+
+        let cell = Vec2::new(
+            photo.width() / image.width() as f32,
+            photo.height() / image.height() as f32,
+        );
+        let painter = ui.painter_at(visible);
+        let stroke = Stroke::new(1.0, Color32::from_rgba_unmultiplied(128, 128, 128, 96));
+
+        let first_column = ((visible.min.x - photo.min.x) / cell.x).ceil() as u32;
+        let last_column = ((visible.max.x - photo.min.x) / cell.x).floor() as u32;
+        for column in first_column..=last_column {
+            let x = photo.min.x + column as f32 * cell.x;
+            painter.vline(x, visible.y_range(), stroke);
+        }
+        let first_row = ((visible.min.y - photo.min.y) / cell.y).ceil() as u32;
+        let last_row = ((visible.max.y - photo.min.y) / cell.y).floor() as u32;
+        for row in first_row..=last_row {
+            let y = photo.min.y + row as f32 * cell.y;
+            painter.hline(visible.x_range(), y, stroke);
+        }
+
+        if let Some((x, y)) = self.hovered_pixel {
+            painter.rect_stroke(
+                Rect::from_min_size(photo.min + Vec2::new(x as f32, y as f32) * cell, cell),
+                CornerRadius::ZERO,
+                Stroke::new(1.0, Color32::WHITE),
+                StrokeKind::Inside,
+            );
+        }
+    }
+
     pub(crate) fn draw_color_info(&self, ui: &mut egui::Ui, rect: Rect) {
         if let Some((r, g, b)) = self.picked_color {
             let info_rect = Rect::from_min_size(
@@ -288,6 +424,113 @@ impl ImageViewerApp {
         }
     }
 
+    pub(crate) fn draw_local_mask_overlay(&self, ui: &mut egui::Ui, image_rect: Rect) {
+        let photo_rect = self.photo_screen_rect(image_rect);
+        let painter = ui.painter().with_clip_rect(photo_rect);
+        // A dark halo keeps the outline visible on light images
+        let halo = Stroke::new(3.0, Color32::from_black_alpha(120));
+        let line = Stroke::new(1.0, Color32::WHITE);
+
+        // Outline of the brush under the pointer
+        if self.local_mask_tool == Some(LocalMaskKind::Brush) {
+            if let Some(pos) = ui.input(|i| i.pointer.hover_pos()) {
+                let radius = self.settings.brush.size * photo_rect.height();
+                for stroke in [halo, line] {
+                    painter.circle_stroke(pos, radius, stroke);
+                }
+            }
+        }
+
+        let Some(local) = self
+            .selected_local_adjustment
+            .and_then(|i| self.adjustments.local.get(i))
+        else {
+            return;
+        };
+
+        let to_screen = |p: [f32; 2]| {
+            egui::pos2(
+                photo_rect.left() + p[0] * photo_rect.width(),
+                photo_rect.top() + p[1] * photo_rect.height(),
+            )
+        };
+        let guide = Stroke::new(1.0, Color32::from_white_alpha(120));
+        let pin = Color32::from_rgb(255, 200, 0);
+
+        match &local.mask {
+            LocalMask::Radial {
+                center,
+                radius,
+                feather,
+            } => {
+                let ellipse = |scale: f32| -> Vec<egui::Pos2> {
+                    (0..64)
+                        .map(|i| {
+                            let angle = i as f32 / 64.0 * std::f32::consts::TAU;
+                            to_screen([
+                                center[0] + radius[0] * scale * angle.cos(),
+                                center[1] + radius[1] * scale * angle.sin(),
+                            ])
+                        })
+                        .collect()
+                };
+                for stroke in [halo, line] {
+                    painter.add(egui::Shape::closed_line(ellipse(1.0), stroke));
+                }
+                // Full strength inside the inner ring
+                painter.add(egui::Shape::closed_line(ellipse(1.0 - feather), guide));
+                painter.circle_filled(to_screen(*center), 3.0, pin);
+            }
+            LocalMask::Linear { start, end } => {
+                let (start, end) = (to_screen(*start), to_screen(*end));
+                let along = (end - start).normalized();
+                let across = along.rot90() * photo_rect.size().length();
+                for point in [start, end] {
+                    for stroke in [halo, line] {
+                        painter.line_segment([point - across, point + across], stroke);
+                    }
+                }
+                painter.line_segment([start, end], guide);
+                painter.circle_filled(start, 3.0, pin);
+                painter.circle_filled(end, 3.0, pin);
+            }
+            LocalMask::Brush { dabs } => {
+                // Painted areas tinted red, stronger where the brush was
+                for dab in dabs {
+                    let alpha = (dab.opacity.clamp(0.0, 1.0) * 24.0) as u8;
+                    painter.circle_filled(
+                        to_screen(dab.center),
+                        dab.radius * photo_rect.height(),
+                        Color32::from_rgba_unmultiplied(255, 40, 40, alpha),
+                    );
+                }
+            }
+        }
+    }
+
+    pub(crate) fn draw_straighten_overlay(&self, ui: &mut egui::Ui, image_rect: Rect) {
+        let painter = ui.painter();
+
+        // Level reference through the image center
+        let guide = Stroke::new(1.0, Color32::from_rgba_unmultiplied(255, 255, 255, 80));
+        painter.line_segment(
+            [
+                egui::pos2(image_rect.left(), image_rect.center().y),
+                egui::pos2(image_rect.right(), image_rect.center().y),
+            ],
+            guide,
+        );
+
+        if let Some((start, end)) = self.straighten_line {
+            painter.line_segment(
+                [start, end],
+                Stroke::new(2.0, Color32::from_rgb(255, 200, 0)),
+            );
+            painter.circle_filled(start, 3.0, Color32::from_rgb(255, 200, 0));
+            painter.circle_filled(end, 3.0, Color32::from_rgb(255, 200, 0));
+        }
+    }
+
     pub(crate) fn draw_crop_overlay(&self, ui: &mut egui::Ui, image_rect: Rect) {
         let painter = ui.painter();
 
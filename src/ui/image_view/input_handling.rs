@@ -1,14 +1,22 @@
 use crate::app::ImageViewerApp;
+use crate::touch::{self, Swipe};
 use egui::{self, Rect};
 use image::GenericImageView;
 
 impl ImageViewerApp {
     pub(crate) fn handle_image_input(&mut self, response: &egui::Response, ui: &mut egui::Ui) {
         // Handle touch gestures
-        self.handle_touch_gestures(response, ui);
+        let pinching = self.handle_touch_gestures(response, ui);
 
-        // Pan with drag (only when not in crop mode)
-        if !self.crop_mode && response.dragged() {
+        // Pan with drag (only when no drag tool is active); dragging out of
+        // the window carries the file to other apps. Touches never drag out,
+        // which would swallow swipes and the long-press context menu.
+        let drag_tool = self.crop_mode || self.straighten_mode || self.local_mask_tool.is_some();
+        let touching = self.settings.touch_mode || ui.input(|i| i.any_touches());
+        if !drag_tool && !touching && response.drag_started() {
+            self.begin_drag_out(None);
+        }
+        if !drag_tool && !pinching && response.dragged() {
             let delta = response.drag_delta();
             self.pan_offset += delta;
             self.target_pan = self.pan_offset;
@@ -37,11 +45,24 @@ impl ImageViewerApp {
             }
         }
 
-        // Double-click to toggle between 100% and fit
+        // Double-click or double-tap toggles between fit and 100% at the
+        // point under the pointer
         if response.double_clicked() {
             if (self.zoom - 1.0).abs() < 0.1 {
                 self.fit_to_window();
             } else {
+                if let Some(pos) = response.interact_pointer_pos() {
+                    let anchor = pos - response.rect.center();
+                    self.target_pan = touch::anchored_pan(
+                        self.pan_offset,
+                        anchor,
+                        1.0 / self.zoom,
+                        egui::Vec2::ZERO,
+                    );
+                    if !self.settings.smooth_zoom {
+                        self.pan_offset = self.target_pan;
+                    }
+                }
                 self.zoom_to(1.0);
             }
         }
@@ -51,6 +72,16 @@ impl ImageViewerApp {
             self.handle_crop_input(response);
         }
 
+        // Handle straighten line input
+        if self.straighten_mode {
+            self.handle_straighten_input(response);
+        }
+
+        // Handle gradient mask input
+        if self.local_mask_tool.is_some() {
+            self.handle_local_mask_input(response);
+        }
+
         // Right-click context menu
         response.context_menu(|ui| {
             if ui.button("Zoom 100%").clicked() {
@@ -71,8 +102,35 @@ impl ImageViewerApp {
                 ui.close_menu();
             }
             ui.separator();
+            if ui.button("Copy Adjustments").clicked() {
+                self.copy_adjustments();
+                ui.close_menu();
+            }
+            if ui
+                .add_enabled(
+                    self.copied_adjustments.is_some(),
+                    egui::Button::new("Paste Adjustments"),
+                )
+                .clicked()
+            {
+                self.paste_adjustments();
+                ui.close_menu();
+            }
+            ui.separator();
+            if ui.button("Copy Image").clicked() {
+                self.copy_image_to_clipboard();
+                ui.close_menu();
+            }
+            if ui.button("Copy File").clicked() {
+                self.copy_file_to_clipboard();
+                ui.close_menu();
+            }
             if ui.button("Copy Path").clicked() {
-                self.copy_to_clipboard();
+                self.copy_path_to_clipboard();
+                ui.close_menu();
+            }
+            if ui.button("Paste Image").clicked() {
+                self.paste_image_from_clipboard();
                 ui.close_menu();
             }
             if ui.button("Open in File Manager").clicked() {
@@ -85,8 +143,27 @@ impl ImageViewerApp {
                 ui.close_menu();
             }
             ui.separator();
-            if ui.button("Export Image").clicked() {
-                self.export_image();
+            if ui.button("Export Image...").clicked() {
+                self.open_export_dialog();
+                ui.close_menu();
+            }
+            if ui.button("Batch Export...").clicked() {
+                self.start_batch_export();
+                ui.close_menu();
+            }
+            if ui.button("Contact Sheet...").clicked() {
+                self.show_contact_sheet_dialog = true;
+                ui.close_menu();
+            }
+            if ui.button("Web Gallery...").clicked() {
+                self.show_web_gallery_dialog = true;
+                ui.close_menu();
+            }
+            ui.menu_button("Share", |ui| {
+                self.render_share_menu(ui, self.selected_or_current_paths())
+            });
+            if ui.button("Rename...").clicked() {
+                self.open_rename_dialog();
                 ui.close_menu();
             }
             ui.separator();
@@ -111,43 +188,60 @@ impl ImageViewerApp {
         }
     }
 
-    pub(crate) fn handle_touch_gestures(&mut self, response: &egui::Response, ui: &mut egui::Ui) {
-        let input = ui.input(|i| i.clone());
+    /// Pinch zoom, two-finger pan and one-finger swipes. Returns whether a
+    /// pinch is under way, which replaces the usual drag to pan.
+    pub(crate) fn handle_touch_gestures(
+        &mut self,
+        response: &egui::Response,
+        ui: &mut egui::Ui,
+    ) -> bool {
+        let (multi_touch, time) = ui.input(|i| (i.multi_touch(), i.time));
 
-        // Pinch-to-zoom gesture
-        if let Some(multi_touch) = input.multi_touch() {
-            if multi_touch.zoom_delta > 1.0 {
-                // Calculate zoom factor from pinch
-                let zoom_factor = multi_touch.zoom_delta;
-                let new_zoom = (self.target_zoom * zoom_factor).clamp(0.1, 20.0);
-
-                // Zoom towards the center of the touch points
-                let touch_center = multi_touch.start_pos;
-                let touch_rel = touch_center - response.rect.center() - self.pan_offset;
-                let zoom_change = new_zoom / self.target_zoom;
-                self.target_pan = self.pan_offset - touch_rel * (zoom_change - 1.0);
-
-                self.target_zoom = new_zoom;
-
-                if !self.settings.smooth_zoom {
-                    self.zoom = self.target_zoom;
-                    self.pan_offset = self.target_pan;
-                }
-            }
+        // Pinch zooms around the fingers' centroid, and moving them pans
+        if let Some(multi_touch) = multi_touch {
+            self.touch_swipe = None;
+            let new_zoom = (self.zoom * multi_touch.zoom_delta).clamp(0.1, 20.0);
+            let pan = touch::anchored_pan(
+                self.pan_offset,
+                multi_touch.center_pos - response.rect.center(),
+                new_zoom / self.zoom,
+                multi_touch.translation_delta,
+            );
+            // Follows the fingers directly, without smoothing
+            self.zoom = new_zoom;
+            self.target_zoom = new_zoom;
+            self.pan_offset = pan;
+            self.target_pan = pan;
+            return true;
         }
 
-        // Swipe navigation (two-finger swipe)
-        if input.pointer.secondary_down() && input.pointer.primary_down() {
-            let delta = input.pointer.delta();
-            if delta.x.abs() > 50.0 {
-                // Threshold for swipe
-                if delta.x > 0.0 {
-                    self.previous_image();
-                } else {
-                    self.next_image();
+        // A quick one-finger swipe pages while the whole image is in view.
+        // Touch mode treats mouse drags as fingers, for screens that send them.
+        let touching = self.settings.touch_mode || ui.input(|i| i.any_touches());
+        let drag_tool = self.crop_mode || self.straighten_mode || self.local_mask_tool.is_some();
+        if touching && !drag_tool && response.drag_started() {
+            self.touch_swipe = Some((egui::Vec2::ZERO, time));
+        }
+        if let Some((travel, _)) = &mut self.touch_swipe {
+            *travel += response.drag_delta();
+        }
+        if response.drag_stopped() {
+            if let Some((travel, started)) = self.touch_swipe.take() {
+                if self.is_fit_to_window() {
+                    match touch::classify_swipe(travel, time - started) {
+                        Some(Swipe::Previous) => self.previous_image(),
+                        Some(Swipe::Next) => self.next_image(),
+                        None => {}
+                    }
+                    // The image followed the finger; settle it back in place
+                    self.target_pan = egui::Vec2::ZERO;
+                    if !self.settings.smooth_zoom {
+                        self.pan_offset = egui::Vec2::ZERO;
+                    }
                 }
             }
         }
+        false
     }
 
     pub(crate) fn pick_color_at(&mut self, pos: egui::Pos2, view_rect: Rect) {
@@ -194,4 +288,90 @@ impl ImageViewerApp {
             self.crop_start_pos = None;
         }
     }
+
+    pub(crate) fn handle_straighten_input(&mut self, response: &egui::Response) {
+        if let Some(pos) = response.interact_pointer_pos() {
+            if response.drag_started() {
+                self.straighten_line = Some((pos, pos));
+            } else if response.dragged() {
+                if let Some((start, _)) = self.straighten_line {
+                    self.straighten_line = Some((start, pos));
+                }
+            }
+        }
+
+        if response.drag_stopped() {
+            if let Some((start, end)) = self.straighten_line.take() {
+                self.apply_straighten_line(start, end);
+            }
+        }
+
+        // Handle escape key to cancel straightening
+        if response.hovered() && response.ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.straighten_mode = false;
+            self.straighten_line = None;
+        }
+    }
+
+    pub(crate) fn handle_local_mask_input(&mut self, response: &egui::Response) {
+        let Some(display_size) = self.image_display_size() else {
+            return;
+        };
+        let image_rect = Rect::from_center_size(
+            response.rect.center() + self.pan_offset,
+            display_size * self.zoom,
+        );
+        let photo_rect = self.photo_screen_rect(image_rect);
+
+        if let Some(pos) = response.interact_pointer_pos() {
+            let relative = [
+                (pos.x - photo_rect.left()) / photo_rect.width().max(1.0),
+                (pos.y - photo_rect.top()) / photo_rect.height().max(1.0),
+            ];
+            let pressure = response.ctx.input(crate::brush::pen_pressure);
+            if response.drag_started() {
+                self.pen_pressure = pressure.unwrap_or(1.0);
+                self.begin_local_mask(relative);
+            } else if response.dragged() {
+                if let Some(pressure) = pressure {
+                    self.pen_pressure = pressure;
+                }
+                self.update_local_mask(relative);
+            }
+        }
+
+        if response.drag_stopped() {
+            self.finish_local_mask();
+        }
+
+        // Handle escape key to disarm the tool
+        if response.hovered() && response.ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.local_mask_tool = None;
+        }
+    }
+
+    /// Screen rect of the photo itself inside the image drawn at `image_rect`,
+    /// which includes any frame
+    pub(crate) fn photo_screen_rect(&self, image_rect: Rect) -> Rect {
+        match &self.current_texture {
+            Some(texture) => self.framed_photo_rect(image_rect, texture.size_vec2()),
+            None => image_rect,
+        }
+    }
+
+    /// Pixel of `current_image` at screen position `pos`. None off the photo,
+    /// and when straightening moves pixels away from a plain grid.
+    pub(crate) fn pixel_under(&self, pos: egui::Pos2, image_rect: Rect) -> Option<(u32, u32)> {
+        let image = self.current_image.as_ref()?;
+        if self.adjustments.straighten_angle != 0.0 {
+            return None;
+        }
+        let photo = self.photo_screen_rect(image_rect);
+        if !photo.contains(pos) {
+            return None;
+        }
+        let x = ((pos.x - photo.min.x) / photo.width() * image.width() as f32) as u32;
+        let y = ((pos.y - photo.min.y) / photo.height() * image.height() as f32) as u32;
+        (x < image.width() && y < image.height()).then_some((x, y))
+    }
 }
@@ -2,4 +2,10 @@
 pub mod compare_view;
 pub mod drawing_utils;
 pub mod input_handling;
+pub mod pasted_banner;
+pub mod reference_overlay;
+pub mod scroll_view;
 pub mod single_view;
+pub mod split_view;
+pub mod tether_overlay;
+pub mod video_controls;
@@ -0,0 +1,58 @@
+use crate::app::ImageViewerApp;
+use egui::{self, Color32, CornerRadius, Pos2, Rect, Sense, Vec2};
+
+impl ImageViewerApp {
+    /// Note along the top of the view that a pasted image isn't on disk yet,
+    /// with a button to save it
+    pub(super) fn draw_pasted_banner(&mut self, ui: &mut egui::Ui, view_rect: Rect) {
+        if !self
+            .get_current_path()
+            .is_some_and(|path| self.is_pasted_image(&path))
+        {
+            return;
+        }
+
+        let bar_rect = Rect::from_center_size(
+            Pos2::new(view_rect.center().x, view_rect.top() + 12.0 + 16.0),
+            Vec2::new(300.0, 32.0),
+        );
+        let painter = ui.painter();
+        painter.rect_filled(
+            bar_rect,
+            CornerRadius::same(6),
+            Color32::from_rgba_unmultiplied(0, 0, 0, 180),
+        );
+        painter.text(
+            Pos2::new(bar_rect.left() + 12.0, bar_rect.center().y),
+            egui::Align2::LEFT_CENTER,
+            "Pasted image, not saved",
+            egui::FontId::proportional(12.0),
+            Color32::from_rgb(200, 200, 200),
+        );
+
+        let button_rect = Rect::from_min_max(
+            Pos2::new(bar_rect.right() - 100.0, bar_rect.top() + 4.0),
+            bar_rect.right_bottom() - Vec2::splat(4.0),
+        );
+        let button = ui
+            .interact(button_rect, ui.id().with("save_pasted"), Sense::click())
+            .on_hover_text("Save As... (Ctrl+Shift+S)");
+        let fill = if button.hovered() {
+            Color32::from_rgb(100, 160, 255)
+        } else {
+            Color32::from_rgb(70, 120, 210)
+        };
+        painter.rect_filled(button_rect, CornerRadius::same(4), fill);
+        painter.text(
+            button_rect.center(),
+            egui::Align2::CENTER_CENTER,
+            "Save As...",
+            egui::FontId::proportional(12.0),
+            Color32::WHITE,
+        );
+
+        if button.clicked() {
+            self.save_pasted_image();
+        }
+    }
+}
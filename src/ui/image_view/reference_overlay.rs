@@ -0,0 +1,36 @@
+use crate::app::reference_overlay::OverlayBlend;
+use crate::app::ImageViewerApp;
+use egui::{self, Color32, Rect};
+
+impl ImageViewerApp {
+    /// Paint the reference over the current image so the image shows through
+    /// at the overlay's opacity, or the difference of the two
+    pub(crate) fn draw_reference_overlay(&mut self, ui: &mut egui::Ui, image_rect: Rect) {
+        let Some(overlay) = &self.reference_overlay else {
+            return;
+        };
+        let (opacity, blend) = (overlay.opacity, overlay.blend);
+        let Some(reference) = self.overlay_reference_texture() else {
+            return;
+        };
+        let uv = Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+        let difference = match blend {
+            OverlayBlend::Normal => None,
+            OverlayBlend::Difference => self.overlay_difference_texture(),
+        };
+        let painter = ui.painter();
+        match difference {
+            Some(difference) => {
+                painter.image(reference, image_rect, uv, Color32::WHITE);
+                let tint = Color32::WHITE.gamma_multiply(opacity);
+                painter.image(difference, image_rect, uv, tint);
+            }
+            // The reference over the image at the remaining opacity looks the
+            // same as the image over the reference
+            None => {
+                let tint = Color32::WHITE.gamma_multiply(1.0 - opacity);
+                painter.image(reference, image_rect, uv, tint);
+            }
+        }
+    }
+}
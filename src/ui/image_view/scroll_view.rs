@@ -0,0 +1,77 @@
+use crate::app::scroll_strip::{strip_layout, strip_range, STRIP_PRELOAD_SCREENS};
+use crate::app::{ImageViewerApp, ViewMode};
+use egui::{self, Color32, Pos2, Rect, Vec2};
+
+impl ImageViewerApp {
+    /// Every image in the filtered list stacked top to bottom in one strip
+    pub(crate) fn render_scroll_view(&mut self, ui: &mut egui::Ui) {
+        let available = ui.available_size();
+        self.available_view_size = available;
+        if self.filtered_list.is_empty() {
+            return;
+        }
+        let width = available.x;
+        let layout = strip_layout(&self.known_strip_sizes(width), width);
+        let height = layout.last().map_or(0.0, |(top, size)| top + size.y);
+
+        // Jump to the current image when keys or the filmstrip change it
+        let mut area = egui::ScrollArea::vertical()
+            .id_salt("scroll_strip")
+            .auto_shrink(false);
+        let jumped = self.strip_index != Some(self.current_index);
+        if jumped {
+            if let Some((top, _)) = layout.get(self.current_index) {
+                area = area.vertical_scroll_offset(*top);
+            }
+            self.strip_index = Some(self.current_index);
+        }
+
+        let mut open = None;
+        let output = area.show_viewport(ui, |ui, viewport| {
+            ui.set_height(height);
+            let origin = ui.max_rect().min;
+            let band = |screens: f32| {
+                let margin = viewport.height() * screens;
+                strip_range(&layout, viewport.min.y - margin, viewport.max.y + margin)
+            };
+            self.update_strip_textures(
+                band(STRIP_PRELOAD_SCREENS),
+                band(2.0 * STRIP_PRELOAD_SCREENS),
+            );
+
+            for index in band(0.0) {
+                let (top, size) = layout[index];
+                let rect =
+                    Rect::from_min_size(origin + Vec2::new((width - size.x) / 2.0, top), size);
+                let uv = Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0));
+                let placeholder = self
+                    .path_at(index)
+                    .and_then(|path| self.thumbnail_textures.get(&path).map(|t| t.id()));
+                match self.strip_texture(index).map(|t| t.id()).or(placeholder) {
+                    Some(texture) => ui.painter().image(texture, rect, uv, Color32::WHITE),
+                    None => ui.painter().rect_filled(rect, 0.0, Color32::from_gray(40)),
+                }
+                let response = ui.interact(rect, ui.id().with(index), egui::Sense::click());
+                if response.double_clicked() {
+                    open = Some(index);
+                }
+            }
+        });
+
+        // Double-click shows the image on its own
+        if let Some(index) = open {
+            self.view_mode = ViewMode::Single;
+            self.go_to_index(index);
+            return;
+        }
+        // The image across the middle of the view becomes the current one
+        if !jumped {
+            let middle = output.state.offset.y + output.inner_rect.height() / 2.0;
+            let index = strip_range(&layout, middle, middle).start;
+            if index != self.current_index && index < layout.len() {
+                self.strip_index = Some(index);
+                self.go_to_index(index);
+            }
+        }
+    }
+}
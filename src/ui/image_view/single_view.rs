@@ -4,12 +4,19 @@ use egui::{self, Color32, CornerRadius, Rect, Vec2};
 
 impl ImageViewerApp {
     pub fn render_main_view(&mut self, ctx: &egui::Context) {
+        self.hovered_pixel = None;
+        // Laid over the desktop, the window is see-through around the image
+        let fill = match self.desktop_overlay_opacity() {
+            Some(_) => Color32::TRANSPARENT,
+            None => self.settings.background_color.to_color(),
+        };
         egui::CentralPanel::default()
-            .frame(egui::Frame::NONE.fill(self.settings.background_color.to_color()))
+            .frame(egui::Frame::NONE.fill(fill))
             .show(ctx, |ui| {
                 match self.view_mode {
                     crate::app::ViewMode::Single => self.render_single_view(ui, ctx),
                     crate::app::ViewMode::Compare => self.render_compare_view(ctx),
+                    crate::app::ViewMode::Scroll => self.render_scroll_view(ui),
                 }
             });
     }
@@ -24,45 +31,113 @@ impl ImageViewerApp {
         self.handle_image_input(&response, ui);
 
         // Draw checkered background if selected
-        if self.settings.background_color == BackgroundColor::Checkered {
+        if self.settings.background_color == BackgroundColor::Checkered
+            && self.desktop_overlay_opacity().is_none()
+        {
             self.draw_checkered_background(ui, rect);
         }
 
+        let animation_texture = self
+            .current_animation_texture(ui.ctx())
+            .or_else(|| self.current_video_texture(ui.ctx()));
+
         // Draw image
         if let Some(tex) = &self.current_texture {
             let tex_size = tex.size_vec2();
-            // Keep the on-screen image size stable while showing a preview: if a smaller
-            // preview texture is used, upscale it to match the original image size so the
-            // image doesn't appear to shrink while dragging adjustments.
-            let display_size = if self.showing_preview {
-                if let Some(orig) = &self.current_image {
-                    egui::Vec2::new(orig.width() as f32, orig.height() as f32) * self.zoom
-                } else {
-                    tex_size * self.zoom
+            // Keep the on-screen image size stable while showing a preview or an
+            // overview: a smaller texture is upscaled to the original image size so
+            // the image doesn't appear to shrink while dragging adjustments.
+            let display_size = self.image_display_size().unwrap_or(tex_size) * self.zoom;
+            let tiled = self.current_tiles().is_some();
+
+            let image_rect = Rect::from_center_size(rect.center() + self.pan_offset, display_size);
+
+            if let Some(opacity) = self.desktop_overlay_opacity() {
+                ui.painter().image(
+                    tex.id(),
+                    image_rect,
+                    Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                    Color32::WHITE.gamma_multiply(opacity),
+                );
+            } else if let Some(frame_tex) = &animation_texture {
+                ui.painter().image(
+                    frame_tex.id(),
+                    image_rect,
+                    Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                    Color32::WHITE,
+                );
+            } else if tiled {
+                // Too big for one texture: draw the tiles in view over the overview
+                ui.painter().image(
+                    tex.id(),
+                    image_rect,
+                    Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                    Color32::WHITE,
+                );
+                let options = self.texture_options_with(self.pixel_inspection());
+                if let Some((_, tiles)) = &mut self.tiled_image {
+                    if tiles.paint(ui.ctx(), ui.painter(), image_rect, rect, options) {
+                        ui.ctx().request_repaint();
+                    }
                 }
+            } else if self.gpu_display_max_dim.is_some()
+                && self.current_image.is_some()
+                && crate::gpu::display::can_preview(&self.adjustments)
+                && !self.soft_proof_enabled
+                && image_rect.intersects(rect)
+            {
+                self.update_display_proxy();
+                ui.painter()
+                    .add(eframe::egui_wgpu::Callback::new_paint_callback(
+                        image_rect,
+                        crate::gpu::display::ImageDisplayCallback {
+                            upload: self.gpu_display_upload.take(),
+                            adjustments: self.adjustments.clone(),
+                            hdr: self.settings.hdr_display.clone(),
+                            nearest: self
+                                .texture_options_with(self.pixel_inspection())
+                                .magnification
+                                == egui::TextureFilter::Nearest,
+                            mipmaps: self.settings.texture_mipmaps,
+                        },
+                    ));
             } else {
-                tex_size * self.zoom
-            };
+                ui.painter().image(
+                    tex.id(),
+                    image_rect,
+                    Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                    Color32::WHITE,
+                );
+                self.sync_texture_filter();
+            }
 
-            let image_rect = Rect::from_center_size(rect.center() + self.pan_offset, display_size);
+            // Pixel grid and the value under the cursor at extreme zoom
+            if self.pixel_inspection() && animation_texture.is_none() {
+                self.hovered_pixel = response
+                    .hover_pos()
+                    .and_then(|pos| self.pixel_under(pos, image_rect));
+                self.draw_pixel_grid(ui, rect, image_rect);
+            }
 
-            ui.painter().image(
-                tex.id(),
-                image_rect,
-                Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
-                Color32::WHITE,
-            );
+            // Before/after wipe over the adjusted image
+            if animation_texture.is_none() {
+                self.draw_split_view(ui, rect, image_rect, tex_size);
+            }
+            self.draw_reference_overlay(ui, image_rect);
 
             // Draw overlays
             self.draw_overlays(ui, image_rect);
 
             // Show EXIF overlay on top of image if enabled (overlay toggle controls only overlay)
             if self.settings.show_exif_overlay {
-                if let Some(ref exif) = self.current_exif {
-                    self.draw_exif_overlay(ui, image_rect, exif);
+                if let (Some(exif), Some(path)) = (&self.current_exif, self.get_current_path()) {
+                    self.draw_exif_overlay(ui, image_rect, &path, exif);
                 }
             }
 
+            self.draw_video_controls(ui, rect);
+            self.draw_pasted_banner(ui, rect);
+
             // Show "Loading full resolution..." indicator for previews
             if self.showing_preview && self.is_loading {
                 let indicator_rect = Rect::from_min_size(
@@ -160,6 +235,8 @@ impl ImageViewerApp {
             );
         }
 
+        self.draw_tether_overlay(ui, rect);
+
         // Draw loupe if enabled
         if self.settings.loupe_enabled {
             self.draw_loupe(ui);
@@ -173,64 +250,6 @@ impl ImageViewerApp {
         // Note: EXIF overlay shown inline above when drawing the image so it has access to image_rect
     }
 
-    fn draw_exif_overlay(
-        &self,
-        ui: &mut egui::Ui,
-        image_rect: Rect,
-        exif: &crate::exif_data::ExifInfo,
-    ) {
-        // Small semi-transparent overlay in bottom-left showing camera/date/settings
-        let overlay_size = egui::Vec2::new(320.0, 44.0);
-        let pos = image_rect.left_bottom() + egui::Vec2::new(12.0, -12.0 - overlay_size.y);
-        let overlay_rect = Rect::from_min_size(pos, overlay_size);
-
-        ui.painter().rect_filled(
-            overlay_rect,
-            CornerRadius::same(6),
-            Color32::from_rgba_unmultiplied(0, 0, 0, 180),
-        );
-
-        if !exif.has_data() {
-            ui.painter().text(
-                overlay_rect.center(),
-                egui::Align2::CENTER_CENTER,
-                "No EXIF data",
-                egui::FontId::proportional(13.0),
-                Color32::WHITE,
-            );
-        } else {
-            let camera = exif
-                .camera_model
-                .clone()
-                .unwrap_or_else(|| "Unknown Camera".to_string());
-            let date = exif
-                .date_taken
-                .clone()
-                .unwrap_or_else(|| "Unknown Date".to_string());
-            let settings = format!(
-                "{} • {} • ISO {}",
-                exif.focal_length_formatted(),
-                exif.aperture_formatted(),
-                exif.iso.clone().unwrap_or_default()
-            );
-
-            ui.painter().text(
-                overlay_rect.left_top() + egui::Vec2::new(8.0, 6.0),
-                egui::Align2::LEFT_TOP,
-                camera,
-                egui::FontId::proportional(13.0),
-                Color32::WHITE,
-            );
-            ui.painter().text(
-                overlay_rect.left_bottom() + egui::Vec2::new(8.0, -6.0),
-                egui::Align2::LEFT_BOTTOM,
-                format!("{} — {}", settings, date),
-                egui::FontId::proportional(11.0),
-                Color32::from_rgb(200, 200, 200),
-            );
-        }
-    }
-
     /// Return the pixel size of a texture given its `TextureId` by inspecting
     /// the current texture and cached thumbnails. Falls back to a sensible
     /// default if the texture is unknown.
@@ -240,6 +259,11 @@ impl ImageViewerApp {
                 return t.size_vec2();
             }
         }
+        for tex in self.compare_textures.values() {
+            if tex.id() == id {
+                return tex.size_vec2();
+            }
+        }
         for (_path, tex) in self.thumbnail_textures.iter() {
             if tex.id() == id {
                 return tex.size_vec2();
@@ -0,0 +1,236 @@
+use crate::app::{ImageViewerApp, LoaderMessage, SplitView};
+use egui::{self, Color32, CornerRadius, Rect, Stroke, Vec2};
+use std::sync::Arc;
+
+impl ImageViewerApp {
+    /// Turn the before/after wipe on, or off again
+    pub fn toggle_split_view(&mut self) {
+        let split = match self.split_view {
+            SplitView::Off => SplitView::Vertical,
+            _ => SplitView::Off,
+        };
+        self.set_split_view(split);
+    }
+
+    /// Swap between a side-by-side and a top-and-bottom wipe
+    pub fn flip_split_direction(&mut self) {
+        let split = match self.split_view {
+            SplitView::Vertical => SplitView::Horizontal,
+            _ => SplitView::Vertical,
+        };
+        self.set_split_view(split);
+    }
+
+    fn set_split_view(&mut self, split: SplitView) {
+        self.split_view = split;
+        if split != SplitView::Off && self.show_original {
+            // The wipe shows the original itself, so keep the adjusted image underneath
+            self.show_original = false;
+            self.refresh_adjustments();
+        }
+    }
+
+    /// Texture of the current image without adjustments, built in the background
+    fn original_texture_id(&mut self) -> Option<egui::TextureId> {
+        let path = self.get_current_path()?;
+        let image = self.current_image.as_ref()?;
+        let size = [image.width(), image.height()];
+        if let Some((texture_path, texture_size, texture)) = &self.original_texture {
+            if *texture_path == path && *texture_size == size {
+                return Some(texture.id());
+            }
+        }
+        if self.original_texture_pending == Some((path.clone(), size)) {
+            return None;
+        }
+
+        let image = Arc::clone(image);
+        let transform = self.display_transform(&path);
+        let hdr = self.settings.hdr_display.clone();
+        let texture_options = self.texture_options();
+        let ctx = self.ctx.clone()?;
+        self.original_texture_pending = Some((path.clone(), size));
+        self.spawn_loader(move |_| {
+            let image = if crate::image_loader::is_float(&image) {
+                crate::image_loader::display_referred(&image, &hdr).into_owned()
+            } else {
+                Arc::unwrap_or_clone(image)
+            };
+            let max_side = ctx.input(|i| i.max_texture_side) as u32;
+            let image = if image.width().max(image.height()) > max_side {
+                crate::image_loader::generate_thumbnail(&image, max_side)
+            } else {
+                image
+            };
+            let image = match &transform {
+                Some(transform) => crate::color_management::apply_transform(transform, &image),
+                None => image,
+            };
+            let rgba = image.to_rgba8();
+            let texture = ctx.load_texture(
+                format!("original_{}", path.display()),
+                egui::ColorImage::from_rgba_unmultiplied(
+                    [image.width() as usize, image.height() as usize],
+                    rgba.as_flat_samples().as_slice(),
+                ),
+                texture_options,
+            );
+            Some(LoaderMessage::OriginalTextureLoaded(path, size, texture))
+        });
+        None
+    }
+
+    /// Screen rect of the photo inside the framed texture drawn at `image_rect`
+    pub(crate) fn framed_photo_rect(&self, image_rect: Rect, texture_size: Vec2) -> Rect {
+        let Some(image) = &self.current_image else {
+            return image_rect;
+        };
+        if texture_size.x <= 0.0 || texture_size.y <= 0.0 {
+            return image_rect;
+        }
+        let [x, y, width, height] = crate::image_loader::framed_photo_area(
+            texture_size.x,
+            texture_size.y,
+            image.width() as f32 / image.height().max(1) as f32,
+            &self.adjustments,
+        );
+        let scale = image_rect.width() / texture_size.x;
+        Rect::from_min_size(
+            image_rect.min + Vec2::new(x, y) * scale,
+            Vec2::new(width, height) * scale,
+        )
+    }
+
+    /// Paint the original over the "before" side of the view with a draggable divider
+    pub(crate) fn draw_split_view(
+        &mut self,
+        ui: &mut egui::Ui,
+        view_rect: Rect,
+        image_rect: Rect,
+        texture_size: Vec2,
+    ) {
+        let vertical = match self.split_view {
+            SplitView::Off => return,
+            SplitView::Vertical => true,
+            SplitView::Horizontal => false,
+        };
+
+        // Divider position on screen
+        let split = if vertical {
+            view_rect.left() + view_rect.width() * self.split_position
+        } else {
+            view_rect.top() + view_rect.height() * self.split_position
+        };
+        let before_rect = if vertical {
+            Rect::from_min_max(view_rect.min, egui::pos2(split, view_rect.bottom()))
+        } else {
+            Rect::from_min_max(view_rect.min, egui::pos2(view_rect.right(), split))
+        };
+
+        if let Some(texture) = self.original_texture_id() {
+            // The adjusted texture includes the frame border; the original doesn't
+            let photo_rect = self.framed_photo_rect(image_rect, texture_size);
+            ui.painter().with_clip_rect(before_rect).image(
+                texture,
+                photo_rect,
+                Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                Color32::WHITE,
+            );
+        } else {
+            ui.ctx().request_repaint();
+        }
+
+        // Divider line and handle
+        let (line, handle_rect) = if vertical {
+            (
+                [
+                    egui::pos2(split, view_rect.top()),
+                    egui::pos2(split, view_rect.bottom()),
+                ],
+                Rect::from_center_size(
+                    egui::pos2(split, view_rect.center().y),
+                    Vec2::new(12.0, view_rect.height()),
+                ),
+            )
+        } else {
+            (
+                [
+                    egui::pos2(view_rect.left(), split),
+                    egui::pos2(view_rect.right(), split),
+                ],
+                Rect::from_center_size(
+                    egui::pos2(view_rect.center().x, split),
+                    Vec2::new(view_rect.width(), 12.0),
+                ),
+            )
+        };
+        let response = ui.interact(
+            handle_rect,
+            ui.id().with("split_divider"),
+            egui::Sense::drag(),
+        );
+        if response.dragged() {
+            let delta = response.drag_delta();
+            let moved = if vertical {
+                delta.x / view_rect.width()
+            } else {
+                delta.y / view_rect.height()
+            };
+            self.split_position = (self.split_position + moved).clamp(0.0, 1.0);
+        }
+        if response.hovered() || response.dragged() {
+            ui.ctx().set_cursor_icon(if vertical {
+                egui::CursorIcon::ResizeHorizontal
+            } else {
+                egui::CursorIcon::ResizeVertical
+            });
+        }
+
+        let painter = ui.painter();
+        painter.line_segment(line, Stroke::new(2.0, Color32::WHITE));
+        let knob = egui::pos2(
+            if vertical {
+                split
+            } else {
+                view_rect.center().x
+            },
+            if vertical {
+                view_rect.center().y
+            } else {
+                split
+            },
+        );
+        painter.circle_filled(knob, 9.0, Color32::WHITE);
+        painter.text(
+            knob,
+            egui::Align2::CENTER_CENTER,
+            if vertical { "↔" } else { "↕" },
+            egui::FontId::proportional(12.0),
+            Color32::BLACK,
+        );
+
+        // Side labels
+        let after_corner = if vertical {
+            egui::pos2(split + 10.0, view_rect.top() + 10.0)
+        } else {
+            egui::pos2(view_rect.left() + 10.0, split + 10.0)
+        };
+        for (text, corner) in [
+            ("Before", view_rect.left_top() + Vec2::new(10.0, 10.0)),
+            ("After", after_corner),
+        ] {
+            let galley = painter.layout_no_wrap(
+                text.to_string(),
+                egui::FontId::proportional(12.0),
+                Color32::WHITE,
+            );
+            let label_rect = Rect::from_min_size(corner, galley.size() + Vec2::new(12.0, 6.0));
+            painter.rect_filled(
+                label_rect,
+                CornerRadius::same(4),
+                Color32::from_rgba_unmultiplied(0, 0, 0, 160),
+            );
+            painter.galley(label_rect.min + Vec2::new(6.0, 3.0), galley, Color32::WHITE);
+        }
+    }
+}
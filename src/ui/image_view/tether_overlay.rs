@@ -0,0 +1,60 @@
+use crate::app::ImageViewerApp;
+use crate::ui::histogram::paint_histogram;
+use egui::{self, Color32, CornerRadius, Pos2, Rect, Vec2};
+
+const HISTOGRAM_SIZE: Vec2 = Vec2::new(360.0, 160.0);
+
+impl ImageViewerApp {
+    /// Tether status and a large histogram in the bottom right of the view
+    pub(super) fn draw_tether_overlay(&self, ui: &mut egui::Ui, view_rect: Rect) {
+        let Some(tether) = self.tether.as_ref().filter(|_| self.is_tethered()) else {
+            return;
+        };
+        let painter = ui.painter();
+        let margin = 16.0;
+
+        let label = format!(
+            "● Tethered to {}  ·  {} shots",
+            tether
+                .folder
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            tether.arrived
+        );
+        let mut bottom = view_rect.bottom() - margin;
+        if self.settings.tether.histogram {
+            let rect = Rect::from_min_size(
+                Pos2::new(
+                    view_rect.right() - margin - HISTOGRAM_SIZE.x,
+                    bottom - HISTOGRAM_SIZE.y,
+                ),
+                HISTOGRAM_SIZE,
+            );
+            painter.rect_filled(
+                rect,
+                CornerRadius::same(6),
+                Color32::from_rgba_unmultiplied(0, 0, 0, 180),
+            );
+            if let Some(histogram) = &self.histogram_data {
+                let settings = &self.settings.histogram;
+                paint_histogram(
+                    painter,
+                    rect.shrink(6.0),
+                    histogram,
+                    settings.luminance_only,
+                    settings.log_scale,
+                );
+            }
+            bottom = rect.top() - 6.0;
+        }
+
+        painter.text(
+            Pos2::new(view_rect.right() - margin, bottom),
+            egui::Align2::RIGHT_BOTTOM,
+            label,
+            egui::FontId::proportional(14.0),
+            Color32::from_rgb(255, 110, 90),
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use crate::app::ImageViewerApp;
+use egui::{self, Color32, CornerRadius, Pos2, Rect, Sense, Stroke, Vec2};
+use std::time::Duration;
+
+fn format_time(time: Duration) -> String {
+    let secs = time.as_secs();
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+impl ImageViewerApp {
+    /// Play/pause button, scrub bar and time along the bottom of the view
+    pub(super) fn draw_video_controls(&mut self, ui: &mut egui::Ui, view_rect: Rect) {
+        let Some(video) = &self.video else {
+            return;
+        };
+        let (playing, position, duration) = (video.playing, video.position, video.info.duration);
+
+        let size = Vec2::new((view_rect.width() - 24.0).min(640.0), 36.0);
+        let bar_rect = Rect::from_center_size(
+            Pos2::new(
+                view_rect.center().x,
+                view_rect.bottom() - 12.0 - size.y / 2.0,
+            ),
+            size,
+        );
+        let painter = ui.painter();
+        painter.rect_filled(
+            bar_rect,
+            CornerRadius::same(6),
+            Color32::from_rgba_unmultiplied(0, 0, 0, 180),
+        );
+
+        // Play/pause
+        let button_rect = Rect::from_min_size(bar_rect.min, Vec2::splat(size.y));
+        let button = ui.interact(button_rect, ui.id().with("video_play"), Sense::click());
+        let color = if button.hovered() {
+            Color32::WHITE
+        } else {
+            Color32::from_rgb(200, 200, 200)
+        };
+        let c = button_rect.center();
+        if playing {
+            for x in [-4.0, 4.0] {
+                painter.rect_filled(
+                    Rect::from_center_size(c + Vec2::new(x, 0.0), Vec2::new(4.0, 14.0)),
+                    CornerRadius::same(1),
+                    color,
+                );
+            }
+        } else {
+            painter.add(egui::Shape::convex_polygon(
+                vec![
+                    c + Vec2::new(-5.0, -8.0),
+                    c + Vec2::new(7.0, 0.0),
+                    c + Vec2::new(-5.0, 8.0),
+                ],
+                color,
+                Stroke::NONE,
+            ));
+        }
+        let button = button.on_hover_text(if playing {
+            "Pause video (Space)"
+        } else {
+            "Play video (Space)"
+        });
+
+        // Time
+        let time_width = 90.0;
+        painter.text(
+            Pos2::new(bar_rect.right() - 10.0, bar_rect.center().y),
+            egui::Align2::RIGHT_CENTER,
+            format!("{} / {}", format_time(position), format_time(duration)),
+            egui::FontId::monospace(11.0),
+            Color32::from_rgb(200, 200, 200),
+        );
+
+        // Scrub bar; seeks when released so ffmpeg isn't restarted on every drag step
+        let track_rect = Rect::from_min_max(
+            Pos2::new(button_rect.right() + 4.0, bar_rect.top()),
+            Pos2::new(bar_rect.right() - time_width, bar_rect.bottom()),
+        );
+        let scrub = ui.interact(
+            track_rect,
+            ui.id().with("video_scrub"),
+            Sense::click_and_drag(),
+        );
+        let pointer_fraction = scrub
+            .interact_pointer_pos()
+            .map(|pos| ((pos.x - track_rect.left()) / track_rect.width()).clamp(0.0, 1.0));
+        let fraction = match pointer_fraction {
+            Some(f) if scrub.dragged() => f,
+            _ if duration.is_zero() => 0.0,
+            _ => (position.as_secs_f32() / duration.as_secs_f32()).min(1.0),
+        };
+
+        let track = Rect::from_center_size(track_rect.center(), Vec2::new(track_rect.width(), 4.0));
+        painter.rect_filled(track, CornerRadius::same(2), Color32::from_gray(90));
+        let knob_x = track.left() + track.width() * fraction;
+        painter.rect_filled(
+            Rect::from_min_max(track.min, Pos2::new(knob_x, track.bottom())),
+            CornerRadius::same(2),
+            Color32::from_rgb(100, 160, 255),
+        );
+        let knob_radius = if scrub.hovered() || scrub.dragged() {
+            6.0
+        } else {
+            4.5
+        };
+        painter.circle_filled(
+            Pos2::new(knob_x, track.center().y),
+            knob_radius,
+            Color32::WHITE,
+        );
+
+        if button.clicked() {
+            self.toggle_video_playback();
+        }
+        if scrub.clicked() || scrub.drag_stopped() {
+            if let Some(f) = pointer_fraction {
+                self.seek_video(duration.mul_f32(f));
+            }
+        }
+    }
+}
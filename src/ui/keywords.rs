@@ -0,0 +1,165 @@
+use crate::app::ImageViewerApp;
+use crate::ui::common::{self, LR_BG_INPUT, LR_TEXT_LABEL, LR_TEXT_PRIMARY, LR_TEXT_SECONDARY};
+use egui::{self, Color32, CornerRadius, RichText, Stroke};
+
+const MAX_SUGGESTIONS: usize = 8;
+const FILTER_ACTIVE: Color32 = Color32::from_rgb(80, 150, 255);
+
+fn keyword_input_id() -> egui::Id {
+    egui::Id::new("keyword_input")
+}
+
+pub fn render_keywords_panel(app: &mut ImageViewerApp, ui: &mut egui::Ui) {
+    common::lr_collapsible_panel(ui, "Keywords", true, |ui| {
+        // Active filter, which may be for a keyword the current image doesn't carry
+        if let Some(active) = app.keyword_filter.clone() {
+            ui.horizontal(|ui| {
+                ui.label(
+                    RichText::new(format!("Filtered by \"{}\"", active))
+                        .size(10.0)
+                        .color(FILTER_ACTIVE),
+                );
+                if ui
+                    .add(egui::Button::new("✕").small())
+                    .on_hover_text("Clear keyword filter")
+                    .clicked()
+                {
+                    app.set_keyword_filter(None);
+                }
+            });
+            ui.add_space(6.0);
+        }
+
+        let Some(path) = app.get_current_path() else {
+            ui.label(
+                RichText::new("No image")
+                    .size(10.0)
+                    .color(LR_TEXT_SECONDARY),
+            );
+            return;
+        };
+        let keywords = app.metadata_db.get(&path).keywords;
+
+        // Current keywords as chips: click to filter by it, × to remove
+        let mut remove = None;
+        let mut filter = None;
+        if keywords.is_empty() {
+            ui.label(
+                RichText::new("No keywords")
+                    .size(10.0)
+                    .color(LR_TEXT_SECONDARY),
+            );
+        } else {
+            ui.horizontal_wrapped(|ui| {
+                ui.spacing_mut().item_spacing = egui::vec2(4.0, 4.0);
+                for keyword in &keywords {
+                    let active = app
+                        .keyword_filter
+                        .as_ref()
+                        .is_some_and(|f| f.eq_ignore_ascii_case(keyword));
+                    egui::Frame::NONE
+                        .fill(LR_BG_INPUT)
+                        .stroke(Stroke::new(
+                            1.0,
+                            if active {
+                                FILTER_ACTIVE
+                            } else {
+                                common::LR_BORDER
+                            },
+                        ))
+                        .corner_radius(CornerRadius::same(2))
+                        .inner_margin(egui::Margin::symmetric(4, 1))
+                        .show(ui, |ui| {
+                            ui.spacing_mut().item_spacing.x = 2.0;
+                            let label = ui
+                                .add(
+                                    egui::Label::new(
+                                        RichText::new(keyword).size(10.0).color(LR_TEXT_PRIMARY),
+                                    )
+                                    .sense(egui::Sense::click()),
+                                )
+                                .on_hover_text("Show only images with this keyword");
+                            if label.clicked() {
+                                filter = Some(if active { None } else { Some(keyword.clone()) });
+                            }
+                            if ui
+                                .add(
+                                    egui::Label::new(
+                                        RichText::new("×").size(10.0).color(LR_TEXT_SECONDARY),
+                                    )
+                                    .sense(egui::Sense::click()),
+                                )
+                                .on_hover_text("Remove keyword")
+                                .clicked()
+                            {
+                                remove = Some(keyword.clone());
+                            }
+                        });
+                }
+            });
+        }
+
+        ui.add_space(6.0);
+
+        // Entry with autocomplete from keywords used anywhere in the catalog
+        let response = ui.add(
+            egui::TextEdit::singleline(&mut app.keyword_input)
+                .id(keyword_input_id())
+                .hint_text("Add keyword (K)...")
+                .desired_width(ui.available_width()),
+        );
+        if app.focus_keyword_input {
+            response.request_focus();
+            app.focus_keyword_input = false;
+        }
+
+        let mut add = None;
+        if response.lost_focus()
+            && ui.input(|i| i.key_pressed(egui::Key::Enter))
+            && !app.keyword_input.trim().is_empty()
+        {
+            add = Some(app.keyword_input.clone());
+        }
+
+        let query = app.keyword_input.trim().to_lowercase();
+        if !query.is_empty() {
+            let suggestions: Vec<String> = app
+                .metadata_db
+                .all_keywords()
+                .into_iter()
+                .filter(|k| k.to_lowercase().starts_with(&query))
+                .filter(|k| {
+                    !keywords
+                        .iter()
+                        .any(|existing| existing.eq_ignore_ascii_case(k))
+                })
+                .take(MAX_SUGGESTIONS)
+                .collect();
+            for suggestion in suggestions {
+                if ui
+                    .selectable_label(
+                        false,
+                        RichText::new(&suggestion).size(10.0).color(LR_TEXT_LABEL),
+                    )
+                    .clicked()
+                {
+                    add = Some(suggestion);
+                }
+            }
+        }
+
+        if let Some(input) = add {
+            app.add_keywords_to_current(&input);
+            app.keyword_input.clear();
+            // Keep the entry focused so several keywords can be typed in a row
+            ui.memory_mut(|m| m.request_focus(keyword_input_id()));
+        }
+        if let Some(keyword) = remove {
+            app.remove_keyword_from_current(&keyword);
+        }
+
+        if let Some(keyword) = filter {
+            app.set_keyword_filter(keyword);
+        }
+    });
+}
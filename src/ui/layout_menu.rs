@@ -0,0 +1,136 @@
+use crate::app::ImageViewerApp;
+use crate::settings::ThumbnailPosition;
+use crate::workspace::{DockSide, PanelPlacement};
+use egui::{self, RichText};
+
+impl ImageViewerApp {
+    /// Contents of the toolbar's layout menu: workspaces and where each panel docks
+    pub fn render_layout_menu(&mut self, ui: &mut egui::Ui) {
+        ui.set_min_width(220.0);
+
+        ui.label(RichText::new("Workspaces").strong());
+        let mut apply = None;
+        let mut delete = None;
+        for workspace in &self.settings.workspaces {
+            ui.horizontal(|ui| {
+                if ui.button(&workspace.name).clicked() {
+                    apply = Some(workspace.name.clone());
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui
+                        .small_button("✖")
+                        .on_hover_text("Delete workspace")
+                        .clicked()
+                    {
+                        delete = Some(workspace.name.clone());
+                    }
+                });
+            });
+        }
+
+        let name_id = egui::Id::new("new_workspace_name");
+        let mut name: String = ui.data_mut(|d| d.get_temp(name_id)).unwrap_or_default();
+        let mut save = false;
+        ui.horizontal(|ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut name)
+                    .hint_text("Workspace name")
+                    .desired_width(130.0),
+            );
+            let entered = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if ui
+                .add_enabled(!name.trim().is_empty(), egui::Button::new("Save"))
+                .on_hover_text("Save the current layout; an existing name is overwritten")
+                .clicked()
+                || entered
+            {
+                save = true;
+            }
+        });
+
+        ui.separator();
+        ui.label(RichText::new("Panels").strong());
+        let layout = &mut self.settings.panel_layout;
+        egui::Grid::new("layout_menu_panels")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.checkbox(&mut layout.show_navigator, "Navigator");
+                dock_side_combo(ui, "dock_navigator", &mut layout.navigator.side);
+                ui.end_row();
+
+                ui.checkbox(&mut self.settings.show_sidebar, "Sidebar");
+                dock_side_combo(ui, "dock_sidebar", &mut layout.sidebar.side);
+                ui.end_row();
+
+                ui.checkbox(&mut self.settings.show_histogram, "Histogram");
+                let selected = layout
+                    .histogram
+                    .map_or("In sidebar", |placement| placement.side.name());
+                egui::ComboBox::from_id_salt("dock_histogram")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut layout.histogram, None, "In sidebar");
+                        for &side in DockSide::all() {
+                            if ui
+                                .selectable_label(
+                                    layout.histogram.is_some_and(|p| p.side == side),
+                                    side.name(),
+                                )
+                                .clicked()
+                            {
+                                layout.histogram = Some(PanelPlacement::new(side, 240.0, 140.0));
+                            }
+                        }
+                    });
+                ui.end_row();
+
+                ui.checkbox(&mut self.settings.show_scopes, "Scopes");
+                ui.label("In sidebar");
+                ui.end_row();
+
+                ui.checkbox(&mut self.settings.show_thumbnails, "Thumbnails");
+                egui::ComboBox::from_id_salt("dock_thumbnails")
+                    .selected_text(format!("{:?}", self.settings.thumbnail_position))
+                    .show_ui(ui, |ui| {
+                        for position in [
+                            ThumbnailPosition::Bottom,
+                            ThumbnailPosition::Top,
+                            ThumbnailPosition::Left,
+                            ThumbnailPosition::Right,
+                        ] {
+                            ui.selectable_value(
+                                &mut self.settings.thumbnail_position,
+                                position,
+                                format!("{:?}", position),
+                            );
+                        }
+                    });
+                ui.end_row();
+            });
+        ui.label(RichText::new("Drag a panel's edge to resize it").weak());
+
+        if save {
+            self.save_workspace(&name);
+            name.clear();
+            ui.close_menu();
+        }
+        ui.data_mut(|d| d.insert_temp(name_id, name));
+        if let Some(name) = delete {
+            self.delete_workspace(&name);
+        }
+        if let Some(name) = apply {
+            self.apply_workspace(&name);
+            ui.close_menu();
+        }
+    }
+}
+
+fn dock_side_combo(ui: &mut egui::Ui, id: &str, side: &mut DockSide) {
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(side.name())
+        .show_ui(ui, |ui| {
+            for &option in DockSide::all() {
+                ui.selectable_value(side, option, option.name());
+            }
+        });
+}
@@ -2,7 +2,7 @@ use crate::app::{ImageViewerApp, ViewMode};
 use crate::gpu::types::GpuProcessor;
 
 impl eframe::App for ImageViewerApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
         crate::profiler::with_profiler(|p| p.start_timer("ui_update"));
 
         self.ctx = Some(ctx.clone());
@@ -30,6 +30,11 @@ impl eframe::App for ImageViewerApp {
         // Process task scheduler results
         self.process_task_results(ctx);
 
+        // Import what arrived in the watched folders
+        self.import_pending_files();
+        // Pick up debounced changes from the folder watcher
+        self.apply_pending_folder_changes();
+
         // Periodic cleanup of unused textures (every 100 frames)
         static mut FRAME_COUNTER: u32 = 0;
         unsafe {
@@ -45,6 +50,11 @@ impl eframe::App for ImageViewerApp {
         // Animate zoom/pan
         self.animate_view(ctx);
 
+        self.advance_slideshow(ctx);
+        self.update_tray(ctx);
+        self.update_fullscreen_move(ctx);
+        self.update_drag_out(ctx, frame);
+
         // Apply theme
         crate::ui::main::theme::apply_theme(ctx, &self.settings);
 
@@ -56,17 +66,37 @@ impl eframe::App for ImageViewerApp {
         // Render dialogs
         self.render_dialogs(ctx);
 
-        // Tabs disabled — do not render tab bar
-
         // Render UI based on view mode
         match self.view_mode {
+            _ if self.mini_viewer.is_some() => {
+                if self.view_mode != ViewMode::Scroll {
+                    self.release_compare_textures();
+                }
+                self.render_main_view(ctx);
+                self.update_mini_viewer();
+                self.render_mini_viewer_overlay(ctx);
+            }
             ViewMode::Single => {
+                self.release_compare_textures();
                 self.render_common_ui(ctx);
                 if !self.panels_hidden {
                     // Render thumbnail bar before side panels so it spans full width
                     self.render_thumbnail_bar(ctx);
+                    self.render_filter_bar(ctx);
                     self.render_navigator_left_panel(ctx);
                     self.render_sidebar(ctx);
+                    self.render_histogram_dock(ctx);
+                }
+                self.render_main_view(ctx);
+            }
+            ViewMode::Scroll => {
+                self.render_common_ui(ctx);
+                if !self.panels_hidden {
+                    self.render_thumbnail_bar(ctx);
+                    self.render_filter_bar(ctx);
+                    self.render_navigator_left_panel(ctx);
+                    self.render_sidebar(ctx);
+                    self.render_histogram_dock(ctx);
                 }
                 self.render_main_view(ctx);
             }
@@ -75,6 +105,7 @@ impl eframe::App for ImageViewerApp {
                 if !self.panels_hidden {
                     self.render_navigator_left_panel(ctx);
                     self.render_sidebar(ctx);
+                    self.render_histogram_dock(ctx);
                 }
                 // Call the public wrapper
                 self.render_compare_view_public(ctx);
@@ -126,9 +157,21 @@ impl eframe::App for ImageViewerApp {
         });
     }
 
+    fn clear_color(&self, visuals: &egui::Visuals) -> [f32; 4] {
+        match self.desktop_overlay_opacity() {
+            Some(_) => [0.0; 4],
+            None => visuals.panel_fill.to_normalized_gamma_f32(),
+        }
+    }
+
     fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.save_session_tabs();
         self.settings.save();
+        // Persist edits made since the last navigation (e.g. film sliders)
+        self.save_current_adjustments();
         self.metadata_db.save();
+        crate::single_instance::release();
+        crate::app::recently_deleted::purge_holding_dirs();
     }
 }
 
@@ -138,6 +181,7 @@ impl ImageViewerApp {
         if self.settings.show_toolbar {
             self.render_toolbar(ctx);
         }
+        self.render_tab_bar(ctx);
         if self.settings.show_statusbar {
             self.render_statusbar(ctx);
         }
@@ -28,8 +28,8 @@ impl ImageViewerApp {
             LoaderMessage::PreviewLoaded(path, preview) => {
                 self.handle_preview_loaded(path, preview)
             }
-            LoaderMessage::ProgressiveLoaded(path, progressive) => {
-                self.handle_progressive_loaded(path, progressive)
+            LoaderMessage::ProgressiveLoaded(path, progressive, size) => {
+                self.handle_progressive_loaded(path, progressive, size, ctx)
             }
             LoaderMessage::ThumbnailLoaded(path, thumb) => {
                 self.handle_thumbnail_loaded(path, thumb, ctx)
@@ -39,18 +39,115 @@ impl ImageViewerApp {
             LoaderMessage::ThumbnailRequestComplete(path) => {
                 self.handle_thumbnail_request_complete(path)
             }
+            LoaderMessage::CompareTextureLoaded(path, texture) => {
+                self.handle_compare_texture_loaded(path, texture)
+            }
+            LoaderMessage::CompareAligned(reference, path, alignment) => {
+                self.handle_compare_aligned(reference, path, alignment)
+            }
+            LoaderMessage::CompareQuality(reference, path, scores) => {
+                self.handle_compare_quality(reference, path, scores)
+            }
+            LoaderMessage::OriginalTextureLoaded(path, size, texture) => {
+                self.handle_original_texture_loaded(path, size, texture)
+            }
+            LoaderMessage::OverlayReferenceLoaded(path, result) => {
+                self.handle_overlay_reference_loaded(path, result)
+            }
+            LoaderMessage::OverlayDifferenceReady(path, size, texture) => {
+                self.handle_overlay_difference_ready(path, size, texture)
+            }
+            LoaderMessage::ExportPreviewEncoded(result) => {
+                self.handle_export_preview_encoded(*result)
+            }
             LoaderMessage::TextureCreated(texture_name, texture, image) => {
                 self.handle_texture_created(texture_name, texture, image)
             }
+            LoaderMessage::DisplaySourceReady(path, scale, source) => {
+                self.handle_display_source_ready(path, scale, source)
+            }
+            LoaderMessage::TilesReady(path, pyramid) => {
+                if self.get_current_path().as_ref() == Some(&path) {
+                    self.tiled_image = Some((path, crate::gpu::tiles::TiledImage::new(pyramid)));
+                }
+            }
             LoaderMessage::HistogramUpdated(hist) => {
                 self.histogram_data = Some(hist);
+                self.request_scopes();
+            }
+            LoaderMessage::LiveHistogramUpdated(hist) => {
+                self.live_histogram_pending = false;
+                // A late result would replace the exact one computed on release
+                if self.slider_dragging || self.drawing_local_mask() {
+                    self.histogram_data = Some(hist);
+                }
+            }
+            LoaderMessage::ScopesUpdated(generation, scopes) => {
+                self.handle_scopes_updated(generation, *scopes)
+            }
+            LoaderMessage::AnimationLoaded(path, frames) => {
+                self.handle_animation_loaded(path, frames, ctx)
+            }
+            LoaderMessage::VideoLoaded(path, info) => self.handle_video_loaded(path, info),
+            LoaderMessage::VideoFrame(path, at, frame) => {
+                self.handle_video_frame(path, at, frame, ctx)
+            }
+            LoaderMessage::XmpLoaded(sidecars) => self.handle_xmp_loaded(sidecars),
+            LoaderMessage::SearchExifIndexed(entries) => self.handle_search_exif_indexed(entries),
+            LoaderMessage::CollectionMatched(index, matches) => {
+                self.handle_collection_matched(index, matches)
+            }
+            LoaderMessage::WatchedFilesArrived(paths) => self.handle_watched_files_arrived(paths),
+            LoaderMessage::WatchedFilesImported(entries) => {
+                self.handle_watched_files_imported(entries)
+            }
+            LoaderMessage::AllMetadataLoaded(path, tags) => {
+                self.handle_all_metadata_loaded(path, tags)
+            }
+            LoaderMessage::ShootSummaryReady(folder, summary, exif) => {
+                self.handle_shoot_summary_ready(folder, summary, exif)
+            }
+            LoaderMessage::FolderChanged(paths) => self.handle_folder_changed(paths),
+            LoaderMessage::FolderScanned(id, paths) => self.handle_folder_scanned(id, paths),
+            LoaderMessage::FolderScanFinished(id) => self.handle_folder_scan_finished(id),
+            LoaderMessage::BatchExportProgress(path, error) => {
+                self.handle_batch_export_progress(path, error)
+            }
+            LoaderMessage::BatchExportSkipped(path) => self.handle_batch_export_skipped(path),
+            LoaderMessage::BatchExportFinished => self.handle_batch_export_finished(),
+            LoaderMessage::SharedLink(link) => self.handle_shared_link(link),
+            LoaderMessage::RemoteCommand(request) => self.handle_remote_command(request),
+            LoaderMessage::ScriptFinished(title, result) => {
+                self.handle_script_finished(title, result)
+            }
+            LoaderMessage::ContactSheetFinished(result) => {
+                self.handle_contact_sheet_finished(result)
+            }
+            LoaderMessage::WebGalleryFinished(result) => self.handle_web_gallery_finished(result),
+            LoaderMessage::VideoExportProgress(frames) => self.handle_video_export_progress(frames),
+            LoaderMessage::VideoExportFinished(result) => self.handle_video_export_finished(result),
+            LoaderMessage::MusicTempo(path, bpm) => self.handle_music_tempo(path, bpm),
+            LoaderMessage::RenameInfoLoaded(entries) => self.handle_rename_info_loaded(entries),
+            LoaderMessage::CaptureDatesRead(entries) => self.handle_capture_dates_read(entries),
+            LoaderMessage::OpenedFromLaunch(path, options) => {
+                self.handle_opened_from_launch(path, options, ctx)
+            }
+            LoaderMessage::TrayCommand(command) => self.handle_tray_command(command, ctx),
+            LoaderMessage::UrlDownloadProgress(url, received, total) => {
+                self.handle_url_download_progress(url, received, total)
             }
+            LoaderMessage::UrlDownloaded(url, result) => self.handle_url_downloaded(url, result),
+            LoaderMessage::MetadataInfoLoaded(entries) => self.handle_metadata_info_loaded(entries),
+            LoaderMessage::MetadataWritten(results) => self.handle_metadata_written(results),
+            LoaderMessage::AutoEnhanced(results) => self.handle_auto_enhanced(results),
             LoaderMessage::MoveCompleted {
-                from,
+                moved,
                 dest_folder,
-                success,
                 error,
-            } => self.handle_move_completed(from, dest_folder, success, error),
+            } => self.handle_move_completed(moved, dest_folder, error),
+            LoaderMessage::DngConversionFinished { converted, failed } => {
+                self.handle_dng_conversion_finished(converted, failed)
+            }
         }
     }
 
@@ -75,12 +172,34 @@ impl ImageViewerApp {
         }
     }
 
-    fn handle_progressive_loaded(&mut self, path: PathBuf, progressive: DynamicImage) {
+    fn handle_progressive_loaded(
+        &mut self,
+        path: PathBuf,
+        progressive: DynamicImage,
+        size: [u32; 2],
+        ctx: &egui::Context,
+    ) {
         crate::profiler::with_profiler(|p| p.increment_counter("progressive_loaded"));
-        if self.get_current_path().as_ref() == Some(&path) && self.is_loading {
-            self.showing_preview = true;
-            self.set_current_image(&path, progressive);
+        // Passes only stand in until the full decode arrives
+        if self.get_current_path().as_ref() != Some(&path) || !self.is_loading {
+            return;
+        }
+        let rgba = progressive.to_rgba8();
+        let color_image = ColorImage::from_rgba_unmultiplied(
+            [rgba.width() as usize, rgba.height() as usize],
+            rgba.as_raw(),
+        );
+        self.current_texture =
+            Some(ctx.load_texture("progressive", color_image, egui::TextureOptions::LINEAR));
+        // Drop the previous file's image so the view draws this texture rather
+        // than that image's GPU copy; the pass itself is never cached
+        self.current_image = None;
+        self.tiled_image = None;
+        if self.progressive_size.is_none() {
+            self.pending_fit_to_window = true;
         }
+        self.progressive_size = Some(egui::Vec2::new(size[0] as f32, size[1] as f32));
+        self.showing_preview = true;
     }
 
     fn handle_thumbnail_loaded(&mut self, path: PathBuf, thumb: DynamicImage, ctx: &egui::Context) {
@@ -137,46 +256,57 @@ impl ImageViewerApp {
         self.thumbnail_requests.remove(&path);
     }
 
+    fn handle_original_texture_loaded(
+        &mut self,
+        path: PathBuf,
+        size: [u32; 2],
+        texture: egui::TextureHandle,
+    ) {
+        // Ignore textures for an image that was left or edited while building
+        if self.original_texture_pending == Some((path.clone(), size)) {
+            self.original_texture_pending = None;
+            self.original_texture = Some((path, size, texture));
+        }
+    }
+
+    fn handle_compare_texture_loaded(&mut self, path: PathBuf, texture: egui::TextureHandle) {
+        // Dropped if the image left the comparison while it was decoding
+        if self.compare_requests.contains(&path) {
+            self.compare_textures.insert(path, texture);
+        }
+    }
+
     fn handle_move_completed(
         &mut self,
-        from: PathBuf,
+        moved: Vec<(PathBuf, PathBuf)>,
         dest_folder: PathBuf,
-        success: bool,
         error: Option<String>,
     ) {
-        if success {
-            let filename = from.file_name().unwrap_or_default();
-            let to = dest_folder.join(filename);
-            self.undo_history.push(FileOperation::Move {
-                from: from.clone(),
-                to: to.clone(),
-            });
-
-            if let Some(&idx) = self.filtered_list.get(self.current_index) {
-                self.image_list.remove(idx);
-            }
-            self.image_cache.remove(&from);
-            self.thumbnail_textures.remove(&from);
+        let count = moved.len();
+        if count > 0 {
+            let ops = moved
+                .iter()
+                .map(|(from, to)| FileOperation::Move {
+                    from: from.clone(),
+                    to: to.clone(),
+                })
+                .collect();
+            self.push_undo_batch(ops);
+            let from: Vec<PathBuf> = moved.into_iter().map(|(from, _)| from).collect();
+            self.remove_from_list(&from);
+            self.settings.add_quick_move_folder(dest_folder.clone());
+        }
 
-            self.apply_filter();
-            if self.current_index >= self.filtered_list.len() && !self.filtered_list.is_empty() {
-                self.current_index = self.filtered_list.len() - 1;
-            }
-            if !self.filtered_list.is_empty() {
-                // Load adjustments for the new current image
-                self.load_adjustments_for_current();
-                self.load_current_image();
-            } else {
-                self.current_texture = None;
-                self.current_image = None;
-            }
-            self.show_status(&format!("Moved to {}", dest_folder.display()));
-            self.settings.add_quick_move_folder(dest_folder);
-        } else {
-            self.show_status(&format!(
+        match (count, error) {
+            (0, error) => self.show_status(&format!(
                 "Failed to move image: {}",
                 error.unwrap_or_else(|| "Unknown error".to_string())
-            ));
+            )),
+            (1, None) => self.show_status(&format!("Moved to {}", dest_folder.display())),
+            (n, None) => {
+                self.show_status(&format!("Moved {} images to {}", n, dest_folder.display()))
+            }
+            (n, Some(error)) => self.show_status(&format!("Moved {} images; {}", n, error)),
         }
     }
 
@@ -3,6 +3,7 @@ use egui::{self, Color32, Margin, RichText};
 
 impl ImageViewerApp {
     pub(crate) fn render_statusbar(&mut self, ctx: &egui::Context) {
+        let text_size = self.settings.status_font_size;
         egui::TopBottomPanel::bottom("statusbar")
             .frame(
                 egui::Frame::NONE
@@ -17,22 +18,25 @@ impl ImageViewerApp {
                             .file_name()
                             .map(|n| n.to_string_lossy().to_string())
                             .unwrap_or_default();
-                        ui.label(RichText::new(&filename).color(Color32::WHITE).size(12.0));
+                        ui.label(
+                            RichText::new(&filename)
+                                .color(Color32::WHITE)
+                                .size(text_size + 1.0),
+                        );
 
                         // Dimensions
-                        if let Some(tex) = &self.current_texture {
-                            let size = tex.size_vec2();
+                        if let Some(size) = self.image_display_size() {
                             ui.label(
                                 RichText::new(format!("{}×{}", size.x as u32, size.y as u32))
                                     .color(Color32::GRAY)
-                                    .size(11.0),
+                                    .size(text_size),
                             );
                         }
 
                         // File size from EXIF
                         if let Some(exif) = &self.current_exif {
                             if let Some(ref size) = exif.file_size {
-                                ui.label(RichText::new(size).color(Color32::GRAY).size(11.0));
+                                ui.label(RichText::new(size).color(Color32::GRAY).size(text_size));
                             }
                         }
 
@@ -41,9 +45,23 @@ impl ImageViewerApp {
                             ui.label(
                                 RichText::new("[Preview]")
                                     .color(Color32::from_rgb(255, 200, 100))
-                                    .size(11.0),
+                                    .size(text_size),
                             );
                         }
+
+                        // Exact source value under the cursor while inspecting pixels
+                        if let (Some((x, y)), Some(image)) =
+                            (self.hovered_pixel, &self.current_image)
+                        {
+                            if let Some(values) = crate::image_loader::pixel_values(image, x, y) {
+                                ui.label(
+                                    RichText::new(format!("{}, {}  {}", x, y, values))
+                                        .color(Color32::LIGHT_GRAY)
+                                        .monospace()
+                                        .size(text_size),
+                                );
+                            }
+                        }
                     }
 
                     // Spacer
@@ -54,7 +72,7 @@ impl ImageViewerApp {
                                 ui.label(
                                     RichText::new(msg)
                                         .color(Color32::from_rgb(100, 200, 100))
-                                        .size(11.0),
+                                        .size(text_size),
                                 );
                             }
                         }
@@ -66,22 +84,28 @@ impl ImageViewerApp {
                         };
                         egui::ComboBox::from_id_salt("statusbar_sort")
                             .selected_text(format!(
-                                "{:?} ({})",
-                                self.settings.sort_mode, order_label
+                                "{} ({})",
+                                self.settings.sort_mode.name(),
+                                order_label
                             ))
                             .width(140.0)
                             .show_ui(ui, |ui| {
                                 for mode in [
                                     crate::settings::SortMode::Name,
                                     crate::settings::SortMode::Date,
+                                    crate::settings::SortMode::DateTaken,
                                     crate::settings::SortMode::Size,
                                     crate::settings::SortMode::Type,
+                                    crate::settings::SortMode::Dimensions,
+                                    crate::settings::SortMode::AspectRatio,
+                                    crate::settings::SortMode::Iso,
+                                    crate::settings::SortMode::FocalLength,
                                     crate::settings::SortMode::Random,
                                 ] {
                                     if ui
                                         .selectable_label(
                                             self.settings.sort_mode == mode,
-                                            format!("{:?}", mode),
+                                            mode.name(),
                                         )
                                         .clicked()
                                     {
@@ -112,19 +136,32 @@ impl ImageViewerApp {
                         ui.label(
                             RichText::new(format!("{:.0}%", self.zoom * 100.0))
                                 .color(Color32::GRAY)
-                                .size(11.0),
+                                .size(text_size),
                         );
 
-                        // Image counter
+                        // Image counter, marked while the folder is still being listed
+                        let scanning = if self.folder_scan.is_some() { "+" } else { "" };
                         if !self.filtered_list.is_empty() {
                             ui.label(
                                 RichText::new(format!(
-                                    "{} / {}",
+                                    "{} / {}{}",
                                     self.current_index + 1,
-                                    self.filtered_list.len()
+                                    self.filtered_list.len(),
+                                    scanning
                                 ))
                                 .color(Color32::GRAY)
-                                .size(11.0),
+                                .size(text_size),
+                            )
+                            .on_hover_text(if scanning.is_empty() {
+                                "Images in this folder"
+                            } else {
+                                "Still scanning the folder"
+                            });
+                        } else if self.folder_scan.is_some() {
+                            ui.label(
+                                RichText::new("Scanning...")
+                                    .color(Color32::GRAY)
+                                    .size(text_size),
                             );
                         }
                     });
@@ -89,4 +89,14 @@ pub fn apply_theme(ctx: &egui::Context, settings: &crate::settings::Settings) {
     visuals.selection.bg_fill = settings.accent_color.to_color().linear_multiply(0.5);
 
     ctx.set_visuals(visuals);
+    ctx.set_zoom_factor(settings.ui_scale());
+
+    let spacing = if settings.touch_mode {
+        crate::touch::touch_spacing()
+    } else {
+        egui::style::Spacing::default()
+    };
+    if ctx.style().spacing != spacing {
+        ctx.style_mut(|style| style.spacing = spacing);
+    }
 }
@@ -3,33 +3,34 @@ use crate::ui::common;
 use egui::{self, RichText};
 
 pub fn render_metadata_info_panel(app: &mut ImageViewerApp, ui: &mut egui::Ui) {
+    let text_size = app.settings.exif_font_size;
     common::lr_collapsible_panel(ui, "Metadata", true, |ui| {
         if let Some(exif) = &app.current_exif {
             if !exif.has_data() {
                 ui.label(
                     RichText::new("No EXIF data")
-                        .size(10.0)
+                        .size(text_size)
                         .color(common::LR_TEXT_SECONDARY),
                 );
             } else {
                 // Camera info
-                common::lr_info_row(ui, "Camera", exif.camera_model.as_deref());
-                common::lr_info_row(ui, "Lens", exif.lens.as_deref());
+                common::lr_info_row(ui, "Camera", exif.camera_model.as_deref(), text_size);
+                common::lr_info_row(ui, "Lens", exif.lens.as_deref(), text_size);
 
                 let fl = exif.focal_length_formatted();
                 if !fl.is_empty() {
-                    common::lr_info_row(ui, "Focal Length", Some(&fl));
+                    common::lr_info_row(ui, "Focal Length", Some(&fl), text_size);
                 }
 
                 let ap = exif.aperture_formatted();
                 if !ap.is_empty() {
-                    common::lr_info_row(ui, "Aperture", Some(&ap));
+                    common::lr_info_row(ui, "Aperture", Some(&ap), text_size);
                 }
 
-                common::lr_info_row(ui, "Shutter", exif.shutter_speed.as_deref());
-                common::lr_info_row(ui, "ISO", exif.iso.as_deref());
-                common::lr_info_row(ui, "Date", exif.date_taken.as_deref());
-                common::lr_info_row(ui, "Dimensions", exif.dimensions.as_deref());
+                common::lr_info_row(ui, "Shutter", exif.shutter_speed.as_deref(), text_size);
+                common::lr_info_row(ui, "ISO", exif.iso.as_deref(), text_size);
+                common::lr_info_row(ui, "Date", exif.date_taken.as_deref(), text_size);
+                common::lr_info_row(ui, "Dimensions", exif.dimensions.as_deref(), text_size);
 
                 if exif.gps_latitude.is_some() && exif.gps_longitude.is_some() {
                     let gps = format!(
@@ -37,15 +38,29 @@ pub fn render_metadata_info_panel(app: &mut ImageViewerApp, ui: &mut egui::Ui) {
                         exif.gps_latitude.unwrap_or(0.0),
                         exif.gps_longitude.unwrap_or(0.0)
                     );
-                    common::lr_info_row(ui, "GPS", Some(&gps));
+                    common::lr_info_row(ui, "GPS", Some(&gps), text_size);
                 }
             }
+
+            if let Some(layers) = exif.layers {
+                common::lr_info_row(ui, "Layers", Some(&layers.to_string()), text_size);
+            }
+            common::lr_info_row(ui, "Color Mode", exif.color_mode.as_deref(), text_size);
         } else {
             ui.label(
                 RichText::new("No metadata")
-                    .size(10.0)
+                    .size(text_size)
                     .color(common::LR_TEXT_SECONDARY),
             );
         }
+
+        if app.get_current_path().is_some()
+            && ui
+                .small_button("Show All Metadata")
+                .on_hover_text("Every EXIF, IPTC and XMP tag (Shift+I)")
+                .clicked()
+        {
+            app.open_all_metadata();
+        }
     });
 }
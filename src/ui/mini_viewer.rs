@@ -0,0 +1,82 @@
+use crate::app::ImageViewerApp;
+use crate::i18n::tr;
+use egui::{Align2, Color32, CursorIcon, Id, Order, Sense, Stroke, Vec2, ViewportCommand};
+
+impl ImageViewerApp {
+    /// Tiny toolbar and resize grip shown over the mini viewer while the
+    /// pointer is on it
+    pub fn render_mini_viewer_overlay(&mut self, ctx: &egui::Context) {
+        if ctx.input(|i| i.pointer.hover_pos()).is_none() {
+            return;
+        }
+
+        let mut previous = false;
+        let mut next = false;
+        let mut leave = false;
+        egui::Area::new(Id::new("mini_viewer_toolbar"))
+            .anchor(Align2::CENTER_TOP, Vec2::new(0.0, 6.0))
+            .order(Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        let grip = ui
+                            .add(egui::Label::new("⠿").sense(Sense::drag()))
+                            .on_hover_cursor(CursorIcon::Grab)
+                            .on_hover_text(tr("mini-viewer-move"));
+                        if grip.drag_started() {
+                            ctx.send_viewport_cmd(ViewportCommand::StartDrag);
+                        }
+                        if ui.small_button("◀").clicked() {
+                            previous = true;
+                        }
+                        if ui.small_button("▶").clicked() {
+                            next = true;
+                        }
+                        if ui
+                            .small_button("⛶")
+                            .on_hover_text(tr("mini-viewer-leave"))
+                            .clicked()
+                        {
+                            leave = true;
+                        }
+                        if ui.small_button("✖").clicked() {
+                            ctx.send_viewport_cmd(ViewportCommand::Close);
+                        }
+                    });
+                });
+            });
+
+        egui::Area::new(Id::new("mini_viewer_resize"))
+            .anchor(Align2::RIGHT_BOTTOM, Vec2::new(-2.0, -2.0))
+            .order(Order::Foreground)
+            .show(ctx, |ui| {
+                let (rect, response) = ui.allocate_exact_size(Vec2::splat(14.0), Sense::drag());
+                let stroke = Stroke::new(1.5, Color32::from_white_alpha(160));
+                for offset in [4.0, 8.0, 12.0] {
+                    ui.painter().line_segment(
+                        [
+                            rect.right_bottom() - Vec2::new(offset, 0.0),
+                            rect.right_bottom() - Vec2::new(0.0, offset),
+                        ],
+                        stroke,
+                    );
+                }
+                if response.drag_started() {
+                    ctx.send_viewport_cmd(ViewportCommand::BeginResize(
+                        egui::ResizeDirection::SouthEast,
+                    ));
+                }
+                response.on_hover_cursor(CursorIcon::ResizeSouthEast);
+            });
+
+        if previous {
+            self.previous_image();
+        }
+        if next {
+            self.next_image();
+        }
+        if leave {
+            self.toggle_mini_viewer();
+        }
+    }
+}
@@ -1,16 +1,42 @@
 use crate::app::LoaderMessage;
 
 mod adjustments;
+mod all_metadata_dialog;
+mod batch_export_dialog;
+mod batch_metadata_dialog;
+mod collection_dialog;
+mod collections;
 mod command_palette;
 mod common;
+mod contact_sheet_dialog;
 mod dialogs;
+mod export_dialog;
+mod film_stocks_dialog;
+mod filter_bar;
 mod folders;
 mod histogram;
 mod image_view;
+mod keywords;
+mod layout_menu;
 mod main;
 mod metadata;
+mod mini_viewer;
+mod move_dialog;
 mod navigator;
+mod open_url_dialog;
+mod presets_dialog;
+mod recent_menu;
+mod recently_deleted_dialog;
+mod reference_overlay_dialog;
+mod rename_dialog;
+mod scopes;
 mod settings_dialog;
+mod share_menu;
+mod shoot_summary_dialog;
 mod sidebar;
+mod slideshow_dialog;
+mod tab_bar;
 mod thumbnails;
+mod timeline_dialog;
 mod toolbar;
+mod web_gallery_dialog;
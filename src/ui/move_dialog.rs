@@ -0,0 +1,91 @@
+use crate::app::ImageViewerApp;
+use egui::{self, RichText, Vec2};
+use std::path::PathBuf;
+
+enum FolderAction {
+    Move(PathBuf),
+    Copy(PathBuf),
+}
+
+impl ImageViewerApp {
+    pub fn render_move_dialog(&mut self, ctx: &egui::Context) {
+        if !self.show_move_dialog {
+            return;
+        }
+
+        let count = self.action_paths().len();
+        let mut open = true;
+        let mut action = None;
+
+        egui::Window::new("Move or Copy")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .default_width(360.0)
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(
+                    RichText::new(match count {
+                        1 => "1 image".to_string(),
+                        n => format!("{} images", n),
+                    })
+                    .weak(),
+                );
+                ui.add_space(4.0);
+
+                if self.settings.quick_move_folders.is_empty() {
+                    ui.label(RichText::new("No recent folders").weak());
+                } else {
+                    egui::Grid::new("move_dialog_folders")
+                        .num_columns(3)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for folder in &self.settings.quick_move_folders {
+                                let name = folder
+                                    .file_name()
+                                    .map(|n| n.to_string_lossy().to_string())
+                                    .unwrap_or_else(|| folder.display().to_string());
+                                ui.label(name).on_hover_text(folder.display().to_string());
+                                if ui.small_button("Move").clicked() {
+                                    action = Some(FolderAction::Move(folder.clone()));
+                                }
+                                if ui.small_button("Copy").clicked() {
+                                    action = Some(FolderAction::Copy(folder.clone()));
+                                }
+                                ui.end_row();
+                            }
+                        });
+                    ui.label(RichText::new("Press M again to move to the first folder").weak());
+                }
+
+                ui.add_space(8.0);
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Move to...").clicked() {
+                        if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                            action = Some(FolderAction::Move(folder));
+                        }
+                    }
+                    if ui.button("Copy to...").clicked() {
+                        if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                            action = Some(FolderAction::Copy(folder));
+                        }
+                    }
+                });
+            });
+
+        match action {
+            Some(FolderAction::Move(folder)) => {
+                self.move_to_folder(folder);
+                self.show_move_dialog = false;
+            }
+            Some(FolderAction::Copy(folder)) => {
+                self.settings.add_quick_move_folder(folder.clone());
+                self.copy_to_folder(folder);
+                self.show_move_dialog = false;
+            }
+            None if !open => self.show_move_dialog = false,
+            None => {}
+        }
+    }
+}
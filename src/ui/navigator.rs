@@ -142,7 +142,7 @@ pub fn render_navigator_panel(app: &mut ImageViewerApp, ui: &mut egui::Ui) {
                 if app.zoom > 1.0 || app.pan_offset != Vec2::ZERO {
                     // Calculate the viewport rectangle
                     let view_size = app.available_view_size;
-                    let display_size = tex_size * app.zoom;
+                    let display_size = app.image_display_size().unwrap_or(tex_size) * app.zoom;
 
                     // What portion of the image is visible
                     let visible_width = (view_size.x / display_size.x).min(1.0);
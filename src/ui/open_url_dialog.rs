@@ -0,0 +1,90 @@
+use crate::app::ImageViewerApp;
+use crate::exif_data::format_file_size;
+use egui::{self, RichText, Vec2};
+
+impl ImageViewerApp {
+    pub fn render_open_url_dialog(&mut self, ctx: &egui::Context) {
+        if !self.show_open_url_dialog {
+            return;
+        }
+
+        // Close on escape, dropping a download in progress
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.cancel_url_download();
+            self.show_open_url_dialog = false;
+            return;
+        }
+
+        let mut open = true;
+        let mut submit = false;
+        let mut cancel = false;
+
+        egui::Window::new("Open URL")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .default_width(420.0)
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                let downloading = self.url_download.is_some();
+                ui.add_enabled_ui(!downloading, |ui| {
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut self.open_url_text)
+                            .hint_text("https://example.com/photo.jpg")
+                            .desired_width(400.0),
+                    );
+                    if !downloading && !response.has_focus() && self.open_url_text.is_empty() {
+                        response.request_focus();
+                    }
+                    if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        submit = true;
+                    }
+                });
+
+                ui.add_space(8.0);
+                match &self.url_download {
+                    Some(download) => {
+                        let text = match download.total {
+                            Some(total) => format!(
+                                "{} of {}",
+                                format_file_size(download.received),
+                                format_file_size(total)
+                            ),
+                            None => format_file_size(download.received),
+                        };
+                        let bar = match download.fraction() {
+                            Some(fraction) => egui::ProgressBar::new(fraction),
+                            // Unknown size: keep the bar moving
+                            None => egui::ProgressBar::new(0.0).animate(true),
+                        };
+                        ui.add(bar.text(text));
+                        ui.add_space(4.0);
+                        if ui.button("Cancel").clicked() {
+                            cancel = true;
+                        }
+                    }
+                    None => {
+                        ui.label(
+                            RichText::new("Downloaded images are kept for the next time").weak(),
+                        );
+                        ui.add_space(4.0);
+                        let valid = crate::download::is_url(&self.open_url_text);
+                        if ui.add_enabled(valid, egui::Button::new("Open")).clicked() {
+                            submit = true;
+                        }
+                    }
+                }
+            });
+
+        if cancel {
+            self.cancel_url_download();
+        }
+        if submit && self.url_download.is_none() {
+            self.open_url(self.open_url_text.clone());
+        }
+        if !open {
+            self.cancel_url_download();
+            self.show_open_url_dialog = false;
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use crate::app::ImageViewerApp;
+use egui::{self, RichText, Vec2};
+
+enum PresetAction {
+    Apply(usize),
+    Rename(usize, String),
+    Delete(usize),
+    Move(usize, isize),
+}
+
+impl ImageViewerApp {
+    pub fn render_presets_dialog(&mut self, ctx: &egui::Context) {
+        if !self.show_presets_dialog {
+            return;
+        }
+
+        // Close on escape
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.show_presets_dialog = false;
+            return;
+        }
+
+        let mut action = None;
+        let mut save_requested = false;
+        let mut open = true;
+
+        egui::Window::new("Adjustment Presets")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(360.0)
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                // Save current adjustments
+                ui.horizontal(|ui| {
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut self.preset_name_input)
+                            .hint_text("Preset name")
+                            .desired_width(220.0),
+                    );
+                    let enter =
+                        response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    let can_save = !self.preset_name_input.trim().is_empty();
+                    if (ui
+                        .add_enabled(can_save, egui::Button::new("Save current"))
+                        .clicked()
+                        || enter)
+                        && can_save
+                    {
+                        save_requested = true;
+                    }
+                });
+
+                ui.add_space(8.0);
+                ui.separator();
+                ui.add_space(4.0);
+
+                if self.settings.user_presets.is_empty() {
+                    ui.label(RichText::new("No saved presets").weak());
+                    return;
+                }
+
+                let count = self.settings.user_presets.len();
+                egui::ScrollArea::vertical()
+                    .max_height(320.0)
+                    .show(ui, |ui| {
+                        for (i, preset) in self.settings.user_presets.iter_mut().enumerate() {
+                            ui.horizontal(|ui| {
+                                // Edited in place; persisted once the field loses focus
+                                let response = ui.add(
+                                    egui::TextEdit::singleline(&mut preset.name)
+                                        .desired_width(160.0),
+                                );
+                                if response.lost_focus() {
+                                    action = Some(PresetAction::Rename(i, preset.name.clone()));
+                                }
+                                if ui
+                                    .add_enabled(i > 0, egui::Button::new("⏶").small())
+                                    .on_hover_text("Move up")
+                                    .clicked()
+                                {
+                                    action = Some(PresetAction::Move(i, -1));
+                                }
+                                if ui
+                                    .add_enabled(i + 1 < count, egui::Button::new("⏷").small())
+                                    .on_hover_text("Move down")
+                                    .clicked()
+                                {
+                                    action = Some(PresetAction::Move(i, 1));
+                                }
+                                if ui.small_button("Apply").clicked() {
+                                    action = Some(PresetAction::Apply(i));
+                                }
+                                if ui.small_button("🗑").on_hover_text("Delete").clicked() {
+                                    action = Some(PresetAction::Delete(i));
+                                }
+                            });
+                        }
+                    });
+            });
+
+        if save_requested {
+            let name = std::mem::take(&mut self.preset_name_input);
+            self.save_user_preset(&name);
+        }
+
+        match action {
+            Some(PresetAction::Apply(i)) => self.apply_user_preset(i),
+            Some(PresetAction::Rename(i, name)) => self.rename_user_preset(i, &name),
+            Some(PresetAction::Delete(i)) => self.delete_user_preset(i),
+            Some(PresetAction::Move(i, offset)) => self.move_user_preset(i, offset),
+            None => {}
+        }
+
+        if !open {
+            self.show_presets_dialog = false;
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use crate::app::ImageViewerApp;
+use egui::{self, RichText};
+
+/// Entries listed in each section of the menu; the palette searches all of them
+const MENU_ENTRIES: usize = 12;
+
+impl ImageViewerApp {
+    /// Contents of the toolbar's recent menu: images viewed lately, across
+    /// sessions, and recently opened folders
+    pub fn render_recent_menu(&mut self, ui: &mut egui::Ui) {
+        ui.set_min_width(280.0);
+        let mut open_image = None;
+        let mut open_folder = None;
+
+        ui.label(RichText::new("Recent Images").strong());
+        if self.settings.recent_images.is_empty() {
+            ui.label(RichText::new("No images viewed yet").weak());
+        }
+        let now = chrono::Local::now();
+        for recent in self.settings.recent_images.iter().take(MENU_ENTRIES) {
+            let name = recent
+                .path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy();
+            ui.horizontal(|ui| {
+                if ui
+                    .button(name)
+                    .on_hover_text(recent.path.display().to_string())
+                    .clicked()
+                {
+                    open_image = Some(recent.path.clone());
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.label(RichText::new(recent.viewed_ago(now)).weak());
+                });
+            });
+        }
+
+        ui.separator();
+        ui.label(RichText::new("Recent Folders").strong());
+        if self.settings.recent_folders.is_empty() {
+            ui.label(RichText::new("No folders opened yet").weak());
+        }
+        for folder in self.settings.recent_folders.iter().take(MENU_ENTRIES) {
+            let name = folder.file_name().unwrap_or(folder.as_os_str());
+            if ui
+                .button(name.to_string_lossy())
+                .on_hover_text(folder.display().to_string())
+                .clicked()
+            {
+                open_folder = Some(folder.clone());
+            }
+        }
+
+        ui.separator();
+        if ui
+            .add_enabled(
+                !self.settings.recent_images.is_empty(),
+                egui::Button::new("Clear Image History"),
+            )
+            .clicked()
+        {
+            self.settings.recent_images.clear();
+            ui.close_menu();
+        }
+
+        if let Some(path) = open_image {
+            ui.close_menu();
+            self.open_recent_image(path);
+        } else if let Some(folder) = open_folder {
+            ui.close_menu();
+            self.load_folder(folder);
+        }
+    }
+}
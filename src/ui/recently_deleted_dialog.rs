@@ -0,0 +1,117 @@
+use crate::app::ImageViewerApp;
+use egui::{self, RichText, Vec2};
+use std::collections::HashSet;
+
+impl ImageViewerApp {
+    pub fn render_recently_deleted_dialog(&mut self, ctx: &egui::Context) {
+        if !self.show_recently_deleted_dialog {
+            return;
+        }
+
+        // Close on escape
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.show_recently_deleted_dialog = false;
+            return;
+        }
+
+        let checked_id = egui::Id::new("recently_deleted_checked");
+        let mut checked: HashSet<usize> =
+            ctx.data_mut(|d| d.get_temp(checked_id)).unwrap_or_default();
+        checked.retain(|&i| i < self.recently_deleted.len());
+
+        let mut open = true;
+        let mut restore = Vec::new();
+
+        egui::Window::new("Recently Deleted")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(460.0)
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                if self.recently_deleted.is_empty() {
+                    ui.label(RichText::new("Nothing deleted this session").weak());
+                    return;
+                }
+
+                egui::ScrollArea::vertical()
+                    .max_height(320.0)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        egui::Grid::new("recently_deleted_list")
+                            .num_columns(4)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                // Newest first
+                                for (index, deleted) in
+                                    self.recently_deleted.iter().enumerate().rev()
+                                {
+                                    let mut on = checked.contains(&index);
+                                    let name = deleted
+                                        .original_path
+                                        .file_name()
+                                        .unwrap_or_default()
+                                        .to_string_lossy();
+                                    if ui.checkbox(&mut on, name).changed() {
+                                        if on {
+                                            checked.insert(index);
+                                        } else {
+                                            checked.remove(&index);
+                                        }
+                                    }
+                                    let location = if deleted.held_path.is_some() {
+                                        "until closed"
+                                    } else {
+                                        "in trash"
+                                    };
+                                    ui.label(RichText::new(location).weak())
+                                        .on_hover_text(deleted.original_path.display().to_string());
+                                    ui.label(
+                                        RichText::new(
+                                            deleted.deleted_at.format("%H:%M").to_string(),
+                                        )
+                                        .weak(),
+                                    );
+                                    if ui.small_button("Restore").clicked() {
+                                        restore = vec![index];
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+
+                ui.add_space(4.0);
+                ui.label(
+                    RichText::new("Files deleted without the trash are removed when rView closes")
+                        .weak(),
+                );
+
+                ui.add_space(8.0);
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(
+                            !checked.is_empty(),
+                            egui::Button::new(format!("Restore {} Selected", checked.len())),
+                        )
+                        .clicked()
+                    {
+                        restore = checked.iter().copied().collect();
+                    }
+                    if ui.button("Restore All").clicked() {
+                        restore = (0..self.recently_deleted.len()).collect();
+                    }
+                });
+            });
+
+        if !restore.is_empty() {
+            // Indices shift once entries are restored
+            checked.clear();
+            self.restore_deleted(&restore);
+        }
+        ctx.data_mut(|d| d.insert_temp(checked_id, checked));
+        if !open {
+            self.show_recently_deleted_dialog = false;
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use crate::app::reference_overlay::{OverlayBlend, OverlaySource};
+use crate::app::ImageViewerApp;
+use crate::image_loader::SUPPORTED_EXTENSIONS;
+use egui::{self, RichText, Vec2};
+
+impl ImageViewerApp {
+    pub fn render_reference_overlay_dialog(&mut self, ctx: &egui::Context) {
+        if !self.show_reference_overlay_dialog {
+            return;
+        }
+
+        let mut open = true;
+        let mut source = None;
+        let mut turn_off = false;
+
+        // Kept in the corner so the image stays in view while it's adjusted
+        egui::Window::new("Reference Overlay")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .default_width(340.0)
+            .anchor(egui::Align2::RIGHT_BOTTOM, Vec2::new(-16.0, -16.0))
+            .show(ctx, |ui| {
+                let current = self.reference_overlay.as_ref().map(|o| o.source.clone());
+                let reference = match &current {
+                    Some(OverlaySource::Image(path)) => path
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .to_string(),
+                    Some(OverlaySource::Desktop) => "The desktop".to_string(),
+                    None => "Nothing".to_string(),
+                };
+                ui.label(format!("Current image laid over: {}", reference));
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    let current_path = self.get_current_path();
+                    if ui
+                        .add_enabled(current_path.is_some(), egui::Button::new("This Image"))
+                        .on_hover_text("Use this image as the reference, then move to another")
+                        .clicked()
+                    {
+                        source = current_path.map(OverlaySource::Image);
+                    }
+                    if ui.button("Choose File...").clicked() {
+                        source = rfd::FileDialog::new()
+                            .add_filter("Images", SUPPORTED_EXTENSIONS)
+                            .pick_file()
+                            .map(OverlaySource::Image);
+                    }
+                    if ui.button("Desktop").clicked() {
+                        source = Some(OverlaySource::Desktop);
+                    }
+                });
+
+                ui.add_space(8.0);
+                let desktop = current == Some(OverlaySource::Desktop);
+                match &mut self.reference_overlay {
+                    Some(overlay) => {
+                        ui.horizontal(|ui| {
+                            ui.label("Opacity:");
+                            ui.add(
+                                egui::Slider::new(&mut overlay.opacity, 0.05..=1.0)
+                                    .custom_formatter(|v, _| format!("{:.0}%", v * 100.0)),
+                            );
+                        });
+                        ui.add_enabled_ui(!desktop, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.label("Blend:");
+                                egui::ComboBox::from_id_salt("overlay_blend")
+                                    .selected_text(overlay.blend.name())
+                                    .show_ui(ui, |ui| {
+                                        for blend in OverlayBlend::all() {
+                                            ui.selectable_value(
+                                                &mut overlay.blend,
+                                                *blend,
+                                                blend.name(),
+                                            );
+                                        }
+                                    });
+                            });
+                        });
+                        if desktop {
+                            let mini = self.settings.keymap.shortcut_label("mini_viewer");
+                            ui.label(
+                                RichText::new(format!(
+                                    "Use the mini viewer ({}) to trace over other windows",
+                                    mini
+                                ))
+                                .weak(),
+                            );
+                        }
+                        ui.add_space(4.0);
+                        if ui.button("Turn Off").clicked() {
+                            turn_off = true;
+                        }
+                    }
+                    None => {
+                        ui.label(RichText::new("Pick a reference to lay the image over").weak());
+                    }
+                }
+            });
+
+        if let Some(source) = source {
+            self.set_overlay_source(source);
+        }
+        if turn_off {
+            self.reference_overlay = None;
+        }
+        if !open {
+            self.show_reference_overlay_dialog = false;
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use crate::app::ImageViewerApp;
+use crate::rename::TOKENS;
+use egui::{self, Color32, RichText, Vec2};
+
+impl ImageViewerApp {
+    pub fn render_rename_dialog(&mut self, ctx: &egui::Context) {
+        let Some(session) = &self.rename_session else {
+            return;
+        };
+
+        // Close on escape
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.rename_session = None;
+            return;
+        }
+
+        let count = session.sources.len();
+        let waiting = session.infos.is_none() && self.settings.rename_pattern.needs_info();
+        let plans = self.rename_plan();
+        let conflicts = plans.iter().filter(|p| p.conflict.is_some()).count();
+        let changes = plans.iter().filter(|p| p.is_change()).count();
+
+        let mut open = true;
+        let mut rename = false;
+        let mut cancel = false;
+
+        egui::Window::new(format!("Rename {} Files", count))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(520.0)
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                let pattern = &mut self.settings.rename_pattern;
+                ui.horizontal(|ui| {
+                    ui.label("Pattern:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut pattern.template)
+                            .id(egui::Id::new("rename_pattern"))
+                            .desired_width(300.0),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Insert:");
+                    for (token, description) in TOKENS {
+                        if ui
+                            .small_button(*token)
+                            .on_hover_text(*description)
+                            .clicked()
+                        {
+                            pattern.template.push_str(token);
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Start at:");
+                    ui.add(egui::DragValue::new(&mut pattern.start).range(0..=999_999));
+                    ui.label("Digits:");
+                    ui.add(egui::DragValue::new(&mut pattern.padding).range(1..=8));
+                });
+
+                ui.add_space(8.0);
+                ui.separator();
+                ui.label(RichText::new("Preview").strong());
+                egui::ScrollArea::vertical()
+                    .max_height(260.0)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        egui::Grid::new("rename_preview")
+                            .num_columns(3)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for plan in &plans {
+                                    let from = plan.from.file_name().unwrap_or_default();
+                                    let to = plan.to.file_name().unwrap_or_default();
+                                    ui.label(RichText::new(from.to_string_lossy()).weak());
+                                    ui.label("→");
+                                    match plan.conflict {
+                                        Some(conflict) => {
+                                            ui.label(
+                                                RichText::new(format!(
+                                                    "{}  ({})",
+                                                    to.to_string_lossy(),
+                                                    conflict.description()
+                                                ))
+                                                .color(Color32::from_rgb(255, 120, 120)),
+                                            );
+                                        }
+                                        None => {
+                                            ui.label(to.to_string_lossy());
+                                        }
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+
+                ui.add_space(4.0);
+                let summary = if waiting {
+                    "Reading dates and camera models...".to_string()
+                } else if conflicts > 0 {
+                    format!("{} name conflicts", conflicts)
+                } else {
+                    format!("{} of {} files will be renamed", changes, count)
+                };
+                ui.label(RichText::new(summary).weak());
+
+                ui.add_space(8.0);
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let enabled = !waiting && conflicts == 0 && changes > 0;
+                    if ui
+                        .add_enabled(enabled, egui::Button::new("Rename"))
+                        .clicked()
+                    {
+                        rename = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+
+        if rename {
+            self.apply_batch_rename();
+        } else if cancel || !open {
+            self.rename_session = None;
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use crate::app::scopes::ScopeTexture;
+use crate::app::ImageViewerApp;
+use crate::image_loader::{ScopeData, ScopeType, SCOPE_SIZE};
+use crate::ui::common::{self, LR_BG_INPUT, LR_TEXT_LABEL, LR_TEXT_SECONDARY};
+use egui::{self, Color32, CornerRadius, Pos2, Rect, RichText, Stroke, Vec2};
+
+/// Trace brightness for `count` samples: log scaled so sparse values still
+/// show, then raised by the intensity setting
+fn trace_level(count: u32, log_max: f32, intensity: f32) -> f32 {
+    if count == 0 {
+        return 0.0;
+    }
+    ((1.0 + count as f32).ln() / log_max * intensity).min(1.0)
+}
+
+fn log_max(plane: &[u32]) -> f32 {
+    (1.0 + plane.iter().copied().max().unwrap_or(0) as f32)
+        .ln()
+        .max(f32::EPSILON)
+}
+
+fn tint(color: [f32; 3], level: f32) -> Color32 {
+    Color32::from_rgb(
+        (color[0] * level * 255.0) as u8,
+        (color[1] * level * 255.0) as u8,
+        (color[2] * level * 255.0) as u8,
+    )
+}
+
+/// A waveform plane drawn with levels rising from the bottom
+fn draw_waveform(
+    pixels: &mut [Color32],
+    stride: usize,
+    offset: usize,
+    plane: &[u32],
+    color: [f32; 3],
+    intensity: f32,
+) {
+    let log_max = log_max(plane);
+    for (i, &count) in plane.iter().enumerate() {
+        let (x, level) = (i % SCOPE_SIZE, i / SCOPE_SIZE);
+        let y = SCOPE_SIZE - 1 - level;
+        pixels[y * stride + offset + x] = tint(color, trace_level(count, log_max, intensity));
+    }
+}
+
+/// The colour of a vectorscope cell, at a mid brightness so hues read clearly
+fn vectorscope_color(x: usize, y: usize) -> [f32; 3] {
+    let cb = x as f32 / (SCOPE_SIZE - 1) as f32 - 0.5;
+    let cr = 0.5 - y as f32 / (SCOPE_SIZE - 1) as f32;
+    let luma = 0.6;
+    let r = luma + 1.5748 * cr;
+    let b = luma + 1.8556 * cb;
+    let g = (luma - 0.2126 * r - 0.0722 * b) / 0.7152;
+    // Mix in white so faint, near-neutral traces stay visible
+    [r, g, b].map(|c| 0.35 + 0.65 * c.clamp(0.0, 1.0))
+}
+
+fn render_scope(data: &ScopeData, scope: ScopeType, intensity: f32) -> egui::ColorImage {
+    match scope {
+        ScopeType::Waveform => {
+            let mut pixels = vec![Color32::BLACK; SCOPE_SIZE * SCOPE_SIZE];
+            draw_waveform(
+                &mut pixels,
+                SCOPE_SIZE,
+                0,
+                &data.luma,
+                [0.75, 1.0, 0.75],
+                intensity,
+            );
+            egui::ColorImage {
+                size: [SCOPE_SIZE, SCOPE_SIZE],
+                pixels,
+            }
+        }
+        ScopeType::Parade => {
+            let stride = SCOPE_SIZE * 3;
+            let mut pixels = vec![Color32::BLACK; stride * SCOPE_SIZE];
+            let colors = [[1.0, 0.35, 0.35], [0.35, 1.0, 0.35], [0.4, 0.5, 1.0]];
+            for (channel, (plane, color)) in data.parade.iter().zip(colors).enumerate() {
+                draw_waveform(
+                    &mut pixels,
+                    stride,
+                    channel * SCOPE_SIZE,
+                    plane,
+                    color,
+                    intensity,
+                );
+            }
+            egui::ColorImage {
+                size: [stride, SCOPE_SIZE],
+                pixels,
+            }
+        }
+        ScopeType::Vectorscope => {
+            let log_max = log_max(&data.vectorscope);
+            let pixels = data
+                .vectorscope
+                .iter()
+                .enumerate()
+                .map(|(i, &count)| {
+                    let color = vectorscope_color(i % SCOPE_SIZE, i / SCOPE_SIZE);
+                    tint(color, trace_level(count, log_max, intensity))
+                })
+                .collect();
+            egui::ColorImage {
+                size: [SCOPE_SIZE, SCOPE_SIZE],
+                pixels,
+            }
+        }
+    }
+}
+
+/// Reference lines: quarter levels for waveforms, and for the vectorscope the
+/// axes, a 75% saturation ring and the skin tone line
+fn draw_graticule(painter: &egui::Painter, rect: Rect, scope: ScopeType) {
+    let stroke = Stroke::new(1.0, Color32::from_rgba_unmultiplied(255, 255, 255, 40));
+    match scope {
+        ScopeType::Waveform | ScopeType::Parade => {
+            for quarter in 0..=4 {
+                let y = rect.bottom() - rect.height() * quarter as f32 / 4.0;
+                painter.hline(rect.x_range(), y, stroke);
+            }
+            if scope == ScopeType::Parade {
+                for third in 1..3 {
+                    let x = rect.left() + rect.width() * third as f32 / 3.0;
+                    painter.vline(x, rect.y_range(), stroke);
+                }
+            }
+        }
+        ScopeType::Vectorscope => {
+            let center = rect.center();
+            let radius = rect.width() / 2.0;
+            painter.hline(rect.x_range(), center.y, stroke);
+            painter.vline(center.x, rect.y_range(), stroke);
+            painter.circle_stroke(center, radius * 0.75, stroke);
+            // Skin tones of every complexion fall along this line, 123° from +Cb
+            let angle = 123f32.to_radians();
+            painter.line_segment(
+                [
+                    center,
+                    center + Vec2::new(angle.cos(), -angle.sin()) * radius,
+                ],
+                Stroke::new(1.0, Color32::from_rgba_unmultiplied(255, 200, 150, 80)),
+            );
+        }
+    }
+}
+
+pub fn render_scopes_panel(app: &mut ImageViewerApp, ui: &mut egui::Ui) {
+    common::lr_collapsible_panel(ui, "Scopes", true, |ui| {
+        let settings = &mut app.settings.scopes;
+        ui.horizontal(|ui| {
+            ui.label(RichText::new("Scope:").size(11.0).color(LR_TEXT_LABEL));
+            ui.add_space(8.0);
+            egui::ComboBox::from_id_salt("scope_type")
+                .width(ui.available_width() - 8.0)
+                .selected_text(settings.scope.label())
+                .show_ui(ui, |ui| {
+                    for scope in ScopeType::ALL {
+                        ui.selectable_value(&mut settings.scope, scope, scope.label());
+                    }
+                });
+        });
+        common::lr_slider(
+            ui,
+            "Intensity",
+            &mut settings.intensity,
+            0.25..=4.0,
+            "",
+            1.0,
+        );
+        ui.add_space(4.0);
+
+        let (scope, intensity) = (settings.scope, settings.intensity);
+        let width = ui.available_width() - 8.0;
+        let height = match scope {
+            ScopeType::Vectorscope => width.min(220.0),
+            _ => width * 0.5,
+        };
+        let (response, painter) =
+            ui.allocate_painter(Vec2::new(width, height), egui::Sense::hover());
+        let rect = response.rect;
+        painter.rect_filled(rect, CornerRadius::same(2), LR_BG_INPUT);
+
+        let Some(data) = &app.scope_data else {
+            // Turned on after the image was shown; compute them now
+            if !app.scopes_pending {
+                app.request_scopes();
+            }
+            let message = if app.current_image.is_some() {
+                "Updating..."
+            } else {
+                "No image"
+            };
+            painter.text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                message,
+                egui::FontId::proportional(10.0),
+                LR_TEXT_SECONDARY,
+            );
+            return;
+        };
+
+        let current = app
+            .scope_texture
+            .as_ref()
+            .is_some_and(|t| t.scope == scope && t.intensity == intensity);
+        if !current {
+            let texture = ui.ctx().load_texture(
+                "scope",
+                render_scope(data, scope, intensity),
+                egui::TextureOptions::LINEAR,
+            );
+            app.scope_texture = Some(ScopeTexture {
+                scope,
+                intensity,
+                texture,
+            });
+        }
+
+        let plot_rect = match scope {
+            ScopeType::Vectorscope => Rect::from_center_size(rect.center(), Vec2::splat(height)),
+            _ => rect.shrink(2.0),
+        };
+        if let Some(scope_texture) = &app.scope_texture {
+            painter.image(
+                scope_texture.texture.id(),
+                plot_rect,
+                Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+                Color32::WHITE,
+            );
+        }
+        draw_graticule(&painter, plot_rect, scope);
+    });
+}
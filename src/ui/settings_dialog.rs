@@ -1,5 +1,19 @@
+use crate::app::actions::{find_action, ActionCategory, ACTIONS};
 use crate::app::ImageViewerApp;
-use crate::settings::{BackgroundColor, FocusPeakingColor, GridType, Theme, ThumbnailPosition};
+use crate::exif_overlay::OverlayCorner;
+use crate::i18n::{tr, tr_args, Language};
+use crate::image_loader::{
+    is_float, FilmPreset, RawDemosaic, RawWhiteBalance, ToneMap, CLIPPED_CHANNEL_HIGHLIGHT,
+    CLIPPED_CHANNEL_SHADOW, CLIPPED_HIGHLIGHT, CLIPPED_SHADOW,
+};
+use crate::keymap::KeyBinding;
+use crate::music::SlideTiming;
+use crate::settings::{
+    BackgroundColor, ExportFormat, FocusPeakingColor, GridType, MagnificationFilter,
+    PairPreference, Theme, ThumbnailPosition, UI_SCALE_RANGE,
+};
+use crate::share::{ShareService, ShareTarget};
+use crate::slideshow::SlideshowOrder;
 use egui::{self, Color32, RichText, Vec2};
 
 impl ImageViewerApp {
@@ -8,8 +22,8 @@ impl ImageViewerApp {
             return;
         }
 
-        // Close on escape
-        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+        // Close on escape, unless it cancels recording a shortcut
+        if self.keymap_recording.is_none() && ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
             self.show_settings_dialog = false;
             return;
         }
@@ -17,7 +31,8 @@ impl ImageViewerApp {
         let screen_rect = ctx.screen_rect();
         let max_height = (screen_rect.height() - 100.0).max(300.0);
 
-        egui::Window::new("⚙ Settings")
+        egui::Window::new(tr("settings-title"))
+            .id(egui::Id::new("settings_window"))
             .collapsible(false)
             .resizable(true)
             .default_width(480.0)
@@ -30,7 +45,18 @@ impl ImageViewerApp {
                     .show(ui, |ui| {
                         self.render_appearance_settings(ui);
                         self.render_view_settings(ui);
+                        self.render_exif_overlay_settings(ui);
+                        self.render_raw_settings(ui);
+                        self.render_hdr_settings(ui);
                         self.render_photography_tools_settings(ui);
+                        self.render_color_management_settings(ui);
+                        self.render_export_settings(ui);
+                        self.render_share_settings(ui);
+                        self.render_remote_control_settings(ui);
+                        self.render_tether_settings(ui);
+                        self.render_slideshow_settings(ui);
+                        self.render_metadata_settings(ui);
+                        self.render_keyboard_settings(ui);
                         self.render_cache_settings(ui);
                         self.render_performance_settings(ui);
                         self.render_gpu_info(ui);
@@ -42,7 +68,10 @@ impl ImageViewerApp {
 
                 ui.horizontal(|ui| {
                     if ui
-                        .add_sized(Vec2::new(80.0, 28.0), egui::Button::new("✓ Close"))
+                        .add_sized(
+                            Vec2::new(80.0, 28.0),
+                            egui::Button::new(tr("settings-close")),
+                        )
                         .clicked()
                     {
                         self.show_settings_dialog = false;
@@ -51,20 +80,39 @@ impl ImageViewerApp {
                     if ui
                         .add_sized(
                             Vec2::new(120.0, 28.0),
-                            egui::Button::new("↺ Reset Defaults"),
+                            egui::Button::new(tr("settings-reset")),
                         )
                         .clicked()
                     {
                         self.settings = crate::settings::Settings::default();
+                        self.watch_catalog_folders();
+                        self.apply_remote_control_settings();
+                        self.apply_tray_settings();
+                        crate::i18n::set_language(self.settings.language);
                     }
                 });
             });
     }
 
     fn render_appearance_settings(&mut self, ui: &mut egui::Ui) {
-        ui.heading("Appearance");
+        ui.heading(tr("settings-appearance"));
         ui.add_space(4.0);
 
+        ui.horizontal(|ui| {
+            ui.label(tr("settings-language"));
+            egui::ComboBox::from_id_salt("language_combo")
+                .selected_text(self.settings.language.name())
+                .show_ui(ui, |ui| {
+                    for language in Language::all() {
+                        let selected = self.settings.language == *language;
+                        if ui.selectable_label(selected, language.name()).clicked() {
+                            self.settings.language = *language;
+                            crate::i18n::set_language(*language);
+                        }
+                    }
+                });
+        });
+
         ui.horizontal(|ui| {
             ui.label("Theme:");
             egui::ComboBox::from_id_salt("theme_combo")
@@ -110,8 +158,37 @@ impl ImageViewerApp {
                 });
         });
 
+        // Applied on release: rescaling mid-drag moves the slider out from
+        // under the pointer
+        let draft_id = egui::Id::new("ui_scale_draft");
+        let mut scale = ui
+            .data(|d| d.get_temp(draft_id))
+            .unwrap_or(self.settings.ui_scale());
+        ui.horizontal(|ui| {
+            ui.label("Interface scale:");
+            let response = ui
+                .add(egui::Slider::new(&mut scale, UI_SCALE_RANGE).suffix("×"))
+                .on_hover_text("On top of the system's display scaling");
+            if response.dragged() {
+                ui.data_mut(|d| d.insert_temp(draft_id, scale));
+            } else {
+                ui.data_mut(|d| d.remove::<f32>(draft_id));
+                self.settings.ui_scale = scale;
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("EXIF panel text:");
+            ui.add(egui::Slider::new(&mut self.settings.exif_font_size, 8.0..=20.0).suffix("pt"));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Status bar text:");
+            ui.add(egui::Slider::new(&mut self.settings.status_font_size, 8.0..=20.0).suffix("pt"));
+        });
+        ui.checkbox(&mut self.settings.touch_mode, "Touch mode")
+            .on_hover_text("Larger controls, and swiping the image pages through the folder");
+
         ui.add_space(12.0);
-        ui.heading("Thumbnails");
+        ui.heading(tr("settings-thumbnails"));
         ui.add_space(4.0);
 
         ui.horizontal(|ui| {
@@ -142,19 +219,25 @@ impl ImageViewerApp {
                 });
         });
 
-        ui.horizontal(|ui| {
-            ui.label("Size:");
-            ui.add(egui::Slider::new(&mut self.settings.thumbnail_size, 50.0..=200.0).suffix("px"));
-        });
+        self.render_thumbnail_display_options(ui);
+        ui.checkbox(
+            &mut self.settings.thumbnail_exposure_warnings,
+            "Mark over- and underexposed thumbnails",
+        )
+        .on_hover_text("Badge frames where a large part of the histogram is clipped");
 
         ui.add_space(12.0);
-        ui.heading("Panels");
+        ui.heading(tr("settings-panels"));
         ui.add_space(4.0);
 
         ui.checkbox(&mut self.settings.show_sidebar, "Show sidebar");
         ui.checkbox(&mut self.settings.show_thumbnails, "Show thumbnails");
         ui.checkbox(&mut self.settings.show_exif, "Show EXIF panel");
         ui.checkbox(&mut self.settings.show_histogram, "Show histogram");
+        ui.checkbox(
+            &mut self.settings.show_scopes,
+            "Show scopes (waveform, parade, vectorscope)",
+        );
         ui.checkbox(
             &mut self.settings.show_adjustments,
             "Show adjustments panel",
@@ -165,7 +248,7 @@ impl ImageViewerApp {
     }
 
     fn render_view_settings(&mut self, ui: &mut egui::Ui) {
-        ui.heading("View");
+        ui.heading(tr("settings-view"));
         ui.add_space(4.0);
 
         ui.checkbox(&mut self.settings.smooth_zoom, "Smooth zoom animation");
@@ -177,10 +260,84 @@ impl ImageViewerApp {
             &mut self.settings.maintain_pan_on_navigate,
             "Keep pan position when navigating",
         );
+
+        let mut filtering_changed = false;
+        ui.horizontal(|ui| {
+            ui.label("Magnified pixels:");
+            filtering_changed |= ui
+                .radio_value(
+                    &mut self.settings.magnification_filter,
+                    MagnificationFilter::Linear,
+                    "Smooth",
+                )
+                .changed();
+            filtering_changed |= ui
+                .radio_value(
+                    &mut self.settings.magnification_filter,
+                    MagnificationFilter::Nearest,
+                    "Sharp",
+                )
+                .on_hover_text("Hard-edged pixels, for pixel art and screenshots")
+                .changed();
+        });
+        filtering_changed |= ui
+            .checkbox(
+                &mut self.settings.texture_mipmaps,
+                "Mipmaps (smoother zoomed-out images)",
+            )
+            .changed();
+        if filtering_changed {
+            self.reload_textures();
+        }
         ui.checkbox(
             &mut self.settings.auto_rotate_exif,
             "Auto-rotate based on EXIF",
         );
+        if ui
+            .checkbox(
+                &mut self.settings.watch_folder,
+                "Refresh the folder when files are added, removed or renamed",
+            )
+            .changed()
+        {
+            self.watch_current_folder();
+        }
+        ui.checkbox(
+            &mut self.settings.single_instance,
+            "Open files in the running window instead of a new one",
+        )
+        .on_hover_text("Takes effect the next time rView starts; --new-window skips it");
+        if ui
+            .checkbox(
+                &mut self.settings.tray_icon,
+                "Keep running in the system tray",
+            )
+            .on_hover_text("Closing the window hides it; quit from the tray icon's menu")
+            .changed()
+        {
+            self.apply_tray_settings();
+        }
+        ui.horizontal(|ui| {
+            ui.label("Fullscreen on:");
+            let chosen = &mut self.settings.fullscreen_monitor;
+            let selected = chosen
+                .clone()
+                .unwrap_or_else(|| "Monitor with the window".to_string());
+            egui::ComboBox::from_id_salt("fullscreen_monitor")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(chosen, None, "Monitor with the window");
+                    for monitor in crate::monitors::list() {
+                        let label = monitor.label();
+                        ui.selectable_value(chosen, Some(monitor.name), label);
+                    }
+                })
+                .response
+                .on_hover_text(format!(
+                    "{} moves fullscreen to the next monitor",
+                    self.settings.keymap.shortcut_label("fullscreen_monitor")
+                ));
+        });
 
         ui.horizontal(|ui| {
             ui.label("Grid overlay:");
@@ -206,13 +363,212 @@ impl ImageViewerApp {
                     ui.selectable_value(&mut self.settings.grid_type, GridType::Center, "Center");
                 });
         });
+    }
+
+    fn render_exif_overlay_settings(&mut self, ui: &mut egui::Ui) {
+        ui.add_space(12.0);
+        ui.heading(tr("settings-exif-overlay"));
+        ui.add_space(4.0);
+
+        let overlay = &mut self.settings.exif_overlay;
+        ui.add(
+            egui::TextEdit::multiline(&mut overlay.template)
+                .desired_rows(3)
+                .desired_width(f32::INFINITY),
+        )
+        .on_hover_text("One overlay line per line; the first is drawn larger");
+        ui.horizontal_wrapped(|ui| {
+            ui.label("Insert:");
+            for (token, description) in crate::exif_overlay::TOKENS {
+                if ui
+                    .small_button(*token)
+                    .on_hover_text(*description)
+                    .clicked()
+                {
+                    overlay.template.push_str(token);
+                }
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Position:");
+            egui::ComboBox::from_id_salt("exif_overlay_corner")
+                .selected_text(overlay.corner.name())
+                .show_ui(ui, |ui| {
+                    for corner in OverlayCorner::all() {
+                        ui.selectable_value(&mut overlay.corner, *corner, corner.name());
+                    }
+                });
+        });
+        ui.horizontal(|ui| {
+            ui.label("Text size:");
+            ui.add(egui::Slider::new(&mut overlay.font_size, 8.0..=32.0).suffix("pt"));
+        });
+
+        // Scaled per monitor, on top of the system's display scaling
+        match crate::exif_overlay::monitor_key(ui.ctx()) {
+            Some(monitor) => {
+                let mut scale = overlay.monitor_scale(Some(&monitor));
+                ui.horizontal(|ui| {
+                    ui.label("Scale on this monitor:");
+                    if ui
+                        .add(egui::Slider::new(&mut scale, 0.5..=3.0).suffix("×"))
+                        .on_hover_text(format!("Applies to the {} monitor only", monitor))
+                        .changed()
+                    {
+                        overlay.monitor_scales.insert(monitor.clone(), scale);
+                    }
+                });
+            }
+            None => {
+                ui.label(RichText::new("Per-monitor scale needs the monitor size").weak());
+            }
+        }
+
+        if ui.button("Reset Overlay").clicked() {
+            *overlay = Default::default();
+        }
+    }
+
+    fn render_raw_settings(&mut self, ui: &mut egui::Ui) {
+        ui.heading(tr("settings-raw"));
+        ui.add_space(4.0);
 
         // RAW loading option: use embedded previews only to avoid heavy RAW decoding
         ui.checkbox(&mut self.settings.load_raw_full_size, "Load full-size RAW files (decode to full resolution). If unchecked, only embedded JPEG previews are used");
+
+        let mut dragging = false;
+        ui.add_enabled_ui(self.settings.load_raw_full_size, |ui| {
+            let raw = &mut self.settings.raw_decode;
+            ui.horizontal(|ui| {
+                ui.label("Demosaic:");
+                egui::ComboBox::from_id_salt("raw_demosaic")
+                    .selected_text(raw.demosaic.label())
+                    .show_ui(ui, |ui| {
+                        for demosaic in [RawDemosaic::HighQuality, RawDemosaic::Bilinear] {
+                            ui.selectable_value(&mut raw.demosaic, demosaic, demosaic.label());
+                        }
+                    });
+            });
+            ui.checkbox(
+                &mut raw.half_size,
+                "Half-size decode (much faster, half the resolution)",
+            );
+
+            ui.horizontal(|ui| {
+                ui.label("White balance:");
+                egui::ComboBox::from_id_salt("raw_white_balance")
+                    .selected_text(raw.white_balance.label())
+                    .show_ui(ui, |ui| {
+                        for white_balance in [RawWhiteBalance::Camera, RawWhiteBalance::Auto] {
+                            ui.selectable_value(
+                                &mut raw.white_balance,
+                                white_balance,
+                                white_balance.label(),
+                            );
+                        }
+                    });
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Highlight recovery:");
+                dragging = ui
+                    .add(egui::Slider::new(&mut raw.highlight_recovery, 0.0..=1.0))
+                    .on_hover_text("0 clips blown highlights; higher keeps more detail in them")
+                    .dragged();
+            });
+        });
+
+        // Decoding again is slow, so wait for the slider to be let go
+        if !dragging && self.settings.raw_decode != crate::image_loader::raw_decode_options() {
+            self.apply_raw_decode_options();
+        }
+
+        ui.add_space(4.0);
+        let mut regroup = ui
+            .checkbox(
+                &mut self.settings.pair_raw_jpeg,
+                "Show RAW+JPEG pairs as one image",
+            )
+            .changed();
+        ui.add_enabled_ui(self.settings.pair_raw_jpeg, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Show:");
+                regroup |= ui
+                    .radio_value(
+                        &mut self.settings.pair_preference,
+                        PairPreference::Raw,
+                        "RAW",
+                    )
+                    .changed();
+                regroup |= ui
+                    .radio_value(
+                        &mut self.settings.pair_preference,
+                        PairPreference::Jpeg,
+                        "JPEG",
+                    )
+                    .changed();
+            });
+        });
+        if regroup {
+            self.refilter_keeping_current();
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("DNG converter:");
+            let current = match &self.settings.dng_converter {
+                Some(path) => path.display().to_string(),
+                None => "Automatic".to_string(),
+            };
+            ui.label(RichText::new(current).weak())
+                .on_hover_text("dnglab on the PATH or Adobe DNG Converter when automatic");
+            if ui.button("Browse...").clicked() {
+                if let Some(path) = rfd::FileDialog::new().pick_file() {
+                    self.settings.dng_converter = Some(path);
+                }
+            }
+            if self.settings.dng_converter.is_some() && ui.button("Automatic").clicked() {
+                self.settings.dng_converter = None;
+            }
+        });
+    }
+
+    fn render_hdr_settings(&mut self, ui: &mut egui::Ui) {
+        ui.heading(tr("settings-hdr"));
+        ui.add_space(4.0);
+
+        let hdr = &mut self.settings.hdr_display;
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label("Tone mapping:");
+            egui::ComboBox::from_id_salt("hdr_tone_map")
+                .selected_text(hdr.tone_map.label())
+                .show_ui(ui, |ui| {
+                    for tone_map in ToneMap::ALL {
+                        changed |= ui
+                            .selectable_value(&mut hdr.tone_map, tone_map, tone_map.label())
+                            .changed();
+                    }
+                });
+        });
+        let mut dragging = false;
+        ui.horizontal(|ui| {
+            ui.label("Exposure:");
+            let response = ui
+                .add(egui::Slider::new(&mut hdr.exposure, -8.0..=8.0).suffix(" EV"))
+                .on_hover_text("Applied to EXR and Radiance HDR images before tone mapping");
+            // Redraw at full size once the slider is let go
+            changed |= response.changed() || response.drag_stopped();
+            dragging = response.dragged();
+        });
+
+        if changed && self.current_image.as_deref().is_some_and(is_float) {
+            self.refresh_adjustments_internal(!dragging);
+        }
     }
 
     fn render_photography_tools_settings(&mut self, ui: &mut egui::Ui) {
-        ui.heading("Photography Tools");
+        ui.heading(tr("settings-photography-tools"));
         ui.add_space(4.0);
 
         ui.horizontal(|ui| {
@@ -263,15 +619,61 @@ impl ImageViewerApp {
                 200..=255,
             ));
         });
+
+        ui.add_space(8.0);
+        ui.label(RichText::new("Clipping warning").strong());
+        let clipping = &mut self.settings.clipping;
+        let mut changed = false;
+        // Rebuilt once a slider is let go rather than on every step of a drag
+        let mut settled = |response: egui::Response| {
+            changed |= (response.changed() && !response.dragged()) || response.drag_stopped();
+        };
+        ui.horizontal(|ui| {
+            ui.label("Highlights clip at:");
+            settled(
+                ui.add(egui::Slider::new(&mut clipping.high_threshold, 200..=255))
+                    .on_hover_text("Channel values at or above this count as blown"),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label("Shadows clip at:");
+            settled(
+                ui.add(egui::Slider::new(&mut clipping.low_threshold, 0..=55))
+                    .on_hover_text("Channel values at or below this count as crushed"),
+            );
+        });
+        settled(ui.checkbox(&mut clipping.per_channel, "Show single-channel clipping"));
+        ui.checkbox(&mut clipping.blink, "Blink");
+        for (color, meaning) in [
+            (CLIPPED_HIGHLIGHT, "Pure white: every channel clipped"),
+            (CLIPPED_SHADOW, "Pure black: every channel clipped"),
+            (CLIPPED_CHANNEL_HIGHLIGHT, "Some channels clipped high"),
+            (CLIPPED_CHANNEL_SHADOW, "Some channels clipped low"),
+        ] {
+            ui.horizontal(|ui| {
+                let [r, g, b, _] = color.0;
+                ui.label(RichText::new("■").color(Color32::from_rgb(r, g, b)));
+                ui.label(meaning);
+            });
+        }
+        if changed {
+            self.refresh_clipping_warning();
+        }
     }
 
     fn render_cache_settings(&mut self, ui: &mut egui::Ui) {
-        ui.heading("Cache");
+        ui.heading(tr("settings-cache"));
         ui.add_space(4.0);
 
         ui.horizontal(|ui| {
-            ui.label("Cache size:");
-            ui.add(egui::Slider::new(&mut self.settings.cache_size_mb, 100..=4096).suffix(" MB"));
+            ui.label("Memory ceiling:");
+            let response = ui
+                .add(egui::Slider::new(&mut self.settings.cache_size_mb, 100..=4096).suffix(" MB"))
+                .on_hover_text("Decoded images kept in memory for quick navigation");
+            if response.changed() {
+                self.image_cache
+                    .set_max_size_mb(self.settings.cache_size_mb);
+            }
         });
 
         ui.horizontal(|ui| {
@@ -284,18 +686,41 @@ impl ImageViewerApp {
         // Cache stats
         let stats = self.image_cache.get_stats();
         ui.label(format!(
-            "Cache: {} images ({:.1} MB)",
+            "Cache: {} images ({:.1} of {:.0} MB)",
             stats.image_count,
-            stats.image_size_bytes as f64 / 1_048_576.0
+            stats.image_size_bytes as f64 / 1_048_576.0,
+            stats.max_size_bytes as f64 / 1_048_576.0
         ));
 
         if ui.button("Clear Cache").clicked() {
             self.image_cache.clear();
         }
+
+        ui.add_space(8.0);
+        ui.horizontal(|ui| {
+            ui.label("Thumbnails on disk:");
+            let response = ui.add(
+                egui::Slider::new(&mut self.settings.thumbnail_disk_cache_mb, 64..=8192)
+                    .logarithmic(true)
+                    .suffix(" MB"),
+            );
+            // A lower cap takes effect as new thumbnails are written
+            if response.changed() {
+                crate::image_cache::disk_thumbnails()
+                    .set_max_size_mb(self.settings.thumbnail_disk_cache_mb);
+            }
+        });
+        if ui
+            .button("Clear Thumbnail Cache")
+            .on_hover_text("Thumbnails are made again as folders are opened")
+            .clicked()
+        {
+            crate::image_cache::disk_thumbnails().clear();
+        }
     }
 
     fn render_performance_settings(&mut self, ui: &mut egui::Ui) {
-        ui.heading("Performance & Diagnostics");
+        ui.heading(tr("settings-performance"));
         ui.add_space(4.0);
 
         ui.checkbox(&mut self.profiler_enabled, "Enable performance profiling");
@@ -311,6 +736,7 @@ impl ImageViewerApp {
             ui.label(RichText::new("Cache Statistics").strong());
             ui.add_space(2.0);
 
+            self.refresh_cache_stats();
             let cache_stats = &self.cache_stats;
             ui.label(format!(
                 "Images cached: {} / {} ({:.1}%)",
@@ -330,6 +756,16 @@ impl ImageViewerApp {
                 "Memory usage: {:.1} MB",
                 cache_stats.memory_usage_mb()
             ));
+            ui.label(format!(
+                "Image cache: {:.1} of {:.0} MB",
+                cache_stats.cache_memory_usage as f64 / 1_048_576.0,
+                self.settings.cache_size_mb
+            ));
+            ui.label(format!(
+                "Thumbnails in memory: {} ({:.1} MB)",
+                cache_stats.thumbnail_count,
+                cache_stats.thumbnail_memory_usage as f64 / 1_048_576.0
+            ));
             ui.label(format!("Evictions: {}", cache_stats.eviction_count));
 
             ui.add_space(8.0);
@@ -388,6 +824,560 @@ impl ImageViewerApp {
         }
     }
 
+    fn render_color_management_settings(&mut self, ui: &mut egui::Ui) {
+        ui.heading(tr("settings-color-management"));
+        ui.add_space(4.0);
+
+        let mut changed = ui
+            .checkbox(
+                &mut self.settings.color_management_enabled,
+                "Convert embedded ICC profiles to display profile",
+            )
+            .changed();
+
+        ui.add_enabled_ui(self.settings.color_management_enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Display profile:");
+                let profile_name = self
+                    .settings
+                    .display_icc_profile
+                    .as_ref()
+                    .and_then(|p| p.file_name())
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "sRGB (built-in)".to_string());
+                ui.label(RichText::new(profile_name).strong());
+            });
+
+            ui.horizontal(|ui| {
+                if ui.button("Choose ICC profile...").clicked() {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .add_filter("ICC profiles", &["icc", "icm"])
+                        .pick_file()
+                    {
+                        self.settings.display_icc_profile = Some(path);
+                        changed = true;
+                    }
+                }
+                if self.settings.display_icc_profile.is_some() && ui.button("Use sRGB").clicked() {
+                    self.settings.display_icc_profile = None;
+                    changed = true;
+                }
+            });
+        });
+
+        if changed {
+            self.reload_color_management();
+        }
+    }
+
+    fn render_metadata_settings(&mut self, ui: &mut egui::Ui) {
+        ui.heading(tr("settings-metadata"));
+        ui.add_space(4.0);
+
+        ui.checkbox(
+            &mut self.settings.write_xmp_sidecars,
+            "Write ratings, labels and keywords to XMP sidecars",
+        )
+        .on_hover_text("Existing .xmp sidecars are always read when a folder is opened");
+
+        ui.add_space(4.0);
+        ui.label("Watched folders:").on_hover_text(
+            "New images in these folders are added to the catalog, even while another folder is open",
+        );
+        let mut changed = false;
+        let mut removed = None;
+        for (index, folder) in self.settings.watched_folders.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(folder.display().to_string()).weak());
+                if ui
+                    .small_button("✖")
+                    .on_hover_text("Stop watching")
+                    .clicked()
+                {
+                    removed = Some(index);
+                }
+            });
+        }
+        if let Some(index) = removed {
+            self.settings.watched_folders.remove(index);
+            changed = true;
+        }
+        if ui.button("Add Folder...").clicked() {
+            if let Some(folder) = rfd::FileDialog::new()
+                .set_title("Watch folder")
+                .pick_folder()
+            {
+                if !self.settings.watched_folders.contains(&folder) {
+                    self.settings.watched_folders.push(folder);
+                    changed = true;
+                }
+            }
+        }
+        if changed {
+            self.watch_catalog_folders();
+        }
+
+        match crate::os_tags::file_manager_name() {
+            Some("Explorer") => {
+                ui.checkbox(
+                    &mut self.settings.write_os_tags,
+                    "Show ratings as Explorer stars",
+                )
+                .on_hover_text("Stored inside JPEG, TIFF and other files Windows can tag");
+            }
+            Some(name) => {
+                ui.checkbox(
+                    &mut self.settings.write_os_tags,
+                    format!("Show color labels as {} tags", name),
+                );
+            }
+            None => {}
+        }
+    }
+
+    fn render_keyboard_settings(&mut self, ui: &mut egui::Ui) {
+        ui.heading(tr("settings-keyboard"));
+        ui.add_space(4.0);
+
+        if let Some(id) = self.keymap_recording.clone() {
+            let key = ui.input(|i| {
+                i.events.iter().find_map(|event| match event {
+                    egui::Event::Key {
+                        key,
+                        pressed: true,
+                        modifiers,
+                        ..
+                    } => Some((*key, *modifiers)),
+                    _ => None,
+                })
+            });
+            if let Some((key, modifiers)) = key {
+                if key != egui::Key::Escape {
+                    let mut bindings = self.settings.keymap.bindings(&id);
+                    let binding = KeyBinding::new(key, modifiers);
+                    if !bindings.contains(&binding) {
+                        bindings.push(binding);
+                    }
+                    self.settings.keymap.set_bindings(&id, &bindings);
+                }
+                self.keymap_recording = None;
+            }
+        }
+
+        let conflicts = self.settings.keymap.conflicts();
+        if !conflicts.is_empty() {
+            let count = [("count", conflicts.len().into())];
+            ui.label(
+                RichText::new(tr_args("settings-shortcut-conflicts", &count))
+                    .color(Color32::from_rgb(255, 120, 120)),
+            );
+        }
+
+        egui::CollapsingHeader::new("Shortcuts")
+            .id_salt("keymap_editor")
+            .show(ui, |ui| {
+                for category in ActionCategory::all() {
+                    ui.add_space(4.0);
+                    ui.label(RichText::new(category.label()).strong());
+                    egui::Grid::new(("keymap_grid", category.name()))
+                        .num_columns(3)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for action in ACTIONS.iter().filter(|a| a.category == *category) {
+                                ui.label(action.label());
+
+                                let recording = self.keymap_recording.as_deref() == Some(action.id);
+                                let bindings = self.settings.keymap.bindings(action.id);
+                                let shared: Vec<String> = bindings
+                                    .iter()
+                                    .filter_map(|b| conflicts.get(b))
+                                    .flatten()
+                                    .filter(|id| **id != action.id)
+                                    .filter_map(|id| find_action(id).map(|a| a.label()))
+                                    .collect();
+                                let text = if recording {
+                                    RichText::new("Press a key...").italics()
+                                } else if bindings.is_empty() {
+                                    RichText::new("None").weak()
+                                } else if !shared.is_empty() {
+                                    RichText::new(self.settings.keymap.shortcut_label(action.id))
+                                        .color(Color32::from_rgb(255, 120, 120))
+                                } else {
+                                    RichText::new(self.settings.keymap.shortcut_label(action.id))
+                                };
+                                let label = ui.label(text);
+                                if !shared.is_empty() {
+                                    label.on_hover_text(format!(
+                                        "Also used by {}",
+                                        shared.join(", ")
+                                    ));
+                                }
+
+                                ui.horizontal(|ui| {
+                                    if ui
+                                        .small_button("Add")
+                                        .on_hover_text(
+                                            "Press the new shortcut, or Escape to cancel",
+                                        )
+                                        .clicked()
+                                    {
+                                        self.keymap_recording = Some(action.id.to_string());
+                                    }
+                                    if ui
+                                        .add_enabled(
+                                            !bindings.is_empty(),
+                                            egui::Button::new("Clear").small(),
+                                        )
+                                        .clicked()
+                                    {
+                                        self.settings.keymap.set_bindings(action.id, &[]);
+                                    }
+                                    if ui
+                                        .add_enabled(
+                                            !self.settings.keymap.is_default(action.id),
+                                            egui::Button::new("Reset").small(),
+                                        )
+                                        .clicked()
+                                    {
+                                        self.settings.keymap.overrides.remove(action.id);
+                                    }
+                                });
+                                ui.end_row();
+                            }
+                        });
+                }
+
+                ui.add_space(4.0);
+                if ui.button("Reset All Shortcuts").clicked() {
+                    self.settings.keymap = Default::default();
+                    self.keymap_recording = None;
+                }
+            });
+    }
+
+    fn render_export_settings(&mut self, ui: &mut egui::Ui) {
+        ui.heading(tr("settings-export"));
+        ui.add_space(4.0);
+
+        ui.horizontal(|ui| {
+            ui.label("Format:");
+            egui::ComboBox::from_id_salt("export_format")
+                .selected_text(self.settings.export_format.name())
+                .show_ui(ui, |ui| {
+                    for format in ExportFormat::all() {
+                        ui.selectable_value(
+                            &mut self.settings.export_format,
+                            *format,
+                            format.name(),
+                        );
+                    }
+                });
+        });
+        ui.add_enabled_ui(self.settings.export_format == ExportFormat::Jpeg, |ui| {
+            ui.add(
+                egui::Slider::new(&mut self.settings.export_quality, 1..=100).text("JPEG quality"),
+            );
+        });
+        self.render_export_resize(ui);
+        self.render_export_profile(ui);
+        ui.add_space(4.0);
+        self.render_export_naming(ui);
+    }
+
+    fn render_share_settings(&mut self, ui: &mut egui::Ui) {
+        ui.heading(tr("settings-share"));
+        ui.add_space(4.0);
+        ui.label(
+            RichText::new(
+                "Upload targets for the Share menu. Keys are saved in the settings file.",
+            )
+            .weak(),
+        );
+
+        let mut remove = None;
+        for (index, target) in self.settings.share_targets.iter_mut().enumerate() {
+            let title = format!("{} ({})", target.name, target.service.kind_name());
+            egui::CollapsingHeader::new(title)
+                .id_salt(("share_target", index))
+                .show(ui, |ui| {
+                    egui::Grid::new(("share_target_grid", index))
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            share_field(ui, "Name:", &mut target.name, "");
+                            match &mut target.service {
+                                ShareService::Http(http) => {
+                                    share_field(ui, "URL:", &mut http.url, "https://");
+                                    share_field(ui, "File field:", &mut http.field, "file");
+                                    ui.label("Headers:");
+                                    ui.add(
+                                        egui::TextEdit::multiline(&mut http.headers)
+                                            .desired_rows(2)
+                                            .hint_text("Authorization: Bearer ..."),
+                                    )
+                                    .on_hover_text(
+                                        "Only the names are saved; values are asked for once per session",
+                                    );
+                                    ui.end_row();
+                                    share_field(
+                                        ui,
+                                        "Link in reply:",
+                                        &mut http.link_field,
+                                        "data.link",
+                                    );
+                                }
+                                ShareService::S3(s3) => {
+                                    share_field(ui, "Endpoint:", &mut s3.endpoint, "https://");
+                                    share_field(ui, "Region:", &mut s3.region, "us-east-1");
+                                    share_field(ui, "Bucket:", &mut s3.bucket, "");
+                                    share_field(ui, "Key prefix:", &mut s3.prefix, "photos/");
+                                    share_field(ui, "Access key:", &mut s3.access_key, "");
+                                    ui.label("Secret key:");
+                                    ui.add(
+                                        egui::TextEdit::singleline(&mut s3.secret_key)
+                                            .password(true)
+                                            .hint_text("asked for once per session"),
+                                    );
+                                    ui.end_row();
+                                }
+                                ShareService::Sftp(sftp) => {
+                                    share_field(ui, "Host:", &mut sftp.host, "example.com");
+                                    ui.label("Port:");
+                                    ui.add(egui::DragValue::new(&mut sftp.port).range(1..=65535));
+                                    ui.end_row();
+                                    share_field(ui, "User:", &mut sftp.user, "");
+                                    share_field(ui, "Folder:", &mut sftp.folder, "public_html");
+                                }
+                            }
+                            share_field(
+                                ui,
+                                "Link:",
+                                &mut target.link_template,
+                                "https://example.com/{name}",
+                            );
+                        });
+                    if matches!(target.service, ShareService::Sftp(_)) {
+                        ui.label(
+                            RichText::new("Signs in with your ssh key or agent, never a password")
+                                .weak(),
+                        );
+                    }
+                    if ui.button("Remove").clicked() {
+                        remove = Some(index);
+                    }
+                });
+        }
+        if let Some(index) = remove {
+            self.settings.share_targets.remove(index);
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Add:");
+            for service in ShareService::all() {
+                if ui.button(service.kind_name()).clicked() {
+                    self.settings.share_targets.push(ShareTarget::new(service));
+                }
+            }
+        });
+    }
+
+    fn render_remote_control_settings(&mut self, ui: &mut egui::Ui) {
+        ui.heading(tr("settings-remote-control"));
+        ui.add_space(4.0);
+
+        let remote = &mut self.settings.remote_control;
+        let mut changed = ui
+            .checkbox(&mut remote.enabled, "Accept commands over HTTP")
+            .on_hover_text(
+                "Next, previous, go to, rating and slideshow from a tablet or button box",
+            )
+            .changed();
+        ui.horizontal(|ui| {
+            ui.label("Port:");
+            let response = ui.add(egui::DragValue::new(&mut remote.port).range(1024..=65535));
+            changed |= response.lost_focus() || response.drag_stopped();
+        });
+        changed |= ui
+            .checkbox(&mut remote.allow_lan, "Allow other devices on the network")
+            .changed();
+        ui.horizontal(|ui| {
+            ui.label("Token:");
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut remote.token)
+                    .password(true)
+                    .hint_text("generated when turned on"),
+            );
+            changed |= response.lost_focus();
+            if ui.button("Generate").clicked() {
+                remote.token = uuid::Uuid::new_v4().simple().to_string();
+                changed = true;
+            }
+        });
+        if changed {
+            self.apply_remote_control_settings();
+        }
+        if let Some(server) = &self.remote_server {
+            let token = &self.settings.remote_control.token;
+            let url = format!("http://{}/?token={}", server.address, token);
+            ui.horizontal(|ui| {
+                ui.label(RichText::new("Listening on").weak());
+                ui.hyperlink(url);
+            });
+        }
+    }
+
+    fn render_tether_settings(&mut self, ui: &mut egui::Ui) {
+        ui.heading(tr("settings-tether"));
+        ui.add_space(4.0);
+        ui.label(
+            RichText::new("Tether to Folder follows a camera's hot folder, showing each new shot.")
+                .weak(),
+        );
+
+        let tether = &mut self.settings.tether;
+        ui.horizontal(|ui| {
+            ui.label("Film preset:");
+            let selected = tether.film().map_or("None", |film| film.name());
+            egui::ComboBox::from_id_salt("tether_film_preset")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for preset in FilmPreset::all() {
+                        let name = match preset {
+                            FilmPreset::None => "",
+                            preset => preset.name(),
+                        };
+                        if ui
+                            .selectable_label(selected == preset.name(), preset.name())
+                            .clicked()
+                        {
+                            tether.film_preset = name.to_string();
+                        }
+                    }
+                });
+        });
+        ui.checkbox(&mut tether.fullscreen, "Switch to fullscreen");
+        ui.checkbox(&mut tether.histogram, "Show a large histogram");
+
+        if let Some(folder) = self.tether.as_ref().map(|t| t.folder.clone()) {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(format!("Tethered to {}", folder.display())).weak());
+                if ui.button("Stop").clicked() {
+                    self.stop_tether();
+                }
+            });
+        }
+    }
+
+    fn render_slideshow_settings(&mut self, ui: &mut egui::Ui) {
+        ui.heading(tr("settings-slideshow"));
+        ui.add_space(4.0);
+
+        ui.horizontal(|ui| {
+            ui.label("Order:");
+            let order = &mut self.settings.slideshow_order;
+            egui::ComboBox::from_id_salt("slideshow_order")
+                .selected_text(order.name())
+                .show_ui(ui, |ui| {
+                    for option in SlideshowOrder::all() {
+                        ui.selectable_value(order, *option, option.name());
+                    }
+                });
+        });
+        let keymap = &self.settings.keymap;
+        ui.label(
+            RichText::new(format!(
+                "{} skips to the next slide, {} holds the current one",
+                keymap.shortcut_label("slideshow_skip"),
+                keymap.shortcut_label("slideshow_hold")
+            ))
+            .weak(),
+        );
+        ui.add_space(4.0);
+
+        let mut volume_changed = false;
+        let mut add_tracks = false;
+        let music = &mut self.settings.music;
+        ui.checkbox(&mut music.enabled, "Play music during slideshows");
+        ui.add_enabled_ui(music.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Volume:");
+                volume_changed |= ui
+                    .add(egui::Slider::new(&mut music.volume, 0.0..=1.0).show_value(false))
+                    .changed();
+                volume_changed |= ui.checkbox(&mut music.muted, "Mute").changed();
+            });
+            ui.horizontal(|ui| {
+                ui.label("Slide timing:");
+                egui::ComboBox::from_id_salt("music_timing")
+                    .selected_text(music.timing.name())
+                    .show_ui(ui, |ui| {
+                        for timing in SlideTiming::all() {
+                            ui.selectable_value(&mut music.timing, *timing, timing.name());
+                        }
+                    });
+            });
+            if music.timing == SlideTiming::Beats {
+                ui.label(
+                    RichText::new("Tracks without a tempo keep the slideshow interval").weak(),
+                );
+            }
+
+            let timing = music.timing;
+            let mut removed = None;
+            for (index, track) in music.tracks.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    let name = track
+                        .path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    ui.label(name)
+                        .on_hover_text(track.path.display().to_string());
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.small_button("🗑").on_hover_text("Remove").clicked() {
+                            removed = Some(index);
+                        }
+                        if timing == SlideTiming::PerTrack {
+                            ui.add(
+                                egui::DragValue::new(&mut track.slide_seconds)
+                                    .range(0.5..=60.0)
+                                    .speed(0.1)
+                                    .suffix(" s"),
+                            )
+                            .on_hover_text("Seconds per slide while this track plays");
+                        }
+                        // Zero stands for an unknown tempo
+                        let mut bpm = track.bpm.unwrap_or(0.0);
+                        if ui
+                            .add(
+                                egui::DragValue::new(&mut bpm)
+                                    .range(0.0..=300.0)
+                                    .speed(0.1)
+                                    .suffix(" bpm"),
+                            )
+                            .on_hover_text("Tempo, estimated when the track was added")
+                            .changed()
+                        {
+                            track.bpm = (bpm > 0.0).then_some(bpm);
+                        }
+                    });
+                });
+            }
+            if let Some(index) = removed {
+                music.tracks.remove(index);
+            }
+            if ui.button("Add Tracks...").clicked() {
+                add_tracks = true;
+            }
+        });
+
+        if volume_changed {
+            self.apply_music_volume();
+        }
+        if add_tracks {
+            self.add_music_tracks_dialog();
+        }
+    }
+
     fn render_gpu_info(&mut self, ui: &mut egui::Ui) {
         // GPU Information
         if let Some(ref gpu) = self.gpu_processor {
@@ -429,3 +1419,10 @@ impl ImageViewerApp {
         }
     }
 }
+
+/// A labelled single-line row of the share target grid
+fn share_field(ui: &mut egui::Ui, label: &str, value: &mut String, hint: &str) {
+    ui.label(label);
+    ui.add(egui::TextEdit::singleline(value).hint_text(hint));
+    ui.end_row();
+}
@@ -0,0 +1,103 @@
+use crate::app::ImageViewerApp;
+use crate::share::ShareService;
+use egui::{self, RichText, Vec2};
+use std::path::PathBuf;
+
+impl ImageViewerApp {
+    /// Contents of the Share submenu: one entry per upload target set up in settings
+    pub fn render_share_menu(&mut self, ui: &mut egui::Ui, paths: Vec<PathBuf>) {
+        ui.set_min_width(180.0);
+        let mut share = None;
+
+        if self.settings.share_targets.is_empty() {
+            ui.label(RichText::new("No share targets yet").weak());
+        }
+        for (index, target) in self.settings.share_targets.iter().enumerate() {
+            if ui
+                .button(&target.name)
+                .on_hover_text(target.service.kind_name())
+                .clicked()
+            {
+                share = Some(index);
+            }
+        }
+
+        ui.separator();
+        if ui.button("Share Targets...").clicked() {
+            self.show_settings_dialog = true;
+            ui.close_menu();
+        }
+
+        if let Some(index) = share {
+            self.share_to(index, paths);
+            ui.close_menu();
+        }
+    }
+
+    /// Ask for the secret key of a share target before its first upload this session
+    pub fn render_share_secret_dialog(&mut self, ctx: &egui::Context) {
+        let Some(prompt) = &mut self.share_secret_prompt else {
+            return;
+        };
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.share_secret_prompt = None;
+            return;
+        }
+        let target = self.settings.share_targets.get(prompt.target_index);
+        let name = target.map(|t| t.name.clone()).unwrap_or_default();
+        let headers = matches!(target.map(|t| &t.service), Some(ShareService::Http(_)));
+
+        let mut open = true;
+        let mut submit = false;
+        egui::Window::new(format!("Share to {}", name))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                let (response, entered) = if headers {
+                    ui.label("Header values:");
+                    let response = ui.add(
+                        egui::TextEdit::multiline(&mut prompt.secret)
+                            .desired_rows(2)
+                            .desired_width(320.0),
+                    );
+                    let entered = !prompt.secret.trim().is_empty()
+                        && !crate::share::missing_header_values(&prompt.secret);
+                    (response, entered)
+                } else {
+                    ui.label("Secret key:");
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut prompt.secret)
+                            .password(true)
+                            .desired_width(320.0),
+                    );
+                    (response, !prompt.secret.is_empty())
+                };
+                if !response.has_focus() && !entered {
+                    response.request_focus();
+                }
+                // Enter starts a new line in the headers
+                if !headers
+                    && response.lost_focus()
+                    && ui.input(|i| i.key_pressed(egui::Key::Enter))
+                {
+                    submit = entered;
+                }
+                ui.label(RichText::new("Kept until rView closes, never saved to disk").weak());
+                ui.add_space(4.0);
+                if ui
+                    .add_enabled(entered, egui::Button::new("Share"))
+                    .clicked()
+                {
+                    submit = true;
+                }
+            });
+
+        if submit {
+            self.submit_share_secret();
+        } else if !open {
+            self.share_secret_prompt = None;
+        }
+    }
+}
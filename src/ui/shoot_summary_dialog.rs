@@ -0,0 +1,150 @@
+use crate::app::ImageViewerApp;
+use crate::exif_data::format_file_size;
+use egui::{self, Color32, RichText, Vec2};
+
+/// Width of the bar for the largest count in a section
+const BAR_WIDTH: f32 = 160.0;
+
+impl ImageViewerApp {
+    pub fn render_shoot_summary_dialog(&mut self, ctx: &egui::Context) {
+        if !self.show_shoot_summary_dialog {
+            return;
+        }
+
+        // Close on escape
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.show_shoot_summary_dialog = false;
+            return;
+        }
+
+        let mut open = true;
+        let mut refresh = false;
+        let accent = self.settings.accent_color.to_color();
+
+        egui::Window::new("Shoot Summary")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(420.0)
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                let Some(summary) = &self.shoot_summary else {
+                    if self.shoot_summary_pending {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label(RichText::new("Reading EXIF...").weak());
+                        });
+                    } else {
+                        ui.label(RichText::new("No images in this folder").weak());
+                    }
+                    return;
+                };
+
+                ui.label(format!(
+                    "{} images, {}",
+                    summary.images,
+                    format_file_size(summary.total_bytes)
+                ));
+                ui.add_space(4.0);
+
+                egui::ScrollArea::vertical()
+                    .max_height(420.0)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        let ratings: Vec<_> = summary
+                            .ratings
+                            .iter()
+                            .enumerate()
+                            .rev()
+                            .map(|(stars, &count)| {
+                                let label = if stars == 0 {
+                                    "Unrated".to_string()
+                                } else {
+                                    "★".repeat(stars)
+                                };
+                                (label, count, accent)
+                            })
+                            .collect();
+                        count_section(ui, "Ratings", &ratings);
+
+                        let labels: Vec<_> = summary
+                            .labels
+                            .iter()
+                            .map(|(label, count)| {
+                                let color = if label.to_color() == Color32::TRANSPARENT {
+                                    accent
+                                } else {
+                                    label.to_color()
+                                };
+                                (label.name().to_string(), *count, color)
+                            })
+                            .collect();
+                        count_section(ui, "Labels", &labels);
+
+                        let with_accent = |counts: &[(String, usize)]| -> Vec<_> {
+                            counts
+                                .iter()
+                                .map(|(name, count)| (name.clone(), *count, accent))
+                                .collect()
+                        };
+                        count_section(ui, "Cameras", &with_accent(&summary.cameras));
+                        count_section(ui, "Lenses", &with_accent(&summary.lenses));
+                        count_section(ui, "Shots per hour", &with_accent(&summary.hours));
+                        if summary.undated > 0 {
+                            ui.label(
+                                RichText::new(format!(
+                                    "{} without a capture date",
+                                    summary.undated
+                                ))
+                                .weak(),
+                            );
+                        }
+                    });
+
+                ui.add_space(8.0);
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(!self.shoot_summary_pending, egui::Button::new("Refresh"))
+                        .on_hover_text("Count again after changing ratings or labels")
+                        .clicked()
+                    {
+                        refresh = true;
+                    }
+                    if self.shoot_summary_pending {
+                        ui.spinner();
+                    }
+                });
+            });
+
+        if refresh {
+            self.refresh_shoot_summary();
+        }
+        if !open {
+            self.show_shoot_summary_dialog = false;
+        }
+    }
+}
+
+/// A heading over rows of name, bar scaled to the largest count, and count
+fn count_section(ui: &mut egui::Ui, title: &str, rows: &[(String, usize, Color32)]) {
+    ui.add_space(6.0);
+    ui.label(RichText::new(title).strong());
+    let max = rows.iter().map(|row| row.1).max().unwrap_or(0).max(1);
+    egui::Grid::new(("shoot_summary", title))
+        .num_columns(3)
+        .spacing([8.0, 2.0])
+        .show(ui, |ui| {
+            for (name, count, color) in rows {
+                ui.label(name);
+                let (rect, _) =
+                    ui.allocate_exact_size(Vec2::new(BAR_WIDTH, 10.0), egui::Sense::hover());
+                let width = BAR_WIDTH * *count as f32 / max as f32;
+                let bar = egui::Rect::from_min_size(rect.min, Vec2::new(width, rect.height()));
+                ui.painter()
+                    .rect_filled(bar, 2.0, color.gamma_multiply(0.8));
+                ui.label(RichText::new(count.to_string()).weak());
+                ui.end_row();
+            }
+        });
+}
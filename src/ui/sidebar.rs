@@ -1,35 +1,108 @@
 use crate::app::ImageViewerApp;
+use crate::workspace::{DockSide, PanelPlacement};
 use egui::{self, Color32, Margin, Stroke};
 
 // Use the modules from the parent ui crate
-use crate::ui::{adjustments, folders, histogram, metadata, navigator};
+use crate::ui::{
+    adjustments, collections, folders, histogram, keywords, metadata, navigator, scopes,
+};
 
 const LR_BG_DARK: Color32 = Color32::from_rgb(38, 38, 38);
 const LR_BORDER: Color32 = Color32::from_rgb(28, 28, 28);
 
+/// Show a resizable panel docked where `placement` says. Returns the placement
+/// with the size the user dragged it to. `width_range` applies on the sides;
+/// docked at the bottom the panel keeps between 100 and 400 points of height.
+fn show_docked(
+    ctx: &egui::Context,
+    id: impl std::hash::Hash,
+    placement: PanelPlacement,
+    width_range: std::ops::RangeInclusive<f32>,
+    add_contents: impl FnOnce(&mut egui::Ui),
+) -> PanelPlacement {
+    let id = egui::Id::new((id, placement.side));
+    let frame = egui::Frame::NONE
+        .fill(LR_BG_DARK)
+        .stroke(Stroke::new(1.0, LR_BORDER))
+        .inner_margin(Margin::same(0));
+    let mut placement = placement;
+    match placement.side {
+        DockSide::Left | DockSide::Right => {
+            let panel = if placement.side == DockSide::Left {
+                egui::SidePanel::left(id)
+            } else {
+                egui::SidePanel::right(id)
+            };
+            placement.width = panel
+                .resizable(true)
+                .default_width(placement.width)
+                .width_range(width_range)
+                .frame(frame)
+                .show(ctx, add_contents)
+                .response
+                .rect
+                .width();
+        }
+        DockSide::Bottom => {
+            placement.height = egui::TopBottomPanel::bottom(id)
+                .resizable(true)
+                .default_height(placement.height)
+                .height_range(100.0..=400.0)
+                .frame(frame)
+                .show(ctx, add_contents)
+                .response
+                .rect
+                .height();
+        }
+    }
+    placement
+}
+
 impl ImageViewerApp {
-    /// Render the navigator panel on the left side of the screen
+    /// Render the folders, collections and navigator panel where the layout docks it
     pub fn render_navigator_left_panel(&mut self, ctx: &egui::Context) {
-        egui::SidePanel::left("navigator_panel")
-            .resizable(true)
-            .default_width(200.0)
-            .min_width(150.0)
-            .max_width(300.0)
-            .frame(
-                egui::Frame::NONE
-                    .fill(LR_BG_DARK)
-                    .stroke(Stroke::new(1.0, LR_BORDER))
-                    .inner_margin(Margin::same(0)),
-            )
-            .show(ctx, |ui| {
+        if !self.settings.panel_layout.show_navigator {
+            return;
+        }
+
+        let placement = self.settings.panel_layout.navigator;
+        let size = show_docked(
+            ctx,
+            ("navigator_panel", self.layout_generation),
+            placement,
+            150.0..=300.0,
+            |ui| {
                 egui::ScrollArea::vertical()
                     .auto_shrink([false, false])
                     .show(ui, |ui| {
                         self.render_folders_panel(ui);
                         ui.separator();
+                        self.render_collections_panel(ui);
+                        ui.separator();
                         self.render_navigator_panel(ui);
                     });
-            });
+            },
+        );
+        self.settings.panel_layout.navigator = size;
+    }
+
+    /// Render the histogram in its own panel when the layout undocks it from the sidebar
+    pub fn render_histogram_dock(&mut self, ctx: &egui::Context) {
+        let Some(placement) = self.settings.panel_layout.histogram else {
+            return;
+        };
+        if !self.settings.show_histogram {
+            return;
+        }
+
+        let size = show_docked(
+            ctx,
+            ("histogram_panel", self.layout_generation),
+            placement,
+            180.0..=400.0,
+            |ui| self.render_histogram_panel(ui),
+        );
+        self.settings.panel_layout.histogram = Some(size);
     }
 
     pub fn render_sidebar(&mut self, ctx: &egui::Context) {
@@ -37,26 +110,27 @@ impl ImageViewerApp {
             return;
         }
 
-        egui::SidePanel::right("sidebar")
-            .resizable(true)
-            .default_width(280.0)
-            .min_width(220.0)
-            .max_width(400.0)
-            .frame(
-                egui::Frame::NONE
-                    .fill(LR_BG_DARK)
-                    .stroke(Stroke::new(1.0, LR_BORDER))
-                    .inner_margin(Margin::same(0)),
-            )
-            .show(ctx, |ui| {
+        let placement = self.settings.panel_layout.sidebar;
+        let histogram_in_sidebar = self.settings.panel_layout.histogram.is_none();
+        let size = show_docked(
+            ctx,
+            ("sidebar", self.layout_generation),
+            placement,
+            220.0..=400.0,
+            |ui| {
                 egui::ScrollArea::vertical()
                     .auto_shrink([false, false])
                     .show(ui, |ui| {
                         // Histogram
-                        if self.settings.show_histogram {
+                        if self.settings.show_histogram && histogram_in_sidebar {
                             self.render_histogram_panel(ui);
                         }
 
+                        // Waveform / parade / vectorscope
+                        if self.settings.show_scopes {
+                            self.render_scopes_panel(ui);
+                        }
+
                         // Quick Develop / Basic adjustments
                         if self.settings.show_adjustments {
                             self.render_basic_panel(ui);
@@ -72,19 +146,28 @@ impl ImageViewerApp {
                             self.render_metadata_info_panel(ui);
                         }
 
+                        // Keywords
+                        self.render_keywords_panel(ui);
+
                         ui.add_space(20.0);
                     });
-            });
+            },
+        );
+        self.settings.panel_layout.sidebar = size;
     }
 
     fn render_navigator_panel(&mut self, ui: &mut egui::Ui) {
         navigator::render_navigator_panel(self, ui);
     }
 
-    fn render_histogram_panel(&self, ui: &mut egui::Ui) {
+    fn render_histogram_panel(&mut self, ui: &mut egui::Ui) {
         histogram::render_histogram_panel(self, ui);
     }
 
+    fn render_scopes_panel(&mut self, ui: &mut egui::Ui) {
+        scopes::render_scopes_panel(self, ui);
+    }
+
     fn render_basic_panel(&mut self, ui: &mut egui::Ui) {
         adjustments::render_basic_panel(self, ui);
     }
@@ -93,6 +176,14 @@ impl ImageViewerApp {
         metadata::render_metadata_info_panel(self, ui);
     }
 
+    fn render_keywords_panel(&mut self, ui: &mut egui::Ui) {
+        keywords::render_keywords_panel(self, ui);
+    }
+
+    fn render_collections_panel(&mut self, ui: &mut egui::Ui) {
+        collections::render_collections_panel(self, ui);
+    }
+
     fn render_folders_panel(&mut self, ui: &mut egui::Ui) {
         folders::render_folders_panel(self, ui);
     }
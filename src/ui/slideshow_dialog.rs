@@ -0,0 +1,269 @@
+use crate::app::ImageViewerApp;
+use crate::slideshow::VideoOptions;
+use egui::{self, Color32, RichText, Vec2};
+
+impl ImageViewerApp {
+    pub fn render_playlist_dialog(&mut self, ctx: &egui::Context) {
+        if !self.show_playlist_dialog {
+            return;
+        }
+
+        // Close on escape
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.show_playlist_dialog = false;
+            return;
+        }
+
+        let exporting = self.video_export.as_ref().is_some_and(|v| !v.is_finished());
+        let mut open = true;
+        let mut add = false;
+        let mut open_file = false;
+        let mut save_file = false;
+        let mut export = false;
+        let mut cancel = false;
+        let mut moved = None;
+        let mut removed = None;
+
+        egui::Window::new("Slideshow Playlist")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .default_width(420.0)
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let selected = self.selected_or_current_paths().len();
+                    if ui
+                        .add_enabled(selected > 0, egui::Button::new("Add Selected"))
+                        .on_hover_text("Add the selected images, or the one on screen")
+                        .clicked()
+                    {
+                        add = true;
+                    }
+                    if ui.button("Open...").clicked() {
+                        open_file = true;
+                    }
+                    if ui
+                        .add_enabled(
+                            !self.playlist.slides.is_empty(),
+                            egui::Button::new("Save..."),
+                        )
+                        .clicked()
+                    {
+                        save_file = true;
+                    }
+                    if ui
+                        .add_enabled(
+                            !self.playlist.slides.is_empty() && !exporting,
+                            egui::Button::new("Clear"),
+                        )
+                        .clicked()
+                    {
+                        self.playlist.slides.clear();
+                    }
+                });
+
+                ui.add_space(4.0);
+                let count = self.playlist.slides.len();
+                if count == 0 {
+                    ui.label(RichText::new("Select images in any folder and add them here").weak());
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(260.0)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        for (i, path) in self.playlist.slides.iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                let name = path
+                                    .file_name()
+                                    .map(|n| n.to_string_lossy().to_string())
+                                    .unwrap_or_default();
+                                ui.label(RichText::new(format!("{:>3}", i + 1)).monospace().weak());
+                                ui.label(name).on_hover_text(path.display().to_string());
+                                ui.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Center),
+                                    |ui| {
+                                        if ui.small_button("🗑").on_hover_text("Remove").clicked()
+                                        {
+                                            removed = Some(i);
+                                        }
+                                        if ui
+                                            .add_enabled(
+                                                i + 1 < count,
+                                                egui::Button::new("⏷").small(),
+                                            )
+                                            .clicked()
+                                        {
+                                            moved = Some((i, i + 1));
+                                        }
+                                        if ui
+                                            .add_enabled(i > 0, egui::Button::new("⏶").small())
+                                            .clicked()
+                                        {
+                                            moved = Some((i, i - 1));
+                                        }
+                                    },
+                                );
+                            });
+                        }
+                    });
+
+                ui.add_space(8.0);
+                ui.add_enabled_ui(!exporting, |ui| {
+                    egui::Grid::new("playlist_options")
+                        .num_columns(2)
+                        .spacing([12.0, 6.0])
+                        .show(ui, |ui| {
+                            ui.label("Each slide:");
+                            ui.add(
+                                egui::Slider::new(&mut self.playlist.slide_seconds, 1.0..=30.0)
+                                    .suffix(" s"),
+                            );
+                            ui.end_row();
+
+                            ui.label("Crossfade:");
+                            ui.add(
+                                egui::Slider::new(&mut self.playlist.transition_seconds, 0.0..=5.0)
+                                    .suffix(" s"),
+                            );
+                            ui.end_row();
+
+                            let video = &mut self.settings.slideshow_video;
+                            ui.label("Video size:");
+                            let size_name = VideoOptions::SIZES
+                                .iter()
+                                .find(|(w, h, _)| (*w, *h) == (video.width, video.height))
+                                .map_or_else(
+                                    || format!("{}×{}", video.width, video.height),
+                                    |(_, _, name)| name.to_string(),
+                                );
+                            egui::ComboBox::from_id_salt("video_size")
+                                .selected_text(size_name)
+                                .show_ui(ui, |ui| {
+                                    for (width, height, name) in VideoOptions::SIZES {
+                                        let selected =
+                                            (video.width, video.height) == (*width, *height);
+                                        let label = format!("{} ({}×{})", name, width, height);
+                                        if ui.selectable_label(selected, label).clicked() {
+                                            video.width = *width;
+                                            video.height = *height;
+                                        }
+                                    }
+                                });
+                            ui.end_row();
+
+                            ui.label("Frame rate:");
+                            egui::ComboBox::from_id_salt("video_fps")
+                                .selected_text(format!("{} fps", video.fps))
+                                .show_ui(ui, |ui| {
+                                    for fps in VideoOptions::FRAME_RATES {
+                                        ui.selectable_value(
+                                            &mut video.fps,
+                                            *fps,
+                                            format!("{} fps", fps),
+                                        );
+                                    }
+                                });
+                            ui.end_row();
+                        });
+                });
+
+                ui.add_space(4.0);
+                let seconds = self.playlist.duration().round() as u32;
+                ui.label(
+                    RichText::new(format!(
+                        "{} slides, {}:{:02} long",
+                        count,
+                        seconds / 60,
+                        seconds % 60
+                    ))
+                    .weak(),
+                );
+
+                if let Some(video) = &self.video_export {
+                    ui.add_space(8.0);
+                    let progress = video.frames_written as f32 / video.total_frames.max(1) as f32;
+                    ui.add(
+                        egui::ProgressBar::new(progress)
+                            .show_percentage()
+                            .animate(!video.is_finished()),
+                    );
+                    match &video.result {
+                        Some(Ok(())) => {
+                            ui.horizontal(|ui| {
+                                ui.label(format!("Saved {}", video.output.display()));
+                                if ui.small_button("Open").clicked() {
+                                    let _ = open::that(&video.output);
+                                }
+                            });
+                            if !video.failed.is_empty() {
+                                ui.label(
+                                    RichText::new(format!(
+                                        "{} slides couldn't be loaded and are black",
+                                        video.failed.len()
+                                    ))
+                                    .color(Color32::from_rgb(255, 120, 120)),
+                                );
+                            }
+                        }
+                        Some(Err(e)) => {
+                            ui.label(RichText::new(e).color(Color32::from_rgb(255, 120, 120)));
+                        }
+                        None => {}
+                    }
+                }
+
+                ui.add_space(8.0);
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if exporting {
+                        if ui.button("Cancel Export").clicked() {
+                            cancel = true;
+                        }
+                    } else {
+                        let ffmpeg = crate::image_loader::find_tool("ffmpeg").is_some();
+                        if ui
+                            .add_enabled(count > 0 && ffmpeg, egui::Button::new("Export Video..."))
+                            .on_disabled_hover_text(if ffmpeg {
+                                "Add slides first"
+                            } else {
+                                "Needs FFmpeg installed"
+                            })
+                            .clicked()
+                        {
+                            export = true;
+                        }
+                    }
+                });
+            });
+
+        if let Some((from, to)) = moved {
+            self.playlist.move_slide(from, to);
+        }
+        if let Some(index) = removed {
+            self.playlist.slides.remove(index);
+        }
+        if add {
+            self.add_to_playlist();
+        }
+        if open_file {
+            self.open_playlist_dialog();
+        }
+        if save_file {
+            self.save_playlist_dialog();
+        }
+        if export {
+            self.start_video_export();
+        }
+        if cancel {
+            self.cancel_video_export();
+        }
+        if !open {
+            self.show_playlist_dialog = false;
+            // A finished export's outcome isn't worth keeping once the dialog closes
+            if self.video_export.as_ref().is_some_and(|v| v.is_finished()) {
+                self.video_export = None;
+            }
+        }
+    }
+}
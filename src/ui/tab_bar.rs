@@ -0,0 +1,107 @@
+use crate::app::tabs::ImageTab;
+use crate::app::ImageViewerApp;
+use egui::{self, Color32, Margin, Stroke};
+
+impl ImageViewerApp {
+    /// One tab per open folder; hidden while only one is open
+    pub fn render_tab_bar(&mut self, ctx: &egui::Context) {
+        if self.tabs.len() < 2 {
+            return;
+        }
+
+        let mut switch_to = None;
+        let mut close = None;
+        let mut reorder = None;
+        let mut new_tab = false;
+
+        egui::TopBottomPanel::top("tab_bar")
+            .resizable(false)
+            .frame(
+                egui::Frame::NONE
+                    .fill(Color32::from_rgb(28, 28, 32))
+                    .inner_margin(Margin::symmetric(6, 3)),
+            )
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let mut rects = Vec::with_capacity(self.tabs.len());
+                    let mut dragging = None;
+                    let mut dropped = None;
+
+                    for (index, tab) in self.tabs.iter().enumerate() {
+                        let active = index == self.active_tab;
+                        // The stored copy of the active tab is only refreshed on switching
+                        let folder = if active {
+                            self.current_folder.as_deref()
+                        } else {
+                            tab.folder.as_deref()
+                        };
+                        let response = ui
+                            .add(
+                                egui::Button::new(ImageTab::title_for(folder))
+                                    .selected(active)
+                                    .sense(egui::Sense::click_and_drag()),
+                            )
+                            .on_hover_text(
+                                folder
+                                    .map(|f| f.display().to_string())
+                                    .unwrap_or_else(|| "No folder open".to_string()),
+                            );
+                        if response.clicked() {
+                            switch_to = Some(index);
+                        }
+                        if response.middle_clicked() {
+                            close = Some(index);
+                        }
+                        if response.dragged() {
+                            dragging = Some(index);
+                        }
+                        if response.drag_stopped() {
+                            dropped = Some(index);
+                        }
+                        rects.push(response.rect);
+
+                        if ui.small_button("×").on_hover_text("Close tab").clicked() {
+                            close = Some(index);
+                        }
+                        ui.add_space(4.0);
+                    }
+
+                    // Drop a dragged tab onto another tab to move it there
+                    let target = ui.ctx().pointer_latest_pos().and_then(|pointer| {
+                        rects.iter().position(|r| r.x_range().contains(pointer.x))
+                    });
+                    if let (Some(from), Some(to)) = (dragging, target) {
+                        if from != to {
+                            let x = if to < from {
+                                rects[to].left()
+                            } else {
+                                rects[to].right()
+                            };
+                            ui.painter().vline(
+                                x,
+                                rects[to].y_range(),
+                                Stroke::new(2.0, Color32::from_rgb(70, 130, 255)),
+                            );
+                        }
+                    }
+                    if let (Some(from), Some(to)) = (dropped, target) {
+                        reorder = Some((from, to));
+                    }
+
+                    if ui.small_button("+").on_hover_text("New tab").clicked() {
+                        new_tab = true;
+                    }
+                });
+            });
+
+        if let Some((from, to)) = reorder {
+            self.reorder_tab(from, to);
+        } else if let Some(index) = close {
+            self.close_tab(index);
+        } else if let Some(index) = switch_to {
+            self.switch_tab(index);
+        } else if new_tab {
+            self.new_tab();
+        }
+    }
+}
@@ -1,8 +1,48 @@
+use crate::app::compare::MAX_COMPARE;
 use crate::app::ImageViewerApp;
-use crate::settings::ThumbnailPosition;
+use crate::image_loader::ExposureWarning;
+use crate::metadata::Flag;
+use crate::settings::{ThumbnailPosition, THUMBNAIL_SIZES};
 use egui::{self, Color32, CornerRadius, Margin, Rect, Vec2};
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+/// Height of one line of the labels under thumbnails
+const THUMBNAIL_LABEL_LINE: f32 = 13.0;
 
 impl ImageViewerApp {
+    /// Room under each thumbnail for its labels
+    fn thumbnail_label_height(&self) -> f32 {
+        match self.settings.thumbnail_labels.max_lines() {
+            lines if self.settings.show_thumbnail_labels && lines > 0 => {
+                lines as f32 * THUMBNAIL_LABEL_LINE + 5.0
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Cell size and label fields, shared with the settings dialog
+    pub(crate) fn render_thumbnail_display_options(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Size:");
+            ui.add(
+                egui::Slider::new(&mut self.settings.thumbnail_size, THUMBNAIL_SIZES).suffix("px"),
+            )
+            .on_hover_text("Ctrl+scroll over the thumbnails also resizes them");
+        });
+        let show_labels = &mut self.settings.show_thumbnail_labels;
+        ui.checkbox(show_labels, "Labels under thumbnails");
+        ui.add_enabled_ui(self.settings.show_thumbnail_labels, |ui| {
+            let labels = &mut self.settings.thumbnail_labels;
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut labels.file_name, "File name");
+                ui.checkbox(&mut labels.date, "Date");
+                ui.checkbox(&mut labels.rating, "Rating");
+                ui.checkbox(&mut labels.dimensions, "Size");
+            });
+        });
+    }
+
     /// Pre-fetch thumbnails for items near the current view
     fn prefetch_visible_thumbnails(&mut self, ctx: &egui::Context) {
         // Request thumbnails for current index and nearby items
@@ -31,7 +71,7 @@ impl ImageViewerApp {
 
         let thumb_size = self.settings.thumbnail_size;
         // Add extra space for optional filename / resolution labels
-        let bar_size = thumb_size + 34.0;
+        let bar_size = thumb_size + 16.0 + self.thumbnail_label_height().max(18.0);
 
         match self.settings.thumbnail_position {
             ThumbnailPosition::Bottom => {
@@ -95,13 +135,19 @@ impl ImageViewerApp {
         ctx: &egui::Context,
         horizontal: bool,
     ) {
+        // Ctrl+scroll resizes the cells instead of scrolling
+        let hovered = ui
+            .input(|i| i.pointer.hover_pos())
+            .is_some_and(|pos| ui.max_rect().contains(pos));
+        let zoom = ui.input(|i| i.zoom_delta());
+        if hovered && zoom != 1.0 {
+            self.settings.thumbnail_size = (self.settings.thumbnail_size * zoom)
+                .clamp(*THUMBNAIL_SIZES.start(), *THUMBNAIL_SIZES.end());
+        }
+
         let thumb_size = self.settings.thumbnail_size;
         let spacing = 4.0; // Same for both orientations
-        let extra_height = if self.settings.show_thumbnail_labels {
-            18.0
-        } else {
-            0.0
-        };
+        let extra_height = self.thumbnail_label_height();
         let item_width = if horizontal {
             thumb_size + spacing
         } else {
@@ -120,7 +166,7 @@ impl ImageViewerApp {
 
             // Handle vertical mouse wheel for horizontal scrolling
             let scroll_delta = ui.input(|i| i.raw_scroll_delta);
-            if scroll_delta.y != 0.0 {
+            if scroll_delta.y != 0.0 && zoom == 1.0 {
                 let hover_pos = ui.input(|i| i.pointer.hover_pos());
                 if let Some(pos) = hover_pos {
                     if ui.max_rect().contains(pos) {
@@ -141,14 +187,14 @@ impl ImageViewerApp {
                         egui::scroll_area::ScrollBarVisibility::VisibleWhenNeeded,
                     )
                     .scroll_offset(self.thumbnail_scroll_offset)
-                    .show(ui, |ui| {
-                        ui.allocate_space(content_size);
+                    .show_viewport(ui, |ui, viewport| {
+                        ui.set_min_size(content_size);
                         self.render_visible_thumbnails(
                             ui,
                             ctx,
+                            viewport,
                             thumb_size,
                             horizontal,
-                            spacing,
                             extra_height,
                             item_width,
                             item_height,
@@ -162,7 +208,7 @@ impl ImageViewerApp {
 
             // Handle mouse wheel for vertical scrolling
             let scroll_delta = ui.input(|i| i.raw_scroll_delta);
-            if scroll_delta.y != 0.0 {
+            if scroll_delta.y != 0.0 && zoom == 1.0 {
                 let hover_pos = ui.input(|i| i.pointer.hover_pos());
                 if let Some(pos) = hover_pos {
                     if ui.max_rect().contains(pos) {
@@ -183,14 +229,14 @@ impl ImageViewerApp {
                         egui::scroll_area::ScrollBarVisibility::VisibleWhenNeeded,
                     )
                     .scroll_offset(self.thumbnail_scroll_offset)
-                    .show(ui, |ui| {
-                        ui.allocate_space(content_size);
+                    .show_viewport(ui, |ui, viewport| {
+                        ui.set_min_size(content_size);
                         self.render_visible_thumbnails(
                             ui,
                             ctx,
+                            viewport,
                             thumb_size,
                             horizontal,
-                            spacing,
                             extra_height,
                             item_width,
                             item_height,
@@ -201,39 +247,29 @@ impl ImageViewerApp {
         }
     }
 
+    /// Draw and request only the thumbnails inside `viewport`, which is in
+    /// content coordinates, so the cost doesn't grow with the folder size
     #[allow(clippy::too_many_arguments)]
     fn render_visible_thumbnails(
         &mut self,
         ui: &mut egui::Ui,
         ctx: &egui::Context,
+        viewport: Rect,
         thumb_size: f32,
         horizontal: bool,
-        spacing: f32,
         extra_height: f32,
-        _item_width: f32,
-        _item_height: f32,
+        item_width: f32,
+        item_height: f32,
     ) {
-        let visible_rect = ui.clip_rect();
-        let content_rect = ui.min_rect();
-
-        let item_width = if horizontal {
-            thumb_size + spacing
-        } else {
-            thumb_size
-        };
-        let item_height = thumb_size + extra_height + if horizontal { 0.0 } else { spacing };
-
-        let (scroll_offset, visible_size) = if horizontal {
-            (-content_rect.left(), visible_rect.width())
+        let origin = ui.min_rect().min;
+        let (start, extent, step) = if horizontal {
+            (viewport.min.x, viewport.width(), item_width)
         } else {
-            (-content_rect.top(), visible_rect.height())
+            (viewport.min.y, viewport.height(), item_height)
         };
-
-        let step = if horizontal { item_width } else { item_height };
-
-        let start_idx = (scroll_offset / step).floor() as usize;
-        let end_idx = ((scroll_offset + visible_size) / step).ceil() as usize;
-        let end_idx = end_idx.min(self.filtered_list.len());
+        let total = self.filtered_list.len();
+        let start_idx = ((start / step).floor().max(0.0) as usize).min(total);
+        let end_idx = (((start + extent) / step).ceil().max(0.0) as usize).min(total);
 
         // Request thumbnails with priority levels
         let mut priority_requests = Vec::new(); // Current image
@@ -254,11 +290,13 @@ impl ImageViewerApp {
                             _ => medium_priority_requests.push(path.clone()), // Visible but not adjacent
                         }
                     }
-                    // Request EXIF if not cached and labels are enabled
+                    // Request EXIF if not cached and the labels show any of it
+                    let labels = self.settings.thumbnail_labels;
                     if self.settings.show_thumbnail_labels
+                        && (labels.date || labels.dimensions)
                         && !self.compare_exifs.contains_key(&path)
                     {
-                        self.load_exif_data(&path);
+                        self.load_exif_data(&path, crate::task_scheduler::TaskPriority::Low);
                     }
                 }
             }
@@ -309,20 +347,28 @@ impl ImageViewerApp {
             self.ensure_thumbnail_requested(&path, ctx);
         }
 
+        // Let go of textures far from the strip's viewport once more are loaded
+        // than the thumbnail cache holds; they load again when scrolled back to
+        let keep = self.settings.thumbnail_cache_size.max(end_idx - start_idx);
+        if self.thumbnail_textures.len() > keep + keep / 4 {
+            let from = start_idx.saturating_sub(keep / 2);
+            let to = (end_idx + keep / 2).min(total);
+            let near: HashSet<&PathBuf> = self.filtered_list[from..to]
+                .iter()
+                .filter_map(|&i| self.image_list.get(i))
+                .collect();
+            self.thumbnail_textures
+                .retain(|path, _| near.contains(path));
+        }
+
         // Render visible thumbnails
         for display_idx in start_idx..end_idx {
             if let Some(&real_idx) = self.filtered_list.get(display_idx) {
                 if let Some(path) = self.image_list.get(real_idx).cloned() {
                     let pos = if horizontal {
-                        egui::pos2(
-                            content_rect.left() + display_idx as f32 * item_width,
-                            content_rect.top(),
-                        )
+                        origin + Vec2::new(display_idx as f32 * item_width, 0.0)
                     } else {
-                        egui::pos2(
-                            content_rect.left(),
-                            content_rect.top() + display_idx as f32 * item_height,
-                        )
+                        origin + Vec2::new(0.0, display_idx as f32 * item_height)
                     };
 
                     self.render_single_thumbnail(
@@ -354,7 +400,10 @@ impl ImageViewerApp {
     ) {
         let is_current = display_idx == self.current_index;
         let is_selected = self.selected_indices.contains(&display_idx);
-        let tex_id = self.thumbnail_textures.get(path).map(|h| h.id());
+        let texture = self
+            .thumbnail_textures
+            .get(path)
+            .map(|h| (h.id(), h.size_vec2()));
         let metadata = self.metadata_db.get(path);
 
         let rect = Rect::from_min_size(pos, Vec2::new(thumb_size, thumb_size + extra_height));
@@ -381,18 +430,23 @@ impl ImageViewerApp {
         painter.rect_filled(image_area, CornerRadius::same(4), bg_color);
 
         // Thumbnail image (preserve original aspect ratio) inside the reserved image area
-        if let Some(tex_id) = tex_id {
+        if let Some((tex_id, tex_size)) = texture {
             let inner_rect = image_area.shrink(3.0);
-            // Determine texture pixel size and scale to fit inside inner_rect while preserving aspect ratio
-            let tex_size = self.texture_size_from_id(tex_id);
+            // Scale the texture to fit inside inner_rect while preserving aspect ratio
             let scale = (inner_rect.width() / tex_size.x).min(inner_rect.height() / tex_size.y);
             let display_size = tex_size * scale;
             let image_rect = Rect::from_center_size(inner_rect.center(), display_size);
+            // Rejected images are dimmed so the keepers stand out
+            let tint = if metadata.rejected {
+                Color32::from_gray(90)
+            } else {
+                Color32::WHITE
+            };
             painter.image(
                 tex_id,
                 image_rect,
                 Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
-                Color32::WHITE,
+                tint,
             );
         } else {
             // Loading indicator - spinning animation (in image area)
@@ -408,30 +462,94 @@ impl ImageViewerApp {
             ui.ctx().request_repaint();
         }
 
-        // Filename and resolution label under thumbnail (optional)
-        if self.settings.show_thumbnail_labels {
-            if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
-                let mut info = String::new();
-                // Prefer cached EXIF dimensions if available, else texture size
-                if let Some(exif) = self.compare_exifs.get(path) {
-                    if let Some(dim) = exif.dimensions.clone() {
-                        info = dim;
-                    }
-                } else if let Some(tex_id) = tex_id {
-                    let tex_size = self.texture_size_from_id(tex_id);
-                    info = format!("{} × {}", tex_size.x as i32, tex_size.y as i32);
-                }
+        // Pick/reject badge
+        let badge = match metadata.flag() {
+            Flag::None => None,
+            Flag::Pick => Some(("⚑", Color32::from_rgb(60, 170, 90))),
+            Flag::Reject => Some(("✖", Color32::from_rgb(210, 60, 60))),
+        };
+        if let Some((symbol, color)) = badge {
+            let center = image_area.left_top() + Vec2::new(12.0, 12.0);
+            painter.circle_filled(center, 8.0, color);
+            painter.text(
+                center,
+                egui::Align2::CENTER_CENTER,
+                symbol,
+                egui::FontId::proportional(10.0),
+                Color32::WHITE,
+            );
+        }
 
-                let label = if info.is_empty() {
-                    file_name.to_string()
-                } else {
-                    format!("{} • {}", file_name, info)
-                };
-                let label_pos = egui::pos2(rect.center().x, image_area.bottom() + 2.0);
-                painter.text(
-                    label_pos,
+        // Heavy clipping found when the thumbnail was generated
+        let exposure = self
+            .settings
+            .thumbnail_exposure_warnings
+            .then(|| self.thumbnail_exposure.get(path))
+            .flatten();
+        if let Some(warning) = exposure {
+            // Up for blown highlights, down for crushed shadows
+            let (symbol, color) = match warning {
+                ExposureWarning::Overexposed => ("▲", Color32::from_rgb(230, 80, 60)),
+                ExposureWarning::Underexposed => ("▼", Color32::from_rgb(70, 120, 230)),
+            };
+            let center = image_area.left_bottom() + Vec2::new(12.0, -12.0);
+            painter.circle_filled(center, 8.0, color);
+            painter.text(
+                center,
+                egui::Align2::CENTER_CENTER,
+                symbol,
+                egui::FontId::proportional(9.0),
+                Color32::WHITE,
+            );
+        }
+
+        // The file hidden behind this one in a RAW+JPEG pair
+        if let Some(companion) = self.pair_companion(path) {
+            let extension = companion
+                .extension()
+                .map(|e| format!("+{}", e.to_string_lossy().to_uppercase()))
+                .unwrap_or_default();
+            let anchor = image_area.right_top() + Vec2::new(-4.0, 4.0);
+            let galley =
+                painter.layout_no_wrap(extension, egui::FontId::proportional(9.0), Color32::WHITE);
+            let badge_rect = egui::Align2::RIGHT_TOP
+                .anchor_size(anchor, galley.size())
+                .expand2(Vec2::new(3.0, 1.0));
+            painter.rect_filled(
+                badge_rect,
+                CornerRadius::same(3),
+                Color32::from_rgba_unmultiplied(0, 0, 0, 170),
+            );
+            painter.galley(
+                badge_rect.shrink2(Vec2::new(3.0, 1.0)).min,
+                galley,
+                Color32::WHITE,
+            );
+        }
+
+        // File name, date, rating and resolution under the thumbnail (optional)
+        if self.settings.show_thumbnail_labels {
+            let file_name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let exif = self.compare_exifs.get(path);
+            // Prefer cached EXIF dimensions if available, else texture size
+            let dimensions = exif.and_then(|e| e.dimensions.clone()).or_else(|| {
+                texture.map(|(_, size)| format!("{} × {}", size.x as i32, size.y as i32))
+            });
+            let lines = self.settings.thumbnail_labels.lines(
+                &file_name,
+                exif.and_then(|e| e.date_taken.as_deref()),
+                metadata.rating,
+                dimensions.as_deref(),
+            );
+            for (line, text) in lines.into_iter().enumerate() {
+                let top = image_area.bottom() + 2.0 + line as f32 * THUMBNAIL_LABEL_LINE;
+                painter.with_clip_rect(rect).text(
+                    egui::pos2(rect.center().x, top),
                     egui::Align2::CENTER_TOP,
-                    label,
+                    text,
                     egui::FontId::proportional(10.0),
                     Color32::from_rgb(200, 200, 200),
                 );
@@ -445,8 +563,11 @@ impl ImageViewerApp {
             egui::Sense::click_and_drag(),
         );
 
-        // Drag source for drag-and-drop to collections
+        // Drag source for drag-and-drop to collections, and out to other apps
         response.dnd_set_drag_payload(path.clone());
+        if response.drag_started() {
+            self.begin_drag_out(Some(display_idx));
+        }
 
         if response.clicked() {
             if ui.input(|i| i.modifiers.ctrl) {
@@ -481,13 +602,148 @@ impl ImageViewerApp {
                 self.go_to_index(display_idx);
                 ui.close_menu();
             }
+            let selected = self.selected_indices.len();
+            if selected > 1 && self.selected_indices.contains(&display_idx) {
+                if ui
+                    .add_enabled(
+                        selected <= MAX_COMPARE,
+                        egui::Button::new(format!("Compare {} Selected", selected)),
+                    )
+                    .on_disabled_hover_text(format!("Select up to {} images", MAX_COMPARE))
+                    .clicked()
+                {
+                    self.start_compare();
+                    ui.close_menu();
+                }
+            }
 
             ui.separator();
-            if ui.button("Delete").clicked() {
+            let can_paste = self.copied_adjustments.is_some();
+            if self.selected_indices.len() > 1 && self.selected_indices.contains(&display_idx) {
+                if ui
+                    .add_enabled(
+                        can_paste,
+                        egui::Button::new(format!(
+                            "Paste Adjustments to {} Selected",
+                            self.selected_indices.len()
+                        )),
+                    )
+                    .clicked()
+                {
+                    self.paste_adjustments_to_selected();
+                    ui.close_menu();
+                }
+            } else if ui
+                .add_enabled(can_paste, egui::Button::new("Paste Adjustments"))
+                .clicked()
+            {
+                self.paste_adjustments_to_paths(vec![path.clone()]);
+                ui.close_menu();
+            }
+            if self.selected_indices.len() > 1 && self.selected_indices.contains(&display_idx) {
+                if ui
+                    .button(format!(
+                        "Auto-enhance {} Selected",
+                        self.selected_indices.len()
+                    ))
+                    .clicked()
+                {
+                    self.auto_enhance_selected();
+                    ui.close_menu();
+                }
+            } else if ui.button("Auto-enhance").clicked() {
+                self.auto_enhance_paths(vec![path.clone()]);
+                ui.close_menu();
+            }
+
+            ui.separator();
+            // Batch export covers the selection, or everything shown when nothing is selected
+            let export_label = if self.selected_indices.is_empty() {
+                format!("Export All {}...", self.filtered_list.len())
+            } else {
+                format!("Export {} Selected...", self.selected_indices.len())
+            };
+            if ui.button(export_label).clicked() {
+                self.start_batch_export();
+                ui.close_menu();
+            }
+            if ui.button("Contact Sheet...").clicked() {
+                self.show_contact_sheet_dialog = true;
+                ui.close_menu();
+            }
+            if ui.button("Web Gallery...").clicked() {
+                self.show_web_gallery_dialog = true;
+                ui.close_menu();
+            }
+            if ui.button("Add to Slideshow Playlist").clicked() {
+                self.add_to_playlist();
+                ui.close_menu();
+            }
+            ui.menu_button("Share", |ui| {
+                let paths = if self.selected_indices.contains(&display_idx) {
+                    self.selected_paths()
+                } else {
+                    vec![path.clone()]
+                };
+                self.render_share_menu(ui, paths);
+            });
+
+            ui.separator();
+            let in_selection = self.selected_indices.contains(&display_idx);
+            let rename_label = if in_selection && self.selected_indices.len() > 1 {
+                format!("Rename {} Selected...", self.selected_indices.len())
+            } else {
+                "Rename...".to_string()
+            };
+            if ui.button(rename_label).clicked() {
+                if !in_selection {
+                    self.selected_indices.clear();
+                    self.go_to_index(display_idx);
+                }
+                self.open_rename_dialog();
+                ui.close_menu();
+            }
+            let batch = in_selection && self.selected_indices.len() > 1;
+            let (move_label, delete_label) = if batch {
+                let n = self.selected_indices.len();
+                (
+                    format!("Move {} Selected...", n),
+                    format!("Delete {} Selected", n),
+                )
+            } else {
+                ("Move or Copy...".to_string(), "Delete".to_string())
+            };
+            if ui.button(move_label).clicked() {
+                if !in_selection {
+                    self.selected_indices.clear();
+                    self.go_to_index(display_idx);
+                }
+                self.show_move_dialog = true;
+                ui.close_menu();
+            }
+            if ui.button(delete_label).clicked() {
+                if !in_selection {
+                    self.selected_indices.clear();
+                }
                 self.current_index = display_idx;
                 self.delete_current_image();
                 ui.close_menu();
             }
+
+            ui.separator();
+            ui.menu_button("Thumbnails", |ui| self.render_thumbnail_display_options(ui));
+            if ui.button("Move Rejected to Folder...").clicked() {
+                if self.select_rejected() {
+                    self.show_move_dialog = true;
+                }
+                ui.close_menu();
+            }
+            if ui.button("Delete Rejected").clicked() {
+                if self.select_rejected() {
+                    self.delete_current_image();
+                }
+                ui.close_menu();
+            }
         });
     }
 }
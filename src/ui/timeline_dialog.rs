@@ -0,0 +1,140 @@
+use crate::app::ImageViewerApp;
+use crate::timeline::heat;
+use chrono::{Datelike, NaiveDate};
+use egui::{self, Color32, RichText, Stroke, Vec2};
+
+/// Side of one day in the calendar strip
+const DAY_SIZE: f32 = 12.0;
+
+impl ImageViewerApp {
+    /// Calendar of the folder or catalog by capture date; clicking a day
+    /// lists only that day's images
+    pub fn render_timeline_dialog(&mut self, ctx: &egui::Context) {
+        if !self.show_timeline_dialog {
+            return;
+        }
+
+        // Close on escape
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.show_timeline_dialog = false;
+            return;
+        }
+
+        let mut open = true;
+        let mut scope_changed = false;
+        let mut picked: Option<Option<NaiveDate>> = None;
+        let accent = self.settings.accent_color.to_color();
+
+        egui::Window::new("Timeline")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    scope_changed |= ui
+                        .radio_value(&mut self.timeline_catalog, false, "This folder")
+                        .changed();
+                    scope_changed |= ui
+                        .radio_value(&mut self.timeline_catalog, true, "Whole catalog")
+                        .on_hover_text("Every image whose capture date has been read")
+                        .changed();
+                    if self.capture_dates_pending {
+                        ui.spinner();
+                    }
+                });
+
+                let timeline = &self.timeline;
+                ui.label(
+                    RichText::new(format!(
+                        "{} images on {} days",
+                        timeline.total(),
+                        timeline.days.len()
+                    ))
+                    .weak(),
+                );
+                if let Some(day) = self.date_filter {
+                    ui.horizontal(|ui| {
+                        ui.label(format!(
+                            "Showing {} ({} images)",
+                            day.format("%Y-%m-%d"),
+                            timeline.count(day)
+                        ));
+                        if ui.small_button("Show All").clicked() {
+                            picked = Some(None);
+                        }
+                    });
+                }
+                ui.add_space(4.0);
+
+                if timeline.days.is_empty() {
+                    ui.label(RichText::new("No dated images").weak());
+                    return;
+                }
+                let busiest = timeline.busiest();
+                egui::ScrollArea::vertical()
+                    .max_height(420.0)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        egui::Grid::new("timeline_months")
+                            .num_columns(2)
+                            .spacing([8.0, 3.0])
+                            .show(ui, |ui| {
+                                for (month, counts) in timeline.months() {
+                                    ui.label(
+                                        RichText::new(month.format("%b %Y").to_string()).weak(),
+                                    );
+                                    ui.horizontal(|ui| {
+                                        ui.spacing_mut().item_spacing.x = 2.0;
+                                        for (i, &count) in counts.iter().enumerate() {
+                                            let day = month.with_day(i as u32 + 1).unwrap_or(month);
+                                            let sense = if count > 0 {
+                                                egui::Sense::click()
+                                            } else {
+                                                egui::Sense::hover()
+                                            };
+                                            let (rect, response) = ui
+                                                .allocate_exact_size(Vec2::splat(DAY_SIZE), sense);
+                                            let fill = if count == 0 {
+                                                Color32::from_gray(45)
+                                            } else {
+                                                accent.gamma_multiply(
+                                                    0.25 + 0.75 * heat(count, busiest),
+                                                )
+                                            };
+                                            ui.painter().rect_filled(rect, 2.0, fill);
+                                            if self.date_filter == Some(day) {
+                                                ui.painter().rect_stroke(
+                                                    rect,
+                                                    2.0,
+                                                    Stroke::new(1.5, Color32::WHITE),
+                                                    egui::StrokeKind::Outside,
+                                                );
+                                            }
+                                            let response = response.on_hover_text(format!(
+                                                "{}: {} images",
+                                                day.format("%a %Y-%m-%d"),
+                                                count
+                                            ));
+                                            if response.clicked() {
+                                                picked = Some(Some(day));
+                                            }
+                                        }
+                                    });
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            });
+
+        if scope_changed {
+            self.refresh_timeline();
+        }
+        if let Some(day) = picked {
+            self.show_timeline_day(day);
+        }
+        if !open {
+            self.show_timeline_dialog = false;
+        }
+    }
+}
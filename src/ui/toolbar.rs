@@ -1,4 +1,5 @@
-use crate::app::{ImageViewerApp, ViewMode};
+use crate::app::{ImageViewerApp, SplitView, ViewMode};
+use crate::i18n::tr;
 
 use egui::{self, Color32, CornerRadius, FontFamily, FontId, Margin, RichText, Vec2};
 use iconflow::{try_icon, Pack, Size, Style};
@@ -18,6 +19,8 @@ fn lucide_font() -> &'static str {
 }
 
 impl ImageViewerApp {
+    // Laid out by hand, one control per line
+    #[rustfmt::skip]
     pub fn render_toolbar(&mut self, ctx: &egui::Context) {
         // Collect state needed for decisions
         let current_index = self.current_index;
@@ -28,6 +31,7 @@ impl ImageViewerApp {
         let is_fullscreen = self.is_fullscreen;
         let show_focus_peaking = self.settings.show_focus_peaking;
         let show_zebras = self.settings.show_zebras;
+        let show_clipping = self.settings.show_clipping;
         let show_grid_overlay = self.settings.show_grid_overlay;
         let show_custom_overlay = self.settings.show_custom_overlay;
         let show_frame = self.settings.show_frame;
@@ -51,20 +55,28 @@ impl ImageViewerApp {
         let mut rotate_right = false;
         let mut toggle_crop_mode = false;
         let mut apply_crop = false;
+        let mut toggle_straighten_mode = false;
         let mut set_view_single = false;
+        let mut toggle_scroll_view = false;
         let mut toggle_focus_peaking = false;
         let mut toggle_zebras = false;
+        let mut toggle_clipping = false;
         let mut toggle_grid = false;
         let mut toggle_loupe = false;
         let mut toggle_panels = false;
         let mut toggle_fullscreen = false;
+        let mut toggle_mini_viewer = false;
         let mut show_settings = false;
         let mut show_command_palette = false;
 
         let mut toggle_load_raw = false;
+        let mut toggle_animation = false;
         let mut toggle_before_after = false;
+        let mut toggle_split = false;
         let mut search_changed = false;
         let mut toggle_search = false;
+        let mut toggle_mute = false;
+        let mut volume_changed = false;
 
         egui::TopBottomPanel::top("toolbar")
             .frame(egui::Frame::NONE
@@ -76,16 +88,21 @@ impl ImageViewerApp {
                         ui.spacing_mut().item_spacing = Vec2::new(4.0, 0.0);
 
                     // File operations - using Lucide icons
-                    if icon_button(ui, lucide("folder-open"), "Open folder (Ctrl+Shift+O)").clicked() {
+                    if icon_button(ui, lucide("folder-open"), "toolbar-open-folder").clicked() {
                         open_folder = true;
                     }
-                    if icon_button(ui, lucide("file"), "Open file (Ctrl+O)").clicked() {
+                    if icon_button(ui, lucide("file"), "toolbar-open-file").clicked() {
                         open_file = true;
                     }
-                    if icon_button(ui, lucide("folder-input"), "Move to folder (M)").clicked() {
+                    let history_icon = RichText::new(lucide("history").to_string())
+                        .font(FontId::new(16.0, FontFamily::Name(lucide_font().into())));
+                    ui.menu_button(history_icon, |ui| self.render_recent_menu(ui))
+                        .response
+                        .on_hover_text(tr("toolbar-recent"));
+                    if icon_button(ui, lucide("folder-input"), "toolbar-move").clicked() {
                         show_move = true;
                     }
-                    if icon_button(ui, lucide("download"), "Export image (Ctrl+S)").clicked() {
+                    if icon_button(ui, lucide("download"), "toolbar-export").clicked() {
                         export_image = true;
                     }
 
@@ -94,7 +111,7 @@ impl ImageViewerApp {
                     ui.add_space(8.0);
 
                     // Search button
-                    if toggle_button(ui, lucide("search"), "Toggle search (Ctrl+F)", self.search_visible).clicked() {
+                    if toggle_button(ui, lucide("search"), "toolbar-search", self.search_visible).clicked() {
                         toggle_search = true;
                     }
 
@@ -104,13 +121,13 @@ impl ImageViewerApp {
                             ui.label(RichText::new("🔍").size(14.0));
                             let search_response = ui.add(
                                 egui::TextEdit::singleline(&mut self.search_query)
-                                    .hint_text("Search images...")
+                                    .hint_text(tr("toolbar-search-hint"))
                                     .desired_width(150.0)
-                            );
+                            ).on_hover_text(tr("toolbar-search-help"));
                             if search_response.changed() {
                                 search_changed = true;
                             }
-                            if !self.search_query.is_empty() && ui.add(egui::Button::new("✕").small()).on_hover_text("Clear search").clicked() {
+                            if !self.search_query.is_empty() && ui.add(egui::Button::new("✕").small()).on_hover_text(tr("toolbar-clear-search")).clicked() {
                                 self.search_query.clear();
                                 search_changed = true;
                             }
@@ -122,7 +139,7 @@ impl ImageViewerApp {
                     }
 
                     // Navigation (previous / next)
-                    if icon_button(ui, lucide("chevron-left"), "Previous image (←)").clicked() {
+                    if icon_button(ui, lucide("chevron-left"), "toolbar-previous").clicked() {
                         go_prev = true;
                     }
 
@@ -134,22 +151,62 @@ impl ImageViewerApp {
                         ).fill(Color32::from_rgb(40, 40, 45))
                          .corner_radius(CornerRadius::same(4))
                          .min_size(Vec2::new(60.0, 24.0)))
-                         .on_hover_text("Go to image (Ctrl+G)")
+                         .on_hover_text(tr("toolbar-go-to"))
                          .clicked() {
                             show_go_to = true;
                         }
                     }
 
-                    if icon_button(ui, lucide("chevron-right"), "Next image (→)").clicked() {
+                    if icon_button(ui, lucide("chevron-right"), "toolbar-next").clicked() {
                         go_next = true;
                     }
 
+                    // Animation playback (only for animated GIF/APNG/WebP)
+                    if let Some(anim) = &self.animation {
+                        let (icon, tip) = if anim.playing {
+                            (lucide("pause"), "toolbar-pause-animation")
+                        } else {
+                            (lucide("play"), "toolbar-play-animation")
+                        };
+                        if icon_button(ui, icon, tip).clicked() {
+                            toggle_animation = true;
+                        }
+                        ui.label(
+                            RichText::new(format!("{}/{}", anim.current_frame + 1, anim.frames.len()))
+                                .size(11.0)
+                                .color(Color32::from_rgb(140, 140, 140)),
+                        );
+                    } else if let Some(video) = &self.video {
+                        let (icon, tip) = if video.playing {
+                            (lucide("pause"), "toolbar-pause-video")
+                        } else {
+                            (lucide("play"), "toolbar-play-video")
+                        };
+                        if icon_button(ui, icon, tip).clicked() {
+                            toggle_animation = true;
+                        }
+                    }
+
+                    // Slideshow music
+                    if self.music.is_some() {
+                        let muted = self.settings.music.muted;
+                        let icon = if muted { lucide("volume-x") } else { lucide("volume-2") };
+                        if toggle_button(ui, icon, "toolbar-mute", muted).clicked() {
+                            toggle_mute = true;
+                        }
+                        let slider = egui::Slider::new(&mut self.settings.music.volume, 0.0..=1.0)
+                            .show_value(false);
+                        if ui.add_sized(Vec2::new(60.0, 20.0), slider).on_hover_text(tr("toolbar-volume")).changed() {
+                            volume_changed = true;
+                        }
+                    }
+
                     ui.add_space(8.0);
                     toolbar_separator(ui);
                     ui.add_space(8.0);
 
                     // Zoom controls
-                    if icon_button(ui, lucide("zoom-out"), "Zoom out (-)").clicked() {
+                    if icon_button(ui, lucide("zoom-out"), "toolbar-zoom-out").clicked() {
                         zoom_out = true;
                     }
 
@@ -161,14 +218,14 @@ impl ImageViewerApp {
                         new_zoom = Some(zoom_pct as f32 / 100.0);
                     }
 
-                    if icon_button(ui, lucide("zoom-in"), "Zoom in (+)").clicked() {
+                    if icon_button(ui, lucide("zoom-in"), "toolbar-zoom-in").clicked() {
                         zoom_in = true;
                     }
 
                     // Zoom presets as buttons (Fit / Fill / 100%)
                     ui.horizontal(|ui| {
-                        if ui.add(egui::Button::new("Fit").min_size(Vec2::new(48.0, 22.0))).clicked() { fit_window = true; }
-                        if ui.add(egui::Button::new("Fill").min_size(Vec2::new(48.0, 22.0))).clicked() { fill_window = true; }
+                        if ui.add(egui::Button::new(tr("toolbar-fit")).min_size(Vec2::new(48.0, 22.0))).clicked() { fit_window = true; }
+                        if ui.add(egui::Button::new(tr("toolbar-fill")).min_size(Vec2::new(48.0, 22.0))).clicked() { fill_window = true; }
                         if ui.add(egui::Button::new("100%").min_size(Vec2::new(48.0, 22.0))).clicked() { new_zoom = Some(1.0); }
                     });
 
@@ -177,84 +234,108 @@ impl ImageViewerApp {
                     ui.add_space(8.0);
 
                     // Rotation
-                    if icon_button(ui, lucide("rotate-ccw"), "Rotate left (L)").clicked() {
+                    if icon_button(ui, lucide("rotate-ccw"), "toolbar-rotate-left").clicked() {
                         rotate_left = true;
                     }
-                    if icon_button(ui, lucide("rotate-cw"), "Rotate right (R)").clicked() {
+                    if icon_button(ui, lucide("rotate-cw"), "toolbar-rotate-right").clicked() {
                         rotate_right = true;
                     }
 
                     // Cropping
-                    if toggle_button(ui, lucide("crop"), "Toggle crop mode (C)", self.crop_mode).clicked() {
+                    if toggle_button(ui, lucide("crop"), "toolbar-crop", self.crop_mode).clicked() {
                         toggle_crop_mode = true;
                     }
-                    if self.crop_mode && icon_button(ui, lucide("check"), "Apply crop").clicked() {
+                    if self.crop_mode && icon_button(ui, lucide("check"), "toolbar-apply-crop").clicked() {
                         apply_crop = true;
                     }
+                    if toggle_button(ui, lucide("ruler"), "toolbar-straighten", self.straighten_mode).clicked() {
+                        toggle_straighten_mode = true;
+                    }
 
                     ui.add_space(8.0);
                     toolbar_separator(ui);
                     ui.add_space(8.0);
 
                     // View modes
-                    if toggle_button(ui, lucide("image"), "Single view", view_mode == ViewMode::Single).clicked() {
+                    if toggle_button(ui, lucide("image"), "toolbar-single-view", view_mode == ViewMode::Single).clicked() {
                         set_view_single = true;
                     }
+                    if toggle_button(ui, lucide("gallery-vertical"), "toolbar-scroll-view", view_mode == ViewMode::Scroll).clicked() {
+                        toggle_scroll_view = true;
+                    }
 
                     ui.add_space(8.0);
                     toolbar_separator(ui);
                     ui.add_space(8.0);
 
                     // Photography tools
-                    if toggle_button(ui, lucide("focus"), "Focus peaking (Ctrl+F)", show_focus_peaking).clicked() {
+                    if toggle_button(ui, lucide("focus"), "toolbar-focus-peaking", show_focus_peaking).clicked() {
                         toggle_focus_peaking = true;
                     }
-                    if toggle_button(ui, lucide("zap"), "Zebras (Alt+Z)", show_zebras).clicked() {
+                    if toggle_button(ui, lucide("zap"), "toolbar-zebras", show_zebras).clicked() {
                         toggle_zebras = true;
                     }
-                    if toggle_button(ui, lucide("grid-3x3"), "Grid overlay", show_grid_overlay).clicked() {
+                    if toggle_button(ui, lucide("contrast"), "toolbar-clipping", show_clipping).clicked() {
+                        toggle_clipping = true;
+                    }
+                    if toggle_button(ui, lucide("grid-3x3"), "toolbar-grid", show_grid_overlay).clicked() {
                         toggle_grid = true;
                     }
-                    if toggle_button(ui, lucide("search"), "Loupe (Ctrl+L)", loupe_enabled).clicked() {
+                    if toggle_button(ui, lucide("search"), "toolbar-loupe", loupe_enabled).clicked() {
                         toggle_loupe = true;
                     }
 
                     // Toggle whether to decode full-size RAW files or only use embedded JPEG previews
-                    if toggle_button(ui, "RAW", "Load full-size RAW files (toggle). When off, only embedded JPEG previews are used.", load_raw_full_size).clicked() {
+                    if toggle_button(ui, "RAW", "toolbar-raw", load_raw_full_size).clicked() {
                         toggle_load_raw = true;
                     }
 
                     // EXIF overlay toggle (only controls overlay, not sidebar panel)
-                    if toggle_button(ui, lucide("info"), "Toggle EXIF overlay (E)", self.settings.show_exif_overlay).clicked() {
+                    if toggle_button(ui, lucide("info"), "toolbar-exif-overlay", self.settings.show_exif_overlay).clicked() {
                         self.settings.show_exif_overlay = !self.settings.show_exif_overlay;
                     }
 
                     // Before/After toggle (only enabled when adjustments are applied)
-                    if !self.adjustments.is_default() && toggle_button(ui, lucide("arrow-left-right"), "Toggle before/after view (\\)", self.show_original).clicked() {
+                    if !self.adjustments.is_default() && toggle_button(ui, lucide("arrow-left-right"), "toolbar-before-after", self.show_original).clicked() {
                         toggle_before_after = true;
                     }
+                    if !self.adjustments.is_default() && toggle_button(ui, lucide("columns-2"), "toolbar-split", self.split_view != SplitView::Off).clicked() {
+                        toggle_split = true;
+                    }
 
                     // Right side
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                         // Settings (toggle)
-                        if icon_button(ui, lucide("settings"), "Settings").clicked() {
+                        if icon_button(ui, lucide("settings"), "toolbar-settings").clicked() {
                             show_settings = true;
                         }
 
-                        if icon_button(ui, lucide("command"), "Command palette (Ctrl+P)").clicked() {
+                        if icon_button(ui, lucide("command"), "toolbar-command-palette").clicked() {
                             show_command_palette = true;
                         }
 
                         // Fullscreen
-                        if toggle_button(ui, lucide("maximize"), "Fullscreen (F11)", is_fullscreen).clicked() {
+                        if toggle_button(ui, lucide("maximize"), "toolbar-fullscreen", is_fullscreen).clicked() {
                             toggle_fullscreen = true;
                         }
 
+                        // Mini viewer
+                        if icon_button(ui, lucide("picture-in-picture-2"), "toolbar-mini-viewer").clicked() {
+                            toggle_mini_viewer = true;
+                        }
+
                         // Toggle panels
-                        if toggle_button(ui, lucide("panel-left"), "Toggle all panels (P)", self.panels_hidden).clicked() {
+                        if toggle_button(ui, lucide("panel-left"), "toolbar-panels", self.panels_hidden).clicked() {
                             toggle_panels = true;
                         }
 
+                        // Panel layout and workspaces
+                        let layout_icon = RichText::new(lucide("layout-dashboard").to_string())
+                            .font(FontId::new(16.0, FontFamily::Name(lucide_font().into())));
+                        ui.menu_button(layout_icon, |ui| self.render_layout_menu(ui))
+                            .response
+                            .on_hover_text(tr("toolbar-layout"));
+
                         // (Slideshow removed per user preference)
                         toolbar_separator(ui);
 
@@ -275,7 +356,7 @@ impl ImageViewerApp {
             self.show_move_dialog = true;
         }
         if export_image {
-            self.export_image();
+            self.open_export_dialog();
         }
         if go_prev {
             self.previous_image();
@@ -316,43 +397,60 @@ impl ImageViewerApp {
         if apply_crop {
             self.apply_crop();
         }
+        if toggle_straighten_mode {
+            self.toggle_straighten_mode();
+        }
         if set_view_single {
             self.view_mode = ViewMode::Single;
         }
+        if toggle_scroll_view {
+            self.toggle_scroll_view();
+        }
 
         if toggle_focus_peaking {
-            self.settings.show_focus_peaking = !self.settings.show_focus_peaking;
-            if self.settings.show_focus_peaking {
-                if let Some(img) = self.current_image.as_ref().cloned() {
-                    self.generate_focus_peaking_overlay(&img, ctx);
-                }
-            }
+            self.toggle_focus_peaking();
         }
         if toggle_zebras {
-            self.settings.show_zebras = !self.settings.show_zebras;
-            if self.settings.show_zebras {
-                if let Some(img) = self.current_image.as_ref().cloned() {
-                    self.generate_zebra_overlay(&img, ctx);
-                }
-            }
+            self.toggle_zebras();
+        }
+        if toggle_clipping {
+            self.toggle_clipping_warning();
         }
         if toggle_grid {
             self.settings.show_grid_overlay = !self.settings.show_grid_overlay;
         }
         if toggle_loupe {
-            self.settings.loupe_enabled = !self.settings.loupe_enabled;
+            self.toggle_loupe();
         }
         if toggle_panels {
             self.toggle_panels();
         }
         if toggle_fullscreen {
-            self.is_fullscreen = !self.is_fullscreen;
-            ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(self.is_fullscreen));
+            self.toggle_fullscreen();
+        }
+        if toggle_mini_viewer {
+            self.toggle_mini_viewer();
+        }
+        if toggle_animation {
+            if self.animation.is_some() {
+                self.toggle_animation_playback();
+            } else {
+                self.toggle_video_playback();
+            }
+        }
+        if toggle_mute {
+            self.toggle_music_mute();
+        }
+        if volume_changed {
+            self.apply_music_volume();
         }
         if toggle_before_after {
             self.show_original = !self.show_original;
             self.refresh_adjustments();
         }
+        if toggle_split {
+            self.toggle_split_view();
+        }
 
         // Handle RAW load toggle change: flip the setting and, if enabling full RAW and current item is a RAW with only a preview, start full load
         if toggle_load_raw {
@@ -377,7 +475,7 @@ impl ImageViewerApp {
         }
 
         if show_command_palette {
-            self.command_palette_open = true;
+            self.open_command_palette();
         }
 
         if toggle_search {
@@ -389,7 +487,8 @@ impl ImageViewerApp {
     }
 }
 
-fn icon_button<T: ToString>(ui: &mut egui::Ui, icon: T, tooltip: &str) -> egui::Response {
+/// Icon button whose tooltip is the message `tooltip_key`
+fn icon_button<T: ToString>(ui: &mut egui::Ui, icon: T, tooltip_key: &str) -> egui::Response {
     let font_id = FontId::new(16.0, FontFamily::Name(lucide_font().into()));
     ui.add(
         egui::Button::new(RichText::new(icon.to_string()).font(font_id))
@@ -397,13 +496,13 @@ fn icon_button<T: ToString>(ui: &mut egui::Ui, icon: T, tooltip: &str) -> egui::
             .corner_radius(CornerRadius::same(4))
             .min_size(Vec2::new(28.0, 28.0)),
     )
-    .on_hover_text(tooltip)
+    .on_hover_text(tr(tooltip_key))
 }
 
 fn toggle_button<T: ToString>(
     ui: &mut egui::Ui,
     icon: T,
-    tooltip: &str,
+    tooltip_key: &str,
     active: bool,
 ) -> egui::Response {
     let bg = if active {
@@ -419,7 +518,7 @@ fn toggle_button<T: ToString>(
             .corner_radius(CornerRadius::same(4))
             .min_size(Vec2::new(28.0, 28.0)),
     )
-    .on_hover_text(tooltip)
+    .on_hover_text(tr(tooltip_key))
 }
 
 fn toolbar_separator(ui: &mut egui::Ui) {
@@ -0,0 +1,132 @@
+use crate::app::ImageViewerApp;
+use egui::{self, Color32, RichText, Vec2};
+
+impl ImageViewerApp {
+    pub fn render_web_gallery_dialog(&mut self, ctx: &egui::Context) {
+        if !self.show_web_gallery_dialog {
+            return;
+        }
+
+        // Close on escape
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.show_web_gallery_dialog = false;
+            self.web_gallery_result = None;
+            return;
+        }
+
+        let count = if self.selected_indices.is_empty() {
+            self.filtered_list.len()
+        } else {
+            self.selected_indices.len()
+        };
+        let running = self.web_gallery_running;
+        let mut open = true;
+        let mut create = false;
+        let mut close = false;
+
+        egui::Window::new("Web Gallery")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .default_width(360.0)
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                let options = &mut self.settings.web_gallery;
+                ui.add_enabled_ui(!running, |ui| {
+                    egui::Grid::new("web_gallery_options")
+                        .num_columns(2)
+                        .spacing([12.0, 6.0])
+                        .show(ui, |ui| {
+                            ui.label("Title:");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut options.title)
+                                    .hint_text("Gallery")
+                                    .desired_width(220.0),
+                            );
+                            ui.end_row();
+
+                            ui.label("Image size:");
+                            ui.add(
+                                egui::Slider::new(&mut options.image_size, 800..=4096)
+                                    .suffix(" px"),
+                            );
+                            ui.end_row();
+
+                            ui.label("Thumbnail size:");
+                            ui.add(
+                                egui::Slider::new(&mut options.thumbnail_size, 150..=800)
+                                    .suffix(" px"),
+                            );
+                            ui.end_row();
+                        });
+
+                    ui.add_space(4.0);
+                    ui.checkbox(&mut options.captions, "File name captions");
+                });
+
+                ui.add_space(8.0);
+                ui.label(
+                    RichText::new(format!(
+                        "{} images, written with index.html into the chosen folder",
+                        count
+                    ))
+                    .weak(),
+                );
+
+                if let Some(result) = &self.web_gallery_result {
+                    ui.add_space(8.0);
+                    ui.label(format!("Created gallery with {} images", result.exported));
+                    if !result.failed.is_empty() {
+                        ui.label(
+                            RichText::new(format!("{} failed", result.failed.len()))
+                                .color(Color32::from_rgb(255, 120, 120)),
+                        )
+                        .on_hover_text(
+                            result
+                                .failed
+                                .iter()
+                                .map(|(path, error)| format!("{}: {}", path.display(), error))
+                                .collect::<Vec<_>>()
+                                .join("\n"),
+                        );
+                    }
+                    ui.horizontal(|ui| {
+                        if ui.button("Open in Browser").clicked() {
+                            let _ = open::that(&result.index);
+                        }
+                        if ui.button("Open Folder").clicked() {
+                            if let Some(folder) = result.index.parent() {
+                                let _ = open::that(folder);
+                            }
+                        }
+                    });
+                }
+
+                ui.add_space(8.0);
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if running {
+                        ui.spinner();
+                        ui.label("Creating web gallery...");
+                    } else {
+                        if ui
+                            .add_enabled(count > 0, egui::Button::new("Generate..."))
+                            .clicked()
+                        {
+                            create = true;
+                        }
+                        if ui.button("Close").clicked() {
+                            close = true;
+                        }
+                    }
+                });
+            });
+
+        if create {
+            self.start_web_gallery();
+        } else if close || !open {
+            self.show_web_gallery_dialog = false;
+            self.web_gallery_result = None;
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use crate::color_management::{convert_for_export, OutputProfile};
+use crate::errors::{Result, ViewerError};
+use crate::image_loader::ImageAdjustments;
+use image::imageops::FilterType;
+use image::DynamicImage;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+const IMAGES_DIR: &str = "images";
+const THUMBS_DIR: &str = "thumbs";
+
+const STYLE: &str = r#"<style>
+body { margin: 0; padding: 24px; background: #1b1b1b; color: #ddd; font-family: sans-serif; }
+h1 { font-weight: 300; margin: 0 0 24px; }
+.grid { display: grid; grid-template-columns: repeat(auto-fill, minmax(200px, 1fr)); gap: 16px; }
+figure { margin: 0; text-align: center; }
+figure img { max-width: 100%; height: auto; background: #2a2a2a; }
+figcaption { font-size: 12px; color: #999; margin-top: 6px; word-break: break-all; }
+</style>
+"#;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebGalleryOptions {
+    pub title: String,
+    /// Long edge of the full-size images
+    pub image_size: u32,
+    /// Long edge of the index thumbnails
+    pub thumbnail_size: u32,
+    /// Show file names under the thumbnails
+    pub captions: bool,
+}
+
+impl Default for WebGalleryOptions {
+    fn default() -> Self {
+        Self {
+            title: String::new(),
+            image_size: 2048,
+            thumbnail_size: 400,
+            captions: true,
+        }
+    }
+}
+
+/// Output JPEG name for each source, unique within the gallery
+pub fn gallery_file_names(sources: &[PathBuf]) -> Vec<String> {
+    let mut taken = HashSet::new();
+    sources
+        .iter()
+        .map(|source| {
+            let stem = source
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| "image".to_string());
+            let mut name = format!("{}.jpg", stem);
+            let mut n = 2;
+            while !taken.insert(name.to_lowercase()) {
+                name = format!("{}_{}.jpg", stem, n);
+                n += 1;
+            }
+            name
+        })
+        .collect()
+}
+
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Percent-encode a file name for use in an href or src attribute
+fn url_encode(name: &str) -> String {
+    let mut encoded = String::with_capacity(name.len());
+    for byte in name.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// The index page; `entries` are the gallery file names with their captions
+pub fn render_index(options: &WebGalleryOptions, entries: &[(String, String)]) -> String {
+    let title = if options.title.trim().is_empty() {
+        "Gallery".to_string()
+    } else {
+        escape_html(options.title.trim())
+    };
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    html.push_str(&format!("<title>{}</title>\n", title));
+    html.push_str(STYLE);
+    html.push_str("</head>\n<body>\n");
+    html.push_str(&format!("<h1>{}</h1>\n<div class=\"grid\">\n", title));
+
+    for (file_name, caption) in entries {
+        let url = url_encode(file_name);
+        let alt = escape_html(caption);
+        html.push_str("<figure>\n");
+        html.push_str(&format!(
+            "<a href=\"{}/{}\"><img src=\"{}/{}\" alt=\"{}\" loading=\"lazy\"></a>\n",
+            IMAGES_DIR, url, THUMBS_DIR, url, alt
+        ));
+        if options.captions {
+            html.push_str(&format!("<figcaption>{}</figcaption>\n", alt));
+        }
+        html.push_str("</figure>\n");
+    }
+
+    html.push_str("</div>\n</body>\n</html>\n");
+    html
+}
+
+/// Write resized images, thumbnails and `index.html` into `folder`.
+/// Returns the index path and the images that could not be exported.
+pub fn generate(
+    sources: Vec<(PathBuf, ImageAdjustments)>,
+    options: &WebGalleryOptions,
+    folder: &Path,
+) -> Result<(PathBuf, Vec<(PathBuf, String)>)> {
+    let images_dir = folder.join(IMAGES_DIR);
+    let thumbs_dir = folder.join(THUMBS_DIR);
+    std::fs::create_dir_all(&images_dir)?;
+    std::fs::create_dir_all(&thumbs_dir)?;
+
+    let paths: Vec<PathBuf> = sources.iter().map(|(path, _)| path.clone()).collect();
+    let names = gallery_file_names(&paths);
+
+    let results: Vec<std::result::Result<(String, String), (PathBuf, String)>> = sources
+        .into_par_iter()
+        .zip(names)
+        .map(|((source, adjustments), name)| {
+            export_one(
+                &source,
+                &adjustments,
+                &images_dir.join(&name),
+                &thumbs_dir.join(&name),
+                options,
+            )
+            .map(|()| {
+                let caption = source
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| name.clone());
+                (name, caption)
+            })
+            .map_err(|e| (source, e))
+        })
+        .collect();
+
+    let mut entries = Vec::new();
+    let mut failed = Vec::new();
+    for result in results {
+        match result {
+            Ok(entry) => entries.push(entry),
+            Err(failure) => failed.push(failure),
+        }
+    }
+
+    let index = folder.join("index.html");
+    std::fs::write(&index, render_index(options, &entries)).map_err(|e| {
+        ViewerError::ExportError {
+            path: index.clone(),
+            message: e.to_string(),
+        }
+    })?;
+    Ok((index, failed))
+}
+
+fn export_one(
+    source: &Path,
+    adjustments: &ImageAdjustments,
+    image_path: &Path,
+    thumb_path: &Path,
+    options: &WebGalleryOptions,
+) -> std::result::Result<(), String> {
+    let image = crate::image_loader::load_image(source).map_err(|e| e.to_string())?;
+    // Shrink before adjusting; the web sizes are far below camera resolution
+    let image = shrink_to(image, options.image_size);
+    let image = if adjustments.is_default() {
+        image
+    } else {
+        crate::image_loader::apply_adjustments_for_export(&image, adjustments, source)
+    };
+
+    // Browsers show untagged images as sRGB
+    let srgb = OutputProfile::Srgb;
+    let image = convert_for_export(image, Some(source), srgb);
+    let quality = crate::app::file_ops::DEFAULT_JPEG_QUALITY;
+    crate::app::file_ops::save_exported_image(&image, image_path, quality, Some(srgb))?;
+    let thumb = shrink_to(image, options.thumbnail_size);
+    crate::app::file_ops::save_exported_image(&thumb, thumb_path, quality, Some(srgb))
+}
+
+/// Fit within `max` pixels on the long edge without upscaling
+fn shrink_to(image: DynamicImage, max: u32) -> DynamicImage {
+    if image.width() > max || image.height() > max {
+        image.resize(max, max, FilterType::Lanczos3)
+    } else {
+        image
+    }
+}
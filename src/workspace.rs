@@ -0,0 +1,158 @@
+use crate::settings::{Settings, ThumbnailPosition};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DockSide {
+    Left,
+    Right,
+    Bottom,
+}
+
+impl DockSide {
+    pub fn all() -> &'static [DockSide] {
+        &[DockSide::Left, DockSide::Right, DockSide::Bottom]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DockSide::Left => "Left",
+            DockSide::Right => "Right",
+            DockSide::Bottom => "Bottom",
+        }
+    }
+}
+
+/// Where a panel is docked; width is used on the sides and height at the bottom
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PanelPlacement {
+    pub side: DockSide,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl PanelPlacement {
+    pub const fn new(side: DockSide, width: f32, height: f32) -> Self {
+        Self {
+            side,
+            width,
+            height,
+        }
+    }
+}
+
+/// Placement of the dockable panels
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PanelLayout {
+    pub show_navigator: bool,
+    pub navigator: PanelPlacement,
+    pub sidebar: PanelPlacement,
+    /// Own panel for the histogram; `None` keeps it at the top of the sidebar
+    pub histogram: Option<PanelPlacement>,
+}
+
+impl Default for PanelLayout {
+    fn default() -> Self {
+        Self {
+            show_navigator: true,
+            navigator: PanelPlacement::new(DockSide::Left, 200.0, 180.0),
+            sidebar: PanelPlacement::new(DockSide::Right, 280.0, 220.0),
+            histogram: None,
+        }
+    }
+}
+
+/// A named snapshot of the panel layout and which panels are shown
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Workspace {
+    pub name: String,
+    pub panels: PanelLayout,
+    pub show_sidebar: bool,
+    pub show_thumbnails: bool,
+    pub show_histogram: bool,
+    pub show_adjustments: bool,
+    pub show_exif: bool,
+    pub thumbnail_position: ThumbnailPosition,
+    pub thumbnail_size: f32,
+}
+
+impl Default for Workspace {
+    fn default() -> Self {
+        Self::capture("Default", &Settings::default())
+    }
+}
+
+impl Workspace {
+    pub fn capture(name: &str, settings: &Settings) -> Self {
+        Self {
+            name: name.to_string(),
+            panels: settings.panel_layout.clone(),
+            show_sidebar: settings.show_sidebar,
+            show_thumbnails: settings.show_thumbnails,
+            show_histogram: settings.show_histogram,
+            show_adjustments: settings.show_adjustments,
+            show_exif: settings.show_exif,
+            thumbnail_position: settings.thumbnail_position,
+            thumbnail_size: settings.thumbnail_size,
+        }
+    }
+
+    pub fn apply(&self, settings: &mut Settings) {
+        settings.panel_layout = self.panels.clone();
+        settings.show_sidebar = self.show_sidebar;
+        settings.show_thumbnails = self.show_thumbnails;
+        settings.show_histogram = self.show_histogram;
+        settings.show_adjustments = self.show_adjustments;
+        settings.show_exif = self.show_exif;
+        settings.thumbnail_position = self.thumbnail_position;
+        settings.thumbnail_size = self.thumbnail_size;
+    }
+
+    /// Starting workspaces: a filmstrip for culling, a wide develop panel for
+    /// editing, and folders plus metadata for reviewing
+    pub fn builtin() -> Vec<Workspace> {
+        let cull = Workspace {
+            name: "Cull".to_string(),
+            panels: PanelLayout {
+                show_navigator: false,
+                histogram: Some(PanelPlacement::new(DockSide::Right, 240.0, 140.0)),
+                ..Default::default()
+            },
+            show_sidebar: false,
+            show_thumbnails: true,
+            show_histogram: true,
+            show_adjustments: false,
+            show_exif: true,
+            thumbnail_position: ThumbnailPosition::Bottom,
+            thumbnail_size: 140.0,
+        };
+        let edit = Workspace {
+            name: "Edit".to_string(),
+            panels: PanelLayout {
+                show_navigator: false,
+                sidebar: PanelPlacement::new(DockSide::Right, 340.0, 220.0),
+                ..Default::default()
+            },
+            show_sidebar: true,
+            show_thumbnails: true,
+            show_histogram: true,
+            show_adjustments: true,
+            show_exif: false,
+            thumbnail_position: ThumbnailPosition::Bottom,
+            thumbnail_size: 80.0,
+        };
+        let review = Workspace {
+            name: "Review".to_string(),
+            panels: PanelLayout::default(),
+            show_sidebar: true,
+            show_thumbnails: true,
+            show_histogram: false,
+            show_adjustments: false,
+            show_exif: true,
+            thumbnail_position: ThumbnailPosition::Right,
+            thumbnail_size: 120.0,
+        };
+        vec![cull, edit, review]
+    }
+}
@@ -0,0 +1,493 @@
+use crate::errors::{Result, ViewerError};
+use crate::metadata::ImageMetadata;
+use crate::settings::ColorLabel;
+use regex::{NoExpand, Regex};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const XMP_NS: &str = "http://ns.adobe.com/xap/1.0/";
+const DC_NS: &str = "http://purl.org/dc/elements/1.1/";
+
+lazy_static::lazy_static! {
+    static ref SUBJECT: Regex = Regex::new(r"(?s)<dc:subject>(.*?)</dc:subject>").unwrap();
+    static ref LIST_ITEM: Regex = Regex::new(r"(?s)<rdf:li[^>]*>(.*?)</rdf:li>").unwrap();
+    static ref DESCRIPTION: Regex = Regex::new(r"(?s)<rdf:Description\b([^>]*)>").unwrap();
+    static ref SELF_CLOSING_DESCRIPTION: Regex =
+        Regex::new(r"(?s)(<rdf:Description\b[^>]*?)\s*/>").unwrap();
+    static ref ATTRIBUTE: Regex = Regex::new(r#"([\w.-]+:[\w.-]+)\s*=\s*"([^"]*)""#).unwrap();
+    static ref OPEN_TAG: Regex = Regex::new(r"<([\w.-]+:[\w.-]+)(\s[^>]*)?>").unwrap();
+    /// Patterns for named properties, compiled the first time each is used
+    static ref PROPERTY_PATTERNS: Mutex<HashMap<String, Regex>> = Mutex::new(HashMap::new());
+}
+
+/// `pattern` with `{0}` standing for the escaped property `name`, compiled once
+fn property_regex(pattern: &str, name: &str) -> Regex {
+    let pattern = pattern.replace("{0}", &regex::escape(name));
+    let mut patterns = PROPERTY_PATTERNS.lock().unwrap_or_else(|e| e.into_inner());
+    patterns
+        .entry(pattern)
+        .or_insert_with_key(|pattern| Regex::new(pattern).unwrap())
+        .clone()
+}
+
+/// Culling metadata found in an XMP sidecar. Fields that aren't present stay `None`
+/// so they don't overwrite what the catalog already has.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct XmpData {
+    pub rating: Option<u8>,
+    pub label: Option<ColorLabel>,
+    pub keywords: Option<Vec<String>>,
+}
+
+impl XmpData {
+    pub fn apply_to(&self, metadata: &mut ImageMetadata) {
+        if let Some(rating) = self.rating {
+            metadata.rating = rating;
+        }
+        if let Some(label) = self.label {
+            metadata.color_label = label;
+        }
+        if let Some(keywords) = &self.keywords {
+            metadata.keywords = keywords.clone();
+        }
+    }
+}
+
+/// Sidecar path written by Lightroom: the image name with an .xmp extension
+pub fn sidecar_path(image: &Path) -> PathBuf {
+    image.with_extension("xmp")
+}
+
+/// Find an existing sidecar, also accepting the `IMG_0001.CR3.xmp` naming used by some tools
+pub fn find_sidecar(image: &Path) -> Option<PathBuf> {
+    let primary = sidecar_path(image);
+    if primary.is_file() {
+        return Some(primary);
+    }
+    let mut full_name = image.as_os_str().to_owned();
+    full_name.push(".xmp");
+    let secondary = PathBuf::from(full_name);
+    secondary.is_file().then_some(secondary)
+}
+
+pub fn read_sidecar(image: &Path) -> Option<XmpData> {
+    let path = find_sidecar(image)?;
+    match std::fs::read_to_string(&path) {
+        Ok(text) => Some(parse_xmp(&text)),
+        Err(e) => {
+            log::warn!("Failed to read XMP sidecar {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Read a property written either as an attribute (`xmp:Rating="3"`) or an element
+fn read_property(text: &str, name: &str) -> Option<String> {
+    let attr = property_regex(r#"{0}\s*=\s*"([^"]*)""#, name);
+    if let Some(c) = attr.captures(text) {
+        return Some(unescape(&c[1]));
+    }
+    let element = property_regex(r"<{0}>\s*([^<]*?)\s*</{0}>", name);
+    element.captures(text).map(|c| unescape(&c[1]))
+}
+
+pub fn parse_xmp(text: &str) -> XmpData {
+    // Lightroom writes -1 for rejected; treat it as unrated here
+    let rating = read_property(text, "xmp:Rating")
+        .and_then(|r| r.trim().parse::<i32>().ok())
+        .map(|r| r.clamp(0, 5) as u8);
+
+    let label = read_property(text, "xmp:Label").map(|l| label_from_name(&l));
+
+    let keywords = SUBJECT.captures(text).map(|c| {
+        LIST_ITEM
+            .captures_iter(&c[1])
+            .map(|k| unescape(k[1].trim()))
+            .filter(|k| !k.is_empty())
+            .collect()
+    });
+
+    XmpData {
+        rating,
+        label,
+        keywords,
+    }
+}
+
+/// The XMP packet embedded in an image file, found by its `x:xmpmeta` wrapper
+/// so it works for JPEG, TIFF, PNG and most RAW containers alike
+pub fn embedded_xmp(bytes: &[u8]) -> Option<String> {
+    let start = find_bytes(bytes, b"<x:xmpmeta")?;
+    let end_tag = b"</x:xmpmeta>";
+    let end = start + find_bytes(&bytes[start..], end_tag)? + end_tag.len();
+    Some(String::from_utf8_lossy(&bytes[start..end]).into_owned())
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// Every property in an XMP packet as (`prefix:Name`, value), in document
+/// order. List values are joined with "; " and structures are flattened into
+/// their fields.
+pub fn properties(text: &str) -> Vec<(String, String)> {
+    let mut found = Vec::new();
+    for tag in DESCRIPTION.captures_iter(text) {
+        for c in ATTRIBUTE.captures_iter(&tag[1]) {
+            let name = &c[1];
+            if !name.starts_with("xmlns:") && !name.starts_with("rdf:") {
+                let position = tag.get(1).unwrap().start() + c.get(0).unwrap().start();
+                found.push((position, name.to_string(), unescape(&c[2])));
+            }
+        }
+    }
+    for c in OPEN_TAG.captures_iter(text) {
+        let whole = c.get(0).unwrap();
+        let name = &c[1];
+        if name.starts_with("rdf:") || name.starts_with("x:") || whole.as_str().ends_with("/>") {
+            continue;
+        }
+        let Some(length) = text[whole.end()..].find(&format!("</{}>", name)) else {
+            continue;
+        };
+        let inner = &text[whole.end()..whole.end() + length];
+        let value = if inner.contains("<rdf:li") {
+            // Items holding structures are listed through their own fields
+            let items: Vec<_> = LIST_ITEM
+                .captures_iter(inner)
+                .map(|i| i[1].trim().to_string())
+                .filter(|i| !i.contains('<'))
+                .map(|i| unescape(&i))
+                .collect();
+            if items.is_empty() {
+                continue;
+            }
+            items.join("; ")
+        } else if inner.contains('<') {
+            continue;
+        } else {
+            unescape(inner.trim())
+        };
+        found.push((whole.start(), name.to_string(), value));
+    }
+
+    found.sort_by_key(|(position, _, _)| *position);
+    found
+        .into_iter()
+        .map(|(_, name, value)| (name, value))
+        .collect()
+}
+
+fn label_from_name(name: &str) -> ColorLabel {
+    match name.trim().to_lowercase().as_str() {
+        "red" => ColorLabel::Red,
+        "yellow" => ColorLabel::Yellow,
+        "green" => ColorLabel::Green,
+        "blue" => ColorLabel::Blue,
+        "purple" => ColorLabel::Purple,
+        _ => ColorLabel::None,
+    }
+}
+
+/// Write rating, label and keywords to the image's sidecar. An existing sidecar is
+/// updated in place so develop settings written by other tools are preserved.
+pub fn write_sidecar(image: &Path, metadata: &ImageMetadata) -> Result<()> {
+    let path = find_sidecar(image).unwrap_or_else(|| sidecar_path(image));
+    let text = if path.is_file() {
+        update_xmp(&std::fs::read_to_string(&path)?, metadata)
+    } else {
+        new_xmp(metadata)
+    };
+    std::fs::write(&path, text)?;
+    Ok(())
+}
+
+fn label_value(label: ColorLabel) -> &'static str {
+    match label {
+        ColorLabel::None => "",
+        other => other.name(),
+    }
+}
+
+fn subject_block(keywords: &[String]) -> String {
+    let items: String = keywords
+        .iter()
+        .map(|k| format!("     <rdf:li>{}</rdf:li>\n", escape(k)))
+        .collect();
+    format!(
+        "<dc:subject>\n    <rdf:Bag>\n{}    </rdf:Bag>\n   </dc:subject>",
+        items
+    )
+}
+
+fn new_xmp(metadata: &ImageMetadata) -> String {
+    let subject = if metadata.keywords.is_empty() {
+        String::new()
+    } else {
+        format!("\n   {}", subject_block(&metadata.keywords))
+    };
+    format!(
+        r#"<x:xmpmeta xmlns:x="adobe:ns:meta/">
+ <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <rdf:Description rdf:about=""
+    xmlns:xmp="{}"
+    xmlns:dc="{}"
+   xmp:Rating="{}"
+   xmp:Label="{}">{}
+  </rdf:Description>
+ </rdf:RDF>
+</x:xmpmeta>
+"#,
+        XMP_NS,
+        DC_NS,
+        metadata.rating,
+        label_value(metadata.color_label),
+        subject
+    )
+}
+
+/// Set an attribute on the first rdf:Description, replacing an existing attribute or element
+fn set_property(text: String, name: &str, value: &str, ns_prefix: &str, ns: &str) -> String {
+    let escaped = escape(value);
+    let attr = property_regex(r#"{0}\s*=\s*"[^"]*""#, name);
+    if attr.is_match(&text) {
+        let replacement = format!(r#"{}="{}""#, name, escaped);
+        return attr.replace(&text, NoExpand(&replacement)).into_owned();
+    }
+    let element = property_regex(r"(?s)<{0}>.*?</{0}>", name);
+    if element.is_match(&text) {
+        let replacement = format!("<{0}>{1}</{0}>", name, escaped);
+        return element.replace(&text, NoExpand(&replacement)).into_owned();
+    }
+
+    let mut insert = String::new();
+    if !text.contains(&format!("xmlns:{}=", ns_prefix)) {
+        insert.push_str(&format!(r#" xmlns:{}="{}""#, ns_prefix, ns));
+    }
+    insert.push_str(&format!(r#" {}="{}""#, name, escaped));
+    text.replacen(
+        "<rdf:Description",
+        &format!("<rdf:Description{}", insert),
+        1,
+    )
+}
+
+fn update_xmp(text: &str, metadata: &ImageMetadata) -> String {
+    if !text.contains("<rdf:Description") {
+        return new_xmp(metadata);
+    }
+
+    let mut text = set_property(
+        text.to_string(),
+        "xmp:Rating",
+        &metadata.rating.to_string(),
+        "xmp",
+        XMP_NS,
+    );
+    text = set_property(
+        text,
+        "xmp:Label",
+        label_value(metadata.color_label),
+        "xmp",
+        XMP_NS,
+    );
+
+    // Replace the keyword list wholesale
+    text = remove_property(text, "dc:subject");
+    if !metadata.keywords.is_empty() {
+        text = insert_element(text, &subject_block(&metadata.keywords));
+    }
+    text
+}
+
+/// Drop a property written either as an attribute or an element
+fn remove_property(text: String, name: &str) -> String {
+    let attr = property_regex(r#"\s{0}\s*=\s*"[^"]*""#, name);
+    let element = property_regex(r"(?s)\s*<{0}>.*?</{0}>", name);
+    let text = attr.replace(&text, "").into_owned();
+    element.replace(&text, "").into_owned()
+}
+
+/// Add a child element to the first rdf:Description, declaring the dc namespace if needed
+fn insert_element(mut text: String, block: &str) -> String {
+    if !text.contains("xmlns:dc=") {
+        text = text.replacen(
+            "<rdf:Description",
+            &format!(r#"<rdf:Description xmlns:dc="{}""#, DC_NS),
+            1,
+        );
+    }
+    // A self-closing Description has to be opened up to hold child elements
+    let first_open = DESCRIPTION.find(&text).map(|m| m.as_str().ends_with("/>"));
+    if first_open == Some(true) {
+        SELF_CLOSING_DESCRIPTION
+            .replace(&text, |c: &regex::Captures| {
+                format!("{}>\n   {}\n  </rdf:Description>", &c[1], block)
+            })
+            .into_owned()
+    } else {
+        text.replacen(
+            "</rdf:Description>",
+            &format!("   {}\n  </rdf:Description>", block),
+            1,
+        )
+    }
+}
+
+/// Creator, copyright, title and keywords set by the batch metadata editor.
+/// These are the XMP properties IPTC Core maps its fields to.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DescriptiveMetadata {
+    /// None leaves a field as it is, an empty value removes it
+    pub creator: Option<String>,
+    pub copyright: Option<String>,
+    pub title: Option<String>,
+    /// Added to the keywords already present
+    pub keywords: Vec<String>,
+}
+
+/// Write descriptive fields into an XMP packet, or a new one when `text` is None
+pub fn set_descriptive(text: Option<&str>, fields: &DescriptiveMetadata) -> String {
+    let mut text = match text {
+        Some(text) if text.contains("<rdf:Description") => text.to_string(),
+        _ => format!(
+            r#"<x:xmpmeta xmlns:x="adobe:ns:meta/">
+ <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <rdf:Description rdf:about=""
+    xmlns:dc="{}"/>
+ </rdf:RDF>
+</x:xmpmeta>
+"#,
+            DC_NS
+        ),
+    };
+
+    let lists = [
+        ("dc:creator", "Seq", &fields.creator),
+        ("dc:rights", "Alt", &fields.copyright),
+        ("dc:title", "Alt", &fields.title),
+    ];
+    for (name, kind, value) in lists {
+        let Some(value) = value else {
+            continue;
+        };
+        text = remove_property(text, name);
+        if !value.trim().is_empty() {
+            let language = if kind == "Alt" {
+                r#" xml:lang="x-default""#
+            } else {
+                ""
+            };
+            let block = format!(
+                "<{0}>\n    <rdf:{1}>\n     <rdf:li{2}>{3}</rdf:li>\n    </rdf:{1}>\n   </{0}>",
+                name,
+                kind,
+                language,
+                escape(value.trim())
+            );
+            text = insert_element(text, &block);
+        }
+    }
+
+    if !fields.keywords.is_empty() {
+        let mut keywords = parse_xmp(&text).keywords.unwrap_or_default();
+        for keyword in &fields.keywords {
+            if !keywords.iter().any(|k| k.eq_ignore_ascii_case(keyword)) {
+                keywords.push(keyword.clone());
+            }
+        }
+        text = remove_property(text, "dc:subject");
+        text = insert_element(text, &subject_block(&keywords));
+    }
+    text
+}
+
+/// Write descriptive fields to the image's XMP sidecar, creating it if needed
+pub fn write_descriptive_sidecar(image: &Path, fields: &DescriptiveMetadata) -> Result<()> {
+    let path = find_sidecar(image).unwrap_or_else(|| sidecar_path(image));
+    let existing = std::fs::read_to_string(&path).ok();
+    std::fs::write(&path, set_descriptive(existing.as_deref(), fields))?;
+    Ok(())
+}
+
+/// Signature opening a JPEG APP1 segment that holds XMP
+const JPEG_XMP_SIGNATURE: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+
+/// Write descriptive fields into the XMP packet embedded in a JPEG. The
+/// file is rewritten through a temporary copy so a failure leaves it intact.
+pub fn embed_descriptive(image: &Path, fields: &DescriptiveMetadata) -> Result<()> {
+    let bytes = std::fs::read(image)?;
+    let invalid = |message: &str| ViewerError::MetadataError {
+        message: format!("{}: {}", image.display(), message),
+    };
+    if !bytes.starts_with(&[0xFF, 0xD8]) {
+        return Err(invalid("not a JPEG file"));
+    }
+
+    // Split off the metadata segments ahead of the image data, dropping old XMP
+    let mut head = Vec::new();
+    let mut existing = None;
+    let mut insert_at = 0;
+    let mut pos = 2;
+    while pos + 4 <= bytes.len() && bytes[pos] == 0xFF {
+        let marker = bytes[pos + 1];
+        let is_app = (0xE0..=0xEF).contains(&marker);
+        if !is_app {
+            break;
+        }
+        let length = u16::from_be_bytes([bytes[pos + 2], bytes[pos + 3]]) as usize;
+        let end = pos + 2 + length;
+        if length < 2 || end > bytes.len() {
+            return Err(invalid("damaged segment"));
+        }
+        let payload = &bytes[pos + 4..end];
+        if marker == 0xE1 && payload.starts_with(JPEG_XMP_SIGNATURE) {
+            let packet = &payload[JPEG_XMP_SIGNATURE.len()..];
+            existing = Some(String::from_utf8_lossy(packet).into_owned());
+        } else {
+            head.extend_from_slice(&bytes[pos..end]);
+            // XMP goes after JFIF and EXIF, where readers expect it
+            if marker == 0xE0 || marker == 0xE1 {
+                insert_at = head.len();
+            }
+        }
+        pos = end;
+    }
+
+    let packet = set_descriptive(existing.as_deref(), fields);
+    let length = JPEG_XMP_SIGNATURE.len() + packet.len() + 2;
+    let length = u16::try_from(length).map_err(|_| invalid("XMP packet too large"))?;
+    let mut segment = vec![0xFF, 0xE1];
+    segment.extend(length.to_be_bytes());
+    segment.extend(JPEG_XMP_SIGNATURE);
+    segment.extend(packet.as_bytes());
+
+    let mut output = Vec::with_capacity(bytes.len() + segment.len());
+    output.extend(&bytes[..2]);
+    output.extend(&head[..insert_at]);
+    output.extend(&segment);
+    output.extend(&head[insert_at..]);
+    output.extend(&bytes[pos..]);
+
+    let name = image.file_name().unwrap_or_default().to_string_lossy();
+    let temp = image.with_file_name(format!(".rview-xmp-{}", name));
+    std::fs::write(&temp, output)?;
+    std::fs::rename(&temp, image)?;
+    Ok(())
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}